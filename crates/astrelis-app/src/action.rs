@@ -0,0 +1,362 @@
+//! Logical actions bound to physical inputs.
+
+use std::collections::{BTreeMap, HashSet};
+
+use astrelis_platform::{
+    DeviceEvent, ElementState, KeyCode, Modifiers, PhysicalKey, PointerButton, WindowEvent,
+};
+
+use crate::{
+    InputState,
+    gamepad::{GamepadAxis, GamepadButton, GamepadEvent, GamepadId},
+};
+
+/// One physical input source.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Input {
+    /// A keyboard key by physical position.
+    Key(KeyCode),
+    /// Whichever key types this lowercase character in the active layout.
+    Character(String),
+    /// A mouse or pointer button.
+    Pointer(PointerButton),
+    /// A raw device button by platform button number.
+    DeviceButton(u32),
+    /// Relative motion on a raw device axis this frame, such as mouse
+    /// movement.
+    DeviceAxis(u32),
+    /// A gamepad button on one gamepad, or on any gamepad when `gamepad` is
+    /// `None`.
+    GamepadButton {
+        /// Gamepad the binding listens to.
+        gamepad: Option<GamepadId>,
+        /// Bound button.
+        button: GamepadButton,
+    },
+    /// A gamepad stick or trigger on one gamepad, or the strongest reading
+    /// across gamepads when `gamepad` is `None`.
+    GamepadAxis {
+        /// Gamepad the binding listens to.
+        gamepad: Option<GamepadId>,
+        /// Bound axis.
+        axis: GamepadAxis,
+    },
+}
+
+/// An input that drives an action while its modifiers are held.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Binding {
+    /// Physical input.
+    pub input: Input,
+    /// Modifiers that must be held; others may be held too.
+    pub modifiers: Modifiers,
+    /// Multiplier applied to the input value, such as `-1.0` for the
+    /// negative half of a keyboard axis.
+    pub scale: f32,
+}
+
+impl Binding {
+    /// Binds an input with no modifiers and unit scale.
+    pub fn new(input: Input) -> Self {
+        Self {
+            input,
+            modifiers: Modifiers::default(),
+            scale: 1.0,
+        }
+    }
+
+    /// Binds a keyboard key.
+    pub fn key(code: KeyCode) -> Self {
+        Self::new(Input::Key(code))
+    }
+
+    /// Binds the key that types `character` in the active layout, such as
+    /// `'z'`, which sits where QWERTY has W on AZERTY keyboards.
+    pub fn character(character: char) -> Self {
+        Self::new(Input::Character(character.to_lowercase().collect()))
+    }
+
+    /// Binds a pointer button.
+    pub fn pointer(button: PointerButton) -> Self {
+        Self::new(Input::Pointer(button))
+    }
+
+    /// Binds a button on any gamepad.
+    pub fn gamepad_button(button: GamepadButton) -> Self {
+        Self::new(Input::GamepadButton {
+            gamepad: None,
+            button,
+        })
+    }
+
+    /// Binds a stick or trigger on any gamepad.
+    pub fn gamepad_axis(axis: GamepadAxis) -> Self {
+        Self::new(Input::GamepadAxis {
+            gamepad: None,
+            axis,
+        })
+    }
+
+    /// Restricts a gamepad binding to one gamepad, as for local
+    /// multiplayer. Other bindings are unchanged.
+    pub fn on_gamepad(mut self, id: GamepadId) -> Self {
+        match &mut self.input {
+            Input::GamepadButton { gamepad, .. } | Input::GamepadAxis { gamepad, .. } => {
+                *gamepad = Some(id);
+            }
+            _ => {}
+        }
+        self
+    }
+
+    /// Requires modifiers to be held.
+    pub fn with_modifiers(mut self, modifiers: Modifiers) -> Self {
+        self.modifiers = modifiers;
+        self
+    }
+
+    /// Scales the input value.
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+}
+
+/// Logical actions such as `"Jump"` or `"MoveX"` bound to keys, pointer
+/// buttons, gamepads, and raw device buttons or axes.
+///
+/// Feed platform events through [`ActionMap::handle_window_event`],
+/// [`ActionMap::handle_device_event`], and
+/// [`ActionMap::handle_gamepad_event`], query during the update, and call
+/// [`ActionMap::end_frame`] once the update has consumed this frame's
+/// transitions. The map keeps its own [`InputState`], readable through
+/// [`ActionMap::input`] for queries that have no action. An action's [`value`](ActionMap::value) is the clamped sum
+/// of its active bindings, and it counts as pressed at a magnitude of at
+/// least one half.
+///
+/// With the `serde` feature the bindings serialize; input state does not.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ActionMap {
+    bindings: BTreeMap<String, Vec<Binding>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    state: State,
+}
+
+#[derive(Clone, Debug, Default)]
+struct State {
+    input: InputState,
+    previous: HashSet<String>,
+    rebinding: Option<String>,
+}
+
+impl ActionMap {
+    /// Creates a map with no actions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a binding to an action, creating the action if needed.
+    pub fn bind(&mut self, action: impl Into<String>, binding: Binding) -> &mut Self {
+        self.bindings
+            .entry(action.into())
+            .or_default()
+            .push(binding);
+        self
+    }
+
+    /// Removes every binding of an action that uses `input`.
+    pub fn unbind(&mut self, action: &str, input: &Input) {
+        if let Some(bindings) = self.bindings.get_mut(action) {
+            bindings.retain(|binding| binding.input != *input);
+        }
+    }
+
+    /// Replaces all bindings of an action.
+    pub fn set_bindings(&mut self, action: impl Into<String>, bindings: Vec<Binding>) {
+        self.bindings.insert(action.into(), bindings);
+    }
+
+    /// Removes an action and its bindings.
+    pub fn remove_action(&mut self, action: &str) -> Option<Vec<Binding>> {
+        self.bindings.remove(action)
+    }
+
+    /// Bindings of an action.
+    pub fn bindings(&self, action: &str) -> &[Binding] {
+        self.bindings.get(action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Every action name in sorted order.
+    pub fn actions(&self) -> impl Iterator<Item = &str> {
+        self.bindings.keys().map(String::as_str)
+    }
+
+    /// Replaces an action's bindings with the next button or key pressed,
+    /// together with the modifiers held at that moment.
+    pub fn rebind_next(&mut self, action: impl Into<String>) {
+        self.state.rebinding = Some(action.into());
+    }
+
+    /// Action waiting for [`ActionMap::rebind_next`] to capture an input.
+    pub fn rebinding(&self) -> Option<&str> {
+        self.state.rebinding.as_deref()
+    }
+
+    /// Abandons a pending rebind.
+    pub fn cancel_rebind(&mut self) {
+        self.state.rebinding = None;
+    }
+
+    /// Input state the actions are evaluated against.
+    pub fn input(&self) -> &InputState {
+        &self.state.input
+    }
+
+    /// Updates input state from a window event.
+    pub fn handle_window_event(&mut self, event: &WindowEvent) {
+        self.state.input.handle_window_event(event);
+        let pressed = match event {
+            WindowEvent::KeyboardInput(input)
+                if !input.repeat && input.state == ElementState::Pressed =>
+            {
+                match &input.physical_key {
+                    PhysicalKey::Code(code) => Some(Input::Key(code.clone())),
+                    _ => None,
+                }
+            }
+            WindowEvent::PointerButton {
+                button,
+                state: ElementState::Pressed,
+                ..
+            } => Some(Input::Pointer(*button)),
+            _ => None,
+        };
+        if let Some(input) = pressed {
+            self.capture_rebind(input);
+        }
+    }
+
+    /// Updates input state from a raw device event.
+    pub fn handle_device_event(&mut self, event: &DeviceEvent) {
+        self.state.input.handle_device_event(event);
+        if let DeviceEvent::Button {
+            button,
+            state: ElementState::Pressed,
+        } = event
+        {
+            self.capture_rebind(Input::DeviceButton(*button));
+        }
+    }
+
+    /// Updates input state from a gamepad event. A rebind captures the
+    /// pressed button on the gamepad that pressed it.
+    pub fn handle_gamepad_event(&mut self, event: &GamepadEvent) {
+        self.state.input.handle_gamepad_event(event);
+        if let GamepadEvent::Button {
+            gamepad,
+            button,
+            state: ElementState::Pressed,
+        } = *event
+        {
+            self.capture_rebind(Input::GamepadButton {
+                gamepad: Some(gamepad),
+                button,
+            });
+        }
+    }
+
+    /// Forgets every held input, as when focus is lost.
+    pub fn release_all(&mut self) {
+        self.state.input.release_all();
+    }
+
+    /// Records which actions are pressed, so the next frame's
+    /// [`just_pressed`](ActionMap::just_pressed) and
+    /// [`just_released`](ActionMap::just_released) compare against them.
+    /// Also ends the frame of the underlying [`InputState`].
+    pub fn end_frame(&mut self) {
+        let pressed = self
+            .bindings
+            .keys()
+            .filter(|action| self.pressed(action))
+            .cloned()
+            .collect();
+        self.state.previous = pressed;
+        self.state.input.end_frame();
+    }
+
+    /// Clamped sum of the action's active bindings, in `-1.0..=1.0`.
+    pub fn value(&self, action: &str) -> f32 {
+        self.bindings(action)
+            .iter()
+            .filter(|binding| modifiers_held(self.state.input.modifiers(), binding.modifiers))
+            .map(|binding| self.input_value(&binding.input) * binding.scale)
+            .sum::<f32>()
+            .clamp(-1.0, 1.0)
+    }
+
+    /// Returns whether the action is active.
+    pub fn pressed(&self, action: &str) -> bool {
+        self.value(action).abs() >= 0.5
+    }
+
+    /// Returns whether the action became active this frame.
+    pub fn just_pressed(&self, action: &str) -> bool {
+        self.pressed(action) && !self.state.previous.contains(action)
+    }
+
+    /// Returns whether the action stopped being active this frame.
+    pub fn just_released(&self, action: &str) -> bool {
+        !self.pressed(action) && self.state.previous.contains(action)
+    }
+
+    fn capture_rebind(&mut self, input: Input) {
+        if let Some(action) = self.state.rebinding.take() {
+            let binding = Binding::new(input).with_modifiers(self.state.input.modifiers());
+            self.bindings.insert(action, vec![binding]);
+        }
+    }
+
+    fn input_value(&self, binding_input: &Input) -> f32 {
+        let input = &self.state.input;
+        let held = match binding_input {
+            Input::Key(code) => input.key_pressed(code),
+            Input::Character(character) => input.character_pressed(character),
+            Input::Pointer(button) => input.button_pressed(*button),
+            Input::DeviceButton(button) => input.device_button_pressed(*button),
+            Input::DeviceAxis(axis) => return input.axis(*axis),
+            Input::GamepadButton { gamepad, button } => match gamepad {
+                Some(gamepad) => input.gamepad_button_pressed(*gamepad, *button),
+                None => input
+                    .gamepads()
+                    .any(|gamepad| input.gamepad_button_pressed(gamepad, *button)),
+            },
+            Input::GamepadAxis { gamepad, axis } => {
+                return match gamepad {
+                    Some(gamepad) => input.gamepad_axis(*gamepad, *axis),
+                    None => input
+                        .gamepads()
+                        .map(|gamepad| input.gamepad_axis(gamepad, *axis))
+                        .fold(0.0, |strongest, value| {
+                            if value.abs() > strongest.abs() {
+                                value
+                            } else {
+                                strongest
+                            }
+                        }),
+                };
+            }
+        };
+        f32::from(u8::from(held))
+    }
+}
+
+fn modifiers_held(held: Modifiers, required: Modifiers) -> bool {
+    (held.shift || !required.shift)
+        && (held.control || !required.control)
+        && (held.alt || !required.alt)
+        && (held.super_key || !required.super_key)
+}
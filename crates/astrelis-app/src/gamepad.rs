@@ -0,0 +1,265 @@
+//! Gamepad buttons and axes, keyed by the gamepad that reported them.
+//!
+//! [`GamepadEvent`]s feed [`InputState`](crate::InputState) and
+//! [`ActionMap`](crate::ActionMap). With the `gamepad` feature, `Gamepads`
+//! produces them from the platform's controllers through gilrs; on Linux that
+//! needs libudev at build time.
+
+use std::{error::Error, fmt};
+
+use astrelis_platform::ElementState;
+
+/// One gamepad, stable while it stays connected. A gamepad that reconnects
+/// usually gets its previous id back.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GamepadId(pub usize);
+
+/// A gamepad button in the standard layout, named by position.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GamepadButton {
+    /// Bottom face button: A on Xbox, Cross on PlayStation.
+    South,
+    /// Right face button: B on Xbox, Circle on PlayStation.
+    East,
+    /// Top face button: Y on Xbox, Triangle on PlayStation.
+    North,
+    /// Left face button: X on Xbox, Square on PlayStation.
+    West,
+    /// Left shoulder button.
+    LeftBumper,
+    /// Left trigger, pressed past its threshold.
+    LeftTrigger,
+    /// Right shoulder button.
+    RightBumper,
+    /// Right trigger, pressed past its threshold.
+    RightTrigger,
+    /// Select, Back, or Share.
+    Select,
+    /// Start, Menu, or Options.
+    Start,
+    /// Guide or Home.
+    Mode,
+    /// Left stick click.
+    LeftStick,
+    /// Right stick click.
+    RightStick,
+    /// D-pad up.
+    DPadUp,
+    /// D-pad down.
+    DPadDown,
+    /// D-pad left.
+    DPadLeft,
+    /// D-pad right.
+    DPadRight,
+}
+
+/// A gamepad stick or trigger axis. Sticks range over `-1.0..=1.0` with
+/// positive up and right; triggers over `0.0..=1.0`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GamepadAxis {
+    /// Left stick, horizontal.
+    LeftStickX,
+    /// Left stick, vertical.
+    LeftStickY,
+    /// Right stick, horizontal.
+    RightStickX,
+    /// Right stick, vertical.
+    RightStickY,
+    /// Left trigger travel.
+    LeftTrigger,
+    /// Right trigger travel.
+    RightTrigger,
+}
+
+/// A change reported by one gamepad.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GamepadEvent {
+    /// A gamepad was connected.
+    Connected(GamepadId),
+    /// A gamepad was disconnected; everything it held is released.
+    Disconnected(GamepadId),
+    /// A button was pressed or released.
+    Button {
+        /// Reporting gamepad.
+        gamepad: GamepadId,
+        /// Button that changed.
+        button: GamepadButton,
+        /// New state.
+        state: ElementState,
+    },
+    /// An axis moved to a new absolute position.
+    Axis {
+        /// Reporting gamepad.
+        gamepad: GamepadId,
+        /// Axis that moved.
+        axis: GamepadAxis,
+        /// New position.
+        value: f32,
+    },
+}
+
+impl GamepadEvent {
+    /// Gamepad that reported the event.
+    pub fn gamepad(&self) -> GamepadId {
+        match *self {
+            Self::Connected(gamepad) | Self::Disconnected(gamepad) => gamepad,
+            Self::Button { gamepad, .. } | Self::Axis { gamepad, .. } => gamepad,
+        }
+    }
+}
+
+/// Failure to open the platform's gamepad backend.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GamepadError(String);
+
+impl fmt::Display for GamepadError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(formatter)
+    }
+}
+
+impl Error for GamepadError {}
+
+/// Connected gamepads, read through gilrs.
+///
+/// Gamepads are not window input, so the runtime does not poll them; call
+/// [`Gamepads::poll`] once per update and pass each event to
+/// [`ActionMap::handle_gamepad_event`](crate::ActionMap::handle_gamepad_event)
+/// or [`InputState::handle_gamepad_event`](crate::InputState::handle_gamepad_event).
+/// Gamepads already connected when it is created report
+/// [`GamepadEvent::Connected`] on the first poll.
+#[cfg(feature = "gamepad")]
+pub struct Gamepads {
+    gilrs: gilrs::Gilrs,
+    pending: Vec<GamepadEvent>,
+}
+
+#[cfg(feature = "gamepad")]
+impl Gamepads {
+    /// Opens the platform backend. Platforms gilrs does not support yield a
+    /// source that never reports a gamepad rather than an error.
+    pub fn new() -> Result<Self, GamepadError> {
+        let gilrs = match gilrs::Gilrs::new() {
+            Ok(gilrs) | Err(gilrs::Error::NotImplemented(gilrs)) => gilrs,
+            Err(error) => return Err(GamepadError(error.to_string())),
+        };
+        let pending = gilrs
+            .gamepads()
+            .map(|(id, _)| GamepadEvent::Connected(GamepadId(id.into())))
+            .collect();
+        Ok(Self { gilrs, pending })
+    }
+
+    /// Events reported since the previous poll, oldest first.
+    pub fn poll(&mut self) -> Vec<GamepadEvent> {
+        let mut events = std::mem::take(&mut self.pending);
+        while let Some(event) = self.gilrs.next_event() {
+            events.extend(convert(event));
+        }
+        events
+    }
+
+    /// Gamepads connected as of the last poll.
+    pub fn connected(&self) -> impl Iterator<Item = GamepadId> + '_ {
+        self.gilrs.gamepads().map(|(id, _)| GamepadId(id.into()))
+    }
+
+    /// Human-readable name of a connected gamepad.
+    pub fn name(&self, gamepad: GamepadId) -> Option<String> {
+        self.gilrs
+            .gamepads()
+            .find(|(id, _)| usize::from(*id) == gamepad.0)
+            .map(|(_, pad)| pad.name().to_owned())
+    }
+}
+
+#[cfg(feature = "gamepad")]
+impl fmt::Debug for Gamepads {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Gamepads")
+            .field("connected", &self.connected().collect::<Vec<_>>())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "gamepad")]
+fn convert(event: gilrs::Event) -> Option<GamepadEvent> {
+    use gilrs::EventType;
+
+    let gamepad = GamepadId(event.id.into());
+    Some(match event.event {
+        EventType::Connected => GamepadEvent::Connected(gamepad),
+        EventType::Disconnected => GamepadEvent::Disconnected(gamepad),
+        EventType::ButtonPressed(button, _) => GamepadEvent::Button {
+            gamepad,
+            button: convert_button(button)?,
+            state: ElementState::Pressed,
+        },
+        EventType::ButtonReleased(button, _) => GamepadEvent::Button {
+            gamepad,
+            button: convert_button(button)?,
+            state: ElementState::Released,
+        },
+        // Analog triggers report their travel as a button value.
+        EventType::ButtonChanged(gilrs::Button::LeftTrigger2, value, _) => GamepadEvent::Axis {
+            gamepad,
+            axis: GamepadAxis::LeftTrigger,
+            value,
+        },
+        EventType::ButtonChanged(gilrs::Button::RightTrigger2, value, _) => GamepadEvent::Axis {
+            gamepad,
+            axis: GamepadAxis::RightTrigger,
+            value,
+        },
+        EventType::AxisChanged(axis, value, _) => GamepadEvent::Axis {
+            gamepad,
+            axis: convert_axis(axis)?,
+            value,
+        },
+        _ => return None,
+    })
+}
+
+#[cfg(feature = "gamepad")]
+fn convert_button(button: gilrs::Button) -> Option<GamepadButton> {
+    use gilrs::Button;
+
+    Some(match button {
+        Button::South => GamepadButton::South,
+        Button::East => GamepadButton::East,
+        Button::North => GamepadButton::North,
+        Button::West => GamepadButton::West,
+        Button::LeftTrigger => GamepadButton::LeftBumper,
+        Button::LeftTrigger2 => GamepadButton::LeftTrigger,
+        Button::RightTrigger => GamepadButton::RightBumper,
+        Button::RightTrigger2 => GamepadButton::RightTrigger,
+        Button::Select => GamepadButton::Select,
+        Button::Start => GamepadButton::Start,
+        Button::Mode => GamepadButton::Mode,
+        Button::LeftThumb => GamepadButton::LeftStick,
+        Button::RightThumb => GamepadButton::RightStick,
+        Button::DPadUp => GamepadButton::DPadUp,
+        Button::DPadDown => GamepadButton::DPadDown,
+        Button::DPadLeft => GamepadButton::DPadLeft,
+        Button::DPadRight => GamepadButton::DPadRight,
+        _ => return None,
+    })
+}
+
+#[cfg(feature = "gamepad")]
+fn convert_axis(axis: gilrs::Axis) -> Option<GamepadAxis> {
+    use gilrs::Axis;
+
+    Some(match axis {
+        Axis::LeftStickX => GamepadAxis::LeftStickX,
+        Axis::LeftStickY => GamepadAxis::LeftStickY,
+        Axis::RightStickX => GamepadAxis::RightStickX,
+        Axis::RightStickY => GamepadAxis::RightStickY,
+        Axis::LeftZ => GamepadAxis::LeftTrigger,
+        Axis::RightZ => GamepadAxis::RightTrigger,
+        _ => return None,
+    })
+}
@@ -0,0 +1,138 @@
+//! Windowless runner driving a runtime on a simulated clock.
+
+use std::time::Duration;
+
+use astrelis_core::geometry::{Physical, Size};
+use astrelis_platform::{
+    Application, DeviceEvent, DeviceId, Instant, PlatformContext, StartCause, WindowEvent, WindowId,
+};
+use astrelis_platform_test::{ScriptEvent, TestRunner};
+
+use crate::{App, ManualClock, Runtime, RuntimeConfig, RuntimeEvent, RuntimePolicy};
+
+/// Settings for [`run_headless_with`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HeadlessConfig {
+    /// Runtime scheduling configuration.
+    pub runtime: RuntimeConfig,
+    /// Simulated time between frames.
+    pub frame_time: Duration,
+    /// Physical size of windows created without an explicit size.
+    pub window_size: Size<Physical, u32>,
+}
+
+impl Default for HeadlessConfig {
+    /// Updates continuously at a simulated 60 Hz with 1280×720 windows.
+    fn default() -> Self {
+        Self {
+            runtime: RuntimeConfig {
+                policy: RuntimePolicy::continuous(),
+                ..RuntimeConfig::default()
+            },
+            frame_time: Duration::from_nanos(16_666_667),
+            window_size: Size::new(1280, 720),
+        }
+    }
+}
+
+/// Runs `frames` simulated frames with [`HeadlessConfig::default`].
+pub fn run_headless<A: App>(app: A, frames: u32) -> Result<A, A::Error> {
+    run_headless_with(app, frames, HeadlessConfig::default())
+}
+
+/// Runs an application without an event loop or OS windows.
+///
+/// The app runs on the scripted [`astrelis_platform_test`] backend: it is
+/// resumed once, then every frame advances a [`ManualClock`] by
+/// [`HeadlessConfig::frame_time`], drains queued work, runs timers and the
+/// update callbacks the policy schedules, and delivers [`App::redraw`] to
+/// every window that requested one. Windows report their size at scale 1.0,
+/// have no raw handles, and record every other command without effect, so
+/// renderers must skip surface creation. The run stops early when the app
+/// exits or a callback fails.
+pub fn run_headless_with<A: App>(
+    app: A,
+    frames: u32,
+    config: HeadlessConfig,
+) -> Result<A, A::Error> {
+    let clock = ManualClock::new(Instant::now());
+    let stepper = Stepper {
+        runtime: Runtime::with_clock(app, config.runtime, clock.clone()),
+        clock,
+        frame_time: config.frame_time,
+    };
+    let mut runner = TestRunner::new();
+    runner.set_window_size(config.window_size);
+    runner.push(ScriptEvent::NewEvents(StartCause::Init));
+    runner.push(ScriptEvent::Resumed);
+    for _ in 0..frames {
+        runner.push(ScriptEvent::NewEvents(StartCause::Poll));
+        runner.push(ScriptEvent::AboutToWait);
+        runner.push(ScriptEvent::PendingRedraws);
+    }
+    let (stepper, _) = runner
+        .run_return(stepper)
+        .expect("the scripted backend does not fail");
+    stepper.runtime.into_result()
+}
+
+/// Forwards to the runtime, advancing the simulated clock at the start of
+/// every frame.
+struct Stepper<A: App> {
+    runtime: Runtime<A>,
+    clock: ManualClock,
+    frame_time: Duration,
+}
+
+impl<A: App> Application for Stepper<A> {
+    type UserEvent = RuntimeEvent<A>;
+
+    fn new_events(
+        &mut self,
+        context: &mut PlatformContext<'_, Self::UserEvent>,
+        cause: StartCause,
+    ) {
+        if cause == StartCause::Poll {
+            self.clock.advance(self.frame_time);
+        }
+        self.runtime.new_events(context, cause);
+    }
+    fn resumed(&mut self, context: &mut PlatformContext<'_, Self::UserEvent>) {
+        self.runtime.resumed(context);
+    }
+    fn suspended(&mut self, context: &mut PlatformContext<'_, Self::UserEvent>) {
+        self.runtime.suspended(context);
+    }
+    fn window_event(
+        &mut self,
+        context: &mut PlatformContext<'_, Self::UserEvent>,
+        window: WindowId,
+        event: WindowEvent,
+    ) {
+        self.runtime.window_event(context, window, event);
+    }
+    fn device_event(
+        &mut self,
+        context: &mut PlatformContext<'_, Self::UserEvent>,
+        device: DeviceId,
+        event: DeviceEvent,
+    ) {
+        self.runtime.device_event(context, device, event);
+    }
+    fn user_event(
+        &mut self,
+        context: &mut PlatformContext<'_, Self::UserEvent>,
+        event: Self::UserEvent,
+    ) {
+        self.runtime.user_event(context, event);
+    }
+    fn memory_warning(&mut self, context: &mut PlatformContext<'_, Self::UserEvent>) {
+        self.runtime.memory_warning(context);
+    }
+    fn about_to_wait(&mut self, context: &mut PlatformContext<'_, Self::UserEvent>) {
+        self.runtime.about_to_wait(context);
+    }
+    fn exiting(&mut self, context: &mut PlatformContext<'_, Self::UserEvent>) {
+        self.runtime.exiting(context);
+    }
+}
@@ -0,0 +1,491 @@
+//! Per-frame keyboard, pointer, and raw device state.
+
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use crate::gamepad::{GamepadAxis, GamepadButton, GamepadEvent, GamepadId};
+use astrelis_core::geometry::{Logical, Physical, Point};
+use astrelis_platform::{
+    DeviceEvent, ElementState, Key, KeyCode, Modifiers, PhysicalKey, PointerButton, ScrollDelta,
+    WindowEvent, WindowId,
+};
+
+/// A file dropped onto the window.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FileDrop {
+    /// Dropped file path.
+    pub path: PathBuf,
+    /// Last known pointer position inside the window, if any. Platforms
+    /// often stop reporting pointer motion during a drag, so this may lag.
+    pub position: Option<Point<Physical, f64>>,
+}
+
+/// Held inputs and this frame's transitions, built from platform events.
+///
+/// Feed every window, device, and gamepad event through
+/// [`InputState::handle_window_event`],
+/// [`InputState::handle_device_event`], and
+/// [`InputState::handle_gamepad_event`], read state during the update, then
+/// call [`InputState::end_frame`] to clear transitions, deltas, wheel
+/// accumulation, and dropped files. Losing focus releases every held key
+/// and button, so keys released in another window do not stay stuck;
+/// gamepads report regardless of focus and keep their state.
+///
+/// Keys are tracked both by physical position ([`KeyCode`]) and by the
+/// lowercase character the active layout maps them to, so `"z"` follows the
+/// Z key on AZERTY and Dvorak as well as QWERTY.
+#[derive(Clone, Debug, Default)]
+pub struct InputState {
+    keys: Transitions<KeyCode>,
+    characters: Transitions<String>,
+    key_characters: HashMap<KeyCode, String>,
+    buttons: Transitions<PointerButton>,
+    device_buttons: Transitions<u32>,
+    axes: HashMap<u32, f32>,
+    gamepads: BTreeSet<GamepadId>,
+    gamepad_buttons: Transitions<(GamepadId, GamepadButton)>,
+    gamepad_axes: HashMap<(GamepadId, GamepadAxis), f32>,
+    modifiers: Modifiers,
+    cursor: Option<Point<Physical, f64>>,
+    cursor_delta: Point<Physical, f64>,
+    motion: Point<Physical, f64>,
+    wheel_lines: Point<Logical, f32>,
+    wheel_pixels: Point<Physical, f64>,
+    hovered_files: Vec<PathBuf>,
+    dropped_files: Vec<FileDrop>,
+}
+
+/// A window gaining or losing keyboard focus.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FocusChange {
+    /// Window whose focus changed.
+    pub window: WindowId,
+    /// Whether it gained focus.
+    pub focused: bool,
+}
+
+/// One [`InputState`] per window, so multi-window editors keep each
+/// document's keys and pointer separate.
+///
+/// Window events update only their own window's state. Raw device events
+/// carry no window and go to the window holding keyboard focus.
+#[derive(Clone, Debug, Default)]
+pub struct WindowInputs {
+    windows: HashMap<WindowId, InputState>,
+    focused: Option<WindowId>,
+    focus_changes: Vec<FocusChange>,
+}
+
+impl WindowInputs {
+    /// Creates tracking with no windows.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Updates the window's state, tracking focus and forgetting destroyed
+    /// windows.
+    pub fn handle_window_event(&mut self, window: WindowId, event: &WindowEvent) {
+        match event {
+            WindowEvent::Destroyed => {
+                self.windows.remove(&window);
+                if self.focused == Some(window) {
+                    self.focused = None;
+                }
+                return;
+            }
+            WindowEvent::Focused(focused) => {
+                if *focused {
+                    self.focused = Some(window);
+                } else if self.focused == Some(window) {
+                    self.focused = None;
+                }
+                self.focus_changes.push(FocusChange {
+                    window,
+                    focused: *focused,
+                });
+            }
+            _ => {}
+        }
+        self.windows
+            .entry(window)
+            .or_default()
+            .handle_window_event(event);
+    }
+
+    /// Updates the focused window's state from a raw device event; dropped
+    /// while no window is focused.
+    pub fn handle_device_event(&mut self, event: &DeviceEvent) {
+        if let Some(state) = self
+            .focused
+            .and_then(|window| self.windows.get_mut(&window))
+        {
+            state.handle_device_event(event);
+        }
+    }
+
+    /// Ends the frame of every window's state and clears focus changes.
+    pub fn end_frame(&mut self) {
+        for state in self.windows.values_mut() {
+            state.end_frame();
+        }
+        self.focus_changes.clear();
+    }
+
+    /// Window holding keyboard focus.
+    pub fn focused(&self) -> Option<WindowId> {
+        self.focused
+    }
+
+    /// Input of the window holding keyboard focus.
+    pub fn focused_input(&self) -> Option<&InputState> {
+        self.focused.and_then(|window| self.windows.get(&window))
+    }
+
+    /// Input of one window, `None` before its first event.
+    pub fn window(&self, window: WindowId) -> Option<&InputState> {
+        self.windows.get(&window)
+    }
+
+    /// Focus gains and losses this frame, in order.
+    pub fn focus_changes(&self) -> &[FocusChange] {
+        &self.focus_changes
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Transitions<T> {
+    held: HashSet<T>,
+    pressed: HashSet<T>,
+    released: HashSet<T>,
+}
+
+impl<T> Default for Transitions<T> {
+    fn default() -> Self {
+        Self {
+            held: HashSet::new(),
+            pressed: HashSet::new(),
+            released: HashSet::new(),
+        }
+    }
+}
+
+impl<T: Clone + Eq + std::hash::Hash> Transitions<T> {
+    fn set(&mut self, input: T, state: ElementState) {
+        match state {
+            ElementState::Pressed => {
+                if self.held.insert(input.clone()) {
+                    self.pressed.insert(input);
+                }
+            }
+            ElementState::Released => {
+                if self.held.remove(&input) {
+                    self.released.insert(input);
+                }
+            }
+        }
+    }
+
+    fn release_all(&mut self) {
+        self.released.extend(self.held.drain());
+    }
+
+    fn release_where(&mut self, mut filter: impl FnMut(&T) -> bool) {
+        let released: Vec<T> = self
+            .held
+            .iter()
+            .filter(|input| filter(input))
+            .cloned()
+            .collect();
+        for input in released {
+            self.held.remove(&input);
+            self.released.insert(input);
+        }
+    }
+
+    fn end_frame(&mut self) {
+        self.pressed.clear();
+        self.released.clear();
+    }
+}
+
+impl InputState {
+    /// Creates state with nothing held.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Updates state from a window event.
+    pub fn handle_window_event(&mut self, event: &WindowEvent) {
+        match event {
+            WindowEvent::KeyboardInput(input) if !input.repeat => {
+                if let PhysicalKey::Code(code) = &input.physical_key {
+                    self.keys.set(code.clone(), input.state);
+                    // Releases follow the key's press, since modifiers held
+                    // in between can change the character it maps to.
+                    match input.state {
+                        ElementState::Pressed => {
+                            if let Key::Character(text) = &input.logical_key {
+                                let character = text.to_lowercase();
+                                self.key_characters.insert(code.clone(), character.clone());
+                                self.characters.set(character, ElementState::Pressed);
+                            }
+                        }
+                        ElementState::Released => {
+                            if let Some(character) = self.key_characters.remove(code) {
+                                self.characters.set(character, ElementState::Released);
+                            }
+                        }
+                    }
+                }
+            }
+            WindowEvent::PointerButton { button, state, .. } => {
+                self.buttons.set(*button, *state);
+            }
+            WindowEvent::PointerMoved { position, .. } => {
+                if let Some(previous) = self.cursor {
+                    self.cursor_delta.x += position.x - previous.x;
+                    self.cursor_delta.y += position.y - previous.y;
+                }
+                self.cursor = Some(*position);
+            }
+            WindowEvent::PointerLeft { .. } => self.cursor = None,
+            WindowEvent::PointerWheel { delta, .. } => match delta {
+                ScrollDelta::Lines { x, y } => {
+                    self.wheel_lines.x += x;
+                    self.wheel_lines.y += y;
+                }
+                ScrollDelta::Pixels(pixels) => {
+                    self.wheel_pixels.x += pixels.x;
+                    self.wheel_pixels.y += pixels.y;
+                }
+            },
+            WindowEvent::HoveredFile(path) => self.hovered_files.push(path.clone()),
+            WindowEvent::HoveredFileCancelled => self.hovered_files.clear(),
+            WindowEvent::DroppedFile(path) => {
+                self.hovered_files.retain(|hovered| hovered != path);
+                self.dropped_files.push(FileDrop {
+                    path: path.clone(),
+                    position: self.cursor,
+                });
+            }
+            WindowEvent::ModifiersChanged(modifiers) => self.modifiers = *modifiers,
+            WindowEvent::Focused(false) => self.release_all(),
+            _ => {}
+        }
+    }
+
+    /// Updates state from a raw device event.
+    pub fn handle_device_event(&mut self, event: &DeviceEvent) {
+        match event {
+            DeviceEvent::Button { button, state } => self.device_buttons.set(*button, *state),
+            DeviceEvent::Motion { axis, value } => {
+                *self.axes.entry(*axis).or_default() += *value as f32;
+            }
+            DeviceEvent::MouseMotion { delta: (x, y) } => {
+                self.motion.x += x;
+                self.motion.y += y;
+            }
+            DeviceEvent::Removed => {
+                self.device_buttons.release_all();
+                self.axes.clear();
+            }
+            _ => {}
+        }
+    }
+
+    /// Updates state from a gamepad event.
+    pub fn handle_gamepad_event(&mut self, event: &GamepadEvent) {
+        match *event {
+            GamepadEvent::Connected(gamepad) => {
+                self.gamepads.insert(gamepad);
+            }
+            GamepadEvent::Disconnected(gamepad) => {
+                self.gamepads.remove(&gamepad);
+                self.gamepad_buttons
+                    .release_where(|(held, _)| *held == gamepad);
+                self.gamepad_axes.retain(|(held, _), _| *held != gamepad);
+            }
+            GamepadEvent::Button {
+                gamepad,
+                button,
+                state,
+            } => {
+                self.gamepads.insert(gamepad);
+                self.gamepad_buttons.set((gamepad, button), state);
+            }
+            GamepadEvent::Axis {
+                gamepad,
+                axis,
+                value,
+            } => {
+                self.gamepads.insert(gamepad);
+                self.gamepad_axes.insert((gamepad, axis), value);
+            }
+        }
+    }
+
+    /// Releases every held key and button and zeroes device axes. Gamepad
+    /// state is kept.
+    pub fn release_all(&mut self) {
+        self.keys.release_all();
+        self.characters.release_all();
+        self.key_characters.clear();
+        self.buttons.release_all();
+        self.device_buttons.release_all();
+        self.axes.clear();
+        self.modifiers = Modifiers::default();
+    }
+
+    /// Clears this frame's transitions, deltas, device axis motion, wheel
+    /// accumulation, and dropped files.
+    pub fn end_frame(&mut self) {
+        self.keys.end_frame();
+        self.characters.end_frame();
+        self.buttons.end_frame();
+        self.device_buttons.end_frame();
+        self.gamepad_buttons.end_frame();
+        self.axes.clear();
+        self.cursor_delta = Point::zero();
+        self.motion = Point::zero();
+        self.wheel_lines = Point::zero();
+        self.wheel_pixels = Point::zero();
+        self.dropped_files.clear();
+    }
+
+    /// Returns whether a key is held.
+    pub fn key_pressed(&self, key: &KeyCode) -> bool {
+        self.keys.held.contains(key)
+    }
+
+    /// Returns whether a key went down this frame.
+    pub fn key_just_pressed(&self, key: &KeyCode) -> bool {
+        self.keys.pressed.contains(key)
+    }
+
+    /// Returns whether a key went up this frame.
+    pub fn key_just_released(&self, key: &KeyCode) -> bool {
+        self.keys.released.contains(key)
+    }
+
+    /// Returns whether a key that types `character` in the active layout is
+    /// held, ignoring case.
+    pub fn character_pressed(&self, character: &str) -> bool {
+        self.characters.held.contains(&character.to_lowercase())
+    }
+
+    /// Returns whether a key that types `character` went down this frame.
+    pub fn character_just_pressed(&self, character: &str) -> bool {
+        self.characters.pressed.contains(&character.to_lowercase())
+    }
+
+    /// Returns whether a key that types `character` went up this frame.
+    pub fn character_just_released(&self, character: &str) -> bool {
+        self.characters.released.contains(&character.to_lowercase())
+    }
+
+    /// Returns whether a pointer button is held.
+    pub fn button_pressed(&self, button: PointerButton) -> bool {
+        self.buttons.held.contains(&button)
+    }
+
+    /// Returns whether a pointer button went down this frame.
+    pub fn button_just_pressed(&self, button: PointerButton) -> bool {
+        self.buttons.pressed.contains(&button)
+    }
+
+    /// Returns whether a pointer button went up this frame.
+    pub fn button_just_released(&self, button: PointerButton) -> bool {
+        self.buttons.released.contains(&button)
+    }
+
+    /// Returns whether a raw device button is held.
+    pub fn device_button_pressed(&self, button: u32) -> bool {
+        self.device_buttons.held.contains(&button)
+    }
+
+    /// Returns whether a raw device button went down this frame.
+    pub fn device_button_just_pressed(&self, button: u32) -> bool {
+        self.device_buttons.pressed.contains(&button)
+    }
+
+    /// Returns whether a raw device button went up this frame.
+    pub fn device_button_just_released(&self, button: u32) -> bool {
+        self.device_buttons.released.contains(&button)
+    }
+
+    /// Relative motion of a raw device axis this frame, such as mouse
+    /// movement; zero in frames where it did not move.
+    pub fn axis(&self, axis: u32) -> f32 {
+        self.axes.get(&axis).copied().unwrap_or(0.0)
+    }
+
+    /// Gamepads that are connected or have reported input.
+    pub fn gamepads(&self) -> impl ExactSizeIterator<Item = GamepadId> + '_ {
+        self.gamepads.iter().copied()
+    }
+
+    /// Returns whether a gamepad button is held.
+    pub fn gamepad_button_pressed(&self, gamepad: GamepadId, button: GamepadButton) -> bool {
+        self.gamepad_buttons.held.contains(&(gamepad, button))
+    }
+
+    /// Returns whether a gamepad button went down this frame.
+    pub fn gamepad_button_just_pressed(&self, gamepad: GamepadId, button: GamepadButton) -> bool {
+        self.gamepad_buttons.pressed.contains(&(gamepad, button))
+    }
+
+    /// Returns whether a gamepad button went up this frame.
+    pub fn gamepad_button_just_released(&self, gamepad: GamepadId, button: GamepadButton) -> bool {
+        self.gamepad_buttons.released.contains(&(gamepad, button))
+    }
+
+    /// Position of a gamepad stick or trigger, zero before it first moves.
+    pub fn gamepad_axis(&self, gamepad: GamepadId, axis: GamepadAxis) -> f32 {
+        self.gamepad_axes
+            .get(&(gamepad, axis))
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    /// Held modifiers.
+    pub fn modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
+    /// Pointer position inside the window, `None` while outside it.
+    pub fn cursor_position(&self) -> Option<Point<Physical, f64>> {
+        self.cursor
+    }
+
+    /// Pointer movement inside the window this frame.
+    pub fn cursor_delta(&self) -> Point<Physical, f64> {
+        self.cursor_delta
+    }
+
+    /// Raw, unaccelerated mouse motion this frame, in device units.
+    pub fn mouse_motion(&self) -> Point<Physical, f64> {
+        self.motion
+    }
+
+    /// Line-based wheel scrolling this frame.
+    pub fn wheel_lines(&self) -> Point<Logical, f32> {
+        self.wheel_lines
+    }
+
+    /// Pixel-based wheel scrolling this frame, as from trackpads.
+    pub fn wheel_pixels(&self) -> Point<Physical, f64> {
+        self.wheel_pixels
+    }
+
+    /// Files currently dragged over the window, for drop previews.
+    pub fn hovered_files(&self) -> impl ExactSizeIterator<Item = &Path> {
+        self.hovered_files.iter().map(PathBuf::as_path)
+    }
+
+    /// Files dropped onto the window this frame.
+    pub fn dropped_files(&self) -> &[FileDrop] {
+        &self.dropped_files
+    }
+}
@@ -2,6 +2,13 @@
 
 #![warn(missing_docs)]
 
+mod action;
+mod gamepad;
+mod headless;
+mod input;
+mod pacing;
+mod stats;
+
 use std::{
     collections::{BTreeMap, HashMap, VecDeque},
     error::Error,
@@ -19,8 +26,18 @@ use astrelis_platform::{
     WindowId,
 };
 
+pub use action::{ActionMap, Binding, Input};
+#[cfg(feature = "gamepad")]
+pub use gamepad::Gamepads;
+pub use gamepad::{GamepadAxis, GamepadButton, GamepadError, GamepadEvent, GamepadId};
+pub use headless::{HeadlessConfig, run_headless, run_headless_with};
+pub use input::{FileDrop, FocusChange, InputState, WindowInputs};
+pub use pacing::{FramePacer, FrameTime};
+pub use stats::{BudgetAlert, FramePhase, FrameStats, Percentiles};
+
 const DEFAULT_TASK_BATCH_LIMIT: usize = 1_024;
 const DEFAULT_MAX_FIXED_STEPS: u32 = 8;
+const DEFAULT_STATS_WINDOW: usize = 120;
 
 /// A monotonic source of runtime time.
 pub trait Clock: fmt::Debug + Send + Sync + 'static {
@@ -131,6 +148,8 @@ pub struct RuntimeConfig {
     pub policy: RuntimePolicy,
     /// Maximum queued tasks executed for one wake event.
     pub task_batch_limit: usize,
+    /// Frames retained by the runtime's [`FrameStats`].
+    pub stats_window: usize,
 }
 
 impl Default for RuntimeConfig {
@@ -138,6 +157,7 @@ impl Default for RuntimeConfig {
         Self {
             policy: RuntimePolicy::Desktop,
             task_batch_limit: DEFAULT_TASK_BATCH_LIMIT,
+            stats_window: DEFAULT_STATS_WINDOW,
         }
     }
 }
@@ -147,9 +167,13 @@ impl Default for RuntimeConfig {
 pub struct UpdateInfo {
     /// Time since the previous variable update.
     pub delta: Duration,
+    /// Game time since the previous variable update: `delta` multiplied by
+    /// the time scale, or zero while paused.
+    pub scaled_delta: Duration,
     /// Time since the runtime was first resumed.
     pub elapsed: Duration,
-    /// Fractional fixed-step accumulator in the range `0.0..1.0`.
+    /// Fractional fixed-step accumulator in the range `0.0..1.0`, the alpha
+    /// for blending the previous and current simulation states when drawing.
     pub interpolation: f64,
 }
 
@@ -166,6 +190,18 @@ pub struct FixedUpdateInfo {
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct TimerId(u64);
 
+/// A time scale outside `0.0..=AppContext::MAX_TIME_SCALE`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TimeScaleError(String);
+
+impl fmt::Display for TimeScaleError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(formatter)
+    }
+}
+
+impl Error for TimeScaleError {}
+
 /// Error returned after a runtime-driven application terminates.
 #[derive(Debug)]
 pub enum RuntimeError<E> {
@@ -204,10 +240,23 @@ pub trait App: Sized + 'static {
     }
 
     /// Called when native resources should be considered unavailable.
+    ///
+    /// Mobile platforms destroy window surfaces while suspended, so
+    /// renderers stop presenting here and recreate surfaces in
+    /// [`App::resumed`].
     fn suspended(&mut self, _context: &mut AppContext<'_, '_, Self>) -> Result<(), Self::Error> {
         Ok(())
     }
 
+    /// Called when the OS is low on memory; release caches that can be
+    /// rebuilt, since mobile platforms may terminate apps that do not.
+    fn memory_warning(
+        &mut self,
+        _context: &mut AppContext<'_, '_, Self>,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
     /// Delivers an event for a registered or platform-known window.
     fn window_event(
         &mut self,
@@ -371,18 +420,23 @@ struct State<A: App> {
     cancel_active_timer: bool,
     suspended: bool,
     work_pending: bool,
+    redrawn: bool,
     started_at: Option<Instant>,
     last_update: Option<Instant>,
     last_frame: Option<Instant>,
     next_frame: Option<Instant>,
     fixed_accumulator: Duration,
     fixed_elapsed: Duration,
+    time_scale: f64,
+    paused: bool,
+    stats: FrameStats,
     application_error: Option<A::Error>,
 }
 
 impl<A: App> State<A> {
     fn new(clock: Arc<dyn Clock>, config: RuntimeConfig, shared: Arc<Shared<A>>) -> Self {
         Self {
+            stats: FrameStats::with_shared_clock(config.stats_window, clock.clone()),
             clock,
             shared,
             policy: config.policy,
@@ -395,12 +449,15 @@ impl<A: App> State<A> {
             cancel_active_timer: false,
             suspended: true,
             work_pending: false,
+            redrawn: false,
             started_at: None,
             last_update: None,
             last_frame: None,
             next_frame: None,
             fixed_accumulator: Duration::ZERO,
             fixed_elapsed: Duration::ZERO,
+            time_scale: 1.0,
+            paused: false,
             application_error: None,
         }
     }
@@ -413,6 +470,10 @@ pub struct AppContext<'a, 'platform, A: App> {
 }
 
 impl<A: App> AppContext<'_, '_, A> {
+    /// Largest accepted [`AppContext::set_time_scale`], so a scaled frame
+    /// delta stays far from [`Duration`]'s range.
+    pub const MAX_TIME_SCALE: f64 = 1_000.0;
+
     /// Returns all currently available monitors.
     pub fn available_monitors(&self) -> Vec<astrelis_platform::Monitor> {
         self.platform.available_monitors()
@@ -559,6 +620,58 @@ impl<A: App> AppContext<'_, '_, A> {
         self.state.work_pending = true;
     }
 
+    /// Returns the multiplier applied to game time.
+    pub fn time_scale(&self) -> f64 {
+        self.state.time_scale
+    }
+
+    /// Speeds up or slows down game time, such as `0.25` for slow motion.
+    ///
+    /// Fixed updates run at the same simulated step, just more or less often
+    /// per real second. Scales that are negative, NaN, or above
+    /// [`AppContext::MAX_TIME_SCALE`] are rejected and leave the current
+    /// scale in place.
+    pub fn set_time_scale(&mut self, scale: f64) -> Result<(), TimeScaleError> {
+        if !(0.0..=Self::MAX_TIME_SCALE).contains(&scale) {
+            return Err(TimeScaleError(format!(
+                "time scale {scale} is outside 0 to {}",
+                Self::MAX_TIME_SCALE
+            )));
+        }
+        self.state.time_scale = scale;
+        Ok(())
+    }
+
+    /// Returns whether game time is paused.
+    pub fn paused(&self) -> bool {
+        self.state.paused
+    }
+
+    /// Pauses or resumes game time. While paused, fixed updates stop and
+    /// [`UpdateInfo::scaled_delta`] is zero, but variable updates and redraws
+    /// continue so pause menus stay responsive.
+    pub fn set_paused(&mut self, paused: bool) {
+        self.state.paused = paused;
+    }
+
+    /// Per-phase CPU timings of recent frames.
+    ///
+    /// The runtime records [`FramePhase::Events`] around event callbacks,
+    /// [`FramePhase::Update`] around timers and updates, and
+    /// [`FramePhase::Render`] around [`App::redraw`], and closes a frame
+    /// whenever a loop iteration redraws. Layout and presentation happen
+    /// inside `redraw`, so apps record them through [`Self::frame_stats_mut`];
+    /// that time is not counted again as render time.
+    pub fn frame_stats(&self) -> &FrameStats {
+        &self.state.stats
+    }
+
+    /// Mutable access to [`Self::frame_stats`], for recording
+    /// [`FramePhase::Layout`] and [`FramePhase::Present`] or adding budgets.
+    pub fn frame_stats_mut(&mut self) -> &mut FrameStats {
+        &mut self.state.stats
+    }
+
     /// Requests orderly application termination.
     pub fn exit(&mut self) {
         self.platform.exit();
@@ -624,6 +737,14 @@ impl<A: App> Runtime<A> {
         }
     }
 
+    /// Runs `work` and records its duration against a phase of the frame.
+    fn timed(&mut self, phase: FramePhase, work: impl FnOnce(&mut Self)) {
+        let start = self.state.clock.now();
+        work(self);
+        let elapsed = self.state.clock.now().saturating_duration_since(start);
+        self.state.stats.record(phase, elapsed);
+    }
+
     fn process_tasks(&mut self, platform: &mut PlatformContext<'_, RuntimeEvent<A>>) {
         astrelis_profiling::profile_scope!("app.tasks");
         self.state
@@ -730,6 +851,11 @@ impl<A: App> Runtime<A> {
         let delta = previous.map_or(Duration::ZERO, |previous| {
             now.saturating_duration_since(previous)
         });
+        let scaled_delta = if self.state.paused {
+            Duration::ZERO
+        } else {
+            delta.mul_f64(self.state.time_scale)
+        };
         let started_at = *self.state.started_at.get_or_insert(now);
         let elapsed = now.saturating_duration_since(started_at);
 
@@ -740,7 +866,8 @@ impl<A: App> Runtime<A> {
         } = self.state.policy
         {
             assert!(!fixed.step.is_zero(), "fixed step must be non-zero");
-            self.state.fixed_accumulator += delta;
+            self.state.fixed_accumulator =
+                self.state.fixed_accumulator.saturating_add(scaled_delta);
             let mut steps = 0;
             while self.state.fixed_accumulator >= fixed.step
                 && steps < fixed.max_steps_per_frame.max(1)
@@ -757,9 +884,11 @@ impl<A: App> Runtime<A> {
                 }
                 steps += 1;
             }
-            while self.state.fixed_accumulator >= fixed.step {
-                self.state.fixed_accumulator -= fixed.step;
-            }
+            // Drop whole steps beyond the per-frame limit in one go; a long
+            // stall at a high time scale can leave millions of them.
+            self.state.fixed_accumulator = Duration::from_nanos(
+                (self.state.fixed_accumulator.as_nanos() % fixed.step.as_nanos()) as u64,
+            );
             interpolation = self.state.fixed_accumulator.as_secs_f64() / fixed.step.as_secs_f64();
         }
 
@@ -768,6 +897,7 @@ impl<A: App> Runtime<A> {
                 context,
                 UpdateInfo {
                     delta,
+                    scaled_delta,
                     elapsed,
                     interpolation,
                 },
@@ -869,12 +999,27 @@ impl<A: App> Application for Runtime<A> {
     ) {
         match &event {
             WindowEvent::RedrawRequested => {
-                astrelis_profiling::profile_scope!("app.redraw");
-                if let Some(entry) = self.state.windows.get_mut(&window) {
-                    entry.redraw_pending = false;
-                    entry.dirty = false;
+                {
+                    astrelis_profiling::profile_scope!("app.redraw");
+                    if let Some(entry) = self.state.windows.get_mut(&window) {
+                        entry.redraw_pending = false;
+                        entry.dirty = false;
+                    }
+                    // Layout and present time the app records inside
+                    // `redraw` is excluded from render time.
+                    let nested = |stats: &FrameStats| {
+                        stats.recorded(FramePhase::Layout) + stats.recorded(FramePhase::Present)
+                    };
+                    let before = nested(&self.state.stats);
+                    let start = self.state.clock.now();
+                    self.call(platform, |app, context| app.redraw(context, window));
+                    let elapsed = self.state.clock.now().saturating_duration_since(start);
+                    let inner = nested(&self.state.stats).saturating_sub(before);
+                    self.state
+                        .stats
+                        .record(FramePhase::Render, elapsed.saturating_sub(inner));
                 }
-                self.call(platform, |app, context| app.redraw(context, window));
+                self.state.redrawn = true;
                 return;
             }
             WindowEvent::Destroyed => {
@@ -899,8 +1044,10 @@ impl<A: App> Application for Runtime<A> {
             _ => {}
         }
         self.state.work_pending = true;
-        self.call(platform, |app, context| {
-            app.window_event(context, window, event)
+        self.timed(FramePhase::Events, |runtime| {
+            runtime.call(platform, |app, context| {
+                app.window_event(context, window, event)
+            });
         });
     }
 
@@ -911,8 +1058,10 @@ impl<A: App> Application for Runtime<A> {
         event: DeviceEvent,
     ) {
         self.state.work_pending = true;
-        self.call(platform, |app, context| {
-            app.device_event(context, device, event)
+        self.timed(FramePhase::Events, |runtime| {
+            runtime.call(platform, |app, context| {
+                app.device_event(context, device, event)
+            });
         });
     }
 
@@ -921,17 +1070,32 @@ impl<A: App> Application for Runtime<A> {
         platform: &mut PlatformContext<'_, Self::UserEvent>,
         _event: Self::UserEvent,
     ) {
-        self.process_tasks(platform);
+        self.timed(FramePhase::Events, |runtime| {
+            runtime.process_tasks(platform)
+        });
     }
 
     fn about_to_wait(&mut self, platform: &mut PlatformContext<'_, Self::UserEvent>) {
+        // A loop iteration that redrew any window closes one profiler frame,
+        // however many windows it presented; the mark also applies the
+        // timeline's retention window.
+        if std::mem::take(&mut self.state.redrawn) {
+            astrelis_profiling::frame_mark();
+            self.state.stats.end_frame();
+        }
         let now = self.state.clock.now();
-        self.process_timers(platform, now);
-        self.run_updates(platform, now);
+        self.timed(FramePhase::Update, |runtime| {
+            runtime.process_timers(platform, now);
+            runtime.run_updates(platform, now);
+        });
         self.request_redraws();
         self.select_control_flow(platform);
     }
 
+    fn memory_warning(&mut self, platform: &mut PlatformContext<'_, Self::UserEvent>) {
+        self.call(platform, |app, context| app.memory_warning(context));
+    }
+
     fn exiting(&mut self, platform: &mut PlatformContext<'_, Self::UserEvent>) {
         self.call(platform, |app, context| app.exiting(context));
     }
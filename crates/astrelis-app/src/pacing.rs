@@ -0,0 +1,139 @@
+//! Frame-rate limiting for presentation without vsync.
+
+use std::{sync::Arc, time::Duration};
+
+use astrelis_platform::Instant;
+
+use crate::{Clock, SystemClock};
+
+/// Weight of the newest frame in [`FrameTime::average`].
+const AVERAGE_WEIGHT: f64 = 0.1;
+
+/// Timing of one frame measured by [`FramePacer`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FrameTime {
+    /// Time from the previous frame boundary to this one.
+    pub delta: Duration,
+    /// Portion of `delta` spent before the pacer started waiting.
+    pub work: Duration,
+    /// Portion of `delta` the pacer spent sleeping.
+    pub slept: Duration,
+    /// Exponential moving average of `delta`.
+    pub average: Duration,
+}
+
+impl FrameTime {
+    /// Frames per second implied by [`FrameTime::average`].
+    pub fn fps(&self) -> f64 {
+        if self.average.is_zero() {
+            0.0
+        } else {
+            1.0 / self.average.as_secs_f64()
+        }
+    }
+}
+
+/// Sleeps at the end of each frame to hold a target frame rate.
+///
+/// Fifo presentation already blocks on vsync; use a pacer with `Mailbox` or
+/// `Immediate` presentation, or to cap a [`crate::RuntimePolicy::continuous`]
+/// loop. Deadlines advance by whole intervals, so a short sleep overshoot is
+/// absorbed by the next frame instead of accumulating; a frame that misses
+/// its deadline entirely restarts the schedule.
+#[derive(Debug)]
+pub struct FramePacer {
+    clock: Arc<dyn Clock>,
+    target: Option<Duration>,
+    boundary: Option<Instant>,
+    deadline: Option<Instant>,
+    average: Option<Duration>,
+}
+
+impl FramePacer {
+    /// Creates a pacer on the system clock. `None` measures without waiting.
+    pub fn new(target: Option<Duration>) -> Self {
+        Self::with_clock(target, SystemClock)
+    }
+
+    /// Creates a pacer on an injected clock.
+    pub fn with_clock(target: Option<Duration>, clock: impl Clock) -> Self {
+        Self {
+            clock: Arc::new(clock),
+            target: target.filter(|target| !target.is_zero()),
+            boundary: None,
+            deadline: None,
+            average: None,
+        }
+    }
+
+    /// Target interval between frames.
+    pub fn target(&self) -> Option<Duration> {
+        self.target
+    }
+
+    /// Changes the target interval, for example when vsync is toggled.
+    pub fn set_target(&mut self, target: Option<Duration>) {
+        self.target = target.filter(|target| !target.is_zero());
+        self.deadline = None;
+    }
+
+    /// Sets the target as frames per second; non-positive rates disable
+    /// pacing.
+    pub fn set_target_fps(&mut self, fps: f64) {
+        self.set_target((fps.is_finite() && fps > 0.0).then(|| Duration::from_secs_f64(1.0 / fps)));
+    }
+
+    /// Time left before the current frame's deadline.
+    pub fn remaining(&self) -> Duration {
+        self.deadline
+            .map(|deadline| deadline.saturating_duration_since(self.clock.now()))
+            .unwrap_or_default()
+    }
+
+    /// Sleeps until the frame's deadline, then records the frame boundary.
+    ///
+    /// Browsers cannot block the main thread, so there this only records.
+    pub fn end_frame(&mut self) -> FrameTime {
+        #[cfg(not(target_arch = "wasm32"))]
+        let slept = {
+            let remaining = self.remaining();
+            if !remaining.is_zero() {
+                std::thread::sleep(remaining);
+            }
+            remaining
+        };
+        #[cfg(target_arch = "wasm32")]
+        let slept = Duration::ZERO;
+        self.record(slept)
+    }
+
+    /// Records a frame boundary without sleeping, for loops that wait on
+    /// [`FramePacer::remaining`] themselves and report how long they waited.
+    pub fn record(&mut self, slept: Duration) -> FrameTime {
+        let now = self.clock.now();
+        let delta = self
+            .boundary
+            .map(|boundary| now.saturating_duration_since(boundary))
+            .unwrap_or_default();
+        let average = match self.average {
+            Some(average) => average.mul_f64(1.0 - AVERAGE_WEIGHT) + delta.mul_f64(AVERAGE_WEIGHT),
+            None => delta,
+        };
+        self.boundary = Some(now);
+        self.average = Some(average);
+        self.deadline = self.target.map(|target| {
+            let deadline = self.deadline.unwrap_or(now) + target;
+            if deadline > now {
+                deadline
+            } else {
+                now + target
+            }
+        });
+        FrameTime {
+            delta,
+            work: delta.saturating_sub(slept),
+            slept,
+            average,
+        }
+    }
+}
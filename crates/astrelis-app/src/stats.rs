@@ -0,0 +1,260 @@
+//! Rolling CPU frame-time statistics with budget alerts.
+
+use std::{collections::VecDeque, fmt, sync::Arc, time::Duration};
+
+use crate::{Clock, SystemClock};
+
+/// A stage of the frame measured by [`FrameStats`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FramePhase {
+    /// Platform and input event handling.
+    Events,
+    /// Application and simulation updates.
+    Update,
+    /// UI measurement and layout.
+    Layout,
+    /// Recording and submitting GPU work.
+    Render,
+    /// Acquiring and presenting the surface.
+    Present,
+}
+
+impl FramePhase {
+    /// Every phase in frame order.
+    pub const ALL: [Self; 5] = [
+        Self::Events,
+        Self::Update,
+        Self::Layout,
+        Self::Render,
+        Self::Present,
+    ];
+
+    /// Lowercase display name.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Events => "events",
+            Self::Update => "update",
+            Self::Layout => "layout",
+            Self::Render => "render",
+            Self::Present => "present",
+        }
+    }
+}
+
+/// Nearest-rank percentiles over the retained frames.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Percentiles {
+    /// Median.
+    pub p50: Duration,
+    /// 95th percentile.
+    pub p95: Duration,
+    /// 99th percentile.
+    pub p99: Duration,
+    /// Slowest retained frame.
+    pub max: Duration,
+}
+
+/// A frame whose phase, or whole CPU time, exceeded a budget.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BudgetAlert {
+    /// Phase over budget, or `None` for the whole frame.
+    pub phase: Option<FramePhase>,
+    /// Time the frame spent.
+    pub duration: Duration,
+    /// Budget it exceeded.
+    pub budget: Duration,
+}
+
+struct Budget {
+    phase: Option<FramePhase>,
+    limit: Duration,
+    callback: Box<dyn FnMut(&BudgetAlert)>,
+}
+
+/// Rolling per-phase CPU timings over the most recent frames.
+///
+/// Record each phase with [`FrameStats::measure`] or [`FrameStats::record`]
+/// during a frame, then close it with [`FrameStats::end_frame`]. A phase
+/// recorded several times in one frame accumulates. The `Display` output is
+/// a compact table suited to a debug overlay label.
+///
+/// The runtime keeps one for the app, reachable through
+/// [`AppContext::frame_stats`](crate::AppContext::frame_stats).
+pub struct FrameStats {
+    clock: Arc<dyn Clock>,
+    window: usize,
+    current: [Duration; 5],
+    phases: [VecDeque<Duration>; 5],
+    frames: VecDeque<Duration>,
+    budgets: Vec<Budget>,
+}
+
+impl FrameStats {
+    /// Keeps the latest `window` frames, at least one, on the system clock.
+    pub fn new(window: usize) -> Self {
+        Self::with_clock(window, SystemClock)
+    }
+
+    /// Keeps the latest `window` frames, measuring with an injected clock.
+    pub fn with_clock(window: usize, clock: impl Clock) -> Self {
+        Self::with_shared_clock(window, Arc::new(clock))
+    }
+
+    pub(crate) fn with_shared_clock(window: usize, clock: Arc<dyn Clock>) -> Self {
+        let window = window.max(1);
+        Self {
+            clock,
+            window,
+            current: [Duration::ZERO; 5],
+            phases: std::array::from_fn(|_| VecDeque::with_capacity(window)),
+            frames: VecDeque::with_capacity(window),
+            budgets: Vec::new(),
+        }
+    }
+
+    /// Number of frames retained.
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Number of frames currently retained.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Returns whether no frame has ended yet.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Adds time spent in a phase during the current frame.
+    pub fn record(&mut self, phase: FramePhase, duration: Duration) {
+        self.current[phase as usize] += duration;
+    }
+
+    /// Time recorded against a phase so far in the current frame.
+    pub(crate) fn recorded(&self, phase: FramePhase) -> Duration {
+        self.current[phase as usize]
+    }
+
+    /// Runs `work` and records its duration against a phase.
+    pub fn measure<R>(&mut self, phase: FramePhase, work: impl FnOnce() -> R) -> R {
+        let start = self.clock.now();
+        let result = work();
+        self.record(phase, self.clock.now().saturating_duration_since(start));
+        result
+    }
+
+    /// Calls `callback` after every frame in which `phase`, or the whole
+    /// frame for `None`, took longer than `limit`.
+    pub fn on_budget_exceeded(
+        &mut self,
+        phase: Option<FramePhase>,
+        limit: Duration,
+        callback: impl FnMut(&BudgetAlert) + 'static,
+    ) {
+        self.budgets.push(Budget {
+            phase,
+            limit,
+            callback: Box::new(callback),
+        });
+    }
+
+    /// Removes every budget callback.
+    pub fn clear_budgets(&mut self) {
+        self.budgets.clear();
+    }
+
+    /// Closes the current frame, fires budget callbacks, and returns its
+    /// total CPU time across phases.
+    pub fn end_frame(&mut self) -> Duration {
+        let current = std::mem::take(&mut self.current);
+        let total = current.iter().sum();
+        for (history, duration) in self.phases.iter_mut().zip(current) {
+            push_bounded(history, duration, self.window);
+        }
+        push_bounded(&mut self.frames, total, self.window);
+        for budget in &mut self.budgets {
+            let duration = budget.phase.map_or(total, |phase| current[phase as usize]);
+            if duration > budget.limit {
+                (budget.callback)(&BudgetAlert {
+                    phase: budget.phase,
+                    duration,
+                    budget: budget.limit,
+                });
+            }
+        }
+        total
+    }
+
+    /// Percentiles of one phase, or `None` before the first frame ends.
+    pub fn phase(&self, phase: FramePhase) -> Option<Percentiles> {
+        percentiles(&self.phases[phase as usize])
+    }
+
+    /// Percentiles of whole-frame CPU time.
+    pub fn frame(&self) -> Option<Percentiles> {
+        percentiles(&self.frames)
+    }
+
+    /// Forgets every retained frame and the frame in progress.
+    pub fn reset(&mut self) {
+        self.current = [Duration::ZERO; 5];
+        self.phases.iter_mut().for_each(VecDeque::clear);
+        self.frames.clear();
+    }
+}
+
+impl fmt::Debug for FrameStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FrameStats")
+            .field("window", &self.window)
+            .field("frames", &self.frames.len())
+            .field("budgets", &self.budgets.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for FrameStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let milliseconds = |duration: Duration| duration.as_secs_f64() * 1_000.0;
+        write!(f, "{:<8} {:>7} {:>7} {:>7}", "ms", "p50", "p95", "p99")?;
+        let rows = FramePhase::ALL
+            .into_iter()
+            .map(|phase| (phase.name(), self.phase(phase)))
+            .chain([("frame", self.frame())]);
+        for (name, stats) in rows {
+            let stats = stats.unwrap_or_default();
+            write!(
+                f,
+                "\n{name:<8} {:>7.2} {:>7.2} {:>7.2}",
+                milliseconds(stats.p50),
+                milliseconds(stats.p95),
+                milliseconds(stats.p99)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn push_bounded(history: &mut VecDeque<Duration>, duration: Duration, window: usize) {
+    if history.len() == window {
+        history.pop_front();
+    }
+    history.push_back(duration);
+}
+
+fn percentiles(history: &VecDeque<Duration>) -> Option<Percentiles> {
+    if history.is_empty() {
+        return None;
+    }
+    let mut sorted = history.iter().copied().collect::<Vec<_>>();
+    sorted.sort_unstable();
+    let rank = |percentile: usize| sorted[(sorted.len() * percentile).div_ceil(100).max(1) - 1];
+    Some(Percentiles {
+        p50: rank(50),
+        p95: rank(95),
+        p99: rank(99),
+        max: sorted[sorted.len() - 1],
+    })
+}
@@ -0,0 +1,77 @@
+//! Profiler frame boundaries emitted by the runtime.
+//!
+//! Lives in its own test binary because the profiler is process-global.
+
+use std::{error::Error, fmt};
+
+use astrelis_app::{App, AppContext, Runtime, RuntimeConfig};
+use astrelis_platform::{Window, WindowAttributes, WindowEvent, WindowId};
+use astrelis_platform_test::{ScriptEvent, TestRunner};
+use astrelis_profiling::Profiler;
+
+#[derive(Debug)]
+struct TestError;
+
+impl fmt::Display for TestError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("test error")
+    }
+}
+
+impl Error for TestError {}
+
+#[derive(Default)]
+struct TwoWindows {
+    windows: Vec<Window>,
+    redraws: usize,
+}
+
+impl App for TwoWindows {
+    type Error = TestError;
+
+    fn resumed(&mut self, context: &mut AppContext<'_, '_, Self>) -> Result<(), Self::Error> {
+        for _ in 0..2 {
+            let window = context.create_window(WindowAttributes::default()).unwrap();
+            context.invalidate_window(window.id());
+            self.windows.push(window);
+        }
+        Ok(())
+    }
+
+    fn redraw(
+        &mut self,
+        _context: &mut AppContext<'_, '_, Self>,
+        _window: WindowId,
+    ) -> Result<(), Self::Error> {
+        self.redraws += 1;
+        Ok(())
+    }
+}
+
+#[test]
+fn one_frame_mark_per_loop_iteration_that_redraws() {
+    let mut runner = TestRunner::new();
+    runner.push(ScriptEvent::Resumed);
+    runner.push(ScriptEvent::AboutToWait);
+    runner.push(ScriptEvent::Window(
+        WindowId(1),
+        WindowEvent::RedrawRequested,
+    ));
+    runner.push(ScriptEvent::Window(
+        WindowId(2),
+        WindowEvent::RedrawRequested,
+    ));
+    runner.push(ScriptEvent::AboutToWait);
+    runner.push(ScriptEvent::AboutToWait);
+    let runtime = Runtime::new(TwoWindows::default(), RuntimeConfig::default());
+    let (runtime, _) = runner.run_return(runtime).unwrap();
+    let app = runtime.into_result().unwrap();
+
+    assert_eq!(app.redraws, 2);
+    let last = Profiler::get().timeline.read().unwrap().last_frame();
+    assert_eq!(
+        last.map(|mark| mark.index),
+        Some(0),
+        "two windows redrawn in one iteration close a single frame, and an idle iteration closes none"
+    );
+}
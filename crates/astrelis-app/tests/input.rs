@@ -0,0 +1,311 @@
+//! Input state tracking and action mapping over platform input events.
+
+use std::path::{Path, PathBuf};
+
+use astrelis_app::{
+    ActionMap, Binding, FileDrop, FocusChange, GamepadAxis, GamepadButton, GamepadEvent, GamepadId,
+    Input, InputState, WindowInputs,
+};
+use astrelis_core::geometry::Point;
+use astrelis_platform::{
+    DeviceEvent, DeviceId, ElementState, Key, KeyCode, KeyLocation, KeyboardInput, Modifiers,
+    PhysicalKey, PointerButton, ScrollDelta, TouchPhase, WindowEvent, WindowId,
+};
+
+fn key(code: KeyCode, state: ElementState) -> WindowEvent {
+    WindowEvent::KeyboardInput(KeyboardInput {
+        device_id: DeviceId(0),
+        physical_key: PhysicalKey::Code(code),
+        logical_key: Key::Unidentified,
+        text: None,
+        location: KeyLocation::Standard,
+        state,
+        repeat: false,
+        synthetic: false,
+    })
+}
+
+#[test]
+fn input_state_tracks_transitions_cursor_and_wheel_per_frame() {
+    let mut input = InputState::new();
+    let device_id = DeviceId(0);
+    input.handle_window_event(&key(KeyCode::KeyW, ElementState::Pressed));
+    input.handle_window_event(&WindowEvent::PointerButton {
+        device_id,
+        button: PointerButton::Secondary,
+        state: ElementState::Pressed,
+    });
+    input.handle_window_event(&WindowEvent::PointerMoved {
+        device_id,
+        position: Point::new(10.0, 20.0),
+    });
+    input.handle_window_event(&WindowEvent::PointerMoved {
+        device_id,
+        position: Point::new(14.0, 17.0),
+    });
+    for _ in 0..2 {
+        input.handle_window_event(&WindowEvent::PointerWheel {
+            device_id,
+            delta: ScrollDelta::Lines { x: 0.0, y: -1.5 },
+            phase: TouchPhase::Moved,
+        });
+    }
+    assert!(input.key_pressed(&KeyCode::KeyW) && input.key_just_pressed(&KeyCode::KeyW));
+    assert!(input.button_just_pressed(PointerButton::Secondary));
+    assert_eq!(input.cursor_position(), Some(Point::new(14.0, 17.0)));
+    assert_eq!(input.cursor_delta(), Point::new(4.0, -3.0));
+    assert_eq!(input.wheel_lines(), Point::new(0.0, -3.0));
+
+    input.end_frame();
+    assert!(input.key_pressed(&KeyCode::KeyW) && !input.key_just_pressed(&KeyCode::KeyW));
+    assert_eq!(input.cursor_delta(), Point::zero());
+    assert_eq!(input.wheel_lines(), Point::zero());
+
+    input.handle_window_event(&key(KeyCode::KeyW, ElementState::Released));
+    input.handle_window_event(&WindowEvent::Focused(false));
+    assert!(input.key_just_released(&KeyCode::KeyW));
+    assert!(input.button_just_released(PointerButton::Secondary));
+    assert!(!input.button_pressed(PointerButton::Secondary));
+    input.handle_window_event(&WindowEvent::PointerLeft { device_id });
+    assert_eq!(input.cursor_position(), None);
+}
+
+#[test]
+fn dropped_files_carry_the_cursor_position_for_one_frame() {
+    let mut input = InputState::new();
+    let asset = PathBuf::from("assets/ship.png");
+    input.handle_window_event(&WindowEvent::PointerMoved {
+        device_id: DeviceId(0),
+        position: Point::new(32.0, 48.0),
+    });
+    input.handle_window_event(&WindowEvent::HoveredFile(asset.clone()));
+    input.handle_window_event(&WindowEvent::HoveredFile("notes.txt".into()));
+    assert_eq!(input.hovered_files().len(), 2);
+
+    input.handle_window_event(&WindowEvent::DroppedFile(asset.clone()));
+    assert_eq!(
+        input.hovered_files().collect::<Vec<_>>(),
+        [Path::new("notes.txt")]
+    );
+    assert_eq!(
+        input.dropped_files(),
+        [FileDrop {
+            path: asset,
+            position: Some(Point::new(32.0, 48.0)),
+        }]
+    );
+    input.handle_window_event(&WindowEvent::HoveredFileCancelled);
+    input.end_frame();
+    assert_eq!(input.hovered_files().len(), 0);
+    assert!(input.dropped_files().is_empty());
+}
+
+#[test]
+fn actions_track_bindings_across_frames() {
+    let mut actions = ActionMap::new();
+    actions
+        .bind("Jump", Binding::key(KeyCode::Space))
+        .bind("MoveX", Binding::key(KeyCode::KeyA).with_scale(-1.0))
+        .bind("MoveX", Binding::key(KeyCode::KeyD))
+        .bind("MoveX", Binding::new(Input::DeviceAxis(0)));
+
+    actions.handle_window_event(&key(KeyCode::Space, ElementState::Pressed));
+    actions.handle_window_event(&key(KeyCode::KeyA, ElementState::Pressed));
+    assert!(actions.pressed("Jump") && actions.just_pressed("Jump"));
+    assert_eq!(actions.value("MoveX"), -1.0);
+    actions.end_frame();
+    assert!(actions.pressed("Jump") && !actions.just_pressed("Jump"));
+
+    actions.handle_window_event(&key(KeyCode::Space, ElementState::Released));
+    actions.handle_device_event(&DeviceEvent::Motion {
+        axis: 0,
+        value: 0.75,
+    });
+    assert!(actions.just_released("Jump"));
+    assert_eq!(actions.value("MoveX"), -0.25);
+    assert!(!actions.pressed("MoveX"));
+    assert!(!actions.pressed("Missing"));
+
+    actions.end_frame();
+    assert_eq!(
+        actions.value("MoveX"),
+        -1.0,
+        "relative axis motion lasts one frame"
+    );
+}
+
+fn pad_button(gamepad: usize, button: GamepadButton, state: ElementState) -> GamepadEvent {
+    GamepadEvent::Button {
+        gamepad: GamepadId(gamepad),
+        button,
+        state,
+    }
+}
+
+#[test]
+fn gamepad_input_is_keyed_by_gamepad() {
+    let (first, second) = (GamepadId(0), GamepadId(1));
+    let mut actions = ActionMap::new();
+    actions
+        .bind("Jump", Binding::gamepad_button(GamepadButton::South))
+        .bind(
+            "P2Jump",
+            Binding::gamepad_button(GamepadButton::South).on_gamepad(second),
+        )
+        .bind("MoveX", Binding::gamepad_axis(GamepadAxis::LeftStickX));
+
+    actions.handle_gamepad_event(&GamepadEvent::Connected(first));
+    actions.handle_gamepad_event(&GamepadEvent::Connected(second));
+    actions.handle_gamepad_event(&pad_button(0, GamepadButton::South, ElementState::Pressed));
+    actions.handle_gamepad_event(&GamepadEvent::Axis {
+        gamepad: first,
+        axis: GamepadAxis::LeftStickX,
+        value: 0.25,
+    });
+    actions.handle_gamepad_event(&GamepadEvent::Axis {
+        gamepad: second,
+        axis: GamepadAxis::LeftStickX,
+        value: -0.75,
+    });
+    assert!(actions.just_pressed("Jump"));
+    assert!(!actions.pressed("P2Jump"));
+    assert_eq!(actions.value("MoveX"), -0.75, "the strongest stick wins");
+    let input = actions.input();
+    assert!(input.gamepad_button_just_pressed(first, GamepadButton::South));
+    assert!(!input.gamepad_button_pressed(second, GamepadButton::South));
+    assert_eq!(input.gamepad_axis(first, GamepadAxis::LeftStickX), 0.25);
+
+    actions.end_frame();
+    actions.handle_window_event(&WindowEvent::Focused(false));
+    assert_eq!(
+        actions.value("MoveX"),
+        -0.75,
+        "sticks hold their position across frames and focus changes"
+    );
+    assert!(actions.pressed("Jump"));
+
+    actions.handle_gamepad_event(&GamepadEvent::Disconnected(first));
+    assert!(actions.just_released("Jump"));
+    assert_eq!(actions.input().gamepads().collect::<Vec<_>>(), [second]);
+
+    actions.rebind_next("P2Jump");
+    actions.handle_gamepad_event(&pad_button(1, GamepadButton::East, ElementState::Pressed));
+    assert_eq!(
+        actions.bindings("P2Jump"),
+        [Binding::gamepad_button(GamepadButton::East).on_gamepad(second)]
+    );
+    assert!(actions.pressed("P2Jump"));
+}
+
+#[test]
+fn modifiers_gate_bindings_and_rebinding_captures_the_next_input() {
+    let control = Modifiers {
+        control: true,
+        ..Modifiers::default()
+    };
+    let mut actions = ActionMap::new();
+    actions.bind("Save", Binding::key(KeyCode::KeyS).with_modifiers(control));
+
+    actions.handle_window_event(&key(KeyCode::KeyS, ElementState::Pressed));
+    assert!(!actions.pressed("Save"));
+    actions.handle_window_event(&WindowEvent::ModifiersChanged(control));
+    assert!(actions.pressed("Save"));
+    actions.handle_window_event(&WindowEvent::Focused(false));
+    assert!(!actions.pressed("Save"));
+
+    actions.rebind_next("Fire");
+    assert_eq!(actions.rebinding(), Some("Fire"));
+    actions.handle_window_event(&WindowEvent::PointerButton {
+        device_id: DeviceId(0),
+        button: PointerButton::Primary,
+        state: ElementState::Pressed,
+    });
+    assert_eq!(actions.rebinding(), None);
+    assert_eq!(
+        actions.bindings("Fire"),
+        [Binding::pointer(PointerButton::Primary)]
+    );
+    assert!(actions.pressed("Fire"));
+    assert_eq!(actions.actions().collect::<Vec<_>>(), ["Fire", "Save"]);
+}
+
+fn typed(code: KeyCode, character: &str, state: ElementState) -> WindowEvent {
+    WindowEvent::KeyboardInput(KeyboardInput {
+        device_id: DeviceId(0),
+        physical_key: PhysicalKey::Code(code),
+        logical_key: Key::Character(character.into()),
+        text: None,
+        location: KeyLocation::Standard,
+        state,
+        repeat: false,
+        synthetic: false,
+    })
+}
+
+#[test]
+fn character_bindings_follow_the_keyboard_layout() {
+    let mut actions = ActionMap::new();
+    actions.bind("Forward", Binding::character('Z'));
+    assert_eq!(
+        actions.bindings("Forward")[0].input,
+        Input::Character("z".into())
+    );
+
+    // AZERTY puts Z where QWERTY has W; Shift changes the character while held.
+    actions.handle_window_event(&typed(KeyCode::KeyW, "Z", ElementState::Pressed));
+    assert!(actions.just_pressed("Forward"));
+    assert!(actions.input().character_pressed("z"));
+    assert!(actions.input().key_pressed(&KeyCode::KeyW));
+    actions.end_frame();
+
+    actions.handle_window_event(&typed(KeyCode::KeyW, "w", ElementState::Released));
+    assert!(actions.just_released("Forward"));
+    assert!(actions.input().character_just_released("Z"));
+    actions.end_frame();
+
+    actions.handle_window_event(&typed(KeyCode::KeyZ, "w", ElementState::Pressed));
+    assert!(!actions.pressed("Forward"));
+    assert!(actions.input().character_just_pressed("w"));
+}
+
+#[test]
+fn window_inputs_keep_keys_per_window_and_route_devices_to_focus() {
+    let (editor, preview) = (WindowId(1), WindowId(2));
+    let mut inputs = WindowInputs::new();
+    inputs.handle_window_event(editor, &WindowEvent::Focused(true));
+    inputs.handle_window_event(editor, &key(KeyCode::KeyS, ElementState::Pressed));
+    inputs.handle_device_event(&DeviceEvent::Button {
+        button: 4,
+        state: ElementState::Pressed,
+    });
+    assert_eq!(inputs.focused(), Some(editor));
+    assert!(inputs.window(editor).unwrap().key_pressed(&KeyCode::KeyS));
+    assert!(inputs.focused_input().unwrap().device_button_pressed(4));
+    assert!(inputs.window(preview).is_none());
+
+    inputs.end_frame();
+    inputs.handle_window_event(editor, &WindowEvent::Focused(false));
+    inputs.handle_window_event(preview, &WindowEvent::Focused(true));
+    inputs.handle_window_event(preview, &key(KeyCode::KeyP, ElementState::Pressed));
+    assert_eq!(
+        inputs.focus_changes(),
+        [
+            FocusChange {
+                window: editor,
+                focused: false,
+            },
+            FocusChange {
+                window: preview,
+                focused: true,
+            },
+        ]
+    );
+    assert_eq!(inputs.focused(), Some(preview));
+    let editor_input = inputs.window(editor).unwrap();
+    assert!(!editor_input.key_pressed(&KeyCode::KeyS) && !editor_input.key_pressed(&KeyCode::KeyP));
+    assert!(inputs.focused_input().unwrap().key_pressed(&KeyCode::KeyP));
+
+    inputs.handle_window_event(preview, &WindowEvent::Destroyed);
+    assert_eq!(inputs.focused(), None);
+    assert!(inputs.window(preview).is_none());
+}
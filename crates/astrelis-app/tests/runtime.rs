@@ -1,7 +1,7 @@
 //! Deterministic scheduling tests for the shared application runtime.
 
 use std::{
-    cell::Cell,
+    cell::{Cell, RefCell},
     error::Error,
     fmt,
     rc::Rc,
@@ -9,8 +9,9 @@ use std::{
 };
 
 use astrelis_app::{
-    App, AppContext, FixedStep, FixedUpdateInfo, ManualClock, Runtime, RuntimeConfig,
-    RuntimePolicy, UpdateInfo,
+    App, AppContext, BudgetAlert, FixedStep, FixedUpdateInfo, FramePacer, FramePhase, FrameStats,
+    HeadlessConfig, ManualClock, Runtime, RuntimeConfig, RuntimePolicy, UpdateInfo, run_headless,
+    run_headless_with,
 };
 use astrelis_platform::{
     ControlFlow, Window, WindowAttributes, WindowCommand, WindowEvent, WindowId,
@@ -495,3 +496,380 @@ fn occluded_continuous_windows_are_not_repeatedly_invalidated() {
             .all(|command| *command != WindowCommand::RequestRedraw)
     );
 }
+
+#[test]
+fn frame_pacer_schedules_deadlines_by_whole_intervals() {
+    let start = Instant::now();
+    let clock = ManualClock::new(start);
+    let mut pacer = FramePacer::with_clock(Some(Duration::from_millis(10)), clock.clone());
+    assert_eq!(pacer.record(Duration::ZERO).delta, Duration::ZERO);
+
+    clock.advance(Duration::from_millis(4));
+    assert_eq!(pacer.remaining(), Duration::from_millis(6));
+    clock.advance(Duration::from_millis(7));
+    let frame = pacer.record(Duration::from_millis(6));
+    assert_eq!(frame.delta, Duration::from_millis(11));
+    assert_eq!(frame.work, Duration::from_millis(5));
+    assert_eq!(
+        pacer.remaining(),
+        Duration::from_millis(9),
+        "a one millisecond overshoot shortens the next frame"
+    );
+
+    clock.advance(Duration::from_millis(50));
+    pacer.record(Duration::ZERO);
+    assert_eq!(pacer.remaining(), Duration::from_millis(10));
+    pacer.set_target_fps(0.0);
+    assert_eq!(pacer.remaining(), Duration::ZERO);
+}
+
+#[test]
+fn frame_stats_report_rolling_percentiles_and_budget_overruns() {
+    let clock = ManualClock::new(Instant::now());
+    let mut stats = FrameStats::with_clock(100, clock.clone());
+    let alerts = Rc::new(RefCell::new(Vec::new()));
+    let sink = alerts.clone();
+    stats.on_budget_exceeded(
+        Some(FramePhase::Render),
+        Duration::from_millis(10),
+        move |alert| sink.borrow_mut().push(*alert),
+    );
+    for frame in 1..=200u64 {
+        stats.measure(FramePhase::Render, || {
+            clock.advance(Duration::from_millis(frame % 100 + 1))
+        });
+        stats.record(FramePhase::Update, Duration::from_millis(1));
+        stats.end_frame();
+    }
+
+    assert_eq!(stats.len(), 100);
+    let render = stats.phase(FramePhase::Render).unwrap();
+    assert_eq!(render.p50, Duration::from_millis(50));
+    assert_eq!(render.p95, Duration::from_millis(95));
+    assert_eq!(render.p99, Duration::from_millis(99));
+    assert_eq!(render.max, Duration::from_millis(100));
+    assert_eq!(stats.frame().unwrap().p50, Duration::from_millis(51));
+    assert!(stats.phase(FramePhase::Layout).unwrap().max.is_zero());
+
+    let alerts = alerts.borrow();
+    assert_eq!(alerts.len(), 180);
+    assert_eq!(
+        alerts[0],
+        BudgetAlert {
+            phase: Some(FramePhase::Render),
+            duration: Duration::from_millis(11),
+            budget: Duration::from_millis(10),
+        }
+    );
+    assert!(stats.to_string().starts_with("ms"));
+}
+
+#[test]
+fn frame_stats_are_empty_before_the_first_frame_ends() {
+    let mut stats = FrameStats::with_clock(10, ManualClock::new(Instant::now()));
+    stats.record(FramePhase::Render, Duration::from_millis(1));
+    assert!(stats.is_empty());
+    assert_eq!(stats.phase(FramePhase::Render), None);
+    assert_eq!(stats.frame(), None);
+    assert!(stats.to_string().starts_with("ms"));
+}
+
+struct StatsApp {
+    clock: ManualClock,
+    window: Option<Window>,
+    observed: Option<(usize, Duration, Duration, Duration, Duration)>,
+}
+
+impl App for StatsApp {
+    type Error = TestError;
+
+    fn resumed(&mut self, context: &mut AppContext<'_, '_, Self>) -> Result<(), Self::Error> {
+        let window = context.create_window(WindowAttributes::default()).unwrap();
+        context.invalidate_window(window.id());
+        self.window = Some(window);
+        Ok(())
+    }
+
+    fn window_event(
+        &mut self,
+        _context: &mut AppContext<'_, '_, Self>,
+        _window: WindowId,
+        _event: WindowEvent,
+    ) -> Result<(), Self::Error> {
+        self.clock.advance(Duration::from_millis(1));
+        Ok(())
+    }
+
+    fn update(
+        &mut self,
+        context: &mut AppContext<'_, '_, Self>,
+        _info: UpdateInfo,
+    ) -> Result<(), Self::Error> {
+        let stats = context.frame_stats();
+        if let Some(frame) = stats.frame() {
+            let p50 = |phase| stats.phase(phase).unwrap().p50;
+            self.observed = Some((
+                stats.len(),
+                p50(FramePhase::Events),
+                p50(FramePhase::Update),
+                p50(FramePhase::Layout),
+                frame.p50,
+            ));
+        }
+        self.clock.advance(Duration::from_millis(4));
+        Ok(())
+    }
+
+    fn redraw(
+        &mut self,
+        context: &mut AppContext<'_, '_, Self>,
+        _window: WindowId,
+    ) -> Result<(), Self::Error> {
+        self.clock.advance(Duration::from_millis(3));
+        let clock = self.clock.clone();
+        context.frame_stats_mut().measure(FramePhase::Layout, || {
+            clock.advance(Duration::from_millis(2))
+        });
+        Ok(())
+    }
+}
+
+#[test]
+fn the_runtime_records_frame_phases_around_its_callbacks() {
+    let clock = ManualClock::new(Instant::now());
+    let app = StatsApp {
+        clock: clock.clone(),
+        window: None,
+        observed: None,
+    };
+    let mut runner = TestRunner::new();
+    runner.push(ScriptEvent::Resumed);
+    runner.push(ScriptEvent::AboutToWait);
+    runner.push(ScriptEvent::Window(WindowId(1), WindowEvent::Focused(true)));
+    runner.push(ScriptEvent::Window(
+        WindowId(1),
+        WindowEvent::RedrawRequested,
+    ));
+    runner.push(ScriptEvent::AboutToWait);
+    let runtime = Runtime::with_clock(app, RuntimeConfig::default(), clock);
+    let (runtime, _) = runner.run_return(runtime).unwrap();
+    let app = runtime.into_result().unwrap();
+
+    let ms = Duration::from_millis;
+    assert_eq!(
+        app.observed,
+        Some((1, ms(1), ms(4), ms(2), ms(10))),
+        "render time excludes the layout recorded inside redraw"
+    );
+}
+
+#[derive(Default)]
+struct HeadlessApp {
+    window: Option<Window>,
+    updates: Vec<Duration>,
+    fixed_updates: usize,
+    redraws: usize,
+    size: Option<(u32, u32)>,
+}
+
+impl App for HeadlessApp {
+    type Error = TestError;
+
+    fn resumed(&mut self, context: &mut AppContext<'_, '_, Self>) -> Result<(), Self::Error> {
+        let window = context.create_window(WindowAttributes::default()).unwrap();
+        let size = window.inner_size().unwrap();
+        self.size = Some((size.width, size.height));
+        self.window = Some(window);
+        Ok(())
+    }
+
+    fn update(
+        &mut self,
+        context: &mut AppContext<'_, '_, Self>,
+        info: UpdateInfo,
+    ) -> Result<(), Self::Error> {
+        self.updates.push(info.delta);
+        if self.updates.len() == 30 {
+            context.exit();
+        }
+        Ok(())
+    }
+
+    fn fixed_update(
+        &mut self,
+        _context: &mut AppContext<'_, '_, Self>,
+        _info: FixedUpdateInfo,
+    ) -> Result<(), Self::Error> {
+        self.fixed_updates += 1;
+        Ok(())
+    }
+
+    fn redraw(
+        &mut self,
+        _context: &mut AppContext<'_, '_, Self>,
+        _window: WindowId,
+    ) -> Result<(), Self::Error> {
+        self.redraws += 1;
+        Ok(())
+    }
+}
+
+#[test]
+fn headless_runs_update_fixed_update_and_redraw_on_a_simulated_clock() {
+    let app = run_headless(HeadlessApp::default(), 10).unwrap();
+    assert_eq!(app.size, Some((1280, 720)));
+    assert_eq!(app.updates, [Duration::from_nanos(16_666_667); 10]);
+    assert_eq!(app.redraws, 10);
+    assert_eq!(app.fixed_updates, 0);
+
+    let config = HeadlessConfig {
+        runtime: RuntimeConfig {
+            policy: RuntimePolicy::Continuous {
+                frame_interval: None,
+                fixed_step: Some(FixedStep::new(Duration::from_millis(5))),
+            },
+            ..RuntimeConfig::default()
+        },
+        frame_time: Duration::from_millis(10),
+        ..HeadlessConfig::default()
+    };
+    let app = run_headless_with(HeadlessApp::default(), 100, config).unwrap();
+    assert_eq!(app.updates.len(), 30, "exiting stops the run early");
+    assert_eq!(app.fixed_updates, 60);
+}
+
+#[derive(Default)]
+struct TimeScaleApp {
+    scaled: Vec<Duration>,
+    fixed_updates: usize,
+}
+
+impl App for TimeScaleApp {
+    type Error = TestError;
+
+    fn update(
+        &mut self,
+        context: &mut AppContext<'_, '_, Self>,
+        info: UpdateInfo,
+    ) -> Result<(), Self::Error> {
+        self.scaled.push(info.scaled_delta);
+        match self.scaled.len() {
+            1 => {
+                context.set_time_scale(0.5).unwrap();
+                assert!(context.set_time_scale(f64::NAN).is_err());
+                assert!(context.set_time_scale(-1.0).is_err());
+                assert!(context.set_time_scale(1e300).is_err());
+                assert_eq!(context.time_scale(), 0.5);
+            }
+            5 => context.set_paused(true),
+            7 => {
+                context.set_paused(false);
+                context.set_time_scale(2.0).unwrap();
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn fixed_update(
+        &mut self,
+        _context: &mut AppContext<'_, '_, Self>,
+        _info: FixedUpdateInfo,
+    ) -> Result<(), Self::Error> {
+        self.fixed_updates += 1;
+        Ok(())
+    }
+}
+
+#[test]
+fn time_scale_and_pause_drive_scaled_delta_and_fixed_steps() {
+    let config = HeadlessConfig {
+        runtime: RuntimeConfig {
+            policy: RuntimePolicy::Continuous {
+                frame_interval: None,
+                fixed_step: Some(FixedStep::new(Duration::from_millis(10))),
+            },
+            ..RuntimeConfig::default()
+        },
+        frame_time: Duration::from_millis(10),
+        ..HeadlessConfig::default()
+    };
+    let app = run_headless_with(TimeScaleApp::default(), 10, config).unwrap();
+    let ms = Duration::from_millis;
+    assert_eq!(
+        app.scaled,
+        [
+            ms(10),
+            ms(5),
+            ms(5),
+            ms(5),
+            ms(5),
+            ms(0),
+            ms(0),
+            ms(20),
+            ms(20),
+            ms(20)
+        ]
+    );
+    assert_eq!(app.fixed_updates, 1 + 2 + 6);
+}
+
+#[derive(Default)]
+struct LifecycleApp {
+    events: Vec<&'static str>,
+}
+
+impl App for LifecycleApp {
+    type Error = TestError;
+
+    fn resumed(&mut self, _context: &mut AppContext<'_, '_, Self>) -> Result<(), Self::Error> {
+        self.events.push("resumed");
+        Ok(())
+    }
+
+    fn suspended(&mut self, _context: &mut AppContext<'_, '_, Self>) -> Result<(), Self::Error> {
+        self.events.push("suspended");
+        Ok(())
+    }
+
+    fn memory_warning(
+        &mut self,
+        _context: &mut AppContext<'_, '_, Self>,
+    ) -> Result<(), Self::Error> {
+        self.events.push("memory_warning");
+        Ok(())
+    }
+
+    fn update(
+        &mut self,
+        _context: &mut AppContext<'_, '_, Self>,
+        _info: UpdateInfo,
+    ) -> Result<(), Self::Error> {
+        self.events.push("update");
+        Ok(())
+    }
+}
+
+#[test]
+fn lifecycle_callbacks_reach_the_app_and_suspension_stops_updates() {
+    let mut runner = TestRunner::new();
+    runner.push(ScriptEvent::Resumed);
+    runner.push(ScriptEvent::Suspended);
+    runner.push(ScriptEvent::MemoryWarning);
+    runner.push(ScriptEvent::AboutToWait);
+    runner.push(ScriptEvent::Resumed);
+    runner.push(ScriptEvent::AboutToWait);
+    let runtime = Runtime::new(LifecycleApp::default(), RuntimeConfig::default());
+    let (runtime, _) = runner.run_return(runtime).unwrap();
+    let app = runtime.into_result().unwrap();
+    assert_eq!(
+        app.events,
+        [
+            "resumed",
+            "suspended",
+            "memory_warning",
+            "resumed",
+            "update"
+        ]
+    );
+}
@@ -109,7 +109,9 @@ fn bench(c: &mut Criterion) {
                         },
                     )
                     .unwrap();
+                compositor.before_submit();
                 queue.submit([encoder.finish().unwrap()]).unwrap();
+                compositor.after_submit().unwrap();
                 device.poll(PollMode::Wait).unwrap();
             })
         });
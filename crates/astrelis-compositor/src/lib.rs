@@ -97,6 +97,21 @@ impl Compositor {
         &mut self.paint
     }
 
+    /// Closes the painter's staging chunks for submission. Call after the
+    /// last [`Compositor::render`] into an encoder and before submitting it.
+    pub fn before_submit(&mut self) {
+        self.paint.before_submit();
+    }
+
+    /// Releases the painter's per-submission buffers and recalls staging
+    /// chunks. Call once after every submission that [`Compositor::render`]
+    /// recorded into.
+    pub fn after_submit(&mut self) -> Result<(), CompositionError> {
+        self.paint
+            .after_submit()
+            .map_err(|error| CompositionError(error.to_string()))
+    }
+
     /// Composes one display list and invokes each unique scene callback in paint order.
     pub fn render<E>(
         &mut self,
@@ -127,6 +142,7 @@ impl Compositor {
         let mut stats = CompositionStats::default();
         for (index, layer) in plan.layers.iter().enumerate() {
             let last = index + 1 == plan.layers.len();
+            encoder.push_debug_group(&format!("ui layer {index}"));
             let paint = if plan.markers.is_empty() {
                 self.paint.render(encoder, layer, target.clone())
             } else if index == 0 {
@@ -137,8 +153,9 @@ impl Compositor {
                     .render_final_layer(encoder, layer, target.clone())
             } else {
                 self.paint.render_layer(encoder, layer, target.clone())
-            }
-            .map_err(|error| CompositionError(error.to_string()))?;
+            };
+            encoder.pop_debug_group();
+            let paint = paint.map_err(|error| CompositionError(error.to_string()))?;
             add_paint_stats(&mut stats.paint, paint);
             stats.ui_layers += 1;
             let Some(marker) = plan.markers.get(index) else {
@@ -168,14 +185,17 @@ impl Compositor {
                     scale_factor: target.scale_factor,
                     clear_color: options.clear_color,
                 };
-                render_view(marker.id, encoder, ViewRenderTarget::Direct(direct))
-                    .map_err(|error| CompositionError(error.to_string()))?;
+                encoder.push_debug_group(&format!("scene view {}", marker.id.get()));
+                let rendered = render_view(marker.id, encoder, ViewRenderTarget::Direct(direct));
+                encoder.pop_debug_group();
+                rendered.map_err(|error| CompositionError(error.to_string()))?;
                 stats.direct_views += 1;
             } else {
                 let desired = transformed_size(marker, target.scale_factor);
                 self.ensure_fallback(marker.id, desired, target.format)?;
                 let fallback = self.fallbacks.get(&marker.id).expect("fallback exists");
-                render_view(
+                encoder.push_debug_group(&format!("scene view {}", marker.id.get()));
+                let rendered = render_view(
                     marker.id,
                     encoder,
                     ViewRenderTarget::Texture(RenderTarget {
@@ -185,8 +205,9 @@ impl Compositor {
                         scale_factor: target.scale_factor,
                         clear_color: options.clear_color,
                     }),
-                )
-                .map_err(|error| CompositionError(error.to_string()))?;
+                );
+                encoder.pop_debug_group();
+                rendered.map_err(|error| CompositionError(error.to_string()))?;
                 let fallback_layer = list
                     .compositor_fallback_layer(index, fallback.image.clone(), desired)
                     .map_err(|error| CompositionError(error.to_string()))?;
@@ -366,6 +387,7 @@ fn add_paint_stats(total: &mut PaintStats, value: PaintStats) {
     total.glyph_cache_hits += value.glyph_cache_hits;
     total.glyph_cache_misses += value.glyph_cache_misses;
     total.glyph_uploads += value.glyph_uploads;
+    total.glyph_pending += value.glyph_pending;
 }
 
 #[cfg(test)]
@@ -0,0 +1,21 @@
+//! Allocation strategies and handle-based storage.
+//!
+//! - [`FrameArena`] hands out bump-allocated scratch collections that are
+//!   freed together when the arena resets, so code that rebuilds buffers
+//!   every frame stops touching the global allocator once the arena has
+//!   grown to its steady-state size.
+//! - [`HandleMap`] stores values behind generational [`Handle`]s that stay
+//!   safe to hold after their value is removed.
+//! - [`Pool`] recycles values whose allocations are worth keeping.
+//! - [`SparseSet`] packs values keyed by small integer indices for fast
+//!   lookup and cache-friendly iteration.
+
+mod frame;
+mod handle_map;
+mod pool;
+mod sparse_set;
+
+pub use frame::{FrameArena, FrameString, FrameVec};
+pub use handle_map::{Handle, HandleMap};
+pub use pool::Pool;
+pub use sparse_set::SparseSet;
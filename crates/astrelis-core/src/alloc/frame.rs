@@ -0,0 +1,119 @@
+use bumpalo::Bump;
+
+/// A growable vector whose storage lives in a [`FrameArena`].
+pub type FrameVec<'a, T> = bumpalo::collections::Vec<'a, T>;
+
+/// A growable UTF-8 string whose storage lives in a [`FrameArena`].
+pub type FrameString<'a> = bumpalo::collections::String<'a>;
+
+/// A bump arena for scratch data that lives for one frame.
+///
+/// Allocation is a pointer increment. [`FrameArena::reset`] frees
+/// everything at once and keeps the largest backing chunk, so a renderer
+/// that resets its arena each frame allocates from the heap only while its
+/// working set is still growing.
+///
+/// Collections borrow the arena, which the borrow checker uses to stop a
+/// reset while any of them are alive:
+///
+/// ```
+/// use astrelis_core::alloc::FrameArena;
+///
+/// let mut arena = FrameArena::new();
+/// for frame in 0..3 {
+///     let mut indices = arena.vec();
+///     indices.extend([frame, frame + 1, frame + 2]);
+///     assert_eq!(indices.len(), 3);
+///     drop(indices);
+///     arena.reset();
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct FrameArena {
+    bump: Bump,
+}
+
+impl FrameArena {
+    /// Creates an empty arena; the first allocation reserves a chunk.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an arena with room for `bytes` before it needs to grow.
+    pub fn with_capacity(bytes: usize) -> Self {
+        Self {
+            bump: Bump::with_capacity(bytes),
+        }
+    }
+
+    /// Moves `value` into the arena.
+    ///
+    /// The value's destructor never runs, so this suits plain data rather
+    /// than types that own heap memory or other resources.
+    pub fn alloc<T>(&self, value: T) -> &mut T {
+        self.bump.alloc(value)
+    }
+
+    /// Copies a slice into the arena.
+    pub fn alloc_slice<T: Copy>(&self, values: &[T]) -> &mut [T] {
+        self.bump.alloc_slice_copy(values)
+    }
+
+    /// Copies a string into the arena.
+    pub fn alloc_str(&self, value: &str) -> &mut str {
+        self.bump.alloc_str(value)
+    }
+
+    /// Creates an empty vector in the arena.
+    pub fn vec<T>(&self) -> FrameVec<'_, T> {
+        FrameVec::new_in(&self.bump)
+    }
+
+    /// Creates a vector in the arena with room for `capacity` elements.
+    pub fn vec_with_capacity<T>(&self, capacity: usize) -> FrameVec<'_, T> {
+        FrameVec::with_capacity_in(capacity, &self.bump)
+    }
+
+    /// Creates an empty string in the arena.
+    pub fn string(&self) -> FrameString<'_> {
+        FrameString::new_in(&self.bump)
+    }
+
+    /// Bytes reserved from the heap, including unused chunk space.
+    pub fn allocated_bytes(&self) -> usize {
+        self.bump.allocated_bytes()
+    }
+
+    /// Frees every allocation, keeping the largest chunk for reuse.
+    pub fn reset(&mut self) {
+        self.bump.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Write;
+
+    use super::*;
+
+    #[test]
+    fn reset_reuses_the_grown_chunk() {
+        let mut arena = FrameArena::new();
+        let mut warm = 0;
+        for frame in 0..4 {
+            let mut values = arena.vec_with_capacity(1024);
+            values.extend(0..1024u32);
+            let mut label = arena.string();
+            write!(label, "frame {frame}").unwrap();
+            assert_eq!(label.as_str(), format!("frame {frame}"));
+            assert_eq!(arena.alloc_slice(&values[..2]), [0, 1]);
+            drop((values, label));
+            if frame == 1 {
+                warm = arena.allocated_bytes();
+            } else if frame > 1 {
+                assert_eq!(arena.allocated_bytes(), warm);
+            }
+            arena.reset();
+        }
+    }
+}
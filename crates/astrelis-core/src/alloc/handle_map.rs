@@ -0,0 +1,309 @@
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    ops::{Index, IndexMut},
+};
+
+/// A generational reference to a value in a [`HandleMap<T>`].
+///
+/// A handle stops resolving once its value is removed, even after the slot
+/// is reused by a later insertion.
+pub struct Handle<T> {
+    index: u32,
+    generation: u32,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Handle<T> {
+    /// Rebuilds a handle from the parts returned by [`Handle::index`] and
+    /// [`Handle::generation`], for handles stored in foreign key types.
+    #[inline]
+    pub const fn from_raw_parts(index: u32, generation: u32) -> Self {
+        Self {
+            index,
+            generation,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Slot index, dense enough to index side tables.
+    #[inline]
+    pub const fn index(self) -> u32 {
+        self.index
+    }
+
+    /// Number of removals from the slot before this handle was issued.
+    #[inline]
+    pub const fn generation(self) -> u32 {
+        self.generation
+    }
+}
+
+// Manual impls because derive would add bounds on T.
+
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Handle<T> {}
+
+impl<T> PartialEq for Handle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index && self.generation == other.generation
+    }
+}
+
+impl<T> Eq for Handle<T> {}
+
+impl<T> Hash for Handle<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+        self.generation.hash(state);
+    }
+}
+
+impl<T> fmt::Debug for Handle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Handle({}v{})", self.index, self.generation)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Slot<T> {
+    generation: u32,
+    value: Option<T>,
+}
+
+/// Dense storage addressed by generational [`Handle`]s.
+///
+/// Insertion reuses the most recently freed slot, so indices stay compact
+/// and lookups are a bounds check plus a generation comparison. Removing a
+/// value advances its slot's generation, which invalidates every handle
+/// issued for it.
+///
+/// ```
+/// use astrelis_core::alloc::HandleMap;
+///
+/// let mut textures = HandleMap::new();
+/// let grass = textures.insert("grass.png");
+/// assert_eq!(textures[grass], "grass.png");
+///
+/// textures.remove(grass);
+/// let stone = textures.insert("stone.png");
+/// assert_eq!(grass.index(), stone.index());
+/// assert_eq!(textures.get(grass), None);
+/// ```
+#[derive(Clone, Debug)]
+pub struct HandleMap<T> {
+    slots: Vec<Slot<T>>,
+    free: Vec<u32>,
+    len: usize,
+}
+
+impl<T> Default for HandleMap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> HandleMap<T> {
+    /// Creates an empty map.
+    pub const fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            free: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Creates an empty map with room for `capacity` values.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            slots: Vec::with_capacity(capacity),
+            free: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Number of live values.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether there are no live values.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of slots, live or free; every handle index is below it.
+    pub fn slot_len(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Stores a value and returns its handle.
+    pub fn insert(&mut self, value: T) -> Handle<T> {
+        self.insert_with(|_| value)
+    }
+
+    /// Stores the value built from its own handle, for values that record
+    /// their identity.
+    pub fn insert_with(&mut self, build: impl FnOnce(Handle<T>) -> T) -> Handle<T> {
+        let index = match self.free.pop() {
+            Some(index) => index,
+            None => {
+                self.slots.push(Slot {
+                    generation: 0,
+                    value: None,
+                });
+                (self.slots.len() - 1) as u32
+            }
+        };
+        let slot = &mut self.slots[index as usize];
+        let handle = Handle::from_raw_parts(index, slot.generation);
+        slot.value = Some(build(handle));
+        self.len += 1;
+        handle
+    }
+
+    /// Returns whether the handle resolves.
+    pub fn contains(&self, handle: Handle<T>) -> bool {
+        self.get(handle).is_some()
+    }
+
+    /// The value for a live handle.
+    pub fn get(&self, handle: Handle<T>) -> Option<&T> {
+        self.slots
+            .get(handle.index as usize)
+            .filter(|slot| slot.generation == handle.generation)?
+            .value
+            .as_ref()
+    }
+
+    /// The mutable value for a live handle.
+    pub fn get_mut(&mut self, handle: Handle<T>) -> Option<&mut T> {
+        self.slots
+            .get_mut(handle.index as usize)
+            .filter(|slot| slot.generation == handle.generation)?
+            .value
+            .as_mut()
+    }
+
+    /// The live handle occupying a slot index, if any.
+    pub fn handle_at(&self, index: usize) -> Option<Handle<T>> {
+        let slot = self.slots.get(index)?;
+        slot.value
+            .as_ref()
+            .map(|_| Handle::from_raw_parts(index as u32, slot.generation))
+    }
+
+    /// Removes and returns the value for a live handle.
+    pub fn remove(&mut self, handle: Handle<T>) -> Option<T> {
+        let slot = self
+            .slots
+            .get_mut(handle.index as usize)
+            .filter(|slot| slot.generation == handle.generation)?;
+        let value = slot.value.take()?;
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(handle.index);
+        self.len -= 1;
+        Some(value)
+    }
+
+    /// Removes every value for which `keep` returns `false`.
+    pub fn retain(&mut self, mut keep: impl FnMut(Handle<T>, &mut T) -> bool) {
+        for index in 0..self.slots.len() {
+            let slot = &mut self.slots[index];
+            let handle = Handle::from_raw_parts(index as u32, slot.generation);
+            if slot
+                .value
+                .as_mut()
+                .is_some_and(|value| !keep(handle, value))
+            {
+                self.remove(handle);
+            }
+        }
+    }
+
+    /// Removes every value, invalidating all handles.
+    pub fn clear(&mut self) {
+        self.retain(|_, _| false);
+    }
+
+    /// Iterates live handles and values in slot order.
+    pub fn iter(&self) -> impl Iterator<Item = (Handle<T>, &T)> + '_ {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            let value = slot.value.as_ref()?;
+            Some((Handle::from_raw_parts(index as u32, slot.generation), value))
+        })
+    }
+
+    /// Iterates live handles and mutable values in slot order.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Handle<T>, &mut T)> + '_ {
+        self.slots
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(index, slot)| {
+                let value = slot.value.as_mut()?;
+                Some((Handle::from_raw_parts(index as u32, slot.generation), value))
+            })
+    }
+
+    /// Iterates live values in slot order.
+    pub fn values(&self) -> impl Iterator<Item = &T> + '_ {
+        self.slots.iter().filter_map(|slot| slot.value.as_ref())
+    }
+}
+
+impl<T> Index<Handle<T>> for HandleMap<T> {
+    type Output = T;
+
+    /// # Panics
+    ///
+    /// Panics if the handle is stale.
+    fn index(&self, handle: Handle<T>) -> &T {
+        self.get(handle).expect("stale handle")
+    }
+}
+
+impl<T> IndexMut<Handle<T>> for HandleMap<T> {
+    fn index_mut(&mut self, handle: Handle<T>) -> &mut T {
+        self.get_mut(handle).expect("stale handle")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stale_handles_never_alias_reused_slots() {
+        let mut map = HandleMap::new();
+        let a = map.insert('a');
+        let b = map.insert_with(|handle| {
+            assert_eq!(handle.index(), 1);
+            'b'
+        });
+        assert_eq!(map.remove(a), Some('a'));
+        assert_eq!(map.remove(a), None);
+        let c = map.insert('c');
+        assert_eq!((c.index(), c.generation()), (0, 1));
+        assert_eq!(map.get(a), None);
+        assert_eq!(map.handle_at(0), Some(c));
+        assert_eq!(map.iter().collect::<Vec<_>>(), [(c, &'c'), (b, &'b')]);
+
+        map.retain(|_, value| *value == 'b');
+        assert_eq!((map.len(), map.slot_len()), (1, 2));
+        assert!(!map.contains(c) && map.contains(b));
+        map.clear();
+        assert!(map.is_empty());
+        let d = map.insert('d');
+        assert_eq!(
+            (d.index(), d.generation()),
+            (1, 1),
+            "the last freed slot is reused"
+        );
+    }
+}
@@ -0,0 +1,72 @@
+/// A free list of reusable values, such as scratch buffers whose heap
+/// capacity is worth keeping between uses.
+///
+/// Released values are reset before they are stored, so acquired values
+/// always start empty.
+///
+/// ```
+/// use astrelis_core::alloc::Pool;
+///
+/// let mut buffers = Pool::new(Vec::<u8>::clear);
+/// let mut buffer = buffers.acquire();
+/// buffer.extend_from_slice(b"frame data");
+/// buffers.release(buffer);
+///
+/// let reused = buffers.acquire();
+/// assert!(reused.is_empty() && reused.capacity() >= 10);
+/// ```
+#[derive(Debug)]
+pub struct Pool<T> {
+    idle: Vec<T>,
+    reset: fn(&mut T),
+    max_idle: usize,
+}
+
+impl<T> Pool<T> {
+    /// Creates an unbounded pool that resets released values with `reset`.
+    pub const fn new(reset: fn(&mut T)) -> Self {
+        Self {
+            idle: Vec::new(),
+            reset,
+            max_idle: usize::MAX,
+        }
+    }
+
+    /// Drops released values beyond `max_idle` instead of keeping them.
+    pub fn with_max_idle(mut self, max_idle: usize) -> Self {
+        self.max_idle = max_idle;
+        self.idle.truncate(max_idle);
+        self
+    }
+
+    /// Takes an idle value, or builds a new one with `create`.
+    pub fn acquire_with(&mut self, create: impl FnOnce() -> T) -> T {
+        self.idle.pop().unwrap_or_else(create)
+    }
+
+    /// Takes an idle value, or creates a default one.
+    pub fn acquire(&mut self) -> T
+    where
+        T: Default,
+    {
+        self.acquire_with(T::default)
+    }
+
+    /// Resets a value and keeps it for reuse.
+    pub fn release(&mut self, mut value: T) {
+        if self.idle.len() < self.max_idle {
+            (self.reset)(&mut value);
+            self.idle.push(value);
+        }
+    }
+
+    /// Number of values waiting for reuse.
+    pub fn idle(&self) -> usize {
+        self.idle.len()
+    }
+
+    /// Drops every idle value.
+    pub fn clear(&mut self) {
+        self.idle.clear();
+    }
+}
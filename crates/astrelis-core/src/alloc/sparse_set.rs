@@ -0,0 +1,228 @@
+use std::ops::{Index, IndexMut};
+
+const VACANT: u32 = u32::MAX;
+
+/// Values keyed by small integer indices, stored densely.
+///
+/// A sparse table maps each index to a position in a packed value array, so
+/// lookups are O(1) and iteration touches only live values. Indices are
+/// usually dense identifiers such as [`Handle::index`](super::Handle::index)
+/// or entity ids.
+///
+/// Removal is a swap-remove: the last packed value moves into the removed
+/// value's position. Iteration order is therefore insertion order only until
+/// the first removal, and slices from [`SparseSet::values`] are unordered.
+#[derive(Clone, Debug)]
+pub struct SparseSet<T> {
+    sparse: Vec<u32>,
+    indices: Vec<u32>,
+    values: Vec<T>,
+}
+
+impl<T> Default for SparseSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> SparseSet<T> {
+    /// Creates an empty set.
+    pub const fn new() -> Self {
+        Self {
+            sparse: Vec::new(),
+            indices: Vec::new(),
+            values: Vec::new(),
+        }
+    }
+
+    /// Creates an empty set with room for `capacity` values.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            sparse: Vec::new(),
+            indices: Vec::with_capacity(capacity),
+            values: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Number of stored values.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns whether no value is stored.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Values storable without reallocating the packed arrays.
+    pub fn capacity(&self) -> usize {
+        self.values.capacity()
+    }
+
+    /// Reserves packed room for at least `additional` more values.
+    pub fn reserve(&mut self, additional: usize) {
+        self.indices.reserve(additional);
+        self.values.reserve(additional);
+    }
+
+    /// Grows the sparse table so indices below `len` insert without
+    /// reallocating it.
+    pub fn reserve_indices(&mut self, len: usize) {
+        self.sparse.reserve(len.saturating_sub(self.sparse.len()));
+    }
+
+    /// Drops unused capacity, including sparse entries past the highest
+    /// stored index.
+    pub fn shrink_to_fit(&mut self) {
+        let end = self
+            .indices
+            .iter()
+            .max()
+            .map_or(0, |&index| index as usize + 1);
+        self.sparse.truncate(end);
+        self.sparse.shrink_to_fit();
+        self.indices.shrink_to_fit();
+        self.values.shrink_to_fit();
+    }
+
+    /// Returns whether `index` holds a value.
+    pub fn contains(&self, index: u32) -> bool {
+        self.position(index).is_some()
+    }
+
+    /// The value at `index`.
+    pub fn get(&self, index: u32) -> Option<&T> {
+        self.position(index).map(|position| &self.values[position])
+    }
+
+    /// The value at `index`, mutably.
+    pub fn get_mut(&mut self, index: u32) -> Option<&mut T> {
+        self.position(index)
+            .map(|position| &mut self.values[position])
+    }
+
+    /// Stores `value` at `index`, returning the value it replaced.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is `u32::MAX`, which marks vacant entries.
+    pub fn insert(&mut self, index: u32, value: T) -> Option<T> {
+        assert_ne!(index, VACANT, "sparse set index out of range");
+        if let Some(position) = self.position(index) {
+            return Some(std::mem::replace(&mut self.values[position], value));
+        }
+        let slot = index as usize;
+        if slot >= self.sparse.len() {
+            self.sparse.resize(slot + 1, VACANT);
+        }
+        self.sparse[slot] = self.values.len() as u32;
+        self.indices.push(index);
+        self.values.push(value);
+        None
+    }
+
+    /// Swap-removes the value at `index`.
+    pub fn remove(&mut self, index: u32) -> Option<T> {
+        let position = self.position(index)?;
+        self.sparse[index as usize] = VACANT;
+        self.indices.swap_remove(position);
+        if let Some(&moved) = self.indices.get(position) {
+            self.sparse[moved as usize] = position as u32;
+        }
+        Some(self.values.swap_remove(position))
+    }
+
+    /// Keeps only the values for which `keep` returns `true`.
+    pub fn retain(&mut self, mut keep: impl FnMut(u32, &mut T) -> bool) {
+        let mut position = 0;
+        while position < self.values.len() {
+            let index = self.indices[position];
+            if keep(index, &mut self.values[position]) {
+                position += 1;
+            } else {
+                self.remove(index);
+            }
+        }
+    }
+
+    /// Removes every value, keeping allocations.
+    pub fn clear(&mut self) {
+        self.sparse.clear();
+        self.indices.clear();
+        self.values.clear();
+    }
+
+    /// Stored indices in packed order.
+    pub fn indices(&self) -> &[u32] {
+        &self.indices
+    }
+
+    /// Stored values in packed order.
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Stored values in packed order, mutably.
+    pub fn values_mut(&mut self) -> &mut [T] {
+        &mut self.values
+    }
+
+    /// Every index with its value, in packed order.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (u32, &T)> + '_ {
+        self.indices.iter().copied().zip(&self.values)
+    }
+
+    /// Every index with its mutable value, in packed order.
+    pub fn iter_mut(&mut self) -> impl ExactSizeIterator<Item = (u32, &mut T)> + '_ {
+        self.indices.iter().copied().zip(&mut self.values)
+    }
+
+    fn position(&self, index: u32) -> Option<usize> {
+        let position = *self.sparse.get(index as usize)?;
+        (position != VACANT).then_some(position as usize)
+    }
+}
+
+impl<T> Index<u32> for SparseSet<T> {
+    type Output = T;
+
+    fn index(&self, index: u32) -> &T {
+        self.get(index).expect("no value at sparse set index")
+    }
+}
+
+impl<T> IndexMut<u32> for SparseSet<T> {
+    fn index_mut(&mut self, index: u32) -> &mut T {
+        self.get_mut(index).expect("no value at sparse set index")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removal_swaps_the_last_value_into_the_hole() {
+        let mut set = SparseSet::with_capacity(4);
+        for index in [7, 2, 40, 5] {
+            assert_eq!(set.insert(index, index * 10), None);
+        }
+        assert_eq!(set.insert(2, 21), Some(20));
+        assert_eq!(set.remove(7), Some(70));
+        assert_eq!(set.remove(7), None);
+        assert_eq!(set.indices(), [5, 2, 40]);
+        assert_eq!(set[5], 50);
+
+        set.retain(|index, value| {
+            *value += 1;
+            index != 40
+        });
+        assert_eq!(set.iter().collect::<Vec<_>>(), [(5, &51), (2, &22)]);
+        set.shrink_to_fit();
+        assert_eq!(set.sparse.len(), 6);
+        assert!(!set.contains(40));
+
+        set.clear();
+        assert!(set.is_empty() && set.get(5).is_none());
+    }
+}
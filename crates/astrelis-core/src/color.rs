@@ -1,11 +1,19 @@
 //! RGBA color type with named constants and format conversions.
+//!
+//! [`Color`] always stores linear channels. Constructors name the space they
+//! accept: `from_srgb*` and `from_hex*` decode display-encoded values,
+//! [`Color::from_linear`] takes linear values as-is, and the HSL/HSV helpers
+//! work on sRGB-encoded channels to match CSS and design tools. OKLab is
+//! provided for perceptually even blending.
 
 use bytemuck::{Pod, Zeroable};
 
 /// An RGBA color with 32-bit floating-point components.
 ///
-/// Components are stored in linear color space in the range `[0.0, 1.0]`.
-/// The type is `#[repr(C)]` and implements [`Pod`], so it can be directly
+/// Components are stored in linear color space, normally in the range
+/// `[0.0, 1.0]`. Values above 1.0 are meaningful when rendering into HDR
+/// targets, for example emissive surfaces built with
+/// [`Color::with_intensity`]. The type is `#[repr(C)]` and implements [`Pod`], so it can be directly
 /// uploaded to GPU buffers.
 #[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
 #[repr(C)]
@@ -66,6 +74,21 @@ impl Color {
         Self::new(r, g, b, 1.0)
     }
 
+    /// Creates a color from linear RGBA components.
+    ///
+    /// Equivalent to [`Color::new`]; use it where the space of the inputs
+    /// should be explicit next to sRGB constructors.
+    #[inline]
+    pub const fn from_linear(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self::new(r, g, b, a)
+    }
+
+    /// Returns the linear channels as `[r, g, b, a]`.
+    #[inline]
+    pub const fn to_linear(self) -> [f32; 4] {
+        [self.r, self.g, self.b, self.a]
+    }
+
     /// Creates a color from 8-bit RGBA components (0-255).
     ///
     /// Performs no sRGB decode: the bytes are treated as already-linear
@@ -157,11 +180,192 @@ impl Color {
         (r << 24) | (g << 16) | (b << 8) | a
     }
 
+    /// Creates a color from floating-point sRGB-encoded components, decoding
+    /// them to linear space.
+    ///
+    /// Unlike [`Color::from_srgb8`], components are not limited to `[0, 1]`:
+    /// the transfer curve is mirrored below zero and extended above one so
+    /// extended-range values survive a round trip through [`Color::to_srgb`].
+    #[inline]
+    pub fn from_srgb(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self::new(
+            srgb_to_linear_extended(r),
+            srgb_to_linear_extended(g),
+            srgb_to_linear_extended(b),
+            a,
+        )
+    }
+
+    /// Encodes the color channels to floating-point sRGB without clamping.
+    ///
+    /// Inverse of [`Color::from_srgb`]. Alpha is returned unchanged.
+    #[inline]
+    pub fn to_srgb(self) -> [f32; 4] {
+        [
+            linear_to_srgb_extended(self.r),
+            linear_to_srgb_extended(self.g),
+            linear_to_srgb_extended(self.b),
+            self.a,
+        ]
+    }
+
+    /// Creates a color from sRGB hue, saturation, and lightness.
+    ///
+    /// `hue` is in degrees and wraps; `saturation` and `lightness` are in
+    /// `[0, 1]`. Like CSS `hsl()`, the result is decoded from sRGB to linear.
+    pub fn from_hsl(hue: f32, saturation: f32, lightness: f32, a: f32) -> Self {
+        let saturation = saturation.clamp(0.0, 1.0);
+        let lightness = lightness.clamp(0.0, 1.0);
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        let [r, g, b] = hue_to_rgb(hue, chroma, lightness - chroma / 2.0);
+        Self::from_srgb(r, g, b, a)
+    }
+
+    /// Returns `[hue, saturation, lightness, alpha]` of the sRGB-encoded
+    /// color, with hue in degrees. Inverse of [`Color::from_hsl`].
+    pub fn to_hsl(self) -> [f32; 4] {
+        let ([r, g, b], max, min) = self.srgb_extremes();
+        let lightness = (max + min) / 2.0;
+        let chroma = max - min;
+        let saturation = if chroma == 0.0 {
+            0.0
+        } else {
+            chroma / (1.0 - (2.0 * lightness - 1.0).abs())
+        };
+        [rgb_hue(r, g, b, max, chroma), saturation, lightness, self.a]
+    }
+
+    /// Creates a color from sRGB hue, saturation, and value.
+    ///
+    /// `hue` is in degrees and wraps; `saturation` and `value` are in
+    /// `[0, 1]`. The result is decoded from sRGB to linear.
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32, a: f32) -> Self {
+        let value = value.clamp(0.0, 1.0);
+        let chroma = value * saturation.clamp(0.0, 1.0);
+        let [r, g, b] = hue_to_rgb(hue, chroma, value - chroma);
+        Self::from_srgb(r, g, b, a)
+    }
+
+    /// Returns `[hue, saturation, value, alpha]` of the sRGB-encoded color,
+    /// with hue in degrees. Inverse of [`Color::from_hsv`].
+    pub fn to_hsv(self) -> [f32; 4] {
+        let ([r, g, b], max, min) = self.srgb_extremes();
+        let chroma = max - min;
+        let saturation = if max == 0.0 { 0.0 } else { chroma / max };
+        [rgb_hue(r, g, b, max, chroma), saturation, max, self.a]
+    }
+
+    /// Creates a color from OKLab lightness and opponent axes.
+    pub fn from_oklab(l: f32, a: f32, b: f32, alpha: f32) -> Self {
+        let l_ = l + 0.396_337_78 * a + 0.215_803_76 * b;
+        let m_ = l - 0.105_561_346 * a - 0.063_854_17 * b;
+        let s_ = l - 0.089_484_18 * a - 1.291_485_5 * b;
+        let (l, m, s) = (l_.powi(3), m_.powi(3), s_.powi(3));
+        Self::new(
+            4.076_741_7 * l - 3.307_711_6 * m + 0.230_969_94 * s,
+            -1.268_438 * l + 2.609_757_4 * m - 0.341_319_38 * s,
+            -0.004_196_086_3 * l - 0.703_418_6 * m + 1.707_614_7 * s,
+            alpha,
+        )
+    }
+
+    /// Returns `[l, a, b, alpha]` in OKLab. Inverse of [`Color::from_oklab`].
+    pub fn to_oklab(self) -> [f32; 4] {
+        let l = 0.412_221_46 * self.r + 0.536_332_55 * self.g + 0.051_445_995 * self.b;
+        let m = 0.211_903_5 * self.r + 0.680_699_5 * self.g + 0.107_396_96 * self.b;
+        let s = 0.088_302_46 * self.r + 0.281_718_85 * self.g + 0.629_978_7 * self.b;
+        let (l, m, s) = (l.cbrt(), m.cbrt(), s.cbrt());
+        [
+            0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+            1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+            0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+            self.a,
+        ]
+    }
+
+    /// Interpolates toward `other` in OKLab, which keeps perceived
+    /// lightness even across gradients. Alpha is interpolated linearly.
+    pub fn mix_oklab(self, other: Self, t: f32) -> Self {
+        let [l0, a0, b0, alpha0] = self.to_oklab();
+        let [l1, a1, b1, alpha1] = other.to_oklab();
+        let lerp = |from: f32, to: f32| from + (to - from) * t;
+        Self::from_oklab(
+            lerp(l0, l1),
+            lerp(a0, a1),
+            lerp(b0, b1),
+            lerp(alpha0, alpha1),
+        )
+    }
+
+    /// Returns the color with its RGB channels multiplied by `intensity`.
+    ///
+    /// Intensities above 1.0 produce HDR values that bloom and tonemapping
+    /// treat as brighter than display white. Alpha is unchanged.
+    #[inline]
+    pub const fn with_intensity(self, intensity: f32) -> Self {
+        Self {
+            r: self.r * intensity,
+            g: self.g * intensity,
+            b: self.b * intensity,
+            a: self.a,
+        }
+    }
+
+    /// Relative luminance of the linear Rec. 709 channels.
+    #[inline]
+    pub fn luminance(self) -> f32 {
+        0.2126 * self.r + 0.7152 * self.g + 0.0722 * self.b
+    }
+
     /// Returns the color with a different alpha value.
     #[inline]
     pub const fn with_alpha(self, a: f32) -> Self {
         Self { a, ..self }
     }
+
+    fn srgb_extremes(self) -> ([f32; 3], f32, f32) {
+        let [r, g, b, _] = self.to_srgb();
+        let (r, g, b) = (r.clamp(0.0, 1.0), g.clamp(0.0, 1.0), b.clamp(0.0, 1.0));
+        ([r, g, b], r.max(g).max(b), r.min(g).min(b))
+    }
+}
+
+/// sRGB channels for a hue in degrees, chroma, and lightness offset.
+fn hue_to_rgb(hue: f32, chroma: f32, offset: f32) -> [f32; 3] {
+    let sector = hue.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+    let [r, g, b] = match sector as u32 {
+        0 => [chroma, x, 0.0],
+        1 => [x, chroma, 0.0],
+        2 => [0.0, chroma, x],
+        3 => [0.0, x, chroma],
+        4 => [x, 0.0, chroma],
+        _ => [chroma, 0.0, x],
+    };
+    [r + offset, g + offset, b + offset]
+}
+
+/// Hue in degrees of sRGB channels with the given maximum and chroma.
+fn rgb_hue(r: f32, g: f32, b: f32, max: f32, chroma: f32) -> f32 {
+    if chroma == 0.0 {
+        return 0.0;
+    }
+    let sector = if max == r {
+        ((g - b) / chroma).rem_euclid(6.0)
+    } else if max == g {
+        (b - r) / chroma + 2.0
+    } else {
+        (r - g) / chroma + 4.0
+    };
+    sector * 60.0
+}
+
+fn srgb_to_linear_extended(c: f32) -> f32 {
+    srgb_to_linear(c.abs()).copysign(c)
+}
+
+fn linear_to_srgb_extended(c: f32) -> f32 {
+    linear_to_srgb(c.abs()).copysign(c)
 }
 
 /// Decodes one sRGB-encoded channel value in `[0, 1]` to linear space.
@@ -245,6 +449,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn extended_srgb_roundtrips_hdr_values() {
+        let c = Color::from_hex(0x4c8dff).with_intensity(4.0);
+        assert!(c.b > 1.0);
+        let [r, g, b, a] = c.to_srgb();
+        let back = Color::from_srgb(r, g, b, a);
+        assert!((back.r - c.r).abs() < 1e-4 && (back.b - c.b).abs() < 1e-4);
+        assert!((Color::WHITE.luminance() - 1.0).abs() < 1e-6);
+        assert_eq!(
+            Color::from_srgb(-0.5, 0.0, 0.0, 1.0).r,
+            -srgb_to_linear(0.5)
+        );
+    }
+
     #[test]
     fn hex_alpha_and_to_srgb8_roundtrip() {
         let c = Color::from_hex_alpha(0x4c8dff80);
@@ -252,6 +470,35 @@ mod tests {
         assert_eq!((back.r, back.g, back.b, back.a), (0x4c, 0x8d, 0xff, 0x80));
     }
 
+    #[test]
+    fn hsl_hsv_and_oklab_roundtrip() {
+        let orange = Color::from_hsl(30.0, 1.0, 0.5, 1.0);
+        assert_eq!(orange.to_srgb8(), Color::from_hex(0xff8000).to_srgb8());
+        assert_eq!(Color::from_hsv(120.0, 1.0, 1.0, 1.0), Color::GREEN);
+        assert_eq!(Color::from_hsl(-120.0, 1.0, 0.5, 1.0), Color::BLUE);
+
+        let c = Color::from_hex(0x4c8dff);
+        let [h, s, l, _] = c.to_hsl();
+        let back = Color::from_hsl(h, s, l, 1.0);
+        assert!((back.r - c.r).abs() < 1e-4 && (back.b - c.b).abs() < 1e-4);
+        let [h, s, v, _] = c.to_hsv();
+        assert!((h - 218.5).abs() < 0.5, "got {h}");
+        assert!((Color::from_hsv(h, s, v, 1.0).g - c.g).abs() < 1e-4);
+
+        let [l, a, b, _] = Color::WHITE.to_oklab();
+        assert!((l - 1.0).abs() < 1e-3 && a.abs() < 1e-3 && b.abs() < 1e-3);
+        let [l, a, b, alpha] = c.to_oklab();
+        let back = Color::from_oklab(l, a, b, alpha);
+        assert!((back.r - c.r).abs() < 1e-3 && (back.b - c.b).abs() < 1e-3);
+        // OKLab lightness is the cube root of linear luminance.
+        let gray = Color::BLACK.mix_oklab(Color::WHITE, 0.5);
+        assert!((gray.g - 0.125).abs() < 1e-3, "got {}", gray.g);
+        assert_eq!(
+            Color::from_linear(0.1, 0.2, 0.3, 1.0).to_linear(),
+            [0.1, 0.2, 0.3, 1.0]
+        );
+    }
+
     #[test]
     fn array_conversion() {
         let arr: [f32; 4] = Color::RED.into();
@@ -4,6 +4,11 @@
 //! [`Physical`]) so the type system prevents accidentally mixing pixel
 //! coordinates with logical (DPI-scaled) coordinates.
 //!
+//! Alongside [`Rect`], the 2D shapes [`Circle`], [`Segment`], and [`Ray`]
+//! provide intersection, containment, and closest-point tests. World-space
+//! 3D shapes ([`Aabb`], [`Obb`], [`Plane`], and [`Ray3`]) use `glam` vectors
+//! directly, since they have no logical/physical distinction.
+//!
 //! # Example
 //!
 //! ```
@@ -17,6 +22,8 @@
 
 use std::marker::PhantomData;
 
+use crate::math::{Mat4, Quat, Vec2, Vec3};
+
 /// Marker type for logical (DPI-independent) coordinates.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
 pub struct Logical;
@@ -195,6 +202,67 @@ where
     }
 }
 
+impl<S> Rect<S, f32> {
+    /// Returns the center point.
+    #[inline]
+    pub fn center(&self) -> Point<S> {
+        Point::new(
+            self.origin.x + self.size.width / 2.0,
+            self.origin.y + self.size.height / 2.0,
+        )
+    }
+
+    /// Returns `true` if the rectangles overlap or touch.
+    #[inline]
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.min_x() <= other.max_x()
+            && other.min_x() <= self.max_x()
+            && self.min_y() <= other.max_y()
+            && other.min_y() <= self.max_y()
+    }
+
+    /// Returns the overlapping region, or `None` if the rectangles are
+    /// disjoint. Touching rectangles yield an empty rectangle.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let x = self.min_x().max(other.min_x());
+        let y = self.min_y().max(other.min_y());
+        let max_x = self.max_x().min(other.max_x());
+        let max_y = self.max_y().min(other.max_y());
+        (x <= max_x && y <= max_y).then(|| Self::from_xywh(x, y, max_x - x, max_y - y))
+    }
+
+    /// Returns the smallest rectangle containing both rectangles.
+    pub fn union(&self, other: &Self) -> Self {
+        let x = self.min_x().min(other.min_x());
+        let y = self.min_y().min(other.min_y());
+        Self::from_xywh(
+            x,
+            y,
+            self.max_x().max(other.max_x()) - x,
+            self.max_y().max(other.max_y()) - y,
+        )
+    }
+
+    /// Returns `true` if `other` lies entirely inside this rectangle.
+    #[inline]
+    pub fn contains_rect(&self, other: &Self) -> bool {
+        other.min_x() >= self.min_x()
+            && other.max_x() <= self.max_x()
+            && other.min_y() >= self.min_y()
+            && other.max_y() <= self.max_y()
+    }
+
+    /// Returns the point of the rectangle nearest to `point`, which is
+    /// `point` itself when inside.
+    #[inline]
+    pub fn closest_point(&self, point: Point<S>) -> Point<S> {
+        Point::new(
+            point.x.clamp(self.min_x(), self.max_x()),
+            point.y.clamp(self.min_y(), self.max_y()),
+        )
+    }
+}
+
 impl Rect<Logical, f32> {
     /// Converts to physical coordinates.
     #[inline]
@@ -217,6 +285,465 @@ impl Rect<Physical, f32> {
     }
 }
 
+// --- 2D shapes ---
+
+/// A circle in coordinate space `S`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Circle<S> {
+    /// Center point.
+    pub center: Point<S>,
+    /// Radius, non-negative.
+    pub radius: f32,
+}
+
+impl<S> Circle<S> {
+    /// Creates a circle.
+    #[inline]
+    pub const fn new(center: Point<S>, radius: f32) -> Self {
+        Self { center, radius }
+    }
+
+    /// Returns `true` if the point is inside or on the circle.
+    #[inline]
+    pub fn contains(&self, point: Point<S>) -> bool {
+        distance_squared(&self.center, &point) <= self.radius * self.radius
+    }
+
+    /// Returns `true` if the circles overlap or touch.
+    #[inline]
+    pub fn intersects(&self, other: &Self) -> bool {
+        let reach = self.radius + other.radius;
+        distance_squared(&self.center, &other.center) <= reach * reach
+    }
+
+    /// Returns `true` if the circle overlaps or touches the rectangle.
+    #[inline]
+    pub fn intersects_rect(&self, rect: &Rect<S>) -> bool {
+        self.contains(rect.closest_point(Point::new(self.center.x, self.center.y)))
+    }
+
+    /// Returns the point of the disc nearest to `point`, which is `point`
+    /// itself when inside.
+    pub fn closest_point(&self, point: Point<S>) -> Point<S> {
+        let offset = Vec2::new(point.x - self.center.x, point.y - self.center.y);
+        if offset.length_squared() <= self.radius * self.radius {
+            return point;
+        }
+        let edge = offset.normalize() * self.radius;
+        Point::new(self.center.x + edge.x, self.center.y + edge.y)
+    }
+}
+
+/// A line segment in coordinate space `S`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Segment<S> {
+    /// First endpoint.
+    pub start: Point<S>,
+    /// Second endpoint.
+    pub end: Point<S>,
+}
+
+impl<S> Segment<S> {
+    /// Creates a segment.
+    #[inline]
+    pub const fn new(start: Point<S>, end: Point<S>) -> Self {
+        Self { start, end }
+    }
+
+    /// Returns the segment's length.
+    #[inline]
+    pub fn length(&self) -> f32 {
+        distance_squared(&self.start, &self.end).sqrt()
+    }
+
+    /// Returns the point of the segment nearest to `point`.
+    pub fn closest_point(&self, point: Point<S>) -> Point<S> {
+        let along = self.delta();
+        let length_squared = along.length_squared();
+        let t = if length_squared == 0.0 {
+            0.0
+        } else {
+            (Vec2::new(point.x - self.start.x, point.y - self.start.y).dot(along) / length_squared)
+                .clamp(0.0, 1.0)
+        };
+        self.lerp(t)
+    }
+
+    /// Returns the crossing point of two segments, or `None` if they do not
+    /// cross. Parallel segments never cross, even when collinear.
+    pub fn intersection(&self, other: &Self) -> Option<Point<S>> {
+        let (t, u) = crossing(&self.start, self.delta(), &other.start, other.delta())?;
+        ((0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u)).then(|| self.lerp(t))
+    }
+
+    fn delta(&self) -> Vec2 {
+        Vec2::new(self.end.x - self.start.x, self.end.y - self.start.y)
+    }
+
+    fn lerp(&self, t: f32) -> Point<S> {
+        let delta = self.delta() * t;
+        Point::new(self.start.x + delta.x, self.start.y + delta.y)
+    }
+}
+
+/// A half-line in coordinate space `S` with a unit-length direction.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Ray<S> {
+    /// Start point.
+    pub origin: Point<S>,
+    /// Unit-length direction; construct with [`Ray::new`] to normalize it.
+    pub direction: Vec2,
+}
+
+impl<S> Ray<S> {
+    /// Creates a ray, normalizing `direction`. Returns `None` if the
+    /// direction is zero or not finite.
+    #[inline]
+    pub fn new(origin: Point<S>, direction: Vec2) -> Option<Self> {
+        Some(Self {
+            origin,
+            direction: direction.try_normalize()?,
+        })
+    }
+
+    /// Returns the point `distance` along the ray.
+    #[inline]
+    pub fn at(&self, distance: f32) -> Point<S> {
+        Point::new(
+            self.origin.x + self.direction.x * distance,
+            self.origin.y + self.direction.y * distance,
+        )
+    }
+
+    /// Distance at which the ray enters the rectangle, zero when the origin
+    /// is inside, or `None` on a miss.
+    pub fn cast_rect(&self, rect: &Rect<S>) -> Option<f32> {
+        let inverse = self.direction.recip();
+        let x1 = (rect.min_x() - self.origin.x) * inverse.x;
+        let x2 = (rect.max_x() - self.origin.x) * inverse.x;
+        let y1 = (rect.min_y() - self.origin.y) * inverse.y;
+        let y2 = (rect.max_y() - self.origin.y) * inverse.y;
+        let near = x1.min(x2).max(y1.min(y2)).max(0.0);
+        let far = x1.max(x2).min(y1.max(y2));
+        (near <= far).then_some(near)
+    }
+
+    /// Distance at which the ray enters the circle, zero when the origin is
+    /// inside, or `None` on a miss.
+    pub fn cast_circle(&self, circle: &Circle<S>) -> Option<f32> {
+        let offset = Vec2::new(
+            self.origin.x - circle.center.x,
+            self.origin.y - circle.center.y,
+        );
+        let c = offset.length_squared() - circle.radius * circle.radius;
+        if c <= 0.0 {
+            return Some(0.0);
+        }
+        let b = offset.dot(self.direction);
+        let discriminant = b * b - c;
+        (b < 0.0 && discriminant >= 0.0).then(|| -b - discriminant.sqrt())
+    }
+
+    /// Distance at which the ray crosses the segment, or `None` on a miss.
+    pub fn cast_segment(&self, segment: &Segment<S>) -> Option<f32> {
+        let (t, u) = crossing(
+            &self.origin,
+            self.direction,
+            &segment.start,
+            segment.delta(),
+        )?;
+        (t >= 0.0 && (0.0..=1.0).contains(&u)).then_some(t)
+    }
+}
+
+fn distance_squared<S>(a: &Point<S>, b: &Point<S>) -> f32 {
+    Vec2::new(a.x - b.x, a.y - b.y).length_squared()
+}
+
+/// Parameters `(t, u)` where `a + t * da == b + u * db`, or `None` when the
+/// lines are parallel.
+fn crossing<S>(a: &Point<S>, da: Vec2, b: &Point<S>, db: Vec2) -> Option<(f32, f32)> {
+    let denominator = da.perp_dot(db);
+    if denominator == 0.0 {
+        return None;
+    }
+    let offset = Vec2::new(b.x - a.x, b.y - a.y);
+    Some((
+        offset.perp_dot(db) / denominator,
+        offset.perp_dot(da) / denominator,
+    ))
+}
+
+// --- 3D shapes ---
+
+/// A world-space axis-aligned bounding box.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Aabb {
+    /// Minimum corner.
+    pub min: Vec3,
+    /// Maximum corner.
+    pub max: Vec3,
+}
+
+impl Aabb {
+    /// Creates a box from its corners.
+    #[inline]
+    pub const fn new(min: Vec3, max: Vec3) -> Self {
+        Self { min, max }
+    }
+
+    /// Creates a box from its center and half extents.
+    #[inline]
+    pub fn from_center_half_extents(center: Vec3, half_extents: Vec3) -> Self {
+        Self::new(center - half_extents, center + half_extents)
+    }
+
+    /// Creates the smallest box containing every point, or `None` when
+    /// there are none.
+    pub fn from_points(points: impl IntoIterator<Item = Vec3>) -> Option<Self> {
+        points
+            .into_iter()
+            .map(|point| Self::new(point, point))
+            .reduce(|a, b| a.union(&b))
+    }
+
+    /// Returns the center point.
+    #[inline]
+    pub fn center(&self) -> Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    /// Returns half the box's size along each axis.
+    #[inline]
+    pub fn half_extents(&self) -> Vec3 {
+        (self.max - self.min) * 0.5
+    }
+
+    /// Returns `true` if the point is inside or on the box.
+    #[inline]
+    pub fn contains(&self, point: Vec3) -> bool {
+        point.cmpge(self.min).all() && point.cmple(self.max).all()
+    }
+
+    /// Returns `true` if the boxes overlap or touch.
+    #[inline]
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.min.cmple(other.max).all() && other.min.cmple(self.max).all()
+    }
+
+    /// Returns the smallest box containing both boxes.
+    #[inline]
+    pub fn union(&self, other: &Self) -> Self {
+        Self::new(self.min.min(other.min), self.max.max(other.max))
+    }
+
+    /// Returns the point of the box nearest to `point`.
+    #[inline]
+    pub fn closest_point(&self, point: Vec3) -> Vec3 {
+        point.clamp(self.min, self.max)
+    }
+
+    /// Returns the box enclosing this box after an affine transform.
+    pub fn transformed(&self, transform: &Mat4) -> Self {
+        let center = transform.transform_point3(self.center());
+        let half = self.half_extents();
+        let extent = transform.x_axis.truncate().abs() * half.x
+            + transform.y_axis.truncate().abs() * half.y
+            + transform.z_axis.truncate().abs() * half.z;
+        Self::from_center_half_extents(center, extent)
+    }
+}
+
+/// A world-space oriented bounding box.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Obb {
+    /// Center point.
+    pub center: Vec3,
+    /// Half the box's size along each local axis.
+    pub half_extents: Vec3,
+    /// Rotation from local to world axes.
+    pub rotation: Quat,
+}
+
+impl Obb {
+    /// Creates an oriented box.
+    #[inline]
+    pub const fn new(center: Vec3, half_extents: Vec3, rotation: Quat) -> Self {
+        Self {
+            center,
+            half_extents,
+            rotation,
+        }
+    }
+
+    /// Returns the box's local axes in world space.
+    #[inline]
+    pub fn axes(&self) -> [Vec3; 3] {
+        [Vec3::X, Vec3::Y, Vec3::Z].map(|axis| self.rotation * axis)
+    }
+
+    /// Returns `true` if the point is inside or on the box.
+    pub fn contains(&self, point: Vec3) -> bool {
+        let local = self.rotation.inverse() * (point - self.center);
+        local.abs().cmple(self.half_extents).all()
+    }
+
+    /// Returns the point of the box nearest to `point`.
+    pub fn closest_point(&self, point: Vec3) -> Vec3 {
+        let local = self.rotation.inverse() * (point - self.center);
+        self.center + self.rotation * local.clamp(-self.half_extents, self.half_extents)
+    }
+
+    /// Returns `true` if the boxes overlap or touch, using the separating
+    /// axis test.
+    pub fn intersects(&self, other: &Self) -> bool {
+        let a = self.axes();
+        let b = other.axes();
+        let offset = other.center - self.center;
+        let crosses = a.iter().flat_map(|a| b.iter().map(move |b| a.cross(*b)));
+        a.into_iter()
+            .chain(b)
+            .chain(crosses)
+            .filter(|axis| axis.length_squared() > 1e-10)
+            .all(|axis| {
+                offset.dot(axis).abs() <= self.radius_along(axis) + other.radius_along(axis)
+            })
+    }
+
+    /// Returns the world-space box enclosing this box.
+    pub fn aabb(&self) -> Aabb {
+        let extent = self
+            .axes()
+            .into_iter()
+            .zip(self.half_extents.to_array())
+            .map(|(axis, half)| axis.abs() * half)
+            .fold(Vec3::ZERO, |sum, extent| sum + extent);
+        Aabb::from_center_half_extents(self.center, extent)
+    }
+
+    fn radius_along(&self, axis: Vec3) -> f32 {
+        self.axes()
+            .into_iter()
+            .zip(self.half_extents.to_array())
+            .map(|(local, half)| local.dot(axis).abs() * half)
+            .sum()
+    }
+}
+
+impl From<Aabb> for Obb {
+    fn from(aabb: Aabb) -> Self {
+        Self::new(aabb.center(), aabb.half_extents(), Quat::IDENTITY)
+    }
+}
+
+/// A world-space plane: points `p` with `normal.dot(p) == distance`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Plane {
+    /// Unit-length normal.
+    pub normal: Vec3,
+    /// Signed distance of the plane from the origin along `normal`.
+    pub distance: f32,
+}
+
+impl Plane {
+    /// Creates the plane through `point` facing `normal`. Returns `None` if
+    /// the normal is zero.
+    pub fn from_point_normal(point: Vec3, normal: Vec3) -> Option<Self> {
+        let normal = normal.try_normalize()?;
+        Some(Self {
+            normal,
+            distance: normal.dot(point),
+        })
+    }
+
+    /// Creates the plane through three points, facing the side from which
+    /// they wind counter-clockwise. Returns `None` if they are collinear.
+    pub fn from_points(a: Vec3, b: Vec3, c: Vec3) -> Option<Self> {
+        Self::from_point_normal(a, (b - a).cross(c - a))
+    }
+
+    /// Signed distance from the plane, positive on the normal's side.
+    #[inline]
+    pub fn signed_distance(&self, point: Vec3) -> f32 {
+        self.normal.dot(point) - self.distance
+    }
+
+    /// Returns the point on the plane nearest to `point`.
+    #[inline]
+    pub fn closest_point(&self, point: Vec3) -> Vec3 {
+        point - self.normal * self.signed_distance(point)
+    }
+
+    /// Returns `true` if the box touches or straddles the plane.
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        let radius = aabb.half_extents().dot(self.normal.abs());
+        self.signed_distance(aabb.center()).abs() <= radius
+    }
+}
+
+/// A world-space half-line with a unit-length direction.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ray3 {
+    /// Start point.
+    pub origin: Vec3,
+    /// Unit-length direction; construct with [`Ray3::new`] to normalize it.
+    pub direction: Vec3,
+}
+
+impl Ray3 {
+    /// Creates a ray, normalizing `direction`. Returns `None` if the
+    /// direction is zero or not finite.
+    #[inline]
+    pub fn new(origin: Vec3, direction: Vec3) -> Option<Self> {
+        Some(Self {
+            origin,
+            direction: direction.try_normalize()?,
+        })
+    }
+
+    /// Returns the point `distance` along the ray.
+    #[inline]
+    pub fn at(&self, distance: f32) -> Vec3 {
+        self.origin + self.direction * distance
+    }
+
+    /// Distance at which the ray enters the box, zero when the origin is
+    /// inside, or `None` on a miss.
+    pub fn cast_aabb(&self, aabb: &Aabb) -> Option<f32> {
+        let inverse = self.direction.recip();
+        let a = (aabb.min - self.origin) * inverse;
+        let b = (aabb.max - self.origin) * inverse;
+        let near = a.min(b).max_element().max(0.0);
+        let far = a.max(b).min_element();
+        (near <= far).then_some(near)
+    }
+
+    /// Distance at which the ray enters the oriented box, or `None` on a
+    /// miss.
+    pub fn cast_obb(&self, obb: &Obb) -> Option<f32> {
+        let inverse = obb.rotation.inverse();
+        let local = Self {
+            origin: inverse * (self.origin - obb.center),
+            direction: inverse * self.direction,
+        };
+        local.cast_aabb(&Aabb::from_center_half_extents(
+            Vec3::ZERO,
+            obb.half_extents,
+        ))
+    }
+
+    /// Distance at which the ray crosses the plane, or `None` if it is
+    /// parallel or points away.
+    pub fn cast_plane(&self, plane: &Plane) -> Option<f32> {
+        let facing = plane.normal.dot(self.direction);
+        if facing == 0.0 {
+            return None;
+        }
+        let distance = -plane.signed_distance(self.origin) / facing;
+        (distance >= 0.0).then_some(distance)
+    }
+}
+
 /// Logical point type alias.
 pub type LogicalPoint = Point<Logical>;
 
@@ -265,6 +792,81 @@ mod tests {
         assert_eq!(rect.max_y(), 60.0);
     }
 
+    #[test]
+    fn rect_circle_segment_and_ray_tests() {
+        let a = Rect::<Logical>::from_xywh(0.0, 0.0, 10.0, 10.0);
+        let b = Rect::from_xywh(5.0, 5.0, 10.0, 10.0);
+        assert_eq!(
+            a.intersection(&b),
+            Some(Rect::from_xywh(5.0, 5.0, 5.0, 5.0))
+        );
+        assert_eq!(a.union(&b), Rect::from_xywh(0.0, 0.0, 15.0, 15.0));
+        assert!(
+            a.intersection(&Rect::from_xywh(20.0, 0.0, 1.0, 1.0))
+                .is_none()
+        );
+        assert!(a.contains_rect(&Rect::from_xywh(1.0, 1.0, 2.0, 2.0)));
+        assert_eq!(a.closest_point(Point::new(-3.0, 4.0)), Point::new(0.0, 4.0));
+
+        let circle = Circle::new(Point::new(13.0, 5.0), 3.0);
+        assert!(circle.intersects_rect(&a));
+        assert!(!Circle::new(Point::new(13.0, 13.0), 3.0).intersects_rect(&a));
+        assert_eq!(
+            circle.closest_point(Point::new(20.0, 5.0)),
+            Point::new(16.0, 5.0)
+        );
+
+        let diagonal = Segment::new(Point::new(0.0, 0.0), Point::new(10.0, 10.0));
+        let cross = Segment::new(Point::new(0.0, 10.0), Point::new(10.0, 0.0));
+        assert_eq!(diagonal.intersection(&cross), Some(Point::new(5.0, 5.0)));
+        assert_eq!(
+            diagonal.closest_point(Point::new(10.0, 0.0)),
+            Point::new(5.0, 5.0)
+        );
+
+        let ray = Ray::new(Point::new(-5.0, 5.0), Vec2::new(2.0, 0.0)).unwrap();
+        assert_eq!(ray.cast_rect(&a), Some(5.0));
+        assert_eq!(ray.cast_circle(&circle), Some(15.0));
+        assert_eq!(ray.cast_segment(&cross), Some(10.0));
+        assert!(Ray::<Logical>::new(Point::ZERO, Vec2::ZERO).is_none());
+    }
+
+    #[test]
+    fn aabb_obb_plane_and_ray3_tests() {
+        let aabb = Aabb::from_center_half_extents(Vec3::ZERO, Vec3::ONE);
+        let moved = aabb.transformed(&Mat4::from_translation(Vec3::new(3.0, 0.0, 0.0)));
+        assert_eq!(moved.center(), Vec3::new(3.0, 0.0, 0.0));
+        assert!(!aabb.intersects(&moved));
+
+        let turned = Obb::new(
+            Vec3::new(2.2, 0.0, 0.0),
+            Vec3::ONE,
+            Quat::from_rotation_z(std::f32::consts::FRAC_PI_4),
+        );
+        assert!(
+            Obb::from(aabb).intersects(&turned),
+            "corner reaches x = 1.2"
+        );
+        let far = Obb {
+            center: Vec3::new(2.5, 0.0, 0.0),
+            ..turned
+        };
+        assert!(!Obb::from(aabb).intersects(&far));
+        assert!(turned.contains(Vec3::new(0.8, 0.0, 0.0)));
+        assert!((turned.aabb().half_extents().x - std::f32::consts::SQRT_2).abs() < 1e-5);
+
+        let floor = Plane::from_point_normal(Vec3::new(0.0, -1.0, 0.0), Vec3::Y).unwrap();
+        assert_eq!(floor.signed_distance(Vec3::new(4.0, 2.0, 0.0)), 3.0);
+        assert!(floor.intersects_aabb(&aabb));
+
+        let ray = Ray3::new(Vec3::new(-5.0, 0.0, 0.0), Vec3::X).unwrap();
+        assert_eq!(ray.cast_aabb(&aabb), Some(4.0));
+        assert!((ray.cast_obb(&turned).unwrap() - (7.2 - std::f32::consts::SQRT_2)).abs() < 1e-5);
+        let down = Ray3::new(Vec3::new(0.0, 4.0, 0.0), -Vec3::Y).unwrap();
+        assert_eq!(down.cast_plane(&floor), Some(5.0));
+        assert_eq!(ray.cast_plane(&floor), None);
+    }
+
     #[test]
     fn generic_integer_size_is_exact() {
         let size = Size::<Physical, u32>::new(3840, 2160);
@@ -1,14 +1,22 @@
 //! Core types and math for the Astrelis engine.
 //!
 //! This crate provides the foundational types used throughout the engine:
+//! - [`alloc`] — Per-frame bump arenas, generational handle maps, and object pools
 //! - [`math`] — Linear algebra types (re-exported from `glam`) and GPU-ready packed types
 //! - [`color`] — RGBA color type with named constants and conversions
 //! - [`geometry`] — Coordinate-space-aware geometric primitives (points, sizes, rects)
 //! - [`id`] — Type-safe generic ID handles
+//! - [`random`] — Seedable, reproducible random number generation
+//! - [`reflect`] — Runtime field access by name for inspectors and editors
+//! - [`spatial`] — Grids, quad trees, and BVHs for area queries and ray casts
 
+pub mod alloc;
 pub mod color;
 pub mod geometry;
 pub mod id;
 #[cfg(feature = "tracing-init")]
 pub mod logging;
 pub mod math;
+pub mod random;
+pub mod reflect;
+pub mod spatial;
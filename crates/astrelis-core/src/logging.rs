@@ -7,6 +7,11 @@
 //! - Default filter: `warn` globally, `info` for all `astrelis_*` crates
 //! - Compact format with thread names
 //!
+//! [`init`] installs the same subscriber from a [`LogConfig`], optionally
+//! adding rolling file output and an in-memory [`LogBuffer`] for console
+//! widgets, and returns a [`LogHandle`] that changes per-target levels while
+//! the application runs.
+//!
 //! # Examples
 //!
 //! ```no_run
@@ -19,6 +24,46 @@
 //! RUST_LOG=debug cargo run --example my_example
 //! RUST_LOG=astrelis_gpu=trace cargo run --example my_example
 //! ```
+//!
+//! Or keep a handle and adjust levels from a debug menu:
+//!
+//! ```no_run
+//! use astrelis_core::logging::{self, LogConfig, RollingFile};
+//! use tracing::level_filters::LevelFilter;
+//!
+//! let handle = logging::init(LogConfig {
+//!     file: Some(RollingFile::new("logs", "game")),
+//!     buffer_capacity: 1024,
+//!     ..Default::default()
+//! })
+//! .unwrap();
+//! handle.set_target_level("astrelis_gpu", LevelFilter::TRACE).unwrap();
+//! for record in handle.buffer().unwrap().records() {
+//!     println!("{} {}: {}", record.level, record.target, record.message);
+//! }
+//! ```
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    error::Error,
+    fmt::{self, Write as _},
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::PathBuf,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use tracing::{Event, Level, Subscriber, field::Field, level_filters::LevelFilter};
+use tracing_subscriber::{
+    EnvFilter, Layer, Registry,
+    field::Visit,
+    fmt::{self as format, MakeWriter},
+    layer::{Context, SubscriberExt},
+    reload,
+    util::SubscriberInitExt,
+};
+
+const DEFAULT_FILTER: &str = "info,astrelis_core=trace";
 
 /// Installs the default tracing subscriber for the Astrelis engine.
 ///
@@ -27,16 +72,534 @@
 /// The filter can be overridden via the `RUST_LOG` environment variable.
 ///
 /// Safe to call multiple times — subsequent calls are silently ignored.
+/// Any other failure is printed to standard error, since there is no
+/// subscriber to report it through.
 #[cfg(feature = "tracing-init")]
 pub fn init_default() {
-    use tracing_subscriber::{EnvFilter, fmt};
+    if tracing::dispatcher::has_been_set() {
+        return;
+    }
+    if let Err(error) = init(LogConfig::default()) {
+        eprintln!("astrelis: cannot install logging: {error}");
+    }
+}
+
+/// What [`init`] installs.
+#[derive(Clone, Debug)]
+pub struct LogConfig {
+    /// Filter directives in `RUST_LOG` syntax, used when `RUST_LOG` is unset.
+    pub filter: String,
+    /// Writes formatted events to standard error.
+    pub stderr: bool,
+    /// Also writes events, without color codes, to rotating files.
+    pub file: Option<RollingFile>,
+    /// Number of recent events kept for [`LogHandle::buffer`]; zero keeps
+    /// none.
+    pub buffer_capacity: usize,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            filter: DEFAULT_FILTER.into(),
+            stderr: true,
+            file: None,
+            buffer_capacity: 0,
+        }
+    }
+}
+
+/// Size-based rotation for file output.
+///
+/// The active file is `<prefix>.log`; when writing an event would grow it
+/// past `max_bytes`, older files shift to `<prefix>.1.log`,
+/// `<prefix>.2.log`, and so on, and the oldest beyond `max_files` is
+/// deleted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RollingFile {
+    /// Directory holding the log files, created if missing.
+    pub directory: PathBuf,
+    /// File name stem.
+    pub prefix: String,
+    /// Size at which the active file rotates.
+    pub max_bytes: u64,
+    /// Files kept, including the active one.
+    pub max_files: usize,
+}
+
+impl RollingFile {
+    /// Rotates at 10 MiB and keeps five files.
+    pub fn new(directory: impl Into<PathBuf>, prefix: impl Into<String>) -> Self {
+        Self {
+            directory: directory.into(),
+            prefix: prefix.into(),
+            max_bytes: 10 * 1024 * 1024,
+            max_files: 5,
+        }
+    }
+
+    fn path(&self, index: usize) -> PathBuf {
+        let name = match index {
+            0 => format!("{}.log", self.prefix),
+            index => format!("{}.{index}.log", self.prefix),
+        };
+        self.directory.join(name)
+    }
+}
+
+/// Installs a global subscriber built from `config`.
+///
+/// `RUST_LOG`, when set, replaces `config.filter`; if it does not parse, a
+/// warning is logged and `config.filter` is used instead. Fails if a global
+/// subscriber is already installed, `config.filter` does not parse, or the
+/// log directory cannot be opened.
+pub fn init(config: LogConfig) -> Result<LogHandle, LogError> {
+    let (directives, filter, rejected) =
+        initial_filter(std::env::var(EnvFilter::DEFAULT_ENV).ok(), config.filter)?;
+    let (filter, reload) = reload::Layer::new(filter);
+    let file = config
+        .file
+        .map(|file| {
+            Rolling::open(file)
+                .map(|rolling| {
+                    format::layer()
+                        .with_ansi(false)
+                        .with_writer(RollingWriter(Arc::new(Mutex::new(rolling))))
+                })
+                .map_err(|error| LogError::new(format!("cannot open log file: {error}")))
+        })
+        .transpose()?;
+    let buffer = (config.buffer_capacity > 0).then(|| LogBuffer::new(config.buffer_capacity));
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(config.stderr.then(format::layer))
+        .with(file)
+        .with(buffer.clone())
+        .try_init()
+        .map_err(|error| LogError::new(error.to_string()))?;
+    if let Some(error) = rejected {
+        tracing::warn!(
+            "ignoring {}: {error}; using `{directives}`",
+            EnvFilter::DEFAULT_ENV
+        );
+    }
+    Ok(LogHandle {
+        reload,
+        filters: Arc::new(Mutex::new(Filters {
+            base: directives,
+            targets: BTreeMap::new(),
+        })),
+        buffer,
+    })
+}
+
+struct Filters {
+    base: String,
+    targets: BTreeMap<String, LevelFilter>,
+}
+
+impl Filters {
+    fn directives(&self) -> String {
+        let mut directives = self.base.clone();
+        for (target, level) in &self.targets {
+            if !directives.is_empty() {
+                directives.push(',');
+            }
+            write!(directives, "{target}={level}").expect("writing to a string");
+        }
+        directives
+    }
+}
+
+/// Runtime control over the subscriber installed by [`init`].
+#[derive(Clone)]
+pub struct LogHandle {
+    reload: reload::Handle<EnvFilter, Registry>,
+    filters: Arc<Mutex<Filters>>,
+    buffer: Option<LogBuffer>,
+}
+
+impl LogHandle {
+    /// Replaces every directive, including per-target overrides.
+    pub fn set_filter(&self, directives: &str) -> Result<(), LogError> {
+        let mut filters = lock(&self.filters);
+        self.apply(directives)?;
+        filters.base = directives.into();
+        filters.targets.clear();
+        Ok(())
+    }
+
+    /// Sets one target's level on top of the current filter; later calls
+    /// for the same target replace earlier ones.
+    pub fn set_target_level(&self, target: &str, level: LevelFilter) -> Result<(), LogError> {
+        let mut filters = lock(&self.filters);
+        let previous = filters.targets.insert(target.into(), level);
+        if let Err(error) = self.apply(&filters.directives()) {
+            match previous {
+                Some(previous) => filters.targets.insert(target.into(), previous),
+                None => filters.targets.remove(target),
+            };
+            return Err(error);
+        }
+        Ok(())
+    }
+
+    /// Removes a target override set by [`LogHandle::set_target_level`].
+    pub fn clear_target_level(&self, target: &str) -> Result<(), LogError> {
+        let mut filters = lock(&self.filters);
+        if filters.targets.remove(target).is_some() {
+            self.apply(&filters.directives())?;
+        }
+        Ok(())
+    }
+
+    /// Current filter directives in `RUST_LOG` syntax.
+    pub fn filter(&self) -> String {
+        lock(&self.filters).directives()
+    }
+
+    /// Recent events, when [`LogConfig::buffer_capacity`] is non-zero.
+    pub fn buffer(&self) -> Option<&LogBuffer> {
+        self.buffer.as_ref()
+    }
+
+    fn apply(&self, directives: &str) -> Result<(), LogError> {
+        self.reload
+            .reload(parse_filter(directives)?)
+            .map_err(|error| LogError::new(error.to_string()))
+    }
+}
+
+impl fmt::Debug for LogHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LogHandle")
+            .field("filter", &self.filter())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Parses `env` when set, otherwise `fallback`. A malformed `env` falls back
+/// too and is returned with its error so the caller can warn once logging
+/// is installed.
+fn initial_filter(
+    env: Option<String>,
+    fallback: String,
+) -> Result<(String, EnvFilter, Option<LogError>), LogError> {
+    let mut rejected = None;
+    if let Some(directives) = env {
+        match parse_filter(&directives) {
+            Ok(filter) => return Ok((directives, filter, None)),
+            Err(error) => rejected = Some(error),
+        }
+    }
+    let filter = parse_filter(&fallback)?;
+    Ok((fallback, filter, rejected))
+}
+
+fn parse_filter(directives: &str) -> Result<EnvFilter, LogError> {
+    EnvFilter::builder()
+        .parse(directives)
+        .map_err(|error| LogError::new(format!("invalid log filter: {error}")))
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// One event captured by a [`LogBuffer`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogRecord {
+    /// Position in the stream of captured events, starting at zero.
+    pub sequence: u64,
+    /// Event severity.
+    pub level: Level,
+    /// Module path or explicit target.
+    pub target: String,
+    /// The message followed by any other fields as `name=value`.
+    pub message: String,
+}
+
+struct Ring {
+    records: VecDeque<LogRecord>,
+    capacity: usize,
+    next: u64,
+}
+
+/// A bounded buffer of recent events, shared between the subscriber and
+/// any number of readers such as an in-game console.
+///
+/// It is a [`Layer`], so it can also be composed into a custom subscriber.
+#[derive(Clone)]
+pub struct LogBuffer(Arc<Mutex<Ring>>);
+
+impl LogBuffer {
+    /// Creates a buffer keeping the latest `capacity` events.
+    pub fn new(capacity: usize) -> Self {
+        Self(Arc::new(Mutex::new(Ring {
+            records: VecDeque::with_capacity(capacity),
+            capacity,
+            next: 0,
+        })))
+    }
+
+    /// Every retained event, oldest first.
+    pub fn records(&self) -> Vec<LogRecord> {
+        lock(&self.0).records.iter().cloned().collect()
+    }
+
+    /// Retained events with a sequence of at least `sequence`, so a reader
+    /// can poll for only what it has not shown yet.
+    pub fn since(&self, sequence: u64) -> Vec<LogRecord> {
+        let ring = lock(&self.0);
+        let skip = ring
+            .records
+            .partition_point(|record| record.sequence < sequence);
+        ring.records.iter().skip(skip).cloned().collect()
+    }
+
+    /// Sequence number the next event will receive.
+    pub fn next_sequence(&self) -> u64 {
+        lock(&self.0).next
+    }
+
+    /// Drops every retained event; sequence numbers keep counting.
+    pub fn clear(&self) {
+        lock(&self.0).records.clear();
+    }
+}
+
+impl fmt::Debug for LogBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ring = lock(&self.0);
+        f.debug_struct("LogBuffer")
+            .field("len", &ring.records.len())
+            .field("capacity", &ring.capacity)
+            .finish()
+    }
+}
+
+impl<S: Subscriber> Layer<S> for LogBuffer {
+    fn on_event(&self, event: &Event<'_>, _: Context<'_, S>) {
+        let mut message = MessageVisitor::default();
+        event.record(&mut message);
+        let mut ring = lock(&self.0);
+        if ring.capacity == 0 {
+            return;
+        }
+        if ring.records.len() == ring.capacity {
+            ring.records.pop_front();
+        }
+        let sequence = ring.next;
+        ring.next += 1;
+        ring.records.push_back(LogRecord {
+            sequence,
+            level: *event.metadata().level(),
+            target: event.metadata().target().into(),
+            message: message.finish(),
+        });
+    }
+}
+
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: String,
+}
+
+impl MessageVisitor {
+    fn finish(mut self) -> String {
+        if !self.fields.is_empty() {
+            if !self.message.is_empty() {
+                self.message.push(' ');
+            }
+            self.message.push_str(&self.fields);
+        }
+        self.message
+    }
+}
+
+impl Visit for MessageVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            self.record_debug(field, &value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            write!(self.message, "{value:?}").expect("writing to a string");
+        } else {
+            if !self.fields.is_empty() {
+                self.fields.push(' ');
+            }
+            write!(self.fields, "{}={value:?}", field.name()).expect("writing to a string");
+        }
+    }
+}
+
+struct Rolling {
+    config: RollingFile,
+    file: File,
+    written: u64,
+}
+
+impl Rolling {
+    fn open(config: RollingFile) -> io::Result<Self> {
+        fs::create_dir_all(&config.directory)?;
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(config.path(0))?;
+        let written = file.metadata()?.len();
+        Ok(Self {
+            config,
+            file,
+            written,
+        })
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        let kept = self.config.max_files.max(1);
+        // Windows refuses to rename over an existing file.
+        match fs::remove_file(self.config.path(kept - 1)) {
+            Err(error) if error.kind() != io::ErrorKind::NotFound => return Err(error),
+            _ => {}
+        }
+        for index in (1..kept).rev() {
+            match fs::rename(self.config.path(index - 1), self.config.path(index)) {
+                Err(error) if error.kind() != io::ErrorKind::NotFound => return Err(error),
+                _ => {}
+            }
+        }
+        self.file = File::create(self.config.path(0))?;
+        self.written = 0;
+        Ok(())
+    }
+}
+
+impl Write for Rolling {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.file.write(buf)?;
+        self.written += written as u64;
+        Ok(written)
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        // The formatter writes each event with one call, so rotating here
+        // never splits an event across files.
+        if self.written > 0 && self.written + buf.len() as u64 > self.config.max_bytes {
+            self.rotate()?;
+        }
+        self.file.write_all(buf)?;
+        self.written += buf.len() as u64;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+struct RollingWriter(Arc<Mutex<Rolling>>);
+
+impl<'a> MakeWriter<'a> for RollingWriter {
+    type Writer = RollingGuard<'a>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RollingGuard(lock(&self.0))
+    }
+}
+
+struct RollingGuard<'a>(MutexGuard<'a, Rolling>);
+
+impl Write for RollingGuard<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.0.write_all(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+/// Failure to install or reconfigure logging.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogError(String);
+
+impl LogError {
+    fn new(message: impl Into<String>) -> Self {
+        Self(message.into())
+    }
+}
+
+impl fmt::Display for LogError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(formatter)
+    }
+}
+
+impl Error for LogError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buffer_keeps_the_latest_events_with_fields() {
+        let buffer = LogBuffer::new(2);
+        let subscriber = tracing_subscriber::registry().with(buffer.clone());
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(target: "game", "first");
+            tracing::warn!(target: "game", frame = 7, "slow frame");
+            tracing::error!(target: "gpu", lost = true, "device lost");
+        });
+        let records = buffer.records();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].sequence, 1);
+        assert_eq!(records[0].message, "slow frame frame=7");
+        assert_eq!(records[1].level, Level::ERROR);
+        assert_eq!(records[1].target, "gpu");
+        assert_eq!(buffer.since(2), records[1..]);
+        assert_eq!(buffer.next_sequence(), 3);
+    }
+
+    #[test]
+    fn malformed_env_filters_fall_back_to_the_config() {
+        let (directives, _, rejected) =
+            initial_filter(Some("astrelis=loud".into()), "info".into()).unwrap();
+        assert_eq!(directives, "info");
+        assert!(rejected.unwrap().to_string().contains("invalid log filter"));
+
+        let (directives, _, rejected) =
+            initial_filter(Some("astrelis=debug".into()), "info".into()).unwrap();
+        assert_eq!(directives, "astrelis=debug");
+        assert!(rejected.is_none());
 
-    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| {
-        EnvFilter::new(
-            "info,\
-             astrelis_core=trace",
-        )
-    });
+        assert!(initial_filter(None, "astrelis=loud".into()).is_err());
+    }
 
-    fmt().with_env_filter(filter).try_init().ok();
+    #[test]
+    fn files_rotate_without_splitting_events() {
+        let directory = std::env::temp_dir().join(format!("astrelis-log-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        let config = RollingFile {
+            max_bytes: 10,
+            max_files: 2,
+            ..RollingFile::new(&directory, "test")
+        };
+        let writer = RollingWriter(Arc::new(Mutex::new(Rolling::open(config.clone()).unwrap())));
+        for line in ["one 1\n", "two 22\n", "three 3\n"] {
+            writer.make_writer().write_all(line.as_bytes()).unwrap();
+        }
+        assert_eq!(fs::read_to_string(config.path(0)).unwrap(), "three 3\n");
+        assert_eq!(fs::read_to_string(config.path(1)).unwrap(), "two 22\n");
+        assert!(!config.path(2).exists());
+        fs::remove_dir_all(&directory).unwrap();
+    }
 }
@@ -2,7 +2,11 @@
 //!
 //! This module re-exports commonly used types from [`glam`] and provides
 //! `#[repr(C)]` packed variants suitable for direct GPU buffer upload via
-//! [`bytemuck`].
+//! [`bytemuck`]. Easing curves and interpolation traits live in [`ease`],
+//! and deterministic fixed-point types for lockstep simulation in [`fixed`].
+
+pub mod ease;
+pub mod fixed;
 
 // Re-export glam types at module root for convenience.
 pub use glam::{
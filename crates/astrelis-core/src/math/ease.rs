@@ -0,0 +1,364 @@
+//! Easing curves and interpolation.
+//!
+//! An [`Ease`] reshapes a normalized progress value `t` in `[0, 1]` before it
+//! is fed to [`Lerp::lerp`] or [`Slerp::slerp`]. [`tween`] combines the two:
+//!
+//! ```
+//! use astrelis_core::math::Vec2;
+//! use astrelis_core::math::ease::{Curve, Ease, tween};
+//!
+//! let start = Vec2::ZERO;
+//! let end = Vec2::new(100.0, 0.0);
+//! let halfway = tween(start, end, 0.5, Ease::InOut(Curve::Cubic));
+//! assert_eq!(halfway, Vec2::new(50.0, 0.0));
+//! ```
+
+use std::f32::consts::{FRAC_PI_2, PI};
+
+use glam::{Quat, Vec2, Vec3, Vec4};
+
+use crate::{
+    color::Color,
+    geometry::{Point, Rect, Size},
+};
+
+/// Shape of an easing curve, applied through an [`Ease`] direction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Curve {
+    /// Quarter sine wave.
+    Sine,
+    /// `t²`.
+    Quad,
+    /// `t³`.
+    Cubic,
+    /// `t⁴`.
+    Quart,
+    /// `t⁵`.
+    Quint,
+    /// Exponential, `2^(10(t - 1))`.
+    Expo,
+    /// Quarter circle.
+    Circ,
+    /// Pulls back slightly before moving forward.
+    Back,
+    /// Damped spring oscillation.
+    Elastic,
+    /// Decaying bounces.
+    Bounce,
+}
+
+impl Curve {
+    /// The curve eased in, from which the other directions are mirrored.
+    fn ease_in(self, t: f32) -> f32 {
+        const BACK: f32 = 1.701_58;
+        match self {
+            Self::Sine => 1.0 - (t * FRAC_PI_2).cos(),
+            Self::Quad => t * t,
+            Self::Cubic => t * t * t,
+            Self::Quart => t.powi(4),
+            Self::Quint => t.powi(5),
+            Self::Expo if t <= 0.0 => 0.0,
+            Self::Expo => 2f32.powf(10.0 * (t - 1.0)),
+            Self::Circ => 1.0 - (1.0 - t * t).max(0.0).sqrt(),
+            Self::Back => t * t * ((BACK + 1.0) * t - BACK),
+            Self::Elastic if t <= 0.0 || t >= 1.0 => t,
+            Self::Elastic => {
+                -(2f32.powf(10.0 * (t - 1.0))) * ((t - 1.075) * (2.0 * PI) / 0.3).sin()
+            }
+            Self::Bounce => 1.0 - bounce_out(1.0 - t),
+        }
+    }
+}
+
+fn bounce_out(t: f32) -> f32 {
+    const N: f32 = 7.5625;
+    const D: f32 = 2.75;
+    if t < 1.0 / D {
+        N * t * t
+    } else if t < 2.0 / D {
+        let t = t - 1.5 / D;
+        N * t * t + 0.75
+    } else if t < 2.5 / D {
+        let t = t - 2.25 / D;
+        N * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D;
+        N * t * t + 0.984_375
+    }
+}
+
+/// An easing function mapping progress `t` in `[0, 1]` to eased progress.
+///
+/// Every ease maps 0 to 0 and 1 to 1. [`Curve::Back`] and
+/// [`Curve::Elastic`] overshoot the range in between.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Ease {
+    /// Constant speed.
+    #[default]
+    Linear,
+    /// Starts slowly and accelerates.
+    In(Curve),
+    /// Starts quickly and decelerates.
+    Out(Curve),
+    /// Accelerates through the first half and decelerates through the
+    /// second.
+    InOut(Curve),
+    /// A CSS-style cubic Bézier timing function.
+    CubicBezier(CubicBezier),
+}
+
+impl Ease {
+    /// Applies the ease to `t`, which is clamped to `[0, 1]`.
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Self::Linear => t,
+            Self::In(curve) => curve.ease_in(t),
+            Self::Out(curve) => 1.0 - curve.ease_in(1.0 - t),
+            Self::InOut(curve) if t < 0.5 => curve.ease_in(2.0 * t) / 2.0,
+            Self::InOut(curve) => 1.0 - curve.ease_in(2.0 - 2.0 * t) / 2.0,
+            Self::CubicBezier(bezier) => bezier.apply(t),
+        }
+    }
+}
+
+/// A cubic Bézier timing curve from `(0, 0)` to `(1, 1)`, as in CSS
+/// `cubic-bezier(x1, y1, x2, y2)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CubicBezier {
+    /// First control point, x clamped to `[0, 1]`.
+    pub p1: Vec2,
+    /// Second control point, x clamped to `[0, 1]`.
+    pub p2: Vec2,
+}
+
+impl CubicBezier {
+    /// CSS `ease`.
+    pub const EASE: Self = Self::new(0.25, 0.1, 0.25, 1.0);
+    /// CSS `ease-in`.
+    pub const EASE_IN: Self = Self::new(0.42, 0.0, 1.0, 1.0);
+    /// CSS `ease-out`.
+    pub const EASE_OUT: Self = Self::new(0.0, 0.0, 0.58, 1.0);
+    /// CSS `ease-in-out`.
+    pub const EASE_IN_OUT: Self = Self::new(0.42, 0.0, 0.58, 1.0);
+
+    /// Creates a curve from its two control points.
+    pub const fn new(x1: f32, y1: f32, x2: f32, y2: f32) -> Self {
+        Self {
+            p1: Vec2::new(x1, y1),
+            p2: Vec2::new(x2, y2),
+        }
+    }
+
+    /// Returns the curve's y at horizontal position `x` in `[0, 1]`.
+    pub fn apply(self, x: f32) -> f32 {
+        let x = x.clamp(0.0, 1.0);
+        let (x1, x2) = (self.p1.x.clamp(0.0, 1.0), self.p2.x.clamp(0.0, 1.0));
+        let mut s = x;
+        // Newton's method converges in a few steps for typical curves.
+        for _ in 0..8 {
+            let error = bezier(x1, x2, s) - x;
+            if error.abs() < 1e-6 {
+                return bezier(self.p1.y, self.p2.y, s);
+            }
+            let slope = bezier_slope(x1, x2, s);
+            if slope.abs() < 1e-6 {
+                break;
+            }
+            s -= error / slope;
+        }
+        // Fall back to bisection where the curve is nearly flat in x.
+        let (mut low, mut high) = (0.0, 1.0);
+        s = x;
+        for _ in 0..32 {
+            let value = bezier(x1, x2, s);
+            if (value - x).abs() < 1e-6 {
+                break;
+            }
+            if value < x {
+                low = s;
+            } else {
+                high = s;
+            }
+            s = (low + high) / 2.0;
+        }
+        bezier(self.p1.y, self.p2.y, s)
+    }
+}
+
+/// One axis of a cubic Bézier with endpoints 0 and 1.
+fn bezier(a: f32, b: f32, s: f32) -> f32 {
+    let inverse = 1.0 - s;
+    3.0 * inverse * inverse * s * a + 3.0 * inverse * s * s * b + s * s * s
+}
+
+fn bezier_slope(a: f32, b: f32, s: f32) -> f32 {
+    let inverse = 1.0 - s;
+    3.0 * inverse * inverse * a + 6.0 * inverse * s * (b - a) + 3.0 * s * s * (1.0 - b)
+}
+
+/// Linear interpolation.
+pub trait Lerp {
+    /// Interpolates from `self` at `t = 0` to `other` at `t = 1`; `t` is not
+    /// clamped, so overshooting eases extrapolate.
+    fn lerp(self, other: Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Lerp for Vec2 {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        Vec2::lerp(self, other, t)
+    }
+}
+
+impl Lerp for Vec3 {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        Vec3::lerp(self, other, t)
+    }
+}
+
+impl Lerp for Vec4 {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        Vec4::lerp(self, other, t)
+    }
+}
+
+/// Interpolates the linear channels; see [`Color::mix_oklab`] for
+/// perceptually even gradients.
+impl Lerp for Color {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        Color::new(
+            self.r.lerp(other.r, t),
+            self.g.lerp(other.g, t),
+            self.b.lerp(other.b, t),
+            self.a.lerp(other.a, t),
+        )
+    }
+}
+
+impl<S> Lerp for Point<S> {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        Point::new(self.x.lerp(other.x, t), self.y.lerp(other.y, t))
+    }
+}
+
+impl<S> Lerp for Size<S> {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        Size::new(
+            self.width.lerp(other.width, t),
+            self.height.lerp(other.height, t),
+        )
+    }
+}
+
+impl<S> Lerp for Rect<S> {
+    fn lerp(self, other: Self, t: f32) -> Self {
+        Rect::new(
+            self.origin.lerp(other.origin, t),
+            self.size.lerp(other.size, t),
+        )
+    }
+}
+
+/// Spherical interpolation along the shortest arc.
+pub trait Slerp {
+    /// Interpolates from `self` at `t = 0` to `other` at `t = 1` at constant
+    /// angular speed.
+    fn slerp(self, other: Self, t: f32) -> Self;
+}
+
+impl Slerp for Quat {
+    fn slerp(self, other: Self, t: f32) -> Self {
+        Quat::slerp(self, other, t)
+    }
+}
+
+/// Rotates between directions, interpolating length linearly. Opposite
+/// directions fall back to [`Lerp`].
+impl Slerp for Vec3 {
+    fn slerp(self, other: Self, t: f32) -> Self {
+        let (Some(from), Some(to)) = (self.try_normalize(), other.try_normalize()) else {
+            return Vec3::lerp(self, other, t);
+        };
+        if from.dot(to) < -0.9999 {
+            return Vec3::lerp(self, other, t);
+        }
+        let rotation = Quat::IDENTITY.slerp(Quat::from_rotation_arc(from, to), t);
+        rotation * from * self.length().lerp(other.length(), t)
+    }
+}
+
+/// Interpolates from `from` to `to` with progress `t` reshaped by `ease`.
+pub fn tween<T: Lerp>(from: T, to: T, t: f32, ease: Ease) -> T {
+    from.lerp(to, ease.apply(t))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CURVES: [Curve; 10] = [
+        Curve::Sine,
+        Curve::Quad,
+        Curve::Cubic,
+        Curve::Quart,
+        Curve::Quint,
+        Curve::Expo,
+        Curve::Circ,
+        Curve::Back,
+        Curve::Elastic,
+        Curve::Bounce,
+    ];
+
+    #[test]
+    fn eases_hit_their_endpoints_and_mirror() {
+        for curve in CURVES {
+            for ease in [Ease::In(curve), Ease::Out(curve), Ease::InOut(curve)] {
+                assert!(ease.apply(0.0).abs() < 1e-5, "{ease:?} at 0");
+                assert!((ease.apply(1.0) - 1.0).abs() < 1e-5, "{ease:?} at 1");
+            }
+            let t = 0.3;
+            assert!(
+                (Ease::Out(curve).apply(t) - (1.0 - Ease::In(curve).apply(1.0 - t))).abs() < 1e-6
+            );
+            assert!((Ease::InOut(curve).apply(0.5) - 0.5).abs() < 1e-5);
+        }
+        assert_eq!(Ease::In(Curve::Quad).apply(0.5), 0.25);
+        assert!(Ease::In(Curve::Back).apply(0.2) < 0.0, "back pulls back");
+        assert_eq!(Ease::Linear.apply(2.0), 1.0, "progress is clamped");
+    }
+
+    #[test]
+    fn cubic_beziers_match_css_reference_values() {
+        let linear = CubicBezier::new(0.0, 0.0, 1.0, 1.0);
+        assert!((linear.apply(0.37) - 0.37).abs() < 1e-4);
+        // Reference values from browser implementations of `ease`.
+        assert!((CubicBezier::EASE.apply(0.25) - 0.4085).abs() < 1e-3);
+        assert!((CubicBezier::EASE_IN_OUT.apply(0.5) - 0.5).abs() < 1e-4);
+        let steep = CubicBezier::new(1.0, 0.0, 0.0, 1.0);
+        assert!((steep.apply(0.5) - 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn lerp_and_slerp_interpolate() {
+        assert_eq!(tween(0.0, 10.0, 0.5, Ease::Linear), 5.0);
+        assert_eq!(
+            Color::BLACK.lerp(Color::WHITE, 0.25),
+            Color::new(0.25, 0.25, 0.25, 1.0)
+        );
+        let rect = Rect::<crate::geometry::Logical>::from_xywh(0.0, 0.0, 10.0, 10.0)
+            .lerp(Rect::from_xywh(10.0, 0.0, 20.0, 10.0), 0.5);
+        assert_eq!(rect, Rect::from_xywh(5.0, 0.0, 15.0, 10.0));
+
+        let halfway = Vec3::X.slerp(Vec3::Y * 3.0, 0.5);
+        assert!((halfway.length() - 2.0).abs() < 1e-5);
+        assert!((halfway.x - halfway.y).abs() < 1e-5);
+        let third = Quat::IDENTITY.slerp(Quat::from_rotation_z(PI / 1.5), 0.5);
+        assert!(third.abs_diff_eq(Quat::from_rotation_z(PI / 3.0), 1e-5));
+    }
+}
@@ -0,0 +1,592 @@
+//! Fixed-point numbers for bit-exact simulation.
+//!
+//! Floating-point results can differ between compilers, instruction sets,
+//! and optimization levels, which desynchronizes lockstep multiplayer and
+//! replays. [`Fixed`] stores a signed Q32.32 value in an `i64`, so every
+//! operation, including [`Fixed::sqrt`] and [`Fixed::sin`], is integer
+//! arithmetic that produces identical bits on every platform.
+//!
+//! Simulate in [`Fixed`], [`FixedVec2`], and [`FixedVec3`], then convert to
+//! `glam` types only for rendering:
+//!
+//! ```
+//! use astrelis_core::math::Vec2;
+//! use astrelis_core::math::fixed::{Fixed, FixedVec2};
+//!
+//! let dt = Fixed::from_ratio(1, 60);
+//! let velocity = FixedVec2::new(Fixed::from_int(3), Fixed::ZERO);
+//! let mut position = FixedVec2::ZERO;
+//! for _ in 0..60 {
+//!     position += velocity * dt;
+//! }
+//! assert!((Vec2::from(position) - Vec2::new(3.0, 0.0)).length() < 1e-6);
+//! ```
+
+use std::{
+    fmt,
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+use glam::{Vec2, Vec3};
+
+const FRACTION_BITS: u32 = 32;
+
+/// A signed Q32.32 fixed-point number.
+///
+/// Arithmetic wraps on overflow like release-mode integers; use the
+/// `saturating_*` and `checked_*` methods where the range matters.
+/// Multiplication and division truncate toward negative infinity.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Fixed(i64);
+
+impl Fixed {
+    /// Zero.
+    pub const ZERO: Self = Self(0);
+    /// One.
+    pub const ONE: Self = Self(1 << FRACTION_BITS);
+    /// One half.
+    pub const HALF: Self = Self(1 << (FRACTION_BITS - 1));
+    /// Smallest positive value, 2⁻³².
+    pub const EPSILON: Self = Self(1);
+    /// Largest value, just under 2³¹.
+    pub const MAX: Self = Self(i64::MAX);
+    /// Smallest value, -2³¹.
+    pub const MIN: Self = Self(i64::MIN);
+    /// π, rounded to the nearest representable value.
+    pub const PI: Self = Self(0x3_243F_6A89);
+    /// π / 2.
+    pub const FRAC_PI_2: Self = Self(0x1_921F_B544);
+    /// 2π.
+    pub const TAU: Self = Self(0x6_487E_D511);
+
+    /// Creates a value from its raw Q32.32 bits.
+    #[inline]
+    pub const fn from_bits(bits: i64) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw Q32.32 bits, for hashing or network encoding.
+    #[inline]
+    pub const fn to_bits(self) -> i64 {
+        self.0
+    }
+
+    /// Creates an integer value.
+    #[inline]
+    pub const fn from_int(value: i32) -> Self {
+        Self((value as i64) << FRACTION_BITS)
+    }
+
+    /// Creates `numerator / denominator`, rounded toward negative infinity
+    /// exactly like `Fixed::from_int(numerator) / Fixed::from_int(denominator)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `denominator` is zero.
+    #[inline]
+    pub const fn from_ratio(numerator: i32, denominator: i32) -> Self {
+        Self(floor_div(
+            ((numerator as i64) << FRACTION_BITS) as i128,
+            denominator as i128,
+        ) as i64)
+    }
+
+    /// Converts from `f32`, rounding to the nearest value and saturating
+    /// out-of-range values. NaN becomes zero.
+    ///
+    /// Use only for authored constants or initial state; converting
+    /// computed floats reintroduces platform differences.
+    #[inline]
+    pub fn from_f32(value: f32) -> Self {
+        Self::from_f64(value as f64)
+    }
+
+    /// Converts from `f64`; see [`Fixed::from_f32`].
+    #[inline]
+    pub fn from_f64(value: f64) -> Self {
+        Self((value * (1u64 << FRACTION_BITS) as f64).round() as i64)
+    }
+
+    /// Converts to the nearest `f32`.
+    #[inline]
+    pub fn to_f32(self) -> f32 {
+        self.to_f64() as f32
+    }
+
+    /// Converts to `f64`, exactly for magnitudes below 2²¹.
+    #[inline]
+    pub fn to_f64(self) -> f64 {
+        self.0 as f64 / (1u64 << FRACTION_BITS) as f64
+    }
+
+    /// Returns the largest integer not above the value, as an `i32`.
+    #[inline]
+    pub const fn to_int(self) -> i32 {
+        (self.0 >> FRACTION_BITS) as i32
+    }
+
+    /// Absolute value, wrapping for [`Fixed::MIN`].
+    #[inline]
+    pub const fn abs(self) -> Self {
+        Self(self.0.wrapping_abs())
+    }
+
+    /// Largest integer value not above the value.
+    #[inline]
+    pub const fn floor(self) -> Self {
+        Self(self.0 & !((1 << FRACTION_BITS) - 1))
+    }
+
+    /// Smallest integer value not below the value.
+    #[inline]
+    pub const fn ceil(self) -> Self {
+        Self(self.0.wrapping_add((1 << FRACTION_BITS) - 1)).floor()
+    }
+
+    /// Nearest integer value, rounding halves up.
+    #[inline]
+    pub const fn round(self) -> Self {
+        Self(self.0.wrapping_add(Self::HALF.0)).floor()
+    }
+
+    /// Fractional part, always in `[0, 1)`.
+    #[inline]
+    pub const fn fract(self) -> Self {
+        Self(self.0 & ((1 << FRACTION_BITS) - 1))
+    }
+
+    /// Sum, clamped to the representable range.
+    #[inline]
+    pub const fn saturating_add(self, other: Self) -> Self {
+        Self(self.0.saturating_add(other.0))
+    }
+
+    /// Difference, clamped to the representable range.
+    #[inline]
+    pub const fn saturating_sub(self, other: Self) -> Self {
+        Self(self.0.saturating_sub(other.0))
+    }
+
+    /// Product, clamped to the representable range.
+    #[inline]
+    pub const fn saturating_mul(self, other: Self) -> Self {
+        let product = (self.0 as i128 * other.0 as i128) >> FRACTION_BITS;
+        Self(if product > i64::MAX as i128 {
+            i64::MAX
+        } else if product < i64::MIN as i128 {
+            i64::MIN
+        } else {
+            product as i64
+        })
+    }
+
+    /// Quotient, or `None` when dividing by zero or overflowing.
+    #[inline]
+    pub const fn checked_div(self, other: Self) -> Option<Self> {
+        if other.0 == 0 {
+            return None;
+        }
+        let quotient = quotient(self, other);
+        if quotient > i64::MAX as i128 || quotient < i64::MIN as i128 {
+            None
+        } else {
+            Some(Self(quotient as i64))
+        }
+    }
+
+    /// Square root, truncated; zero for negative values.
+    pub const fn sqrt(self) -> Self {
+        if self.0 <= 0 {
+            return Self::ZERO;
+        }
+        Self(((self.0 as u128) << FRACTION_BITS).isqrt() as i64)
+    }
+
+    /// Sine of an angle in radians, accurate to about 10⁻⁸.
+    pub fn sin(self) -> Self {
+        // Reduce to [-π, π], then fold into [-π/2, π/2] where the Taylor
+        // series converges quickly.
+        let mut x = Self(self.0.rem_euclid(Self::TAU.0));
+        if x > Self::PI {
+            x -= Self::TAU;
+        }
+        if x > Self::FRAC_PI_2 {
+            x = Self::PI - x;
+        } else if x < -Self::FRAC_PI_2 {
+            x = -Self::PI - x;
+        }
+        let square = x * x;
+        let mut term = x;
+        let mut sum = x;
+        for n in 1..=6 {
+            term = -(term * square) / Self::from_int((2 * n) * (2 * n + 1));
+            sum += term;
+        }
+        sum
+    }
+
+    /// Cosine of an angle in radians; see [`Fixed::sin`].
+    pub fn cos(self) -> Self {
+        (self + Self::FRAC_PI_2).sin()
+    }
+
+    /// The smaller value.
+    #[inline]
+    pub fn min(self, other: Self) -> Self {
+        Ord::min(self, other)
+    }
+
+    /// The larger value.
+    #[inline]
+    pub fn max(self, other: Self) -> Self {
+        Ord::max(self, other)
+    }
+
+    /// The value clamped to `[min, max]`.
+    #[inline]
+    pub fn clamp(self, min: Self, max: Self) -> Self {
+        Ord::clamp(self, min, max)
+    }
+}
+
+impl Add for Fixed {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, other: Self) -> Self {
+        Self(self.0.wrapping_add(other.0))
+    }
+}
+
+impl Sub for Fixed {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, other: Self) -> Self {
+        Self(self.0.wrapping_sub(other.0))
+    }
+}
+
+impl Mul for Fixed {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, other: Self) -> Self {
+        Self(((self.0 as i128 * other.0 as i128) >> FRACTION_BITS) as i64)
+    }
+}
+
+impl Div for Fixed {
+    type Output = Self;
+
+    /// # Panics
+    ///
+    /// Panics when dividing by zero.
+    #[inline]
+    fn div(self, other: Self) -> Self {
+        Self(quotient(self, other) as i64)
+    }
+}
+
+impl Neg for Fixed {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        Self(self.0.wrapping_neg())
+    }
+}
+
+impl AddAssign for Fixed {
+    #[inline]
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Fixed {
+    #[inline]
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl MulAssign for Fixed {
+    #[inline]
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
+
+impl DivAssign for Fixed {
+    #[inline]
+    fn div_assign(&mut self, other: Self) {
+        *self = *self / other;
+    }
+}
+
+impl From<i32> for Fixed {
+    fn from(value: i32) -> Self {
+        Self::from_int(value)
+    }
+}
+
+impl From<Fixed> for f32 {
+    fn from(value: Fixed) -> Self {
+        value.to_f32()
+    }
+}
+
+impl fmt::Debug for Fixed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Fixed({})", self.to_f64())
+    }
+}
+
+impl fmt::Display for Fixed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.to_f64(), f)
+    }
+}
+
+/// Full-precision `dividend / divisor`, rounded toward negative infinity.
+const fn quotient(dividend: Fixed, divisor: Fixed) -> i128 {
+    floor_div((dividend.0 as i128) << FRACTION_BITS, divisor.0 as i128)
+}
+
+/// `dividend / divisor` rounded toward negative infinity for either sign.
+///
+/// Plain `/` truncates toward zero and `div_euclid` rounds toward negative
+/// infinity only for positive divisors, so neither alone is a floor.
+const fn floor_div(dividend: i128, divisor: i128) -> i128 {
+    let quotient = dividend / divisor;
+    if dividend % divisor != 0 && (dividend < 0) != (divisor < 0) {
+        quotient - 1
+    } else {
+        quotient
+    }
+}
+
+macro_rules! fixed_vector {
+    ($(#[$meta:meta])* $name:ident, $glam:ident, $($field:ident),+) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        pub struct $name {
+            $(
+                #[doc = concat!("The ", stringify!($field), " component.")]
+                pub $field: Fixed,
+            )+
+        }
+
+        impl $name {
+            /// All components zero.
+            pub const ZERO: Self = Self { $($field: Fixed::ZERO),+ };
+
+            /// Creates a vector.
+            #[inline]
+            pub const fn new($($field: Fixed),+) -> Self {
+                Self { $($field),+ }
+            }
+
+            /// Dot product.
+            #[inline]
+            pub fn dot(self, other: Self) -> Fixed {
+                Fixed::ZERO $(+ self.$field * other.$field)+
+            }
+
+            /// Squared length, exact up to truncation.
+            #[inline]
+            pub fn length_squared(self) -> Fixed {
+                self.dot(self)
+            }
+
+            /// Length.
+            #[inline]
+            pub fn length(self) -> Fixed {
+                self.length_squared().sqrt()
+            }
+
+            /// The vector scaled to unit length, or `None` when it is zero.
+            pub fn try_normalize(self) -> Option<Self> {
+                let length = self.length();
+                (length != Fixed::ZERO).then(|| Self { $($field: self.$field / length),+ })
+            }
+
+            /// Converts from a `glam` vector; see [`Fixed::from_f32`].
+            #[inline]
+            pub fn from_glam(value: $glam) -> Self {
+                Self { $($field: Fixed::from_f32(value.$field)),+ }
+            }
+        }
+
+        impl Add for $name {
+            type Output = Self;
+
+            #[inline]
+            fn add(self, other: Self) -> Self {
+                Self { $($field: self.$field + other.$field),+ }
+            }
+        }
+
+        impl Sub for $name {
+            type Output = Self;
+
+            #[inline]
+            fn sub(self, other: Self) -> Self {
+                Self { $($field: self.$field - other.$field),+ }
+            }
+        }
+
+        impl Mul<Fixed> for $name {
+            type Output = Self;
+
+            #[inline]
+            fn mul(self, scale: Fixed) -> Self {
+                Self { $($field: self.$field * scale),+ }
+            }
+        }
+
+        impl Neg for $name {
+            type Output = Self;
+
+            #[inline]
+            fn neg(self) -> Self {
+                Self { $($field: -self.$field),+ }
+            }
+        }
+
+        impl AddAssign for $name {
+            #[inline]
+            fn add_assign(&mut self, other: Self) {
+                *self = *self + other;
+            }
+        }
+
+        impl SubAssign for $name {
+            #[inline]
+            fn sub_assign(&mut self, other: Self) {
+                *self = *self - other;
+            }
+        }
+
+        impl From<$name> for $glam {
+            fn from(value: $name) -> Self {
+                $glam::new($(value.$field.to_f32()),+)
+            }
+        }
+    };
+}
+
+fixed_vector!(
+    /// A 2D vector of [`Fixed`] components.
+    FixedVec2,
+    Vec2,
+    x,
+    y
+);
+
+fixed_vector!(
+    /// A 3D vector of [`Fixed`] components.
+    FixedVec3,
+    Vec3,
+    x,
+    y,
+    z
+);
+
+impl FixedVec3 {
+    /// Cross product.
+    #[inline]
+    pub fn cross(self, other: Self) -> Self {
+        Self::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic_is_exact_and_rounds_down() {
+        let third = Fixed::from_ratio(1, 3);
+        assert_eq!(third * Fixed::from_int(3), Fixed::ONE - Fixed::EPSILON);
+        assert_eq!(
+            Fixed::from_int(7) / Fixed::from_int(2),
+            Fixed::from_f32(3.5)
+        );
+        assert_eq!(Fixed::from_f32(-1.5).floor(), Fixed::from_int(-2));
+        assert_eq!(Fixed::from_f32(-1.5).ceil(), Fixed::from_int(-1));
+        assert_eq!(Fixed::from_f32(-1.5).round(), Fixed::from_int(-1));
+        assert_eq!(Fixed::from_f32(-1.25).fract(), Fixed::from_f32(0.75));
+        assert_eq!(Fixed::from_f32(-1.25).to_int(), -2);
+        assert_eq!(Fixed::from_int(16).sqrt(), Fixed::from_int(4));
+        assert_eq!(Fixed::MAX.saturating_mul(Fixed::from_int(2)), Fixed::MAX);
+        assert_eq!(Fixed::ONE.checked_div(Fixed::ZERO), None);
+        assert!((Fixed::PI.to_f64() - std::f64::consts::PI).abs() < 1e-9);
+        assert!((Fixed::TAU.to_f64() - std::f64::consts::TAU).abs() < 1e-9);
+    }
+
+    #[test]
+    fn division_floors_for_every_sign() {
+        for (numerator, denominator) in [(1, 3), (-1, 3), (1, -3), (-1, -3), (-7, 2), (7, -2)] {
+            let ratio = Fixed::from_ratio(numerator, denominator);
+            let divided = Fixed::from_int(numerator) / Fixed::from_int(denominator);
+            assert_eq!(ratio, divided, "{numerator}/{denominator}");
+            let exact = numerator as f64 / denominator as f64;
+            assert!(ratio.to_f64() <= exact, "{numerator}/{denominator} floors");
+            assert!(exact - ratio.to_f64() < Fixed::EPSILON.to_f64());
+        }
+        assert_eq!(
+            Fixed::from_ratio(-1, 3),
+            -Fixed::from_ratio(1, 3) - Fixed::EPSILON
+        );
+        assert_eq!(Fixed::from_ratio(1, -3), Fixed::from_ratio(-1, 3));
+        assert_eq!(
+            Fixed::from_int(-7) / Fixed::from_int(-2),
+            Fixed::from_f32(3.5)
+        );
+        assert_eq!(
+            Fixed::EPSILON.checked_div(Fixed::from_int(-2)),
+            Some(-Fixed::EPSILON)
+        );
+    }
+
+    #[test]
+    fn trig_matches_floating_point() {
+        for step in -40..=40 {
+            let angle = step as f64 * 0.37;
+            let fixed = Fixed::from_f64(angle);
+            assert!(
+                (fixed.sin().to_f64() - angle.sin()).abs() < 1e-7,
+                "sin {angle}"
+            );
+            assert!(
+                (fixed.cos().to_f64() - angle.cos()).abs() < 1e-7,
+                "cos {angle}"
+            );
+        }
+    }
+
+    #[test]
+    fn vectors_convert_and_normalize() {
+        let v = FixedVec3::from_glam(Vec3::new(3.0, 4.0, 0.0));
+        assert_eq!(v.length(), Fixed::from_int(5));
+        let unit = v.try_normalize().unwrap();
+        assert_eq!(Vec3::from(unit), Vec3::new(0.6, 0.8, 0.0));
+        assert_eq!(
+            FixedVec3::new(Fixed::ONE, Fixed::ZERO, Fixed::ZERO).cross(FixedVec3::new(
+                Fixed::ZERO,
+                Fixed::ONE,
+                Fixed::ZERO
+            )),
+            FixedVec3::new(Fixed::ZERO, Fixed::ZERO, Fixed::ONE)
+        );
+        assert_eq!(FixedVec2::ZERO.try_normalize(), None);
+    }
+}
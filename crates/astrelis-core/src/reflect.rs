@@ -0,0 +1,246 @@
+//! Runtime field access for inspectors and editors.
+//!
+//! [`Reflect`] exposes a struct's fields by name as dynamically typed
+//! [`Value`]s, so tooling can list and edit components and widget styles
+//! without type-specific code. Implement it with [`reflect_struct!`](crate::reflect_struct).
+//!
+//! # Example
+//!
+//! ```
+//! use astrelis_core::reflect::{Reflect, Value};
+//!
+//! #[derive(Default)]
+//! struct Light {
+//!     intensity: f32,
+//!     enabled: bool,
+//!     label: Option<String>,
+//! }
+//!
+//! astrelis_core::reflect_struct!(Light { intensity, enabled, label });
+//!
+//! let mut light = Light::default();
+//! light.set_field("intensity", Value::Float(2.5)).unwrap();
+//! assert_eq!(light.field("intensity"), Some(Value::Float(2.5)));
+//! assert_eq!(light.field("label"), Some(Value::None));
+//! assert!(light.set_field("enabled", Value::Float(1.0)).is_err());
+//! ```
+
+use std::{error::Error, fmt};
+
+use crate::{
+    color::Color,
+    math::{Vec2, Vec3},
+};
+
+/// A dynamically typed field value.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    /// An unset optional field.
+    None,
+    /// A boolean.
+    Bool(bool),
+    /// Any integer, widened to 64 bits.
+    Int(i64),
+    /// Any float, widened to 64 bits.
+    Float(f64),
+    /// Owned text.
+    Text(String),
+    /// A linear RGBA color.
+    Color(Color),
+    /// A two-component vector.
+    Vec2(Vec2),
+    /// A three-component vector.
+    Vec3(Vec3),
+}
+
+/// A field type that converts to and from [`Value`].
+pub trait ReflectValue: Sized {
+    /// Converts the field to a dynamic value.
+    fn to_value(&self) -> Value;
+
+    /// Converts a dynamic value back, or `None` when the variant or range
+    /// does not fit.
+    fn from_value(value: Value) -> Option<Self>;
+}
+
+/// A struct whose fields can be read and written by name.
+pub trait Reflect {
+    /// Name of the concrete type.
+    fn type_name(&self) -> &'static str;
+
+    /// Field names in declaration order.
+    fn field_names(&self) -> &'static [&'static str];
+
+    /// Reads a field, or `None` when no field has that name.
+    fn field(&self, name: &str) -> Option<Value>;
+
+    /// Writes a field, leaving it unchanged on error.
+    fn set_field(&mut self, name: &str, value: Value) -> Result<(), ReflectError>;
+
+    /// Every field name with its current value.
+    fn fields(&self) -> Vec<(&'static str, Value)> {
+        self.field_names()
+            .iter()
+            .filter_map(|name| Some((*name, self.field(name)?)))
+            .collect()
+    }
+}
+
+/// Implements [`Reflect`] for a struct whose listed fields implement
+/// [`ReflectValue`].
+///
+/// Unlisted fields stay hidden from tooling.
+#[macro_export]
+macro_rules! reflect_struct {
+    ($type:ty { $($field:ident),* $(,)? }) => {
+        impl $crate::reflect::Reflect for $type {
+            fn type_name(&self) -> &'static str {
+                ::std::any::type_name::<Self>()
+            }
+
+            fn field_names(&self) -> &'static [&'static str] {
+                &[$(stringify!($field)),*]
+            }
+
+            fn field(&self, name: &str) -> Option<$crate::reflect::Value> {
+                match name {
+                    $(stringify!($field) => Some($crate::reflect::ReflectValue::to_value(&self.$field)),)*
+                    _ => None,
+                }
+            }
+
+            fn set_field(
+                &mut self,
+                name: &str,
+                value: $crate::reflect::Value,
+            ) -> Result<(), $crate::reflect::ReflectError> {
+                match name {
+                    $(stringify!($field) => {
+                        self.$field = $crate::reflect::ReflectValue::from_value(value.clone())
+                            .ok_or_else(|| $crate::reflect::ReflectError::mismatch(
+                                ::std::any::type_name::<Self>(),
+                                name,
+                                &value,
+                            ))?;
+                        Ok(())
+                    })*
+                    _ => Err($crate::reflect::ReflectError::unknown(
+                        ::std::any::type_name::<Self>(),
+                        name,
+                    )),
+                }
+            }
+        }
+    };
+}
+
+/// A field that does not exist or cannot hold a value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReflectError(String);
+
+impl ReflectError {
+    #[doc(hidden)]
+    pub fn unknown(type_name: &str, field: &str) -> Self {
+        Self(format!("{type_name} has no field `{field}`"))
+    }
+
+    #[doc(hidden)]
+    pub fn mismatch(type_name: &str, field: &str, value: &Value) -> Self {
+        Self(format!(
+            "{value:?} cannot be assigned to {type_name}::{field}"
+        ))
+    }
+}
+
+impl fmt::Display for ReflectError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(formatter)
+    }
+}
+
+impl Error for ReflectError {}
+
+impl ReflectValue for bool {
+    fn to_value(&self) -> Value {
+        Value::Bool(*self)
+    }
+
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::Bool(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+macro_rules! reflect_int {
+    ($($type:ty),+) => {$(
+        impl ReflectValue for $type {
+            fn to_value(&self) -> Value {
+                Value::Int(*self as i64)
+            }
+
+            fn from_value(value: Value) -> Option<Self> {
+                match value {
+                    Value::Int(value) => value.try_into().ok(),
+                    _ => None,
+                }
+            }
+        }
+    )+};
+}
+
+reflect_int!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+macro_rules! reflect_float {
+    ($($type:ty),+) => {$(
+        impl ReflectValue for $type {
+            fn to_value(&self) -> Value {
+                Value::Float(*self as f64)
+            }
+
+            /// Integers are accepted, since editors often produce them.
+            fn from_value(value: Value) -> Option<Self> {
+                match value {
+                    Value::Float(value) => Some(value as $type),
+                    Value::Int(value) => Some(value as $type),
+                    _ => None,
+                }
+            }
+        }
+    )+};
+}
+
+reflect_float!(f32, f64);
+
+macro_rules! reflect_variant {
+    ($($type:ty => $variant:ident),+) => {$(
+        impl ReflectValue for $type {
+            fn to_value(&self) -> Value {
+                Value::$variant(self.clone())
+            }
+
+            fn from_value(value: Value) -> Option<Self> {
+                match value {
+                    Value::$variant(value) => Some(value),
+                    _ => None,
+                }
+            }
+        }
+    )+};
+}
+
+reflect_variant!(String => Text, Color => Color, Vec2 => Vec2, Vec3 => Vec3);
+
+impl<T: ReflectValue> ReflectValue for Option<T> {
+    fn to_value(&self) -> Value {
+        self.as_ref().map_or(Value::None, T::to_value)
+    }
+
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::None => Some(None),
+            value => T::from_value(value).map(Some),
+        }
+    }
+}
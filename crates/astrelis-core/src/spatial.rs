@@ -0,0 +1,972 @@
+//! Spatial indexes for rectangle queries and ray casts.
+//!
+//! Three containers share one query vocabulary over [`Rect`] bounds:
+//!
+//! - [`Grid2D`] buckets items into uniform cells. It suits many similarly
+//!   sized items that move every frame, such as a physics broadphase.
+//! - [`QuadTree`] subdivides a fixed region where items cluster. It suits
+//!   uneven, mostly static sets such as chart points.
+//! - [`Bvh`] is built once from a complete item set and answers queries with
+//!   the fewest tests. It suits sets rebuilt wholesale, such as laid-out UI.
+//!
+//! Queries return every item whose bounds touch the query area, edges
+//! included; callers refine hits against exact shapes.
+//!
+//! # Example
+//!
+//! ```
+//! use astrelis_core::geometry::{Logical, Point, Ray, Rect};
+//! use astrelis_core::math::Vec2;
+//! use astrelis_core::spatial::Grid2D;
+//!
+//! let mut grid = Grid2D::<&str, Logical>::new(64.0);
+//! let button = grid.insert(Rect::from_xywh(10.0, 10.0, 80.0, 20.0), "button");
+//! assert_eq!(grid.query_point(Point::new(50.0, 20.0)), [(button, &"button")]);
+//!
+//! let ray = Ray::new(Point::new(0.0, 20.0), Vec2::X).unwrap();
+//! let hit = grid.raycast(ray, 100.0).unwrap();
+//! assert_eq!((hit.key, hit.distance), (button, 10.0));
+//! ```
+
+use std::collections::HashMap;
+
+use crate::{
+    alloc::{Handle, HandleMap},
+    geometry::{Logical, Point, Ray, Rect, Size},
+};
+
+/// Stable key of an item in a [`Grid2D`] or [`QuadTree`].
+///
+/// A key stops resolving once its item is removed, even if the slot is
+/// reused by a later insertion.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SpatialKey {
+    index: u32,
+    generation: u32,
+}
+
+impl SpatialKey {
+    fn handle<T>(self) -> Handle<T> {
+        Handle::from_raw_parts(self.index, self.generation)
+    }
+}
+
+/// The nearest item hit by a ray.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RayHit<K = SpatialKey> {
+    /// Key of the hit item; an item index for a [`Bvh`].
+    pub key: K,
+    /// Distance from the ray origin to the item's bounds, zero when the
+    /// origin is inside them.
+    pub distance: f32,
+}
+
+struct Item<T, S> {
+    bounds: Rect<S>,
+    value: T,
+    /// Quad-tree node holding the item; unused by grids.
+    node: u32,
+}
+
+/// Item storage shared by the dynamic containers, keyed by the untyped
+/// [`SpatialKey`] so keys do not name the private item type.
+struct Slots<T, S>(HandleMap<Item<T, S>>);
+
+impl<T, S> Slots<T, S> {
+    const fn new() -> Self {
+        Self(HandleMap::new())
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn insert(&mut self, item: Item<T, S>) -> SpatialKey {
+        let handle = self.0.insert(item);
+        SpatialKey {
+            index: handle.index(),
+            generation: handle.generation(),
+        }
+    }
+
+    fn get(&self, key: SpatialKey) -> Option<&Item<T, S>> {
+        self.0.get(key.handle())
+    }
+
+    fn get_mut(&mut self, key: SpatialKey) -> Option<&mut Item<T, S>> {
+        self.0.get_mut(key.handle())
+    }
+
+    fn remove(&mut self, key: SpatialKey) -> Option<Item<T, S>> {
+        self.0.remove(key.handle())
+    }
+
+    /// The live item at `index`, which containers only store while live.
+    fn item(&self, index: u32) -> &Item<T, S> {
+        self.get(self.key(index)).expect("indexed items are live")
+    }
+
+    fn item_mut(&mut self, index: u32) -> Option<&mut Item<T, S>> {
+        let handle = self.0.handle_at(index as usize)?;
+        self.0.get_mut(handle)
+    }
+
+    fn key(&self, index: u32) -> SpatialKey {
+        let handle = self
+            .0
+            .handle_at(index as usize)
+            .expect("indexed items are live");
+        SpatialKey {
+            index,
+            generation: handle.generation(),
+        }
+    }
+
+    fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
+/// Most cells one item is listed in; larger items are kept aside and tested
+/// by every query instead.
+const MAX_ITEM_CELLS: i64 = 1024;
+
+type CellRange = ((i32, i32), (i32, i32));
+
+/// Items bucketed into a uniform grid of square cells.
+///
+/// Each item is listed in every cell its bounds overlap, so cells should be
+/// a little larger than typical items. Cells are allocated only where items
+/// exist, and the grid is unbounded. Items spanning more than a thousand or
+/// so cells are not bucketed at all; they are tested against every query.
+pub struct Grid2D<T, S = Logical> {
+    cell_size: f32,
+    cells: HashMap<(i32, i32), Vec<u32>>,
+    /// Cells that have held an item since the last clear. It never shrinks,
+    /// so it only bounds how far a ray walk can go.
+    extent: Option<CellRange>,
+    oversized: Vec<u32>,
+    slots: Slots<T, S>,
+}
+
+impl<T, S: Copy> Grid2D<T, S> {
+    /// Creates an empty grid.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cell_size` is not finite and positive.
+    pub fn new(cell_size: f32) -> Self {
+        assert!(
+            cell_size.is_finite() && cell_size > 0.0,
+            "grid cell size must be finite and positive"
+        );
+        Self {
+            cell_size,
+            cells: HashMap::new(),
+            extent: None,
+            oversized: Vec::new(),
+            slots: Slots::new(),
+        }
+    }
+
+    /// Side length of each cell.
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    /// Number of items.
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Returns whether the grid holds no items.
+    pub fn is_empty(&self) -> bool {
+        self.slots.len() == 0
+    }
+
+    /// Adds an item and returns its key.
+    pub fn insert(&mut self, bounds: Rect<S>, value: T) -> SpatialKey {
+        let key = self.slots.insert(Item {
+            bounds,
+            value,
+            node: 0,
+        });
+        self.link(key.index, bounds);
+        key
+    }
+
+    /// Removes an item, returning its value.
+    pub fn remove(&mut self, key: SpatialKey) -> Option<T> {
+        let item = self.slots.remove(key)?;
+        self.unlink(key.index, item.bounds);
+        Some(item.value)
+    }
+
+    /// Moves an item. Returns whether `key` was live.
+    pub fn set_bounds(&mut self, key: SpatialKey, bounds: Rect<S>) -> bool {
+        let Some(item) = self.slots.get_mut(key) else {
+            return false;
+        };
+        let previous = std::mem::replace(&mut item.bounds, bounds);
+        if self.cell_range(previous) != self.cell_range(bounds) {
+            self.unlink(key.index, previous);
+            self.link(key.index, bounds);
+        }
+        true
+    }
+
+    /// An item's bounds and value.
+    pub fn get(&self, key: SpatialKey) -> Option<(Rect<S>, &T)> {
+        self.slots.get(key).map(|item| (item.bounds, &item.value))
+    }
+
+    /// An item's value, mutably.
+    pub fn get_mut(&mut self, key: SpatialKey) -> Option<&mut T> {
+        self.slots.get_mut(key).map(|item| &mut item.value)
+    }
+
+    /// Removes every item.
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.extent = None;
+        self.oversized.clear();
+        self.slots.clear();
+    }
+
+    /// Items whose bounds touch `area`, each listed once.
+    ///
+    /// An area covering more cells than the grid has allocated walks the
+    /// allocated cells instead, so huge areas stay cheap.
+    pub fn query(&self, area: Rect<S>) -> Vec<(SpatialKey, &T)> {
+        let range = self.cell_range(area);
+        let touches = |index: &&u32| self.slots.item(**index).bounds.intersects(&area);
+        let mut indices = self
+            .oversized
+            .iter()
+            .filter(touches)
+            .copied()
+            .collect::<Vec<_>>();
+        if cell_count(range) > self.cells.len() as i64 {
+            for (_, cell) in self
+                .cells
+                .iter()
+                .filter(|(cell, _)| contains(range, **cell))
+            {
+                indices.extend(cell.iter().filter(touches));
+            }
+        } else {
+            let ((x0, y0), (x1, y1)) = range;
+            for y in y0..=y1 {
+                for x in x0..=x1 {
+                    if let Some(cell) = self.cells.get(&(x, y)) {
+                        indices.extend(cell.iter().filter(touches));
+                    }
+                }
+            }
+        }
+        indices.sort_unstable();
+        indices.dedup();
+        indices
+            .into_iter()
+            .map(|index| (self.slots.key(index), &self.slots.item(index).value))
+            .collect()
+    }
+
+    /// Items whose bounds contain `point`.
+    pub fn query_point(&self, point: Point<S>) -> Vec<(SpatialKey, &T)> {
+        self.query(Rect::new(point, Size::ZERO))
+    }
+
+    /// The item nearest to the ray's origin within `max_distance`, walking
+    /// only the cells the ray crosses.
+    ///
+    /// The walk stops once the ray has left every allocated cell, so an
+    /// infinite `max_distance` is fine.
+    pub fn raycast(&self, ray: Ray<S>, max_distance: f32) -> Option<RayHit> {
+        let mut best: Option<RayHit> = None;
+        for &index in &self.oversized {
+            let limit = best.map_or(max_distance, |hit| hit.distance);
+            if let Some(distance) = cast(&ray, &self.slots.item(index).bounds, limit) {
+                best = Some(RayHit {
+                    key: self.slots.key(index),
+                    distance,
+                });
+            }
+        }
+        let Some(((min_x, min_y), (max_x, max_y))) = self.extent else {
+            return best;
+        };
+        let origin = ray.origin;
+        let inverse = ray.direction.recip();
+        let size = self.cell_size;
+        let mut cell = self.cell(origin.x, origin.y);
+        let step = (signum(inverse.x), signum(inverse.y));
+        let boundary = |cell: i32, step: i32, origin: f32, inverse: f32| {
+            if step == 0 {
+                f32::INFINITY
+            } else {
+                ((cell + i32::from(step > 0)) as f32 * size - origin) * inverse
+            }
+        };
+        let mut next = (
+            boundary(cell.0, step.0, origin.x, inverse.x),
+            boundary(cell.1, step.1, origin.y, inverse.y),
+        );
+        let delta = (size * inverse.x.abs(), size * inverse.y.abs());
+        // Whether a ray at `cell` moving by `step` can no longer reach
+        // `min..=max` along one axis.
+        let departed = |cell: i32, step: i32, min: i32, max: i32| {
+            (step >= 0 && cell > max) || (step <= 0 && cell < min)
+        };
+        loop {
+            if departed(cell.0, step.0, min_x, max_x) || departed(cell.1, step.1, min_y, max_y) {
+                return best;
+            }
+            for &index in self.cells.get(&cell).into_iter().flatten() {
+                let limit = best.map_or(max_distance, |hit| hit.distance);
+                if let Some(distance) = cast(&ray, &self.slots.item(index).bounds, limit)
+                    && best.is_none_or(|hit| distance < hit.distance)
+                {
+                    best = Some(RayHit {
+                        key: self.slots.key(index),
+                        distance,
+                    });
+                }
+            }
+            let exit = next.0.min(next.1);
+            if exit > max_distance || best.is_some_and(|hit| hit.distance <= exit) {
+                return best;
+            }
+            if next.0 < next.1 {
+                cell.0 = cell.0.checked_add(step.0)?;
+                next.0 += delta.0;
+            } else {
+                cell.1 = cell.1.checked_add(step.1)?;
+                next.1 += delta.1;
+            }
+        }
+    }
+
+    fn cell(&self, x: f32, y: f32) -> (i32, i32) {
+        (
+            (x / self.cell_size).floor() as i32,
+            (y / self.cell_size).floor() as i32,
+        )
+    }
+
+    fn cell_range(&self, bounds: Rect<S>) -> CellRange {
+        (
+            self.cell(bounds.min_x(), bounds.min_y()),
+            self.cell(bounds.max_x(), bounds.max_y()),
+        )
+    }
+
+    fn link(&mut self, index: u32, bounds: Rect<S>) {
+        let range = self.cell_range(bounds);
+        if cell_count(range) > MAX_ITEM_CELLS {
+            self.oversized.push(index);
+            return;
+        }
+        let ((x0, y0), (x1, y1)) = range;
+        self.extent = Some(match self.extent {
+            Some(((ex0, ey0), (ex1, ey1))) => {
+                ((ex0.min(x0), ey0.min(y0)), (ex1.max(x1), ey1.max(y1)))
+            }
+            None => range,
+        });
+        for y in y0..=y1 {
+            for x in x0..=x1 {
+                self.cells.entry((x, y)).or_default().push(index);
+            }
+        }
+    }
+
+    fn unlink(&mut self, index: u32, bounds: Rect<S>) {
+        let range = self.cell_range(bounds);
+        if cell_count(range) > MAX_ITEM_CELLS {
+            self.oversized.retain(|&other| other != index);
+            return;
+        }
+        let ((x0, y0), (x1, y1)) = range;
+        for y in y0..=y1 {
+            for x in x0..=x1 {
+                if let Some(cell) = self.cells.get_mut(&(x, y)) {
+                    cell.retain(|&other| other != index);
+                    if cell.is_empty() {
+                        self.cells.remove(&(x, y));
+                    }
+                }
+            }
+        }
+    }
+}
+
+struct QuadNode<S> {
+    bounds: Rect<S>,
+    depth: u32,
+    /// Index of the first of four consecutive children.
+    children: Option<u32>,
+    items: Vec<u32>,
+}
+
+/// Items stored in a region quad tree.
+///
+/// A node splits into quadrants once it holds more than its capacity; items
+/// that straddle a quadrant boundary stay in the parent. Items outside the
+/// root region are kept at the root and still returned by queries. Nodes are
+/// not merged when items are removed; call [`QuadTree::clear`] to reset.
+pub struct QuadTree<T, S = Logical> {
+    nodes: Vec<QuadNode<S>>,
+    slots: Slots<T, S>,
+    capacity: usize,
+    max_depth: u32,
+}
+
+impl<T, S: Copy> QuadTree<T, S> {
+    /// Creates an empty tree over `bounds` that splits nodes above eight
+    /// items, down to eight levels.
+    pub fn new(bounds: Rect<S>) -> Self {
+        Self::with_limits(bounds, 8, 8)
+    }
+
+    /// Creates an empty tree with a custom node capacity and depth limit.
+    pub fn with_limits(bounds: Rect<S>, capacity: usize, max_depth: u32) -> Self {
+        Self {
+            nodes: vec![QuadNode {
+                bounds,
+                depth: 0,
+                children: None,
+                items: Vec::new(),
+            }],
+            slots: Slots::new(),
+            capacity: capacity.max(1),
+            max_depth,
+        }
+    }
+
+    /// Region covered by the root node.
+    pub fn bounds(&self) -> Rect<S> {
+        self.nodes[0].bounds
+    }
+
+    /// Number of items.
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Returns whether the tree holds no items.
+    pub fn is_empty(&self) -> bool {
+        self.slots.len() == 0
+    }
+
+    /// Adds an item and returns its key.
+    pub fn insert(&mut self, bounds: Rect<S>, value: T) -> SpatialKey {
+        let key = self.slots.insert(Item {
+            bounds,
+            value,
+            node: 0,
+        });
+        self.place(key.index, 0);
+        key
+    }
+
+    /// Removes an item, returning its value.
+    pub fn remove(&mut self, key: SpatialKey) -> Option<T> {
+        let item = self.slots.remove(key)?;
+        self.unlink(key.index, item.node);
+        Some(item.value)
+    }
+
+    /// Moves an item. Returns whether `key` was live.
+    pub fn set_bounds(&mut self, key: SpatialKey, bounds: Rect<S>) -> bool {
+        let Some(item) = self.slots.get_mut(key) else {
+            return false;
+        };
+        item.bounds = bounds;
+        let node = item.node;
+        self.unlink(key.index, node);
+        self.place(key.index, 0);
+        true
+    }
+
+    /// An item's bounds and value.
+    pub fn get(&self, key: SpatialKey) -> Option<(Rect<S>, &T)> {
+        self.slots.get(key).map(|item| (item.bounds, &item.value))
+    }
+
+    /// An item's value, mutably.
+    pub fn get_mut(&mut self, key: SpatialKey) -> Option<&mut T> {
+        self.slots.get_mut(key).map(|item| &mut item.value)
+    }
+
+    /// Removes every item and collapses the tree to its root.
+    pub fn clear(&mut self) {
+        self.nodes.truncate(1);
+        self.nodes[0].children = None;
+        self.nodes[0].items.clear();
+        self.slots.clear();
+    }
+
+    /// Items whose bounds touch `area`.
+    pub fn query(&self, area: Rect<S>) -> Vec<(SpatialKey, &T)> {
+        let mut found = Vec::new();
+        let mut stack = vec![0];
+        while let Some(node) = stack.pop() {
+            let node = &self.nodes[node as usize];
+            for &index in &node.items {
+                let item = self.slots.item(index);
+                if item.bounds.intersects(&area) {
+                    found.push((self.slots.key(index), &item.value));
+                }
+            }
+            if let Some(first) = node.children {
+                stack.extend(
+                    (first..first + 4)
+                        .filter(|&child| self.nodes[child as usize].bounds.intersects(&area)),
+                );
+            }
+        }
+        found
+    }
+
+    /// Items whose bounds contain `point`.
+    pub fn query_point(&self, point: Point<S>) -> Vec<(SpatialKey, &T)> {
+        self.query(Rect::new(point, Size::ZERO))
+    }
+
+    /// The item nearest to the ray's origin within `max_distance`, skipping
+    /// quadrants the ray misses.
+    pub fn raycast(&self, ray: Ray<S>, max_distance: f32) -> Option<RayHit> {
+        let mut best: Option<RayHit> = None;
+        let mut stack = vec![0];
+        while let Some(node) = stack.pop() {
+            let node = &self.nodes[node as usize];
+            for &index in &node.items {
+                let limit = best.map_or(max_distance, |hit| hit.distance);
+                if let Some(distance) = cast(&ray, &self.slots.item(index).bounds, limit)
+                    && best.is_none_or(|hit| distance < hit.distance)
+                {
+                    best = Some(RayHit {
+                        key: self.slots.key(index),
+                        distance,
+                    });
+                }
+            }
+            if let Some(first) = node.children {
+                let limit = best.map_or(max_distance, |hit| hit.distance);
+                stack.extend((first..first + 4).filter(|&child| {
+                    cast(&ray, &self.nodes[child as usize].bounds, limit).is_some()
+                }));
+            }
+        }
+        best
+    }
+
+    /// Stores an item in the deepest node below `node` that contains it.
+    fn place(&mut self, index: u32, mut node: u32) {
+        let bounds = self.slots.item(index).bounds;
+        while let Some(first) = self.nodes[node as usize].children {
+            match (first..first + 4)
+                .find(|&child| self.nodes[child as usize].bounds.contains_rect(&bounds))
+            {
+                Some(child) => node = child,
+                None => break,
+            }
+        }
+        let target = &mut self.nodes[node as usize];
+        target.items.push(index);
+        let split = target.children.is_none()
+            && target.items.len() > self.capacity
+            && target.depth < self.max_depth;
+        self.set_node(index, node);
+        if split {
+            self.split(node);
+        }
+    }
+
+    fn split(&mut self, node: u32) {
+        let QuadNode { bounds, depth, .. } = self.nodes[node as usize];
+        let half = Size::new(bounds.size.width / 2.0, bounds.size.height / 2.0);
+        let first = self.nodes.len() as u32;
+        for (x, y) in [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0), (1.0, 1.0)] {
+            self.nodes.push(QuadNode {
+                bounds: Rect::new(
+                    Point::new(
+                        bounds.origin.x + half.width * x,
+                        bounds.origin.y + half.height * y,
+                    ),
+                    half,
+                ),
+                depth: depth + 1,
+                children: None,
+                items: Vec::new(),
+            });
+        }
+        self.nodes[node as usize].children = Some(first);
+        for index in std::mem::take(&mut self.nodes[node as usize].items) {
+            self.place(index, node);
+        }
+    }
+
+    fn unlink(&mut self, index: u32, node: u32) {
+        let items = &mut self.nodes[node as usize].items;
+        if let Some(position) = items.iter().position(|&other| other == index) {
+            items.swap_remove(position);
+        }
+    }
+
+    fn set_node(&mut self, index: u32, node: u32) {
+        if let Some(item) = self.slots.item_mut(index) {
+            item.node = node;
+        }
+    }
+}
+
+/// Items per BVH leaf.
+const BVH_LEAF_SIZE: usize = 4;
+
+enum BvhKind {
+    /// Items `order[start..start + count]`.
+    Leaf { start: u32, count: u32 },
+    /// Two children; the left one immediately follows this node.
+    Branch { right: u32 },
+}
+
+struct BvhNode<S> {
+    bounds: Rect<S>,
+    kind: BvhKind,
+}
+
+/// A bounding volume hierarchy built once over a fixed item set.
+///
+/// Items are addressed by their position in the build input. The hierarchy
+/// is split at the median along each node's longest axis; rebuild it when
+/// the set changes.
+pub struct Bvh<T, S = Logical> {
+    items: Vec<(Rect<S>, T)>,
+    order: Vec<u32>,
+    nodes: Vec<BvhNode<S>>,
+}
+
+impl<T, S: Copy> Bvh<T, S> {
+    /// Builds a hierarchy over `items`.
+    pub fn build(items: impl IntoIterator<Item = (Rect<S>, T)>) -> Self {
+        let items = items.into_iter().collect::<Vec<_>>();
+        let mut bvh = Self {
+            order: (0..items.len() as u32).collect(),
+            items,
+            nodes: Vec::new(),
+        };
+        if !bvh.items.is_empty() {
+            bvh.build_node(0, bvh.items.len());
+        }
+        bvh
+    }
+
+    /// Number of items.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns whether the hierarchy holds no items.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// An item's bounds and value by build position.
+    pub fn get(&self, index: usize) -> Option<(Rect<S>, &T)> {
+        self.items
+            .get(index)
+            .map(|(bounds, value)| (*bounds, value))
+    }
+
+    /// Items whose bounds touch `area`, by build position.
+    pub fn query(&self, area: Rect<S>) -> Vec<(usize, &T)> {
+        let mut found = Vec::new();
+        self.visit(
+            |bounds| bounds.intersects(&area),
+            |index, (bounds, value)| {
+                if bounds.intersects(&area) {
+                    found.push((index, value));
+                }
+            },
+        );
+        found
+    }
+
+    /// Items whose bounds contain `point`.
+    pub fn query_point(&self, point: Point<S>) -> Vec<(usize, &T)> {
+        self.query(Rect::new(point, Size::ZERO))
+    }
+
+    /// The item nearest to the ray's origin within `max_distance`.
+    pub fn raycast(&self, ray: Ray<S>, max_distance: f32) -> Option<RayHit<usize>> {
+        let mut best: Option<RayHit<usize>> = None;
+        let mut stack = Vec::new();
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+        while let Some(node) = stack.pop() {
+            let limit = best.map_or(max_distance, |hit| hit.distance);
+            let node_index = node;
+            let node = &self.nodes[node as usize];
+            if cast(&ray, &node.bounds, limit).is_none() {
+                continue;
+            }
+            match node.kind {
+                BvhKind::Leaf { start, count } => {
+                    for &index in &self.order[start as usize..(start + count) as usize] {
+                        let limit = best.map_or(max_distance, |hit| hit.distance);
+                        if let Some(distance) = cast(&ray, &self.items[index as usize].0, limit)
+                            && best.is_none_or(|hit| distance < hit.distance)
+                        {
+                            best = Some(RayHit {
+                                key: index as usize,
+                                distance,
+                            });
+                        }
+                    }
+                }
+                BvhKind::Branch { right } => stack.extend([right, node_index + 1]),
+            }
+        }
+        best
+    }
+
+    fn visit<'a>(
+        &'a self,
+        mut enter: impl FnMut(&Rect<S>) -> bool,
+        mut item: impl FnMut(usize, &'a (Rect<S>, T)),
+    ) {
+        let mut stack = Vec::new();
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+        while let Some(node) = stack.pop() {
+            let node_index = node;
+            let node = &self.nodes[node as usize];
+            if !enter(&node.bounds) {
+                continue;
+            }
+            match node.kind {
+                BvhKind::Leaf { start, count } => {
+                    for &index in &self.order[start as usize..(start + count) as usize] {
+                        item(index as usize, &self.items[index as usize]);
+                    }
+                }
+                BvhKind::Branch { right } => stack.extend([right, node_index + 1]),
+            }
+        }
+    }
+
+    fn build_node(&mut self, start: usize, end: usize) -> u32 {
+        let node = self.nodes.len() as u32;
+        let bounds = self.order[start..end]
+            .iter()
+            .map(|&index| self.items[index as usize].0)
+            .reduce(|a, b| a.union(&b))
+            .expect("nodes are never empty");
+        if end - start <= BVH_LEAF_SIZE {
+            self.nodes.push(BvhNode {
+                bounds,
+                kind: BvhKind::Leaf {
+                    start: start as u32,
+                    count: (end - start) as u32,
+                },
+            });
+            return node;
+        }
+        let center = |bounds: &Rect<S>, wide: bool| {
+            if wide {
+                bounds.min_x() + bounds.size.width / 2.0
+            } else {
+                bounds.min_y() + bounds.size.height / 2.0
+            }
+        };
+        let wide = bounds.size.width >= bounds.size.height;
+        let items = &self.items;
+        let middle = (end - start) / 2;
+        self.order[start..end].select_nth_unstable_by(middle, |&a, &b| {
+            center(&items[a as usize].0, wide).total_cmp(&center(&items[b as usize].0, wide))
+        });
+        self.nodes.push(BvhNode {
+            bounds,
+            kind: BvhKind::Branch { right: 0 },
+        });
+        self.build_node(start, start + middle);
+        let right = self.build_node(start + middle, end);
+        self.nodes[node as usize].kind = BvhKind::Branch { right };
+        node
+    }
+}
+
+/// Distance along `ray` to `bounds`, if within `max`.
+fn cast<S>(ray: &Ray<S>, bounds: &Rect<S>, max: f32) -> Option<f32> {
+    ray.cast_rect(bounds).filter(|&distance| distance <= max)
+}
+
+/// Number of cells in an inclusive range, zero when it is inverted.
+fn cell_count(((x0, y0), (x1, y1)): CellRange) -> i64 {
+    let width = (i64::from(x1) - i64::from(x0) + 1).max(0);
+    let height = (i64::from(y1) - i64::from(y0) + 1).max(0);
+    width.saturating_mul(height)
+}
+
+fn contains(((x0, y0), (x1, y1)): CellRange, (x, y): (i32, i32)) -> bool {
+    (x0..=x1).contains(&x) && (y0..=y1).contains(&y)
+}
+
+fn signum(value: f32) -> i32 {
+    if value > 0.0 {
+        1
+    } else if value < 0.0 {
+        -1
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Vec2;
+
+    fn rect(x: f32, y: f32, w: f32, h: f32) -> Rect<Logical> {
+        Rect::from_xywh(x, y, w, h)
+    }
+
+    /// A scattered, deterministic set of small rectangles.
+    fn scatter(count: u32) -> Vec<Rect<Logical>> {
+        (0..count)
+            .map(|n| {
+                let x = (n * 37 % 101) as f32 * 5.0;
+                let y = (n * 61 % 97) as f32 * 5.0;
+                rect(x, y, 3.0 + (n % 4) as f32, 3.0)
+            })
+            .collect()
+    }
+
+    fn sorted<K: Ord>(mut keys: Vec<K>) -> Vec<K> {
+        keys.sort();
+        keys
+    }
+
+    #[test]
+    fn grid_tracks_moves_and_walks_rays_through_cells() {
+        let mut grid = Grid2D::<u32, Logical>::new(10.0);
+        let a = grid.insert(rect(0.0, 0.0, 25.0, 5.0), 1);
+        let b = grid.insert(rect(40.0, 0.0, 5.0, 5.0), 2);
+        assert_eq!(grid.query(rect(22.0, 0.0, 1.0, 1.0)), [(a, &1)]);
+        assert!(grid.query(rect(30.0, 0.0, 5.0, 5.0)).is_empty());
+
+        assert!(grid.set_bounds(b, rect(-40.0, 0.0, 5.0, 5.0)));
+        assert!(grid.query_point(Point::new(42.0, 2.0)).is_empty());
+        assert_eq!(grid.query_point(Point::new(-38.0, 2.0)), [(b, &2)]);
+
+        let ray = Ray::new(Point::new(100.0, 2.0), -Vec2::X).unwrap();
+        let hit = grid.raycast(ray, 200.0).unwrap();
+        assert_eq!((hit.key, hit.distance), (a, 75.0));
+        assert!(grid.raycast(ray, 50.0).is_none());
+
+        assert_eq!(grid.remove(a), Some(1));
+        assert_eq!(grid.remove(a), None, "stale keys are rejected");
+        let hit = grid.raycast(ray, 200.0).unwrap();
+        assert_eq!((hit.key, hit.distance), (b, 135.0));
+        assert_eq!(grid.len(), 1);
+    }
+
+    #[test]
+    fn grid_bounds_infinite_rays_and_huge_rects() {
+        let mut grid = Grid2D::<u32, Logical>::new(10.0);
+        assert!(
+            grid.raycast(Ray::new(Point::ZERO, Vec2::X).unwrap(), f32::INFINITY)
+                .is_none()
+        );
+
+        let small = grid.insert(rect(0.0, 0.0, 5.0, 5.0), 1);
+        let miss = Ray::new(Point::new(0.0, 50.0), Vec2::new(1.0, 0.5)).unwrap();
+        assert!(grid.raycast(miss, f32::INFINITY).is_none());
+        let away = Ray::new(Point::new(-1e30, -1e30), -Vec2::X).unwrap();
+        assert!(grid.raycast(away, f32::INFINITY).is_none());
+
+        let huge = grid.insert(rect(-1e9, 100.0, 2e9, 2e9), 2);
+        let everything = rect(-1e30, -1e30, 2e30, 2e30);
+        assert_eq!(grid.query(everything), [(small, &1), (huge, &2)]);
+        let up = Ray::new(Point::new(2.0, -20.0), Vec2::Y).unwrap();
+        let hit = grid.raycast(up, f32::INFINITY).unwrap();
+        assert_eq!((hit.key, hit.distance), (small, 20.0));
+        assert!(grid.set_bounds(small, rect(-5.0, -5.0, 1.0, 1.0)));
+        let hit = grid.raycast(up, f32::INFINITY).unwrap();
+        assert_eq!((hit.key, hit.distance), (huge, 120.0));
+        assert_eq!(grid.remove(huge), Some(2));
+        assert!(grid.raycast(up, f32::INFINITY).is_none());
+    }
+
+    #[test]
+    fn quad_tree_splits_and_matches_brute_force() {
+        let bounds = scatter(300);
+        let mut tree = QuadTree::with_limits(rect(0.0, 0.0, 512.0, 512.0), 4, 6);
+        let keys = bounds
+            .iter()
+            .enumerate()
+            .map(|(index, bounds)| tree.insert(*bounds, index))
+            .collect::<Vec<_>>();
+        let outside = tree.insert(rect(-50.0, -50.0, 10.0, 10.0), usize::MAX);
+        assert!(tree.nodes.len() > 1, "crowded nodes split");
+
+        let area = rect(100.0, 100.0, 120.0, 80.0);
+        let expected = (0..bounds.len())
+            .filter(|&index| bounds[index].intersects(&area))
+            .collect::<Vec<_>>();
+        let found = tree
+            .query(area)
+            .into_iter()
+            .map(|(_, &index)| index)
+            .collect();
+        assert_eq!(sorted(found), expected);
+        assert_eq!(
+            tree.query_point(Point::new(-45.0, -45.0)),
+            [(outside, &usize::MAX)]
+        );
+
+        tree.remove(keys[expected[0]]);
+        assert_eq!(tree.query(area).len(), expected.len() - 1);
+
+        let ray = Ray::new(Point::new(-10.0, 250.0), Vec2::X).unwrap();
+        let nearest = (0..bounds.len())
+            .filter(|&index| index != expected[0])
+            .filter_map(|index| cast(&ray, &bounds[index], 1000.0))
+            .reduce(f32::min);
+        let hit = tree.raycast(ray, 1000.0);
+        assert_eq!(hit.map(|hit| hit.distance), nearest);
+    }
+
+    #[test]
+    fn bvh_queries_and_rays_match_brute_force() {
+        let bounds = scatter(200);
+        let bvh = Bvh::build(bounds.iter().map(|bounds| (*bounds, ())));
+        assert_eq!(bvh.len(), 200);
+
+        let area = rect(50.0, 200.0, 150.0, 100.0);
+        let expected = (0..bounds.len())
+            .filter(|&index| bounds[index].intersects(&area))
+            .collect::<Vec<_>>();
+        let found = bvh
+            .query(area)
+            .into_iter()
+            .map(|(index, _)| index)
+            .collect();
+        assert_eq!(sorted(found), expected);
+
+        let ray = Ray::new(Point::ZERO, Vec2::new(1.0, 1.0)).unwrap();
+        let nearest = (0..bounds.len())
+            .filter_map(|index| {
+                ray.cast_rect(&bounds[index])
+                    .map(|distance| (index, distance))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        let hit = bvh.raycast(ray, f32::INFINITY);
+        assert_eq!(hit.map(|hit| (hit.key, hit.distance)), nearest);
+        assert!(Bvh::<(), Logical>::build([]).query(area).is_empty());
+    }
+}
@@ -224,6 +224,7 @@ impl Application for App {
                 }),
                 depth_stencil: None,
                 multisample: Default::default(),
+                cache: None,
             })
             .expect("create pipeline");
         let vertices: [[f32; 5]; 3] = [
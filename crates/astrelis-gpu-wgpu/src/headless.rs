@@ -0,0 +1,155 @@
+//! Window-free device creation for tests and offline rendering.
+
+use astrelis_gpu as gpu;
+
+use crate::InstanceDescriptor;
+
+/// Settings for [`HeadlessContext::new`].
+#[derive(Clone, Debug, Default)]
+pub struct HeadlessOptions {
+    /// Instance backends and environment overrides.
+    pub instance: InstanceDescriptor,
+    /// Adapter selection; a compatible surface is never required.
+    pub adapter: gpu::RequestAdapterOptions,
+    /// Requested features and limits.
+    pub device: gpu::DeviceDescriptor,
+}
+
+/// An instance, adapter, device, and queue with no surface.
+///
+/// Offscreen textures created with [`HeadlessContext::create_target`] are the
+/// only render targets, so rendering code can run in CI containers that have
+/// a software or GPU adapter but no display. [`HeadlessContext::read_texture`]
+/// copies results back for comparison.
+#[derive(Clone, Debug)]
+pub struct HeadlessContext {
+    instance: gpu::Instance,
+    adapter: gpu::Adapter,
+    device: gpu::Device,
+    queue: gpu::Queue,
+}
+
+impl HeadlessContext {
+    /// Creates a context, failing when no adapter is available so callers
+    /// can skip GPU work gracefully.
+    pub async fn new(options: HeadlessOptions) -> Result<Self, gpu::GpuError> {
+        let instance = crate::create_instance(options.instance);
+        let adapter = instance
+            .request_adapter(gpu::RequestAdapterOptions {
+                compatible_surface: None,
+                ..options.adapter
+            })
+            .await?;
+        let (device, queue) = adapter.request_device(options.device).await?;
+        Ok(Self {
+            instance,
+            adapter,
+            device,
+            queue,
+        })
+    }
+
+    /// Blocking form of [`HeadlessContext::new`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn new_blocking(options: HeadlessOptions) -> Result<Self, gpu::GpuError> {
+        pollster::block_on(Self::new(options))
+    }
+
+    /// The instance the adapter came from.
+    pub fn instance(&self) -> &gpu::Instance {
+        &self.instance
+    }
+
+    /// The selected adapter.
+    pub fn adapter(&self) -> &gpu::Adapter {
+        &self.adapter
+    }
+
+    /// The logical device.
+    pub fn device(&self) -> &gpu::Device {
+        &self.device
+    }
+
+    /// The device's queue.
+    pub fn queue(&self) -> &gpu::Queue {
+        &self.queue
+    }
+
+    /// Creates a 2D texture that can be rendered to, sampled, and read back.
+    pub fn create_target(
+        &self,
+        width: u32,
+        height: u32,
+        format: gpu::TextureFormat,
+    ) -> gpu::Texture {
+        self.device.create_texture(gpu::TextureDescriptor {
+            label: Some("headless target".into()),
+            size: gpu::Extent3d::d2(width, height),
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: gpu::TextureDimension::D2,
+            format,
+            usage: gpu::TextureUsages::RENDER_ATTACHMENT
+                | gpu::TextureUsages::TEXTURE_BINDING
+                | gpu::TextureUsages::COPY_SRC,
+        })
+    }
+
+    /// Waits for submitted work and returns the base level of `texture` as
+    /// tightly packed rows.
+    pub async fn read_texture(
+        &self,
+        texture: &gpu::Texture,
+        format: gpu::TextureFormat,
+        width: u32,
+        height: u32,
+    ) -> Result<Vec<u8>, gpu::GpuError> {
+        let texel_size = match format.block_copy_size() {
+            Some(size) if !format.is_compressed() => size,
+            _ => {
+                return Err(gpu::GpuError::new(format!(
+                    "{format:?} textures cannot be read back"
+                )));
+            }
+        };
+        let row = width * texel_size;
+        let padded_row = row.next_multiple_of(256);
+        let size = u64::from(padded_row) * u64::from(height);
+        let readback = self.device.create_buffer(gpu::BufferDescriptor {
+            label: Some("headless readback".into()),
+            size,
+            usage: gpu::BufferUsages::COPY_DST | gpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let mut encoder = self
+            .device
+            .create_command_encoder(gpu::CommandEncoderDescriptor {
+                label: Some("headless readback".into()),
+            });
+        encoder.copy_texture_to_buffer(
+            &gpu::TextureCopy {
+                texture: texture.clone(),
+                mip_level: 0,
+                origin: Default::default(),
+            },
+            &gpu::BufferTextureCopy {
+                buffer: readback.clone(),
+                offset: 0,
+                bytes_per_row: Some(padded_row),
+                rows_per_image: Some(height),
+            },
+            gpu::Extent3d::d2(width, height),
+        )?;
+        self.queue.submit([encoder.finish()?])?;
+        let mapping = readback.map_async(gpu::MapMode::Read, 0..size);
+        self.device.poll(gpu::PollMode::Wait)?;
+        mapping.await?;
+        let padded = readback.read_mapped(0..size);
+        readback.unmap();
+        Ok(padded?
+            .chunks_exact(padded_row as usize)
+            .flat_map(|chunk| &chunk[..row as usize])
+            .copied()
+            .collect())
+    }
+}
@@ -15,17 +15,19 @@ use astrelis_gpu::{
     CompositeAlphaMode, ComputePassDescriptor, ComputePipelineDescriptor, DeviceCapabilities,
     DeviceDescriptor, DeviceError, DeviceErrorKind, DeviceId, DeviceType, Extent3d, Face, Features,
     FilterMode, FrontFace, GpuError, GraphicsApi, IndexFormat, Limits, LoadOp, LoadOpValue,
-    MapMode, PipelineLayoutDescriptor, PollMode, PowerPreference, PresentMode, PrimitiveTopology,
-    QuerySetDescriptor, QueryType, RenderPassDescriptor, RenderPipelineDescriptor,
-    RequestAdapterOptions, SamplerBindingType, SamplerDescriptor, ShaderModuleDescriptor,
-    ShaderStages, StencilOperation, StoreOp, SurfaceCapabilities, SurfaceConfiguration,
-    SurfaceFrameStatus, TextureCopy, TextureDataLayout, TextureDescriptor, TextureDimension,
-    TextureFormat, TextureSampleType, TextureUsages, TextureViewDescriptor, TextureViewDimension,
-    VertexFormat, VertexStepMode, backend,
+    MapMode, PipelineCacheDescriptor, PipelineLayoutDescriptor, PollMode, PowerPreference,
+    PresentMode, PrimitiveTopology, QuerySetDescriptor, QueryType, RenderPassDescriptor,
+    RenderPipelineDescriptor, RequestAdapterOptions, SamplerBindingType, SamplerDescriptor,
+    ShaderModuleDescriptor, ShaderStages, StencilOperation, StoreOp, SurfaceCapabilities,
+    SurfaceConfiguration, SurfaceFrameStatus, TextureCopy, TextureDataLayout, TextureDescriptor,
+    TextureDimension, TextureFormat, TextureSampleType, TextureUsages, TextureViewDescriptor,
+    TextureViewDimension, VertexFormat, VertexStepMode, backend,
 };
 
+mod headless;
 #[cfg(feature = "profiling")]
 mod profiling;
+pub use headless::{HeadlessContext, HeadlessOptions};
 #[cfg(feature = "profiling")]
 pub use profiling::WgpuGpuProfiler;
 
@@ -81,6 +83,7 @@ pub fn wrap_device(
                 && features.contains(Features::TIMESTAMP_QUERY),
         },
         raw: device,
+        adapter: None,
         error_handler: Mutex::new(None),
     });
     install_wgpu_error_handlers(&wrapped_device);
@@ -334,6 +337,7 @@ impl backend::Adapter for WgpuAdapter {
             let device = Arc::new(WgpuDevice {
                 id,
                 raw: device,
+                adapter: Some(adapter),
                 capabilities,
                 error_handler: Mutex::new(None),
             });
@@ -352,6 +356,8 @@ type ErrorHandler = Arc<dyn Fn(DeviceError) + Send + Sync>;
 struct WgpuDevice {
     id: DeviceId,
     raw: wgpu::Device,
+    /// Originating adapter, unknown for wrapped external devices.
+    adapter: Option<wgpu::Adapter>,
     capabilities: DeviceCapabilities,
     error_handler: Mutex<Option<ErrorHandler>>,
 }
@@ -444,6 +450,20 @@ impl backend::Device for WgpuDevice {
             .map_err(|error| GpuError::new(error.to_string()))
     }
 
+    fn supports_sample_count(&self, format: TextureFormat, count: u32) -> bool {
+        let format = convert_texture_format(format);
+        let features = self.raw.features();
+        let format_features = match &self.adapter {
+            Some(adapter)
+                if features.contains(wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES) =>
+            {
+                adapter.get_texture_format_features(format)
+            }
+            _ => format.guaranteed_format_features(features),
+        };
+        format_features.flags.sample_count_supported(count)
+    }
+
     fn create_buffer(&self, descriptor: BufferDescriptor) -> Arc<dyn backend::Buffer> {
         let buffer = self.raw.create_buffer(&wgpu::BufferDescriptor {
             label: descriptor.label.as_deref(),
@@ -508,6 +528,39 @@ impl backend::Device for WgpuDevice {
         })
     }
 
+    fn create_shader_module_checked(
+        &self,
+        descriptor: ShaderModuleDescriptor,
+    ) -> backend::BackendFuture<Result<Arc<dyn backend::ShaderModule>, GpuError>> {
+        // Scopes are thread-local, so the module is created and the scope
+        // popped before anything is awaited.
+        let scope = self.raw.push_error_scope(wgpu::ErrorFilter::Validation);
+        let raw = self.raw.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: descriptor.label.as_deref(),
+            source: wgpu::ShaderSource::Wgsl(descriptor.wgsl.into()),
+        });
+        let scope_error = scope.pop();
+        let id = self.id;
+        Box::pin(async move {
+            let errors = compilation_errors(raw.get_compilation_info().await);
+            let scope_error = scope_error.await;
+            if !errors.is_empty() {
+                return Err(GpuError::new(errors.join("; ")));
+            }
+            if let Some(error) = scope_error {
+                return Err(GpuError::new(error.to_string()));
+            }
+            Ok(Arc::new(WgpuShaderModule { id, raw }) as Arc<dyn backend::ShaderModule>)
+        })
+    }
+
+    fn validation_scope(&self, body: &mut dyn FnMut()) -> backend::BackendFuture<Option<GpuError>> {
+        let scope = self.raw.push_error_scope(wgpu::ErrorFilter::Validation);
+        body();
+        let error = scope.pop();
+        Box::pin(async move { error.await.map(|error| GpuError::new(error.to_string())) })
+    }
+
     fn create_bind_group_layout(
         &self,
         descriptor: BindGroupLayoutDescriptor,
@@ -778,7 +831,14 @@ impl backend::Device for WgpuDevice {
                 },
                 fragment: fragment_state,
                 multiview_mask: None,
-                cache: None,
+                cache: descriptor.cache.as_ref().map(|cache| {
+                    &cache
+                        .backend()
+                        .as_any()
+                        .downcast_ref::<WgpuPipelineCache>()
+                        .expect("pipeline cache backend was checked by astrelis-gpu")
+                        .raw
+                }),
             });
         Arc::new(WgpuRenderPipeline {
             id: self.id,
@@ -786,6 +846,27 @@ impl backend::Device for WgpuDevice {
         })
     }
 
+    fn pipeline_cache_key(&self) -> Option<String> {
+        wgpu::util::pipeline_cache_key(&self.adapter.as_ref()?.get_info())
+    }
+
+    fn create_pipeline_cache(
+        &self,
+        descriptor: PipelineCacheDescriptor,
+    ) -> Arc<dyn backend::PipelineCache> {
+        // SAFETY: the data contract is documented on PipelineCacheDescriptor,
+        // and `fallback` makes the driver discard data it cannot use.
+        let raw = unsafe {
+            self.raw
+                .create_pipeline_cache(&wgpu::PipelineCacheDescriptor {
+                    label: descriptor.label.as_deref(),
+                    data: descriptor.data.as_deref(),
+                    fallback: true,
+                })
+        };
+        Arc::new(WgpuPipelineCache { id: self.id, raw })
+    }
+
     fn create_query_set(&self, descriptor: QuerySetDescriptor) -> Arc<dyn backend::QuerySet> {
         Arc::new(WgpuQuerySet {
             id: self.id,
@@ -827,7 +908,14 @@ impl backend::Device for WgpuDevice {
                     module: &module.raw,
                     entry_point: Some(&descriptor.entry_point),
                     compilation_options: Default::default(),
-                    cache: None,
+                    cache: descriptor.cache.as_ref().map(|cache| {
+                        &cache
+                            .backend()
+                            .as_any()
+                            .downcast_ref::<WgpuPipelineCache>()
+                            .expect("pipeline cache backend was checked by astrelis-gpu")
+                            .raw
+                    }),
                 }),
         })
     }
@@ -1097,18 +1185,31 @@ impl backend::Buffer for WgpuBuffer {
         mode: MapMode,
         range: Range<u64>,
     ) -> backend::BackendFuture<Result<(), GpuError>> {
+        // The callback fires from a later device poll. The future checks the
+        // channel without blocking so callers may poll it opportunistically.
         let (sender, receiver) = std::sync::mpsc::sync_channel(1);
+        let waker = Arc::new(Mutex::new(None::<std::task::Waker>));
+        let callback_waker = Arc::clone(&waker);
         self.raw
             .slice(range)
             .map_async(convert_map_mode(mode), move |result| {
                 let _ = sender.send(result);
+                if let Some(waker) = callback_waker.lock().expect("map waker poisoned").take() {
+                    waker.wake();
+                }
             });
-        Box::pin(async move {
-            receiver
-                .recv()
-                .map_err(|_| GpuError::new("mapping callback was dropped"))?
-                .map_err(|error| GpuError::new(error.to_string()))
-        })
+        Box::pin(std::future::poll_fn(move |context| {
+            *waker.lock().expect("map waker poisoned") = Some(context.waker().clone());
+            match receiver.try_recv() {
+                Ok(result) => {
+                    std::task::Poll::Ready(result.map_err(|error| GpuError::new(error.to_string())))
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => std::task::Poll::Pending,
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    std::task::Poll::Ready(Err(GpuError::new("mapping callback was dropped")))
+                }
+            }
+        }))
     }
 
     fn read_mapped(&self, range: Range<u64>) -> Result<Vec<u8>, GpuError> {
@@ -1152,11 +1253,30 @@ impl backend::Texture for WgpuTexture {
     }
 
     fn create_view(&self, descriptor: TextureViewDescriptor) -> Arc<dyn backend::TextureView> {
+        // Mirrors wgpu's default: a 2D texture viewed over several layers
+        // is an array.
+        let view_dimension = descriptor.dimension.unwrap_or(match self.dimension {
+            TextureDimension::D1 => TextureViewDimension::D1,
+            TextureDimension::D2 => {
+                let layers = descriptor.array_layer_count.unwrap_or(
+                    self.raw
+                        .depth_or_array_layers()
+                        .saturating_sub(descriptor.base_array_layer),
+                );
+                if layers == 1 {
+                    TextureViewDimension::D2
+                } else {
+                    TextureViewDimension::D2Array
+                }
+            }
+            TextureDimension::D3 => TextureViewDimension::D3,
+        });
         Arc::new(WgpuTextureView {
             id: self.id,
             raw: self.raw.create_view(&wgpu::TextureViewDescriptor {
                 label: descriptor.label.as_deref(),
                 format: descriptor.format.map(convert_texture_format),
+                dimension: descriptor.dimension.map(convert_view_dimension),
                 base_mip_level: descriptor.base_mip_level,
                 mip_level_count: descriptor.mip_level_count,
                 base_array_layer: descriptor.base_array_layer,
@@ -1165,6 +1285,7 @@ impl backend::Texture for WgpuTexture {
             }),
             sample_count: self.sample_count,
             dimension: self.dimension,
+            view_dimension,
             format: descriptor.format.unwrap_or(self.format),
         })
     }
@@ -1176,6 +1297,7 @@ struct WgpuTextureView {
     raw: wgpu::TextureView,
     sample_count: u32,
     dimension: TextureDimension,
+    view_dimension: TextureViewDimension,
     format: TextureFormat,
 }
 
@@ -1198,6 +1320,10 @@ impl backend::TextureView for WgpuTextureView {
         self.dimension
     }
 
+    fn view_dimension(&self) -> TextureViewDimension {
+        self.view_dimension
+    }
+
     fn format(&self) -> TextureFormat {
         self.format
     }
@@ -1237,6 +1363,32 @@ impl backend::ShaderModule for WgpuShaderModule {
     fn device_id(&self) -> DeviceId {
         self.id
     }
+
+    fn compilation_errors(&self) -> Vec<String> {
+        // wgpu-core resolves compilation info immediately; the browser backend
+        // does not, and its errors arrive through the device error handler.
+        let mut info = std::pin::pin!(self.raw.get_compilation_info());
+        let mut context = std::task::Context::from_waker(std::task::Waker::noop());
+        let std::task::Poll::Ready(info) = info.as_mut().poll(&mut context) else {
+            return Vec::new();
+        };
+        compilation_errors(info)
+    }
+}
+
+/// Error messages of a compilation, prefixed with their line and column.
+fn compilation_errors(info: wgpu::CompilationInfo) -> Vec<String> {
+    info.messages
+        .into_iter()
+        .filter(|message| message.message_type == wgpu::CompilationMessageType::Error)
+        .map(|message| match message.location {
+            Some(location) => format!(
+                "{}:{}: {}",
+                location.line_number, location.line_position, message.message
+            ),
+            None => message.message,
+        })
+        .collect()
 }
 
 #[derive(Debug)]
@@ -1329,6 +1481,28 @@ impl backend::ComputePipeline for WgpuComputePipeline {
     }
 }
 
+#[derive(Debug)]
+struct WgpuPipelineCache {
+    id: DeviceId,
+    raw: wgpu::PipelineCache,
+}
+
+impl backend::NativeHandle for WgpuPipelineCache {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl backend::PipelineCache for WgpuPipelineCache {
+    fn device_id(&self) -> DeviceId {
+        self.id
+    }
+
+    fn data(&self) -> Option<Vec<u8>> {
+        self.raw.get_data()
+    }
+}
+
 #[derive(Debug)]
 struct WgpuQuerySet {
     id: DeviceId,
@@ -1531,6 +1705,51 @@ impl backend::CommandEncoder for WgpuCommandEncoder {
         Ok(())
     }
 
+    fn copy_buffer_to_texture(
+        &mut self,
+        source: &BufferTextureCopy,
+        destination: &TextureCopy,
+        extent: Extent3d,
+    ) -> Result<(), GpuError> {
+        let source_buffer = source
+            .buffer
+            .inner_backend()
+            .as_any()
+            .downcast_ref::<WgpuBuffer>()
+            .ok_or_else(|| GpuError::new("buffer belongs to another backend"))?;
+        let destination_texture = destination
+            .texture
+            .backend()
+            .as_any()
+            .downcast_ref::<WgpuTexture>()
+            .ok_or_else(|| GpuError::new("texture belongs to another backend"))?;
+        self.raw
+            .as_mut()
+            .ok_or_else(|| GpuError::new("encoder was already finished"))?
+            .copy_buffer_to_texture(
+                wgpu::TexelCopyBufferInfo {
+                    buffer: &source_buffer.raw,
+                    layout: wgpu::TexelCopyBufferLayout {
+                        offset: source.offset,
+                        bytes_per_row: source.bytes_per_row,
+                        rows_per_image: source.rows_per_image,
+                    },
+                },
+                wgpu::TexelCopyTextureInfo {
+                    texture: &destination_texture.raw,
+                    mip_level: destination.mip_level,
+                    origin: wgpu::Origin3d {
+                        x: destination.origin.x,
+                        y: destination.origin.y,
+                        z: destination.origin.z,
+                    },
+                    aspect: wgpu::TextureAspect::All,
+                },
+                convert_extent(extent),
+            );
+        Ok(())
+    }
+
     fn copy_texture_to_buffer(
         &mut self,
         source: &TextureCopy,
@@ -1597,6 +1816,19 @@ impl backend::CommandEncoder for WgpuCommandEncoder {
         Ok(())
     }
 
+    fn write_timestamp(
+        &mut self,
+        query_set: &dyn backend::QuerySet,
+        index: u32,
+    ) -> Result<(), GpuError> {
+        let query_set = downcast_ref::<WgpuQuerySet>(query_set)?;
+        self.raw
+            .as_mut()
+            .ok_or_else(|| GpuError::new("encoder was already finished"))?
+            .write_timestamp(&query_set.raw, index);
+        Ok(())
+    }
+
     fn push_debug_group(&mut self, label: &str) {
         if let Some(encoder) = &mut self.raw {
             encoder.push_debug_group(label);
@@ -1609,6 +1841,12 @@ impl backend::CommandEncoder for WgpuCommandEncoder {
         }
     }
 
+    fn insert_debug_marker(&mut self, label: &str) {
+        if let Some(encoder) = &mut self.raw {
+            encoder.insert_debug_marker(label);
+        }
+    }
+
     fn finish(mut self: Box<Self>) -> Result<Box<dyn backend::CommandBuffer>, GpuError> {
         let raw = self
             .raw
@@ -1679,6 +1917,18 @@ impl backend::RenderPass for WgpuRenderPass {
         self.raw.draw_indexed(indices, base_vertex, instances);
     }
 
+    fn multi_draw_indexed_indirect(
+        &mut self,
+        buffer: &dyn backend::Buffer,
+        offset: u64,
+        count: u32,
+    ) -> Result<(), GpuError> {
+        let buffer = downcast_ref::<WgpuBuffer>(buffer)?;
+        self.raw
+            .multi_draw_indexed_indirect(&buffer.raw, offset, count);
+        Ok(())
+    }
+
     fn set_scissor_rect(&mut self, x: u32, y: u32, width: u32, height: u32) {
         self.raw.set_scissor_rect(x, y, width, height);
     }
@@ -1699,6 +1949,27 @@ impl backend::RenderPass for WgpuRenderPass {
     fn set_stencil_reference(&mut self, reference: u32) {
         self.raw.set_stencil_reference(reference);
     }
+
+    fn set_blend_constant(&mut self, color: gpu::Color) {
+        self.raw.set_blend_constant(wgpu::Color {
+            r: color.r,
+            g: color.g,
+            b: color.b,
+            a: color.a,
+        });
+    }
+
+    fn push_debug_group(&mut self, label: &str) {
+        self.raw.push_debug_group(label);
+    }
+
+    fn pop_debug_group(&mut self) {
+        self.raw.pop_debug_group();
+    }
+
+    fn insert_debug_marker(&mut self, label: &str) {
+        self.raw.insert_debug_marker(label);
+    }
 }
 
 #[derive(Debug)]
@@ -1728,6 +1999,18 @@ impl backend::ComputePass for WgpuComputePass {
     fn dispatch_workgroups(&mut self, x: u32, y: u32, z: u32) {
         self.raw.dispatch_workgroups(x, y, z);
     }
+
+    fn push_debug_group(&mut self, label: &str) {
+        self.raw.push_debug_group(label);
+    }
+
+    fn pop_debug_group(&mut self) {
+        self.raw.pop_debug_group();
+    }
+
+    fn insert_debug_marker(&mut self, label: &str) {
+        self.raw.insert_debug_marker(label);
+    }
 }
 
 #[derive(Debug)]
@@ -1810,6 +2093,11 @@ fn convert_features_from_wgpu(value: wgpu::Features) -> Features {
             wgpu::Features::POLYGON_MODE_LINE,
             Features::POLYGON_MODE_LINE,
         ),
+        (
+            wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES,
+            Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES,
+        ),
+        (wgpu::Features::PIPELINE_CACHE, Features::PIPELINE_CACHE),
     ];
     for (native, neutral) in mappings {
         if value.contains(native) {
@@ -1847,6 +2135,11 @@ fn convert_features_to_wgpu(value: Features) -> wgpu::Features {
             Features::POLYGON_MODE_LINE,
             wgpu::Features::POLYGON_MODE_LINE,
         ),
+        (
+            Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES,
+            wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES,
+        ),
+        (Features::PIPELINE_CACHE, wgpu::Features::PIPELINE_CACHE),
     ];
     for (neutral, native) in mappings {
         if value.contains(neutral) {
@@ -1916,6 +2209,20 @@ fn convert_texture_format(value: TextureFormat) -> wgpu::TextureFormat {
         TextureFormat::Depth16Unorm => wgpu::TextureFormat::Depth16Unorm,
         TextureFormat::Depth24PlusStencil8 => wgpu::TextureFormat::Depth24PlusStencil8,
         TextureFormat::Depth32Float => wgpu::TextureFormat::Depth32Float,
+        TextureFormat::Bc1RgbaUnorm => wgpu::TextureFormat::Bc1RgbaUnorm,
+        TextureFormat::Bc1RgbaUnormSrgb => wgpu::TextureFormat::Bc1RgbaUnormSrgb,
+        TextureFormat::Bc3RgbaUnorm => wgpu::TextureFormat::Bc3RgbaUnorm,
+        TextureFormat::Bc3RgbaUnormSrgb => wgpu::TextureFormat::Bc3RgbaUnormSrgb,
+        TextureFormat::Bc7RgbaUnorm => wgpu::TextureFormat::Bc7RgbaUnorm,
+        TextureFormat::Bc7RgbaUnormSrgb => wgpu::TextureFormat::Bc7RgbaUnormSrgb,
+        TextureFormat::Astc4x4Unorm => wgpu::TextureFormat::Astc {
+            block: wgpu::AstcBlock::B4x4,
+            channel: wgpu::AstcChannel::Unorm,
+        },
+        TextureFormat::Astc4x4UnormSrgb => wgpu::TextureFormat::Astc {
+            block: wgpu::AstcBlock::B4x4,
+            channel: wgpu::AstcChannel::UnormSrgb,
+        },
         _ => panic!("texture format is not supported by the wgpu backend"),
     }
 }
@@ -1933,6 +2240,20 @@ fn convert_texture_format_from_wgpu(value: wgpu::TextureFormat) -> Option<Textur
         wgpu::TextureFormat::Depth16Unorm => TextureFormat::Depth16Unorm,
         wgpu::TextureFormat::Depth24PlusStencil8 => TextureFormat::Depth24PlusStencil8,
         wgpu::TextureFormat::Depth32Float => TextureFormat::Depth32Float,
+        wgpu::TextureFormat::Bc1RgbaUnorm => TextureFormat::Bc1RgbaUnorm,
+        wgpu::TextureFormat::Bc1RgbaUnormSrgb => TextureFormat::Bc1RgbaUnormSrgb,
+        wgpu::TextureFormat::Bc3RgbaUnorm => TextureFormat::Bc3RgbaUnorm,
+        wgpu::TextureFormat::Bc3RgbaUnormSrgb => TextureFormat::Bc3RgbaUnormSrgb,
+        wgpu::TextureFormat::Bc7RgbaUnorm => TextureFormat::Bc7RgbaUnorm,
+        wgpu::TextureFormat::Bc7RgbaUnormSrgb => TextureFormat::Bc7RgbaUnormSrgb,
+        wgpu::TextureFormat::Astc {
+            block: wgpu::AstcBlock::B4x4,
+            channel: wgpu::AstcChannel::Unorm,
+        } => TextureFormat::Astc4x4Unorm,
+        wgpu::TextureFormat::Astc {
+            block: wgpu::AstcBlock::B4x4,
+            channel: wgpu::AstcChannel::UnormSrgb,
+        } => TextureFormat::Astc4x4UnormSrgb,
         _ => return None,
     })
 }
@@ -2016,19 +2337,23 @@ fn convert_binding_type(value: &BindingType) -> wgpu::BindingType {
                 TextureSampleType::Uint => wgpu::TextureSampleType::Uint,
                 TextureSampleType::Depth => wgpu::TextureSampleType::Depth,
             },
-            view_dimension: match view_dimension {
-                TextureViewDimension::D1 => wgpu::TextureViewDimension::D1,
-                TextureViewDimension::D2 => wgpu::TextureViewDimension::D2,
-                TextureViewDimension::D2Array => wgpu::TextureViewDimension::D2Array,
-                TextureViewDimension::Cube => wgpu::TextureViewDimension::Cube,
-                TextureViewDimension::CubeArray => wgpu::TextureViewDimension::CubeArray,
-                TextureViewDimension::D3 => wgpu::TextureViewDimension::D3,
-            },
+            view_dimension: convert_view_dimension(*view_dimension),
             multisampled: *multisampled,
         },
     }
 }
 
+fn convert_view_dimension(value: TextureViewDimension) -> wgpu::TextureViewDimension {
+    match value {
+        TextureViewDimension::D1 => wgpu::TextureViewDimension::D1,
+        TextureViewDimension::D2 => wgpu::TextureViewDimension::D2,
+        TextureViewDimension::D2Array => wgpu::TextureViewDimension::D2Array,
+        TextureViewDimension::Cube => wgpu::TextureViewDimension::Cube,
+        TextureViewDimension::CubeArray => wgpu::TextureViewDimension::CubeArray,
+        TextureViewDimension::D3 => wgpu::TextureViewDimension::D3,
+    }
+}
+
 fn convert_vertex_format(value: VertexFormat) -> wgpu::VertexFormat {
     match value {
         VertexFormat::Float32x2 => wgpu::VertexFormat::Float32x2,
@@ -2079,6 +2404,10 @@ fn convert_blend_component(value: BlendComponent) -> wgpu::BlendComponent {
             BlendFactor::OneMinusSrcAlpha => wgpu::BlendFactor::OneMinusSrcAlpha,
             BlendFactor::DstAlpha => wgpu::BlendFactor::DstAlpha,
             BlendFactor::OneMinusDstAlpha => wgpu::BlendFactor::OneMinusDstAlpha,
+            BlendFactor::Src => wgpu::BlendFactor::Src,
+            BlendFactor::OneMinusSrc => wgpu::BlendFactor::OneMinusSrc,
+            BlendFactor::Constant => wgpu::BlendFactor::Constant,
+            BlendFactor::OneMinusConstant => wgpu::BlendFactor::OneMinusConstant,
         },
         dst_factor: match value.dst_factor {
             BlendFactor::Zero => wgpu::BlendFactor::Zero,
@@ -2087,6 +2416,10 @@ fn convert_blend_component(value: BlendComponent) -> wgpu::BlendComponent {
             BlendFactor::OneMinusSrcAlpha => wgpu::BlendFactor::OneMinusSrcAlpha,
             BlendFactor::DstAlpha => wgpu::BlendFactor::DstAlpha,
             BlendFactor::OneMinusDstAlpha => wgpu::BlendFactor::OneMinusDstAlpha,
+            BlendFactor::Src => wgpu::BlendFactor::Src,
+            BlendFactor::OneMinusSrc => wgpu::BlendFactor::OneMinusSrc,
+            BlendFactor::Constant => wgpu::BlendFactor::Constant,
+            BlendFactor::OneMinusConstant => wgpu::BlendFactor::OneMinusConstant,
         },
         operation: match value.operation {
             BlendOperation::Add => wgpu::BlendOperation::Add,
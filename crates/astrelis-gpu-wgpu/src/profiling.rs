@@ -4,22 +4,40 @@ use astrelis_gpu::{CommandEncoder, Device, Features, GpuError, GraphicsApi, Poll
 use astrelis_profiling::{
     Profiler,
     data::GpuLaneId,
-    gpu::{GpuBackend, GpuFrame, GpuScope},
+    gpu::{GpuBackend, GpuClockSample, GpuFrame, GpuScope},
+};
+use std::{
+    sync::mpsc::{Receiver, TryRecvError},
+    time::{Duration, Instant},
 };
-use std::time::{Duration, Instant};
 
 use crate::{WgpuCommandEncoder, WgpuDeviceExt, WgpuQueueExt};
 
+/// How often the lane's CPU-to-GPU clock offset is measured again.
+const CALIBRATION_INTERVAL: Duration = Duration::from_secs(5);
+
 /// Per-queue wgpu timestamp collector.
 ///
 /// Metal is rejected while wgpu issue #9414 remains unresolved. On macOS,
 /// select Vulkan and install MoltenVK when GPU profiling is required.
+///
+/// The lane's clock is calibrated when the collector is created, which waits
+/// for the queue once, and every few seconds after from timestamps read back
+/// on a later frame.
 pub struct WgpuGpuProfiler {
     inner: wgpu_profiler::GpuProfiler,
     lane: GpuLaneId,
+    calibration: Option<Calibration>,
     last_calibration: Instant,
 }
 
+/// Two back-to-back timestamps on their way back to the CPU.
+struct Calibration {
+    readback: wgpu::Buffer,
+    mapped: Receiver<Result<(), wgpu::BufferAsyncError>>,
+    submitted_ns: u64,
+}
+
 impl WgpuGpuProfiler {
     /// Creates and calibrates a timestamp collector for a device queue.
     pub fn new(device: &Device, queue: &Queue, label: Option<&str>) -> Result<Self, GpuError> {
@@ -41,10 +59,16 @@ impl WgpuGpuProfiler {
         let raw_queue = queue
             .as_wgpu()
             .ok_or_else(|| GpuError::new("queue does not use the wgpu backend"))?;
+        let mut calibration = Calibration::submit(raw_device, raw_queue);
+        raw_device
+            .poll(wgpu::PollType::wait_indefinitely())
+            .map_err(|error| GpuError::new(error.to_string()))?;
+        let sample = calibration
+            .poll(raw_queue)?
+            .ok_or_else(|| GpuError::new("timestamp calibration readback did not complete"))?;
         let lane =
             astrelis_profiling::gpu::register_gpu_lane(convert_backend(capabilities.api), label);
-        let offset = calibrate(raw_device, raw_queue)?;
-        astrelis_profiling::gpu::set_gpu_lane_offset_ns(lane, offset);
+        astrelis_profiling::gpu::calibrate_gpu_lane(lane, sample);
         let inner = wgpu_profiler::GpuProfiler::new(
             raw_device,
             wgpu_profiler::GpuProfilerSettings::default(),
@@ -53,6 +77,7 @@ impl WgpuGpuProfiler {
         Ok(Self {
             inner,
             lane,
+            calibration: None,
             last_calibration: Instant::now(),
         })
     }
@@ -95,23 +120,16 @@ impl WgpuGpuProfiler {
     }
 
     /// Processes all completed frames and forwards them to `astrelis-profiling`.
+    ///
+    /// Every few seconds this also submits a clock calibration, which a later
+    /// call applies once its timestamps have been read back.
     pub fn process_finished_frames(
         &mut self,
         device: &Device,
         queue: &Queue,
     ) -> Result<usize, GpuError> {
-        if self.last_calibration.elapsed() >= Duration::from_secs(5) {
-            let raw_device = device
-                .as_wgpu()
-                .ok_or_else(|| GpuError::new("device does not use the wgpu backend"))?;
-            let raw_queue = queue
-                .as_wgpu()
-                .ok_or_else(|| GpuError::new("queue does not use the wgpu backend"))?;
-            let offset = calibrate(raw_device, raw_queue)?;
-            astrelis_profiling::gpu::set_gpu_lane_offset_ns(self.lane, offset);
-            self.last_calibration = Instant::now();
-        }
         device.poll(PollMode::Poll)?;
+        self.recalibrate(device, queue)?;
         let mut processed = 0;
         while let Some(results) = self.inner.process_finished_frame(queue.timestamp_period()) {
             let scopes = results.into_iter().filter_map(convert_scope).collect();
@@ -120,6 +138,33 @@ impl WgpuGpuProfiler {
         }
         Ok(processed)
     }
+
+    /// Applies a finished calibration, or submits the next one when due.
+    fn recalibrate(&mut self, device: &Device, queue: &Queue) -> Result<(), GpuError> {
+        let raw_queue = queue
+            .as_wgpu()
+            .ok_or_else(|| GpuError::new("queue does not use the wgpu backend"))?;
+        match &mut self.calibration {
+            Some(calibration) => {
+                let sample = calibration.poll(raw_queue);
+                if !matches!(sample, Ok(None)) {
+                    self.calibration = None;
+                    self.last_calibration = Instant::now();
+                }
+                if let Some(sample) = sample? {
+                    astrelis_profiling::gpu::calibrate_gpu_lane(self.lane, sample);
+                }
+            }
+            None if self.last_calibration.elapsed() >= CALIBRATION_INTERVAL => {
+                let raw_device = device
+                    .as_wgpu()
+                    .ok_or_else(|| GpuError::new("device does not use the wgpu backend"))?;
+                self.calibration = Some(Calibration::submit(raw_device, raw_queue));
+            }
+            None => {}
+        }
+        Ok(())
+    }
 }
 
 fn raw_encoder(encoder: &mut CommandEncoder) -> Result<&mut wgpu::CommandEncoder, GpuError> {
@@ -156,75 +201,89 @@ fn convert_backend(api: GraphicsApi) -> GpuBackend {
     }
 }
 
-fn calibrate(device: &wgpu::Device, queue: &wgpu::Queue) -> Result<i64, GpuError> {
-    let queries = device.create_query_set(&wgpu::QuerySetDescriptor {
-        label: Some("Astrelis GPU clock calibration"),
-        ty: wgpu::QueryType::Timestamp,
-        count: 2,
-    });
-    let resolve = device.create_buffer(&wgpu::BufferDescriptor {
-        label: Some("Astrelis GPU clock calibration resolve"),
-        size: 16,
-        usage: wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
-        mapped_at_creation: false,
-    });
-    let readback = device.create_buffer(&wgpu::BufferDescriptor {
-        label: Some("Astrelis GPU clock calibration readback"),
-        size: 16,
-        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
-        mapped_at_creation: false,
-    });
-    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
-        label: Some("Astrelis GPU clock calibration"),
-    });
-    {
-        let _pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-            label: Some("Astrelis GPU clock calibration pass"),
-            color_attachments: &[],
-            depth_stencil_attachment: None,
-            timestamp_writes: Some(wgpu::RenderPassTimestampWrites {
-                query_set: &queries,
-                beginning_of_pass_write_index: Some(0),
-                end_of_pass_write_index: Some(1),
-            }),
-            occlusion_query_set: None,
-            multiview_mask: None,
+impl Calibration {
+    /// Submits two back-to-back timestamps and starts mapping them for
+    /// reading.
+    fn submit(device: &wgpu::Device, queue: &wgpu::Queue) -> Self {
+        let queries = device.create_query_set(&wgpu::QuerySetDescriptor {
+            label: Some("Astrelis GPU clock calibration"),
+            ty: wgpu::QueryType::Timestamp,
+            count: 2,
+        });
+        let resolve = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Astrelis GPU clock calibration resolve"),
+            size: 16,
+            usage: wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
         });
+        let readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Astrelis GPU clock calibration readback"),
+            size: 16,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Astrelis GPU clock calibration"),
+        });
+        {
+            let _pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Astrelis GPU clock calibration pass"),
+                color_attachments: &[],
+                depth_stencil_attachment: None,
+                timestamp_writes: Some(wgpu::RenderPassTimestampWrites {
+                    query_set: &queries,
+                    beginning_of_pass_write_index: Some(0),
+                    end_of_pass_write_index: Some(1),
+                }),
+                occlusion_query_set: None,
+                multiview_mask: None,
+            });
+        }
+        encoder.resolve_query_set(&queries, 0..2, &resolve, 0);
+        encoder.copy_buffer_to_buffer(&resolve, 0, &readback, 0, 16);
+        let submitted_ns = Profiler::get().clock.now_ns();
+        queue.submit([encoder.finish()]);
+        let (sender, mapped) = std::sync::mpsc::sync_channel(1);
+        readback
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                let _ = sender.send(result);
+            });
+        Self {
+            readback,
+            mapped,
+            submitted_ns,
+        }
     }
-    encoder.resolve_query_set(&queries, 0..2, &resolve, 0);
-    encoder.copy_buffer_to_buffer(&resolve, 0, &readback, 0, 16);
-    let cpu_start = Profiler::get().clock.now_ns();
-    queue.submit([encoder.finish()]);
-    device
-        .poll(wgpu::PollType::wait_indefinitely())
-        .map_err(|error| GpuError::new(error.to_string()))?;
-    let cpu_end = Profiler::get().clock.now_ns();
 
-    let (sender, receiver) = std::sync::mpsc::sync_channel(1);
-    readback
-        .slice(..)
-        .map_async(wgpu::MapMode::Read, move |result| {
-            let _ = sender.send(result);
-        });
-    device
-        .poll(wgpu::PollType::wait_indefinitely())
-        .map_err(|error| GpuError::new(error.to_string()))?;
-    receiver
-        .recv()
-        .map_err(|_| GpuError::new("calibration mapping callback was dropped"))?
-        .map_err(|error| GpuError::new(error.to_string()))?;
-    let bytes = readback.slice(..).get_mapped_range();
-    let start = u64::from_le_bytes(bytes[0..8].try_into().expect("timestamp byte count"));
-    let end = u64::from_le_bytes(bytes[8..16].try_into().expect("timestamp byte count"));
-    drop(bytes);
-    readback.unmap();
-    if start == 0 || end < start {
-        return Err(GpuError::new(
-            "backend returned invalid timestamp calibration samples",
-        ));
+    /// The calibration sample once the timestamps have been read back,
+    /// bracketed by the submission and this call.
+    fn poll(&mut self, queue: &wgpu::Queue) -> Result<Option<GpuClockSample>, GpuError> {
+        let status = match self.mapped.try_recv() {
+            Ok(status) => status,
+            Err(TryRecvError::Empty) => return Ok(None),
+            Err(TryRecvError::Disconnected) => {
+                return Err(GpuError::new("calibration mapping callback was dropped"));
+            }
+        };
+        let read_ns = Profiler::get().clock.now_ns();
+        status.map_err(|error| GpuError::new(error.to_string()))?;
+        let bytes = self.readback.slice(..).get_mapped_range();
+        let start = u64::from_le_bytes(bytes[0..8].try_into().expect("timestamp byte count"));
+        let end = u64::from_le_bytes(bytes[8..16].try_into().expect("timestamp byte count"));
+        drop(bytes);
+        self.readback.unmap();
+        if start == 0 || end < start {
+            return Err(GpuError::new(
+                "backend returned invalid timestamp calibration samples",
+            ));
+        }
+        let gpu_ns = (((start as u128 + end as u128) / 2) as f64
+            * queue.get_timestamp_period() as f64) as u64;
+        Ok(Some(GpuClockSample {
+            cpu_before_ns: self.submitted_ns,
+            cpu_after_ns: read_ns,
+            gpu_ns,
+        }))
     }
-    let gpu_mid_ns =
-        (((start as u128 + end as u128) / 2) as f64 * queue.get_timestamp_period() as f64) as i128;
-    let cpu_mid_ns = ((cpu_start as u128 + cpu_end as u128) / 2) as i128;
-    Ok((cpu_mid_ns - gpu_mid_ns).clamp(i64::MIN as i128, i64::MAX as i128) as i64)
 }
@@ -181,6 +181,7 @@ fn draws_a_triangle_and_reads_back_a_pixel() {
                 }),
                 depth_stencil: None,
                 multisample: Default::default(),
+                cache: None,
             })
             .expect("pipeline");
 
@@ -330,6 +331,7 @@ fn compute_pipeline_writes_through_a_bind_group() {
                 layout: Some(pipeline_layout),
                 module: shader,
                 entry_point: "main".into(),
+                cache: None,
             })
             .expect("compute pipeline");
         let mut encoder = device.create_command_encoder(CommandEncoderDescriptor::default());
@@ -359,3 +361,44 @@ fn compute_pipeline_writes_through_a_bind_group() {
         );
     });
 }
+
+#[test]
+fn headless_context_renders_without_a_surface() {
+    let _guard = gpu_test_lock().lock().expect("GPU test lock poisoned");
+    let context = match astrelis_gpu_wgpu::HeadlessContext::new_blocking(Default::default()) {
+        Ok(context) => context,
+        Err(error) => {
+            eprintln!("skipping GPU integration test: {error}");
+            return;
+        }
+    };
+    let target = context.create_target(3, 2, TextureFormat::Rgba8Unorm);
+    let mut encoder = context
+        .device()
+        .create_command_encoder(CommandEncoderDescriptor::default());
+    encoder
+        .render_pass(RenderPassDescriptor {
+            label: Some("clear blue".into()),
+            color_attachments: vec![Some(RenderPassColorAttachment {
+                view: target.create_view(TextureViewDescriptor::default()),
+                resolve_target: None,
+                load: LoadOp::Clear(Color {
+                    r: 0.0,
+                    g: 0.0,
+                    b: 1.0,
+                    a: 1.0,
+                }),
+                store: StoreOp::Store,
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+        })
+        .expect("record clear");
+    context
+        .queue()
+        .submit([encoder.finish().expect("finish encoder")])
+        .expect("submit");
+    let pixels = pollster::block_on(context.read_texture(&target, TextureFormat::Rgba8Unorm, 3, 2))
+        .expect("read back target");
+    assert_eq!(pixels, [0, 0, 255, 255].repeat(6));
+}
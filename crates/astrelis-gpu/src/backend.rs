@@ -4,12 +4,14 @@ use std::{any::Any, fmt::Debug, ops::Range, sync::Arc};
 
 use crate::{
     AdapterInfo, BindGroupDescriptor, BindGroupLayoutDescriptor, BufferDescriptor,
-    BufferTextureCopy, CommandEncoderDescriptor, ComputePassDescriptor, ComputePipelineDescriptor,
-    DeviceCapabilities, DeviceDescriptor, DeviceError, DeviceId, Extent3d, Features, GpuError,
-    Limits, MapMode, PipelineLayoutDescriptor, PollMode, QuerySetDescriptor, RenderPassDescriptor,
+    BufferTextureCopy, Color, CommandEncoderDescriptor, ComputePassDescriptor,
+    ComputePipelineDescriptor, DeviceCapabilities, DeviceDescriptor, DeviceError, DeviceId,
+    Extent3d, Features, GpuError, Limits, MapMode, PipelineCacheDescriptor,
+    PipelineLayoutDescriptor, PollMode, QuerySetDescriptor, RenderPassDescriptor,
     RenderPipelineDescriptor, RequestAdapterOptions, SamplerDescriptor, ShaderModuleDescriptor,
     SurfaceCapabilities, SurfaceConfiguration, SurfaceFrameStatus, TextureCopy, TextureDataLayout,
     TextureDescriptor, TextureDimension, TextureFormat, TextureViewDescriptor,
+    TextureViewDimension,
 };
 
 /// Boxed backend future.
@@ -61,6 +63,8 @@ pub trait Device: NativeHandle {
     fn set_error_handler(&self, handler: Arc<dyn Fn(DeviceError) + Send + Sync>);
     /// Advances callbacks and mapping.
     fn poll(&self, mode: PollMode) -> Result<(), GpuError>;
+    /// Returns whether `format` can be rendered with `count` samples.
+    fn supports_sample_count(&self, format: TextureFormat, count: u32) -> bool;
     /// Creates a buffer.
     fn create_buffer(&self, descriptor: BufferDescriptor) -> Arc<dyn Buffer>;
     /// Creates a texture.
@@ -69,6 +73,15 @@ pub trait Device: NativeHandle {
     fn create_sampler(&self, descriptor: SamplerDescriptor) -> Arc<dyn Sampler>;
     /// Creates a WGSL shader module.
     fn create_shader_module(&self, descriptor: ShaderModuleDescriptor) -> Arc<dyn ShaderModule>;
+    /// Creates a WGSL shader module inside a validation error scope and
+    /// resolves once compilation has finished.
+    fn create_shader_module_checked(
+        &self,
+        descriptor: ShaderModuleDescriptor,
+    ) -> BackendFuture<Result<Arc<dyn ShaderModule>, GpuError>>;
+    /// Runs `body` inside a validation error scope, resolving to the first
+    /// validation error it raised.
+    fn validation_scope(&self, body: &mut dyn FnMut()) -> BackendFuture<Option<GpuError>>;
     /// Creates a bind-group layout.
     fn create_bind_group_layout(
         &self,
@@ -86,6 +99,11 @@ pub trait Device: NativeHandle {
         &self,
         descriptor: RenderPipelineDescriptor,
     ) -> Arc<dyn RenderPipeline>;
+    /// Key identifying adapters whose pipeline cache data is interchangeable,
+    /// or `None` when caches cannot be persisted.
+    fn pipeline_cache_key(&self) -> Option<String>;
+    /// Creates a pipeline cache, requiring [`Features::PIPELINE_CACHE`].
+    fn create_pipeline_cache(&self, descriptor: PipelineCacheDescriptor) -> Arc<dyn PipelineCache>;
     /// Creates a query set.
     fn create_query_set(&self, descriptor: QuerySetDescriptor) -> Arc<dyn QuerySet>;
     /// Creates a compute pipeline.
@@ -175,6 +193,8 @@ pub trait TextureView: NativeHandle {
     fn sample_count(&self) -> u32;
     /// Texture dimension represented by this view.
     fn dimension(&self) -> TextureDimension;
+    /// Shape of the view as seen by a shader.
+    fn view_dimension(&self) -> TextureViewDimension;
     /// Pixel format represented by this view.
     fn format(&self) -> TextureFormat;
 }
@@ -189,6 +209,8 @@ pub trait Sampler: NativeHandle {
 pub trait ShaderModule: NativeHandle {
     /// Owning device.
     fn device_id(&self) -> DeviceId;
+    /// Compilation errors known at creation time.
+    fn compilation_errors(&self) -> Vec<String>;
 }
 
 /// Backend render pipeline.
@@ -221,6 +243,14 @@ pub trait ComputePipeline: NativeHandle {
     fn device_id(&self) -> DeviceId;
 }
 
+/// Backend pipeline cache.
+pub trait PipelineCache: NativeHandle {
+    /// Owning device.
+    fn device_id(&self) -> DeviceId;
+    /// Serialized cache contents, when the driver provides them.
+    fn data(&self) -> Option<Vec<u8>>;
+}
+
 /// Backend query set.
 pub trait QuerySet: NativeHandle {
     /// Owning device.
@@ -252,6 +282,13 @@ pub trait CommandEncoder: Debug + Send {
         destination_offset: u64,
         size: u64,
     ) -> Result<(), GpuError>;
+    /// Copies buffer bytes into texture texels.
+    fn copy_buffer_to_texture(
+        &mut self,
+        source: &BufferTextureCopy,
+        destination: &TextureCopy,
+        extent: Extent3d,
+    ) -> Result<(), GpuError>;
     /// Copies texture texels into a buffer.
     fn copy_texture_to_buffer(
         &mut self,
@@ -267,10 +304,14 @@ pub trait CommandEncoder: Debug + Send {
         destination: &dyn Buffer,
         destination_offset: u64,
     ) -> Result<(), GpuError>;
+    /// Writes a timestamp between passes.
+    fn write_timestamp(&mut self, query_set: &dyn QuerySet, index: u32) -> Result<(), GpuError>;
     /// Adds a debug group.
     fn push_debug_group(&mut self, label: &str);
     /// Removes a debug group.
     fn pop_debug_group(&mut self);
+    /// Inserts a single debug marker.
+    fn insert_debug_marker(&mut self, label: &str);
     /// Finishes recording.
     fn finish(self: Box<Self>) -> Result<Box<dyn CommandBuffer>, GpuError>;
 }
@@ -304,6 +345,13 @@ pub trait RenderPass: Debug {
     fn draw(&mut self, vertices: Range<u32>, instances: Range<u32>);
     /// Draws indexed vertices and instances.
     fn draw_indexed(&mut self, indices: Range<u32>, base_vertex: i32, instances: Range<u32>);
+    /// Draws indexed primitives from tightly packed indirect arguments.
+    fn multi_draw_indexed_indirect(
+        &mut self,
+        buffer: &dyn Buffer,
+        offset: u64,
+        count: u32,
+    ) -> Result<(), GpuError>;
     /// Sets the rasterization scissor rectangle.
     fn set_scissor_rect(&mut self, x: u32, y: u32, width: u32, height: u32);
     /// Sets the rasterization viewport and depth range.
@@ -318,6 +366,14 @@ pub trait RenderPass: Debug {
     );
     /// Sets the dynamic stencil reference.
     fn set_stencil_reference(&mut self, reference: u32);
+    /// Sets the dynamic blend constant.
+    fn set_blend_constant(&mut self, color: Color);
+    /// Adds a debug group.
+    fn push_debug_group(&mut self, label: &str);
+    /// Removes a debug group.
+    fn pop_debug_group(&mut self);
+    /// Inserts a single debug marker.
+    fn insert_debug_marker(&mut self, label: &str);
 }
 
 /// Backend compute pass commands.
@@ -333,6 +389,12 @@ pub trait ComputePass: Debug {
     ) -> Result<(), GpuError>;
     /// Dispatches workgroups.
     fn dispatch_workgroups(&mut self, x: u32, y: u32, z: u32);
+    /// Adds a debug group.
+    fn push_debug_group(&mut self, label: &str);
+    /// Removes a debug group.
+    fn pop_debug_group(&mut self);
+    /// Inserts a single debug marker.
+    fn insert_debug_marker(&mut self, label: &str);
 }
 
 /// Backend command buffer.
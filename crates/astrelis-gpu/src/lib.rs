@@ -101,6 +101,7 @@ shared_handle!(Sampler, backend::Sampler);
 shared_handle!(ShaderModule, backend::ShaderModule);
 shared_handle!(RenderPipeline, backend::RenderPipeline);
 shared_handle!(QuerySet, backend::QuerySet);
+shared_handle!(PipelineCache, backend::PipelineCache);
 shared_handle!(BindGroupLayout, backend::BindGroupLayout);
 shared_handle!(PipelineLayout, backend::PipelineLayout);
 shared_handle!(BindGroup, backend::BindGroup);
@@ -211,6 +212,15 @@ impl Device {
         self.inner.capabilities()
     }
 
+    /// Returns whether `format` can be rendered with `count` samples.
+    ///
+    /// Four samples are available for most renderable formats. Other counts
+    /// require [`Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES`] and
+    /// adapter support.
+    pub fn supports_sample_count(&self, format: TextureFormat, count: u32) -> bool {
+        self.inner.supports_sample_count(format, count)
+    }
+
     /// Installs a handler for asynchronous validation and device errors.
     pub fn set_error_handler(&self, handler: impl Fn(DeviceError) + Send + Sync + 'static) {
         self.inner.set_error_handler(Arc::new(handler));
@@ -268,6 +278,39 @@ impl Device {
         }
     }
 
+    /// Creates a shader module and waits for compilation to finish.
+    ///
+    /// Compilation and validation errors are captured by an error scope and
+    /// returned here, formatted with their source line and column, instead
+    /// of also reaching the device error handler.
+    pub async fn create_shader_module_checked(
+        &self,
+        descriptor: ShaderModuleDescriptor,
+    ) -> Result<ShaderModule, GpuError> {
+        self.inner
+            .create_shader_module_checked(descriptor)
+            .await
+            .map(|inner| ShaderModule { inner })
+    }
+
+    /// Runs `body` inside a validation error scope and returns its result,
+    /// or the first validation error raised while it ran, for example by
+    /// [`Device::create_render_pipeline`]. A captured error does not also
+    /// reach the device error handler.
+    pub async fn validated<R>(&self, body: impl FnOnce() -> R) -> Result<R, GpuError> {
+        let mut body = Some(body);
+        let mut result = None;
+        let scope = self.inner.validation_scope(&mut || {
+            if let Some(body) = body.take() {
+                result = Some(body());
+            }
+        });
+        match scope.await {
+            Some(error) => Err(error),
+            None => Ok(result.expect("validation scope runs its body")),
+        }
+    }
+
     /// Creates a bind-group layout.
     pub fn create_bind_group_layout(
         &self,
@@ -322,11 +365,47 @@ impl Device {
         if let Some(fragment) = &descriptor.fragment {
             ensure_device(self.id(), fragment.module.device_id())?;
         }
+        if let Some(cache) = &descriptor.cache {
+            ensure_device(self.id(), cache.device_id())?;
+        }
         Ok(RenderPipeline {
             inner: self.inner.create_render_pipeline(descriptor),
         })
     }
 
+    /// Key identifying adapters and drivers that can share pipeline cache
+    /// data, suitable for naming a cache file. `None` means this device
+    /// cannot persist pipeline caches.
+    pub fn pipeline_cache_key(&self) -> Option<String> {
+        if !self
+            .capabilities()
+            .features
+            .contains(Features::PIPELINE_CACHE)
+        {
+            return None;
+        }
+        self.inner.pipeline_cache_key()
+    }
+
+    /// Creates a driver pipeline cache, optionally seeded with saved data.
+    pub fn create_pipeline_cache(
+        &self,
+        descriptor: PipelineCacheDescriptor,
+    ) -> Result<PipelineCache, GpuError> {
+        if !self
+            .capabilities()
+            .features
+            .contains(Features::PIPELINE_CACHE)
+        {
+            return Err(GpuError::new(
+                "pipeline caches require Features::PIPELINE_CACHE",
+            ));
+        }
+        Ok(PipelineCache {
+            inner: self.inner.create_pipeline_cache(descriptor),
+        })
+    }
+
     /// Creates a GPU query set.
     pub fn create_query_set(&self, descriptor: QuerySetDescriptor) -> QuerySet {
         QuerySet {
@@ -343,6 +422,9 @@ impl Device {
         if let Some(layout) = &descriptor.layout {
             ensure_device(self.id(), layout.device_id())?;
         }
+        if let Some(cache) = &descriptor.cache {
+            ensure_device(self.id(), cache.device_id())?;
+        }
         Ok(ComputePipeline {
             inner: self.inner.create_compute_pipeline(descriptor),
         })
@@ -531,6 +613,17 @@ impl Buffer {
 }
 
 impl Sampler {
+    /// Reports whether two handles refer to the same underlying sampler.
+    pub fn same_resource(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+
+    /// Identity consistent with [`Sampler::same_resource`], for hashing.
+    /// Only unique while some handle to the sampler is alive.
+    pub fn resource_key(&self) -> usize {
+        Arc::as_ptr(&self.inner).cast::<()>() as usize
+    }
+
     /// Stable identifier of the owning device.
     pub fn device_id(&self) -> DeviceId {
         self.inner.device_id()
@@ -564,6 +657,17 @@ impl Texture {
 }
 
 impl TextureView {
+    /// Reports whether two handles refer to the same underlying view.
+    pub fn same_resource(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+
+    /// Identity consistent with [`TextureView::same_resource`], for hashing.
+    /// Only unique while some handle to the view is alive.
+    pub fn resource_key(&self) -> usize {
+        Arc::as_ptr(&self.inner).cast::<()>() as usize
+    }
+
     /// Stable identifier of the owning device.
     pub fn device_id(&self) -> DeviceId {
         self.inner.device_id()
@@ -579,6 +683,12 @@ impl TextureView {
         self.inner.dimension()
     }
 
+    /// Shape of the view as seen by a shader, such as a cube over a 2D
+    /// array texture.
+    pub fn view_dimension(&self) -> TextureViewDimension {
+        self.inner.view_dimension()
+    }
+
     /// Pixel format represented by this view.
     pub fn format(&self) -> TextureFormat {
         self.inner.format()
@@ -597,6 +707,20 @@ impl ShaderModule {
         self.inner.device_id()
     }
 
+    /// Compilation errors, formatted with their source line and column.
+    ///
+    /// Native backends compile during creation and report every error here.
+    /// Browser backends compile asynchronously, so this may be empty even
+    /// for invalid source; those errors still reach the device error handler.
+    pub fn compilation_errors(&self) -> Vec<String> {
+        self.inner.compilation_errors()
+    }
+
+    /// Reports whether two handles refer to the same underlying module.
+    pub fn same_resource(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+
     /// Borrows unstable backend storage.
     #[doc(hidden)]
     pub fn backend(&self) -> &dyn backend::ShaderModule {
@@ -610,6 +734,11 @@ impl RenderPipeline {
         self.inner.device_id()
     }
 
+    /// Reports whether two handles refer to the same underlying pipeline.
+    pub fn same_resource(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+
     /// Borrows unstable backend storage.
     #[doc(hidden)]
     pub fn backend(&self) -> &dyn backend::RenderPipeline {
@@ -630,6 +759,24 @@ impl QuerySet {
     }
 }
 
+impl PipelineCache {
+    /// Stable identifier of the owning device.
+    pub fn device_id(&self) -> DeviceId {
+        self.inner.device_id()
+    }
+
+    /// Serialized cache contents for saving to disk, when available.
+    pub fn data(&self) -> Option<Vec<u8>> {
+        self.inner.data()
+    }
+
+    /// Borrows unstable backend storage.
+    #[doc(hidden)]
+    pub fn backend(&self) -> &dyn backend::PipelineCache {
+        self.inner.as_ref()
+    }
+}
+
 macro_rules! device_owned_handle {
     ($name:ident, $backend:path) => {
         impl $name {
@@ -649,6 +796,13 @@ macro_rules! device_owned_handle {
 
 device_owned_handle!(BindGroupLayout, backend::BindGroupLayout);
 device_owned_handle!(PipelineLayout, backend::PipelineLayout);
+
+impl PipelineLayout {
+    /// Reports whether two handles refer to the same underlying layout.
+    pub fn same_resource(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
 device_owned_handle!(BindGroup, backend::BindGroup);
 device_owned_handle!(ComputePipeline, backend::ComputePipeline);
 
@@ -741,6 +895,22 @@ impl CommandEncoder {
         )
     }
 
+    /// Copies buffer bytes into texture texels.
+    pub fn copy_buffer_to_texture(
+        &mut self,
+        source: &BufferTextureCopy,
+        destination: &TextureCopy,
+        extent: Extent3d,
+    ) -> Result<(), GpuError> {
+        let inner = self
+            .inner
+            .as_mut()
+            .ok_or_else(|| GpuError::new("encoder was already finished"))?;
+        ensure_device(inner.device_id(), source.buffer.device_id())?;
+        ensure_device(inner.device_id(), destination.texture.device_id())?;
+        inner.copy_buffer_to_texture(source, destination, extent)
+    }
+
     /// Copies texture texels into a buffer.
     pub fn copy_texture_to_buffer(
         &mut self,
@@ -779,6 +949,18 @@ impl CommandEncoder {
         )
     }
 
+    /// Writes a timestamp query outside any pass.
+    ///
+    /// Requires [`Features::TIMESTAMP_QUERY_INSIDE_ENCODERS`].
+    pub fn write_timestamp(&mut self, query_set: &QuerySet, index: u32) -> Result<(), GpuError> {
+        let inner = self
+            .inner
+            .as_mut()
+            .ok_or_else(|| GpuError::new("encoder was already finished"))?;
+        ensure_device(inner.device_id(), query_set.device_id())?;
+        inner.write_timestamp(query_set.backend(), index)
+    }
+
     /// Pushes a backend debug group.
     pub fn push_debug_group(&mut self, label: &str) {
         if let Some(inner) = &mut self.inner {
@@ -793,6 +975,22 @@ impl CommandEncoder {
         }
     }
 
+    /// Inserts a single backend debug marker.
+    pub fn insert_debug_marker(&mut self, label: &str) {
+        if let Some(inner) = &mut self.inner {
+            inner.insert_debug_marker(label);
+        }
+    }
+
+    /// Records `record` inside a debug group, so captures in tools such as
+    /// RenderDoc or Xcode show its passes nested under `label`.
+    pub fn debug_group<R>(&mut self, label: &str, record: impl FnOnce(&mut Self) -> R) -> R {
+        self.push_debug_group(label);
+        let result = record(self);
+        self.pop_debug_group();
+        result
+    }
+
     /// Finishes recording.
     pub fn finish(mut self) -> Result<CommandBuffer, GpuError> {
         let inner = self
@@ -871,6 +1069,22 @@ impl RenderPass<'_> {
         self.inner.draw_indexed(indices, base_vertex, instances);
     }
 
+    /// Draws `count` indexed commands read from `buffer` at `offset`.
+    ///
+    /// Each command is five 32-bit values: index count, instance count, first
+    /// index, base vertex, and first instance. The buffer needs
+    /// [`BufferUsages::INDIRECT`].
+    pub fn multi_draw_indexed_indirect(
+        &mut self,
+        buffer: &Buffer,
+        offset: u64,
+        count: u32,
+    ) -> Result<(), GpuError> {
+        ensure_device(self.id, buffer.device_id())?;
+        self.inner
+            .multi_draw_indexed_indirect(buffer.inner_backend(), offset, count)
+    }
+
     /// Sets the rasterization scissor rectangle.
     pub fn set_scissor_rect(&mut self, x: u32, y: u32, width: u32, height: u32) {
         self.inner.set_scissor_rect(x, y, width, height);
@@ -894,6 +1108,26 @@ impl RenderPass<'_> {
     pub fn set_stencil_reference(&mut self, reference: u32) {
         self.inner.set_stencil_reference(reference);
     }
+
+    /// Sets the constant used by [`BlendFactor::Constant`] blend terms.
+    pub fn set_blend_constant(&mut self, color: Color) {
+        self.inner.set_blend_constant(color);
+    }
+
+    /// Pushes a backend debug group.
+    pub fn push_debug_group(&mut self, label: &str) {
+        self.inner.push_debug_group(label);
+    }
+
+    /// Pops a backend debug group.
+    pub fn pop_debug_group(&mut self) {
+        self.inner.pop_debug_group();
+    }
+
+    /// Inserts a single backend debug marker.
+    pub fn insert_debug_marker(&mut self, label: &str) {
+        self.inner.insert_debug_marker(label);
+    }
 }
 
 /// Commands recorded within a compute pass.
@@ -925,6 +1159,21 @@ impl ComputePass<'_> {
     pub fn dispatch_workgroups(&mut self, x: u32, y: u32, z: u32) {
         self.inner.dispatch_workgroups(x, y, z);
     }
+
+    /// Pushes a backend debug group.
+    pub fn push_debug_group(&mut self, label: &str) {
+        self.inner.push_debug_group(label);
+    }
+
+    /// Pops a backend debug group.
+    pub fn pop_debug_group(&mut self) {
+        self.inner.pop_debug_group();
+    }
+
+    /// Inserts a single backend debug marker.
+    pub fn insert_debug_marker(&mut self, label: &str) {
+        self.inner.insert_debug_marker(label);
+    }
 }
 
 impl fmt::Debug for ComputePass<'_> {
@@ -91,6 +91,11 @@ bitflags! {
         const MULTI_DRAW_INDIRECT_COUNT = 1 << 5;
         /// Polygon line mode.
         const POLYGON_MODE_LINE = 1 << 6;
+        /// Adapter-specific format capabilities, including multisample counts
+        /// other than 1 and 4.
+        const TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES = 1 << 7;
+        /// Driver pipeline caches whose data can be saved and reused.
+        const PIPELINE_CACHE = 1 << 8;
     }
 }
 
@@ -265,6 +270,105 @@ pub enum TextureFormat {
     Depth24PlusStencil8,
     /// 32-bit floating-point depth.
     Depth32Float,
+    /// BC1 compressed RGBA, 4x4 blocks of 8 bytes.
+    Bc1RgbaUnorm,
+    /// BC1 compressed sRGB RGBA.
+    Bc1RgbaUnormSrgb,
+    /// BC3 compressed RGBA, 4x4 blocks of 16 bytes.
+    Bc3RgbaUnorm,
+    /// BC3 compressed sRGB RGBA.
+    Bc3RgbaUnormSrgb,
+    /// BC7 compressed RGBA, 4x4 blocks of 16 bytes.
+    Bc7RgbaUnorm,
+    /// BC7 compressed sRGB RGBA.
+    Bc7RgbaUnormSrgb,
+    /// ASTC compressed RGBA, 4x4 blocks of 16 bytes.
+    Astc4x4Unorm,
+    /// ASTC compressed sRGB RGBA.
+    Astc4x4UnormSrgb,
+}
+
+impl TextureFormat {
+    /// Texel block width and height; `(1, 1)` for uncompressed formats.
+    pub fn block_dimensions(self) -> (u32, u32) {
+        if self.is_compressed() { (4, 4) } else { (1, 1) }
+    }
+
+    /// Bytes in one texel block, or `None` for depth/stencil formats whose
+    /// layout is implementation-defined.
+    pub fn block_copy_size(self) -> Option<u32> {
+        Some(match self {
+            Self::R8Unorm => 1,
+            Self::Rgba8Unorm | Self::Rgba8UnormSrgb | Self::Bgra8Unorm | Self::Bgra8UnormSrgb => 4,
+            Self::Rgba16Float | Self::Bc1RgbaUnorm | Self::Bc1RgbaUnormSrgb => 8,
+            Self::R32Float | Self::R32Uint => 4,
+            Self::Bc3RgbaUnorm
+            | Self::Bc3RgbaUnormSrgb
+            | Self::Bc7RgbaUnorm
+            | Self::Bc7RgbaUnormSrgb
+            | Self::Astc4x4Unorm
+            | Self::Astc4x4UnormSrgb => 16,
+            Self::Depth16Unorm | Self::Depth24PlusStencil8 | Self::Depth32Float => return None,
+        })
+    }
+
+    /// Whether texels are stored in compressed blocks.
+    pub fn is_compressed(self) -> bool {
+        self.required_features() != Features::empty()
+    }
+
+    /// Scalar category sampled from the format's color or depth aspect,
+    /// without optional filtering features.
+    pub fn sample_type(self) -> TextureSampleType {
+        match self {
+            Self::R32Float => TextureSampleType::UnfilterableFloat,
+            Self::R32Uint => TextureSampleType::Uint,
+            Self::Depth16Unorm | Self::Depth24PlusStencil8 | Self::Depth32Float => {
+                TextureSampleType::Depth
+            }
+            _ => TextureSampleType::Float,
+        }
+    }
+
+    /// Whether the format has a depth aspect.
+    pub fn has_depth(self) -> bool {
+        matches!(
+            self,
+            Self::Depth16Unorm | Self::Depth24PlusStencil8 | Self::Depth32Float
+        )
+    }
+
+    /// Whether the format has a stencil aspect.
+    pub fn has_stencil(self) -> bool {
+        matches!(self, Self::Depth24PlusStencil8)
+    }
+
+    /// Whether sampling decodes sRGB-encoded values to linear.
+    pub fn is_srgb(self) -> bool {
+        matches!(
+            self,
+            Self::Rgba8UnormSrgb
+                | Self::Bgra8UnormSrgb
+                | Self::Bc1RgbaUnormSrgb
+                | Self::Bc3RgbaUnormSrgb
+                | Self::Bc7RgbaUnormSrgb
+                | Self::Astc4x4UnormSrgb
+        )
+    }
+
+    /// Device features needed to create textures of this format.
+    pub fn required_features(self) -> Features {
+        match self {
+            Self::Bc1RgbaUnorm
+            | Self::Bc1RgbaUnormSrgb
+            | Self::Bc3RgbaUnorm
+            | Self::Bc3RgbaUnormSrgb
+            | Self::Bc7RgbaUnorm
+            | Self::Bc7RgbaUnormSrgb => Features::TEXTURE_COMPRESSION_BC,
+            Self::Astc4x4Unorm | Self::Astc4x4UnormSrgb => Features::TEXTURE_COMPRESSION_ASTC,
+            _ => Features::empty(),
+        }
+    }
 }
 
 /// Three-dimensional texel extent.
@@ -361,6 +465,9 @@ pub struct TextureViewDescriptor {
     pub label: Option<String>,
     /// Format reinterpretation.
     pub format: Option<TextureFormat>,
+    /// View dimension, such as a cube over six array layers, or the
+    /// texture's own dimension.
+    pub dimension: Option<TextureViewDimension>,
     /// First mip level.
     pub base_mip_level: u32,
     /// Number of mip levels, or all remaining levels.
@@ -630,7 +737,7 @@ pub enum VertexStepMode {
 }
 
 /// One shader vertex input.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct VertexAttribute {
     /// Byte offset within a vertex.
     pub offset: u64,
@@ -641,7 +748,7 @@ pub struct VertexAttribute {
 }
 
 /// One vertex buffer slot layout.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct VertexBufferLayout {
     /// Byte stride between elements.
     pub array_stride: u64,
@@ -687,7 +794,7 @@ pub enum Face {
 }
 
 /// Primitive assembly and rasterization settings.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct PrimitiveState {
     /// Primitive topology.
     pub topology: PrimitiveTopology,
@@ -725,7 +832,7 @@ bitflags! {
 }
 
 /// Fragment render target state.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct ColorTargetState {
     /// Target format.
     pub format: TextureFormat,
@@ -750,6 +857,14 @@ pub enum BlendFactor {
     DstAlpha,
     /// One minus destination alpha.
     OneMinusDstAlpha,
+    /// Source color, component-wise.
+    Src,
+    /// One minus source color, component-wise.
+    OneMinusSrc,
+    /// Pass blend constant, component-wise.
+    Constant,
+    /// One minus the pass blend constant, component-wise.
+    OneMinusConstant,
 }
 
 /// Blend arithmetic operation.
@@ -971,6 +1086,8 @@ pub struct RenderPipelineDescriptor {
     pub multisample: MultisampleState,
     /// Optional fragment stage.
     pub fragment: Option<FragmentState>,
+    /// Optional driver cache consulted and filled during compilation.
+    pub cache: Option<crate::PipelineCache>,
 }
 
 /// Compute pipeline creation settings.
@@ -984,6 +1101,8 @@ pub struct ComputePipelineDescriptor {
     pub module: crate::ShaderModule,
     /// Entry point.
     pub entry_point: String,
+    /// Optional driver cache consulted and filled during compilation.
+    pub cache: Option<crate::PipelineCache>,
 }
 
 /// RGBA render color in linear space.
@@ -1114,6 +1233,17 @@ pub struct QuerySetDescriptor {
     pub count: u32,
 }
 
+/// Pipeline cache creation settings.
+#[derive(Clone, Debug, Default)]
+pub struct PipelineCacheDescriptor {
+    /// Optional debug label.
+    pub label: Option<String>,
+    /// Data previously returned by [`crate::PipelineCache::data`] on an
+    /// adapter with the same [`crate::Device::pipeline_cache_key`]. Data the
+    /// driver rejects is ignored and the cache starts empty.
+    pub data: Option<Vec<u8>>,
+}
+
 /// Timestamp writes associated with a render pass.
 #[derive(Clone, Debug)]
 pub struct RenderPassTimestampWrites {
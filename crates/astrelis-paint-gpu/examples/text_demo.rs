@@ -219,9 +219,11 @@ impl Demo {
                 },
             )
             .expect("render text");
+        gpu.renderer.before_submit();
         gpu.queue
             .submit([encoder.finish().expect("finish encoder")])
             .expect("submit");
+        gpu.renderer.after_submit().expect("after submit");
         frame.present().expect("present");
     }
 }
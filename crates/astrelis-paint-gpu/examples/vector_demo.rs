@@ -206,9 +206,11 @@ impl Demo {
                 },
             )
             .expect("render display list");
+        gpu.renderer.before_submit();
         gpu.queue
             .submit([encoder.finish().expect("finish encoder")])
             .expect("submit");
+        gpu.renderer.after_submit().expect("after submit");
         frame.present().expect("present");
     }
 }
@@ -5,6 +5,7 @@
 use std::{collections::HashMap, error::Error, fmt, mem::size_of, ops::Range};
 
 use astrelis_core::{
+    alloc::{FrameArena, FrameVec},
     color::Color,
     geometry::{LogicalRect, Physical, Rect, Size},
     math::{Affine2, Vec2},
@@ -12,14 +13,19 @@ use astrelis_core::{
 use astrelis_gpu as gpu;
 pub use astrelis_paint::ExternalImage;
 use astrelis_paint::{
-    Brush, Command, CornerRadii, DisplayList, FillRule, Image, ImageOptions, ImageSampling,
-    LineCap, LineJoin, LinearGradient, Path, PathVerb, RadialGradient, RoundedRect, StrokeStyle,
+    Brush, Command, CornerRadii, DashPattern, DisplayList, FillRule, Image, ImageOptions,
+    ImageSampling, LineCap, LineJoin, LinearGradient, Path, PathVerb, RadialGradient, RoundedRect,
+    StrokeStyle,
 };
+use astrelis_render::StagingBelt;
+pub use astrelis_text_gpu::TextAntialiasing;
 use astrelis_text_gpu::{AtlasKind, GlyphCache, GlyphCacheOptions};
 use bytemuck::{Pod, Zeroable};
 use lyon_tessellation::{
     BuffersBuilder, FillOptions, FillTessellator, FillVertex, StrokeOptions, StrokeTessellator,
-    StrokeVertex, VertexBuffers, math::point, path::Path as LyonPath,
+    StrokeVertex, VertexBuffers,
+    math::{Point as LyonPoint, point},
+    path::{Path as LyonPath, PathEvent, iterator::PathIterator, path::Builder as LyonPathBuilder},
 };
 
 /// Renderer antialiasing mode.
@@ -27,16 +33,22 @@ use lyon_tessellation::{
 pub enum Antialiasing {
     /// Single-sample rasterization.
     None,
+    /// Two-sample multisampling.
+    Msaa2,
     /// Four-sample multisampling.
     #[default]
     Msaa4,
+    /// Eight-sample multisampling, where the adapter supports it.
+    Msaa8,
 }
 
 impl Antialiasing {
     fn samples(self) -> u32 {
         match self {
             Self::None => 1,
+            Self::Msaa2 => 2,
             Self::Msaa4 => 4,
+            Self::Msaa8 => 8,
         }
     }
 }
@@ -68,13 +80,56 @@ impl Default for CacheLimits {
     }
 }
 
+/// Glyph rasterization quality settings.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TextOptions {
+    /// Outline glyph coverage mode.
+    pub antialiasing: TextAntialiasing,
+    /// Rasterizes quarter-pixel horizontal glyph offsets.
+    pub subpixel_positioning: bool,
+    /// Coverage gamma; values above one thicken light-on-dark strokes.
+    pub gamma: f32,
+}
+
+impl Default for TextOptions {
+    fn default() -> Self {
+        Self {
+            antialiasing: TextAntialiasing::Grayscale,
+            subpixel_positioning: false,
+            gamma: 1.0,
+        }
+    }
+}
+
 /// Device-bound renderer configuration.
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct RendererOptions {
     /// Edge antialiasing mode.
     pub antialiasing: Antialiasing,
     /// Persistent cache limits.
     pub cache_limits: CacheLimits,
+    /// Glyph rasterization quality.
+    pub text: TextOptions,
+    /// Routes vertex and index uploads through a [`StagingBelt`] with chunks
+    /// of this many bytes, or through queue writes when `None`. Defaults to
+    /// [`RendererOptions::DEFAULT_STAGING_CHUNK_SIZE`].
+    pub staging_chunk_size: Option<u64>,
+}
+
+impl RendererOptions {
+    /// Staging chunk size used by [`RendererOptions::default`].
+    pub const DEFAULT_STAGING_CHUNK_SIZE: u64 = 64 * 1024;
+}
+
+impl Default for RendererOptions {
+    fn default() -> Self {
+        Self {
+            antialiasing: Antialiasing::default(),
+            cache_limits: CacheLimits::default(),
+            text: TextOptions::default(),
+            staging_chunk_size: Some(Self::DEFAULT_STAGING_CHUNK_SIZE),
+        }
+    }
 }
 
 /// One complete paint destination.
@@ -117,6 +172,8 @@ pub struct RenderStats {
     pub glyph_cache_misses: u32,
     /// Newly uploaded glyph images.
     pub glyph_uploads: u32,
+    /// Glyphs left blank while background rasterization completes.
+    pub glyph_pending: u32,
     /// Reused shadow uniform resources.
     pub shadow_cache_hits: u32,
     /// Newly uploaded shadow uniform resources.
@@ -154,8 +211,13 @@ struct Vertex {
     uv: [f32; 2],
     color: [f32; 4],
     local_position: [f32; 2],
+    /// Physical-pixel clip rectangle `[min_x, min_y, max_x, max_y]` applied
+    /// in the fragment shader by text pipelines.
+    clip: [f32; 4],
 }
 
+const UNCLIPPED: [f32; 4] = [f32::MIN, f32::MIN, f32::MAX, f32::MAX];
+
 #[derive(Clone)]
 struct Mesh {
     vertices: Vec<[f32; 2]>,
@@ -171,7 +233,32 @@ impl Mesh {
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 enum MeshKind {
     Fill(FillRule),
-    Stroke(u32, LineCap, LineJoin, u32),
+    Stroke(u32, LineCap, LineJoin, u32, Option<DashKey>),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct DashKey([u32; DashPattern::MAX_INTERVALS], u8, u32);
+
+impl DashKey {
+    fn new(dash: DashPattern) -> Self {
+        let mut intervals = [0; DashPattern::MAX_INTERVALS];
+        for (key, interval) in intervals.iter_mut().zip(dash.intervals()) {
+            *key = interval.to_bits();
+        }
+        Self(
+            intervals,
+            dash.intervals().len() as u8,
+            dash.offset().to_bits(),
+        )
+    }
+
+    fn pattern(self) -> Result<DashPattern, RenderError> {
+        let intervals = self.0[..usize::from(self.1)]
+            .iter()
+            .map(|bits| f32::from_bits(*bits))
+            .collect::<Vec<_>>();
+        DashPattern::new(&intervals, f32::from_bits(self.2)).map_err(paint_error)
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
@@ -220,6 +307,7 @@ struct Pipelines {
     image: gpu::RenderPipeline,
     text_mask: gpu::RenderPipeline,
     text_color: gpu::RenderPipeline,
+    text_subpixel: gpu::RenderPipeline,
     shadow: gpu::RenderPipeline,
     clip_push: gpu::RenderPipeline,
     clip_pop: gpu::RenderPipeline,
@@ -236,8 +324,38 @@ struct Attachments {
 struct FrameBuffer {
     buffer: gpu::Buffer,
     capacity: usize,
+    /// Bytes last written, so later uploads only write what changed.
+    contents: Vec<u8>,
+}
+
+/// Persistent geometry buffers for one layer recorded before a submission.
+///
+/// Queue writes are applied before any encoder in the submission executes,
+/// so every layer recorded between two [`Renderer::after_submit`] calls needs
+/// its own slot, even across encoders. Slots are handed out in recording
+/// order, so a layer usually lands in the slot it used last frame and only
+/// uploads the bytes that changed.
+#[derive(Default)]
+struct LayerBuffers {
+    vertices: Option<FrameBuffer>,
+    indices: Option<FrameBuffer>,
+}
+
+impl LayerBuffers {
+    /// Forgets the uploaded contents after a write that may never execute.
+    fn invalidate(&mut self) {
+        for buffer in [&mut self.vertices, &mut self.indices]
+            .into_iter()
+            .flatten()
+        {
+            buffer.contents.clear();
+        }
+    }
 }
 
+/// Most layers recorded between two [`Renderer::after_submit`] calls.
+const MAX_LAYERS_PER_SUBMISSION: usize = 1024;
+
 #[derive(Clone, Copy, PartialEq, Eq)]
 struct Scissor {
     x: u32,
@@ -281,6 +399,11 @@ struct Clip {
 struct State {
     transform: Affine2,
     scissor: Scissor,
+    /// Intersection of axis-aligned rectangular clips in physical pixels.
+    ///
+    /// Glyph draws carry this per vertex instead of using `scissor`, so text
+    /// in differently clipped regions can still share one draw.
+    clip_rect: [f32; 4],
     clips: Vec<Clip>,
     opacity: f32,
 }
@@ -291,6 +414,8 @@ enum DrawKind {
     Image(gpu::BindGroup),
     TextMask(gpu::BindGroup),
     TextColor(gpu::BindGroup),
+    /// Per-channel coverage blended against a constant run color.
+    TextSubpixel(gpu::BindGroup, [f32; 4]),
     Shadow(gpu::BindGroup),
     ClipPush,
     ClipPop,
@@ -301,7 +426,8 @@ impl DrawKind {
     /// by extending its index range, given equal scissor and stencil.
     ///
     /// Content kinds merge when they bind the same resource (solids always do;
-    /// gradients, images, and glyph atlases must be the same bind group). Clip
+    /// gradients, images, and glyph atlases must be the same bind group;
+    /// subpixel text must also share its blend-constant color). Clip
     /// stencil operations never merge: each push and pop is a distinct stencil
     /// state change the pass loop must issue separately.
     fn mergeable_with(&self, other: &DrawKind) -> bool {
@@ -312,6 +438,9 @@ impl DrawKind {
             | (DrawKind::TextMask(a), DrawKind::TextMask(b))
             | (DrawKind::TextColor(a), DrawKind::TextColor(b))
             | (DrawKind::Shadow(a), DrawKind::Shadow(b)) => a.same_resource(b),
+            (DrawKind::TextSubpixel(a, color_a), DrawKind::TextSubpixel(b, color_b)) => {
+                a.same_resource(b) && color_a == color_b
+            }
             _ => false,
         }
     }
@@ -340,8 +469,10 @@ pub struct Renderer {
     gradients: HashMap<u64, CachedGradient>,
     shadows: HashMap<u64, CachedShadow>,
     glyphs: GlyphCache,
-    vertex_buffer: Option<FrameBuffer>,
-    index_buffer: Option<FrameBuffer>,
+    frame: FrameArena,
+    layer_buffers: Vec<LayerBuffers>,
+    staging: Option<StagingBelt>,
+    next_layer: usize,
     clock: u64,
 }
 
@@ -489,10 +620,16 @@ impl Renderer {
             queue.clone(),
             GlyphCacheOptions {
                 max_bytes: options.cache_limits.glyph_bytes,
+                antialiasing: options.text.antialiasing,
+                subpixel_positioning: options.text.subpixel_positioning,
+                gamma: options.text.gamma,
                 ..Default::default()
             },
         )
         .map_err(|error| RenderError::new(error.to_string()))?;
+        let staging = options
+            .staging_chunk_size
+            .map(|size| StagingBelt::new(device.clone(), size));
         Ok(Self {
             device,
             queue,
@@ -508,20 +645,24 @@ impl Renderer {
             gradients: HashMap::new(),
             shadows: HashMap::new(),
             glyphs,
-            vertex_buffer: None,
-            index_buffer: None,
+            frame: FrameArena::new(),
+            layer_buffers: Vec::new(),
+            staging,
+            next_layer: 0,
             clock: 0,
         })
     }
 
     /// Records a complete display-list render into an existing encoder.
+    ///
+    /// Call [`Renderer::after_submit`] once the encoder has been submitted.
     pub fn render(
         &mut self,
         encoder: &mut gpu::CommandEncoder,
         list: &DisplayList,
         target: RenderTarget,
     ) -> Result<RenderStats, RenderError> {
-        self.render_internal(encoder, list, target, false, true, false)
+        self.render_internal(encoder, list, target, false, true)
     }
 
     /// Records the first compositor UI layer without resolving shared MSAA.
@@ -531,7 +672,7 @@ impl Renderer {
         list: &DisplayList,
         target: RenderTarget,
     ) -> Result<RenderStats, RenderError> {
-        self.render_internal(encoder, list, target, false, false, true)
+        self.render_internal(encoder, list, target, false, false)
     }
 
     /// Records a display-list layer while preserving existing color and stencil.
@@ -543,7 +684,7 @@ impl Renderer {
         list: &DisplayList,
         target: RenderTarget,
     ) -> Result<RenderStats, RenderError> {
-        self.render_internal(encoder, list, target, true, false, true)
+        self.render_internal(encoder, list, target, true, false)
     }
 
     /// Records the final compositor UI layer and resolves shared MSAA.
@@ -553,7 +694,32 @@ impl Renderer {
         list: &DisplayList,
         target: RenderTarget,
     ) -> Result<RenderStats, RenderError> {
-        self.render_internal(encoder, list, target, true, true, true)
+        self.render_internal(encoder, list, target, true, true)
+    }
+
+    /// Closes geometry uploads staged since the previous submission. Call
+    /// once after the last render into the encoders and before submitting
+    /// them, so every layer's upload shares the same staging chunks.
+    pub fn before_submit(&mut self) {
+        if let Some(staging) = &mut self.staging {
+            staging.finish();
+        }
+    }
+
+    /// Ends a submission. Call after submitting every encoder recorded into
+    /// since the previous call, each preceded by [`Renderer::before_submit`].
+    ///
+    /// Layer geometry buffers become reusable and staging chunks are
+    /// reclaimed once the GPU has consumed them. Rendering more than a
+    /// thousand layers without calling this is reported as an error.
+    pub fn after_submit(&mut self) -> Result<(), RenderError> {
+        self.next_layer = 0;
+        match &mut self.staging {
+            Some(staging) => staging
+                .recall()
+                .map_err(|error| RenderError::new(error.to_string())),
+            None => Ok(()),
+        }
     }
 
     /// Returns the compositor-owned color attachment used by subsequent layers.
@@ -592,11 +758,16 @@ impl Renderer {
         target: RenderTarget,
         load: bool,
         resolve: bool,
-        transient_buffers: bool,
     ) -> Result<RenderStats, RenderError> {
         if target.view.device_id() != self.device.id() {
             return Err(RenderError::new("render target belongs to another device"));
         }
+        if !is_target_format(target.format) {
+            return Err(RenderError::new(format!(
+                "{:?} is not a supported paint target format",
+                target.format
+            )));
+        }
         if !target.scale_factor.is_finite() || target.scale_factor <= 0.0 {
             return Err(RenderError::new("scale factor must be finite and positive"));
         }
@@ -609,14 +780,38 @@ impl Renderer {
         self.ensure_pipelines(target.format, samples)?;
         self.ensure_attachments(target.size, target.format, samples);
 
+        // Geometry is rebuilt every layer, so it lives in the frame arena and
+        // the arena's chunk is reused once it has grown to fit a layer.
+        let mut frame = std::mem::take(&mut self.frame);
+        let result = self.record(encoder, list, target, load, resolve, &frame);
+        frame.reset();
+        self.frame = frame;
+        result
+    }
+
+    fn record(
+        &mut self,
+        encoder: &mut gpu::CommandEncoder,
+        list: &DisplayList,
+        target: RenderTarget,
+        load: bool,
+        resolve: bool,
+        frame: &FrameArena,
+    ) -> Result<RenderStats, RenderError> {
         let dpi = Affine2::from_scale(Vec2::splat(target.scale_factor));
-        let mut vertices = Vec::new();
-        let mut indices = Vec::new();
-        let mut draws = Vec::new();
+        let mut vertices = frame.vec();
+        let mut indices = frame.vec();
+        let mut draws = frame.vec();
         let mut stats = RenderStats::default();
         let mut state = State {
             transform: Affine2::IDENTITY,
             scissor: Scissor::full(target.size),
+            clip_rect: [
+                0.0,
+                0.0,
+                target.size.width as f32,
+                target.size.height as f32,
+            ],
             clips: Vec::new(),
             opacity: 1.0,
         };
@@ -625,6 +820,7 @@ impl Renderer {
             if let Err(error) = self.compile(
                 command,
                 list,
+                frame,
                 dpi,
                 target.size,
                 &mut state,
@@ -638,29 +834,41 @@ impl Renderer {
             }
         }
 
-        let transient_vertex = if transient_buffers && !vertices.is_empty() {
-            Some(self.device.create_buffer_init(
-                &self.queue,
-                Some("compositor UI layer vertices".into()),
-                bytemuck::cast_slice(&vertices),
-                gpu::BufferUsages::VERTEX,
-            )?)
-        } else {
-            None
-        };
-        let transient_index = if transient_buffers && !indices.is_empty() {
-            Some(self.device.create_buffer_init(
-                &self.queue,
-                Some("compositor UI layer indices".into()),
-                bytemuck::cast_slice(&indices),
-                gpu::BufferUsages::INDEX,
-            )?)
-        } else {
-            None
-        };
-        if !transient_buffers {
-            self.upload(&vertices, &indices)?;
+        let layer = self.next_layer;
+        if layer == MAX_LAYERS_PER_SUBMISSION {
+            return Err(RenderError::new(
+                "too many layers recorded; call Renderer::after_submit after each submission",
+            ));
         }
+        self.next_layer += 1;
+        let recorded = self.record_layer(
+            encoder, layer, target, load, resolve, &vertices, &indices, draws,
+        );
+        if recorded.is_err()
+            && let Some(buffers) = self.layer_buffers.get_mut(layer)
+        {
+            buffers.invalidate();
+        }
+        recorded?;
+        self.glyphs.finish_frame();
+        self.evict();
+        Ok(stats)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn record_layer(
+        &mut self,
+        encoder: &mut gpu::CommandEncoder,
+        layer: usize,
+        target: RenderTarget,
+        load: bool,
+        resolve: bool,
+        vertices: &[Vertex],
+        indices: &[u32],
+        draws: FrameVec<'_, Draw>,
+    ) -> Result<(), RenderError> {
+        let samples = self.options.antialiasing.samples();
+        self.upload(encoder, layer, vertices, indices)?;
         let attachments = self.attachments.as_ref().expect("attachments exist");
         let pipeline = self
             .pipelines
@@ -711,18 +919,11 @@ impl Renderer {
             timestamp_writes: None,
         })?;
         if !indices.is_empty() {
-            let vb = transient_vertex
-                .as_ref()
-                .unwrap_or_else(|| &self.vertex_buffer.as_ref().expect("vertex buffer").buffer);
-            let ib = transient_index
-                .as_ref()
-                .unwrap_or_else(|| &self.index_buffer.as_ref().expect("index buffer").buffer);
-            pass.set_vertex_buffer(0, vb, 0..(vertices.len() * size_of::<Vertex>()) as u64)?;
-            pass.set_index_buffer(
-                ib,
-                0..(indices.len() * size_of::<u32>()) as u64,
-                gpu::IndexFormat::Uint32,
-            )?;
+            let buffers = &self.layer_buffers[layer];
+            let vb = &buffers.vertices.as_ref().expect("vertex buffer").buffer;
+            let ib = &buffers.indices.as_ref().expect("index buffer").buffer;
+            pass.set_vertex_buffer(0, vb, 0..size_of_val(vertices) as u64)?;
+            pass.set_index_buffer(ib, 0..size_of_val(indices) as u64, gpu::IndexFormat::Uint32)?;
             for draw in draws {
                 if draw.scissor.width == 0 || draw.scissor.height == 0 {
                     continue;
@@ -752,6 +953,16 @@ impl Renderer {
                         pass.set_pipeline(&pipeline.text_color)?;
                         pass.set_bind_group(0, &bind, &[])?;
                     }
+                    DrawKind::TextSubpixel(bind, color) => {
+                        pass.set_pipeline(&pipeline.text_subpixel)?;
+                        pass.set_bind_group(0, &bind, &[])?;
+                        pass.set_blend_constant(gpu::Color {
+                            r: color[0] as f64,
+                            g: color[1] as f64,
+                            b: color[2] as f64,
+                            a: color[3] as f64,
+                        });
+                    }
                     DrawKind::Shadow(bind) => {
                         pass.set_pipeline(&pipeline.shadow)?;
                         pass.set_bind_group(0, &bind, &[])?;
@@ -762,10 +973,33 @@ impl Renderer {
                 pass.draw_indexed(draw.indices, 0, 0..1);
             }
         }
-        drop(pass);
-        self.glyphs.finish_frame();
-        self.evict();
-        Ok(stats)
+        Ok(())
+    }
+
+    /// Rasterizes newly seen glyphs on a background thread.
+    ///
+    /// Text renders with blank space for glyphs still in flight, reported in
+    /// [`RenderStats::glyph_pending`]; `wake` runs after each glyph finishes so
+    /// the host can schedule a repaint. On wasm this is a no-op.
+    pub fn enable_async_glyph_rasterization<W>(&mut self, wake: W)
+    where
+        W: Fn() + Send + Sync + 'static,
+    {
+        self.glyphs.enable_async_rasterization(wake);
+    }
+
+    /// Builds pipelines for a color target format ahead of its first frame.
+    ///
+    /// Rendering compiles pipelines lazily for each target format, so this is
+    /// only needed to move that cost out of a latency-sensitive frame. Any
+    /// blendable 8-bit or 16-bit floating-point color format is accepted.
+    pub fn prepare_target_format(&mut self, format: gpu::TextureFormat) -> Result<(), RenderError> {
+        if !is_target_format(format) {
+            return Err(RenderError::new(format!(
+                "{format:?} is not a supported paint target format"
+            )));
+        }
+        self.ensure_pipelines(format, self.options.antialiasing.samples())
     }
 
     /// Clears persistent image and tessellation caches.
@@ -782,13 +1016,14 @@ impl Renderer {
         &mut self,
         command: &Command,
         list: &DisplayList,
+        frame: &FrameArena,
         dpi: Affine2,
         size: Size<Physical, u32>,
         state: &mut State,
         stack: &mut Vec<State>,
-        vertices: &mut Vec<Vertex>,
-        indices: &mut Vec<u32>,
-        draws: &mut Vec<Draw>,
+        vertices: &mut FrameVec<'_, Vertex>,
+        indices: &mut FrameVec<'_, u32>,
+        draws: &mut FrameVec<'_, Draw>,
         stats: &mut RenderStats,
     ) -> Result<(), RenderError> {
         match command {
@@ -819,6 +1054,14 @@ impl Renderer {
             Command::Transform(value) => state.transform *= *value,
             Command::MultiplyOpacity(value) => state.opacity *= *value,
             Command::ClipRect(rect) => {
+                if let Some(bounds) = axis_aligned_bounds(*rect, dpi * state.transform) {
+                    state.clip_rect = [
+                        state.clip_rect[0].max(bounds[0]),
+                        state.clip_rect[1].max(bounds[1]),
+                        state.clip_rect[2].min(bounds[2]),
+                        state.clip_rect[3].min(bounds[3]),
+                    ];
+                }
                 if let Some(scissor) = exact_scissor(*rect, dpi * state.transform, size) {
                     state.scissor = state.scissor.intersect(scissor);
                 } else {
@@ -1024,6 +1267,7 @@ impl Renderer {
                         style.cap,
                         style.join,
                         style.miter_limit.to_bits(),
+                        style.dash.map(DashKey::new),
                     ),
                     dpi * state.transform,
                     stats,
@@ -1107,11 +1351,12 @@ impl Renderer {
                 let physical_scale = effective_scale(dpi * state.transform);
                 let (glyphs, glyph_stats) = self
                     .glyphs
-                    .prepare_layout(text, physical_scale)
+                    .prepare_layout_in(frame, text, physical_scale)
                     .map_err(|error| RenderError::new(error.to_string()))?;
                 stats.glyph_cache_hits += glyph_stats.hits;
                 stats.glyph_cache_misses += glyph_stats.misses;
                 stats.glyph_uploads += glyph_stats.uploads;
+                stats.glyph_pending += glyph_stats.pending;
                 for run in text.glyph_runs() {
                     for decoration in [run.underline, run.strikethrough].into_iter().flatten() {
                         let rect = Rect::from_xywh(
@@ -1157,7 +1402,15 @@ impl Renderer {
                             [effective_opacity; 4],
                             DrawKind::TextColor(glyph.bind_group),
                         ),
+                        AtlasKind::Subpixel => (
+                            [alpha; 4],
+                            DrawKind::TextSubpixel(
+                                glyph.bind_group,
+                                [run.color.r, run.color.g, run.color.b, 1.0],
+                            ),
+                        ),
                     };
+                    let first_vertex = vertices.len();
                     append(
                         &rect_mesh(rect),
                         dpi * state.transform,
@@ -1168,10 +1421,13 @@ impl Renderer {
                         indices,
                         draws,
                         kind,
-                        state.scissor,
+                        Scissor::full(size),
                         state.clips.len() as u32,
                         stats,
                     );
+                    for vertex in &mut vertices[first_vertex..] {
+                        vertex.clip = state.clip_rect;
+                    }
                 }
             }
         }
@@ -1194,13 +1450,14 @@ impl Renderer {
         stats.mesh_cache_misses += 1;
         let mesh = match kind {
             MeshKind::Fill(rule) => tessellate_fill(path, rule, local_tolerance(transform))?,
-            MeshKind::Stroke(width, cap, join, miter) => tessellate_stroke(
+            MeshKind::Stroke(width, cap, join, miter, dash) => tessellate_stroke(
                 path,
                 StrokeStyle {
                     width: f32::from_bits(width),
                     cap,
                     join,
                     miter_limit: f32::from_bits(miter),
+                    dash: dash.map(DashKey::pattern).transpose()?,
                 },
                 local_tolerance(transform),
             )?,
@@ -1313,9 +1570,9 @@ impl Renderer {
         state: &State,
         dpi: Affine2,
         size: Size<Physical, u32>,
-        vertices: &mut Vec<Vertex>,
-        indices: &mut Vec<u32>,
-        draws: &mut Vec<Draw>,
+        vertices: &mut FrameVec<'_, Vertex>,
+        indices: &mut FrameVec<'_, u32>,
+        draws: &mut FrameVec<'_, Draw>,
         stats: &mut RenderStats,
     ) -> Result<(), RenderError> {
         if state.opacity <= 0.0 {
@@ -1553,6 +1810,17 @@ impl Renderer {
         if self.pipelines.contains_key(&key) {
             return Ok(());
         }
+        // The stencil attachment is multisampled alongside the color target,
+        // so both formats must accept the sample count.
+        if !self.device.supports_sample_count(format, samples)
+            || !self
+                .device
+                .supports_sample_count(gpu::TextureFormat::Depth24PlusStencil8, samples)
+        {
+            return Err(RenderError::new(format!(
+                "{format:?} with a Depth24PlusStencil8 stencil does not support {samples}x multisampling"
+            )));
+        }
         let shader = self
             .device
             .create_shader_module(gpu::ShaderModuleDescriptor {
@@ -1610,6 +1878,11 @@ impl Renderer {
                         shader_location: 3,
                         format: gpu::VertexFormat::Float32x2,
                     },
+                    gpu::VertexAttribute {
+                        offset: 40,
+                        shader_location: 4,
+                        format: gpu::VertexFormat::Float32x4,
+                    },
                 ],
             }],
         };
@@ -1631,11 +1904,12 @@ impl Renderer {
             compare: gpu::CompareFunction::Equal,
             ..gpu::StencilFaceState::IGNORE
         };
-        let create = |label: &str,
-                      layout: Option<gpu::PipelineLayout>,
-                      fragment: &str,
-                      writes: gpu::ColorWrites,
-                      face: gpu::StencilFaceState| {
+        let create_blended = |label: &str,
+                              layout: Option<gpu::PipelineLayout>,
+                              fragment: &str,
+                              writes: gpu::ColorWrites,
+                              face: gpu::StencilFaceState,
+                              blend: gpu::BlendState| {
             self.device
                 .create_render_pipeline(gpu::RenderPipelineDescriptor {
                     label: Some(label.into()),
@@ -1652,12 +1926,27 @@ impl Renderer {
                         entry_point: fragment.into(),
                         targets: vec![Some(gpu::ColorTargetState {
                             format,
-                            blend: Some(gpu::BlendState::PREMULTIPLIED_ALPHA),
+                            blend: Some(blend),
                             write_mask: writes,
                         })],
                     }),
+                    cache: None,
                 })
         };
+        let create = |label: &str,
+                      layout: Option<gpu::PipelineLayout>,
+                      fragment: &str,
+                      writes: gpu::ColorWrites,
+                      face: gpu::StencilFaceState| {
+            create_blended(
+                label,
+                layout,
+                fragment,
+                writes,
+                face,
+                gpu::BlendState::PREMULTIPLIED_ALPHA,
+            )
+        };
         let solid = create(
             "paint solid",
             None,
@@ -1688,11 +1977,29 @@ impl Renderer {
         )?;
         let text_color = create(
             "paint text color",
-            Some(text_layout),
+            Some(text_layout.clone()),
             "fs_text_color",
             gpu::ColorWrites::ALL,
             content,
         )?;
+        // Without dual-source blending, per-channel coverage is exact only
+        // when the text color is a blend constant: the fragment emits coverage
+        // and the destination keeps `1 - coverage` of each channel.
+        let text_subpixel = create_blended(
+            "paint text subpixel",
+            Some(text_layout),
+            "fs_text_subpixel",
+            gpu::ColorWrites::ALL,
+            content,
+            gpu::BlendState {
+                color: gpu::BlendComponent {
+                    src_factor: gpu::BlendFactor::Constant,
+                    dst_factor: gpu::BlendFactor::OneMinusSrc,
+                    operation: gpu::BlendOperation::Add,
+                },
+                alpha: gpu::BlendComponent::PREMULTIPLIED_ALPHA,
+            },
+        )?;
         let shadow = create(
             "paint shadow",
             Some(shadow_layout),
@@ -1730,6 +2037,7 @@ impl Renderer {
                 image,
                 text_mask,
                 text_color,
+                text_subpixel,
                 shadow,
                 clip_push,
                 clip_pop,
@@ -1786,22 +2094,37 @@ impl Renderer {
         });
     }
 
-    fn upload(&mut self, vertices: &[Vertex], indices: &[u32]) -> Result<(), RenderError> {
+    fn upload(
+        &mut self,
+        encoder: &mut gpu::CommandEncoder,
+        layer: usize,
+        vertices: &[Vertex],
+        indices: &[u32],
+    ) -> Result<(), RenderError> {
         if vertices.is_empty() {
             return Ok(());
         }
+        if self.layer_buffers.len() <= layer {
+            self.layer_buffers
+                .resize_with(layer + 1, LayerBuffers::default);
+        }
+        let buffers = &mut self.layer_buffers[layer];
+        let mut upload = Upload {
+            device: &self.device,
+            queue: &self.queue,
+            staging: self.staging.as_mut(),
+            encoder,
+        };
         ensure_buffer(
-            &self.device,
-            &self.queue,
-            &mut self.vertex_buffer,
+            &mut upload,
+            &mut buffers.vertices,
             bytemuck::cast_slice(vertices),
             gpu::BufferUsages::VERTEX,
             "paint vertices",
         )?;
         ensure_buffer(
-            &self.device,
-            &self.queue,
-            &mut self.index_buffer,
+            &mut upload,
+            &mut buffers.indices,
             bytemuck::cast_slice(indices),
             gpu::BufferUsages::INDEX,
             "paint indices",
@@ -1876,9 +2199,29 @@ impl Renderer {
     }
 }
 
+fn is_target_format(format: gpu::TextureFormat) -> bool {
+    matches!(
+        format,
+        gpu::TextureFormat::Rgba8Unorm
+            | gpu::TextureFormat::Rgba8UnormSrgb
+            | gpu::TextureFormat::Bgra8Unorm
+            | gpu::TextureFormat::Bgra8UnormSrgb
+            | gpu::TextureFormat::Rgba16Float
+    )
+}
+
+/// Destination of per-frame buffer uploads.
+struct Upload<'a> {
+    device: &'a gpu::Device,
+    queue: &'a gpu::Queue,
+    staging: Option<&'a mut StagingBelt>,
+    encoder: &'a mut gpu::CommandEncoder,
+}
+
+/// Grows `slot` to fit `bytes` and writes the range that differs from its
+/// previous contents.
 fn ensure_buffer(
-    device: &gpu::Device,
-    queue: &gpu::Queue,
+    upload: &mut Upload<'_>,
     slot: &mut Option<FrameBuffer>,
     bytes: &[u8],
     usage: gpu::BufferUsages,
@@ -1890,17 +2233,55 @@ fn ensure_buffer(
     {
         let capacity = bytes.len().next_power_of_two().max(256);
         *slot = Some(FrameBuffer {
-            buffer: device.create_buffer(gpu::BufferDescriptor {
+            buffer: upload.device.create_buffer(gpu::BufferDescriptor {
                 label: Some(label.into()),
                 size: capacity as u64,
                 usage: usage | gpu::BufferUsages::COPY_DST,
                 mapped_at_creation: false,
             }),
             capacity,
+            contents: Vec::new(),
         });
     }
-    queue.write_buffer(&slot.as_ref().expect("buffer exists").buffer, 0, bytes)?;
-    Ok(())
+    let slot = slot.as_mut().expect("buffer exists");
+    let Some(range) = dirty_range(&slot.contents, bytes) else {
+        return Ok(());
+    };
+    slot.contents.clear();
+    slot.contents.extend_from_slice(bytes);
+    let offset = range.start as u64;
+    let bytes = &bytes[range];
+    match upload.staging.as_deref_mut() {
+        Some(staging) => staging
+            .write_buffer(upload.encoder, &slot.buffer, offset, bytes)
+            .map_err(|error| RenderError::new(error.to_string())),
+        None => Ok(upload.queue.write_buffer(&slot.buffer, offset, bytes)?),
+    }
+}
+
+/// The four-byte-aligned range of `new` that differs from `old`, or `None`
+/// when `new` is a prefix of `old`.
+fn dirty_range(old: &[u8], new: &[u8]) -> Option<std::ops::Range<usize>> {
+    let shared = old.len().min(new.len());
+    let start = old[..shared]
+        .iter()
+        .zip(new)
+        .position(|(old, new)| old != new)
+        .unwrap_or(shared);
+    if start == new.len() {
+        return None;
+    }
+    let end = if new.len() > shared {
+        new.len()
+    } else {
+        old[..shared]
+            .iter()
+            .zip(new)
+            .rposition(|(old, new)| old != new)
+            .expect("a byte differs")
+            + 1
+    };
+    Some(start / 4 * 4..end.next_multiple_of(4).min(new.len()))
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -1909,9 +2290,9 @@ fn push_clip(
     state: &mut State,
     dpi: Affine2,
     size: Size<Physical, u32>,
-    vertices: &mut Vec<Vertex>,
-    indices: &mut Vec<u32>,
-    draws: &mut Vec<Draw>,
+    vertices: &mut FrameVec<'_, Vertex>,
+    indices: &mut FrameVec<'_, u32>,
+    draws: &mut FrameVec<'_, Draw>,
     stats: &mut RenderStats,
 ) -> Result<(), RenderError> {
     if state.clips.len() == 255 {
@@ -1946,9 +2327,9 @@ fn draw_solid(
     state: &State,
     dpi: Affine2,
     size: Size<Physical, u32>,
-    vertices: &mut Vec<Vertex>,
-    indices: &mut Vec<u32>,
-    draws: &mut Vec<Draw>,
+    vertices: &mut FrameVec<'_, Vertex>,
+    indices: &mut FrameVec<'_, u32>,
+    draws: &mut FrameVec<'_, Draw>,
     stats: &mut RenderStats,
 ) {
     if color.a <= 0.0 {
@@ -1978,9 +2359,9 @@ fn append(
     size: Size<Physical, u32>,
     color: [f32; 4],
     uv_rect: Option<[f32; 4]>,
-    vertices: &mut Vec<Vertex>,
-    indices: &mut Vec<u32>,
-    draws: &mut Vec<Draw>,
+    vertices: &mut FrameVec<'_, Vertex>,
+    indices: &mut FrameVec<'_, u32>,
+    draws: &mut FrameVec<'_, Draw>,
     kind: DrawKind,
     scissor: Scissor,
     stencil: u32,
@@ -2010,6 +2391,7 @@ fn append(
             uv,
             color,
             local_position: *point,
+            clip: UNCLIPPED,
         });
     }
     indices.extend(mesh.indices.iter().map(|index| base + index));
@@ -2137,6 +2519,174 @@ fn tessellate_fill(path: &Path, rule: FillRule, tolerance: f32) -> Result<Mesh,
     })
 }
 
+/// Most dash boundaries walked on one contour before it is stroked solid.
+const MAX_DASH_SPLITS: usize = 1 << 16;
+
+/// Splits a path into one open sub-path per dash.
+///
+/// Curves are flattened first so the pattern is measured along arc length.
+/// A dash that runs through a corner or a curve stays one polyline, letting
+/// the stroker join it normally, and on closed contours the dash crossing the
+/// start point is stitched back together. A pattern whose period is below the
+/// tessellation tolerance cannot be resolved and is stroked solid, as is any
+/// contour that would need more than [`MAX_DASH_SPLITS`] dash boundaries.
+fn dash_path(path: &LyonPath, dash: DashPattern, tolerance: f32) -> LyonPath {
+    if dash.period() < tolerance {
+        return path.clone();
+    }
+    let mut walker = DashWalker::new(dash);
+    let mut builder = LyonPath::builder();
+    for event in path.iter().flattened(tolerance) {
+        match event {
+            PathEvent::Begin { at } => walker.begin(at),
+            PathEvent::Line { from, to } => walker.line(from, to),
+            PathEvent::End { last, first, close } => {
+                if close {
+                    walker.line(last, first);
+                }
+                walker.end(close, &mut builder);
+            }
+            PathEvent::Quadratic { .. } | PathEvent::Cubic { .. } => {
+                unreachable!("flattened paths only contain lines")
+            }
+        }
+    }
+    builder.build()
+}
+
+/// Walks flattened contours, cutting them at dash boundaries.
+///
+/// Distances are tracked in `f64` so that short intervals still make
+/// progress along long segments, and the split count is capped so that no
+/// pattern can loop without bound.
+struct DashWalker {
+    dash: DashPattern,
+    cycle: usize,
+    start_index: usize,
+    start_remaining: f64,
+    index: usize,
+    remaining: f64,
+    splits: usize,
+    contour: Vec<LyonPoint>,
+    dashes: Vec<Vec<LyonPoint>>,
+    current: Option<Vec<LyonPoint>>,
+}
+
+impl DashWalker {
+    fn new(dash: DashPattern) -> Self {
+        let len = dash.intervals().len();
+        let mut walker = Self {
+            dash,
+            cycle: if len.is_multiple_of(2) { len } else { len * 2 },
+            start_index: 0,
+            start_remaining: 0.0,
+            index: 0,
+            remaining: f64::from(dash.intervals()[0]),
+            splits: 0,
+            contour: Vec::new(),
+            dashes: Vec::new(),
+            current: None,
+        };
+        // The offset is already wrapped into one period, so one cycle of
+        // intervals is always enough to consume it.
+        let mut skip = f64::from(dash.offset());
+        for _ in 0..walker.cycle {
+            if skip < walker.remaining {
+                break;
+            }
+            skip -= walker.remaining;
+            walker.advance();
+        }
+        walker.start_index = walker.index;
+        walker.start_remaining = (walker.remaining - skip).max(0.0);
+        walker
+    }
+
+    fn advance(&mut self) {
+        let pattern = self.dash.intervals();
+        self.index = (self.index + 1) % self.cycle;
+        self.remaining = f64::from(pattern[self.index % pattern.len()]);
+    }
+
+    fn begin(&mut self, at: LyonPoint) {
+        self.dashes.clear();
+        self.contour.clear();
+        self.contour.push(at);
+        self.splits = 0;
+        self.index = self.start_index;
+        self.remaining = self.start_remaining;
+        self.current = self.index.is_multiple_of(2).then(|| vec![at]);
+    }
+
+    fn line(&mut self, from: LyonPoint, to: LyonPoint) {
+        self.contour.push(to);
+        if self.splits > MAX_DASH_SPLITS {
+            return;
+        }
+        let length = f64::from((to - from).length());
+        let mut travelled = 0.0;
+        while length - travelled > self.remaining {
+            self.splits += 1;
+            if self.splits > MAX_DASH_SPLITS {
+                return;
+            }
+            travelled += self.remaining;
+            let split = from.lerp(to, (travelled / length) as f32);
+            match self.current.take() {
+                Some(mut points) => {
+                    points.push(split);
+                    self.dashes.push(points);
+                }
+                None => self.current = Some(vec![split]),
+            }
+            self.advance();
+        }
+        self.remaining -= length - travelled;
+        if let Some(points) = &mut self.current {
+            points.push(to);
+        }
+    }
+
+    fn end(&mut self, close: bool, builder: &mut LyonPathBuilder) {
+        if self.splits > MAX_DASH_SPLITS {
+            self.dashes.clear();
+            self.current = None;
+            if close {
+                // `line(last, first)` pushed the start point a second time.
+                self.contour.pop();
+            }
+            emit_polyline(builder, &self.contour, close);
+            return;
+        }
+        let starts_on = self.start_index.is_multiple_of(2);
+        match self.current.take() {
+            Some(points) if close && starts_on && self.dashes.is_empty() => {
+                emit_polyline(builder, &points, true);
+            }
+            Some(mut points) if close && starts_on => {
+                points.extend_from_slice(&self.dashes[0][1..]);
+                self.dashes[0] = points;
+            }
+            Some(points) => self.dashes.push(points),
+            None => {}
+        }
+        for points in self.dashes.drain(..) {
+            emit_polyline(builder, &points, false);
+        }
+    }
+}
+
+fn emit_polyline(builder: &mut LyonPathBuilder, points: &[LyonPoint], close: bool) {
+    let Some((first, rest)) = points.split_first() else {
+        return;
+    };
+    builder.begin(*first);
+    for point in rest {
+        builder.line_to(*point);
+    }
+    builder.end(close);
+}
+
 fn tessellate_stroke(path: &Path, style: StrokeStyle, tolerance: f32) -> Result<Mesh, RenderError> {
     astrelis_profiling::profile_scope!("paint.tessellate_stroke");
     if style.width == 0.0 {
@@ -2145,7 +2695,10 @@ fn tessellate_stroke(path: &Path, style: StrokeStyle, tolerance: f32) -> Result<
             indices: Vec::new(),
         });
     }
-    let path = to_lyon(path);
+    let mut path = to_lyon(path);
+    if let Some(dash) = style.dash {
+        path = dash_path(&path, dash, tolerance);
+    }
     let cap = match style.cap {
         LineCap::Butt => lyon_tessellation::LineCap::Butt,
         LineCap::Square => lyon_tessellation::LineCap::Square,
@@ -2321,11 +2874,9 @@ fn image_uv_for_size(size: Size<Physical, u32>, options: ImageOptions) -> [f32;
     ]
 }
 
-fn exact_scissor(
-    rect: LogicalRect,
-    transform: Affine2,
-    size: Size<Physical, u32>,
-) -> Option<Scissor> {
+/// Returns the physical `[min_x, min_y, max_x, max_y]` of a rectangle whose
+/// transformed edges remain axis-aligned.
+fn axis_aligned_bounds(rect: LogicalRect, transform: Affine2) -> Option<[f32; 4]> {
     let points = [
         transform.transform_point2(Vec2::new(rect.min_x(), rect.min_y())),
         transform.transform_point2(Vec2::new(rect.max_x(), rect.min_y())),
@@ -2344,6 +2895,16 @@ fn exact_scissor(
     let min_y = points.iter().map(|p| p.y).fold(f32::INFINITY, f32::min);
     let max_x = points.iter().map(|p| p.x).fold(f32::NEG_INFINITY, f32::max);
     let max_y = points.iter().map(|p| p.y).fold(f32::NEG_INFINITY, f32::max);
+    Some([min_x, min_y, max_x, max_y])
+}
+
+fn exact_scissor(
+    rect: LogicalRect,
+    transform: Affine2,
+    size: Size<Physical, u32>,
+) -> Option<Scissor> {
+    let [min_x, min_y, max_x, max_y] = axis_aligned_bounds(rect, transform)?;
+    let e = 1e-4;
     if [min_x, min_y, max_x, max_y]
         .into_iter()
         .any(|v| (v - v.round()).abs() >= e)
@@ -2385,12 +2946,14 @@ struct Input {
     @location(1) uv: vec2<f32>,
     @location(2) color: vec4<f32>,
     @location(3) local_position: vec2<f32>,
+    @location(4) clip: vec4<f32>,
 };
 struct Output {
     @builtin(position) position: vec4<f32>,
     @location(0) uv: vec2<f32>,
     @location(1) color: vec4<f32>,
     @location(2) local_position: vec2<f32>,
+    @location(3) clip: vec4<f32>,
 };
 @vertex fn vs_main(input: Input) -> Output {
     var output: Output;
@@ -2398,8 +2961,16 @@ struct Output {
     output.uv = input.uv;
     output.color = input.color;
     output.local_position = input.local_position;
+    output.clip = input.clip;
     return output;
 }
+// Pixel coverage of the per-vertex clip rectangle, antialiased at
+// fractional edges.
+fn clip_coverage(input: Output) -> f32 {
+    let low = clamp(input.position.xy - input.clip.xy + 0.5, vec2<f32>(0.0), vec2<f32>(1.0));
+    let high = clamp(input.clip.zw - input.position.xy + 0.5, vec2<f32>(0.0), vec2<f32>(1.0));
+    return low.x * low.y * high.x * high.y;
+}
 @fragment fn fs_solid(input: Output) -> @location(0) vec4<f32> {
     return input.color;
 }
@@ -2505,18 +3076,41 @@ fn shadow_blur_x(x: f32, y: f32, sigma: f32, corner: f32, half: vec2<f32>) -> f3
 }
 @fragment fn fs_text_mask(input: Output) -> @location(0) vec4<f32> {
     let coverage = textureSample(image, image_sampler, input.uv).r;
-    return input.color * coverage;
+    return input.color * coverage * clip_coverage(input);
+}
+@fragment fn fs_text_subpixel(input: Output) -> @location(0) vec4<f32> {
+    let coverage = textureSample(image, image_sampler, input.uv);
+    return coverage * input.color.a * clip_coverage(input);
 }
 @fragment fn fs_text_color(input: Output) -> @location(0) vec4<f32> {
     let sample = textureSample(image, image_sampler, input.uv);
-    return vec4<f32>(sample.rgb * sample.a, sample.a) * input.color.a;
+    return vec4<f32>(sample.rgb * sample.a, sample.a) * input.color.a * clip_coverage(input);
 }
 "#;
 
 #[cfg(test)]
 mod tests {
+    use astrelis_core::geometry::Point;
+
     use super::*;
 
+    #[test]
+    fn dirty_ranges_cover_changed_words() {
+        let old = [0u8; 16];
+        let mut new = old;
+        assert_eq!(dirty_range(&old, &new), None);
+        assert_eq!(
+            dirty_range(&old, &new[..8]),
+            None,
+            "shrinking writes nothing"
+        );
+        new[5] = 1;
+        new[9] = 1;
+        assert_eq!(dirty_range(&old, &new), Some(4..12));
+        assert_eq!(dirty_range(&old[..8], &new), Some(4..16));
+        assert_eq!(dirty_range(&[], &new), Some(0..16));
+    }
+
     #[test]
     fn scale_bucket_ignores_translation() {
         assert_eq!(
@@ -2525,6 +3119,90 @@ mod tests {
         );
     }
 
+    fn polylines(path: &LyonPath) -> Vec<Vec<[f32; 2]>> {
+        let mut lines = Vec::new();
+        for event in path.iter() {
+            match event {
+                PathEvent::Begin { at } => lines.push(vec![at.to_array()]),
+                PathEvent::Line { to, .. } => lines.last_mut().unwrap().push(to.to_array()),
+                PathEvent::End { close, .. } => assert!(!close),
+                _ => unreachable!(),
+            }
+        }
+        lines
+    }
+
+    #[test]
+    fn dashes_follow_corners_and_wrap_closed_contours() {
+        let mut square = Path::builder();
+        square.move_to(Point::new(0.0, 0.0)).unwrap();
+        square.line_to(Point::new(10.0, 0.0)).unwrap();
+        square.line_to(Point::new(10.0, 10.0)).unwrap();
+        square.line_to(Point::new(0.0, 10.0)).unwrap();
+        square.close().unwrap();
+        let dash = DashPattern::new(&[10.0, 10.0], 5.0).unwrap();
+        let dashed = dash_path(&to_lyon(&square.finish()), dash, 0.1);
+        assert_eq!(
+            polylines(&dashed),
+            [
+                vec![[0.0, 5.0], [0.0, 0.0], [5.0, 0.0]],
+                vec![[10.0, 5.0], [10.0, 10.0], [5.0, 10.0]],
+            ],
+            "dashes keep their corners and the last dash wraps into the first"
+        );
+
+        let mut line = Path::builder();
+        line.move_to(Point::new(0.0, 0.0)).unwrap();
+        line.line_to(Point::new(10.0, 0.0)).unwrap();
+        let dash = DashPattern::new(&[2.0, 3.0], 0.0).unwrap();
+        let line = to_lyon(&line.finish());
+        assert_eq!(
+            polylines(&dash_path(&line, dash, 0.1)),
+            [vec![[0.0, 0.0], [2.0, 0.0]], vec![[5.0, 0.0], [7.0, 0.0]]]
+        );
+        assert_eq!(
+            polylines(&dash_path(&line, dash.with_offset(1.0), 0.1)),
+            [
+                vec![[0.0, 0.0], [1.0, 0.0]],
+                vec![[4.0, 0.0], [6.0, 0.0]],
+                vec![[9.0, 0.0], [10.0, 0.0]],
+            ],
+            "advancing the offset marches the dashes backwards along the path"
+        );
+    }
+
+    #[test]
+    fn tiny_dash_intervals_terminate() {
+        let mut line = Path::builder();
+        line.move_to(Point::new(0.0, 0.0)).unwrap();
+        line.line_to(Point::new(100.0, 0.0)).unwrap();
+        let line = to_lyon(&line.finish());
+
+        let dash = DashPattern::new(&[1e-6, 1e-6], 0.0).unwrap();
+        assert_eq!(
+            polylines(&dash_path(&line, dash, 0.1)),
+            [vec![[0.0, 0.0], [100.0, 0.0]]],
+            "a period below the tolerance strokes solid"
+        );
+
+        let dash = DashPattern::new(&[1e-6, 1e-6], 0.0).unwrap();
+        let dashes = polylines(&dash_path(&line, dash, 1e-7));
+        assert_eq!(
+            dashes,
+            [vec![[0.0, 0.0], [100.0, 0.0]]],
+            "a contour past the split cap strokes solid"
+        );
+
+        let mut long = Path::builder();
+        long.move_to(Point::new(0.0, 0.0)).unwrap();
+        long.line_to(Point::new(1e6, 0.0)).unwrap();
+        let dash = DashPattern::new(&[0.5, 0.5], 0.0).unwrap();
+        assert_eq!(
+            polylines(&dash_path(&to_lyon(&long.finish()), dash, 0.1)).len(),
+            1
+        );
+    }
+
     #[test]
     fn scissor_requires_pixel_alignment() {
         let size = Size::new(100, 100);
@@ -2535,4 +3213,29 @@ mod tests {
             exact_scissor(Rect::from_xywh(1.5, 2.0, 3.0, 4.0), Affine2::IDENTITY, size).is_none()
         );
     }
+
+    #[test]
+    fn fractional_clip_rects_keep_physical_bounds() {
+        let bounds = axis_aligned_bounds(
+            Rect::from_xywh(1.5, 2.0, 3.0, 4.0),
+            Affine2::from_scale(Vec2::splat(2.0)),
+        );
+        assert_eq!(bounds, Some([3.0, 4.0, 9.0, 12.0]));
+        assert!(
+            axis_aligned_bounds(
+                Rect::from_xywh(0.0, 0.0, 3.0, 4.0),
+                Affine2::from_angle(0.3)
+            )
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn target_formats_include_hdr_and_exclude_depth() {
+        assert!(is_target_format(gpu::TextureFormat::Bgra8UnormSrgb));
+        assert!(is_target_format(gpu::TextureFormat::Rgba8Unorm));
+        assert!(is_target_format(gpu::TextureFormat::Rgba16Float));
+        assert!(!is_target_format(gpu::TextureFormat::R32Uint));
+        assert!(!is_target_format(gpu::TextureFormat::Depth24PlusStencil8));
+    }
 }
@@ -247,6 +247,7 @@ async fn render_external_pixel(
             Extent3d::d2(2, 2),
         )
         .expect("copy external image result");
+    renderer.before_submit();
     queue
         .submit([encoder.finish().expect("finish external image encoder")])
         .expect("submit external image render");
@@ -392,6 +393,7 @@ fn renders_solid_stencil_clip_and_reuses_mesh() {
         let first = renderer
             .render(&mut encoder, &list, render_target())
             .expect("first paint render");
+        renderer.before_submit();
         queue
             .submit([encoder.finish().expect("finish first encoder")])
             .expect("submit first");
@@ -429,6 +431,7 @@ fn renders_solid_stencil_clip_and_reuses_mesh() {
                 Extent3d::d2(16, 16),
             )
             .expect("copy target");
+        renderer.before_submit();
         queue
             .submit([encoder.finish().expect("finish second encoder")])
             .expect("submit second");
@@ -531,6 +534,7 @@ fn merges_a_glyph_run_into_one_draw() {
                 },
             )
             .expect("render");
+        renderer.before_submit();
         queue
             .submit([encoder.finish().expect("finish encoder")])
             .expect("submit");
@@ -613,6 +617,7 @@ fn renders_analytic_shadow_and_reuses_cache() {
         let first = renderer
             .render(&mut encoder, &list, render_target())
             .expect("first shadow render");
+        renderer.before_submit();
         queue
             .submit([encoder.finish().expect("finish first encoder")])
             .expect("submit first");
@@ -645,6 +650,7 @@ fn renders_analytic_shadow_and_reuses_cache() {
                 Extent3d::d2(64, 64),
             )
             .expect("copy target");
+        renderer.before_submit();
         queue
             .submit([encoder.finish().expect("finish second encoder")])
             .expect("submit second");
@@ -695,6 +701,7 @@ fn renders_analytic_shadow_and_reuses_cache() {
                 Extent3d::d2(64, 64),
             )
             .expect("copy clipped target");
+        renderer.before_submit();
         queue
             .submit([encoder.finish().expect("finish clipped encoder")])
             .expect("submit clipped");
@@ -708,3 +715,223 @@ fn renders_analytic_shadow_and_reuses_cache() {
         readback.unmap();
     });
 }
+
+/// Layers recorded into one encoder keep distinct persistent geometry, and a
+/// second frame reuses those buffers without clobbering earlier layers.
+#[test]
+fn layers_in_one_encoder_keep_their_geometry() {
+    let _guard = gpu_test_lock().lock().expect("GPU test lock poisoned");
+    pollster::block_on(async {
+        let instance = astrelis_gpu_wgpu::create_instance(Default::default());
+        let adapter = match instance
+            .request_adapter(RequestAdapterOptions::default())
+            .await
+        {
+            Ok(adapter) => adapter,
+            Err(error) => {
+                eprintln!("skipping paint GPU test: {error}");
+                return;
+            }
+        };
+        let (device, queue) = adapter
+            .request_device(DeviceDescriptor::default())
+            .await
+            .expect("request device");
+        let texture = device.create_texture(TextureDescriptor {
+            label: Some("paint layer target".into()),
+            size: Extent3d::d2(16, 16),
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba8Unorm,
+            usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC,
+        });
+        let view = texture.create_view(TextureViewDescriptor::default());
+        let layer = |rect: Rect<astrelis_core::geometry::Logical, f32>, color: Color| {
+            let mut painter = Painter::new();
+            painter.fill_rect(rect, Brush::Solid(color)).unwrap();
+            painter.finish().unwrap()
+        };
+        let left = layer(Rect::from_xywh(0.0, 0.0, 8.0, 16.0), Color::RED);
+        let right = layer(Rect::from_xywh(8.0, 0.0, 8.0, 16.0), Color::BLUE);
+
+        for staging_chunk_size in [None, Some(256)] {
+            let mut renderer = Renderer::new(
+                device.clone(),
+                queue.clone(),
+                RendererOptions {
+                    antialiasing: Antialiasing::None,
+                    staging_chunk_size,
+                    ..Default::default()
+                },
+            )
+            .expect("renderer");
+            let render_target = || RenderTarget {
+                view: view.clone(),
+                format: TextureFormat::Rgba8Unorm,
+                size: Size::new(16, 16),
+                scale_factor: 1.0,
+                clear_color: Color::BLACK,
+            };
+            let readback = device.create_buffer(BufferDescriptor {
+                label: Some("paint layer readback".into()),
+                size: 256 * 16,
+                usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+                mapped_at_creation: false,
+            });
+            for frame in 0..2 {
+                let mut encoder =
+                    device.create_command_encoder(CommandEncoderDescriptor::default());
+                renderer
+                    .render_first_layer(&mut encoder, &left, render_target())
+                    .expect("first layer");
+                renderer
+                    .render_final_layer(&mut encoder, &right, render_target())
+                    .expect("final layer");
+                if frame == 1 {
+                    encoder
+                        .copy_texture_to_buffer(
+                            &TextureCopy {
+                                texture: texture.clone(),
+                                mip_level: 0,
+                                origin: Default::default(),
+                            },
+                            &BufferTextureCopy {
+                                buffer: readback.clone(),
+                                offset: 0,
+                                bytes_per_row: Some(256),
+                                rows_per_image: Some(16),
+                            },
+                            Extent3d::d2(16, 16),
+                        )
+                        .expect("copy target");
+                }
+                renderer.before_submit();
+                queue
+                    .submit([encoder.finish().expect("finish encoder")])
+                    .expect("submit");
+                renderer.after_submit().expect("after submit");
+            }
+            let mapping = readback.map_async(MapMode::Read, 0..256 * 16);
+            device.poll(PollMode::Wait).expect("wait");
+            mapping.await.expect("map");
+            let bytes = readback.read_mapped(0..256 * 16).expect("read");
+            let pixel = |x: usize, y: usize| {
+                let offset = y * 256 + x * 4;
+                &bytes[offset..offset + 4]
+            };
+            assert_eq!(pixel(3, 8), [255, 0, 0, 255]);
+            assert_eq!(pixel(12, 8), [0, 0, 255, 255]);
+            readback.unmap();
+        }
+    });
+}
+
+/// Encoders recorded before one submission, such as one per window, each keep
+/// their own geometry even though every render starts a new frame.
+#[test]
+fn encoders_submitted_together_keep_their_geometry() {
+    let _guard = gpu_test_lock().lock().expect("GPU test lock poisoned");
+    pollster::block_on(async {
+        let instance = astrelis_gpu_wgpu::create_instance(Default::default());
+        let adapter = match instance
+            .request_adapter(RequestAdapterOptions::default())
+            .await
+        {
+            Ok(adapter) => adapter,
+            Err(error) => {
+                eprintln!("skipping paint GPU test: {error}");
+                return;
+            }
+        };
+        let (device, queue) = adapter
+            .request_device(DeviceDescriptor::default())
+            .await
+            .expect("request device");
+        let fill = |color: Color| {
+            let mut painter = Painter::new();
+            painter
+                .fill_rect(Rect::from_xywh(0.0, 0.0, 16.0, 16.0), Brush::Solid(color))
+                .unwrap();
+            painter.finish().unwrap()
+        };
+        let lists = [fill(Color::RED), fill(Color::BLUE)];
+        let textures = lists.each_ref().map(|_| {
+            device.create_texture(TextureDescriptor {
+                label: Some("paint window target".into()),
+                size: Extent3d::d2(16, 16),
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: TextureFormat::Rgba8Unorm,
+                usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC,
+            })
+        });
+        let readback = device.create_buffer(BufferDescriptor {
+            label: Some("paint window readback".into()),
+            size: 256 * 16 * 2,
+            usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        for staging_chunk_size in [None, Some(256)] {
+            let mut renderer = Renderer::new(
+                device.clone(),
+                queue.clone(),
+                RendererOptions {
+                    antialiasing: Antialiasing::None,
+                    staging_chunk_size,
+                    ..Default::default()
+                },
+            )
+            .expect("renderer");
+            for _ in 0..2 {
+                let mut encoders = Vec::new();
+                for (index, (list, texture)) in lists.iter().zip(&textures).enumerate() {
+                    let mut encoder =
+                        device.create_command_encoder(CommandEncoderDescriptor::default());
+                    renderer
+                        .render(
+                            &mut encoder,
+                            list,
+                            RenderTarget {
+                                view: texture.create_view(TextureViewDescriptor::default()),
+                                format: TextureFormat::Rgba8Unorm,
+                                size: Size::new(16, 16),
+                                scale_factor: 1.0,
+                                clear_color: Color::BLACK,
+                            },
+                        )
+                        .expect("render window");
+                    encoder
+                        .copy_texture_to_buffer(
+                            &TextureCopy {
+                                texture: texture.clone(),
+                                mip_level: 0,
+                                origin: Default::default(),
+                            },
+                            &BufferTextureCopy {
+                                buffer: readback.clone(),
+                                offset: 256 * 16 * index as u64,
+                                bytes_per_row: Some(256),
+                                rows_per_image: Some(16),
+                            },
+                            Extent3d::d2(16, 16),
+                        )
+                        .expect("copy target");
+                    encoders.push(encoder.finish().expect("finish encoder"));
+                }
+                renderer.before_submit();
+                queue.submit(encoders).expect("submit");
+                renderer.after_submit().expect("after submit");
+            }
+            let mapping = readback.map_async(MapMode::Read, 0..256 * 16 * 2);
+            device.poll(PollMode::Wait).expect("wait");
+            mapping.await.expect("map");
+            let bytes = readback.read_mapped(0..256 * 16 * 2).expect("read");
+            assert_eq!(&bytes[8 * 256 + 32..][..4], [255, 0, 0, 255]);
+            assert_eq!(&bytes[(16 + 8) * 256 + 32..][..4], [0, 0, 255, 255]);
+            readback.unmap();
+        }
+    });
+}
@@ -282,6 +282,91 @@ pub enum LineJoin {
     Round,
 }
 
+/// Alternating on/off lengths applied along a stroke.
+///
+/// Intervals follow SVG semantics: even entries are drawn, odd entries are
+/// gaps, and an odd-length list repeats once to form an even period. The
+/// pattern restarts at every contour and continues across joins and curve
+/// segments, so each dash keeps the stroke's join and cap styles. Animating
+/// [`DashPattern::with_offset`] produces marching ants.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DashPattern {
+    intervals: [f32; DashPattern::MAX_INTERVALS],
+    len: u8,
+    offset: f32,
+}
+
+impl DashPattern {
+    /// Maximum number of intervals in one pattern.
+    pub const MAX_INTERVALS: usize = 8;
+
+    /// Creates a dash pattern starting `offset` units into its period.
+    pub fn new(intervals: &[f32], offset: f32) -> Result<Self, PaintError> {
+        if intervals.is_empty() || intervals.len() > Self::MAX_INTERVALS {
+            return Err(PaintError::new(format!(
+                "dash pattern must have between 1 and {} intervals",
+                Self::MAX_INTERVALS
+            )));
+        }
+        if intervals
+            .iter()
+            .any(|interval| !interval.is_finite() || *interval < 0.0)
+        {
+            return Err(PaintError::new(
+                "dash intervals must be finite and non-negative",
+            ));
+        }
+        if intervals.iter().sum::<f32>() <= 0.0 {
+            return Err(PaintError::new("dash intervals must not all be zero"));
+        }
+        if !offset.is_finite() {
+            return Err(PaintError::new("dash offset must be finite"));
+        }
+        let mut stored = [0.0; Self::MAX_INTERVALS];
+        stored[..intervals.len()].copy_from_slice(intervals);
+        Ok(Self {
+            intervals: stored,
+            len: intervals.len() as u8,
+            offset: 0.0,
+        }
+        .with_offset(offset))
+    }
+
+    /// On/off lengths as given to [`DashPattern::new`].
+    pub fn intervals(&self) -> &[f32] {
+        &self.intervals[..usize::from(self.len)]
+    }
+
+    /// Distance into the period where each contour starts, in `0..period`.
+    pub fn offset(&self) -> f32 {
+        self.offset
+    }
+
+    /// Length of one full on/off cycle.
+    pub fn period(&self) -> f32 {
+        let sum = self.intervals().iter().sum::<f32>();
+        if self.len.is_multiple_of(2) {
+            sum
+        } else {
+            sum * 2.0
+        }
+    }
+
+    /// Returns the pattern shifted to `offset`, wrapped into one period.
+    ///
+    /// A non-finite offset is kept as-is and rejected when the stroke is
+    /// recorded.
+    pub fn with_offset(self, offset: f32) -> Self {
+        let offset = if offset.is_finite() {
+            // Adding zero folds a negative-zero remainder into positive zero.
+            offset.rem_euclid(self.period()) + 0.0
+        } else {
+            offset
+        };
+        Self { offset, ..self }
+    }
+}
+
 /// Basic path stroke settings.
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct StrokeStyle {
@@ -293,6 +378,8 @@ pub struct StrokeStyle {
     pub join: LineJoin,
     /// Maximum miter length as a multiple of stroke width.
     pub miter_limit: f32,
+    /// Optional dash pattern; `None` draws a solid stroke.
+    pub dash: Option<DashPattern>,
 }
 
 impl Default for StrokeStyle {
@@ -302,6 +389,7 @@ impl Default for StrokeStyle {
             cap: LineCap::Butt,
             join: LineJoin::Miter,
             miter_limit: 4.0,
+            dash: None,
         }
     }
 }
@@ -1517,6 +1605,11 @@ fn validate_stroke(style: StrokeStyle) -> Result<(), PaintError> {
             "stroke miter limit must be finite and at least 1",
         ));
     }
+    if let Some(dash) = style.dash
+        && !dash.offset.is_finite()
+    {
+        return Err(PaintError::new("dash offset must be finite"));
+    }
     Ok(())
 }
 
@@ -1601,6 +1694,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn validates_dash_patterns() {
+        assert!(DashPattern::new(&[], 0.0).is_err());
+        assert!(DashPattern::new(&[0.0, 0.0], 0.0).is_err());
+        assert!(DashPattern::new(&[4.0, -1.0], 0.0).is_err());
+        assert!(DashPattern::new(&[1.0; 9], 0.0).is_err());
+        assert!(DashPattern::new(&[4.0], f32::NAN).is_err());
+
+        let dash = DashPattern::new(&[4.0, 2.0, 1.0], -3.0).unwrap();
+        assert_eq!(dash.intervals(), [4.0, 2.0, 1.0]);
+        assert_eq!(dash.period(), 14.0);
+        assert_eq!(dash.offset(), 11.0);
+        assert_eq!(dash.with_offset(30.0).offset(), 2.0);
+
+        let mut painter = Painter::new();
+        let rect = Rect::from_xywh(0.0, 0.0, 10.0, 10.0);
+        let brush = Brush::Solid(Color::WHITE);
+        let style = StrokeStyle {
+            dash: Some(dash),
+            ..Default::default()
+        };
+        assert!(painter.stroke_rect(rect, style, brush.clone()).is_ok());
+        let style = StrokeStyle {
+            dash: Some(dash.with_offset(f32::INFINITY)),
+            ..Default::default()
+        };
+        assert!(painter.stroke_rect(rect, style, brush).is_err());
+    }
+
     #[test]
     fn validates_shadows() {
         let rect = RoundedRect::new(
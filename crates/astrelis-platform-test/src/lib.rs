@@ -14,11 +14,12 @@ use std::{
     },
 };
 
-use astrelis_core::geometry::{Point, Size};
+use astrelis_core::geometry::{Physical, Point, Size};
 use astrelis_platform::{
-    Application, Clipboard, ControlFlow, DeviceEvent, DeviceId, EventLoopClosed, EventLoopProxy,
-    Monitor, PlatformContext, PlatformError, StartCause, Window, WindowAttributes,
-    WindowCapabilities, WindowCommand, WindowEvent, WindowId, WindowValue, backend,
+    Application, Clipboard, ControlFlow, CursorImage, CustomCursor, DeviceEvent, DeviceId,
+    EventLoopClosed, EventLoopProxy, Monitor, PlatformContext, PlatformError, StartCause, Window,
+    WindowAttributes, WindowCapabilities, WindowCommand, WindowEvent, WindowId, WindowValue,
+    backend,
 };
 use raw_window_handle::{
     DisplayHandle, HandleError, HasDisplayHandle, HasWindowHandle, WindowHandle,
@@ -42,6 +43,11 @@ pub enum ScriptEvent<T> {
     User(T),
     /// Invokes the pre-wait callback.
     AboutToWait,
+    /// Delivers [`WindowEvent::RedrawRequested`] to every window that
+    /// requested a redraw since the previous delivery, in request order.
+    PendingRedraws,
+    /// Delivers a low-memory warning.
+    MemoryWarning,
     /// Terminates the loop.
     Exit,
 }
@@ -63,6 +69,8 @@ pub enum Dispatch {
     User,
     /// Pre-wait callback.
     AboutToWait,
+    /// Low-memory callback.
+    MemoryWarning,
     /// Exit callback.
     Exiting,
 }
@@ -99,6 +107,7 @@ struct Shared<T> {
     state: Mutex<TestState>,
     queued: Mutex<VecDeque<T>>,
     destroyed: Mutex<VecDeque<WindowId>>,
+    redraws: Mutex<Vec<WindowId>>,
     open: AtomicBool,
     next_id: AtomicU64,
 }
@@ -108,6 +117,7 @@ pub struct TestRunner<T> {
     script: Vec<ScriptEvent<T>>,
     shared: Arc<Shared<T>>,
     monitors: Vec<Monitor>,
+    window_size: Size<Physical, u32>,
 }
 
 impl<T: Send + 'static> Default for TestRunner<T> {
@@ -125,10 +135,12 @@ impl<T: Send + 'static> TestRunner<T> {
                 state: Mutex::new(TestState::default()),
                 queued: Mutex::new(VecDeque::new()),
                 destroyed: Mutex::new(VecDeque::new()),
+                redraws: Mutex::new(Vec::new()),
                 open: AtomicBool::new(true),
                 next_id: AtomicU64::new(1),
             }),
             monitors: Vec::new(),
+            window_size: Size::new(800, 600),
         }
     }
 
@@ -142,6 +154,12 @@ impl<T: Send + 'static> TestRunner<T> {
         self.monitors = monitors;
     }
 
+    /// Sets the physical size reported by windows created without an
+    /// explicit size; 800×600 by default. Windows report scale 1.0.
+    pub fn set_window_size(&mut self, size: Size<Physical, u32>) {
+        self.window_size = size;
+    }
+
     /// Returns a proxy that can be used before or during the run.
     pub fn proxy(&self) -> EventLoopProxy<T> {
         EventLoopProxy::from_backend(Arc::new(TestProxy {
@@ -185,6 +203,7 @@ impl<T: Send + 'static> TestRunner<T> {
         let mut context = TestContext {
             shared: self.shared.clone(),
             monitors: self.monitors.clone(),
+            window_size: self.window_size,
             control_flow: ControlFlow::Wait,
             exited: false,
         };
@@ -213,6 +232,19 @@ impl<T: Send + 'static> TestRunner<T> {
                 ScriptEvent::AboutToWait => invoke(&mut context, Dispatch::AboutToWait, |c| {
                     app.about_to_wait(c)
                 }),
+                ScriptEvent::PendingRedraws => {
+                    let redraws = std::mem::take(
+                        &mut *self.shared.redraws.lock().expect("redraw queue poisoned"),
+                    );
+                    for id in redraws {
+                        invoke(&mut context, Dispatch::Window(id), |c| {
+                            app.window_event(c, id, WindowEvent::RedrawRequested)
+                        });
+                    }
+                }
+                ScriptEvent::MemoryWarning => invoke(&mut context, Dispatch::MemoryWarning, |c| {
+                    app.memory_warning(c)
+                }),
                 ScriptEvent::Exit => context.exited = true,
             }
             drain_proxy(&mut context, app);
@@ -270,6 +302,7 @@ fn drain_destroyed<A: Application>(context: &mut TestContext<A::UserEvent>, app:
 struct TestContext<T> {
     shared: Arc<Shared<T>>,
     monitors: Vec<Monitor>,
+    window_size: Size<Physical, u32>,
     control_flow: ControlFlow,
     exited: bool,
 }
@@ -277,6 +310,9 @@ struct TestContext<T> {
 impl<T: Send + 'static> backend::ActiveContext<T> for TestContext<T> {
     fn create_window(&mut self, attributes: WindowAttributes) -> Result<Window, PlatformError> {
         let id = WindowId(self.shared.next_id.fetch_add(1, Ordering::Relaxed));
+        let size = attributes.inner_size.map_or(self.window_size, |size| {
+            Size::new(size.width.round() as u32, size.height.round() as u32)
+        });
         self.shared
             .state
             .lock()
@@ -291,6 +327,7 @@ impl<T: Send + 'static> backend::ActiveContext<T> for TestContext<T> {
             ));
         Ok(Window::from_backend(Arc::new(TestWindow {
             id,
+            size,
             shared: self.shared.clone(),
         })))
     }
@@ -324,6 +361,9 @@ impl<T: Send + 'static> backend::ActiveContext<T> for TestContext<T> {
             shared: self.shared.clone(),
         }))
     }
+    fn create_custom_cursor(&mut self, image: &CursorImage) -> Result<CustomCursor, PlatformError> {
+        Ok(CustomCursor::from_backend(Arc::new(image.clone())))
+    }
     fn exit(&mut self) {
         self.exited = true;
         self.shared
@@ -405,6 +445,7 @@ impl<T: Send + 'static> backend::EventLoopProxy<T> for TestProxy<T> {
 
 struct TestWindow<T> {
     id: WindowId,
+    size: Size<Physical, u32>,
     shared: Arc<Shared<T>>,
 }
 
@@ -460,13 +501,20 @@ impl<T: Send + 'static> backend::Window for TestWindow<T> {
             .ok_or_else(|| PlatformError::new("unknown test window"))?;
         window.commands.push(command.clone());
         let value = match command {
-            WindowCommand::InnerSize => Some(WindowValue::PhysicalSize(Size::new(800, 600))),
+            WindowCommand::InnerSize => Some(WindowValue::PhysicalSize(self.size)),
             WindowCommand::OuterPosition => Some(WindowValue::PhysicalPosition(Point::new(0, 0))),
             WindowCommand::ScaleFactor => Some(WindowValue::Float(1.0)),
             WindowCommand::IsFocused => Some(WindowValue::Bool(false)),
             WindowCommand::IsMaximized => Some(WindowValue::Bool(false)),
             WindowCommand::Theme => Some(WindowValue::Theme(None)),
             WindowCommand::CurrentMonitor => Some(WindowValue::Monitor(None)),
+            WindowCommand::RequestRedraw => {
+                let mut redraws = self.shared.redraws.lock().expect("redraw queue poisoned");
+                if !redraws.contains(&self.id) {
+                    redraws.push(self.id);
+                }
+                None
+            }
             WindowCommand::SetCursorGrab(_)
             | WindowCommand::SetCursorPosition(_)
             | WindowCommand::DragWindow
@@ -484,7 +532,10 @@ impl<T: Send + 'static> backend::Window for TestWindow<T> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use astrelis_platform::{Application, WindowAttributes};
+    use astrelis_platform::{
+        Application, CursorGrabMode, CursorIcon, CursorRequests, UserAttention, WindowAttributes,
+        WindowIcon, WindowLevel,
+    };
 
     struct App {
         window: Option<Window>,
@@ -565,6 +616,177 @@ mod tests {
         );
     }
 
+    #[test]
+    fn relative_mouse_mode_tries_lock_then_confine_and_keeps_the_cursor_visible() {
+        struct RelativeApp;
+        impl Application for RelativeApp {
+            type UserEvent = ();
+            fn resumed(&mut self, context: &mut PlatformContext<'_, ()>) {
+                let window = context.create_window(WindowAttributes::default()).unwrap();
+                assert!(window.set_relative_mouse_mode(true).is_err());
+                context.exit();
+            }
+        }
+        let mut runner = TestRunner::new();
+        runner.push(ScriptEvent::Resumed);
+        let state = runner.run(RelativeApp).unwrap();
+        assert_eq!(
+            state.windows[0].1.commands,
+            [
+                WindowCommand::SetCursorGrab(CursorGrabMode::Locked),
+                WindowCommand::SetCursorGrab(CursorGrabMode::Confined),
+            ]
+        );
+    }
+
+    #[test]
+    fn cursor_requests_apply_the_highest_priority_only_when_it_changes() {
+        struct CursorApp;
+        impl Application for CursorApp {
+            type UserEvent = ();
+            fn resumed(&mut self, context: &mut PlatformContext<'_, ()>) {
+                let window = context.create_window(WindowAttributes::default()).unwrap();
+                let image = CursorImage::from_rgba([255; 16], 2, 2, 1, 1).unwrap();
+                assert!(CursorImage::from_rgba([255; 12], 2, 2, 0, 0).is_err());
+                assert!(CursorImage::from_rgba([255; 16], 2, 2, 2, 0).is_err());
+                let custom = context.create_custom_cursor(&image).unwrap();
+
+                let mut cursors = CursorRequests::new();
+                cursors.request(0, CursorIcon::Text);
+                cursors.request(10, custom.clone());
+                cursors.request(5, CursorIcon::Pointer);
+                cursors.apply(&window).unwrap();
+                cursors.request(10, custom.clone());
+                cursors.apply(&window).unwrap();
+                cursors.apply(&window).unwrap();
+                context.exit();
+            }
+        }
+        let mut runner = TestRunner::new();
+        runner.push(ScriptEvent::Resumed);
+        let state = runner.run(CursorApp).unwrap();
+        let commands = &state.windows[0].1.commands;
+        assert_eq!(commands.len(), 2);
+        assert!(matches!(commands[0], WindowCommand::SetCustomCursor(_)));
+        assert_eq!(
+            commands[1],
+            WindowCommand::SetCursorIcon(CursorIcon::Default)
+        );
+    }
+
+    #[test]
+    fn window_icon_and_attention_requests_are_recorded() {
+        struct IconApp;
+        impl Application for IconApp {
+            type UserEvent = ();
+            fn resumed(&mut self, context: &mut PlatformContext<'_, ()>) {
+                let window = context.create_window(WindowAttributes::default()).unwrap();
+                assert!(WindowIcon::from_rgba([0; 12], 2, 2).is_err());
+                assert!(WindowIcon::from_rgba([], 0, 0).is_err());
+                let icon = WindowIcon::from_rgba([255; 16], 2, 2).unwrap();
+                window.set_window_icon(Some(icon.clone()));
+                window.set_title("Build finished");
+                window.request_user_attention(Some(UserAttention::Informational));
+                window.set_window_icon(None);
+                context.exit();
+            }
+        }
+        let mut runner = TestRunner::new();
+        runner.push(ScriptEvent::Resumed);
+        let state = runner.run(IconApp).unwrap();
+        let icon = WindowIcon::from_rgba([255; 16], 2, 2).unwrap();
+        assert_eq!(
+            state.windows[0].1.commands,
+            [
+                WindowCommand::SetWindowIcon(Some(icon)),
+                WindowCommand::SetTitle("Build finished".into()),
+                WindowCommand::RequestUserAttention(Some(UserAttention::Informational)),
+                WindowCommand::SetWindowIcon(None),
+            ]
+        );
+    }
+
+    #[test]
+    fn overlay_attributes_and_setters_are_recorded() {
+        struct OverlayApp;
+        impl Application for OverlayApp {
+            type UserEvent = ();
+            fn resumed(&mut self, context: &mut PlatformContext<'_, ()>) {
+                let window = context
+                    .create_window(WindowAttributes {
+                        transparent: true,
+                        cursor_hittest: false,
+                        level: WindowLevel::AlwaysOnTop,
+                        ..WindowAttributes::default()
+                    })
+                    .unwrap();
+                window.set_cursor_hittest(true).unwrap();
+                window.set_window_level(WindowLevel::Normal);
+                window.set_transparent(false);
+                context.exit();
+            }
+        }
+        let mut runner = TestRunner::new();
+        runner.push(ScriptEvent::Resumed);
+        let state = runner.run(OverlayApp).unwrap();
+        let (_, window) = &state.windows[0];
+        assert!(window.attributes.transparent);
+        assert!(!window.attributes.cursor_hittest);
+        assert_eq!(window.attributes.level, WindowLevel::AlwaysOnTop);
+        assert_eq!(
+            window.commands,
+            [
+                WindowCommand::SetCursorHittest(true),
+                WindowCommand::SetWindowLevel(WindowLevel::Normal),
+                WindowCommand::SetTransparent(false),
+            ]
+        );
+    }
+
+    #[test]
+    fn pending_redraws_are_delivered_once_per_window_in_request_order() {
+        #[derive(Default)]
+        struct RedrawApp {
+            windows: Vec<Window>,
+            redrawn: Vec<WindowId>,
+        }
+        impl Application for RedrawApp {
+            type UserEvent = ();
+            fn resumed(&mut self, context: &mut PlatformContext<'_, ()>) {
+                let sized = context
+                    .create_window(WindowAttributes {
+                        inner_size: Some(Size::new(320.4, 200.6)),
+                        ..WindowAttributes::default()
+                    })
+                    .unwrap();
+                let default = context.create_window(WindowAttributes::default()).unwrap();
+                assert_eq!(sized.inner_size().unwrap(), Size::new(320, 201));
+                assert_eq!(default.inner_size().unwrap(), Size::new(1024, 768));
+                default.request_redraw();
+                sized.request_redraw();
+                default.request_redraw();
+                self.windows = vec![sized, default];
+            }
+            fn window_event(
+                &mut self,
+                _context: &mut PlatformContext<'_, ()>,
+                window: WindowId,
+                event: WindowEvent,
+            ) {
+                if event == WindowEvent::RedrawRequested {
+                    self.redrawn.push(window);
+                }
+            }
+        }
+        let mut runner = TestRunner::new();
+        runner.set_window_size(Size::new(1024, 768));
+        runner.push(ScriptEvent::Resumed);
+        runner.push(ScriptEvent::PendingRedraws);
+        runner.push(ScriptEvent::PendingRedraws);
+        let (app, _) = runner.run_return(RedrawApp::default()).unwrap();
+        assert_eq!(app.redrawn, [app.windows[1].id(), app.windows[0].id()]);
+    }
+
     #[test]
     fn clipboard_is_shared_and_recorded() {
         struct ClipboardApp;
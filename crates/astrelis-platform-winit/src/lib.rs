@@ -181,6 +181,10 @@ impl<A: Application> ApplicationHandler<A::UserEvent> for Adapter<A> {
         self.with_context(event_loop, |app, context| app.about_to_wait(context));
     }
 
+    fn memory_warning(&mut self, event_loop: &ActiveEventLoop) {
+        self.with_context(event_loop, |app, context| app.memory_warning(context));
+    }
+
     fn exiting(&mut self, event_loop: &ActiveEventLoop) {
         self.with_context(event_loop, |app, context| app.exiting(context));
     }
@@ -202,6 +206,7 @@ impl<T: Send + 'static> backend::ActiveContext<T> for Context<'_, T> {
         attributes: astrelis_platform::WindowAttributes,
     ) -> Result<astrelis_platform::Window, PlatformError> {
         astrelis_profiling::profile_scope!("platform.create_window");
+        let cursor_hittest = attributes.cursor_hittest;
         let attributes = window::attributes(attributes);
         #[cfg(target_arch = "wasm32")]
         let attributes = {
@@ -219,6 +224,11 @@ impl<T: Send + 'static> backend::ActiveContext<T> for Context<'_, T> {
             .event_loop
             .create_window(attributes)
             .map_err(|error| PlatformError::new(error.to_string()))?;
+        if !cursor_hittest {
+            native
+                .set_cursor_hittest(false)
+                .map_err(|error| PlatformError::new(error.to_string()))?;
+        }
         let id = WindowId(self.next_window_id.fetch_add(1, Ordering::Relaxed));
         let backend = Arc::new(WinitWindow {
             id,
@@ -258,6 +268,25 @@ impl<T: Send + 'static> backend::ActiveContext<T> for Context<'_, T> {
     fn event_loop_proxy(&self) -> EventLoopProxy<T> {
         EventLoopProxy::from_backend(Arc::new(WinitProxy(self.proxy.clone())))
     }
+    fn create_custom_cursor(
+        &mut self,
+        image: &astrelis_platform::CursorImage,
+    ) -> Result<astrelis_platform::CustomCursor, PlatformError> {
+        let (hotspot_x, hotspot_y) = image.hotspot();
+        let source = winit::window::CustomCursor::from_rgba(
+            image.rgba(),
+            image.width(),
+            image.height(),
+            hotspot_x,
+            hotspot_y,
+        )
+        .map_err(|error| PlatformError::new(error.to_string()))?;
+        let cursor = self.event_loop.create_custom_cursor(source);
+        Ok(astrelis_platform::CustomCursor::from_backend(Arc::new(
+            cursor,
+        )))
+    }
+
     fn clipboard(&self) -> astrelis_platform::Clipboard {
         self.clipboard.clone()
     }
@@ -2,8 +2,9 @@ use std::{fmt, sync::Arc};
 
 use astrelis_core::geometry::{Point, Size};
 use astrelis_platform::{
-    CursorGrabMode, CursorIcon, PlatformError, ResizeDirection, Theme, WindowAttributes,
-    WindowCapabilities, WindowCommand, WindowId, WindowLevel, WindowValue, backend,
+    CursorGrabMode, CursorIcon, PlatformError, ResizeDirection, Theme, UserAttention,
+    WindowAttributes, WindowCapabilities, WindowCommand, WindowId, WindowLevel, WindowValue,
+    backend,
 };
 use raw_window_handle::{
     DisplayHandle, HandleError, HasDisplayHandle, HasWindowHandle, WindowHandle,
@@ -39,8 +40,15 @@ impl backend::Window for WinitWindow {
         WindowCapabilities {
             ime: true,
             cursor_confined: cfg!(any(target_os = "linux", target_os = "windows")),
-            cursor_locked: cfg!(any(target_os = "linux", target_os = "windows")),
+            // X11 cannot lock; `Window::set_relative_mouse_mode` falls back
+            // to confinement there.
+            cursor_locked: cfg!(any(target_os = "linux", target_os = "macos")),
             transparent: true,
+            cursor_hittest: cfg!(any(
+                target_os = "windows",
+                target_os = "macos",
+                target_os = "linux"
+            )),
             drag_window: true,
             drag_resize_window: true,
         }
@@ -108,6 +116,20 @@ impl backend::Window for WinitWindow {
                 });
                 None
             }
+            WindowCommand::SetCustomCursor(cursor) => {
+                let cursor = cursor
+                    .backend()
+                    .downcast_ref::<winit::window::CustomCursor>()
+                    .ok_or_else(|| PlatformError::new("custom cursor belongs to another backend"))?
+                    .clone();
+                #[cfg(not(target_arch = "wasm32"))]
+                self.native.set_cursor(cursor);
+                #[cfg(target_arch = "wasm32")]
+                defer_window_command(self.native.clone(), move |window| {
+                    window.set_cursor(cursor);
+                });
+                None
+            }
             WindowCommand::SetCursorVisible(value) => {
                 self.native.set_cursor_visible(value);
                 None
@@ -176,6 +198,36 @@ impl backend::Window for WinitWindow {
             WindowCommand::CurrentMonitor => Some(WindowValue::Monitor(
                 self.native.current_monitor().map(crate::convert::monitor),
             )),
+            WindowCommand::SetTransparent(value) => {
+                self.native.set_transparent(value);
+                None
+            }
+            WindowCommand::SetWindowLevel(value) => {
+                self.native.set_window_level(map_level(value));
+                None
+            }
+            WindowCommand::SetCursorHittest(value) => {
+                self.native.set_cursor_hittest(value).map_err(error)?;
+                None
+            }
+            WindowCommand::SetWindowIcon(icon) => {
+                let icon = icon
+                    .map(|icon| {
+                        winit::window::Icon::from_rgba(
+                            icon.rgba().to_vec(),
+                            icon.width(),
+                            icon.height(),
+                        )
+                    })
+                    .transpose()
+                    .map_err(error)?;
+                self.native.set_window_icon(icon);
+                None
+            }
+            WindowCommand::RequestUserAttention(value) => {
+                self.native.request_user_attention(value.map(map_attention));
+                None
+            }
             _ => return Err(PlatformError::new("unsupported window command")),
         };
         Ok(result)
@@ -201,11 +253,7 @@ pub(crate) fn attributes(value: WindowAttributes) -> winit::window::WindowAttrib
         .with_transparent(value.transparent)
         .with_active(value.active)
         .with_maximized(value.maximized)
-        .with_window_level(match value.level {
-            WindowLevel::AlwaysOnBottom => winit::window::WindowLevel::AlwaysOnBottom,
-            WindowLevel::Normal => winit::window::WindowLevel::Normal,
-            WindowLevel::AlwaysOnTop => winit::window::WindowLevel::AlwaysOnTop,
-        })
+        .with_window_level(map_level(value.level))
         .with_theme(value.theme.map(|theme| match theme {
             Theme::Light => winit::window::Theme::Light,
             Theme::Dark => winit::window::Theme::Dark,
@@ -234,6 +282,19 @@ fn map_theme(value: winit::window::Theme) -> Theme {
         winit::window::Theme::Dark => Theme::Dark,
     }
 }
+fn map_level(value: WindowLevel) -> winit::window::WindowLevel {
+    match value {
+        WindowLevel::AlwaysOnBottom => winit::window::WindowLevel::AlwaysOnBottom,
+        WindowLevel::Normal => winit::window::WindowLevel::Normal,
+        WindowLevel::AlwaysOnTop => winit::window::WindowLevel::AlwaysOnTop,
+    }
+}
+fn map_attention(value: UserAttention) -> winit::window::UserAttentionType {
+    match value {
+        UserAttention::Critical => winit::window::UserAttentionType::Critical,
+        UserAttention::Informational => winit::window::UserAttentionType::Informational,
+    }
+}
 fn map_grab(value: CursorGrabMode) -> winit::window::CursorGrabMode {
     match value {
         CursorGrabMode::None => winit::window::CursorGrabMode::None,
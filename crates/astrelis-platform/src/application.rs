@@ -3,8 +3,8 @@
 use std::{fmt, sync::Arc};
 
 use crate::{
-    Clipboard, DeviceEvent, DeviceId, EventLoopClosed, Instant, Monitor, PlatformError, StartCause,
-    Window, WindowAttributes, WindowEvent, WindowId, backend,
+    Clipboard, CursorImage, CustomCursor, DeviceEvent, DeviceId, EventLoopClosed, Instant, Monitor,
+    PlatformError, StartCause, Window, WindowAttributes, WindowEvent, WindowId, backend,
 };
 
 /// Determines how the event loop waits for new work.
@@ -98,6 +98,15 @@ impl<'a, T> PlatformContext<'a, T> {
         self.inner.clipboard()
     }
 
+    /// Uploads a cursor image for use with
+    /// [`Window::set_cursor`](crate::Window::set_cursor) on any window.
+    pub fn create_custom_cursor(
+        &mut self,
+        image: &CursorImage,
+    ) -> Result<CustomCursor, PlatformError> {
+        self.inner.create_custom_cursor(image)
+    }
+
     /// Requests event-loop termination.
     pub fn exit(&mut self) {
         self.inner.exit();
@@ -143,6 +152,9 @@ pub trait Application: 'static {
         _event: Self::UserEvent,
     ) {
     }
+    /// Called when the OS is low on memory and asks the app to release
+    /// caches; mobile platforms may terminate apps that do not.
+    fn memory_warning(&mut self, _context: &mut PlatformContext<'_, Self::UserEvent>) {}
     /// Called immediately before the event loop waits.
     fn about_to_wait(&mut self, _context: &mut PlatformContext<'_, Self::UserEvent>) {}
     /// Called when the event loop is terminating.
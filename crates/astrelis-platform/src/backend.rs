@@ -3,8 +3,8 @@
 use std::fmt::Debug;
 
 use crate::{
-    Clipboard as ClipboardHandle, ControlFlow, EventLoopClosed, Monitor, PlatformError,
-    WindowAttributes, WindowCapabilities, WindowId,
+    Clipboard as ClipboardHandle, ControlFlow, CursorImage, CustomCursor, EventLoopClosed, Monitor,
+    PlatformError, WindowAttributes, WindowCapabilities, WindowId,
 };
 
 /// Backend text clipboard operations.
@@ -57,6 +57,15 @@ pub trait ActiveContext<T> {
     fn event_loop_proxy(&self) -> crate::EventLoopProxy<T>;
     /// Returns the process clipboard handle.
     fn clipboard(&self) -> ClipboardHandle;
+    /// Uploads a custom cursor image.
+    fn create_custom_cursor(
+        &mut self,
+        _image: &CursorImage,
+    ) -> Result<CustomCursor, PlatformError> {
+        Err(PlatformError::new(
+            "custom cursors are unsupported by this backend",
+        ))
+    }
     /// Requests exit.
     fn exit(&mut self);
 }
@@ -0,0 +1,191 @@
+//! Custom cursor images and per-frame cursor arbitration.
+
+use std::{any::Any, fmt, sync::Arc};
+
+use crate::{CursorIcon, PlatformError, Window};
+
+/// Straight-alpha RGBA8 pixels for a custom cursor.
+#[derive(Clone, PartialEq, Eq)]
+pub struct CursorImage {
+    rgba: Arc<[u8]>,
+    width: u16,
+    height: u16,
+    hotspot: (u16, u16),
+}
+
+impl CursorImage {
+    /// Validates row-major RGBA8 pixels and a hotspot inside the image.
+    pub fn from_rgba(
+        rgba: impl Into<Vec<u8>>,
+        width: u16,
+        height: u16,
+        hotspot_x: u16,
+        hotspot_y: u16,
+    ) -> Result<Self, PlatformError> {
+        let rgba = rgba.into();
+        if width == 0 || height == 0 {
+            return Err(PlatformError::new("cursor image must be non-empty"));
+        }
+        if rgba.len() != width as usize * height as usize * 4 {
+            return Err(PlatformError::new(
+                "cursor pixel data does not match its dimensions",
+            ));
+        }
+        if hotspot_x >= width || hotspot_y >= height {
+            return Err(PlatformError::new("cursor hotspot is outside the image"));
+        }
+        Ok(Self {
+            rgba: rgba.into(),
+            width,
+            height,
+            hotspot: (hotspot_x, hotspot_y),
+        })
+    }
+
+    /// Row-major RGBA8 pixels.
+    pub fn rgba(&self) -> &[u8] {
+        &self.rgba
+    }
+
+    /// Width in pixels.
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    /// Height in pixels.
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Click position relative to the top-left pixel.
+    pub fn hotspot(&self) -> (u16, u16) {
+        self.hotspot
+    }
+}
+
+impl fmt::Debug for CursorImage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CursorImage")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .field("hotspot", &self.hotspot)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A custom cursor uploaded to the platform, cheap to clone and reuse.
+///
+/// Created by [`PlatformContext::create_custom_cursor`](crate::PlatformContext::create_custom_cursor).
+/// Clones compare equal; separately created cursors do not.
+#[derive(Clone)]
+pub struct CustomCursor {
+    inner: Arc<dyn Any + Send + Sync>,
+}
+
+impl CustomCursor {
+    /// Wraps backend cursor storage.
+    pub fn from_backend(inner: Arc<dyn Any + Send + Sync>) -> Self {
+        Self { inner }
+    }
+
+    /// Backend cursor storage, for backends to downcast.
+    pub fn backend(&self) -> &(dyn Any + Send + Sync) {
+        &*self.inner
+    }
+}
+
+impl PartialEq for CustomCursor {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl Eq for CustomCursor {}
+
+impl fmt::Debug for CustomCursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CustomCursor").finish_non_exhaustive()
+    }
+}
+
+/// A standard or custom cursor.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Cursor {
+    /// A standard system cursor.
+    Icon(CursorIcon),
+    /// An uploaded cursor image.
+    Custom(CustomCursor),
+}
+
+impl Default for Cursor {
+    fn default() -> Self {
+        Self::Icon(CursorIcon::Default)
+    }
+}
+
+impl From<CursorIcon> for Cursor {
+    fn from(icon: CursorIcon) -> Self {
+        Self::Icon(icon)
+    }
+}
+
+impl From<CustomCursor> for Cursor {
+    fn from(cursor: CustomCursor) -> Self {
+        Self::Custom(cursor)
+    }
+}
+
+/// Chooses one cursor per frame among layers that each request one.
+///
+/// Layers such as UI, gizmos, and game code call [`CursorRequests::request`]
+/// during a frame; [`CursorRequests::apply`] then shows the highest-priority
+/// request, or the default cursor when nothing asked, touching the window
+/// only when the choice changes.
+#[derive(Clone, Debug, Default)]
+pub struct CursorRequests {
+    pending: Option<(i32, Cursor)>,
+    applied: Option<Cursor>,
+}
+
+impl CursorRequests {
+    /// Creates arbitration with no requests.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests a cursor for this frame; on equal priority the later
+    /// request wins.
+    pub fn request(&mut self, priority: i32, cursor: impl Into<Cursor>) {
+        if self
+            .pending
+            .as_ref()
+            .is_none_or(|(current, _)| priority >= *current)
+        {
+            self.pending = Some((priority, cursor.into()));
+        }
+    }
+
+    /// Cursor that [`CursorRequests::apply`] would show now.
+    pub fn requested(&self) -> Option<&Cursor> {
+        self.pending.as_ref().map(|(_, cursor)| cursor)
+    }
+
+    /// Shows the winning cursor and clears this frame's requests.
+    pub fn apply(&mut self, window: &Window) -> Result<(), PlatformError> {
+        let cursor = self
+            .pending
+            .take()
+            .map_or_else(Cursor::default, |(_, cursor)| cursor);
+        if self.applied.as_ref() != Some(&cursor) {
+            window.set_cursor(&cursor)?;
+            self.applied = Some(cursor);
+        }
+        Ok(())
+    }
+
+    /// Forgets the applied cursor, so the next apply always reaches the
+    /// window, as after it is recreated.
+    pub fn invalidate(&mut self) {
+        self.applied = None;
+    }
+}
@@ -29,6 +29,7 @@ pub enum PhysicalKey {
 
 /// Common physical key codes.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 pub enum KeyCode {
     /// `A` key.
@@ -180,6 +181,7 @@ pub enum KeyLocation {
 
 /// Modifier state.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Modifiers {
     /// Shift is active.
     pub shift: bool,
@@ -214,6 +216,7 @@ pub struct KeyboardInput {
 
 /// Pointer or mouse button.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PointerButton {
     /// Primary button.
     Primary,
@@ -272,6 +275,38 @@ pub enum TouchForce {
     Normalized(f64),
 }
 
+impl TouchForce {
+    /// Pressure where `1.0` is the strongest the device reports.
+    ///
+    /// Stylus pressure is corrected for the pen's altitude angle, so tilting
+    /// the pen does not lower the result.
+    pub fn normalized(&self) -> f64 {
+        match *self {
+            Self::Calibrated {
+                force,
+                max_possible_force,
+                altitude_angle,
+            } => {
+                let force = match altitude_angle {
+                    Some(angle) => force / angle.sin(),
+                    None => force,
+                };
+                force / max_possible_force
+            }
+            Self::Normalized(force) => force,
+        }
+    }
+
+    /// Stylus altitude in radians, where `π / 2` is perpendicular to the
+    /// surface; `None` for fingers and unsupported pens.
+    pub fn altitude_angle(&self) -> Option<f64> {
+        match *self {
+            Self::Calibrated { altitude_angle, .. } => altitude_angle,
+            Self::Normalized(_) => None,
+        }
+    }
+}
+
 /// A touch contact.
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Touch {
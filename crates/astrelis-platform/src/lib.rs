@@ -4,6 +4,7 @@
 
 mod application;
 mod clipboard;
+mod cursor;
 mod error;
 mod event;
 mod input;
@@ -11,6 +12,7 @@ mod window;
 
 pub use application::*;
 pub use clipboard::*;
+pub use cursor::*;
 pub use error::*;
 pub use event::*;
 pub use input::*;
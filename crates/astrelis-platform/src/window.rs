@@ -7,7 +7,7 @@ use raw_window_handle::{
     DisplayHandle, HandleError, HasDisplayHandle, HasWindowHandle, WindowHandle,
 };
 
-use crate::{ImePurpose, PlatformError, backend};
+use crate::{Cursor, CustomCursor, ImePurpose, PlatformError, backend};
 
 /// Stable window identifier.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -115,6 +115,71 @@ pub enum ResizeDirection {
     NorthWest,
 }
 
+/// Straight-alpha RGBA8 pixels for a window and taskbar icon.
+#[derive(Clone, PartialEq, Eq)]
+pub struct WindowIcon {
+    rgba: Arc<[u8]>,
+    width: u32,
+    height: u32,
+}
+
+impl WindowIcon {
+    /// Validates row-major RGBA8 pixels.
+    pub fn from_rgba(
+        rgba: impl Into<Vec<u8>>,
+        width: u32,
+        height: u32,
+    ) -> Result<Self, PlatformError> {
+        let rgba = rgba.into();
+        if width == 0 || height == 0 {
+            return Err(PlatformError::new("window icon must be non-empty"));
+        }
+        if rgba.len() as u64 != u64::from(width) * u64::from(height) * 4 {
+            return Err(PlatformError::new(
+                "icon pixel data does not match its dimensions",
+            ));
+        }
+        Ok(Self {
+            rgba: rgba.into(),
+            width,
+            height,
+        })
+    }
+
+    /// Row-major RGBA8 pixels.
+    pub fn rgba(&self) -> &[u8] {
+        &self.rgba
+    }
+
+    /// Width in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Height in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+}
+
+impl fmt::Debug for WindowIcon {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WindowIcon")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .finish_non_exhaustive()
+    }
+}
+
+/// How urgently a window asks for the user's attention.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum UserAttention {
+    /// Flashes or bounces until the window is focused.
+    Critical,
+    /// Flashes or bounces once.
+    Informational,
+}
+
 /// Focused platform capability flags.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub struct WindowCapabilities {
@@ -126,6 +191,8 @@ pub struct WindowCapabilities {
     pub cursor_locked: bool,
     /// Transparent windows are supported.
     pub transparent: bool,
+    /// Pointer input can pass through the window.
+    pub cursor_hittest: bool,
     /// Client-area window dragging is supported.
     pub drag_window: bool,
     /// Client-area resize dragging is supported.
@@ -153,6 +220,9 @@ pub struct WindowAttributes {
     pub decorations: bool,
     /// Transparent framebuffer.
     pub transparent: bool,
+    /// Receives pointer input; `false` lets clicks reach whatever is below,
+    /// for overlays.
+    pub cursor_hittest: bool,
     /// Activate on creation.
     pub active: bool,
     /// Initially maximized.
@@ -175,6 +245,7 @@ impl Default for WindowAttributes {
             resizable: true,
             decorations: true,
             transparent: false,
+            cursor_hittest: true,
             active: true,
             maximized: false,
             theme: None,
@@ -215,6 +286,8 @@ pub enum WindowCommand {
     SetDecorations(bool),
     /// Set cursor icon.
     SetCursorIcon(CursorIcon),
+    /// Set an uploaded custom cursor.
+    SetCustomCursor(CustomCursor),
     /// Set cursor visibility.
     SetCursorVisible(bool),
     /// Set cursor grab.
@@ -237,6 +310,16 @@ pub enum WindowCommand {
     Theme,
     /// Query current monitor.
     CurrentMonitor,
+    /// Change framebuffer transparency.
+    SetTransparent(bool),
+    /// Change stacking level.
+    SetWindowLevel(WindowLevel),
+    /// Change whether the window receives pointer input.
+    SetCursorHittest(bool),
+    /// Change or clear the window icon.
+    SetWindowIcon(Option<WindowIcon>),
+    /// Request or cancel a taskbar attention request.
+    RequestUserAttention(Option<UserAttention>),
 }
 
 /// Value returned by a backend command.
@@ -306,6 +389,16 @@ impl Window {
     pub fn set_title(&self, title: impl Into<String>) {
         let _ = self.command(WindowCommand::SetTitle(title.into()));
     }
+    /// Changes the window and taskbar icon, or restores the default with
+    /// `None`. Ignored where windows have no icon, such as macOS.
+    pub fn set_window_icon(&self, icon: Option<WindowIcon>) {
+        let _ = self.command(WindowCommand::SetWindowIcon(icon));
+    }
+    /// Flashes the taskbar entry or bounces the dock icon until the window
+    /// is focused, or cancels a request with `None`. Ignored while focused.
+    pub fn request_user_attention(&self, attention: Option<UserAttention>) {
+        let _ = self.command(WindowCommand::RequestUserAttention(attention));
+    }
     /// Changes visibility.
     pub fn set_visible(&self, visible: bool) {
         let _ = self.command(WindowCommand::SetVisible(visible));
@@ -340,6 +433,20 @@ impl Window {
     pub fn set_borderless_fullscreen(&self, value: bool) {
         let _ = self.command(WindowCommand::SetFullscreen(value));
     }
+    /// Changes framebuffer transparency. The surface must also use a
+    /// non-opaque alpha mode for the desktop to show through.
+    pub fn set_transparent(&self, value: bool) {
+        let _ = self.command(WindowCommand::SetTransparent(value));
+    }
+    /// Keeps the window above or below others.
+    pub fn set_window_level(&self, value: WindowLevel) {
+        let _ = self.command(WindowCommand::SetWindowLevel(value));
+    }
+    /// Lets pointer input pass through the window when `false`.
+    pub fn set_cursor_hittest(&self, value: bool) -> Result<(), PlatformError> {
+        self.command(WindowCommand::SetCursorHittest(value))
+            .map(|_| ())
+    }
     /// Changes resizability.
     pub fn set_resizable(&self, value: bool) {
         let _ = self.command(WindowCommand::SetResizable(value));
@@ -352,6 +459,18 @@ impl Window {
     pub fn set_cursor_icon(&self, value: CursorIcon) {
         let _ = self.command(WindowCommand::SetCursorIcon(value));
     }
+    /// Changes to a standard or custom cursor.
+    pub fn set_cursor(&self, cursor: &Cursor) -> Result<(), PlatformError> {
+        match cursor {
+            Cursor::Icon(icon) => {
+                self.set_cursor_icon(*icon);
+                Ok(())
+            }
+            Cursor::Custom(cursor) => self
+                .command(WindowCommand::SetCustomCursor(cursor.clone()))
+                .map(|_| ()),
+        }
+    }
     /// Changes cursor visibility.
     pub fn set_cursor_visible(&self, value: bool) {
         let _ = self.command(WindowCommand::SetCursorVisible(value));
@@ -361,6 +480,29 @@ impl Window {
         self.command(WindowCommand::SetCursorGrab(value))
             .map(|_| ())
     }
+    /// Enters or leaves relative mouse mode for FPS-style camera control.
+    ///
+    /// Entering locks the cursor in place, falling back to confining it where
+    /// locking is unsupported, then hides it; read movement from
+    /// [`DeviceEvent::MouseMotion`](crate::DeviceEvent::MouseMotion). Returns
+    /// the grab mode applied. When neither grab succeeds the cursor stays
+    /// visible and unrestricted.
+    pub fn set_relative_mouse_mode(&self, enabled: bool) -> Result<CursorGrabMode, PlatformError> {
+        if !enabled {
+            self.set_cursor_visible(true);
+            self.set_cursor_grab(CursorGrabMode::None)?;
+            return Ok(CursorGrabMode::None);
+        }
+        let mode = self
+            .set_cursor_grab(CursorGrabMode::Locked)
+            .map(|()| CursorGrabMode::Locked)
+            .or_else(|_| {
+                self.set_cursor_grab(CursorGrabMode::Confined)
+                    .map(|()| CursorGrabMode::Confined)
+            })?;
+        self.set_cursor_visible(false);
+        Ok(mode)
+    }
     /// Moves the cursor.
     pub fn set_cursor_position(&self, value: Point<Physical, f64>) -> Result<(), PlatformError> {
         self.command(WindowCommand::SetCursorPosition(value))
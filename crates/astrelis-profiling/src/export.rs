@@ -0,0 +1,171 @@
+//! Export of the retained timeline for external viewers.
+//!
+//! [`write_chrome_trace`] writes the Chrome trace-event JSON format,
+//! which `chrome://tracing`, Perfetto, and Speedscope all open. CPU
+//! threads appear under one process and GPU lanes under another;
+//! counters become counter tracks and frame marks global instant
+//! events.
+
+use std::fmt::Write as _;
+use std::io::{self, Write};
+
+use crate::data::{ScopeId, StringId};
+use crate::profiler::Profiler;
+use crate::string_table::StringTable;
+use crate::timeline::Timeline;
+
+const CPU_PID: u32 = 1;
+const GPU_PID: u32 = 2;
+
+/// Writes every retained span, counter sample, and frame mark as a
+/// Chrome trace-event JSON document.
+pub fn write_chrome_trace(
+    timeline: &Timeline,
+    strings: &StringTable,
+    mut out: impl Write,
+) -> io::Result<()> {
+    let mut events = Vec::new();
+    let mut event = String::new();
+    let string = |id: StringId| strings.get(id).unwrap_or_default();
+    let scope = |id: ScopeId| &timeline.scopes[id.0.get() as usize - 1];
+
+    for (pid, name) in [(CPU_PID, "CPU"), (GPU_PID, "GPU")] {
+        events.push(format!(
+            r#"{{"name":"process_name","ph":"M","pid":{pid},"args":{{"name":"{name}"}}}}"#
+        ));
+    }
+    for (thread, info) in &timeline.threads {
+        events.push(format!(
+            r#"{{"name":"thread_name","ph":"M","pid":{CPU_PID},"tid":{},"args":{{"name":{}}}}}"#,
+            thread.0,
+            json_string(&string(info.name))
+        ));
+    }
+    for (lane, info) in &timeline.gpu_lanes {
+        events.push(format!(
+            r#"{{"name":"thread_name","ph":"M","pid":{GPU_PID},"tid":{},"args":{{"name":{}}}}}"#,
+            lane.0,
+            json_string(&string(info.name))
+        ));
+    }
+    for (thread, stream) in &timeline.thread_streams {
+        for span in &stream.spans {
+            let info = scope(span.scope);
+            event.clear();
+            write!(
+                event,
+                r#"{{"name":{},"cat":"cpu","ph":"X","pid":{CPU_PID},"tid":{},"ts":{},"dur":{}"#,
+                json_string(&string(info.name)),
+                thread.0,
+                micros(span.start_ns),
+                micros(span.end_ns.saturating_sub(span.start_ns)),
+            )
+            .expect("writing to a string");
+            if !info.file.is_empty() {
+                write!(
+                    event,
+                    r#","args":{{"file":{},"line":{}}}"#,
+                    json_string(info.file),
+                    info.line
+                )
+                .expect("writing to a string");
+            }
+            event.push('}');
+            events.push(event.clone());
+        }
+    }
+    for (lane, stream) in &timeline.gpu_streams {
+        for span in &stream.spans {
+            events.push(format!(
+                r#"{{"name":{},"cat":"gpu","ph":"X","pid":{GPU_PID},"tid":{},"ts":{},"dur":{}}}"#,
+                json_string(&string(scope(span.scope).name)),
+                lane.0,
+                micros(span.start_ns),
+                micros(span.end_ns.saturating_sub(span.start_ns)),
+            ));
+        }
+    }
+    for (counter, stream) in &timeline.counter_streams {
+        let name = json_string(&string(*counter));
+        for sample in &stream.samples {
+            // JSON has no NaN or infinity.
+            let value = if sample.value.is_finite() {
+                sample.value
+            } else {
+                0.0
+            };
+            events.push(format!(
+                r#"{{"name":{name},"ph":"C","pid":{CPU_PID},"ts":{},"args":{{"value":{value}}}}}"#,
+                micros(sample.ts_ns),
+            ));
+        }
+    }
+    for mark in &timeline.frame_marks {
+        events.push(format!(
+            r#"{{"name":"frame {}","ph":"i","s":"g","pid":{CPU_PID},"tid":0,"ts":{}}}"#,
+            mark.index,
+            micros(mark.end_ns),
+        ));
+    }
+
+    out.write_all(br#"{"displayTimeUnit":"ns","traceEvents":["#)?;
+    for (index, event) in events.iter().enumerate() {
+        if index > 0 {
+            out.write_all(b",\n")?;
+        }
+        out.write_all(event.as_bytes())?;
+    }
+    out.write_all(b"]}\n")?;
+    out.flush()
+}
+
+impl Profiler {
+    /// Writes the global timeline as Chrome trace-event JSON; see
+    /// [`write_chrome_trace`].
+    ///
+    /// Only data aggregated by a frame mark is included, so call
+    /// [`frame_mark`](crate::frame_mark) first to flush in-flight
+    /// thread buffers.
+    pub fn export_chrome_trace(&self, out: impl Write) -> io::Result<()> {
+        let timeline = self.timeline.read().unwrap();
+        write_chrome_trace(&timeline, &self.strings, out)
+    }
+}
+
+/// Trace-event timestamps are microseconds; keep nanosecond precision
+/// as three decimals.
+fn micros(ns: u64) -> String {
+    format!("{}.{:03}", ns / 1_000, ns % 1_000)
+}
+
+fn json_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+    for character in value.chars() {
+        match character {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            control if control < ' ' => {
+                write!(escaped, "\\u{:04x}", control as u32).expect("writing to a string");
+            }
+            other => escaped.push(other),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strings_and_timestamps_are_json_safe() {
+        assert_eq!(json_string("a \"b\"\\\n\u{1}"), r#""a \"b\"\\\n\u0001""#);
+        assert_eq!(micros(1_234_567), "1234.567");
+        assert_eq!(micros(5), "0.005");
+    }
+}
@@ -12,9 +12,12 @@
 //!
 //! # Clock alignment
 //!
-//! The CPU↔GPU clock offset is installed by `astrelis-gpu` via a
-//! synchronous calibration round-trip at device creation, and
-//! refreshed periodically (every 5 s). This module assumes the
+//! Each lane keeps a CPU↔GPU clock offset, fed by
+//! [`calibrate_gpu_lane`] with GPU timestamps bracketed by CPU clock
+//! reads. GPU profilers take one tight sample by waiting for the
+//! queue when they are created, then refresh the offset periodically
+//! (every 5 s) from samples read back frames later, which only
+//! correct drift and never stall the CPU. This module assumes the
 //! offset is already set when `report_gpu_frame` is called.
 
 use std::{
@@ -122,6 +125,46 @@ pub fn set_gpu_lane_offset_ns(lane: GpuLaneId, offset_ns: i64) {
         );
 }
 
+/// A GPU timestamp bracketed by two reads of the profiler's CPU clock.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GpuClockSample {
+    /// CPU time before the timestamp could have been written, such as
+    /// just before the submission that writes it.
+    pub cpu_before_ns: u64,
+    /// CPU time after the timestamp was known to be written.
+    pub cpu_after_ns: u64,
+    /// The GPU timestamp in nanoseconds.
+    pub gpu_ns: u64,
+}
+
+/// Aligns a lane's CPU-to-GPU clock offset with a sample and returns the
+/// offset now in use.
+///
+/// A lane's first sample maps the GPU timestamp onto the middle of its CPU
+/// bracket. Later samples keep the offset while it agrees with their
+/// bracket and otherwise move it just far enough to agree, so loose
+/// brackets from samples read back frames after submission correct clock
+/// drift without adding jitter.
+pub fn calibrate_gpu_lane(lane: GpuLaneId, sample: GpuClockSample) -> i64 {
+    let before = i128::from(sample.cpu_before_ns.min(sample.cpu_after_ns));
+    let after = i128::from(sample.cpu_before_ns.max(sample.cpu_after_ns));
+    let gpu = i128::from(sample.gpu_ns);
+    let to_offset = |ns: i128| ns.clamp(i128::from(i64::MIN), i128::from(i64::MAX)) as i64;
+    let (earliest, latest) = (to_offset(before - gpu), to_offset(after - gpu));
+    let mut clocks = GPU_LANE_CLOCKS
+        .get_or_init(Default::default)
+        .write()
+        .expect("GPU lane clocks poisoned");
+    let clock = clocks.entry(lane).or_default();
+    clock.offset_ns = if clock.calibrated {
+        clock.offset_ns.clamp(earliest, latest)
+    } else {
+        to_offset((before + after) / 2 - gpu)
+    };
+    clock.calibrated = true;
+    clock.offset_ns
+}
+
 /// Reports a completed GPU frame on a registered queue lane.
 pub fn report_gpu_frame(lane: GpuLaneId, frame: GpuFrame) {
     if frame.scopes.is_empty() {
@@ -183,3 +226,33 @@ fn convert_timestamp(timestamp_ns: u64, clock: LaneClock) -> u64 {
         timestamp_ns.saturating_sub((-clock.offset_ns) as u64)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(cpu_before_ns: u64, cpu_after_ns: u64, gpu_ns: u64) -> GpuClockSample {
+        GpuClockSample {
+            cpu_before_ns,
+            cpu_after_ns,
+            gpu_ns,
+        }
+    }
+
+    #[test]
+    fn later_samples_only_move_the_offset_out_of_disagreement() {
+        let lane = register_gpu_lane(GpuBackend::Unknown, Some("calibration test"));
+        assert_eq!(calibrate_gpu_lane(lane, sample(1_000, 1_200, 100)), 1_000);
+        assert_eq!(
+            calibrate_gpu_lane(lane, sample(5_000, 9_000, 4_000)),
+            1_000,
+            "a loose bracket that agrees keeps the offset"
+        );
+        assert_eq!(
+            calibrate_gpu_lane(lane, sample(9_500, 20_000, 8_000)),
+            1_500,
+            "drift moves the offset to the nearest agreeing value"
+        );
+        assert_eq!(calibrate_gpu_lane(lane, sample(0, 0, 800)), -800);
+    }
+}
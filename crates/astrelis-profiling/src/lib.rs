@@ -3,9 +3,11 @@
 //! `astrelis-profiling` collects CPU and GPU timing data into a
 //! global [`Timeline`](crate::timeline::Timeline) that can be read
 //! by an in-process viewer (see the `astrelis-profiling-egui`
-//! crate). It replaces the previous Tracy-based backend entirely —
-//! there is no external profiler binary to attach; everything lives
-//! in-engine.
+//! crate). The same events can also be forwarded to an external
+//! profiler chosen at runtime through [`sink::set_backend`]: Chrome
+//! trace-event JSON (Perfetto, `chrome://tracing`) is always available,
+//! puffin behind the `puffin` feature, and Tracy behind the `tracy`
+//! feature.
 //!
 //! # Overview
 //!
@@ -47,8 +49,10 @@
 
 pub mod clock;
 pub mod data;
+pub mod export;
 pub mod gpu;
 pub mod profiler;
+pub mod sink;
 pub mod string_table;
 pub mod thread;
 pub(crate) mod thread_local;
@@ -58,9 +62,11 @@ pub mod timeline;
 // Public API — backwards-compatible function surface
 // ============================================================================
 
+pub use export::write_chrome_trace;
 pub use profiler::{Profiler, ScopeGuard, finish, frame_mark, init, set_thread_name};
 #[cfg(feature = "enabled")]
 pub use profiler::{is_enabled, set_enabled};
+pub use sink::{Backend, ProfilerSink, set_backend, set_backend_from_env, set_sink};
 pub use thread::{configure_pool_thread, spawn_profiled};
 
 /// Signals a frame boundary. Equivalent to calling [`frame_mark`].
@@ -13,6 +13,7 @@ use crate::clock::Clock;
 use crate::data::{
     CounterValue, FrameMark, GpuLaneId, ScopeId, SpanId, StringId, ThreadId, counter_to_f64,
 };
+use crate::sink::{self, ProfilerSink, ScopeSite};
 use crate::string_table::StringTable;
 use crate::thread_local::{Event, ThreadBuffer, ThreadRegistry, ThreadState, with_state};
 use crate::timeline::Timeline;
@@ -132,10 +133,14 @@ pub fn init() {
     let _ = Profiler::get();
 }
 
-/// Explicit shutdown hook. Kept for symmetry with [`init`]; currently
-/// a no-op since the profiler has no resources that require explicit
-/// teardown.
-pub fn finish() {}
+/// Explicit shutdown hook. Flushes the active
+/// [`ProfilerSink`](crate::sink::ProfilerSink), such as a pending Chrome
+/// trace file; the in-engine timeline needs no teardown.
+pub fn finish() {
+    if let Err(error) = sink::flush_sink() {
+        eprintln!("astrelis-profiling: flushing the profiler sink failed: {error}");
+    }
+}
 
 /// Registers the current thread with the profiler and returns its
 /// thread-local state. Called lazily from [`with_state`] on first
@@ -184,6 +189,9 @@ pub fn set_thread_name(name: &str) {
 #[must_use = "scope guard must be held for the duration of the span"]
 pub struct ScopeGuard {
     span_id: SpanId,
+    /// The sink that saw this scope begin, so it also sees the end even
+    /// if the active sink changes in between.
+    sink: Option<Arc<dyn ProfilerSink>>,
 }
 
 impl Drop for ScopeGuard {
@@ -210,6 +218,9 @@ impl Drop for ScopeGuard {
                 ts_ns,
             });
         });
+        if let Some(sink) = &self.sink {
+            sink.end_scope();
+        }
     }
 }
 
@@ -231,6 +242,7 @@ pub fn enter_scope(
     if !RUNTIME_ENABLED.load(Ordering::Relaxed) {
         return ScopeGuard {
             span_id: SpanId::NONE,
+            sink: None,
         };
     }
     let p = Profiler::get();
@@ -252,7 +264,16 @@ pub fn enter_scope(
             parent,
         });
     });
-    ScopeGuard { span_id }
+    let sink = sink::active();
+    if let Some(sink) = &sink {
+        sink.begin_scope(&ScopeSite {
+            id: scope_id,
+            name,
+            file,
+            line,
+        });
+    }
+    ScopeGuard { span_id, sink }
 }
 
 /// Records a counter sample on the current thread's event buffer.
@@ -274,6 +295,9 @@ pub fn record_counter_value(name_cache: &OnceLock<StringId>, name: &'static str,
             value,
         });
     });
+    if let Some(sink) = sink::active() {
+        sink.counter(name, value);
+    }
 }
 
 /// Shim called by the `profile_counter!` macro.
@@ -293,7 +317,8 @@ pub fn record_counter_shim(
 
 /// Marks a frame boundary. Drains every thread's event buffer,
 /// writes the paired spans into the global timeline, appends a
-/// [`FrameMark`], and applies the retention policy.
+/// [`FrameMark`], applies the retention policy, and forwards the mark
+/// to the active sink.
 ///
 /// Must be called from any thread — typically the main loop — once
 /// per frame. Called from the `new_frame` public API for backwards
@@ -316,6 +341,10 @@ pub fn frame_mark() {
         start_ns,
         end_ns,
     });
+    drop(timeline);
+    if let Some(sink) = sink::active() {
+        sink.frame_mark();
+    }
 }
 
 #[cfg(test)]
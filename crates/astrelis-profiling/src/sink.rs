@@ -0,0 +1,346 @@
+//! Forwarding of profiling events to an external profiler.
+//!
+//! The in-engine [`Timeline`](crate::timeline::Timeline) always records.
+//! At most one [`ProfilerSink`] additionally receives every scope, counter
+//! sample, and frame mark as it happens, and it can be swapped at runtime
+//! with [`set_backend`] or [`set_sink`]. Built-in sinks:
+//!
+//! - [`ChromeTraceSink`] writes the retained timeline as Chrome trace-event
+//!   JSON whenever it is flushed;
+//! - `PuffinSink` (feature `puffin`) streams into puffin's global profiler,
+//!   which `puffin_http` or `puffin_egui` can serve;
+//! - `TracySink` (feature `tracy`) streams to a connected Tracy client.
+
+use std::{
+    error::Error,
+    fmt, fs, io,
+    path::PathBuf,
+    str::FromStr,
+    sync::{
+        Arc, RwLock,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use crate::{data::ScopeId, profiler::Profiler};
+
+/// Fast-path flag mirroring whether [`SINK`] holds a sink, so scopes pay
+/// one `Relaxed` load when no external profiler is attached.
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+static SINK: RwLock<Option<Arc<dyn ProfilerSink>>> = RwLock::new(None);
+
+/// A scope call site, as passed to [`ProfilerSink::begin_scope`].
+#[derive(Clone, Copy, Debug)]
+pub struct ScopeSite {
+    /// The call site's id in the in-engine timeline; stable for the
+    /// process lifetime, so sinks may cache their own registration by it.
+    pub id: ScopeId,
+    /// Scope or function name.
+    pub name: &'static str,
+    /// Source file of the call site.
+    pub file: &'static str,
+    /// Source line of the call site.
+    pub line: u32,
+}
+
+/// Receives profiling events as they are recorded.
+///
+/// Scopes begin and end on the same thread in stack order, so sinks that
+/// need per-scope state can keep a thread-local stack.
+pub trait ProfilerSink: Send + Sync {
+    /// A scope opened on the current thread.
+    fn begin_scope(&self, site: &ScopeSite);
+
+    /// The innermost scope opened on the current thread closed.
+    fn end_scope(&self);
+
+    /// A counter or plot sample on the current thread.
+    fn counter(&self, _name: &'static str, _value: f64) {}
+
+    /// A frame boundary.
+    fn frame_mark(&self) {}
+
+    /// Writes out buffered data. Called when the sink is replaced and by
+    /// [`finish`](crate::finish).
+    fn flush(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// External profilers selectable at runtime through [`set_backend`].
+///
+/// Parses from `"timeline"`, `"chrome"`, `"chrome:<path>"`, `"puffin"`, and
+/// `"tracy"`, as read from `ASTRELIS_PROFILER` by [`set_backend_from_env`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Backend {
+    /// Only the in-engine timeline records.
+    Timeline,
+    /// Chrome trace-event JSON written to a file; see [`ChromeTraceSink`].
+    ChromeTrace(PathBuf),
+    /// The puffin profiler. Requires the `puffin` feature.
+    Puffin,
+    /// The Tracy profiler. Requires the `tracy` feature.
+    Tracy,
+}
+
+impl Backend {
+    /// Environment variable read by [`set_backend_from_env`].
+    pub const ENV: &'static str = "ASTRELIS_PROFILER";
+
+    /// Trace file used by `"chrome"` without a path.
+    pub const DEFAULT_TRACE_PATH: &'static str = "astrelis-trace.json";
+}
+
+impl FromStr for Backend {
+    type Err = BackendError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim() {
+            "" | "timeline" => Ok(Self::Timeline),
+            "chrome" => Ok(Self::ChromeTrace(Self::DEFAULT_TRACE_PATH.into())),
+            "puffin" => Ok(Self::Puffin),
+            "tracy" => Ok(Self::Tracy),
+            other => match other.strip_prefix("chrome:") {
+                Some(path) if !path.is_empty() => Ok(Self::ChromeTrace(path.into())),
+                _ => Err(BackendError(format!("unknown profiler backend `{other}`"))),
+            },
+        }
+    }
+}
+
+/// Error returned when a [`Backend`] cannot be selected.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BackendError(String);
+
+impl fmt::Display for BackendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Error for BackendError {}
+
+/// Routes events to `backend`, flushing and detaching the previous sink.
+///
+/// Fails without changing the active sink when `backend` was not compiled
+/// in.
+pub fn set_backend(backend: Backend) -> Result<(), BackendError> {
+    let sink: Option<Arc<dyn ProfilerSink>> = match backend {
+        Backend::Timeline => None,
+        Backend::ChromeTrace(path) => Some(Arc::new(ChromeTraceSink::new(path))),
+        #[cfg(feature = "puffin")]
+        Backend::Puffin => Some(Arc::new(puffin_sink::PuffinSink::new())),
+        #[cfg(not(feature = "puffin"))]
+        Backend::Puffin => return Err(missing_feature("puffin")),
+        #[cfg(feature = "tracy")]
+        Backend::Tracy => Some(Arc::new(tracy_sink::TracySink::new())),
+        #[cfg(not(feature = "tracy"))]
+        Backend::Tracy => return Err(missing_feature("tracy")),
+    };
+    replace(sink);
+    Ok(())
+}
+
+/// Selects the backend named by the `ASTRELIS_PROFILER` environment
+/// variable, keeping the current one when it is unset.
+pub fn set_backend_from_env() -> Result<(), BackendError> {
+    match std::env::var(Backend::ENV) {
+        Ok(value) => set_backend(value.parse()?),
+        Err(_) => Ok(()),
+    }
+}
+
+/// Routes events to a custom sink, flushing and detaching the previous one.
+pub fn set_sink(sink: Arc<dyn ProfilerSink>) {
+    replace(Some(sink));
+}
+
+/// Detaches the active sink after flushing it, returning it.
+pub fn clear_sink() -> Option<Arc<dyn ProfilerSink>> {
+    replace(None)
+}
+
+/// Flushes the active sink, if any.
+pub fn flush_sink() -> io::Result<()> {
+    match active() {
+        Some(sink) => sink.flush(),
+        None => Ok(()),
+    }
+}
+
+/// The active sink, or `None` without loading more than one atomic.
+#[inline]
+pub(crate) fn active() -> Option<Arc<dyn ProfilerSink>> {
+    if !ACTIVE.load(Ordering::Relaxed) {
+        return None;
+    }
+    SINK.read().unwrap().clone()
+}
+
+fn replace(sink: Option<Arc<dyn ProfilerSink>>) -> Option<Arc<dyn ProfilerSink>> {
+    let mut slot = SINK.write().unwrap();
+    ACTIVE.store(sink.is_some(), Ordering::Relaxed);
+    let previous = std::mem::replace(&mut *slot, sink);
+    drop(slot);
+    if let Some(previous) = &previous
+        && let Err(error) = previous.flush()
+    {
+        eprintln!("astrelis-profiling: flushing the previous sink failed: {error}");
+    }
+    previous
+}
+
+#[cfg(not(all(feature = "puffin", feature = "tracy")))]
+fn missing_feature(feature: &str) -> BackendError {
+    BackendError(format!(
+        "astrelis-profiling was built without the `{feature}` feature"
+    ))
+}
+
+/// Writes the retained timeline as Chrome trace-event JSON on every flush.
+///
+/// Events reach the file through the timeline rather than one by one, so
+/// the file covers the frames inside the retention window at flush time.
+#[derive(Debug)]
+pub struct ChromeTraceSink {
+    path: PathBuf,
+}
+
+impl ChromeTraceSink {
+    /// Creates a sink that writes to `path`.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Destination file.
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+}
+
+impl ProfilerSink for ChromeTraceSink {
+    fn begin_scope(&self, _site: &ScopeSite) {}
+
+    fn end_scope(&self) {}
+
+    fn flush(&self) -> io::Result<()> {
+        let file = io::BufWriter::new(fs::File::create(&self.path)?);
+        Profiler::get().export_chrome_trace(file)
+    }
+}
+
+#[cfg(feature = "puffin")]
+mod puffin_sink {
+    use std::{cell::RefCell, collections::HashMap, sync::RwLock};
+
+    use super::{ProfilerSink, ScopeSite};
+    use crate::data::ScopeId;
+
+    thread_local! {
+        static OFFSETS: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+    }
+
+    /// Streams scopes into puffin's global profiler.
+    pub(super) struct PuffinSink {
+        scopes: RwLock<HashMap<ScopeId, puffin::ScopeId>>,
+    }
+
+    impl PuffinSink {
+        pub(super) fn new() -> Self {
+            puffin::set_scopes_on(true);
+            Self {
+                scopes: RwLock::new(HashMap::new()),
+            }
+        }
+
+        fn scope(&self, site: &ScopeSite) -> puffin::ScopeId {
+            if let Some(&id) = self.scopes.read().unwrap().get(&site.id) {
+                return id;
+            }
+            *self
+                .scopes
+                .write()
+                .unwrap()
+                .entry(site.id)
+                .or_insert_with(|| {
+                    puffin::ThreadProfiler::call(|profiler| {
+                        profiler.register_named_scope(site.name, site.name, site.file, site.line)
+                    })
+                })
+        }
+    }
+
+    impl ProfilerSink for PuffinSink {
+        fn begin_scope(&self, site: &ScopeSite) {
+            let scope = self.scope(site);
+            let offset = puffin::ThreadProfiler::call(|profiler| profiler.begin_scope(scope, ""));
+            OFFSETS.with(|offsets| offsets.borrow_mut().push(offset));
+        }
+
+        fn end_scope(&self) {
+            if let Some(offset) = OFFSETS.with(|offsets| offsets.borrow_mut().pop()) {
+                puffin::ThreadProfiler::call(|profiler| profiler.end_scope(offset));
+            }
+        }
+
+        fn frame_mark(&self) {
+            puffin::GlobalProfiler::lock().new_frame();
+        }
+    }
+}
+
+#[cfg(feature = "tracy")]
+mod tracy_sink {
+    use std::{cell::RefCell, collections::HashMap, sync::Mutex};
+
+    use tracy_client::{Client, PlotName, Span};
+
+    use super::{ProfilerSink, ScopeSite};
+
+    thread_local! {
+        static SPANS: RefCell<Vec<Span>> = const { RefCell::new(Vec::new()) };
+    }
+
+    /// Streams scopes, plots, and frame marks to a Tracy client.
+    pub(super) struct TracySink {
+        client: Client,
+        plots: Mutex<HashMap<&'static str, PlotName>>,
+    }
+
+    impl TracySink {
+        pub(super) fn new() -> Self {
+            Self {
+                client: Client::start(),
+                plots: Mutex::new(HashMap::new()),
+            }
+        }
+    }
+
+    impl ProfilerSink for TracySink {
+        fn begin_scope(&self, site: &ScopeSite) {
+            let span =
+                self.client
+                    .clone()
+                    .span_alloc(Some(site.name), site.name, site.file, site.line, 0);
+            SPANS.with(|spans| spans.borrow_mut().push(span));
+        }
+
+        fn end_scope(&self) {
+            SPANS.with(|spans| drop(spans.borrow_mut().pop()));
+        }
+
+        fn counter(&self, name: &'static str, value: f64) {
+            let plot = *self
+                .plots
+                .lock()
+                .unwrap()
+                .entry(name)
+                .or_insert_with(|| PlotName::new_leak(name.to_owned()));
+            self.client.plot(plot, value);
+        }
+
+        fn frame_mark(&self) {
+            self.client.frame_mark();
+        }
+    }
+}
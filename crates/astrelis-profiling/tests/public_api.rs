@@ -87,3 +87,23 @@ fn runtime_disable_skips_new_events() {
         .sum::<usize>();
     assert_eq!(after, before);
 }
+
+#[test]
+fn exports_spans_and_frames_as_chrome_trace_json() {
+    let _test_guard = TEST_LOCK.lock().expect("test lock poisoned");
+    astrelis_profiling::init();
+    {
+        astrelis_profiling::profile_scope!("exported_integration_scope");
+    }
+    astrelis_profiling::frame_mark();
+
+    let mut json = Vec::new();
+    astrelis_profiling::Profiler::get()
+        .export_chrome_trace(&mut json)
+        .expect("export to memory");
+    let json = String::from_utf8(json).expect("trace is UTF-8");
+    assert!(json.starts_with(r#"{"displayTimeUnit":"ns","traceEvents":["#));
+    assert!(json.contains(r#"{"name":"exported_integration_scope","cat":"cpu","ph":"X""#));
+    assert!(json.contains(r#""ph":"i","s":"g""#));
+    assert!(json.trim_end().ends_with("]}"));
+}
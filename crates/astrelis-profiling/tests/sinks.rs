@@ -0,0 +1,116 @@
+//! Runtime selection of external profiler sinks.
+//!
+//! Lives in its own test binary because the active sink is process-global.
+
+#![cfg(feature = "enabled")]
+
+use std::sync::{Arc, Mutex};
+
+use astrelis_profiling::{
+    Backend, ProfilerSink,
+    sink::{self, ScopeSite},
+};
+
+#[derive(Default)]
+struct Recorder {
+    events: Mutex<Vec<String>>,
+}
+
+impl ProfilerSink for Recorder {
+    fn begin_scope(&self, site: &ScopeSite) {
+        self.events
+            .lock()
+            .unwrap()
+            .push(format!("begin {}", site.name));
+    }
+
+    fn end_scope(&self) {
+        self.events.lock().unwrap().push("end".into());
+    }
+
+    fn counter(&self, name: &'static str, value: f64) {
+        self.events
+            .lock()
+            .unwrap()
+            .push(format!("{name} = {value}"));
+    }
+
+    fn frame_mark(&self) {
+        self.events.lock().unwrap().push("frame".into());
+    }
+}
+
+#[test]
+fn sinks_receive_events_and_switch_at_runtime() {
+    let recorder = Arc::new(Recorder::default());
+    astrelis_profiling::set_sink(recorder.clone());
+    {
+        astrelis_profiling::profile_scope!("outer");
+        {
+            astrelis_profiling::profile_scope!("inner");
+            astrelis_profiling::profile_plot!("items", 3.0);
+        }
+    }
+    astrelis_profiling::frame_mark();
+    assert_eq!(
+        *recorder.events.lock().unwrap(),
+        [
+            "begin outer",
+            "begin inner",
+            "items = 3",
+            "end",
+            "end",
+            "frame"
+        ]
+    );
+
+    {
+        astrelis_profiling::profile_scope!("spanning a switch");
+        astrelis_profiling::set_backend(Backend::Timeline).unwrap();
+        recorder.events.lock().unwrap().clear();
+    }
+    assert_eq!(
+        *recorder.events.lock().unwrap(),
+        ["end"],
+        "a scope ends on the sink that saw it begin"
+    );
+    astrelis_profiling::frame_mark();
+    assert_eq!(recorder.events.lock().unwrap().len(), 1);
+
+    let path = std::env::temp_dir().join(format!("astrelis-trace-{}.json", std::process::id()));
+    astrelis_profiling::set_backend(Backend::ChromeTrace(path.clone())).unwrap();
+    {
+        astrelis_profiling::profile_scope!("traced");
+    }
+    astrelis_profiling::frame_mark();
+    astrelis_profiling::finish();
+    let trace = std::fs::read_to_string(&path).expect("trace file");
+    assert!(trace.contains(r#""name":"traced""#), "{trace}");
+    assert!(sink::clear_sink().is_some());
+    let _ = std::fs::remove_file(path);
+}
+
+#[test]
+fn backends_parse_and_report_missing_features() {
+    assert_eq!("timeline".parse(), Ok(Backend::Timeline));
+    assert_eq!(
+        "chrome".parse(),
+        Ok(Backend::ChromeTrace(Backend::DEFAULT_TRACE_PATH.into()))
+    );
+    assert_eq!(
+        "chrome:frames.json".parse(),
+        Ok(Backend::ChromeTrace("frames.json".into()))
+    );
+    assert_eq!("puffin".parse(), Ok(Backend::Puffin));
+    assert_eq!("tracy".parse(), Ok(Backend::Tracy));
+    assert!("optick".parse::<Backend>().is_err());
+    #[cfg(not(feature = "puffin"))]
+    assert!(
+        astrelis_profiling::set_backend(Backend::Puffin)
+            .unwrap_err()
+            .to_string()
+            .contains("`puffin` feature")
+    );
+    #[cfg(not(feature = "tracy"))]
+    assert!(astrelis_profiling::set_backend(Backend::Tracy).is_err());
+}
@@ -1,4 +1,4 @@
-//! Orthographic two-dimensional camera.
+//! Orthographic two-dimensional camera and camera shake.
 
 use astrelis_core::math::{Affine2, Mat4, Vec2, Vec3};
 
@@ -64,6 +64,130 @@ impl Camera2D {
         }
         Some((min, max))
     }
+
+    /// Converts a logical-pixel position measured from the viewport's
+    /// top-left corner to world space.
+    pub fn screen_to_world(self, screen: Vec2, logical_size: Vec2) -> Option<Vec2> {
+        self.view_projection(logical_size)?;
+        Some(
+            self.view_to_world()
+                .transform_point2((screen - logical_size * 0.5) / self.zoom),
+        )
+    }
+
+    /// Converts a world-space position to logical pixels from the viewport's
+    /// top-left corner.
+    pub fn world_to_screen(self, world: Vec2, logical_size: Vec2) -> Option<Vec2> {
+        self.view_projection(logical_size)?;
+        Some(
+            self.view_to_world().inverse().transform_point2(world) * self.zoom + logical_size * 0.5,
+        )
+    }
+
+    fn view_to_world(self) -> Affine2 {
+        Affine2::from_translation(self.center) * Affine2::from_angle(self.rotation)
+    }
+}
+
+/// Trauma-driven camera shake.
+///
+/// Trauma in `[0, 1]` decays linearly over time; the shake amount is trauma
+/// squared, so small hits stay subtle while large ones ramp up sharply.
+/// Offsets follow smooth value noise rather than per-frame randomness, which
+/// reads as a shake instead of jitter.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CameraShake {
+    /// Largest positional offset in world units at full trauma.
+    pub max_offset: Vec2,
+    /// Largest rotation in radians at full trauma.
+    pub max_rotation: f32,
+    /// Trauma removed per second.
+    pub decay: f32,
+    /// Noise samples per second; higher values shake faster.
+    pub frequency: f32,
+    trauma: f32,
+    time: f32,
+    seed: u32,
+}
+
+impl Default for CameraShake {
+    fn default() -> Self {
+        Self {
+            max_offset: Vec2::splat(12.0),
+            max_rotation: 0.05,
+            decay: 1.5,
+            frequency: 25.0,
+            trauma: 0.0,
+            time: 0.0,
+            seed: 0,
+        }
+    }
+}
+
+impl CameraShake {
+    /// Creates a shake with default limits and a noise seed, so several
+    /// cameras do not shake in lockstep.
+    pub fn new(seed: u32) -> Self {
+        Self {
+            seed,
+            ..Self::default()
+        }
+    }
+
+    /// Current trauma in `[0, 1]`.
+    pub fn trauma(&self) -> f32 {
+        self.trauma
+    }
+
+    /// Adds trauma, saturating at 1.
+    pub fn add_trauma(&mut self, amount: f32) {
+        if amount.is_finite() {
+            self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+        }
+    }
+
+    /// Advances time and decays trauma.
+    pub fn update(&mut self, delta_seconds: f32) {
+        if !delta_seconds.is_finite() || delta_seconds <= 0.0 {
+            return;
+        }
+        self.time += delta_seconds;
+        self.trauma = (self.trauma - self.decay * delta_seconds).max(0.0);
+    }
+
+    /// Returns `camera` displaced by the current shake.
+    pub fn apply(&self, camera: Camera2D) -> Camera2D {
+        let shake = self.trauma * self.trauma;
+        if shake == 0.0 {
+            return camera;
+        }
+        let t = self.time * self.frequency;
+        Camera2D {
+            center: camera.center
+                + Vec2::new(
+                    self.max_offset.x * shake * noise(self.seed, t),
+                    self.max_offset.y * shake * noise(self.seed.wrapping_add(1), t),
+                ),
+            rotation: camera.rotation
+                + self.max_rotation * shake * noise(self.seed.wrapping_add(2), t),
+            ..camera
+        }
+    }
+}
+
+/// Smoothly interpolated value noise in `[-1, 1]`.
+fn noise(seed: u32, t: f32) -> f32 {
+    let index = t.floor();
+    let fraction = t - index;
+    let sample = |i: f32| {
+        let mut x = (i as i32 as u32) ^ seed.wrapping_mul(0x9e37_79b9);
+        x = (x ^ (x >> 16)).wrapping_mul(0x7feb_352d);
+        x = (x ^ (x >> 15)).wrapping_mul(0x846c_a68b);
+        x ^= x >> 16;
+        x as f32 / u32::MAX as f32 * 2.0 - 1.0
+    };
+    let smooth = fraction * fraction * (3.0 - 2.0 * fraction);
+    sample(index) + (sample(index + 1.0) - sample(index)) * smooth
 }
 
 #[cfg(test)]
@@ -86,4 +210,43 @@ mod tests {
         );
         assert!(matrix.transform_point3(Vec3::new(10.0, 30.0, 0.0)).y < 0.0);
     }
+
+    #[test]
+    fn screen_and_world_round_trip() {
+        let camera = Camera2D {
+            center: Vec2::new(50.0, -20.0),
+            rotation: 0.3,
+            zoom: 2.0,
+        };
+        let viewport = Vec2::new(320.0, 180.0);
+        assert!(
+            camera
+                .screen_to_world(viewport * 0.5, viewport)
+                .unwrap()
+                .abs_diff_eq(camera.center, 1e-4)
+        );
+        let world = Vec2::new(61.0, -7.0);
+        let screen = camera.world_to_screen(world, viewport).unwrap();
+        assert!(
+            camera
+                .screen_to_world(screen, viewport)
+                .unwrap()
+                .abs_diff_eq(world, 1e-3)
+        );
+    }
+
+    #[test]
+    fn shake_decays_to_rest() {
+        let mut shake = CameraShake::new(7);
+        let camera = Camera2D::default();
+        assert_eq!(shake.apply(camera), camera);
+        shake.add_trauma(2.0);
+        assert_eq!(shake.trauma(), 1.0);
+        shake.update(0.1);
+        let shaken = shake.apply(camera);
+        assert!(shaken.center.abs().max_element() <= 12.0);
+        assert!(shaken.rotation.abs() <= 0.05);
+        shake.update(1.0);
+        assert_eq!(shake.apply(camera), camera);
+    }
 }
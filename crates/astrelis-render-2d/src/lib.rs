@@ -4,9 +4,11 @@
 
 mod camera;
 mod scene;
+mod sprites;
 
-pub use camera::Camera2D;
+pub use camera::{Camera2D, CameraShake};
 pub use scene::{DrawList2D, SpriteDraw, TileAtlas, Tilemap, TilemapDraw};
+pub use sprites::{SpriteRegion, SpriteRenderer, SpriteSheet, SpriteSort};
 
 use std::{
     collections::HashMap,
@@ -17,6 +19,7 @@ use std::{
 };
 
 use astrelis_core::{
+    alloc::{Handle, HandleMap},
     color::Color,
     geometry::{Physical, Point, Rect, Size},
     math::{Mat4, Vec2},
@@ -52,6 +55,10 @@ pub struct TextureHandle {
 }
 
 impl TextureHandle {
+    fn resource(self) -> Handle<TextureResource> {
+        Handle::from_raw_parts(self.slot, self.generation)
+    }
+
     #[cfg(test)]
     pub(crate) const fn testing(slot: u32) -> Self {
         Self {
@@ -73,11 +80,6 @@ pub struct TextureOptions {
     pub address_v: gpu::AddressMode,
 }
 
-struct TextureSlot {
-    generation: u32,
-    resource: Option<TextureResource>,
-}
-
 struct TextureResource {
     _texture: Option<gpu::Texture>,
     _view: gpu::TextureView,
@@ -137,7 +139,7 @@ pub struct Renderer2D {
     camera_bind_group: gpu::BindGroup,
     pipelines: HashMap<PipelineKey, gpu::RenderPipeline>,
     attachments: Vec<Attachments>,
-    textures: Vec<TextureSlot>,
+    textures: HandleMap<TextureResource>,
 }
 
 impl Renderer2D {
@@ -211,7 +213,7 @@ impl Renderer2D {
             camera_bind_group,
             pipelines: HashMap::new(),
             attachments: Vec::new(),
-            textures: Vec::new(),
+            textures: HandleMap::new(),
         })
     }
 
@@ -268,12 +270,18 @@ impl Renderer2D {
         self.insert_texture(None, view, size, options)
     }
 
+    /// Returns the texel dimensions of a registered texture.
+    pub fn texture_size(&self, handle: TextureHandle) -> Result<Size<Physical, u32>, RenderError> {
+        Ok(self.texture(handle)?.size)
+    }
+
     /// Removes a texture. Existing handles become stale.
     pub fn remove_texture(&mut self, handle: TextureHandle) -> Result<(), RenderError> {
-        let slot = self.texture_slot_mut(handle)?;
-        slot.resource = None;
-        slot.generation = slot.generation.wrapping_add(1);
-        Ok(())
+        self.check_owner(handle)?;
+        self.textures
+            .remove(handle.resource())
+            .map(drop)
+            .ok_or_else(|| RenderError::new("stale or removed texture handle"))
     }
 
     /// Clears and renders one camera-specific draw list.
@@ -567,63 +575,28 @@ impl Renderer2D {
             bind_group,
             size,
         };
-        if let Some((index, slot)) = self
-            .textures
-            .iter_mut()
-            .enumerate()
-            .find(|(_, slot)| slot.resource.is_none())
-        {
-            slot.resource = Some(resource);
-            return Ok(TextureHandle {
-                owner: self.owner,
-                slot: index as u32,
-                generation: slot.generation,
-            });
-        }
-        let slot = self.textures.len() as u32;
-        self.textures.push(TextureSlot {
-            generation: 0,
-            resource: Some(resource),
-        });
+        let resource = self.textures.insert(resource);
         Ok(TextureHandle {
             owner: self.owner,
-            slot,
-            generation: 0,
+            slot: resource.index(),
+            generation: resource.generation(),
         })
     }
 
     fn texture(&self, handle: TextureHandle) -> Result<&TextureResource, RenderError> {
-        if handle.owner != self.owner {
-            return Err(RenderError::new(
-                "texture handle belongs to another renderer",
-            ));
-        }
-        let slot = self
-            .textures
-            .get(handle.slot as usize)
-            .ok_or_else(|| RenderError::new("invalid texture handle"))?;
-        if slot.generation != handle.generation {
-            return Err(RenderError::new("stale texture handle"));
-        }
-        slot.resource
-            .as_ref()
-            .ok_or_else(|| RenderError::new("removed texture handle"))
+        self.check_owner(handle)?;
+        self.textures
+            .get(handle.resource())
+            .ok_or_else(|| RenderError::new("stale or removed texture handle"))
     }
 
-    fn texture_slot_mut(&mut self, handle: TextureHandle) -> Result<&mut TextureSlot, RenderError> {
+    fn check_owner(&self, handle: TextureHandle) -> Result<(), RenderError> {
         if handle.owner != self.owner {
             return Err(RenderError::new(
                 "texture handle belongs to another renderer",
             ));
         }
-        let slot = self
-            .textures
-            .get_mut(handle.slot as usize)
-            .ok_or_else(|| RenderError::new("invalid texture handle"))?;
-        if slot.generation != handle.generation || slot.resource.is_none() {
-            return Err(RenderError::new("stale or removed texture handle"));
-        }
-        Ok(slot)
+        Ok(())
     }
 
     fn ensure_pipeline(
@@ -635,6 +608,11 @@ impl Renderer2D {
         if self.pipelines.contains_key(&key) {
             return Ok(());
         }
+        if !self.device.supports_sample_count(format, samples) {
+            return Err(RenderError::new(format!(
+                "{format:?} does not support {samples}x multisampling"
+            )));
+        }
         let shader = self
             .device
             .create_shader_module(gpu::ShaderModuleDescriptor {
@@ -692,6 +670,7 @@ impl Renderer2D {
                         write_mask: gpu::ColorWrites::ALL,
                     })],
                 }),
+                cache: None,
             })?;
         self.pipelines.insert(key, pipeline);
         Ok(())
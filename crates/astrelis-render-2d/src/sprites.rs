@@ -0,0 +1,361 @@
+//! Immediate-style sprite drawing on top of [`Renderer2D`] batching.
+
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use astrelis_core::{
+    color::Color,
+    geometry::{Physical, Rect},
+    math::{Affine2, Vec2},
+};
+use astrelis_gpu as gpu;
+use astrelis_render::{CompositedRenderTarget, RenderStats, RenderTarget};
+
+use crate::{Camera2D, DrawList2D, RenderError, Renderer2D, SpriteDraw, TextureHandle, TileAtlas};
+
+static NEXT_SHEET: AtomicU64 = AtomicU64::new(1);
+
+/// Index of a region within the [`SpriteSheet`] that issued it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SpriteRegion {
+    sheet: u64,
+    index: u32,
+}
+
+/// Source rectangles within one texture, addressable by index or name.
+///
+/// Regions are only valid on the sheet that issued them and on its clones.
+#[derive(Clone, Debug)]
+pub struct SpriteSheet {
+    id: u64,
+    texture: TextureHandle,
+    regions: Vec<Rect<Physical>>,
+    names: HashMap<String, SpriteRegion>,
+}
+
+impl SpriteSheet {
+    /// Creates a sheet with no regions.
+    pub fn new(texture: TextureHandle) -> Self {
+        Self {
+            id: NEXT_SHEET.fetch_add(1, Ordering::Relaxed),
+            texture,
+            regions: Vec::new(),
+            names: HashMap::new(),
+        }
+    }
+
+    /// Creates a sheet whose region `i` is tile `i` of a uniform grid, for
+    /// up to `count` tiles that fit in the texture.
+    pub fn from_atlas(atlas: TileAtlas, count: u32) -> Self {
+        let mut sheet = Self::new(atlas.texture);
+        sheet
+            .regions
+            .extend((0..count).map_while(|tile| atlas.source(tile)));
+        sheet
+    }
+
+    /// Texture the regions refer to.
+    pub fn texture(&self) -> TextureHandle {
+        self.texture
+    }
+
+    /// Appends a region. Reusing a name points it at the new region.
+    pub fn add_region(&mut self, name: impl Into<String>, source: Rect<Physical>) -> SpriteRegion {
+        let region = SpriteRegion {
+            sheet: self.id,
+            index: self.regions.len() as u32,
+        };
+        self.regions.push(source);
+        self.names.insert(name.into(), region);
+        region
+    }
+
+    /// Looks up a named region.
+    pub fn region(&self, name: &str) -> Option<SpriteRegion> {
+        self.names.get(name).copied()
+    }
+
+    /// Region by position, such as an animation frame number.
+    pub fn region_at(&self, index: u32) -> Option<SpriteRegion> {
+        ((index as usize) < self.regions.len()).then_some(SpriteRegion {
+            sheet: self.id,
+            index,
+        })
+    }
+
+    /// Source rectangle of a region in texels, or `None` for a region issued
+    /// by another sheet.
+    pub fn source(&self, region: SpriteRegion) -> Option<Rect<Physical>> {
+        if region.sheet != self.id {
+            return None;
+        }
+        self.regions.get(region.index as usize).copied()
+    }
+
+    /// Number of regions.
+    pub fn len(&self) -> usize {
+        self.regions.len()
+    }
+
+    /// Returns whether the sheet has no regions.
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+}
+
+/// Order of sprites that share a layer.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SpriteSort {
+    /// Later draws appear on top.
+    #[default]
+    Submission,
+    /// Sprites lower on screen (larger world Y) appear on top, for top-down
+    /// and isometric scenes; ties keep submission order.
+    YSort,
+}
+
+/// Sprites queued for one frame, drawn through a [`Renderer2D`].
+///
+/// Sprites are positioned by their pivot, centered by default; with
+/// [`SpriteSort::YSort`] a bottom-center pivot sorts characters by their
+/// feet. Sprites sharing a texture are drawn as one instanced batch.
+pub struct SpriteRenderer {
+    renderer: Renderer2D,
+    sprites: Vec<SpriteDraw>,
+    list: DrawList2D,
+    sort: SpriteSort,
+    layer: i32,
+    pivot: Vec2,
+}
+
+impl SpriteRenderer {
+    /// Wraps a renderer that owns the textures sprites refer to.
+    pub fn new(renderer: Renderer2D) -> Self {
+        Self {
+            renderer,
+            sprites: Vec::new(),
+            list: DrawList2D::new(),
+            sort: SpriteSort::Submission,
+            layer: 0,
+            pivot: Vec2::splat(0.5),
+        }
+    }
+
+    /// The underlying renderer, for texture registration.
+    pub fn renderer(&self) -> &Renderer2D {
+        &self.renderer
+    }
+
+    /// Mutable access to the underlying renderer.
+    pub fn renderer_mut(&mut self) -> &mut Renderer2D {
+        &mut self.renderer
+    }
+
+    /// Sets how sprites within a layer are ordered.
+    pub fn set_sort(&mut self, sort: SpriteSort) {
+        self.sort = sort;
+    }
+
+    /// Sets the painter layer for subsequent draws.
+    pub fn set_layer(&mut self, layer: i32) {
+        self.layer = layer;
+    }
+
+    /// Sets the normalized pivot for subsequent draws.
+    pub fn set_pivot(&mut self, pivot: Vec2) {
+        self.pivot = pivot;
+    }
+
+    /// Queues a sprite whose size is its source rectangle (or the whole
+    /// texture) in world units multiplied by `scale`.
+    pub fn draw_sprite(
+        &mut self,
+        texture: TextureHandle,
+        position: Vec2,
+        rotation: f32,
+        scale: Vec2,
+        color: Color,
+        uv_rect: Option<Rect<Physical>>,
+    ) -> Result<(), RenderError> {
+        let size = match uv_rect {
+            Some(source) => Vec2::new(source.size.width, source.size.height),
+            None => {
+                let size = self.renderer.texture_size(texture)?;
+                Vec2::new(size.width as f32, size.height as f32)
+            }
+        };
+        self.sprites.push(SpriteDraw {
+            texture,
+            source: uv_rect,
+            transform: Affine2::from_angle_translation(rotation, position),
+            size: size * scale,
+            pivot: self.pivot,
+            tint: color,
+            layer: self.layer,
+        });
+        Ok(())
+    }
+
+    /// Queues one region of a sprite sheet.
+    pub fn draw_region(
+        &mut self,
+        sheet: &SpriteSheet,
+        region: SpriteRegion,
+        position: Vec2,
+        rotation: f32,
+        scale: Vec2,
+        color: Color,
+    ) -> Result<(), RenderError> {
+        let source = sheet
+            .source(region)
+            .ok_or_else(|| RenderError::new("sprite region was not issued by this sheet"))?;
+        self.draw_sprite(
+            sheet.texture(),
+            position,
+            rotation,
+            scale,
+            color,
+            Some(source),
+        )
+    }
+
+    /// Number of sprites queued this frame.
+    pub fn len(&self) -> usize {
+        self.sprites.len()
+    }
+
+    /// Returns whether no sprites are queued.
+    pub fn is_empty(&self) -> bool {
+        self.sprites.is_empty()
+    }
+
+    /// Drops queued sprites without drawing them.
+    pub fn clear(&mut self) {
+        self.sprites.clear();
+    }
+
+    /// Clears `target` and draws every queued sprite, emptying the queue.
+    pub fn render(
+        &mut self,
+        encoder: &mut gpu::CommandEncoder,
+        target: &RenderTarget,
+        camera: &Camera2D,
+    ) -> Result<RenderStats, RenderError> {
+        self.prepare();
+        let result = self.renderer.render(encoder, target, camera, &self.list);
+        self.list.clear();
+        result
+    }
+
+    /// Draws every queued sprite into a compositor-owned region, emptying
+    /// the queue.
+    pub fn render_composited(
+        &mut self,
+        encoder: &mut gpu::CommandEncoder,
+        target: &CompositedRenderTarget,
+        camera: &Camera2D,
+    ) -> Result<RenderStats, RenderError> {
+        self.prepare();
+        let result = self
+            .renderer
+            .render_composited(encoder, target, camera, &self.list);
+        self.list.clear();
+        result
+    }
+
+    fn prepare(&mut self) {
+        sort(&mut self.sprites, self.sort);
+        self.list.clear();
+        for sprite in self.sprites.drain(..) {
+            self.list.draw_sprite(sprite);
+        }
+    }
+}
+
+/// Orders sprites for submission; [`Renderer2D`] then keeps that order within
+/// each layer.
+fn sort(sprites: &mut [SpriteDraw], mode: SpriteSort) {
+    match mode {
+        SpriteSort::Submission => {}
+        SpriteSort::YSort => sprites.sort_by(|a, b| {
+            a.layer.cmp(&b.layer).then(
+                a.transform
+                    .translation
+                    .y
+                    .total_cmp(&b.transform.translation.y),
+            )
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use astrelis_core::geometry::Size;
+    use astrelis_core::math::UVec2;
+
+    use super::*;
+
+    fn sprite(layer: i32, y: f32, slot: u32) -> SpriteDraw {
+        SpriteDraw {
+            texture: TextureHandle::testing(slot),
+            source: None,
+            transform: Affine2::from_translation(Vec2::new(0.0, y)),
+            size: Vec2::ONE,
+            pivot: Vec2::ZERO,
+            tint: Color::WHITE,
+            layer,
+        }
+    }
+
+    #[test]
+    fn y_sort_orders_within_layers_and_keeps_ties_stable() {
+        let mut sprites = vec![
+            sprite(1, 0.0, 0),
+            sprite(0, 5.0, 1),
+            sprite(0, -2.0, 2),
+            sprite(0, 5.0, 3),
+        ];
+        sort(&mut sprites, SpriteSort::YSort);
+        let order = sprites
+            .iter()
+            .map(|sprite| sprite.texture)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            order,
+            [2, 1, 3, 0].map(TextureHandle::testing),
+            "layer first, then Y, then submission order"
+        );
+    }
+
+    #[test]
+    fn sheet_regions_come_from_atlas_and_names() {
+        let atlas = TileAtlas {
+            texture: TextureHandle::testing(0),
+            texture_size: Size::new(32, 16),
+            tile_size: Size::new(16, 16),
+            margin: UVec2::ZERO,
+            spacing: UVec2::ZERO,
+        };
+        let mut sheet = SpriteSheet::from_atlas(atlas, 8);
+        assert_eq!(sheet.len(), 2);
+        let idle = sheet.add_region("idle", Rect::from_xywh(0.0, 0.0, 8.0, 8.0));
+        assert_eq!(sheet.region("idle"), Some(idle));
+        assert_eq!(
+            sheet.source(sheet.region_at(1).unwrap()),
+            Some(Rect::from_xywh(16.0, 0.0, 16.0, 16.0))
+        );
+        assert!(sheet.region_at(3).is_none());
+
+        let other = SpriteSheet::from_atlas(atlas, 8);
+        assert_eq!(
+            other.source(idle),
+            None,
+            "indices do not carry across sheets"
+        );
+        assert_eq!(other.source(sheet.region_at(0).unwrap()), None);
+        assert!(other.source(other.region_at(0).unwrap()).is_some());
+        assert_eq!(sheet.clone().source(idle), sheet.source(idle));
+    }
+}
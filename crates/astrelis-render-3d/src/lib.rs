@@ -4,10 +4,14 @@
 
 mod camera;
 mod mesh;
+mod nodes;
+mod particles;
 mod scene;
 
 pub use camera::Camera3D;
 pub use mesh::{MeshData, MeshVertex, cube, plane, uv_sphere};
+pub use nodes::{NodeId, NodeMesh, Scene3D};
+pub use particles::{EmitterDescriptor, ParticleSimulation};
 pub use scene::{
     AlphaMode, DebugLine, DirectionalLight, DrawList3D, Lighting, MaterialDescriptor, MeshDraw,
 };
@@ -21,15 +25,20 @@ use std::{
 };
 
 use astrelis_core::{
+    alloc::{Handle, HandleMap},
     color::Color,
     geometry::{Physical, Point, Rect, Size},
     math::{Mat3, Vec3},
 };
 use astrelis_gpu as gpu;
-use astrelis_render::{Antialiasing, CompositedRenderTarget, RenderStats, RenderTarget};
+use astrelis_render::{
+    Antialiasing, CapabilityReport, CapabilityTier, CompositedRenderTarget, RenderStats,
+    RenderTarget, TierCapabilities,
+};
 use bytemuck::{Pod, Zeroable};
 
 const SHADER: &str = include_str!("shader.wgsl");
+const PARTICLE_SHADER: &str = include_str!("particles.wgsl");
 static NEXT_RENDERER: AtomicU64 = AtomicU64::new(1);
 
 /// Device-bound 3D renderer configuration.
@@ -71,6 +80,10 @@ resource_handle!(
     MaterialHandle,
     "Generational Lambert material handle owned by one renderer."
 );
+resource_handle!(
+    ParticleHandle,
+    "Generational particle emitter handle owned by one renderer."
+);
 
 /// Texture filtering and addressing options.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
@@ -83,10 +96,6 @@ pub struct TextureOptions {
     pub address_v: gpu::AddressMode,
 }
 
-struct Slot<T> {
-    generation: u32,
-    value: Option<T>,
-}
 struct TextureResource {
     _texture: Option<gpu::Texture>,
     view: gpu::TextureView,
@@ -115,6 +124,8 @@ struct FrameUniform {
     ambient: [f32; 4],
     light_direction_intensity: [f32; 4],
     light_color: [f32; 4],
+    camera_right: [f32; 4],
+    camera_up: [f32; 4],
 }
 
 #[repr(C)]
@@ -186,11 +197,14 @@ pub struct Renderer3D {
     frame_bind_group: gpu::BindGroup,
     white_view: gpu::TextureView,
     white_sampler: gpu::Sampler,
-    textures: Vec<Slot<TextureResource>>,
-    meshes: Vec<Slot<MeshResource>>,
-    materials: Vec<Slot<MaterialResource>>,
+    textures: HandleMap<TextureResource>,
+    meshes: HandleMap<MeshResource>,
+    materials: HandleMap<MaterialResource>,
     mesh_pipelines: HashMap<MeshPipelineKey, gpu::RenderPipeline>,
     line_pipelines: HashMap<LinePipelineKey, gpu::RenderPipeline>,
+    particles: HandleMap<particles::ParticleResource>,
+    particle_pipelines: particles::ParticlePipelines,
+    capabilities: CapabilityReport,
     attachments: Vec<Attachments>,
 }
 
@@ -291,6 +305,8 @@ impl Renderer3D {
             min_filter: gpu::FilterMode::Linear,
             ..Default::default()
         });
+        let capabilities = CapabilityReport::evaluate(&device.capabilities(), &tiers(), None)
+            .expect("unforced evaluation always succeeds");
         Ok(Self {
             owner: NEXT_RENDERER.fetch_add(1, Ordering::Relaxed),
             device,
@@ -302,15 +318,34 @@ impl Renderer3D {
             frame_bind_group,
             white_view,
             white_sampler,
-            textures: Vec::new(),
-            meshes: Vec::new(),
-            materials: Vec::new(),
+            textures: HandleMap::new(),
+            meshes: HandleMap::new(),
+            materials: HandleMap::new(),
             mesh_pipelines: HashMap::new(),
             line_pipelines: HashMap::new(),
+            particles: HandleMap::new(),
+            particle_pipelines: Default::default(),
+            capabilities,
             attachments: Vec::new(),
         })
     }
 
+    /// Tier selection for optional rendering paths: `"compute"` simulates
+    /// particles in compute passes and `"baseline"` keeps everything on the
+    /// CPU.
+    pub fn capability_report(&self) -> &CapabilityReport {
+        &self.capabilities
+    }
+
+    /// Caps the renderer at a named tier, or lifts the cap with `None`, for
+    /// A/B performance comparisons. Resources created afterwards follow the
+    /// new tier; existing ones keep theirs.
+    pub fn force_tier(&mut self, tier: Option<&str>) -> Result<&CapabilityReport, RenderError> {
+        self.capabilities = CapabilityReport::evaluate(&self.device.capabilities(), &tiers(), tier)
+            .ok_or_else(|| RenderError::new("unknown 3D capability tier"))?;
+        Ok(&self.capabilities)
+    }
+
     /// Uploads an immutable straight-alpha RGBA8 sRGB texture.
     pub fn create_texture_rgba8(
         &mut self,
@@ -619,6 +654,8 @@ impl Renderer3D {
                 lighting.directional.intensity,
             ],
             light_color: lighting.directional.color.into(),
+            camera_right: (camera.rotation * Vec3::X).extend(0.0).to_array(),
+            camera_up: (camera.rotation * Vec3::Y).extend(0.0).to_array(),
         };
         self.queue
             .write_buffer(&self.frame_buffer, 0, bytemuck::bytes_of(&frame))?;
@@ -707,6 +744,10 @@ impl Renderer3D {
         if !draw_list.lines.is_empty() {
             self.ensure_line_pipeline(target.view.format(), sample_count)?;
         }
+        let particle_batches = self.prepare_particles(&draw_list.particles, camera.position)?;
+        if !particle_batches.is_empty() {
+            self.ensure_particle_pipeline(target.view.format(), sample_count)?;
+        }
         self.ensure_attachments(
             &RenderTarget {
                 view: target.view.clone(),
@@ -870,6 +911,11 @@ impl Renderer3D {
                 start = end;
             }
         }
+        stats.draw_calls += self.draw_particles(
+            &mut pass,
+            (target.view.format(), sample_count),
+            &particle_batches,
+        )?;
         if let Some(buffer) = &line_buffer {
             pass.set_pipeline(
                 self.line_pipelines
@@ -934,6 +980,15 @@ impl Renderer3D {
         if self.mesh_pipelines.contains_key(&key) {
             return Ok(());
         }
+        if !self.device.supports_sample_count(format, samples)
+            || !self
+                .device
+                .supports_sample_count(gpu::TextureFormat::Depth32Float, samples)
+        {
+            return Err(RenderError::new(format!(
+                "{format:?} does not support {samples}x multisampling"
+            )));
+        }
         let shader = self
             .device
             .create_shader_module(gpu::ShaderModuleDescriptor {
@@ -1014,6 +1069,7 @@ impl Renderer3D {
                         write_mask: gpu::ColorWrites::ALL,
                     })],
                 }),
+                cache: None,
             })?;
         self.mesh_pipelines.insert(key, pipeline);
         Ok(())
@@ -1028,6 +1084,15 @@ impl Renderer3D {
         if self.line_pipelines.contains_key(&key) {
             return Ok(());
         }
+        if !self.device.supports_sample_count(format, samples)
+            || !self
+                .device
+                .supports_sample_count(gpu::TextureFormat::Depth32Float, samples)
+        {
+            return Err(RenderError::new(format!(
+                "{format:?} does not support {samples}x multisampling"
+            )));
+        }
         let shader = self
             .device
             .create_shader_module(gpu::ShaderModuleDescriptor {
@@ -1083,6 +1148,7 @@ impl Renderer3D {
                         write_mask: gpu::ColorWrites::ALL,
                     })],
                 }),
+                cache: None,
             })?;
         self.line_pipelines.insert(key, pipeline);
         Ok(())
@@ -1139,6 +1205,17 @@ impl Renderer3D {
     }
 }
 
+fn tiers() -> [CapabilityTier; 2] {
+    [
+        CapabilityTier {
+            unsupported_apis: vec![gpu::GraphicsApi::Gl],
+            capabilities: TierCapabilities::COMPUTE,
+            ..CapabilityTier::new("compute")
+        },
+        CapabilityTier::new("baseline"),
+    ]
+}
+
 fn depth_state(write: bool) -> gpu::DepthStencilState {
     gpu::DepthStencilState {
         format: gpu::TextureFormat::Depth32Float,
@@ -1166,29 +1243,17 @@ fn alpha_key(mode: AlphaMode) -> u8 {
 
 fn insert_slot<T, H>(
     owner: u64,
-    slots: &mut Vec<Slot<T>>,
+    slots: &mut HandleMap<T>,
     value: T,
     make: impl FnOnce(u64, u32, u32) -> H,
 ) -> H {
-    if let Some((index, slot)) = slots
-        .iter_mut()
-        .enumerate()
-        .find(|(_, slot)| slot.value.is_none())
-    {
-        slot.value = Some(value);
-        return make(owner, index as u32, slot.generation);
-    }
-    let index = slots.len() as u32;
-    slots.push(Slot {
-        generation: 0,
-        value: Some(value),
-    });
-    make(owner, index, 0)
+    let handle = slots.insert(value);
+    make(owner, handle.index(), handle.generation())
 }
 
 fn get_slot<'a, T>(
     owner: u64,
-    slots: &'a [Slot<T>],
+    slots: &'a HandleMap<T>,
     handle_owner: u64,
     index: u32,
     generation: u32,
@@ -1199,20 +1264,32 @@ fn get_slot<'a, T>(
             "{kind} handle belongs to another renderer"
         )));
     }
-    let slot = slots
-        .get(index as usize)
-        .ok_or_else(|| RenderError::new(format!("invalid {kind} handle")))?;
-    if slot.generation != generation {
-        return Err(RenderError::new(format!("stale {kind} handle")));
+    slots
+        .get(Handle::from_raw_parts(index, generation))
+        .ok_or_else(|| RenderError::new(format!("stale or removed {kind} handle")))
+}
+
+fn get_slot_mut<'a, T>(
+    owner: u64,
+    slots: &'a mut HandleMap<T>,
+    handle_owner: u64,
+    index: u32,
+    generation: u32,
+    kind: &str,
+) -> Result<&'a mut T, RenderError> {
+    if owner != handle_owner {
+        return Err(RenderError::new(format!(
+            "{kind} handle belongs to another renderer"
+        )));
     }
-    slot.value
-        .as_ref()
-        .ok_or_else(|| RenderError::new(format!("removed {kind} handle")))
+    slots
+        .get_mut(Handle::from_raw_parts(index, generation))
+        .ok_or_else(|| RenderError::new(format!("stale or removed {kind} handle")))
 }
 
 fn remove_slot<T>(
     owner: u64,
-    slots: &mut [Slot<T>],
+    slots: &mut HandleMap<T>,
     handle_owner: u64,
     index: u32,
     generation: u32,
@@ -1222,15 +1299,10 @@ fn remove_slot<T>(
             "resource handle belongs to another renderer",
         ));
     }
-    let slot = slots
-        .get_mut(index as usize)
-        .ok_or_else(|| RenderError::new("invalid resource handle"))?;
-    if slot.generation != generation || slot.value.is_none() {
-        return Err(RenderError::new("stale or removed resource handle"));
-    }
-    slot.value = None;
-    slot.generation = slot.generation.wrapping_add(1);
-    Ok(())
+    slots
+        .remove(Handle::from_raw_parts(index, generation))
+        .map(drop)
+        .ok_or_else(|| RenderError::new("stale or removed resource handle"))
 }
 
 /// 3D renderer failure.
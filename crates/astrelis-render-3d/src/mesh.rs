@@ -72,135 +72,39 @@ impl MeshData {
     }
 }
 
+impl From<astrelis_render::Mesh<astrelis_render::StandardVertex>> for MeshData {
+    /// Converts a generic mesh with white vertex colors; sub-mesh ranges are
+    /// dropped because registered meshes draw as one range.
+    fn from(mesh: astrelis_render::Mesh<astrelis_render::StandardVertex>) -> Self {
+        Self {
+            vertices: mesh
+                .vertices
+                .into_iter()
+                .map(|vertex| MeshVertex {
+                    position: vertex.position,
+                    normal: vertex.normal,
+                    uv: vertex.uv,
+                    color: [1.0; 4],
+                })
+                .collect(),
+            indices: mesh.indices,
+        }
+    }
+}
+
 /// Creates a +Y-facing XZ plane centered at the origin.
 pub fn plane(width: f32, depth: f32) -> MeshData {
-    let positions = [
-        [-width * 0.5, 0.0, -depth * 0.5],
-        [-width * 0.5, 0.0, depth * 0.5],
-        [width * 0.5, 0.0, depth * 0.5],
-        [width * 0.5, 0.0, -depth * 0.5],
-    ];
-    MeshData {
-        vertices: positions
-            .into_iter()
-            .enumerate()
-            .map(|(index, position)| MeshVertex {
-                position,
-                normal: [0.0, 1.0, 0.0],
-                uv: [[0.0, 0.0], [0.0, 1.0], [1.0, 1.0], [1.0, 0.0]][index],
-                color: [1.0; 4],
-            })
-            .collect(),
-        indices: vec![0, 1, 2, 0, 2, 3],
-    }
+    astrelis_render::plane(width, depth).into()
 }
 
 /// Creates a cube with independent face normals and UVs.
 pub fn cube(size: f32) -> MeshData {
-    let h = size * 0.5;
-    let faces = [
-        (
-            Vec3::X,
-            [
-                Vec3::new(h, -h, h),
-                Vec3::new(h, h, h),
-                Vec3::new(h, h, -h),
-                Vec3::new(h, -h, -h),
-            ],
-        ),
-        (
-            Vec3::NEG_X,
-            [
-                Vec3::new(-h, -h, -h),
-                Vec3::new(-h, h, -h),
-                Vec3::new(-h, h, h),
-                Vec3::new(-h, -h, h),
-            ],
-        ),
-        (
-            Vec3::Y,
-            [
-                Vec3::new(-h, h, h),
-                Vec3::new(-h, h, -h),
-                Vec3::new(h, h, -h),
-                Vec3::new(h, h, h),
-            ],
-        ),
-        (
-            Vec3::NEG_Y,
-            [
-                Vec3::new(-h, -h, -h),
-                Vec3::new(-h, -h, h),
-                Vec3::new(h, -h, h),
-                Vec3::new(h, -h, -h),
-            ],
-        ),
-        (
-            Vec3::Z,
-            [
-                Vec3::new(-h, -h, h),
-                Vec3::new(-h, h, h),
-                Vec3::new(h, h, h),
-                Vec3::new(h, -h, h),
-            ],
-        ),
-        (
-            Vec3::NEG_Z,
-            [
-                Vec3::new(h, -h, -h),
-                Vec3::new(h, h, -h),
-                Vec3::new(-h, h, -h),
-                Vec3::new(-h, -h, -h),
-            ],
-        ),
-    ];
-    let mut vertices = Vec::with_capacity(24);
-    let mut indices = Vec::with_capacity(36);
-    for (face, (normal, positions)) in faces.into_iter().enumerate() {
-        let base = (face * 4) as u32;
-        for (index, position) in positions.into_iter().enumerate() {
-            vertices.push(MeshVertex {
-                position: position.to_array(),
-                normal: normal.to_array(),
-                uv: [[0.0, 1.0], [0.0, 0.0], [1.0, 0.0], [1.0, 1.0]][index],
-                color: [1.0; 4],
-            });
-        }
-        indices.extend_from_slice(&[base, base + 2, base + 1, base, base + 3, base + 2]);
-    }
-    MeshData { vertices, indices }
+    astrelis_render::cube(size).into()
 }
 
 /// Creates a latitude-longitude sphere with smooth normals.
 pub fn uv_sphere(radius: f32, sectors: u32, stacks: u32) -> MeshData {
-    let sectors = sectors.max(3);
-    let stacks = stacks.max(2);
-    let mut vertices = Vec::new();
-    for stack in 0..=stacks {
-        let v = stack as f32 / stacks as f32;
-        let phi = v * std::f32::consts::PI;
-        for sector in 0..=sectors {
-            let u = sector as f32 / sectors as f32;
-            let theta = u * std::f32::consts::TAU;
-            let normal = Vec3::new(theta.cos() * phi.sin(), phi.cos(), theta.sin() * phi.sin());
-            vertices.push(MeshVertex {
-                position: (normal * radius).to_array(),
-                normal: normal.to_array(),
-                uv: [u, v],
-                color: [1.0; 4],
-            });
-        }
-    }
-    let mut indices = Vec::new();
-    let row = sectors + 1;
-    for stack in 0..stacks {
-        for sector in 0..sectors {
-            let a = stack * row + sector;
-            let b = a + row;
-            indices.extend_from_slice(&[a, b + 1, b, a, a + 1, b + 1]);
-        }
-    }
-    MeshData { vertices, indices }
+    astrelis_render::uv_sphere(radius, sectors, stacks).into()
 }
 
 #[cfg(test)]
@@ -0,0 +1,294 @@
+//! Retained mesh instances with parent-relative transforms.
+
+use astrelis_core::{
+    alloc::{Handle, HandleMap},
+    color::Color,
+    math::Mat4,
+};
+
+use crate::{DrawList3D, MaterialHandle, MeshDraw, MeshHandle};
+
+/// Generational identifier of a node in one [`Scene3D`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct NodeId {
+    slot: u32,
+    generation: u32,
+}
+
+impl NodeId {
+    fn from_handle(handle: Handle<Node>) -> Self {
+        Self {
+            slot: handle.index(),
+            generation: handle.generation(),
+        }
+    }
+
+    fn handle(self) -> Handle<Node> {
+        Handle::from_raw_parts(self.slot, self.generation)
+    }
+}
+
+/// Mesh and material drawn at a node.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NodeMesh {
+    /// Registered mesh.
+    pub mesh: MeshHandle,
+    /// Registered material.
+    pub material: MaterialHandle,
+    /// Per-instance straight-alpha tint.
+    pub tint: Color,
+}
+
+struct Node {
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+    local: Mat4,
+    mesh: Option<NodeMesh>,
+    visible: bool,
+}
+
+/// Scene objects that persist across frames, flattened into a
+/// [`DrawList3D`] on demand.
+///
+/// Nodes without a mesh act as pivots for their children. A hidden node
+/// hides its whole subtree, and removing a node removes its descendants.
+#[derive(Default)]
+pub struct Scene3D {
+    nodes: HandleMap<Node>,
+}
+
+impl Scene3D {
+    /// Creates an empty scene.
+    pub const fn new() -> Self {
+        Self {
+            nodes: HandleMap::new(),
+        }
+    }
+
+    /// Adds a node under `parent`, or at the root.
+    ///
+    /// Returns `None` when `parent` is not a live node.
+    pub fn insert(
+        &mut self,
+        parent: Option<NodeId>,
+        local: Mat4,
+        mesh: Option<NodeMesh>,
+    ) -> Option<NodeId> {
+        if parent.is_some_and(|parent| !self.contains(parent)) {
+            return None;
+        }
+        let id = NodeId::from_handle(self.nodes.insert(Node {
+            parent,
+            children: Vec::new(),
+            local,
+            mesh,
+            visible: true,
+        }));
+        if let Some(parent) = parent.and_then(|parent| self.node_mut(parent)) {
+            parent.children.push(id);
+        }
+        Some(id)
+    }
+
+    /// Removes a node and every descendant. Returns whether it existed.
+    pub fn remove(&mut self, id: NodeId) -> bool {
+        let Some(parent) = self.node(id).map(|node| node.parent) else {
+            return false;
+        };
+        if let Some(siblings) = parent
+            .and_then(|parent| self.node_mut(parent))
+            .map(|parent| &mut parent.children)
+            && let Some(position) = siblings.iter().position(|&sibling| sibling == id)
+        {
+            siblings.swap_remove(position);
+        }
+        let mut doomed = vec![id];
+        while let Some(current) = doomed.pop() {
+            let node = self
+                .nodes
+                .remove(current.handle())
+                .expect("descendants of a live node are live");
+            doomed.extend(node.children);
+        }
+        true
+    }
+
+    /// Returns whether `id` refers to a live node.
+    pub fn contains(&self, id: NodeId) -> bool {
+        self.node(id).is_some()
+    }
+
+    /// Number of live nodes.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns whether the scene has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Parent of a node.
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.node(id)?.parent
+    }
+
+    /// Parent-relative transform of a node.
+    pub fn local_transform(&self, id: NodeId) -> Option<Mat4> {
+        Some(self.node(id)?.local)
+    }
+
+    /// Replaces a node's parent-relative transform.
+    pub fn set_local_transform(&mut self, id: NodeId, local: Mat4) -> bool {
+        self.node_mut(id).map(|node| node.local = local).is_some()
+    }
+
+    /// Object-to-world transform, composed through every ancestor.
+    pub fn world_transform(&self, id: NodeId) -> Option<Mat4> {
+        let node = self.node(id)?;
+        Some(match node.parent {
+            Some(parent) => self.world_transform(parent)? * node.local,
+            None => node.local,
+        })
+    }
+
+    /// Mesh drawn at a node.
+    pub fn mesh(&self, id: NodeId) -> Option<NodeMesh> {
+        self.node(id)?.mesh
+    }
+
+    /// Replaces or clears the mesh drawn at a node.
+    pub fn set_mesh(&mut self, id: NodeId, mesh: Option<NodeMesh>) -> bool {
+        self.node_mut(id).map(|node| node.mesh = mesh).is_some()
+    }
+
+    /// Shows or hides a node's subtree.
+    pub fn set_visible(&mut self, id: NodeId, visible: bool) -> bool {
+        self.node_mut(id)
+            .map(|node| node.visible = visible)
+            .is_some()
+    }
+
+    /// Appends every visible mesh with its world transform.
+    pub fn extend_draw_list(&self, list: &mut DrawList3D) {
+        let mut worlds = vec![None; self.nodes.slot_len()];
+        for (handle, node) in self.nodes.iter() {
+            let Some(world) = self.visible_world(NodeId::from_handle(handle), &mut worlds) else {
+                continue;
+            };
+            if let Some(mesh) = node.mesh {
+                list.draw_mesh(MeshDraw {
+                    mesh: mesh.mesh,
+                    material: mesh.material,
+                    transform: world,
+                    tint: mesh.tint,
+                });
+            }
+        }
+    }
+
+    /// World transform of a visible node, memoized per slot; `None` when the
+    /// node or an ancestor is hidden.
+    fn visible_world(&self, id: NodeId, worlds: &mut [Option<Option<Mat4>>]) -> Option<Mat4> {
+        if let Some(world) = worlds[id.slot as usize] {
+            return world;
+        }
+        let node = self.node(id)?;
+        let world = if !node.visible {
+            None
+        } else {
+            match node.parent {
+                Some(parent) => self
+                    .visible_world(parent, worlds)
+                    .map(|parent| parent * node.local),
+                None => Some(node.local),
+            }
+        };
+        worlds[id.slot as usize] = Some(world);
+        world
+    }
+
+    fn node(&self, id: NodeId) -> Option<&Node> {
+        self.nodes.get(id.handle())
+    }
+
+    fn node_mut(&mut self, id: NodeId) -> Option<&mut Node> {
+        self.nodes.get_mut(id.handle())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use astrelis_core::math::Vec3;
+
+    use super::*;
+
+    fn mesh() -> NodeMesh {
+        NodeMesh {
+            mesh: MeshHandle {
+                owner: 0,
+                slot: 0,
+                generation: 0,
+            },
+            material: MaterialHandle {
+                owner: 0,
+                slot: 0,
+                generation: 0,
+            },
+            tint: Color::WHITE,
+        }
+    }
+
+    #[test]
+    fn children_inherit_transforms_visibility_and_removal() {
+        let mut scene = Scene3D::new();
+        let root = scene
+            .insert(None, Mat4::from_translation(Vec3::X), None)
+            .unwrap();
+        let child = scene
+            .insert(Some(root), Mat4::from_translation(Vec3::Y), Some(mesh()))
+            .unwrap();
+        assert_eq!(
+            scene.world_transform(child),
+            Some(Mat4::from_translation(Vec3::new(1.0, 1.0, 0.0)))
+        );
+
+        let mut list = DrawList3D::new();
+        scene.extend_draw_list(&mut list);
+        assert_eq!(list.meshes.len(), 1);
+
+        scene.set_visible(root, false);
+        list.clear();
+        scene.extend_draw_list(&mut list);
+        assert!(list.meshes.is_empty(), "hidden parents hide children");
+
+        assert!(scene.remove(root));
+        assert!(!scene.contains(child));
+        assert!(scene.is_empty());
+        let reused = scene.insert(None, Mat4::IDENTITY, None).unwrap();
+        assert_ne!(reused, root, "stale ids never alias reused slots");
+    }
+
+    #[test]
+    fn removing_a_subtree_keeps_its_siblings() {
+        let mut scene = Scene3D::new();
+        let root = scene.insert(None, Mat4::IDENTITY, None).unwrap();
+        let left = scene.insert(Some(root), Mat4::IDENTITY, None).unwrap();
+        let right = scene
+            .insert(Some(root), Mat4::IDENTITY, Some(mesh()))
+            .unwrap();
+        let leaf = scene.insert(Some(left), Mat4::IDENTITY, None).unwrap();
+
+        assert!(scene.remove(left));
+        assert!(!scene.contains(leaf));
+        assert!(scene.contains(right));
+        assert_eq!(scene.len(), 2);
+        assert!(!scene.remove(left), "removed ids stay dead");
+
+        // The reused slot must not be mistaken for a child of `root`.
+        let unrelated = scene.insert(None, Mat4::IDENTITY, None).unwrap();
+        assert!(scene.remove(root));
+        assert!(scene.contains(unrelated));
+        assert_eq!(scene.len(), 1);
+    }
+}
@@ -0,0 +1,721 @@
+//! Emitter-driven billboard particles simulated by compute or on the CPU.
+
+use astrelis_core::{color::Color, math::Vec3, random::Rng};
+use astrelis_gpu as gpu;
+use astrelis_render::TierCapabilities;
+use bytemuck::{Pod, Zeroable};
+
+use crate::{
+    ParticleHandle, RenderError, Renderer3D, get_slot, get_slot_mut, insert_slot, remove_slot,
+};
+
+const WORKGROUP_SIZE: u32 = 64;
+
+/// Where an emitter's particles are integrated.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ParticleSimulation {
+    /// Compute when the device supports it and the emitter is unsorted,
+    /// otherwise the CPU.
+    #[default]
+    Auto,
+    /// A compute pass over the particle buffer.
+    Compute,
+    /// The CPU, uploading the whole buffer after each update.
+    Cpu,
+}
+
+/// Spawning, motion, and appearance of one particle emitter.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EmitterDescriptor {
+    /// Maximum live particles; the oldest are recycled first.
+    pub capacity: u32,
+    /// Particles spawned per second.
+    pub rate: f32,
+    /// World-space spawn position.
+    pub origin: Vec3,
+    /// Initial velocity in world units per second.
+    pub velocity: Vec3,
+    /// Per-axis random velocity added in `-spread..spread`.
+    pub spread: Vec3,
+    /// Constant acceleration.
+    pub gravity: Vec3,
+    /// Seconds each particle lives.
+    pub lifetime: f32,
+    /// World-space billboard size at spawn.
+    pub start_size: f32,
+    /// World-space billboard size at death.
+    pub end_size: f32,
+    /// Linear straight-alpha color at spawn.
+    pub start_color: Color,
+    /// Linear straight-alpha color at death.
+    pub end_color: Color,
+    /// Draw back-to-front for correct blending; sorted emitters simulate on
+    /// the CPU.
+    pub sort: bool,
+    /// Simulation placement.
+    pub simulation: ParticleSimulation,
+    /// Seed for spawn velocity jitter.
+    pub seed: u64,
+}
+
+impl Default for EmitterDescriptor {
+    fn default() -> Self {
+        Self {
+            capacity: 1_024,
+            rate: 64.0,
+            origin: Vec3::ZERO,
+            velocity: Vec3::Y,
+            spread: Vec3::splat(0.25),
+            gravity: Vec3::ZERO,
+            lifetime: 2.0,
+            start_size: 0.1,
+            end_size: 0.0,
+            start_color: Color::WHITE,
+            end_color: Color::TRANSPARENT,
+            sort: false,
+            simulation: ParticleSimulation::Auto,
+            seed: 0x9e37_79b9_7f4a_7c15,
+        }
+    }
+}
+
+impl EmitterDescriptor {
+    fn validate(&self) -> Result<(), &'static str> {
+        let colors = [self.start_color, self.end_color];
+        if self.capacity == 0
+            || !self.rate.is_finite()
+            || self.rate < 0.0
+            || !self.lifetime.is_finite()
+            || self.lifetime <= 0.0
+            || !self.start_size.is_finite()
+            || !self.end_size.is_finite()
+            || self.start_size < 0.0
+            || self.end_size < 0.0
+            || !self.origin.is_finite()
+            || !self.velocity.is_finite()
+            || !self.spread.is_finite()
+            || !self.gravity.is_finite()
+            || colors
+                .iter()
+                .any(|color| !<[f32; 4]>::from(*color).iter().all(|c| c.is_finite()))
+        {
+            return Err("emitter capacity, rates, sizes, vectors, and colors must be valid");
+        }
+        Ok(())
+    }
+}
+
+/// GPU particle layout shared by the compute and billboard shaders. Zeroed
+/// particles are dead because their age is not below their lifetime.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub(crate) struct Particle {
+    position_age: [f32; 4],
+    velocity_lifetime: [f32; 4],
+}
+
+impl Particle {
+    fn alive(&self) -> bool {
+        self.position_age[3] < self.velocity_lifetime[3]
+    }
+
+    fn position(&self) -> Vec3 {
+        Vec3::new(
+            self.position_age[0],
+            self.position_age[1],
+            self.position_age[2],
+        )
+    }
+
+    fn integrate(&mut self, gravity: Vec3, dt: f32) {
+        if !self.alive() {
+            return;
+        }
+        let velocity = Vec3::new(
+            self.velocity_lifetime[0],
+            self.velocity_lifetime[1],
+            self.velocity_lifetime[2],
+        ) + gravity * dt;
+        let position = self.position() + velocity * dt;
+        self.position_age = position.extend(self.position_age[3] + dt).to_array();
+        self.velocity_lifetime = velocity.extend(self.velocity_lifetime[3]).to_array();
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct ParticleParams {
+    gravity_dt: [f32; 4],
+    start_color: [f32; 4],
+    end_color: [f32; 4],
+    size: [f32; 4],
+}
+
+/// CPU spawning state, plus the authoritative particles when simulating on
+/// the CPU.
+pub(crate) struct Emitter {
+    descriptor: EmitterDescriptor,
+    particles: Vec<Particle>,
+    cursor: u32,
+    pending: f32,
+    rng: Rng,
+}
+
+impl Emitter {
+    fn new(descriptor: EmitterDescriptor) -> Self {
+        Self {
+            descriptor,
+            particles: vec![Particle::zeroed(); descriptor.capacity as usize],
+            cursor: 0,
+            pending: 0.0,
+            rng: Rng::new(descriptor.seed),
+        }
+    }
+
+    /// Writes due particles into ring slots and returns the first slot and
+    /// how many were spawned.
+    fn spawn(&mut self, dt: f32) -> (u32, u32) {
+        let capacity = self.descriptor.capacity;
+        self.pending += self.descriptor.rate * dt;
+        let count = (self.pending.floor() as u32).min(capacity);
+        self.pending -= self.pending.floor();
+        let start = self.cursor;
+        for _ in 0..count {
+            let jitter = Vec3::from_array([(); 3].map(|()| self.rng.range_f32(-1.0..1.0)));
+            let velocity = self.descriptor.velocity + jitter * self.descriptor.spread;
+            self.particles[self.cursor as usize] = Particle {
+                position_age: self.descriptor.origin.extend(0.0).to_array(),
+                velocity_lifetime: velocity.extend(self.descriptor.lifetime).to_array(),
+            };
+            self.cursor = (self.cursor + 1) % capacity;
+        }
+        (start, count)
+    }
+
+    fn integrate(&mut self, dt: f32) {
+        let gravity = self.descriptor.gravity;
+        for particle in &mut self.particles {
+            particle.integrate(gravity, dt);
+        }
+    }
+
+    /// Live particles farthest from `eye` first.
+    fn sorted(&self, eye: Vec3) -> Vec<Particle> {
+        let mut live = self
+            .particles
+            .iter()
+            .copied()
+            .filter(Particle::alive)
+            .collect::<Vec<_>>();
+        live.sort_by(|a, b| {
+            b.position()
+                .distance_squared(eye)
+                .total_cmp(&a.position().distance_squared(eye))
+        });
+        live
+    }
+
+    fn params(&self, dt: f32) -> ParticleParams {
+        ParticleParams {
+            gravity_dt: self.descriptor.gravity.extend(dt).to_array(),
+            start_color: self.descriptor.start_color.into(),
+            end_color: self.descriptor.end_color.into(),
+            size: [
+                self.descriptor.start_size,
+                self.descriptor.end_size,
+                0.0,
+                0.0,
+            ],
+        }
+    }
+}
+
+pub(crate) struct ParticleResource {
+    emitter: Emitter,
+    simulation: ParticleSimulation,
+    buffer: gpu::Buffer,
+    params: gpu::Buffer,
+    params_bind_group: gpu::BindGroup,
+    storage_bind_group: Option<gpu::BindGroup>,
+}
+
+/// Particle layouts and pipelines, created on first use.
+#[derive(Default)]
+pub(crate) struct ParticlePipelines {
+    params_layout: Option<gpu::BindGroupLayout>,
+    storage_layout: Option<gpu::BindGroupLayout>,
+    update: Option<gpu::ComputePipeline>,
+    billboards: Vec<((gpu::TextureFormat, u32), gpu::RenderPipeline)>,
+}
+
+/// A prepared particle draw for one frame.
+pub(crate) struct ParticleBatch {
+    buffer: gpu::Buffer,
+    bind_group: gpu::BindGroup,
+    instances: u32,
+}
+
+impl Renderer3D {
+    /// Creates a particle emitter with an empty particle buffer.
+    ///
+    /// Compute simulation needs a selected capability tier with
+    /// [`TierCapabilities::COMPUTE`] and is unavailable for sorted emitters;
+    /// requesting it otherwise is an error, while
+    /// [`ParticleSimulation::Auto`] falls back to the CPU.
+    pub fn create_particles(
+        &mut self,
+        descriptor: EmitterDescriptor,
+    ) -> Result<ParticleHandle, RenderError> {
+        descriptor.validate().map_err(RenderError::new)?;
+        let compute = self.supports_compute() && !descriptor.sort;
+        let simulation = match descriptor.simulation {
+            ParticleSimulation::Compute if !compute => {
+                return Err(RenderError::new(
+                    "compute particle simulation is unavailable for this emitter or device",
+                ));
+            }
+            ParticleSimulation::Auto if compute => ParticleSimulation::Compute,
+            ParticleSimulation::Auto => ParticleSimulation::Cpu,
+            other => other,
+        };
+        let emitter = Emitter::new(descriptor);
+        let mut usage = gpu::BufferUsages::VERTEX | gpu::BufferUsages::COPY_DST;
+        if simulation == ParticleSimulation::Compute {
+            usage |= gpu::BufferUsages::STORAGE;
+        }
+        let buffer = self.device.create_buffer_init(
+            &self.queue,
+            Some("render-3d particles".into()),
+            bytemuck::cast_slice(&emitter.particles),
+            usage,
+        )?;
+        let params = self.device.create_buffer_init(
+            &self.queue,
+            Some("render-3d particle params".into()),
+            bytemuck::bytes_of(&emitter.params(0.0)),
+            gpu::BufferUsages::UNIFORM | gpu::BufferUsages::COPY_DST,
+        )?;
+        let params_layout = self.particle_params_layout();
+        let params_bind_group = self.device.create_bind_group(gpu::BindGroupDescriptor {
+            label: Some("render-3d particle params bind group".into()),
+            layout: params_layout,
+            entries: vec![buffer_entry(0, &params)],
+        })?;
+        let storage_bind_group = if simulation == ParticleSimulation::Compute {
+            let layout = self.particle_storage_layout();
+            Some(self.device.create_bind_group(gpu::BindGroupDescriptor {
+                label: Some("render-3d particle storage bind group".into()),
+                layout,
+                entries: vec![buffer_entry(0, &buffer)],
+            })?)
+        } else {
+            None
+        };
+        Ok(insert_slot(
+            self.owner,
+            &mut self.particles,
+            ParticleResource {
+                emitter,
+                simulation,
+                buffer,
+                params,
+                params_bind_group,
+                storage_bind_group,
+            },
+            |owner, slot, generation| ParticleHandle {
+                owner,
+                slot,
+                generation,
+            },
+        ))
+    }
+
+    /// Removes a particle emitter handle.
+    pub fn remove_particles(&mut self, handle: ParticleHandle) -> Result<(), RenderError> {
+        remove_slot(
+            self.owner,
+            &mut self.particles,
+            handle.owner,
+            handle.slot,
+            handle.generation,
+        )
+    }
+
+    /// Resolved simulation placement of an emitter, never
+    /// [`ParticleSimulation::Auto`].
+    pub fn particle_simulation(
+        &self,
+        handle: ParticleHandle,
+    ) -> Result<ParticleSimulation, RenderError> {
+        Ok(self.particle_slot(handle)?.simulation)
+    }
+
+    /// Moves an emitter's spawn position; live particles stay where they are.
+    pub fn set_particle_origin(
+        &mut self,
+        handle: ParticleHandle,
+        origin: Vec3,
+    ) -> Result<(), RenderError> {
+        if !origin.is_finite() {
+            return Err(RenderError::new("emitter origin must be finite"));
+        }
+        self.particle_slot_mut(handle)?.emitter.descriptor.origin = origin;
+        Ok(())
+    }
+
+    /// Spawns due particles and advances every live particle by `dt` seconds.
+    ///
+    /// Compute emitters record their update into `encoder`; call this at most
+    /// once per emitter per submission, because parameter uploads land when
+    /// the queue is next submitted.
+    pub fn update_particles(
+        &mut self,
+        encoder: &mut gpu::CommandEncoder,
+        handle: ParticleHandle,
+        dt: f32,
+    ) -> Result<(), RenderError> {
+        astrelis_profiling::profile_function!();
+        if !dt.is_finite() || dt < 0.0 {
+            return Err(RenderError::new("particle time step must be nonnegative"));
+        }
+        if self.particle_slot(handle)?.simulation == ParticleSimulation::Compute {
+            self.ensure_particle_update()?;
+        }
+        let resource = get_slot_mut(
+            self.owner,
+            &mut self.particles,
+            handle.owner,
+            handle.slot,
+            handle.generation,
+            "particle",
+        )?;
+        let (start, count) = resource.emitter.spawn(dt);
+        self.queue.write_buffer(
+            &resource.params,
+            0,
+            bytemuck::bytes_of(&resource.emitter.params(dt)),
+        )?;
+        if resource.simulation == ParticleSimulation::Cpu {
+            resource.emitter.integrate(dt);
+            if !resource.emitter.descriptor.sort {
+                self.queue.write_buffer(
+                    &resource.buffer,
+                    0,
+                    bytemuck::cast_slice(&resource.emitter.particles),
+                )?;
+            }
+            return Ok(());
+        }
+        let capacity = resource.emitter.descriptor.capacity;
+        let first = count.min(capacity - start);
+        for (slot, len) in [(start, first), (0, count - first)] {
+            if len > 0 {
+                let particles = &resource.emitter.particles[slot as usize..(slot + len) as usize];
+                self.queue.write_buffer(
+                    &resource.buffer,
+                    slot as u64 * size_of::<Particle>() as u64,
+                    bytemuck::cast_slice(particles),
+                )?;
+            }
+        }
+        let mut pass = encoder.begin_compute_pass(gpu::ComputePassDescriptor {
+            label: Some("render-3d particle update".into()),
+        })?;
+        pass.set_pipeline(
+            self.particle_pipelines
+                .update
+                .as_ref()
+                .expect("ensured above"),
+        )?;
+        pass.set_bind_group(0, &self.frame_bind_group, &[])?;
+        pass.set_bind_group(1, &resource.params_bind_group, &[])?;
+        pass.set_bind_group(
+            2,
+            resource
+                .storage_bind_group
+                .as_ref()
+                .expect("compute emitters own a storage bind group"),
+            &[],
+        )?;
+        pass.dispatch_workgroups(capacity.div_ceil(WORKGROUP_SIZE), 1, 1);
+        Ok(())
+    }
+
+    /// Uploads sorted emitters for `eye` and collects the frame's batches.
+    pub(crate) fn prepare_particles(
+        &mut self,
+        handles: &[ParticleHandle],
+        eye: Vec3,
+    ) -> Result<Vec<ParticleBatch>, RenderError> {
+        let mut batches = Vec::with_capacity(handles.len());
+        for &handle in handles {
+            let resource = self.particle_slot(handle)?;
+            let instances = if resource.emitter.descriptor.sort {
+                let sorted = resource.emitter.sorted(eye);
+                if !sorted.is_empty() {
+                    self.queue
+                        .write_buffer(&resource.buffer, 0, bytemuck::cast_slice(&sorted))?;
+                }
+                sorted.len() as u32
+            } else {
+                resource.emitter.descriptor.capacity
+            };
+            if instances > 0 {
+                batches.push(ParticleBatch {
+                    buffer: resource.buffer.clone(),
+                    bind_group: resource.params_bind_group.clone(),
+                    instances,
+                });
+            }
+        }
+        Ok(batches)
+    }
+
+    /// Draws prepared batches as camera-facing premultiplied billboards.
+    pub(crate) fn draw_particles(
+        &self,
+        pass: &mut gpu::RenderPass<'_>,
+        key: (gpu::TextureFormat, u32),
+        batches: &[ParticleBatch],
+    ) -> Result<u32, RenderError> {
+        let Some((_, pipeline)) = self
+            .particle_pipelines
+            .billboards
+            .iter()
+            .find(|(value, _)| *value == key)
+        else {
+            return Ok(0);
+        };
+        pass.set_pipeline(pipeline)?;
+        for batch in batches {
+            pass.set_bind_group(1, &batch.bind_group, &[])?;
+            pass.set_vertex_buffer(0, &batch.buffer, 0..batch.buffer.size())?;
+            pass.draw(0..6, 0..batch.instances);
+        }
+        Ok(batches.len() as u32)
+    }
+
+    pub(crate) fn ensure_particle_pipeline(
+        &mut self,
+        format: gpu::TextureFormat,
+        samples: u32,
+    ) -> Result<(), RenderError> {
+        let key = (format, samples);
+        if self
+            .particle_pipelines
+            .billboards
+            .iter()
+            .any(|(value, _)| *value == key)
+        {
+            return Ok(());
+        }
+        let shader = self
+            .device
+            .create_shader_module(gpu::ShaderModuleDescriptor {
+                label: Some("render-3d particle shader".into()),
+                wgsl: crate::PARTICLE_SHADER.into(),
+            });
+        let params_layout = self.particle_params_layout();
+        let layout = self
+            .device
+            .create_pipeline_layout(gpu::PipelineLayoutDescriptor {
+                label: Some("render-3d particle pipeline layout".into()),
+                bind_group_layouts: vec![self.frame_layout.clone(), params_layout],
+            })?;
+        let pipeline = self
+            .device
+            .create_render_pipeline(gpu::RenderPipelineDescriptor {
+                label: Some("render-3d particle pipeline".into()),
+                layout: Some(layout),
+                vertex: gpu::VertexState {
+                    module: shader.clone(),
+                    entry_point: "vs_particle".into(),
+                    buffers: vec![gpu::VertexBufferLayout {
+                        array_stride: size_of::<Particle>() as u64,
+                        step_mode: gpu::VertexStepMode::Instance,
+                        attributes: vec![
+                            gpu::VertexAttribute {
+                                offset: 0,
+                                shader_location: 0,
+                                format: gpu::VertexFormat::Float32x4,
+                            },
+                            gpu::VertexAttribute {
+                                offset: 16,
+                                shader_location: 1,
+                                format: gpu::VertexFormat::Float32x4,
+                            },
+                        ],
+                    }],
+                },
+                primitive: gpu::PrimitiveState::default(),
+                depth_stencil: Some(crate::depth_state(false)),
+                multisample: gpu::MultisampleState {
+                    count: samples,
+                    ..Default::default()
+                },
+                fragment: Some(gpu::FragmentState {
+                    module: shader,
+                    entry_point: "fs_particle".into(),
+                    targets: vec![Some(gpu::ColorTargetState {
+                        format,
+                        blend: Some(gpu::BlendState::PREMULTIPLIED_ALPHA),
+                        write_mask: gpu::ColorWrites::ALL,
+                    })],
+                }),
+                cache: None,
+            })?;
+        self.particle_pipelines.billboards.push((key, pipeline));
+        Ok(())
+    }
+
+    fn ensure_particle_update(&mut self) -> Result<(), RenderError> {
+        if self.particle_pipelines.update.is_some() {
+            return Ok(());
+        }
+        let shader = self
+            .device
+            .create_shader_module(gpu::ShaderModuleDescriptor {
+                label: Some("render-3d particle shader".into()),
+                wgsl: crate::PARTICLE_SHADER.into(),
+            });
+        let bind_group_layouts = vec![
+            self.frame_layout.clone(),
+            self.particle_params_layout(),
+            self.particle_storage_layout(),
+        ];
+        let layout = self
+            .device
+            .create_pipeline_layout(gpu::PipelineLayoutDescriptor {
+                label: Some("render-3d particle update layout".into()),
+                bind_group_layouts,
+            })?;
+        let pipeline = self
+            .device
+            .create_compute_pipeline(gpu::ComputePipelineDescriptor {
+                label: Some("render-3d particle update".into()),
+                layout: Some(layout),
+                module: shader,
+                entry_point: "cs_particles".into(),
+                cache: None,
+            })?;
+        self.particle_pipelines.update = Some(pipeline);
+        Ok(())
+    }
+
+    fn supports_compute(&self) -> bool {
+        self.capabilities
+            .selected_capabilities()
+            .contains(TierCapabilities::COMPUTE)
+    }
+
+    fn particle_params_layout(&mut self) -> gpu::BindGroupLayout {
+        let device = &self.device;
+        self.particle_pipelines
+            .params_layout
+            .get_or_insert_with(|| {
+                device.create_bind_group_layout(gpu::BindGroupLayoutDescriptor {
+                    label: Some("render-3d particle params layout".into()),
+                    entries: vec![gpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: gpu::ShaderStages::VERTEX | gpu::ShaderStages::COMPUTE,
+                        ty: gpu::BindingType::Buffer {
+                            ty: gpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                    }],
+                })
+            })
+            .clone()
+    }
+
+    fn particle_storage_layout(&mut self) -> gpu::BindGroupLayout {
+        let device = &self.device;
+        self.particle_pipelines
+            .storage_layout
+            .get_or_insert_with(|| {
+                device.create_bind_group_layout(gpu::BindGroupLayoutDescriptor {
+                    label: Some("render-3d particle storage layout".into()),
+                    entries: vec![gpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: gpu::ShaderStages::COMPUTE,
+                        ty: gpu::BindingType::Buffer {
+                            ty: gpu::BufferBindingType::Storage,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                    }],
+                })
+            })
+            .clone()
+    }
+
+    fn particle_slot(&self, handle: ParticleHandle) -> Result<&ParticleResource, RenderError> {
+        get_slot(
+            self.owner,
+            &self.particles,
+            handle.owner,
+            handle.slot,
+            handle.generation,
+            "particle",
+        )
+    }
+
+    fn particle_slot_mut(
+        &mut self,
+        handle: ParticleHandle,
+    ) -> Result<&mut ParticleResource, RenderError> {
+        get_slot_mut(
+            self.owner,
+            &mut self.particles,
+            handle.owner,
+            handle.slot,
+            handle.generation,
+            "particle",
+        )
+    }
+}
+
+fn buffer_entry(binding: u32, buffer: &gpu::Buffer) -> gpu::BindGroupEntry {
+    gpu::BindGroupEntry {
+        binding,
+        resource: gpu::BindingResource::Buffer(gpu::BufferBinding {
+            buffer: buffer.clone(),
+            offset: 0,
+            size: None,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn emitters_spawn_into_a_ring_and_sort_back_to_front() {
+        let mut emitter = Emitter::new(EmitterDescriptor {
+            capacity: 4,
+            rate: 10.0,
+            velocity: Vec3::Z,
+            spread: Vec3::new(0.0, 0.0, 0.5),
+            gravity: Vec3::NEG_Y,
+            lifetime: 1.0,
+            ..Default::default()
+        });
+        assert_eq!(emitter.spawn(0.25), (0, 2));
+        emitter.integrate(0.25);
+        assert_eq!(emitter.particles[0].position().y, -0.0625);
+        assert_eq!(emitter.spawn(0.3), (2, 3), "spawning wraps the ring");
+        assert_eq!(emitter.cursor, 1);
+
+        emitter.integrate(0.8);
+        assert_eq!(emitter.sorted(Vec3::ZERO).len(), 3, "expired particles die");
+        let sorted = emitter.sorted(Vec3::new(0.0, 0.0, 10.0));
+        assert!(
+            sorted
+                .windows(2)
+                .all(|pair| pair[0].position().z <= pair[1].position().z),
+            "farther particles draw first"
+        );
+    }
+}
@@ -0,0 +1,84 @@
+struct Frame {
+    view_projection: mat4x4<f32>,
+    camera_position: vec4<f32>,
+    ambient: vec4<f32>,
+    light_direction_intensity: vec4<f32>,
+    light_color: vec4<f32>,
+    camera_right: vec4<f32>,
+    camera_up: vec4<f32>,
+};
+
+struct ParticleParams {
+    gravity_dt: vec4<f32>,
+    start_color: vec4<f32>,
+    end_color: vec4<f32>,
+    size: vec4<f32>,
+};
+
+struct Particle {
+    position_age: vec4<f32>,
+    velocity_lifetime: vec4<f32>,
+};
+
+@group(0) @binding(0) var<uniform> frame: Frame;
+@group(1) @binding(0) var<uniform> params: ParticleParams;
+@group(2) @binding(0) var<storage, read_write> particles: array<Particle>;
+
+struct ParticleOut {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) offset: vec2<f32>,
+    @location(1) color: vec4<f32>,
+};
+
+@vertex
+fn vs_particle(
+    @builtin(vertex_index) vertex: u32,
+    @location(0) position_age: vec4<f32>,
+    @location(1) velocity_lifetime: vec4<f32>,
+) -> ParticleOut {
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(-1.0, -1.0),
+        vec2<f32>(1.0, -1.0),
+        vec2<f32>(1.0, 1.0),
+        vec2<f32>(-1.0, -1.0),
+        vec2<f32>(1.0, 1.0),
+        vec2<f32>(-1.0, 1.0),
+    );
+    let corner = corners[vertex];
+    var out: ParticleOut;
+    out.offset = corner;
+    if position_age.w >= velocity_lifetime.w {
+        out.clip_position = vec4<f32>(2.0, 2.0, 2.0, 1.0);
+        out.color = vec4<f32>(0.0);
+        return out;
+    }
+    let t = clamp(position_age.w / velocity_lifetime.w, 0.0, 1.0);
+    let half_size = mix(params.size.x, params.size.y, t) * 0.5;
+    let world = position_age.xyz
+        + (frame.camera_right.xyz * corner.x + frame.camera_up.xyz * corner.y) * half_size;
+    out.clip_position = frame.view_projection * vec4<f32>(world, 1.0);
+    out.color = mix(params.start_color, params.end_color, t);
+    return out;
+}
+
+@fragment
+fn fs_particle(in: ParticleOut) -> @location(0) vec4<f32> {
+    let alpha = in.color.a * (1.0 - smoothstep(0.6, 1.0, length(in.offset)));
+    return vec4<f32>(in.color.rgb * alpha, alpha);
+}
+
+@compute @workgroup_size(64)
+fn cs_particles(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x >= arrayLength(&particles) {
+        return;
+    }
+    var particle = particles[id.x];
+    if particle.position_age.w >= particle.velocity_lifetime.w {
+        return;
+    }
+    let dt = params.gravity_dt.w;
+    let velocity = particle.velocity_lifetime.xyz + params.gravity_dt.xyz * dt;
+    particle.position_age = vec4<f32>(particle.position_age.xyz + velocity * dt, particle.position_age.w + dt);
+    particle.velocity_lifetime = vec4<f32>(velocity, particle.velocity_lifetime.w);
+    particles[id.x] = particle;
+}
@@ -5,7 +5,7 @@ use astrelis_core::{
     math::{Mat4, Vec3},
 };
 
-use crate::{MaterialHandle, MeshHandle};
+use crate::{MaterialHandle, MeshHandle, ParticleHandle};
 
 /// Material alpha policy.
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -107,6 +107,7 @@ pub struct DebugLine {
 pub struct DrawList3D {
     pub(crate) meshes: Vec<MeshDraw>,
     pub(crate) lines: Vec<DebugLine>,
+    pub(crate) particles: Vec<ParticleHandle>,
 }
 
 impl DrawList3D {
@@ -115,6 +116,7 @@ impl DrawList3D {
         Self {
             meshes: Vec::new(),
             lines: Vec::new(),
+            particles: Vec::new(),
         }
     }
     /// Records a mesh instance.
@@ -125,6 +127,10 @@ impl DrawList3D {
     pub fn draw_line(&mut self, line: DebugLine) {
         self.lines.push(line);
     }
+    /// Records a particle emitter, drawn after meshes as blended billboards.
+    pub fn draw_particles(&mut self, particles: ParticleHandle) {
+        self.particles.push(particles);
+    }
     /// Records an XZ grid centered on the origin.
     pub fn draw_grid(&mut self, half_lines: u32, spacing: f32, color: Color) {
         let extent = half_lines as f32 * spacing;
@@ -161,5 +167,6 @@ impl DrawList3D {
     pub fn clear(&mut self) {
         self.meshes.clear();
         self.lines.clear();
+        self.particles.clear();
     }
 }
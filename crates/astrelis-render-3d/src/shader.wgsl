@@ -4,6 +4,8 @@ struct Frame {
     ambient: vec4<f32>,
     light_direction_intensity: vec4<f32>,
     light_color: vec4<f32>,
+    camera_right: vec4<f32>,
+    camera_up: vec4<f32>,
 };
 
 struct Material {
@@ -11,7 +11,8 @@ use astrelis_gpu::{
 };
 use astrelis_render::{Antialiasing, RenderTarget};
 use astrelis_render_3d::{
-    Camera3D, DrawList3D, Lighting, MaterialDescriptor, MeshDraw, Renderer3D, RendererOptions, cube,
+    Camera3D, DrawList3D, EmitterDescriptor, Lighting, MaterialDescriptor, MeshDraw,
+    ParticleSimulation, Renderer3D, RendererOptions, cube,
 };
 
 #[test]
@@ -74,12 +75,35 @@ fn renders_lit_reverse_z_mesh_and_debug_lines() {
             ..Default::default()
         };
         camera.look_at(Vec3::ZERO, Vec3::Y);
+        assert!(renderer.force_tier(Some("missing")).is_err());
+        let forced = renderer.force_tier(Some("baseline")).unwrap();
+        assert_eq!(forced.selected_name(), Some("baseline"));
+        assert!(forced.selected_capabilities().is_empty());
+        renderer.force_tier(None).unwrap();
         let mut encoder = device.create_command_encoder(Default::default());
+        for sort in [false, true] {
+            let particles = renderer
+                .create_particles(EmitterDescriptor {
+                    sort,
+                    ..Default::default()
+                })
+                .unwrap();
+            if sort {
+                assert_eq!(
+                    renderer.particle_simulation(particles),
+                    Ok(ParticleSimulation::Cpu)
+                );
+            }
+            renderer
+                .update_particles(&mut encoder, particles, 0.1)
+                .unwrap();
+            list.draw_particles(particles);
+        }
         let stats = renderer
             .render(&mut encoder, &target, &camera, &Lighting::default(), &list)
             .unwrap();
         assert_eq!(stats.instances, 1);
-        assert!(stats.draw_calls >= 2);
+        assert!(stats.draw_calls >= 4);
         queue.submit([encoder.finish().unwrap()]).unwrap();
         device.poll(astrelis_gpu::PollMode::Wait).unwrap();
     });
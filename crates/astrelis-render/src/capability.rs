@@ -0,0 +1,299 @@
+//! Renderer capability tiers with explained rejections.
+
+use std::fmt;
+
+use astrelis_gpu as gpu;
+use bitflags::bitflags;
+
+bitflags! {
+    /// Optional rendering paths a tier enables once selected.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+    pub struct TierCapabilities: u32 {
+        /// GPU compute passes, such as particle simulation.
+        const COMPUTE = 1 << 0;
+    }
+}
+
+/// Minimum device limits a tier needs; zero fields impose nothing.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TierLimits {
+    /// Minimum 2D texture dimension.
+    pub max_texture_dimension_2d: u32,
+    /// Minimum bind groups per pipeline.
+    pub max_bind_groups: u32,
+    /// Minimum vertex buffer slots.
+    pub max_vertex_buffers: u32,
+    /// Minimum buffer size in bytes.
+    pub max_buffer_size: u64,
+}
+
+/// One rendering path and what the device must provide for it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CapabilityTier {
+    /// Name used in reports and to force a downgrade.
+    pub name: String,
+    /// Features that must all be enabled.
+    pub features: gpu::Features,
+    /// Limits that must all be met.
+    pub limits: TierLimits,
+    /// APIs on which the path is known not to work.
+    pub unsupported_apis: Vec<gpu::GraphicsApi>,
+    /// Paths renderers may take when this tier is selected.
+    pub capabilities: TierCapabilities,
+}
+
+impl CapabilityTier {
+    /// Creates a tier with no requirements that enables nothing optional.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            features: gpu::Features::empty(),
+            limits: TierLimits::default(),
+            unsupported_apis: Vec::new(),
+            capabilities: TierCapabilities::empty(),
+        }
+    }
+
+    /// Reasons the device cannot run this tier; empty when it can.
+    pub fn rejections(&self, capabilities: &gpu::DeviceCapabilities) -> Vec<TierRejection> {
+        let mut rejections = Vec::new();
+        if self.unsupported_apis.contains(&capabilities.api) {
+            rejections.push(TierRejection::Api(capabilities.api));
+        }
+        let missing = self.features - capabilities.features;
+        if !missing.is_empty() {
+            rejections.push(TierRejection::MissingFeatures(missing));
+        }
+        let available = capabilities.limits;
+        for (name, required, available) in [
+            (
+                "max_texture_dimension_2d",
+                self.limits.max_texture_dimension_2d as u64,
+                available.max_texture_dimension_2d as u64,
+            ),
+            (
+                "max_bind_groups",
+                self.limits.max_bind_groups as u64,
+                available.max_bind_groups as u64,
+            ),
+            (
+                "max_vertex_buffers",
+                self.limits.max_vertex_buffers as u64,
+                available.max_vertex_buffers as u64,
+            ),
+            (
+                "max_buffer_size",
+                self.limits.max_buffer_size,
+                available.max_buffer_size,
+            ),
+        ] {
+            if required > available {
+                rejections.push(TierRejection::Limit {
+                    name,
+                    required,
+                    available,
+                });
+            }
+        }
+        rejections
+    }
+}
+
+/// Why a tier was not selected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TierRejection {
+    /// The backend API cannot run the tier.
+    Api(gpu::GraphicsApi),
+    /// Required features the device did not enable.
+    MissingFeatures(gpu::Features),
+    /// A device limit below the tier's minimum.
+    Limit {
+        /// Limit field name.
+        name: &'static str,
+        /// Minimum the tier needs.
+        required: u64,
+        /// Value the device provides.
+        available: u64,
+    },
+    /// The tier is above a forced maximum.
+    Forced,
+}
+
+impl fmt::Display for TierRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Api(api) => write!(f, "unsupported on {api:?}"),
+            Self::MissingFeatures(features) => write!(f, "missing features {features:?}"),
+            Self::Limit {
+                name,
+                required,
+                available,
+            } => write!(f, "{name} is {available}, needs {required}"),
+            Self::Forced => f.write_str("above the forced maximum tier"),
+        }
+    }
+}
+
+/// Evaluation of one tier.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TierStatus {
+    /// Tier name.
+    pub name: String,
+    /// Paths the tier enables.
+    pub capabilities: TierCapabilities,
+    /// Empty when the tier is usable.
+    pub rejections: Vec<TierRejection>,
+}
+
+impl TierStatus {
+    /// Returns whether nothing rejected the tier.
+    pub fn accepted(&self) -> bool {
+        self.rejections.is_empty()
+    }
+}
+
+/// Structured record of tier selection, suitable for logs and telemetry.
+///
+/// `Display` renders one line per tier, marking the selected tier with `*`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CapabilityReport {
+    /// Device backend API.
+    pub api: gpu::GraphicsApi,
+    /// Enabled device features.
+    pub features: gpu::Features,
+    /// Enabled device limits.
+    pub limits: gpu::Limits,
+    /// Every tier, best first.
+    pub tiers: Vec<TierStatus>,
+    /// Index of the chosen tier, if any was accepted.
+    pub selected: Option<usize>,
+    /// Forced maximum tier, if one was requested.
+    pub forced: Option<String>,
+}
+
+impl CapabilityReport {
+    /// Evaluates `tiers`, ordered best first, and selects the first accepted
+    /// one at or below `forced`.
+    ///
+    /// Returns `None` when `forced` names no tier.
+    pub fn evaluate(
+        capabilities: &gpu::DeviceCapabilities,
+        tiers: &[CapabilityTier],
+        forced: Option<&str>,
+    ) -> Option<Self> {
+        let ceiling = match forced {
+            Some(name) => tiers.iter().position(|tier| tier.name == name)?,
+            None => 0,
+        };
+        let tiers = tiers
+            .iter()
+            .enumerate()
+            .map(|(index, tier)| {
+                let mut rejections = tier.rejections(capabilities);
+                if index < ceiling {
+                    rejections.push(TierRejection::Forced);
+                }
+                TierStatus {
+                    name: tier.name.clone(),
+                    capabilities: tier.capabilities,
+                    rejections,
+                }
+            })
+            .collect::<Vec<_>>();
+        Some(Self {
+            api: capabilities.api,
+            features: capabilities.features,
+            limits: capabilities.limits,
+            selected: tiers.iter().position(TierStatus::accepted),
+            tiers,
+            forced: forced.map(Into::into),
+        })
+    }
+
+    /// Name of the selected tier.
+    pub fn selected_name(&self) -> Option<&str> {
+        self.selected.map(|index| self.tiers[index].name.as_str())
+    }
+
+    /// Paths enabled by the selected tier; empty when none was selected.
+    pub fn selected_capabilities(&self) -> TierCapabilities {
+        self.selected.map_or(TierCapabilities::empty(), |index| {
+            self.tiers[index].capabilities
+        })
+    }
+}
+
+impl fmt::Display for CapabilityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} features {:?}", self.api, self.features)?;
+        if let Some(forced) = &self.forced {
+            write!(f, ", forced to {forced}")?;
+        }
+        for (index, tier) in self.tiers.iter().enumerate() {
+            let mark = if self.selected == Some(index) {
+                '*'
+            } else {
+                ' '
+            };
+            write!(f, "\n{mark} {}", tier.name)?;
+            for (position, rejection) in tier.rejections.iter().enumerate() {
+                let separator = if position == 0 { ": " } else { "; " };
+                write!(f, "{separator}{rejection}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_explain_rejections_and_honor_forced_downgrades() {
+        let capabilities = gpu::DeviceCapabilities {
+            features: gpu::Features::TIMESTAMP_QUERY,
+            limits: gpu::Limits::default(),
+            api: gpu::GraphicsApi::Vulkan,
+            reliable_timestamps: true,
+        };
+        let tiers = [
+            CapabilityTier {
+                features: gpu::Features::MULTI_DRAW_INDIRECT_COUNT,
+                limits: TierLimits {
+                    max_texture_dimension_2d: 16_384,
+                    ..Default::default()
+                },
+                ..CapabilityTier::new("ultra")
+            },
+            CapabilityTier {
+                unsupported_apis: vec![gpu::GraphicsApi::Gl],
+                capabilities: TierCapabilities::COMPUTE,
+                ..CapabilityTier::new("gpu simulation")
+            },
+            CapabilityTier::new("baseline"),
+        ];
+
+        let report = CapabilityReport::evaluate(&capabilities, &tiers, None).unwrap();
+        assert_eq!(report.selected_name(), Some("gpu simulation"));
+        assert_eq!(report.selected_capabilities(), TierCapabilities::COMPUTE);
+        assert_eq!(
+            report.tiers[0].rejections,
+            [
+                TierRejection::MissingFeatures(gpu::Features::MULTI_DRAW_INDIRECT_COUNT),
+                TierRejection::Limit {
+                    name: "max_texture_dimension_2d",
+                    required: 16_384,
+                    available: 8_192,
+                },
+            ]
+        );
+
+        let forced = CapabilityReport::evaluate(&capabilities, &tiers, Some("baseline")).unwrap();
+        assert_eq!(forced.selected_name(), Some("baseline"));
+        assert!(forced.selected_capabilities().is_empty());
+        assert_eq!(forced.tiers[1].rejections, [TierRejection::Forced]);
+        assert!(forced.to_string().contains("* baseline"));
+        assert!(CapabilityReport::evaluate(&capabilities, &tiers, Some("missing")).is_none());
+    }
+}
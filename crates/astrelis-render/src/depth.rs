@@ -0,0 +1,205 @@
+//! Persistent depth/stencil attachments and matching pipeline states.
+
+use astrelis_core::geometry::{Physical, Size};
+use astrelis_gpu as gpu;
+
+use crate::TargetError;
+
+/// How a pass uses one aspect of a depth/stencil attachment.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AttachmentAccess<T> {
+    /// Clears to a value and stores the pass's writes.
+    Clear(T),
+    /// Keeps earlier contents and stores the pass's writes.
+    Load,
+    /// Keeps earlier contents and forbids writes, so the attachment can also
+    /// be sampled by the pass.
+    ReadOnly,
+}
+
+impl<T> AttachmentAccess<T> {
+    fn operations(self) -> Option<gpu::AttachmentOperations<T>> {
+        let load = match self {
+            Self::Clear(value) => gpu::LoadOpValue::Clear(value),
+            Self::Load => gpu::LoadOpValue::Load,
+            Self::ReadOnly => return None,
+        };
+        Some(gpu::AttachmentOperations {
+            load,
+            store: gpu::StoreOp::Store,
+        })
+    }
+}
+
+/// A depth/stencil texture that persists across frames.
+///
+/// Passes that draw 2D layers with depth testing, or clip UI through the
+/// stencil buffer, share one attachment; later passes [`AttachmentAccess::Load`]
+/// what earlier ones wrote. The texture is reallocated when the frame size
+/// changes.
+pub struct DepthStencilTarget {
+    device: gpu::Device,
+    format: gpu::TextureFormat,
+    sample_count: u32,
+    allocation: Option<(gpu::Texture, gpu::TextureView, Size<Physical, u32>)>,
+}
+
+impl DepthStencilTarget {
+    /// Creates an unallocated target. `format` must have a depth or stencil
+    /// aspect.
+    pub fn new(
+        device: gpu::Device,
+        format: gpu::TextureFormat,
+        sample_count: u32,
+    ) -> Result<Self, TargetError> {
+        if !format.has_depth() && !format.has_stencil() {
+            return Err(TargetError::new(format!(
+                "{format:?} is not a depth or stencil format"
+            )));
+        }
+        Ok(Self {
+            device,
+            format,
+            sample_count: sample_count.max(1),
+            allocation: None,
+        })
+    }
+
+    /// Attachment format, for pipeline [`gpu::DepthStencilState`].
+    pub fn format(&self) -> gpu::TextureFormat {
+        self.format
+    }
+
+    /// Sample count, which must match the color attachments.
+    pub fn sample_count(&self) -> u32 {
+        self.sample_count
+    }
+
+    /// The view, once allocated.
+    pub fn view(&self) -> Option<&gpu::TextureView> {
+        self.allocation.as_ref().map(|(_, view, _)| view)
+    }
+
+    /// Returns a pass attachment covering `size`, allocating on demand.
+    ///
+    /// Aspects the format lacks are ignored. Reallocation discards contents,
+    /// so a pass that loads after a resize sees undefined values.
+    pub fn attachment(
+        &mut self,
+        size: Size<Physical, u32>,
+        depth: AttachmentAccess<f32>,
+        stencil: AttachmentAccess<u32>,
+    ) -> gpu::RenderPassDepthStencilAttachment {
+        let has_depth = self.format.has_depth();
+        let has_stencil = self.format.has_stencil();
+        let (_, view, _) = self.ensure(size);
+        gpu::RenderPassDepthStencilAttachment {
+            view: view.clone(),
+            depth_ops: depth.operations().filter(|_| has_depth),
+            stencil_ops: stencil.operations().filter(|_| has_stencil),
+        }
+    }
+
+    /// Pipeline state that tests depth with `compare` and optionally writes
+    /// it, ignoring stencil.
+    pub fn depth_state(
+        &self,
+        compare: gpu::CompareFunction,
+        write: bool,
+    ) -> gpu::DepthStencilState {
+        gpu::DepthStencilState {
+            format: self.format,
+            depth_write_enabled: write,
+            depth_compare: compare,
+            stencil: stencil_state(gpu::StencilFaceState::IGNORE, 0),
+            bias_constant: 0,
+            bias_slope_scale: 0.0,
+            bias_clamp: 0.0,
+        }
+    }
+
+    /// Pipeline state that increments the stencil under a clip shape, so
+    /// nested clips leave the nesting depth in the buffer.
+    pub fn stencil_push_state(&self) -> gpu::DepthStencilState {
+        self.stencil_state(gpu::StencilFaceState {
+            compare: gpu::CompareFunction::Equal,
+            fail_op: gpu::StencilOperation::Keep,
+            depth_fail_op: gpu::StencilOperation::Keep,
+            pass_op: gpu::StencilOperation::IncrementClamp,
+        })
+    }
+
+    /// Pipeline state that draws only where the stencil equals the render
+    /// pass's stencil reference, which callers set to the clip depth.
+    pub fn stencil_test_state(&self) -> gpu::DepthStencilState {
+        self.stencil_state(gpu::StencilFaceState {
+            compare: gpu::CompareFunction::Equal,
+            fail_op: gpu::StencilOperation::Keep,
+            depth_fail_op: gpu::StencilOperation::Keep,
+            pass_op: gpu::StencilOperation::Keep,
+        })
+    }
+
+    fn stencil_state(&self, face: gpu::StencilFaceState) -> gpu::DepthStencilState {
+        gpu::DepthStencilState {
+            format: self.format,
+            depth_write_enabled: false,
+            depth_compare: gpu::CompareFunction::Always,
+            stencil: stencil_state(face, u32::MAX),
+            bias_constant: 0,
+            bias_slope_scale: 0.0,
+            bias_clamp: 0.0,
+        }
+    }
+
+    fn ensure(
+        &mut self,
+        size: Size<Physical, u32>,
+    ) -> &(gpu::Texture, gpu::TextureView, Size<Physical, u32>) {
+        let size = Size::new(size.width.max(1), size.height.max(1));
+        if self
+            .allocation
+            .as_ref()
+            .is_none_or(|(_, _, allocation)| *allocation != size)
+        {
+            let texture = self.device.create_texture(gpu::TextureDescriptor {
+                label: Some("depth stencil target".into()),
+                size: gpu::Extent3d::d2(size.width, size.height),
+                mip_level_count: 1,
+                sample_count: self.sample_count,
+                dimension: gpu::TextureDimension::D2,
+                format: self.format,
+                usage: gpu::TextureUsages::RENDER_ATTACHMENT | gpu::TextureUsages::TEXTURE_BINDING,
+            });
+            let view = texture.create_view(Default::default());
+            self.allocation = Some((texture, view, size));
+        }
+        self.allocation.as_ref().expect("allocated above")
+    }
+}
+
+fn stencil_state(face: gpu::StencilFaceState, mask: u32) -> gpu::StencilState {
+    gpu::StencilState {
+        front: face,
+        back: face,
+        read_mask: mask,
+        write_mask: mask,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_only_access_has_no_operations() {
+        assert!(AttachmentAccess::<f32>::ReadOnly.operations().is_none());
+        let clear = AttachmentAccess::Clear(1.0).operations().unwrap();
+        assert_eq!(clear.load, gpu::LoadOpValue::Clear(1.0));
+        assert_eq!(clear.store, gpu::StoreOp::Store);
+        assert_eq!(
+            AttachmentAccess::<u32>::Load.operations().unwrap().load,
+            gpu::LoadOpValue::Load
+        );
+    }
+}
@@ -0,0 +1,749 @@
+//! Declarative per-frame pass scheduling.
+//!
+//! Passes declare which textures they read and write; the graph orders them
+//! by those dependencies, culls passes whose results are never observed, and
+//! allocates transient textures from a pool that persists across frames.
+//! Every write produces a new texture version, so a pass that reads version
+//! `n` always runs after the writer of `n` and before the writer of `n + 1`,
+//! regardless of the order in which passes were added.
+//!
+//! Backend resource transitions are tracked by the GPU layer itself; the graph
+//! only guarantees the recording order those transitions are derived from.
+
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use astrelis_core::geometry::{Physical, Size};
+use astrelis_gpu::{
+    self as gpu, CommandEncoder, Device, Texture, TextureDescriptor, TextureDimension,
+    TextureFormat, TextureUsages, TextureView,
+};
+
+static NEXT_POOL: AtomicU64 = AtomicU64::new(1);
+static NEXT_GRAPH: AtomicU64 = AtomicU64::new(1);
+
+/// One version of a texture tracked by a [`RenderGraph`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TextureHandle {
+    graph: u64,
+    index: u32,
+    version: u32,
+}
+
+/// Description of a graph-owned texture that only lives for one frame.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransientTexture {
+    /// Debug label.
+    pub label: String,
+    /// Texture dimensions.
+    pub size: Size<Physical, u32>,
+    /// Pixel format.
+    pub format: TextureFormat,
+    /// Rasterization sample count.
+    pub sample_count: u32,
+    /// Allowed usages.
+    pub usage: TextureUsages,
+}
+
+impl TransientTexture {
+    fn pool_key(&self) -> PoolKey {
+        PoolKey {
+            size: (self.size.width, self.size.height),
+            format: self.format,
+            sample_count: self.sample_count,
+            usage: self.usage,
+        }
+    }
+}
+
+/// Texture views available to an executing pass.
+pub struct PassResources<'a> {
+    label: &'a str,
+    declared: &'a [TextureHandle],
+    views: &'a [Option<TextureView>],
+}
+
+impl PassResources<'_> {
+    /// Returns the view backing a texture the pass declared.
+    ///
+    /// Any version of a texture the pass reads or writes resolves to the
+    /// same view. Textures the pass did not declare are an error, since the
+    /// graph neither ordered the pass against their writers nor allocated
+    /// them for it.
+    pub fn view(&self, handle: TextureHandle) -> Result<&TextureView, GraphError> {
+        self.declared
+            .iter()
+            .any(|declared| declared.graph == handle.graph && declared.index == handle.index)
+            .then(|| self.views.get(handle.index as usize)?.as_ref())
+            .flatten()
+            .ok_or_else(|| {
+                GraphError::new(format!(
+                    "pass `{}` uses a texture it did not declare",
+                    self.label
+                ))
+            })
+    }
+}
+
+type PassCallback<'a> =
+    Box<dyn FnOnce(&mut CommandEncoder, &PassResources<'_>) -> Result<(), GraphError> + 'a>;
+
+struct PassNode<'a> {
+    label: String,
+    reads: Vec<TextureHandle>,
+    writes: Vec<TextureHandle>,
+    side_effect: bool,
+    execute: Option<PassCallback<'a>>,
+}
+
+enum TextureNode {
+    Imported(TextureView),
+    Transient(TransientTexture),
+}
+
+struct TextureState {
+    node: TextureNode,
+    version: u32,
+}
+
+/// Dependency declarations for one pass being added to a graph.
+pub struct PassBuilder<'g, 'a> {
+    graph: &'g mut RenderGraph<'a>,
+    pass: usize,
+}
+
+impl<'a> PassBuilder<'_, 'a> {
+    /// Declares that the pass samples or loads a texture version.
+    pub fn read(&mut self, handle: TextureHandle) -> &mut Self {
+        if self.graph.accepts(handle, self.pass) {
+            self.graph.passes[self.pass].reads.push(handle);
+        }
+        self
+    }
+
+    /// Declares that the pass writes a texture, returning the new version.
+    ///
+    /// Writing a version other than the latest is rejected when the graph
+    /// executes, since it would silently discard another pass's output.
+    pub fn write(&mut self, handle: TextureHandle) -> TextureHandle {
+        if !self.graph.accepts(handle, self.pass) {
+            return handle;
+        }
+        let texture = &mut self.graph.textures[handle.index as usize];
+        let written = if handle.version == texture.version {
+            texture.version += 1;
+            TextureHandle {
+                version: texture.version,
+                ..handle
+            }
+        } else {
+            let label = &self.graph.passes[self.pass].label;
+            self.graph
+                .declaration_error
+                .get_or_insert_with(|| format!("pass `{label}` writes a stale texture version"));
+            handle
+        };
+        self.graph.passes[self.pass].writes.push(written);
+        written
+    }
+
+    /// Keeps the pass even if none of its writes are observed.
+    ///
+    /// Use this for passes with effects outside the graph, such as query
+    /// resolves or buffer copies read back by the application.
+    pub fn side_effect(&mut self) -> &mut Self {
+        self.graph.passes[self.pass].side_effect = true;
+        self
+    }
+
+    /// Supplies the recording callback and finishes the pass declaration.
+    pub fn execute(
+        &mut self,
+        callback: impl FnOnce(&mut CommandEncoder, &PassResources<'_>) -> Result<(), GraphError> + 'a,
+    ) {
+        self.graph.passes[self.pass].execute = Some(Box::new(callback));
+    }
+}
+
+/// Statistics from executing one [`RenderGraph`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GraphStats {
+    /// Passes recorded into the encoder.
+    pub passes: u32,
+    /// Passes dropped because no output was observed.
+    pub culled_passes: u32,
+    /// Transient textures newly created this frame.
+    pub textures_created: u32,
+    /// Transient textures served from the pool or aliased within the frame.
+    pub textures_reused: u32,
+}
+
+/// A single frame's pass and texture declarations.
+///
+/// Graphs are cheap to build and are expected to be rebuilt every frame.
+/// Textures written by passes but never imported are allocated from the
+/// [`TransientPool`] supplied to [`RenderGraph::execute`].
+pub struct RenderGraph<'a> {
+    id: u64,
+    passes: Vec<PassNode<'a>>,
+    textures: Vec<TextureState>,
+    /// First invalid declaration, reported when the graph executes.
+    declaration_error: Option<String>,
+}
+
+impl Default for RenderGraph<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> RenderGraph<'a> {
+    /// Creates an empty graph.
+    pub fn new() -> Self {
+        Self {
+            id: NEXT_GRAPH.fetch_add(1, Ordering::Relaxed),
+            passes: Vec::new(),
+            textures: Vec::new(),
+            declaration_error: None,
+        }
+    }
+
+    /// Registers an externally owned texture such as a surface frame.
+    ///
+    /// Writes to imported textures are the graph's observable outputs.
+    pub fn import_texture(&mut self, view: TextureView) -> TextureHandle {
+        self.push_texture(TextureNode::Imported(view))
+    }
+
+    /// Declares a graph-owned texture with frame lifetime.
+    ///
+    /// Its first version has undefined contents, so a pass must write it
+    /// before any pass reads it.
+    pub fn create_texture(&mut self, descriptor: TransientTexture) -> TextureHandle {
+        self.push_texture(TextureNode::Transient(descriptor))
+    }
+
+    /// Begins declaring a pass.
+    pub fn add_pass(&mut self, label: impl Into<String>) -> PassBuilder<'_, 'a> {
+        self.passes.push(PassNode {
+            label: label.into(),
+            reads: Vec::new(),
+            writes: Vec::new(),
+            side_effect: false,
+            execute: None,
+        });
+        PassBuilder {
+            pass: self.passes.len() - 1,
+            graph: self,
+        }
+    }
+
+    /// Schedules, allocates, and records every observed pass.
+    ///
+    /// Each pass is wrapped in a debug group named after its label. Pooled
+    /// textures are returned to `pool` even when a pass fails.
+    pub fn execute(
+        mut self,
+        pool: &mut TransientPool,
+        encoder: &mut CommandEncoder,
+    ) -> Result<GraphStats, GraphError> {
+        let order = self.schedule()?;
+        let mut stats = GraphStats {
+            culled_passes: (self.passes.len() - order.len()) as u32,
+            ..Default::default()
+        };
+        pool.begin_frame();
+        let mut slots = HashMap::new();
+        let result = self.record(&order, pool, encoder, &mut slots, &mut stats);
+        for slot in slots.into_values() {
+            pool.free_slot(slot);
+        }
+        pool.end_frame();
+        result.map(|()| stats)
+    }
+
+    /// Records `order`, leaving slots still claimed in `slots` so the caller
+    /// can return them on every exit path.
+    fn record(
+        &mut self,
+        order: &[usize],
+        pool: &mut TransientPool,
+        encoder: &mut CommandEncoder,
+        slots: &mut HashMap<usize, usize>,
+        stats: &mut GraphStats,
+    ) -> Result<(), GraphError> {
+        let last_use = self.last_use(order);
+        let mut views: Vec<Option<TextureView>> = self
+            .textures
+            .iter()
+            .map(|texture| match &texture.node {
+                TextureNode::Imported(view) => Some(view.clone()),
+                TextureNode::Transient(_) => None,
+            })
+            .collect();
+        for (step, &pass) in order.iter().enumerate() {
+            let node = &mut self.passes[pass];
+            for handle in node.reads.iter().chain(&node.writes) {
+                let index = handle.index as usize;
+                if views[index].is_some() {
+                    continue;
+                }
+                let TextureNode::Transient(descriptor) = &self.textures[index].node else {
+                    continue;
+                };
+                let (slot, created) = pool.claim_slot(descriptor)?;
+                if created {
+                    stats.textures_created += 1;
+                } else {
+                    stats.textures_reused += 1;
+                }
+                views[index] = Some(pool.slot_view(slot));
+                slots.insert(index, slot);
+            }
+            let callback = node
+                .execute
+                .take()
+                .ok_or_else(|| GraphError::new(format!("pass `{}` has no callback", node.label)))?;
+            encoder.push_debug_group(&node.label);
+            let declared = [node.reads.as_slice(), node.writes.as_slice()].concat();
+            let resources = PassResources {
+                label: &node.label,
+                declared: &declared,
+                views: &views,
+            };
+            let result = callback(encoder, &resources);
+            encoder.pop_debug_group();
+            result?;
+            stats.passes += 1;
+            // Release transients whose final use was this pass so later
+            // transients with an identical description can alias them.
+            for (&index, &last) in &last_use {
+                if last == step
+                    && let Some(slot) = slots.remove(&index)
+                {
+                    pool.free_slot(slot);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn push_texture(&mut self, node: TextureNode) -> TextureHandle {
+        self.textures.push(TextureState { node, version: 0 });
+        TextureHandle {
+            graph: self.id,
+            index: self.textures.len() as u32 - 1,
+            version: 0,
+        }
+    }
+
+    /// Returns whether `handle` was issued by this graph, recording an
+    /// error against `pass` otherwise.
+    fn accepts(&mut self, handle: TextureHandle, pass: usize) -> bool {
+        let valid = handle.graph == self.id
+            && self
+                .textures
+                .get(handle.index as usize)
+                .is_some_and(|texture| handle.version <= texture.version);
+        if !valid {
+            let label = &self.passes[pass].label;
+            self.declaration_error.get_or_insert_with(|| {
+                format!("pass `{label}` uses a texture from another render graph")
+            });
+        }
+        valid
+    }
+
+    /// Returns observed passes in a dependency-respecting order, preferring
+    /// declaration order between independent passes.
+    fn schedule(&self) -> Result<Vec<usize>, GraphError> {
+        if let Some(error) = &self.declaration_error {
+            return Err(GraphError::new(error.clone()));
+        }
+        let mut writer = HashMap::new();
+        for (pass, node) in self.passes.iter().enumerate() {
+            for handle in &node.writes {
+                writer.insert(*handle, pass);
+            }
+        }
+        let mut readers: HashMap<TextureHandle, Vec<usize>> = HashMap::new();
+        let mut edges = vec![Vec::new(); self.passes.len()];
+        for (pass, node) in self.passes.iter().enumerate() {
+            for handle in &node.reads {
+                if let Some(&source) = writer.get(handle) {
+                    edges[pass].push(source);
+                } else if handle.version != 0
+                    || matches!(
+                        self.textures[handle.index as usize].node,
+                        TextureNode::Transient(_)
+                    )
+                {
+                    return Err(GraphError::new(format!(
+                        "pass `{}` reads a texture version no pass writes",
+                        node.label
+                    )));
+                }
+                readers.entry(*handle).or_default().push(pass);
+            }
+        }
+        for (pass, node) in self.passes.iter().enumerate() {
+            for handle in &node.writes {
+                let previous = TextureHandle {
+                    version: handle.version - 1,
+                    ..*handle
+                };
+                if let Some(&source) = writer.get(&previous) {
+                    edges[pass].push(source);
+                }
+                for &reader in readers.get(&previous).into_iter().flatten() {
+                    if reader != pass {
+                        edges[pass].push(reader);
+                    }
+                }
+            }
+        }
+
+        let mut live = vec![false; self.passes.len()];
+        let mut stack: Vec<usize> = self
+            .passes
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| {
+                node.side_effect
+                    || node.writes.iter().any(|handle| {
+                        matches!(
+                            self.textures[handle.index as usize].node,
+                            TextureNode::Imported(_)
+                        )
+                    })
+            })
+            .map(|(pass, _)| pass)
+            .collect();
+        while let Some(pass) = stack.pop() {
+            if !std::mem::replace(&mut live[pass], true) {
+                stack.extend(edges[pass].iter().copied());
+            }
+        }
+
+        let mut pending: Vec<usize> = edges
+            .iter()
+            .map(|sources| sources.iter().filter(|&&source| live[source]).count())
+            .collect();
+        let mut order = Vec::new();
+        let mut emitted = vec![false; self.passes.len()];
+        while order.len() < live.iter().filter(|&&value| value).count() {
+            let Some(next) = (0..self.passes.len())
+                .find(|&pass| live[pass] && !emitted[pass] && pending[pass] == 0)
+            else {
+                return Err(GraphError::new("render graph contains a dependency cycle"));
+            };
+            emitted[next] = true;
+            order.push(next);
+            for (pass, sources) in edges.iter().enumerate() {
+                pending[pass] -= sources.iter().filter(|&&source| source == next).count();
+            }
+        }
+        Ok(order)
+    }
+
+    fn last_use(&self, order: &[usize]) -> HashMap<usize, usize> {
+        let mut last = HashMap::new();
+        for (step, &pass) in order.iter().enumerate() {
+            let node = &self.passes[pass];
+            for handle in node.reads.iter().chain(&node.writes) {
+                if matches!(
+                    self.textures[handle.index as usize].node,
+                    TextureNode::Transient(_)
+                ) {
+                    last.insert(handle.index as usize, step);
+                }
+            }
+        }
+        last
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct PoolKey {
+    size: (u32, u32),
+    format: TextureFormat,
+    sample_count: u32,
+    usage: TextureUsages,
+}
+
+struct PooledTexture {
+    key: PoolKey,
+    id: u64,
+    _texture: Texture,
+    view: TextureView,
+    in_use: bool,
+    used: u64,
+}
+
+/// A pooled texture leased outside a render graph.
+///
+/// Return it with [`TransientPool::release`] on the pool that issued it
+/// once its last pass has been recorded, so later work in the same frame can
+/// alias the memory.
+#[derive(Debug)]
+pub struct TransientLease {
+    pool: u64,
+    id: u64,
+    view: TextureView,
+    created: bool,
+}
+
+impl TransientLease {
+    /// View of the leased texture.
+    pub fn view(&self) -> &TextureView {
+        &self.view
+    }
+
+    /// Returns whether the pool allocated a new texture for this lease.
+    pub fn created(&self) -> bool {
+        self.created
+    }
+}
+
+/// Transient textures retained between frames for reuse by render graphs
+/// and [`PostProcessStack::render_pooled`](crate::PostProcessStack::render_pooled).
+///
+/// Textures are keyed by size, format, sample count, and usage, so any two
+/// users with matching descriptions share memory once the first returns its
+/// texture. Textures unused for more than `max_idle_frames` frames are
+/// released.
+pub struct TransientPool {
+    id: u64,
+    device: Device,
+    textures: Vec<PooledTexture>,
+    frame: u64,
+    next_id: u64,
+    max_idle_frames: u64,
+}
+
+impl TransientPool {
+    /// Creates an empty pool for one device.
+    pub fn new(device: Device) -> Self {
+        Self {
+            id: NEXT_POOL.fetch_add(1, Ordering::Relaxed),
+            device,
+            textures: Vec::new(),
+            frame: 0,
+            next_id: 0,
+            max_idle_frames: 2,
+        }
+    }
+
+    /// Number of textures currently retained.
+    pub fn len(&self) -> usize {
+        self.textures.len()
+    }
+
+    /// Returns whether no textures are retained.
+    pub fn is_empty(&self) -> bool {
+        self.textures.is_empty()
+    }
+
+    /// Releases every retained texture.
+    pub fn clear(&mut self) {
+        self.textures.clear();
+    }
+
+    /// Leases a texture matching `descriptor`, reusing an idle one when
+    /// possible.
+    pub fn acquire(&mut self, descriptor: &TransientTexture) -> Result<TransientLease, GraphError> {
+        let (slot, created) = self.claim_slot(descriptor)?;
+        let texture = &self.textures[slot];
+        Ok(TransientLease {
+            pool: self.id,
+            id: texture.id,
+            view: texture.view.clone(),
+            created,
+        })
+    }
+
+    /// Returns a lease so the texture can be reused.
+    ///
+    /// Fails without effect if another pool issued the lease. Leases whose
+    /// texture was dropped by [`TransientPool::clear`] are accepted.
+    pub fn release(&mut self, lease: TransientLease) -> Result<(), GraphError> {
+        if lease.pool != self.id {
+            return Err(GraphError::new(
+                "transient lease was released into a pool that did not issue it",
+            ));
+        }
+        if let Some(texture) = self
+            .textures
+            .iter_mut()
+            .find(|texture| texture.id == lease.id)
+        {
+            texture.in_use = false;
+        }
+        Ok(())
+    }
+
+    /// Ages the pool by one frame and frees idle textures.
+    ///
+    /// Render graphs advance the pool on every execution; call this once per
+    /// frame when the pool is only used through [`TransientPool::acquire`].
+    pub fn advance_frame(&mut self) {
+        self.begin_frame();
+        self.end_frame();
+    }
+
+    fn begin_frame(&mut self) {
+        self.frame = self.frame.wrapping_add(1);
+    }
+
+    fn end_frame(&mut self) {
+        let frame = self.frame;
+        let max_idle = self.max_idle_frames;
+        self.textures
+            .retain(|texture| texture.in_use || frame.wrapping_sub(texture.used) <= max_idle);
+    }
+
+    /// Marks a matching texture in use, creating one if none is idle, and
+    /// returns its slot and whether it was created.
+    fn claim_slot(&mut self, descriptor: &TransientTexture) -> Result<(usize, bool), GraphError> {
+        if descriptor.size.width == 0 || descriptor.size.height == 0 {
+            return Err(GraphError::new(format!(
+                "transient texture `{}` must be non-empty",
+                descriptor.label
+            )));
+        }
+        let key = descriptor.pool_key();
+        if let Some(index) = self
+            .textures
+            .iter()
+            .position(|texture| !texture.in_use && texture.key == key)
+        {
+            let texture = &mut self.textures[index];
+            texture.in_use = true;
+            texture.used = self.frame;
+            return Ok((index, false));
+        }
+        let texture = self.device.create_texture(TextureDescriptor {
+            label: Some(descriptor.label.clone()),
+            size: gpu::Extent3d::d2(descriptor.size.width, descriptor.size.height),
+            mip_level_count: 1,
+            sample_count: descriptor.sample_count,
+            dimension: TextureDimension::D2,
+            format: descriptor.format,
+            usage: descriptor.usage,
+        });
+        let view = texture.create_view(Default::default());
+        self.next_id += 1;
+        self.textures.push(PooledTexture {
+            key,
+            id: self.next_id,
+            _texture: texture,
+            view,
+            in_use: true,
+            used: self.frame,
+        });
+        Ok((self.textures.len() - 1, true))
+    }
+
+    fn slot_view(&self, slot: usize) -> TextureView {
+        self.textures[slot].view.clone()
+    }
+
+    /// Returns a slot claimed by a graph within the current execution.
+    fn free_slot(&mut self, slot: usize) {
+        self.textures[slot].in_use = false;
+    }
+}
+
+/// Invalid render-graph declarations or a failed pass.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GraphError(String);
+
+impl GraphError {
+    /// Creates an error reported by a pass callback.
+    pub fn new(message: impl Into<String>) -> Self {
+        Self(message.into())
+    }
+}
+
+impl fmt::Display for GraphError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(formatter)
+    }
+}
+
+impl Error for GraphError {}
+
+impl From<gpu::GpuError> for GraphError {
+    fn from(value: gpu::GpuError) -> Self {
+        Self::new(value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transient(graph: &mut RenderGraph<'_>, label: &str) -> TextureHandle {
+        graph.create_texture(TransientTexture {
+            label: label.into(),
+            size: Size::new(4, 4),
+            format: TextureFormat::Rgba16Float,
+            sample_count: 1,
+            usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+        })
+    }
+
+    #[test]
+    fn orders_by_versions_and_culls_unobserved_passes() {
+        let mut graph = RenderGraph::new();
+        let hdr = transient(&mut graph, "hdr");
+        let unused = transient(&mut graph, "unused");
+        graph.add_pass("debug").write(unused);
+        let lit = graph.add_pass("lighting").write(hdr);
+        let bloomed = {
+            let mut pass = graph.add_pass("bloom");
+            pass.read(lit);
+            pass.write(lit)
+        };
+        graph.add_pass("tonemap").read(bloomed).side_effect();
+        graph.add_pass("readback").side_effect();
+        assert_eq!(graph.schedule().unwrap(), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn rejects_reads_of_unwritten_transients_and_stale_writes() {
+        let mut graph = RenderGraph::new();
+        let texture = transient(&mut graph, "texture");
+        graph.add_pass("reader").read(texture).side_effect();
+        assert!(graph.schedule().is_err());
+
+        let mut graph = RenderGraph::new();
+        let texture = transient(&mut graph, "texture");
+        graph.add_pass("first").write(texture);
+        graph.add_pass("second").side_effect().write(texture);
+        assert!(graph.schedule().is_err());
+    }
+
+    #[test]
+    fn rejects_handles_from_other_graphs() {
+        let mut other = RenderGraph::new();
+        transient(&mut other, "first");
+        let foreign = transient(&mut other, "second");
+
+        let mut graph = RenderGraph::new();
+        let local = transient(&mut graph, "local");
+        graph.add_pass("reader").read(foreign).side_effect();
+        let written = graph.add_pass("writer").write(foreign);
+        assert_eq!(written, foreign);
+        graph.add_pass("local").write(local);
+        let error = graph.schedule().unwrap_err();
+        assert!(
+            error.to_string().contains("from another render graph"),
+            "{error}"
+        );
+    }
+}
@@ -0,0 +1,280 @@
+//! Floating-point scene targets resolved to the display by a tonemap pass.
+
+use std::{
+    collections::{HashMap, hash_map::Entry},
+    hash::Hash,
+};
+
+use astrelis_core::{
+    color::Color,
+    geometry::{Physical, Size},
+};
+use astrelis_gpu as gpu;
+
+use crate::{PostEffect, PostError, PostProcessStack, RenderTarget, Tonemap};
+
+/// An `Rgba16Float` scene target that outlives individual frames.
+///
+/// Scene renderers draw into [`HdrTarget::render_target`] with unclamped
+/// linear colors; [`HdrTarget::resolve`] then runs a post-processing stack
+/// into the display surface. The texture is reallocated whenever the frame
+/// size changes, since post-processing samples the whole texture.
+pub struct HdrTarget {
+    device: gpu::Device,
+    allocation: Option<(gpu::Texture, gpu::TextureView, Size<Physical, u32>)>,
+}
+
+impl HdrTarget {
+    /// Color format of the intermediate texture.
+    pub const FORMAT: gpu::TextureFormat = gpu::TextureFormat::Rgba16Float;
+
+    /// Creates an unallocated target for one device.
+    pub fn new(device: gpu::Device) -> Self {
+        Self {
+            device,
+            allocation: None,
+        }
+    }
+
+    /// Returns a scene destination covering `size`, allocating on demand.
+    pub fn render_target(
+        &mut self,
+        size: Size<Physical, u32>,
+        scale_factor: f32,
+        clear_color: Color,
+    ) -> RenderTarget {
+        let (_, view, allocation_size) = self.ensure(size);
+        RenderTarget {
+            view: view.clone(),
+            allocation_size: *allocation_size,
+            render_size: size,
+            scale_factor,
+            clear_color,
+        }
+    }
+
+    /// The intermediate view, once allocated.
+    pub fn view(&self) -> Option<&gpu::TextureView> {
+        self.allocation.as_ref().map(|(_, view, _)| view)
+    }
+
+    /// Runs `stack` from the HDR texture into a display `target` of `size`.
+    ///
+    /// A stack without a [`PostEffect::Tonemap`] gets a default ACES tonemap
+    /// for this frame only, so highlights are compressed rather than clipped.
+    /// It runs after leading bloom passes and before vignette, FXAA, and
+    /// custom passes, which expect display-referred colors.
+    pub fn resolve(
+        &self,
+        encoder: &mut gpu::CommandEncoder,
+        stack: &mut PostProcessStack,
+        size: Size<Physical, u32>,
+        target: &gpu::TextureView,
+    ) -> Result<(), PostError> {
+        let Some((_, view, allocation)) = &self.allocation else {
+            return Err(PostError::new(
+                "render into the HDR target before resolving it",
+            ));
+        };
+        let size = allocated_size(size);
+        if size != *allocation {
+            return Err(PostError::new(
+                "resolve size differs from the HDR allocation",
+            ));
+        }
+        let inserted = default_tonemap_index(stack.effects());
+        if let Some(index) = inserted {
+            stack
+                .effects_mut()
+                .insert(index, PostEffect::Tonemap(Tonemap::default()));
+        }
+        let result = stack.render(encoder, view, size, target);
+        if let Some(index) = inserted {
+            stack.effects_mut().remove(index);
+        }
+        result
+    }
+
+    fn ensure(
+        &mut self,
+        size: Size<Physical, u32>,
+    ) -> &(gpu::Texture, gpu::TextureView, Size<Physical, u32>) {
+        let size = allocated_size(size);
+        if self
+            .allocation
+            .as_ref()
+            .is_none_or(|(_, _, allocation)| *allocation != size)
+        {
+            let texture = self.device.create_texture(gpu::TextureDescriptor {
+                label: Some("hdr scene target".into()),
+                size: gpu::Extent3d::d2(size.width, size.height),
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: gpu::TextureDimension::D2,
+                format: Self::FORMAT,
+                usage: gpu::TextureUsages::RENDER_ATTACHMENT | gpu::TextureUsages::TEXTURE_BINDING,
+            });
+            let view = texture.create_view(Default::default());
+            self.allocation = Some((texture, view, size));
+        }
+        self.allocation.as_ref().expect("allocated above")
+    }
+}
+
+/// An [`HdrTarget`] and [`PostProcessStack`] for each window.
+///
+/// Windows are keyed by the caller's identifier, such as the platform's
+/// `WindowId`. Each window has its own effect list and an HDR texture sized
+/// to its own surface, while every stack shares one device. Entries are
+/// created on first use; remove them when their window closes.
+pub struct WindowPostStacks<K> {
+    device: gpu::Device,
+    queue: gpu::Queue,
+    windows: HashMap<K, (HdrTarget, PostProcessStack)>,
+}
+
+impl<K: Eq + Hash> WindowPostStacks<K> {
+    /// Creates an empty set for one matching device/queue pair.
+    pub fn new(device: gpu::Device, queue: gpu::Queue) -> Result<Self, PostError> {
+        if device.id() != queue.device_id() {
+            return Err(PostError::new("device and queue do not match"));
+        }
+        Ok(Self {
+            device,
+            queue,
+            windows: HashMap::new(),
+        })
+    }
+
+    /// The window's stack, if it has one.
+    pub fn stack(&self, window: &K) -> Option<&PostProcessStack> {
+        self.windows.get(window).map(|(_, stack)| stack)
+    }
+
+    /// The window's stack for configuring its effects, created empty on
+    /// first use.
+    pub fn stack_mut(&mut self, window: K) -> Result<&mut PostProcessStack, PostError> {
+        Ok(&mut self.entry(window)?.1)
+    }
+
+    /// The window's scene destination covering `size`; see
+    /// [`HdrTarget::render_target`].
+    pub fn render_target(
+        &mut self,
+        window: K,
+        size: Size<Physical, u32>,
+        scale_factor: f32,
+        clear_color: Color,
+    ) -> Result<RenderTarget, PostError> {
+        Ok(self
+            .entry(window)?
+            .0
+            .render_target(size, scale_factor, clear_color))
+    }
+
+    /// Runs the window's stack from its HDR texture into `target`; see
+    /// [`HdrTarget::resolve`].
+    pub fn resolve(
+        &mut self,
+        window: &K,
+        encoder: &mut gpu::CommandEncoder,
+        size: Size<Physical, u32>,
+        target: &gpu::TextureView,
+    ) -> Result<(), PostError> {
+        let (hdr, stack) = self.windows.get_mut(window).ok_or_else(|| {
+            PostError::new("render into the window's HDR target before resolving it")
+        })?;
+        hdr.resolve(encoder, stack, size, target)
+    }
+
+    /// Drops a window's stack and HDR texture, returning whether it had them.
+    pub fn remove(&mut self, window: &K) -> bool {
+        self.windows.remove(window).is_some()
+    }
+
+    /// Number of windows with a stack.
+    pub fn len(&self) -> usize {
+        self.windows.len()
+    }
+
+    /// Returns whether no window has a stack.
+    pub fn is_empty(&self) -> bool {
+        self.windows.is_empty()
+    }
+
+    fn entry(&mut self, window: K) -> Result<&mut (HdrTarget, PostProcessStack), PostError> {
+        match self.windows.entry(window) {
+            Entry::Occupied(entry) => Ok(entry.into_mut()),
+            Entry::Vacant(entry) => {
+                let stack = PostProcessStack::new(self.device.clone(), self.queue.clone())?;
+                Ok(entry.insert((HdrTarget::new(self.device.clone()), stack)))
+            }
+        }
+    }
+}
+
+/// The texture size allocated for a requested frame size; zero-sized frames
+/// still get a 1×1 texture.
+fn allocated_size(size: Size<Physical, u32>) -> Size<Physical, u32> {
+    Size::new(size.width.max(1), size.height.max(1))
+}
+
+/// Where [`HdrTarget::resolve`] inserts its default tonemap, or `None` when
+/// `effects` already tonemap.
+fn default_tonemap_index(effects: &[PostEffect]) -> Option<usize> {
+    if effects
+        .iter()
+        .any(|effect| matches!(effect, PostEffect::Tonemap(_)))
+    {
+        return None;
+    }
+    Some(
+        effects
+            .iter()
+            .position(|effect| !matches!(effect, PostEffect::Bloom(_)))
+            .unwrap_or(effects.len()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Bloom, Fxaa, Vignette};
+
+    #[test]
+    fn default_tonemap_runs_before_display_referred_effects() {
+        assert_eq!(default_tonemap_index(&[]), Some(0));
+        assert_eq!(
+            default_tonemap_index(&[
+                PostEffect::Bloom(Bloom::default()),
+                PostEffect::Fxaa(Fxaa::default()),
+            ]),
+            Some(1)
+        );
+        assert_eq!(
+            default_tonemap_index(&[
+                PostEffect::Vignette(Vignette::default()),
+                PostEffect::Fxaa(Fxaa::default()),
+            ]),
+            Some(0)
+        );
+        assert_eq!(
+            default_tonemap_index(&[PostEffect::Bloom(Bloom::default())]),
+            Some(1)
+        );
+        assert_eq!(
+            default_tonemap_index(&[
+                PostEffect::Fxaa(Fxaa::default()),
+                PostEffect::Tonemap(Tonemap::default()),
+            ]),
+            None
+        );
+    }
+
+    #[test]
+    fn zero_sized_frames_resolve_against_the_clamped_allocation() {
+        assert_eq!(allocated_size(Size::new(0, 0)), Size::new(1, 1));
+        assert_eq!(allocated_size(Size::new(0, 7)), Size::new(1, 7));
+        assert_eq!(allocated_size(Size::new(5, 3)), Size::new(5, 3));
+    }
+}
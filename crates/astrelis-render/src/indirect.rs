@@ -0,0 +1,395 @@
+//! Growable indirect draw buffers with recycled command slots.
+
+use std::{error::Error, fmt, ops::Range};
+
+use astrelis_core::alloc::{Handle, HandleMap};
+use astrelis_gpu as gpu;
+use bytemuck::{Pod, Zeroable};
+
+/// Smallest allocation, in commands.
+const MIN_CAPACITY: u32 = 16;
+
+/// Error returned when an indirect buffer cannot be updated.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IndirectError(String);
+
+impl IndirectError {
+    fn new(message: impl Into<String>) -> Self {
+        Self(message.into())
+    }
+}
+
+impl fmt::Display for IndirectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Error for IndirectError {}
+
+impl From<gpu::GpuError> for IndirectError {
+    fn from(error: gpu::GpuError) -> Self {
+        Self::new(error.to_string())
+    }
+}
+
+/// Arguments of one indexed indirect draw, in GPU layout.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Pod, Zeroable)]
+pub struct DrawIndexedIndirect {
+    /// Indices per instance.
+    pub index_count: u32,
+    /// Instances to draw; zero makes the command a no-op.
+    pub instance_count: u32,
+    /// First index read from the bound index buffer.
+    pub first_index: u32,
+    /// Value added to each index.
+    pub base_vertex: i32,
+    /// First instance index.
+    pub first_instance: u32,
+}
+
+/// Stable handle to a command in an [`IndirectBuffer`]; it stays valid when
+/// compaction moves the command.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct IndirectId {
+    entry: u32,
+    generation: u32,
+}
+
+impl IndirectId {
+    fn handle(self) -> Handle<u32> {
+        Handle::from_raw_parts(self.entry, self.generation)
+    }
+}
+
+/// CPU mirror of the command array and the handle table.
+#[derive(Default)]
+struct CommandTable {
+    commands: Vec<DrawIndexedIndirect>,
+    /// Entry owning each command, `None` for holes.
+    owners: Vec<Option<Handle<u32>>>,
+    /// Command index of each live id.
+    entries: HandleMap<u32>,
+    /// Holes in `commands`, reused before the array grows.
+    holes: Vec<u32>,
+    dirty: Option<Range<u32>>,
+}
+
+impl CommandTable {
+    fn insert(&mut self, command: DrawIndexedIndirect) -> IndirectId {
+        let index = match self.holes.pop() {
+            Some(index) => {
+                self.commands[index as usize] = command;
+                index
+            }
+            None => {
+                self.commands.push(command);
+                self.owners.push(None);
+                (self.commands.len() - 1) as u32
+            }
+        };
+        let entry = self.entries.insert(index);
+        self.owners[index as usize] = Some(entry);
+        self.mark(index..index + 1);
+        IndirectId {
+            entry: entry.index(),
+            generation: entry.generation(),
+        }
+    }
+
+    fn index(&self, id: IndirectId) -> Option<u32> {
+        self.entries.get(id.handle()).copied()
+    }
+
+    fn set(&mut self, id: IndirectId, command: DrawIndexedIndirect) -> bool {
+        let Some(index) = self.index(id) else {
+            return false;
+        };
+        self.commands[index as usize] = command;
+        self.mark(index..index + 1);
+        true
+    }
+
+    fn remove(&mut self, id: IndirectId) -> bool {
+        let Some(index) = self.entries.remove(id.handle()) else {
+            return false;
+        };
+        self.commands[index as usize] = DrawIndexedIndirect::default();
+        self.owners[index as usize] = None;
+        self.holes.push(index);
+        self.mark(index..index + 1);
+        true
+    }
+
+    fn live(&self) -> u32 {
+        (self.commands.len() - self.holes.len()) as u32
+    }
+
+    fn fragmentation(&self) -> f32 {
+        if self.commands.is_empty() {
+            0.0
+        } else {
+            self.holes.len() as f32 / self.commands.len() as f32
+        }
+    }
+
+    /// Moves live commands to the front, preserving their order.
+    fn compact(&mut self) {
+        if self.holes.is_empty() {
+            return;
+        }
+        let mut write = 0;
+        for read in 0..self.commands.len() {
+            let Some(entry) = self.owners[read] else {
+                continue;
+            };
+            self.commands[write] = self.commands[read];
+            self.owners[write] = Some(entry);
+            self.entries[entry] = write as u32;
+            write += 1;
+        }
+        self.commands.truncate(write);
+        self.owners.truncate(write);
+        self.holes.clear();
+        self.mark(0..write as u32);
+    }
+
+    fn mark(&mut self, range: Range<u32>) {
+        if range.is_empty() {
+            return;
+        }
+        self.dirty = Some(match self.dirty.take() {
+            Some(dirty) => dirty.start.min(range.start)..dirty.end.max(range.end),
+            None => range,
+        });
+    }
+}
+
+/// Indexed indirect draw commands in one GPU buffer.
+///
+/// Removing a command zeroes it in place, leaving a no-op hole that the next
+/// insertion reuses. Commands are drawn with one multi-draw over every slot,
+/// so long-running scenes should call [`IndirectBuffer::compact`] or
+/// [`IndirectBuffer::compact_if_fragmented`] now and then to pack live
+/// commands and shorten that draw. [`IndirectBuffer::flush`] grows the
+/// buffer by doubling, and after compaction leaves a quarter or less of it
+/// in use, shrinks it to twice what the commands need, never below the
+/// capacity requested at creation. Handles stay valid across reallocation
+/// and compaction, but byte offsets do not.
+pub struct IndirectBuffer {
+    device: gpu::Device,
+    label: String,
+    buffer: gpu::Buffer,
+    capacity: u32,
+    min_capacity: u32,
+    table: CommandTable,
+}
+
+impl IndirectBuffer {
+    /// Allocates room for at least `capacity` commands.
+    pub fn new(device: &gpu::Device, label: &str, capacity: u32) -> Self {
+        let capacity = capacity.max(MIN_CAPACITY).next_power_of_two();
+        Self {
+            device: device.clone(),
+            label: label.into(),
+            buffer: allocate(device, label, capacity),
+            capacity,
+            min_capacity: capacity,
+            table: CommandTable::default(),
+        }
+    }
+
+    /// Adds a command, reusing a free slot when one exists.
+    pub fn insert(&mut self, command: DrawIndexedIndirect) -> IndirectId {
+        self.table.insert(command)
+    }
+
+    /// Replaces a live command. Returns whether `id` was live.
+    pub fn set(&mut self, id: IndirectId, command: DrawIndexedIndirect) -> bool {
+        self.table.set(id, command)
+    }
+
+    /// Removes a command, leaving a no-op hole. Returns whether `id` was live.
+    pub fn remove(&mut self, id: IndirectId) -> bool {
+        self.table.remove(id)
+    }
+
+    /// Current arguments of a live command.
+    pub fn get(&self, id: IndirectId) -> Option<DrawIndexedIndirect> {
+        self.table
+            .index(id)
+            .map(|index| self.table.commands[index as usize])
+    }
+
+    /// Byte offset of a live command, valid until the next compaction.
+    pub fn offset(&self, id: IndirectId) -> Option<u64> {
+        self.table
+            .index(id)
+            .map(|index| index as u64 * size_of::<DrawIndexedIndirect>() as u64)
+    }
+
+    /// Number of live commands.
+    pub fn len(&self) -> u32 {
+        self.table.live()
+    }
+
+    /// Returns whether no commands are live.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Commands drawn by [`IndirectBuffer::draw`], holes included.
+    pub fn command_count(&self) -> u32 {
+        self.table.commands.len() as u32
+    }
+
+    /// Commands the GPU buffer holds before it must grow.
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    /// Fraction of drawn commands that are holes.
+    pub fn fragmentation(&self) -> f32 {
+        self.table.fragmentation()
+    }
+
+    /// Packs live commands to the front. Offsets change; ids do not. The
+    /// next [`IndirectBuffer::flush`] shrinks the buffer when the packed
+    /// commands leave most of it unused.
+    pub fn compact(&mut self) {
+        self.table.compact();
+    }
+
+    /// Compacts when more than `max_fragmentation` of drawn commands are
+    /// holes. Returns whether it compacted.
+    pub fn compact_if_fragmented(&mut self, max_fragmentation: f32) -> bool {
+        if self.table.fragmentation() <= max_fragmentation {
+            return false;
+        }
+        self.table.compact();
+        true
+    }
+
+    /// Uploads changed commands, reallocating when the commands no longer
+    /// fit or use a quarter or less of the buffer. Returns whether the
+    /// buffer was replaced.
+    pub fn flush(&mut self, queue: &gpu::Queue) -> Result<bool, IndirectError> {
+        let count = self.command_count();
+        let capacity = resized_capacity(self.capacity, self.min_capacity, count);
+        let replaced = capacity != self.capacity;
+        if replaced {
+            self.capacity = capacity;
+            self.buffer = allocate(&self.device, &self.label, self.capacity);
+            self.table.dirty = (count > 0).then_some(0..count);
+        }
+        if let Some(dirty) = self.table.dirty.take() {
+            let end = dirty.end.min(count);
+            if dirty.start < end {
+                queue.write_buffer(
+                    &self.buffer,
+                    dirty.start as u64 * size_of::<DrawIndexedIndirect>() as u64,
+                    bytemuck::cast_slice(&self.table.commands[dirty.start as usize..end as usize]),
+                )?;
+            }
+        }
+        Ok(replaced)
+    }
+
+    /// The GPU buffer; replaced when [`IndirectBuffer::flush`] resizes it.
+    pub fn buffer(&self) -> &gpu::Buffer {
+        &self.buffer
+    }
+
+    /// Draws every command with the pass's bound pipeline, vertex buffers,
+    /// and index buffer. Call [`IndirectBuffer::flush`] first.
+    pub fn draw(&self, pass: &mut gpu::RenderPass<'_>) -> Result<(), IndirectError> {
+        let count = self.command_count();
+        if count > self.capacity {
+            return Err(IndirectError::new(
+                "indirect buffer must be flushed after growing",
+            ));
+        }
+        if count > 0 {
+            pass.multi_draw_indexed_indirect(&self.buffer, 0, count)?;
+        }
+        Ok(())
+    }
+}
+
+/// Capacity for `count` commands in a buffer of `capacity`: the next power
+/// of two past the end, or twice the need, but at least `min_capacity`,
+/// once a quarter or less is in use.
+fn resized_capacity(capacity: u32, min_capacity: u32, count: u32) -> u32 {
+    if count > capacity {
+        count.next_power_of_two()
+    } else if count <= capacity / 4 {
+        (count * 2).next_power_of_two().max(min_capacity)
+    } else {
+        capacity
+    }
+}
+
+fn allocate(device: &gpu::Device, label: &str, capacity: u32) -> gpu::Buffer {
+    device.create_buffer(gpu::BufferDescriptor {
+        label: Some(label.into()),
+        size: capacity as u64 * size_of::<DrawIndexedIndirect>() as u64,
+        usage: gpu::BufferUsages::INDIRECT | gpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(index_count: u32) -> DrawIndexedIndirect {
+        DrawIndexedIndirect {
+            index_count,
+            instance_count: 1,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn holes_are_recycled_and_compaction_keeps_ids() {
+        let mut table = CommandTable::default();
+        let ids = (1..=4)
+            .map(|n| table.insert(command(n)))
+            .collect::<Vec<_>>();
+        table.dirty = None;
+
+        assert!(table.remove(ids[1]));
+        assert!(!table.remove(ids[1]), "stale ids are rejected");
+        assert_eq!(table.commands[1], DrawIndexedIndirect::default());
+        let reused = table.insert(command(5));
+        assert_eq!(table.index(reused), Some(1), "holes fill before growth");
+        assert_ne!(reused, ids[1]);
+
+        table.remove(ids[0]);
+        table.remove(ids[2]);
+        assert_eq!(table.fragmentation(), 0.5);
+        table.dirty = None;
+        table.compact();
+        assert_eq!(table.commands, [command(5), command(4)]);
+        assert_eq!(table.index(reused), Some(0));
+        assert_eq!(table.index(ids[3]), Some(1));
+        assert_eq!(table.dirty, Some(0..2));
+        assert_eq!(table.fragmentation(), 0.0);
+    }
+
+    #[test]
+    fn capacity_doubles_past_the_end_and_shrinks_when_mostly_unused() {
+        assert_eq!(resized_capacity(16, 16, 17), 32);
+        assert_eq!(resized_capacity(64, 16, 40), 64);
+        assert_eq!(resized_capacity(64, 16, 17), 64, "over a quarter is kept");
+        assert_eq!(resized_capacity(256, 16, 60), 128);
+        assert_eq!(resized_capacity(256, 16, 0), 16);
+        assert_eq!(
+            resized_capacity(256, 64, 3),
+            64,
+            "never below the requested capacity"
+        );
+        assert_eq!(resized_capacity(16, 16, 0), 16);
+    }
+}
@@ -0,0 +1,236 @@
+//! Render targets with several addressable layers: arrays and cubemaps.
+
+use astrelis_core::{
+    color::Color,
+    geometry::{Physical, Size},
+    math::{Mat4, Vec3},
+};
+use astrelis_gpu as gpu;
+
+use crate::{RenderTarget, TargetError};
+
+/// One face of a cube target, in array-layer order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CubeFace {
+    /// +X.
+    PositiveX,
+    /// -X.
+    NegativeX,
+    /// +Y.
+    PositiveY,
+    /// -Y.
+    NegativeY,
+    /// +Z.
+    PositiveZ,
+    /// -Z.
+    NegativeZ,
+}
+
+impl CubeFace {
+    /// Every face, indexed by array layer.
+    pub const ALL: [Self; 6] = [
+        Self::PositiveX,
+        Self::NegativeX,
+        Self::PositiveY,
+        Self::NegativeY,
+        Self::PositiveZ,
+        Self::NegativeZ,
+    ];
+
+    /// Array layer holding this face.
+    pub fn layer(self) -> u32 {
+        self as u32
+    }
+
+    /// View matrix looking out of this face from `position`, following the
+    /// cubemap sampling convention so rendered faces sample back seamlessly.
+    /// Pair it with a 90° square perspective projection.
+    pub fn view(self, position: Vec3) -> Mat4 {
+        let (forward, up) = match self {
+            Self::PositiveX => (Vec3::X, Vec3::NEG_Y),
+            Self::NegativeX => (Vec3::NEG_X, Vec3::NEG_Y),
+            Self::PositiveY => (Vec3::Y, Vec3::Z),
+            Self::NegativeY => (Vec3::NEG_Y, Vec3::NEG_Z),
+            Self::PositiveZ => (Vec3::Z, Vec3::NEG_Y),
+            Self::NegativeZ => (Vec3::NEG_Z, Vec3::NEG_Y),
+        };
+        Mat4::look_to_rh(position, forward, up)
+    }
+}
+
+/// Shape of a [`LayeredTarget`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LayerKind {
+    /// Independent 2D layers sampled as a `texture_2d_array`, such as shadow
+    /// cascades or split-screen views.
+    Array(u32),
+    /// Six square faces sampled as a `texture_cube`, such as a reflection
+    /// probe or omnidirectional shadow map.
+    Cube,
+}
+
+/// A color or depth texture with one render view per layer.
+///
+/// Each layer is rendered as an ordinary 2D target through
+/// [`LayeredTarget::layer_view`] or [`LayeredTarget::render_target`], then the
+/// whole texture is sampled through [`LayeredTarget::sampling_view`].
+pub struct LayeredTarget {
+    texture: gpu::Texture,
+    kind: LayerKind,
+    format: gpu::TextureFormat,
+    size: Size<Physical, u32>,
+    layers: Vec<gpu::TextureView>,
+    sampling: gpu::TextureView,
+}
+
+impl LayeredTarget {
+    /// Allocates a target. Cube faces must be square.
+    pub fn new(
+        device: &gpu::Device,
+        kind: LayerKind,
+        size: Size<Physical, u32>,
+        format: gpu::TextureFormat,
+    ) -> Result<Self, TargetError> {
+        let count = match kind {
+            LayerKind::Array(0) => {
+                return Err(TargetError::new("layered targets need at least one layer"));
+            }
+            LayerKind::Array(count) => count,
+            LayerKind::Cube if size.width != size.height => {
+                return Err(TargetError::new("cube target faces must be square"));
+            }
+            LayerKind::Cube => 6,
+        };
+        if size.width == 0 || size.height == 0 {
+            return Err(TargetError::new("layered target size must be non-zero"));
+        }
+        let texture = device.create_texture(gpu::TextureDescriptor {
+            label: Some("layered target".into()),
+            size: gpu::Extent3d {
+                width: size.width,
+                height: size.height,
+                depth_or_array_layers: count,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: gpu::TextureDimension::D2,
+            format,
+            usage: gpu::TextureUsages::RENDER_ATTACHMENT | gpu::TextureUsages::TEXTURE_BINDING,
+        });
+        let layers = (0..count)
+            .map(|layer| {
+                texture.create_view(gpu::TextureViewDescriptor {
+                    label: Some(format!("layered target layer {layer}")),
+                    dimension: Some(gpu::TextureViewDimension::D2),
+                    base_array_layer: layer,
+                    array_layer_count: Some(1),
+                    ..Default::default()
+                })
+            })
+            .collect();
+        let sampling = texture.create_view(gpu::TextureViewDescriptor {
+            label: Some("layered target".into()),
+            dimension: Some(match kind {
+                LayerKind::Array(_) => gpu::TextureViewDimension::D2Array,
+                LayerKind::Cube => gpu::TextureViewDimension::Cube,
+            }),
+            ..Default::default()
+        });
+        Ok(Self {
+            texture,
+            kind,
+            format,
+            size,
+            layers,
+            sampling,
+        })
+    }
+
+    /// The underlying texture.
+    pub fn texture(&self) -> &gpu::Texture {
+        &self.texture
+    }
+
+    /// Array or cube shape.
+    pub fn kind(&self) -> LayerKind {
+        self.kind
+    }
+
+    /// Texel format of every layer.
+    pub fn format(&self) -> gpu::TextureFormat {
+        self.format
+    }
+
+    /// Size of one layer.
+    pub fn size(&self) -> Size<Physical, u32> {
+        self.size
+    }
+
+    /// Number of layers, six for cubes.
+    pub fn layer_count(&self) -> u32 {
+        self.layers.len() as u32
+    }
+
+    /// Single-layer 2D view for use as a pass attachment.
+    pub fn layer_view(&self, layer: u32) -> Option<&gpu::TextureView> {
+        self.layers.get(layer as usize)
+    }
+
+    /// Single-face view of a cube target.
+    pub fn face_view(&self, face: CubeFace) -> Option<&gpu::TextureView> {
+        match self.kind {
+            LayerKind::Cube => self.layer_view(face.layer()),
+            LayerKind::Array(_) => None,
+        }
+    }
+
+    /// View over every layer, as a 2D array or cube for sampling.
+    pub fn sampling_view(&self) -> &gpu::TextureView {
+        &self.sampling
+    }
+
+    /// Scene destination covering one whole layer.
+    pub fn render_target(
+        &self,
+        layer: u32,
+        scale_factor: f32,
+        clear_color: Color,
+    ) -> Option<RenderTarget> {
+        self.layer_view(layer).map(|view| RenderTarget {
+            view: view.clone(),
+            allocation_size: self.size,
+            render_size: self.size,
+            scale_factor,
+            clear_color,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use astrelis_core::math::Vec4;
+
+    use super::*;
+
+    #[test]
+    fn cube_face_views_look_along_their_axis() {
+        let position = Vec3::new(1.0, 2.0, 3.0);
+        for face in CubeFace::ALL {
+            let view = face.view(position);
+            let axis = match face {
+                CubeFace::PositiveX => Vec3::X,
+                CubeFace::NegativeX => Vec3::NEG_X,
+                CubeFace::PositiveY => Vec3::Y,
+                CubeFace::NegativeY => Vec3::NEG_Y,
+                CubeFace::PositiveZ => Vec3::Z,
+                CubeFace::NegativeZ => Vec3::NEG_Z,
+            };
+            let ahead = view * (position + axis).extend(1.0);
+            assert!(
+                (ahead - Vec4::new(0.0, 0.0, -1.0, 1.0)).length() < 1e-5,
+                "{face:?} looks down -Z in view space"
+            );
+        }
+        assert_eq!(CubeFace::NegativeZ.layer(), 5);
+    }
+}
@@ -2,6 +2,22 @@
 
 #![warn(missing_docs)]
 
+mod capability;
+mod depth;
+mod graph;
+mod hdr;
+mod indirect;
+mod layered;
+mod material;
+mod mesh;
+mod pipeline_cache;
+mod post;
+mod profiler;
+mod shaders;
+mod staging;
+mod texture;
+mod viewport;
+
 use std::{error::Error, fmt};
 
 use astrelis_core::{
@@ -10,6 +26,33 @@ use astrelis_core::{
 };
 use astrelis_gpu::{DeviceId, TextureDimension, TextureView};
 
+pub use capability::{
+    CapabilityReport, CapabilityTier, TierCapabilities, TierLimits, TierRejection, TierStatus,
+};
+pub use depth::{AttachmentAccess, DepthStencilTarget};
+pub use graph::{
+    GraphError, GraphStats, PassBuilder, PassResources, RenderGraph, TextureHandle, TransientLease,
+    TransientPool, TransientTexture,
+};
+pub use hdr::{HdrTarget, WindowPostStacks};
+pub use indirect::{DrawIndexedIndirect, IndirectBuffer, IndirectError, IndirectId};
+pub use layered::{CubeFace, LayerKind, LayeredTarget};
+pub use material::{
+    Material, MaterialError, MaterialKey, MaterialLayout, MaterialLayoutBuilder, MaterialParam,
+    MaterialParamKind,
+};
+pub use mesh::{Mesh, MeshBuffer, MeshError, StandardVertex, Vertex, cube, plane, quad, uv_sphere};
+pub use pipeline_cache::{PipelineCache, PipelineCacheError};
+pub use post::{
+    Bloom, CustomEffect, Fxaa, PostEffect, PostError, PostProcessStack, ToneMapping, Tonemap,
+    Vignette,
+};
+pub use profiler::{GpuProfiler, ProfilerError};
+pub use shaders::{ShaderError, ShaderId, ShaderPipelineId, ShaderRegistry, ShaderReload};
+pub use staging::{StagingBelt, StagingError};
+pub use texture::{ColorSpace, Texture2D, TextureError, TextureLoader, TextureOptions};
+pub use viewport::{SplitLayout, Viewport, ViewportManager};
+
 /// A rectangular scene destination supplied by a frame compositor.
 ///
 /// The color attachment may be multisampled and already contains earlier UI
@@ -61,9 +104,16 @@ impl CompositedRenderTarget {
 pub enum Antialiasing {
     /// Render directly into a single-sampled target.
     None,
+    /// Render into a two-sample attachment and resolve into the target.
+    Msaa2,
     /// Render into a four-sample attachment and resolve into the target.
     #[default]
     Msaa4,
+    /// Render into an eight-sample attachment and resolve into the target.
+    ///
+    /// Requires adapter-specific format support; see
+    /// [`astrelis_gpu::Device::supports_sample_count`].
+    Msaa8,
 }
 
 impl Antialiasing {
@@ -71,7 +121,9 @@ impl Antialiasing {
     pub const fn sample_count(self) -> u32 {
         match self {
             Self::None => 1,
+            Self::Msaa2 => 2,
             Self::Msaa4 => 4,
+            Self::Msaa8 => 8,
         }
     }
 }
@@ -170,6 +222,8 @@ mod tests {
     #[test]
     fn antialiasing_sample_counts_are_stable() {
         assert_eq!(Antialiasing::None.sample_count(), 1);
+        assert_eq!(Antialiasing::Msaa2.sample_count(), 2);
         assert_eq!(Antialiasing::Msaa4.sample_count(), 4);
+        assert_eq!(Antialiasing::Msaa8.sample_count(), 8);
     }
 }
@@ -0,0 +1,510 @@
+//! Materials whose bind group layouts are derived from declared parameters.
+
+use std::{
+    error::Error,
+    fmt,
+    hash::{Hash, Hasher},
+    num::NonZeroU64,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use astrelis_gpu as gpu;
+use bytemuck::Pod;
+
+static NEXT_LAYOUT: AtomicU64 = AtomicU64::new(1);
+/// Size granularity of structs in the WGSL uniform address space.
+const UNIFORM_ALIGNMENT: u64 = 16;
+
+/// Error returned by [`MaterialLayout`] and [`Material`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MaterialError(String);
+
+impl MaterialError {
+    fn new(message: impl Into<String>) -> Self {
+        Self(message.into())
+    }
+}
+
+impl fmt::Display for MaterialError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Error for MaterialError {}
+
+impl From<gpu::GpuError> for MaterialError {
+    fn from(error: gpu::GpuError) -> Self {
+        Self::new(error.to_string())
+    }
+}
+
+/// Category of one declared material parameter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MaterialParamKind {
+    /// Uniform buffer holding a plain-old-data struct of this many bytes.
+    Uniform(u64),
+    /// Sampled texture.
+    Texture {
+        /// Scalar category, `Float` for ordinary color textures.
+        sample_type: gpu::TextureSampleType,
+        /// Shader-side dimension.
+        view_dimension: gpu::TextureViewDimension,
+    },
+    /// Sampler.
+    Sampler(gpu::SamplerBindingType),
+}
+
+/// One named binding in a [`MaterialLayout`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MaterialParam {
+    /// Name used by [`Material`] setters.
+    pub name: String,
+    /// Binding index, assigned in declaration order.
+    pub binding: u32,
+    /// Resource category.
+    pub kind: MaterialParamKind,
+}
+
+/// Declares the parameters of a material type.
+#[derive(Clone, Debug)]
+pub struct MaterialLayoutBuilder {
+    label: String,
+    visibility: gpu::ShaderStages,
+    params: Vec<MaterialParam>,
+}
+
+impl MaterialLayoutBuilder {
+    /// Declares a uniform block holding `T`, which must match the WGSL struct
+    /// layout. WGSL pads uniform structs to a multiple of 16 bytes, so `T`
+    /// must include that padding; [`MaterialLayoutBuilder::build`] rejects
+    /// other sizes.
+    pub fn uniform<T: Pod>(self, name: impl Into<String>) -> Self {
+        self.param(
+            name,
+            MaterialParamKind::Uniform(std::mem::size_of::<T>() as u64),
+        )
+    }
+
+    /// Declares a filterable 2D color texture.
+    pub fn texture(self, name: impl Into<String>) -> Self {
+        self.param(
+            name,
+            MaterialParamKind::Texture {
+                sample_type: gpu::TextureSampleType::Float,
+                view_dimension: gpu::TextureViewDimension::D2,
+            },
+        )
+    }
+
+    /// Declares a filtering sampler.
+    pub fn sampler(self, name: impl Into<String>) -> Self {
+        self.param(
+            name,
+            MaterialParamKind::Sampler(gpu::SamplerBindingType::Filtering),
+        )
+    }
+
+    /// Declares a parameter of any kind.
+    pub fn param(mut self, name: impl Into<String>, kind: MaterialParamKind) -> Self {
+        let binding = self.params.len() as u32;
+        self.params.push(MaterialParam {
+            name: name.into(),
+            binding,
+            kind,
+        });
+        self
+    }
+
+    /// Creates the bind group layout.
+    pub fn build(self, device: &gpu::Device) -> Result<MaterialLayout, MaterialError> {
+        validate_params(&self.params)?;
+        let layout = device.create_bind_group_layout(gpu::BindGroupLayoutDescriptor {
+            label: Some(format!("{} material layout", self.label)),
+            entries: self
+                .params
+                .iter()
+                .map(|param| gpu::BindGroupLayoutEntry {
+                    binding: param.binding,
+                    visibility: self.visibility,
+                    ty: match param.kind {
+                        MaterialParamKind::Uniform(size) => gpu::BindingType::Buffer {
+                            ty: gpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: NonZeroU64::new(size),
+                        },
+                        MaterialParamKind::Texture {
+                            sample_type,
+                            view_dimension,
+                        } => gpu::BindingType::Texture {
+                            sample_type,
+                            view_dimension,
+                            multisampled: false,
+                        },
+                        MaterialParamKind::Sampler(ty) => gpu::BindingType::Sampler(ty),
+                    },
+                })
+                .collect(),
+        });
+        Ok(MaterialLayout {
+            inner: Arc::new(LayoutInner {
+                id: NEXT_LAYOUT.fetch_add(1, Ordering::Relaxed),
+                label: self.label,
+                params: self.params,
+                layout,
+            }),
+        })
+    }
+}
+
+fn validate_params(params: &[MaterialParam]) -> Result<(), MaterialError> {
+    for (index, param) in params.iter().enumerate() {
+        if params[..index].iter().any(|other| other.name == param.name) {
+            return Err(MaterialError::new(format!(
+                "material parameter `{}` is declared twice",
+                param.name
+            )));
+        }
+        match param.kind {
+            MaterialParamKind::Uniform(0) => {
+                return Err(MaterialError::new(format!(
+                    "uniform `{}` has no data",
+                    param.name
+                )));
+            }
+            MaterialParamKind::Uniform(size) if size % UNIFORM_ALIGNMENT != 0 => {
+                return Err(MaterialError::new(format!(
+                    "uniform `{}` is {size} bytes; WGSL uniform structs occupy a multiple of \
+                     {UNIFORM_ALIGNMENT} bytes, so pad it to {}",
+                    param.name,
+                    size.next_multiple_of(UNIFORM_ALIGNMENT)
+                )));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Whether textures of `format` bind where `sample_type` is declared. Any
+/// float or depth format also binds as unfilterable float.
+fn samples_as(format: gpu::TextureFormat, sample_type: gpu::TextureSampleType) -> bool {
+    use gpu::TextureSampleType as Sample;
+
+    let native = format.sample_type();
+    native == sample_type
+        || (sample_type == Sample::UnfilterableFloat
+            && matches!(native, Sample::Float | Sample::Depth))
+}
+
+struct LayoutInner {
+    id: u64,
+    label: String,
+    params: Vec<MaterialParam>,
+    layout: gpu::BindGroupLayout,
+}
+
+/// A material type: declared parameters and their bind group layout.
+///
+/// Pipelines using the material put [`MaterialLayout::bind_group_layout`] in
+/// their pipeline layout; each [`Material`] of this type then supplies
+/// matching resources.
+#[derive(Clone)]
+pub struct MaterialLayout {
+    inner: Arc<LayoutInner>,
+}
+
+impl MaterialLayout {
+    /// Starts declaring parameters visible to vertex and fragment stages.
+    pub fn builder(label: impl Into<String>) -> MaterialLayoutBuilder {
+        MaterialLayoutBuilder {
+            label: label.into(),
+            visibility: gpu::ShaderStages::VERTEX | gpu::ShaderStages::FRAGMENT,
+            params: Vec::new(),
+        }
+    }
+
+    /// Declared parameters in binding order.
+    pub fn params(&self) -> &[MaterialParam] {
+        &self.inner.params
+    }
+
+    /// Generated bind group layout.
+    pub fn bind_group_layout(&self) -> &gpu::BindGroupLayout {
+        &self.inner.layout
+    }
+
+    fn index(&self, name: &str) -> Result<usize, MaterialError> {
+        self.inner
+            .params
+            .iter()
+            .position(|param| param.name == name)
+            .ok_or_else(|| {
+                MaterialError::new(format!(
+                    "{} materials have no parameter `{name}`",
+                    self.inner.label
+                ))
+            })
+    }
+}
+
+impl fmt::Debug for MaterialLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MaterialLayout")
+            .field("label", &self.inner.label)
+            .field("params", &self.inner.params)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Value {
+    Unset,
+    Uniform(Vec<u8>),
+    Texture(gpu::TextureView),
+    Sampler(gpu::Sampler),
+}
+
+/// Identifies materials that can share one instanced draw.
+///
+/// Keys are equal when materials have the same layout and bind the same
+/// textures and samplers; uniform values are excluded, so instanced renderers
+/// can move them into per-instance data.
+#[derive(Clone, Debug)]
+pub struct MaterialKey {
+    layout: u64,
+    resources: Vec<Value>,
+}
+
+impl PartialEq for MaterialKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.layout == other.layout
+            && self
+                .resources
+                .iter()
+                .zip(&other.resources)
+                .all(|pair| match pair {
+                    (Value::Texture(a), Value::Texture(b)) => a.same_resource(b),
+                    (Value::Sampler(a), Value::Sampler(b)) => a.same_resource(b),
+                    (Value::Unset, Value::Unset) => true,
+                    _ => false,
+                })
+    }
+}
+
+impl Eq for MaterialKey {}
+
+impl Hash for MaterialKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.layout.hash(state);
+        for resource in &self.resources {
+            match resource {
+                Value::Unset | Value::Uniform(_) => 0u8.hash(state),
+                Value::Texture(view) => {
+                    1u8.hash(state);
+                    view.resource_key().hash(state);
+                }
+                Value::Sampler(sampler) => {
+                    2u8.hash(state);
+                    sampler.resource_key().hash(state);
+                }
+            }
+        }
+    }
+}
+
+/// Parameter values for one material of a [`MaterialLayout`].
+///
+/// The bind group and uniform buffers are created on first use and rebuilt
+/// only when a texture or sampler changes; uniform changes are queue writes.
+#[derive(Debug)]
+pub struct Material {
+    layout: MaterialLayout,
+    values: Vec<Value>,
+    buffers: Vec<Option<gpu::Buffer>>,
+    bind_group: Option<gpu::BindGroup>,
+    dirty_uniforms: Vec<bool>,
+}
+
+impl Material {
+    /// Creates a material with every parameter unset.
+    pub fn new(layout: &MaterialLayout) -> Self {
+        let count = layout.params().len();
+        Self {
+            layout: layout.clone(),
+            values: vec![Value::Unset; count],
+            buffers: vec![None; count],
+            bind_group: None,
+            dirty_uniforms: vec![false; count],
+        }
+    }
+
+    /// The material's type.
+    pub fn layout(&self) -> &MaterialLayout {
+        &self.layout
+    }
+
+    /// Sets a uniform parameter.
+    pub fn set_uniform<T: Pod>(&mut self, name: &str, value: &T) -> Result<(), MaterialError> {
+        let index = self.layout.index(name)?;
+        let MaterialParamKind::Uniform(size) = self.layout.params()[index].kind else {
+            return Err(MaterialError::new(format!("`{name}` is not a uniform")));
+        };
+        let bytes = bytemuck::bytes_of(value);
+        if bytes.len() as u64 != size {
+            return Err(MaterialError::new(format!(
+                "`{name}` holds {size} bytes, not {}",
+                bytes.len()
+            )));
+        }
+        self.values[index] = Value::Uniform(bytes.to_vec());
+        self.dirty_uniforms[index] = true;
+        Ok(())
+    }
+
+    /// Sets a texture parameter. The view must have the declared dimension
+    /// and a format that can be sampled as the declared sample type.
+    pub fn set_texture(&mut self, name: &str, view: gpu::TextureView) -> Result<(), MaterialError> {
+        let index = self.layout.index(name)?;
+        let MaterialParamKind::Texture {
+            sample_type,
+            view_dimension,
+        } = self.layout.params()[index].kind
+        else {
+            return Err(MaterialError::new(format!("`{name}` is not a texture")));
+        };
+        if view.view_dimension() != view_dimension {
+            return Err(MaterialError::new(format!(
+                "`{name}` is a {view_dimension:?} texture, not {:?}",
+                view.view_dimension()
+            )));
+        }
+        if !samples_as(view.format(), sample_type) {
+            return Err(MaterialError::new(format!(
+                "`{name}` samples {sample_type:?} texels, which {:?} does not provide",
+                view.format()
+            )));
+        }
+        self.values[index] = Value::Texture(view);
+        self.bind_group = None;
+        Ok(())
+    }
+
+    /// Sets a sampler parameter.
+    pub fn set_sampler(&mut self, name: &str, sampler: gpu::Sampler) -> Result<(), MaterialError> {
+        let index = self.layout.index(name)?;
+        if !matches!(
+            self.layout.params()[index].kind,
+            MaterialParamKind::Sampler(_)
+        ) {
+            return Err(MaterialError::new(format!("`{name}` is not a sampler")));
+        }
+        self.values[index] = Value::Sampler(sampler);
+        self.bind_group = None;
+        Ok(())
+    }
+
+    /// Instancing key; see [`MaterialKey`].
+    pub fn key(&self) -> MaterialKey {
+        MaterialKey {
+            layout: self.layout.inner.id,
+            resources: self
+                .values
+                .iter()
+                .map(|value| match value {
+                    Value::Uniform(_) => Value::Unset,
+                    other => other.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Uploads changed uniforms and returns a bind group for the current
+    /// values. Fails while any parameter is unset.
+    pub fn bind_group(
+        &mut self,
+        device: &gpu::Device,
+        queue: &gpu::Queue,
+    ) -> Result<&gpu::BindGroup, MaterialError> {
+        if let Some(index) = self
+            .values
+            .iter()
+            .position(|value| matches!(value, Value::Unset))
+        {
+            return Err(MaterialError::new(format!(
+                "material parameter `{}` is unset",
+                self.layout.params()[index].name
+            )));
+        }
+        for (index, value) in self.values.iter().enumerate() {
+            let Value::Uniform(bytes) = value else {
+                continue;
+            };
+            match &self.buffers[index] {
+                Some(buffer) if self.dirty_uniforms[index] => {
+                    queue.write_buffer(buffer, 0, bytes)?
+                }
+                Some(_) => {}
+                None => {
+                    self.buffers[index] = Some(device.create_buffer_init(
+                        queue,
+                        Some(self.layout.params()[index].name.clone()),
+                        bytes,
+                        gpu::BufferUsages::UNIFORM | gpu::BufferUsages::COPY_DST,
+                    )?);
+                    self.bind_group = None;
+                }
+            }
+            self.dirty_uniforms[index] = false;
+        }
+        if self.bind_group.is_none() {
+            let entries = self
+                .values
+                .iter()
+                .zip(self.layout.params())
+                .zip(&self.buffers)
+                .map(|((value, param), buffer)| gpu::BindGroupEntry {
+                    binding: param.binding,
+                    resource: match value {
+                        Value::Uniform(_) => gpu::BindingResource::Buffer(gpu::BufferBinding {
+                            buffer: buffer.clone().expect("uniform buffers exist"),
+                            offset: 0,
+                            size: None,
+                        }),
+                        Value::Texture(view) => gpu::BindingResource::TextureView(view.clone()),
+                        Value::Sampler(sampler) => gpu::BindingResource::Sampler(sampler.clone()),
+                        Value::Unset => unreachable!("unset values were rejected"),
+                    },
+                })
+                .collect();
+            self.bind_group = Some(device.create_bind_group(gpu::BindGroupDescriptor {
+                label: Some(format!("{} material", self.layout.inner.label)),
+                layout: self.layout.inner.layout.clone(),
+                entries,
+            })?);
+        }
+        Ok(self.bind_group.as_ref().expect("bind group was created"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uniform_sizes_follow_wgsl_padding() {
+        let uniform = |name: &str, size| MaterialParam {
+            name: name.into(),
+            binding: 0,
+            kind: MaterialParamKind::Uniform(size),
+        };
+        assert!(validate_params(&[uniform("color", 16), uniform("light", 64)]).is_ok());
+        let error = validate_params(&[uniform("tint", 12)]).unwrap_err();
+        assert!(error.to_string().contains("pad it to 16"), "{error}");
+        assert!(validate_params(&[uniform("empty", 0)]).is_err());
+        assert!(validate_params(&[uniform("color", 16), uniform("color", 16)]).is_err());
+    }
+}
@@ -0,0 +1,440 @@
+//! Indexed meshes with interleaved vertices, sub-mesh ranges, and GPU upload.
+
+use std::{error::Error, fmt, ops::Range};
+
+use astrelis_core::math::{Vec2, Vec3};
+use astrelis_gpu as gpu;
+use bytemuck::{Pod, Zeroable};
+
+/// Error returned when a mesh is malformed or cannot be uploaded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MeshError(String);
+
+impl MeshError {
+    fn new(message: impl Into<String>) -> Self {
+        Self(message.into())
+    }
+}
+
+impl fmt::Display for MeshError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Error for MeshError {}
+
+impl From<gpu::GpuError> for MeshError {
+    fn from(error: gpu::GpuError) -> Self {
+        Self::new(error.to_string())
+    }
+}
+
+/// An interleaved vertex type and its buffer layout.
+pub trait Vertex: Pod {
+    /// Layout of one vertex buffer holding `Self`, stepping per vertex.
+    fn layout() -> gpu::VertexBufferLayout;
+}
+
+/// Position, normal, and texture coordinates at shader locations 0, 1, and 2.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+pub struct StandardVertex {
+    /// Object-space position.
+    pub position: [f32; 3],
+    /// Object-space unit normal.
+    pub normal: [f32; 3],
+    /// Texture coordinates.
+    pub uv: [f32; 2],
+}
+
+impl Vertex for StandardVertex {
+    fn layout() -> gpu::VertexBufferLayout {
+        gpu::VertexBufferLayout {
+            array_stride: size_of::<Self>() as u64,
+            step_mode: gpu::VertexStepMode::Vertex,
+            attributes: vec![
+                gpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: gpu::VertexFormat::Float32x3,
+                },
+                gpu::VertexAttribute {
+                    offset: 12,
+                    shader_location: 1,
+                    format: gpu::VertexFormat::Float32x3,
+                },
+                gpu::VertexAttribute {
+                    offset: 24,
+                    shader_location: 2,
+                    format: gpu::VertexFormat::Float32x2,
+                },
+            ],
+        }
+    }
+}
+
+/// CPU-side indexed triangle list split into drawable index ranges.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mesh<V> {
+    /// Interleaved vertices.
+    pub vertices: Vec<V>,
+    /// Triangle-list indices.
+    pub indices: Vec<u32>,
+    /// Index ranges drawn separately, typically one per material.
+    pub submeshes: Vec<Range<u32>>,
+}
+
+impl<V: Vertex> Mesh<V> {
+    /// Creates a mesh with one sub-mesh covering every index.
+    pub fn new(vertices: Vec<V>, indices: Vec<u32>) -> Self {
+        let submeshes = std::iter::once(0..indices.len() as u32).collect();
+        Self {
+            vertices,
+            indices,
+            submeshes,
+        }
+    }
+
+    /// Checks that indices form whole triangles within range and that every
+    /// sub-mesh covers whole triangles within the index list.
+    pub fn validate(&self) -> Result<(), MeshError> {
+        if self.vertices.is_empty() || !self.indices.len().is_multiple_of(3) {
+            return Err(MeshError::new(
+                "mesh must contain vertices and complete indexed triangles",
+            ));
+        }
+        if self
+            .indices
+            .iter()
+            .any(|&index| index as usize >= self.vertices.len())
+        {
+            return Err(MeshError::new("mesh index is out of range"));
+        }
+        if self.submeshes.iter().any(|range| {
+            range.start > range.end
+                || range.end as usize > self.indices.len()
+                || !range.start.is_multiple_of(3)
+                || !range.end.is_multiple_of(3)
+        }) {
+            return Err(MeshError::new(
+                "sub-meshes must cover whole triangles within the index list",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Appends another mesh's vertices and indices as new sub-meshes.
+    pub fn append(&mut self, other: &Self) {
+        let base_vertex = self.vertices.len() as u32;
+        let base_index = self.indices.len() as u32;
+        self.vertices.extend_from_slice(&other.vertices);
+        self.indices
+            .extend(other.indices.iter().map(|index| index + base_vertex));
+        self.submeshes.extend(
+            other
+                .submeshes
+                .iter()
+                .map(|range| range.start + base_index..range.end + base_index),
+        );
+    }
+}
+
+/// A mesh uploaded to vertex and index buffers.
+///
+/// Indices are stored as 16-bit values whenever the vertex count allows,
+/// halving index bandwidth for typical meshes.
+#[derive(Clone, Debug)]
+pub struct MeshBuffer {
+    vertices: gpu::Buffer,
+    indices: gpu::Buffer,
+    index_format: gpu::IndexFormat,
+    index_bytes: u64,
+    vertex_count: u32,
+    submeshes: Vec<Range<u32>>,
+    layout: gpu::VertexBufferLayout,
+}
+
+impl MeshBuffer {
+    /// Validates and uploads a mesh.
+    pub fn upload<V: Vertex>(
+        device: &gpu::Device,
+        queue: &gpu::Queue,
+        label: &str,
+        mesh: &Mesh<V>,
+    ) -> Result<Self, MeshError> {
+        mesh.validate()?;
+        if mesh.indices.is_empty() {
+            return Err(MeshError::new("mesh has no indices to upload"));
+        }
+        let index_format = index_format(mesh.vertices.len());
+        let index_bytes = match index_format {
+            gpu::IndexFormat::Uint16 => {
+                let mut narrow = mesh
+                    .indices
+                    .iter()
+                    .map(|&index| index as u16)
+                    .collect::<Vec<_>>();
+                let unpadded = (narrow.len() * 2) as u64;
+                if !narrow.len().is_multiple_of(2) {
+                    narrow.push(0);
+                }
+                (bytemuck::cast_slice::<u16, u8>(&narrow).to_vec(), unpadded)
+            }
+            gpu::IndexFormat::Uint32 => {
+                let bytes = bytemuck::cast_slice::<u32, u8>(&mesh.indices).to_vec();
+                let len = bytes.len() as u64;
+                (bytes, len)
+            }
+        };
+        let vertices = device.create_buffer_init(
+            queue,
+            Some(format!("{label} vertices")),
+            bytemuck::cast_slice(&mesh.vertices),
+            gpu::BufferUsages::VERTEX,
+        )?;
+        let indices = device.create_buffer_init(
+            queue,
+            Some(format!("{label} indices")),
+            &index_bytes.0,
+            gpu::BufferUsages::INDEX,
+        )?;
+        Ok(Self {
+            vertices,
+            indices,
+            index_format,
+            index_bytes: index_bytes.1,
+            vertex_count: mesh.vertices.len() as u32,
+            submeshes: mesh.submeshes.clone(),
+            layout: V::layout(),
+        })
+    }
+
+    /// Vertex buffer layout for pipeline creation.
+    pub fn layout(&self) -> &gpu::VertexBufferLayout {
+        &self.layout
+    }
+
+    /// Index element width chosen at upload.
+    pub fn index_format(&self) -> gpu::IndexFormat {
+        self.index_format
+    }
+
+    /// Number of vertices.
+    pub fn vertex_count(&self) -> u32 {
+        self.vertex_count
+    }
+
+    /// Index ranges of each sub-mesh.
+    pub fn submeshes(&self) -> &[Range<u32>] {
+        &self.submeshes
+    }
+
+    /// Binds the vertex buffer to `slot` and the index buffer.
+    pub fn bind(&self, pass: &mut gpu::RenderPass, slot: u32) -> Result<(), MeshError> {
+        pass.set_vertex_buffer(slot, &self.vertices, 0..self.vertices.size())?;
+        pass.set_index_buffer(&self.indices, 0..self.index_bytes, self.index_format)?;
+        Ok(())
+    }
+
+    /// Draws one sub-mesh; the mesh must be bound.
+    pub fn draw_submesh(
+        &self,
+        pass: &mut gpu::RenderPass,
+        submesh: usize,
+        instances: Range<u32>,
+    ) -> Result<(), MeshError> {
+        let range = self
+            .submeshes
+            .get(submesh)
+            .ok_or_else(|| MeshError::new(format!("mesh has no sub-mesh {submesh}")))?;
+        pass.draw_indexed(range.clone(), 0, instances);
+        Ok(())
+    }
+
+    /// Binds the mesh to slot zero and draws every sub-mesh.
+    pub fn draw(&self, pass: &mut gpu::RenderPass, instances: Range<u32>) -> Result<(), MeshError> {
+        self.bind(pass, 0)?;
+        for range in &self.submeshes {
+            pass.draw_indexed(range.clone(), 0, instances.clone());
+        }
+        Ok(())
+    }
+}
+
+fn index_format(vertex_count: usize) -> gpu::IndexFormat {
+    if vertex_count <= usize::from(u16::MAX) + 1 {
+        gpu::IndexFormat::Uint16
+    } else {
+        gpu::IndexFormat::Uint32
+    }
+}
+
+fn vertex(position: Vec3, normal: Vec3, uv: Vec2) -> StandardVertex {
+    StandardVertex {
+        position: position.to_array(),
+        normal: normal.to_array(),
+        uv: uv.to_array(),
+    }
+}
+
+/// Creates a +Z-facing XY quad centered at the origin.
+pub fn quad(width: f32, height: f32) -> Mesh<StandardVertex> {
+    let (w, h) = (width * 0.5, height * 0.5);
+    let corners = [
+        (Vec3::new(-w, -h, 0.0), Vec2::new(0.0, 1.0)),
+        (Vec3::new(w, -h, 0.0), Vec2::new(1.0, 1.0)),
+        (Vec3::new(w, h, 0.0), Vec2::new(1.0, 0.0)),
+        (Vec3::new(-w, h, 0.0), Vec2::new(0.0, 0.0)),
+    ];
+    Mesh::new(
+        corners
+            .into_iter()
+            .map(|(position, uv)| vertex(position, Vec3::Z, uv))
+            .collect(),
+        vec![0, 1, 2, 0, 2, 3],
+    )
+}
+
+/// Creates a +Y-facing XZ plane centered at the origin.
+pub fn plane(width: f32, depth: f32) -> Mesh<StandardVertex> {
+    let positions = [
+        [-width * 0.5, 0.0, -depth * 0.5],
+        [-width * 0.5, 0.0, depth * 0.5],
+        [width * 0.5, 0.0, depth * 0.5],
+        [width * 0.5, 0.0, -depth * 0.5],
+    ];
+    let uvs = [[0.0, 0.0], [0.0, 1.0], [1.0, 1.0], [1.0, 0.0]];
+    Mesh::new(
+        positions
+            .into_iter()
+            .zip(uvs)
+            .map(|(position, uv)| vertex(position.into(), Vec3::Y, uv.into()))
+            .collect(),
+        vec![0, 1, 2, 0, 2, 3],
+    )
+}
+
+/// Creates a cube with independent face normals and UVs.
+pub fn cube(size: f32) -> Mesh<StandardVertex> {
+    let h = size * 0.5;
+    let faces = [
+        (
+            Vec3::X,
+            [
+                Vec3::new(h, -h, h),
+                Vec3::new(h, h, h),
+                Vec3::new(h, h, -h),
+                Vec3::new(h, -h, -h),
+            ],
+        ),
+        (
+            Vec3::NEG_X,
+            [
+                Vec3::new(-h, -h, -h),
+                Vec3::new(-h, h, -h),
+                Vec3::new(-h, h, h),
+                Vec3::new(-h, -h, h),
+            ],
+        ),
+        (
+            Vec3::Y,
+            [
+                Vec3::new(-h, h, h),
+                Vec3::new(-h, h, -h),
+                Vec3::new(h, h, -h),
+                Vec3::new(h, h, h),
+            ],
+        ),
+        (
+            Vec3::NEG_Y,
+            [
+                Vec3::new(-h, -h, -h),
+                Vec3::new(-h, -h, h),
+                Vec3::new(h, -h, h),
+                Vec3::new(h, -h, -h),
+            ],
+        ),
+        (
+            Vec3::Z,
+            [
+                Vec3::new(-h, -h, h),
+                Vec3::new(-h, h, h),
+                Vec3::new(h, h, h),
+                Vec3::new(h, -h, h),
+            ],
+        ),
+        (
+            Vec3::NEG_Z,
+            [
+                Vec3::new(h, -h, -h),
+                Vec3::new(h, h, -h),
+                Vec3::new(-h, h, -h),
+                Vec3::new(-h, -h, -h),
+            ],
+        ),
+    ];
+    let uvs = [[0.0, 1.0], [0.0, 0.0], [1.0, 0.0], [1.0, 1.0]];
+    let mut vertices = Vec::with_capacity(24);
+    let mut indices = Vec::with_capacity(36);
+    for (face, (normal, positions)) in faces.into_iter().enumerate() {
+        let base = (face * 4) as u32;
+        vertices.extend(
+            positions
+                .into_iter()
+                .zip(uvs)
+                .map(|(position, uv)| vertex(position, normal, uv.into())),
+        );
+        indices.extend_from_slice(&[base, base + 2, base + 1, base, base + 3, base + 2]);
+    }
+    Mesh::new(vertices, indices)
+}
+
+/// Creates a latitude-longitude sphere with smooth normals.
+pub fn uv_sphere(radius: f32, sectors: u32, stacks: u32) -> Mesh<StandardVertex> {
+    let sectors = sectors.max(3);
+    let stacks = stacks.max(2);
+    let mut vertices = Vec::new();
+    for stack in 0..=stacks {
+        let v = stack as f32 / stacks as f32;
+        let phi = v * std::f32::consts::PI;
+        for sector in 0..=sectors {
+            let u = sector as f32 / sectors as f32;
+            let theta = u * std::f32::consts::TAU;
+            let normal = Vec3::new(theta.cos() * phi.sin(), phi.cos(), theta.sin() * phi.sin());
+            vertices.push(vertex(normal * radius, normal, Vec2::new(u, v)));
+        }
+    }
+    let mut indices = Vec::new();
+    let row = sectors + 1;
+    for stack in 0..stacks {
+        for sector in 0..sectors {
+            let a = stack * row + sector;
+            let b = a + row;
+            indices.extend_from_slice(&[a, b + 1, b, a, a + 1, b + 1]);
+        }
+    }
+    Mesh::new(vertices, indices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn appended_meshes_become_submeshes() {
+        let mut mesh = quad(1.0, 1.0);
+        mesh.append(&cube(1.0));
+        mesh.validate().unwrap();
+        assert_eq!(mesh.submeshes, [0..6, 6..42]);
+        assert_eq!(mesh.indices[6], 4, "appended indices are rebased");
+        mesh.submeshes.push(40..44);
+        assert!(mesh.validate().is_err());
+    }
+
+    #[test]
+    fn small_meshes_use_sixteen_bit_indices() {
+        assert_eq!(index_format(65_536), gpu::IndexFormat::Uint16);
+        assert_eq!(index_format(65_537), gpu::IndexFormat::Uint32);
+    }
+}
@@ -0,0 +1,41 @@
+struct Params {
+    size: vec2<u32>,
+    row_words: u32,
+    srgb: u32,
+}
+
+@group(0) @binding(0) var source: texture_2d<f32>;
+@group(0) @binding(1) var<storage, read_write> destination: array<u32>;
+@group(0) @binding(2) var<uniform> params: Params;
+
+fn encode_srgb(linear: vec3<f32>) -> vec3<f32> {
+    let low = linear * 12.92;
+    let high = 1.055 * pow(linear, vec3<f32>(1.0 / 2.4)) - 0.055;
+    return select(high, low, linear <= vec3<f32>(0.0031308));
+}
+
+@compute @workgroup_size(8, 8)
+fn cs_main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x >= params.size.x || id.y >= params.size.y {
+        return;
+    }
+    let last = textureDimensions(source) - vec2<u32>(1u);
+    let base = id.xy * 2u;
+    // Average premultiplied colour so transparent texels, whose colour is
+    // arbitrary, do not bleed into their neighbours.
+    var sum = vec4<f32>(0.0);
+    for (var y = 0u; y < 2u; y++) {
+        for (var x = 0u; x < 2u; x++) {
+            let texel = textureLoad(source, min(base + vec2<u32>(x, y), last), 0);
+            sum += vec4<f32>(texel.rgb * texel.a, texel.a);
+        }
+    }
+    var color = vec4<f32>(0.0);
+    if sum.a > 0.0 {
+        color = vec4<f32>(sum.rgb / sum.a, sum.a * 0.25);
+    }
+    if params.srgb != 0u {
+        color = vec4<f32>(encode_srgb(color.rgb), color.a);
+    }
+    destination[id.y * params.row_words + id.x] = pack4x8unorm(color);
+}
@@ -0,0 +1,648 @@
+//! Shared render pipeline deduplication and background compilation.
+//!
+//! Renderers describe pipelines with ordinary descriptors; the cache keys them
+//! on shader module identity, pipeline layout identity, entry points, and
+//! fixed-function state, so two renderers asking for the same variant share
+//! one pipeline. [`PipelineCache::request`] hands compilation to worker
+//! threads and returns `None` until the variant is ready, letting a frame skip
+//! a draw instead of hitching. When the device supports driver pipeline
+//! caches, their data can be persisted between runs with
+//! [`PipelineCache::with_disk_cache`] and [`PipelineCache::save`].
+//!
+//! Every compilation runs inside a validation scope, so a descriptor the
+//! device rejects yields an error rather than an unusable pipeline. A failed
+//! compilation is reported once and then forgotten, so the next request for
+//! that variant compiles it again. Ready variants stay cached until
+//! [`PipelineCache::remove`] or [`PipelineCache::clear`] drops them, for
+//! example after a shader reload retires the modules they were built from.
+
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt, fs,
+    future::Future,
+    hash::{DefaultHasher, Hash, Hasher},
+    path::PathBuf,
+    pin::Pin,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{Receiver, Sender},
+    },
+    task::{Context, Poll, Waker},
+    thread::JoinHandle,
+};
+
+use astrelis_gpu as gpu;
+
+enum Slot {
+    Ready(gpu::RenderPipeline),
+    Pending,
+    Failed(PipelineCacheError),
+}
+
+struct Entry {
+    descriptor: gpu::RenderPipelineDescriptor,
+    slot: Slot,
+}
+
+struct Job {
+    hash: u64,
+    descriptor: gpu::RenderPipelineDescriptor,
+}
+
+struct Done {
+    hash: u64,
+    descriptor: gpu::RenderPipelineDescriptor,
+    result: Result<gpu::RenderPipeline, gpu::GpuError>,
+}
+
+/// A compilation whose validation scope has not resolved yet.
+type Compile = Pin<Box<dyn Future<Output = Result<gpu::RenderPipeline, gpu::GpuError>> + Send>>;
+
+/// A variant compiled on the requesting thread, waiting for its validation
+/// result when the backend reports it asynchronously.
+struct Validating {
+    hash: u64,
+    descriptor: gpu::RenderPipelineDescriptor,
+    compile: Compile,
+}
+
+struct Workers {
+    job_tx: Sender<Job>,
+    done_rx: Receiver<Done>,
+    /// Set when the cache is dropped so workers skip queued jobs.
+    cancelled: Arc<AtomicBool>,
+    handles: Vec<JoinHandle<()>>,
+}
+
+impl Workers {
+    /// Stops every worker after its current job and waits for it to exit.
+    fn shutdown(self) {
+        self.cancelled.store(true, Ordering::Release);
+        drop(self.job_tx);
+        for handle in self.handles {
+            let _ = handle.join();
+        }
+    }
+}
+
+struct State {
+    entries: HashMap<u64, Vec<Entry>>,
+    workers: Option<Workers>,
+    validating: Vec<Validating>,
+    pending: usize,
+}
+
+struct Inner {
+    device: gpu::Device,
+    driver_cache: Option<gpu::PipelineCache>,
+    file: Option<PathBuf>,
+    worker_count: usize,
+    state: Mutex<State>,
+}
+
+/// Render pipelines shared between renderers on one device.
+///
+/// Cloning the cache shares its storage, so one instance can be handed to
+/// every renderer.
+#[derive(Clone)]
+pub struct PipelineCache {
+    inner: Arc<Inner>,
+}
+
+impl PipelineCache {
+    /// Creates an in-memory cache compiling on up to `workers` background
+    /// threads, which are spawned on the first [`PipelineCache::request`].
+    pub fn new(device: gpu::Device, workers: usize) -> Self {
+        let driver_cache = device
+            .create_pipeline_cache(gpu::PipelineCacheDescriptor {
+                label: Some("astrelis pipeline cache".into()),
+                data: None,
+            })
+            .ok();
+        Self::from_parts(device, workers, driver_cache, None)
+    }
+
+    /// Creates a cache that seeds and saves driver pipeline cache data in
+    /// `directory`.
+    ///
+    /// The file name includes the device's
+    /// [`gpu::Device::pipeline_cache_key`], so data from another adapter or
+    /// driver version is never loaded. Devices without driver pipeline caches
+    /// behave like [`PipelineCache::new`] and [`PipelineCache::save`] does
+    /// nothing.
+    pub fn with_disk_cache(
+        device: gpu::Device,
+        workers: usize,
+        directory: impl Into<PathBuf>,
+    ) -> Result<Self, PipelineCacheError> {
+        let Some(key) = device.pipeline_cache_key() else {
+            return Ok(Self::new(device, workers));
+        };
+        let file = directory
+            .into()
+            .join(format!("astrelis-pipelines-{key}.bin"));
+        let data = match fs::read(&file) {
+            Ok(data) => Some(data),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => None,
+            Err(error) => {
+                return Err(PipelineCacheError(format!("{}: {error}", file.display())));
+            }
+        };
+        let driver_cache = device.create_pipeline_cache(gpu::PipelineCacheDescriptor {
+            label: Some("astrelis pipeline cache".into()),
+            data,
+        })?;
+        Ok(Self::from_parts(
+            device,
+            workers,
+            Some(driver_cache),
+            Some(file),
+        ))
+    }
+
+    fn from_parts(
+        device: gpu::Device,
+        workers: usize,
+        driver_cache: Option<gpu::PipelineCache>,
+        file: Option<PathBuf>,
+    ) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                device,
+                driver_cache,
+                file,
+                worker_count: workers,
+                state: Mutex::new(State {
+                    entries: HashMap::new(),
+                    workers: None,
+                    validating: Vec::new(),
+                    pending: 0,
+                }),
+            }),
+        }
+    }
+
+    /// Returns the pipeline for `descriptor`, compiling it on this thread if
+    /// no equivalent pipeline exists yet.
+    ///
+    /// A variant still compiling in the background, or whose last compilation
+    /// failed, is compiled again here rather than waited on; a later
+    /// background result is then discarded. The cache stays unlocked while
+    /// compiling, so other threads keep requesting and polling meanwhile. A
+    /// descriptor the device rejects is returned as an error and not cached.
+    pub async fn get_or_create(
+        &self,
+        descriptor: gpu::RenderPipelineDescriptor,
+    ) -> Result<gpu::RenderPipeline, PipelineCacheError> {
+        let hash = key_hash(&descriptor);
+        {
+            let mut state = self.lock();
+            state.collect();
+            if let Some(Slot::Ready(pipeline)) =
+                state.find(hash, &descriptor).map(|entry| &entry.slot)
+            {
+                return Ok(pipeline.clone());
+            }
+        }
+        let descriptor = self.with_driver_cache(descriptor);
+        let pipeline = compile(&self.inner.device, descriptor.clone()).await?;
+        Ok(self.lock().keep(hash, descriptor, pipeline))
+    }
+
+    /// Returns the pipeline for `descriptor` if it is ready, otherwise queues
+    /// it for background compilation and returns `None`.
+    ///
+    /// A failed background compilation is returned as an error by the next
+    /// request, which forgets it so the request after that retries. Without
+    /// worker threads the pipeline is compiled on this thread, and is
+    /// returned at once unless the backend reports validation later.
+    pub fn request(
+        &self,
+        descriptor: gpu::RenderPipelineDescriptor,
+    ) -> Result<Option<gpu::RenderPipeline>, PipelineCacheError> {
+        let hash = key_hash(&descriptor);
+        let mut state = self.lock();
+        state.collect();
+        match state.find(hash, &descriptor).map(|entry| &entry.slot) {
+            Some(Slot::Ready(pipeline)) => return Ok(Some(pipeline.clone())),
+            Some(Slot::Failed(_)) => {
+                let Some(Slot::Failed(error)) = state.forget(hash, &descriptor) else {
+                    unreachable!("the entry was just found failed");
+                };
+                return Err(error);
+            }
+            Some(Slot::Pending) => return Ok(None),
+            None => {}
+        }
+        if self.inner.worker_count == 0 {
+            drop(state);
+            return self.compile_here(hash, descriptor);
+        }
+        let descriptor = self.with_driver_cache(descriptor);
+        if state.workers.is_none() {
+            state.workers = Some(spawn(&self.inner.device, self.inner.worker_count)?);
+        }
+        let job = Job {
+            hash,
+            descriptor: descriptor.clone(),
+        };
+        let workers = state.workers.as_ref().expect("workers were spawned");
+        if workers.job_tx.send(job).is_err() {
+            // Every worker has exited, taking its queued jobs along. Forget
+            // what they were compiling and respawn them on the next request.
+            state.collect();
+            if let Some(workers) = state.workers.take() {
+                workers.shutdown();
+            }
+            state.abandon_pending();
+            return Err(PipelineCacheError(
+                "pipeline compilation workers have exited".into(),
+            ));
+        }
+        state.entries.entry(hash).or_default().push(Entry {
+            descriptor,
+            slot: Slot::Pending,
+        });
+        state.pending += 1;
+        Ok(None)
+    }
+
+    /// Collects finished background compilations, returning how many
+    /// variants are still compiling.
+    pub fn poll(&self) -> usize {
+        let mut state = self.lock();
+        state.collect();
+        state.pending
+    }
+
+    /// Number of pipelines ready for use.
+    pub fn len(&self) -> usize {
+        self.lock()
+            .entries
+            .values()
+            .flatten()
+            .filter(|entry| matches!(entry.slot, Slot::Ready(_)))
+            .count()
+    }
+
+    /// Returns whether no pipeline is ready yet.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Forgets the ready or failed variant equivalent to `descriptor`,
+    /// returning whether one was cached. A variant still compiling is kept.
+    pub fn remove(&self, descriptor: &gpu::RenderPipelineDescriptor) -> bool {
+        let hash = key_hash(descriptor);
+        let mut state = self.lock();
+        state.collect();
+        match state.find(hash, descriptor).map(|entry| &entry.slot) {
+            None | Some(Slot::Pending) => false,
+            Some(_) => state.forget(hash, descriptor).is_some(),
+        }
+    }
+
+    /// Forgets every ready and failed variant, releasing pipelines that hold
+    /// retired shader modules or layouts. Variants still compiling are kept.
+    pub fn clear(&self) {
+        let mut state = self.lock();
+        state.collect();
+        for bucket in state.entries.values_mut() {
+            bucket.retain(|entry| matches!(entry.slot, Slot::Pending));
+        }
+        state.entries.retain(|_, bucket| !bucket.is_empty());
+    }
+
+    /// Writes driver cache data to the file chosen by
+    /// [`PipelineCache::with_disk_cache`].
+    ///
+    /// Returns `false` without touching the disk when the cache is not
+    /// file-backed or the driver has no data to save.
+    pub fn save(&self) -> Result<bool, PipelineCacheError> {
+        let (Some(file), Some(cache)) = (&self.inner.file, &self.inner.driver_cache) else {
+            return Ok(false);
+        };
+        let Some(data) = cache.data() else {
+            return Ok(false);
+        };
+        let io_error =
+            |error: std::io::Error| PipelineCacheError(format!("{}: {error}", file.display()));
+        if let Some(directory) = file.parent() {
+            fs::create_dir_all(directory).map_err(io_error)?;
+        }
+        // Write beside the destination and rename so a crash mid-write never
+        // leaves a truncated cache for the next launch.
+        let temporary = file.with_extension("bin.tmp");
+        fs::write(&temporary, data).map_err(io_error)?;
+        fs::rename(&temporary, file).map_err(io_error)?;
+        Ok(true)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.inner
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Compiles a variant for [`PipelineCache::request`] without workers.
+    /// The cache stays unlocked while compiling.
+    fn compile_here(
+        &self,
+        hash: u64,
+        descriptor: gpu::RenderPipelineDescriptor,
+    ) -> Result<Option<gpu::RenderPipeline>, PipelineCacheError> {
+        let descriptor = self.with_driver_cache(descriptor);
+        let mut compile = compile(&self.inner.device, descriptor.clone());
+        let result = poll_once(&mut compile);
+        let mut state = self.lock();
+        match result {
+            Poll::Ready(result) => Ok(Some(state.keep(hash, descriptor, result?))),
+            Poll::Pending => {
+                if state.find(hash, &descriptor).is_none() {
+                    state.entries.entry(hash).or_default().push(Entry {
+                        descriptor: descriptor.clone(),
+                        slot: Slot::Pending,
+                    });
+                    state.pending += 1;
+                    state.validating.push(Validating {
+                        hash,
+                        descriptor,
+                        compile,
+                    });
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    fn with_driver_cache(
+        &self,
+        mut descriptor: gpu::RenderPipelineDescriptor,
+    ) -> gpu::RenderPipelineDescriptor {
+        if descriptor.cache.is_none() {
+            descriptor.cache = self.inner.driver_cache.clone();
+        }
+        descriptor
+    }
+}
+
+impl State {
+    fn find(&self, hash: u64, descriptor: &gpu::RenderPipelineDescriptor) -> Option<&Entry> {
+        self.entries
+            .get(&hash)?
+            .iter()
+            .find(|entry| same_pipeline(&entry.descriptor, descriptor))
+    }
+
+    /// Removes an entry, returning its slot.
+    fn forget(&mut self, hash: u64, descriptor: &gpu::RenderPipelineDescriptor) -> Option<Slot> {
+        let bucket = self.entries.get_mut(&hash)?;
+        let index = bucket
+            .iter()
+            .position(|entry| same_pipeline(&entry.descriptor, descriptor))?;
+        let entry = bucket.swap_remove(index);
+        if bucket.is_empty() {
+            self.entries.remove(&hash);
+        }
+        Some(entry.slot)
+    }
+
+    /// Forgets every variant still waiting for a worker.
+    fn abandon_pending(&mut self) {
+        for bucket in self.entries.values_mut() {
+            bucket.retain(|entry| !matches!(entry.slot, Slot::Pending));
+        }
+        self.entries.retain(|_, bucket| !bucket.is_empty());
+        self.pending = 0;
+    }
+
+    fn collect(&mut self) {
+        for mut validating in std::mem::take(&mut self.validating) {
+            match poll_once(&mut validating.compile) {
+                Poll::Ready(result) => {
+                    self.finish(validating.hash, validating.descriptor, result);
+                }
+                Poll::Pending => self.validating.push(validating),
+            }
+        }
+        let Some(workers) = &self.workers else {
+            return;
+        };
+        let done = workers.done_rx.try_iter().collect::<Vec<_>>();
+        for done in done {
+            self.finish(done.hash, done.descriptor, done.result);
+        }
+    }
+
+    /// Stores a pipeline compiled on a requesting thread and returns the one
+    /// the cache kept: another thread may have finished the same variant
+    /// first, and every caller shares one pipeline.
+    fn keep(
+        &mut self,
+        hash: u64,
+        descriptor: gpu::RenderPipelineDescriptor,
+        pipeline: gpu::RenderPipeline,
+    ) -> gpu::RenderPipeline {
+        self.finish(hash, descriptor.clone(), Ok(pipeline.clone()));
+        match self.find(hash, &descriptor).map(|entry| &entry.slot) {
+            Some(Slot::Ready(kept)) => kept.clone(),
+            _ => pipeline,
+        }
+    }
+
+    /// Stores a compilation result, keeping an existing ready pipeline.
+    fn finish(
+        &mut self,
+        hash: u64,
+        descriptor: gpu::RenderPipelineDescriptor,
+        result: Result<gpu::RenderPipeline, gpu::GpuError>,
+    ) {
+        let bucket = self.entries.entry(hash).or_default();
+        let slot = match result {
+            Ok(pipeline) => Slot::Ready(pipeline),
+            Err(error) => Slot::Failed(error.into()),
+        };
+        match bucket
+            .iter_mut()
+            .find(|entry| same_pipeline(&entry.descriptor, &descriptor))
+        {
+            Some(entry) => match entry.slot {
+                Slot::Ready(_) => {}
+                Slot::Pending => {
+                    entry.slot = slot;
+                    self.pending -= 1;
+                }
+                Slot::Failed(_) => entry.slot = slot,
+            },
+            None => bucket.push(Entry { descriptor, slot }),
+        }
+    }
+}
+
+/// Cancels queued compilations and joins the workers so no thread outlives
+/// the device handle it compiles against. Only compilations already under
+/// way are waited for.
+impl Drop for Inner {
+    fn drop(&mut self) {
+        let state = self
+            .state
+            .get_mut()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(workers) = state.workers.take() {
+            workers.shutdown();
+        }
+    }
+}
+
+fn spawn(device: &gpu::Device, count: usize) -> Result<Workers, PipelineCacheError> {
+    let (job_tx, job_rx) = std::sync::mpsc::channel::<Job>();
+    let (done_tx, done_rx) = std::sync::mpsc::channel::<Done>();
+    let job_rx = Arc::new(Mutex::new(job_rx));
+    let mut workers = Workers {
+        job_tx,
+        done_rx,
+        cancelled: Arc::new(AtomicBool::new(false)),
+        handles: Vec::with_capacity(count),
+    };
+    for index in 0..count {
+        let device = device.clone();
+        let job_rx = Arc::clone(&job_rx);
+        let done_tx = done_tx.clone();
+        let cancelled = Arc::clone(&workers.cancelled);
+        let spawned = std::thread::Builder::new()
+            .name(format!("astrelis-pipelines-{index}"))
+            .spawn(move || run(&device, &job_rx, &done_tx, &cancelled));
+        match spawned {
+            Ok(handle) => workers.handles.push(handle),
+            Err(error) => {
+                workers.shutdown();
+                return Err(PipelineCacheError(format!(
+                    "failed to spawn pipeline compilation worker: {error}"
+                )));
+            }
+        }
+    }
+    Ok(workers)
+}
+
+fn run(
+    device: &gpu::Device,
+    job_rx: &Mutex<Receiver<Job>>,
+    done_tx: &Sender<Done>,
+    cancelled: &AtomicBool,
+) {
+    loop {
+        let job = match job_rx.lock() {
+            Ok(receiver) => receiver.recv(),
+            Err(_) => return,
+        };
+        let Ok(job) = job else {
+            return;
+        };
+        if cancelled.load(Ordering::Acquire) {
+            return;
+        }
+        let result = pollster::block_on(compile(device, job.descriptor.clone()));
+        let done = Done {
+            hash: job.hash,
+            descriptor: job.descriptor,
+            result,
+        };
+        if done_tx.send(done).is_err() {
+            return;
+        }
+    }
+}
+
+/// Creates a pipeline inside a validation scope, so a descriptor the device
+/// rejects yields an error instead of an invalid pipeline.
+fn compile(device: &gpu::Device, descriptor: gpu::RenderPipelineDescriptor) -> Compile {
+    let device = device.clone();
+    Box::pin(async move {
+        device
+            .validated(|| device.create_render_pipeline(descriptor))
+            .await?
+    })
+}
+
+/// Polls a compilation once; native backends resolve validation scopes
+/// immediately.
+fn poll_once(compile: &mut Compile) -> Poll<Result<gpu::RenderPipeline, gpu::GpuError>> {
+    compile
+        .as_mut()
+        .poll(&mut Context::from_waker(Waker::noop()))
+}
+
+/// Hashes the state compared by [`same_pipeline`]. Resource identity is
+/// checked only on lookup, so equal state with different shaders shares a
+/// bucket.
+fn key_hash(descriptor: &gpu::RenderPipelineDescriptor) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    descriptor.vertex.entry_point.hash(&mut hasher);
+    descriptor.vertex.buffers.hash(&mut hasher);
+    descriptor.primitive.hash(&mut hasher);
+    descriptor.layout.is_some().hash(&mut hasher);
+    if let Some(state) = &descriptor.depth_stencil {
+        state.format.hash(&mut hasher);
+        state.depth_write_enabled.hash(&mut hasher);
+        state.depth_compare.hash(&mut hasher);
+        state.stencil.hash(&mut hasher);
+        state.bias_constant.hash(&mut hasher);
+        state.bias_slope_scale.to_bits().hash(&mut hasher);
+        state.bias_clamp.to_bits().hash(&mut hasher);
+    }
+    descriptor.multisample.hash(&mut hasher);
+    if let Some(fragment) = &descriptor.fragment {
+        fragment.entry_point.hash(&mut hasher);
+        fragment.targets.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+/// Compares everything but labels and driver caches, with shader modules and
+/// layouts compared by identity.
+fn same_pipeline(a: &gpu::RenderPipelineDescriptor, b: &gpu::RenderPipelineDescriptor) -> bool {
+    let layouts = match (&a.layout, &b.layout) {
+        (Some(a), Some(b)) => a.same_resource(b),
+        (None, None) => true,
+        _ => false,
+    };
+    let fragments = match (&a.fragment, &b.fragment) {
+        (Some(a), Some(b)) => {
+            a.module.same_resource(&b.module)
+                && a.entry_point == b.entry_point
+                && a.targets == b.targets
+        }
+        (None, None) => true,
+        _ => false,
+    };
+    layouts
+        && fragments
+        && a.vertex.module.same_resource(&b.vertex.module)
+        && a.vertex.entry_point == b.vertex.entry_point
+        && a.vertex.buffers == b.vertex.buffers
+        && a.primitive == b.primitive
+        && a.depth_stencil == b.depth_stencil
+        && a.multisample == b.multisample
+}
+
+/// Pipeline cache I/O or compilation failure.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PipelineCacheError(String);
+
+impl fmt::Display for PipelineCacheError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(formatter)
+    }
+}
+
+impl Error for PipelineCacheError {}
+
+impl From<gpu::GpuError> for PipelineCacheError {
+    fn from(value: gpu::GpuError) -> Self {
+        Self(value.to_string())
+    }
+}
@@ -0,0 +1,1030 @@
+//! Fullscreen post-processing over an HDR scene texture.
+//!
+//! A [`PostProcessStack`] runs its effects in order, ping-ponging between two
+//! `Rgba16Float` intermediates so values above 1.0 survive until an explicit
+//! [`Tonemap`]. The final effect writes directly into the caller's target.
+//! Stacks are cheap to keep per window: intermediates follow the most recent
+//! frame size and pipelines are cached per output format.
+//! [`WindowPostStacks`](crate::WindowPostStacks) keeps one stack per window.
+//! Renderers that run several stacks can lease intermediates from a shared
+//! [`TransientPool`] instead, so effects reuse one set of textures across
+//! passes.
+
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use astrelis_core::{
+    color::Color,
+    geometry::{Physical, Size},
+};
+use astrelis_gpu as gpu;
+use bytemuck::{Pod, Zeroable};
+
+use crate::{GraphError, TransientPool, TransientTexture};
+
+const PRELUDE: &str = include_str!("post_prelude.wgsl");
+const BUILTIN: &str = concat!(include_str!("post_prelude.wgsl"), include_str!("post.wgsl"));
+const INTERMEDIATE_FORMAT: gpu::TextureFormat = gpu::TextureFormat::Rgba16Float;
+static NEXT_CUSTOM_EFFECT: AtomicU64 = AtomicU64::new(1);
+
+/// HDR-to-display tone curve.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ToneMapping {
+    /// Narkowicz's ACES filmic fit.
+    #[default]
+    Aces,
+    /// `c / (1 + c)` per channel.
+    Reinhard,
+    /// Clamp to `[0, 1]` without compression.
+    Clamp,
+}
+
+/// Exposure and tone-curve settings.
+///
+/// When the stack's target is an 8-bit format without hardware sRGB
+/// encoding, the last tonemap in the stack also applies the sRGB transfer
+/// curve, and effects after it operate on display-encoded values.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tonemap {
+    /// Linear multiplier applied before the curve.
+    pub exposure: f32,
+    /// Tone curve.
+    pub operator: ToneMapping,
+}
+
+impl Default for Tonemap {
+    fn default() -> Self {
+        Self {
+            exposure: 1.0,
+            operator: ToneMapping::Aces,
+        }
+    }
+}
+
+/// Half-resolution threshold bloom.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bloom {
+    /// Brightness above which pixels contribute.
+    pub threshold: f32,
+    /// Fraction of `threshold` over which the cutoff is softened.
+    pub knee: f32,
+    /// Multiplier for the blurred contribution added back to the scene.
+    pub intensity: f32,
+    /// Number of separable blur iterations; 0 turns the effect off.
+    pub passes: u32,
+}
+
+impl Default for Bloom {
+    fn default() -> Self {
+        Self {
+            threshold: 1.0,
+            knee: 0.5,
+            intensity: 0.6,
+            passes: 2,
+        }
+    }
+}
+
+/// Darkening toward the frame corners.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Vignette {
+    /// Maximum blend toward `color`, from 0 to 1.
+    pub intensity: f32,
+    /// Normalized distance from the center where darkening begins.
+    pub radius: f32,
+    /// Normalized width of the transition.
+    pub smoothness: f32,
+    /// Color blended into the corners.
+    pub color: Color,
+}
+
+impl Default for Vignette {
+    fn default() -> Self {
+        Self {
+            intensity: 0.4,
+            radius: 0.6,
+            smoothness: 0.5,
+            color: Color::BLACK,
+        }
+    }
+}
+
+/// Fast approximate antialiasing, best applied after tonemapping.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Fxaa {
+    /// Minimum local contrast, relative to the brightest neighbor, treated
+    /// as an edge.
+    pub edge_threshold: f32,
+    /// Longest blend span in texels.
+    pub max_span: f32,
+}
+
+impl Default for Fxaa {
+    fn default() -> Self {
+        Self {
+            edge_threshold: 0.125,
+            max_span: 8.0,
+        }
+    }
+}
+
+/// A user-supplied fullscreen fragment shader.
+///
+/// The source is appended to a prelude declaring `input_texture`,
+/// `input_sampler`, `params` (`texel_size`, `values`, `extra`), the
+/// `FullscreenOut` vertex output, and `luminance`. It must define
+/// `fn fs_main(input: FullscreenOut) -> @location(0) vec4<f32>`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CustomEffect {
+    id: u64,
+    label: String,
+    source: Arc<str>,
+    /// Values exposed to the shader as `params.values`.
+    pub values: [f32; 4],
+    /// Values exposed to the shader as `params.extra`.
+    pub extra: [f32; 4],
+}
+
+impl CustomEffect {
+    /// Creates an effect from WGSL fragment source.
+    pub fn new(label: impl Into<String>, wgsl: impl Into<Arc<str>>) -> Self {
+        Self {
+            id: NEXT_CUSTOM_EFFECT.fetch_add(1, Ordering::Relaxed),
+            label: label.into(),
+            source: wgsl.into(),
+            values: [0.0; 4],
+            extra: [0.0; 4],
+        }
+    }
+
+    /// Debug label.
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+}
+
+/// One entry in a [`PostProcessStack`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum PostEffect {
+    /// HDR-to-display conversion.
+    Tonemap(Tonemap),
+    /// Threshold bloom.
+    Bloom(Bloom),
+    /// Corner darkening.
+    Vignette(Vignette),
+    /// Fast approximate antialiasing.
+    Fxaa(Fxaa),
+    /// Custom WGSL pass.
+    Custom(CustomEffect),
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+struct Params {
+    texel_size: [f32; 2],
+    padding: [f32; 2],
+    values: [f32; 4],
+    extra: [f32; 4],
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum ShaderKey {
+    Builtin(&'static str),
+    Custom(u64),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Slot {
+    Source,
+    Ping(usize),
+    Bloom(usize),
+    Target,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Step {
+    shader: ShaderKey,
+    input: Slot,
+    secondary: Option<Slot>,
+    output: Slot,
+    values: [f32; 4],
+    extra: [f32; 4],
+}
+
+/// Expands effects into fullscreen passes, alternating intermediates so no
+/// pass samples the texture it renders into. `encode_srgb` makes the last
+/// tonemap write display-encoded values. Effects that are turned off are
+/// skipped entirely.
+fn plan(effects: &[PostEffect], encode_srgb: bool) -> Vec<Step> {
+    let effects = effects
+        .iter()
+        .filter(|effect| !matches!(effect, PostEffect::Bloom(Bloom { passes: 0, .. })))
+        .collect::<Vec<_>>();
+    let last_tonemap = effects
+        .iter()
+        .rposition(|effect| matches!(effect, PostEffect::Tonemap(_)));
+    let mut steps = Vec::new();
+    let mut current = Slot::Source;
+    let builtin = |name, input, output, values, extra| Step {
+        shader: ShaderKey::Builtin(name),
+        input,
+        secondary: None,
+        output,
+        values,
+        extra,
+    };
+    for (index, effect) in effects.iter().enumerate() {
+        let output = if index + 1 == effects.len() {
+            Slot::Target
+        } else if current == Slot::Ping(0) {
+            Slot::Ping(1)
+        } else {
+            Slot::Ping(0)
+        };
+        match effect {
+            PostEffect::Tonemap(tonemap) => {
+                let operator = match tonemap.operator {
+                    ToneMapping::Aces => 0.0,
+                    ToneMapping::Reinhard => 1.0,
+                    ToneMapping::Clamp => 2.0,
+                };
+                let encode = encode_srgb && last_tonemap == Some(index);
+                steps.push(builtin(
+                    "fs_tonemap",
+                    current,
+                    output,
+                    [tonemap.exposure, operator, f32::from(u8::from(encode)), 0.0],
+                    [0.0; 4],
+                ));
+            }
+            PostEffect::Bloom(bloom) => {
+                steps.push(builtin(
+                    "fs_bloom_extract",
+                    current,
+                    Slot::Bloom(0),
+                    [bloom.threshold, bloom.knee, 0.0, 0.0],
+                    [0.0; 4],
+                ));
+                for _ in 0..bloom.passes {
+                    steps.push(builtin(
+                        "fs_blur",
+                        Slot::Bloom(0),
+                        Slot::Bloom(1),
+                        [1.0, 0.0, 0.0, 0.0],
+                        [0.0; 4],
+                    ));
+                    steps.push(builtin(
+                        "fs_blur",
+                        Slot::Bloom(1),
+                        Slot::Bloom(0),
+                        [0.0, 1.0, 0.0, 0.0],
+                        [0.0; 4],
+                    ));
+                }
+                steps.push(Step {
+                    secondary: Some(Slot::Bloom(0)),
+                    ..builtin(
+                        "fs_bloom_combine",
+                        current,
+                        output,
+                        [bloom.intensity, 0.0, 0.0, 0.0],
+                        [0.0; 4],
+                    )
+                });
+            }
+            PostEffect::Vignette(vignette) => {
+                let color = vignette.color;
+                steps.push(builtin(
+                    "fs_vignette",
+                    current,
+                    output,
+                    [
+                        vignette.intensity,
+                        vignette.radius,
+                        vignette.smoothness,
+                        0.0,
+                    ],
+                    [color.r, color.g, color.b, color.a],
+                ));
+            }
+            PostEffect::Fxaa(fxaa) => steps.push(builtin(
+                "fs_fxaa",
+                current,
+                output,
+                [fxaa.edge_threshold, fxaa.max_span, 0.0, 0.0],
+                [0.0; 4],
+            )),
+            PostEffect::Custom(custom) => steps.push(Step {
+                shader: ShaderKey::Custom(custom.id),
+                input: current,
+                secondary: None,
+                output,
+                values: custom.values,
+                extra: custom.extra,
+            }),
+        }
+        current = output;
+    }
+    if steps.is_empty() {
+        steps.push(builtin(
+            "fs_copy",
+            Slot::Source,
+            Slot::Target,
+            [0.0; 4],
+            [0.0; 4],
+        ));
+    }
+    steps
+}
+
+/// Intermediate slots read or written by `steps`, each listed once.
+fn intermediate_slots(steps: &[Step]) -> Vec<Slot> {
+    let mut slots = Vec::new();
+    for step in steps {
+        for slot in [Some(step.input), step.secondary, Some(step.output)]
+            .into_iter()
+            .flatten()
+        {
+            if matches!(slot, Slot::Ping(_) | Slot::Bloom(_)) && !slots.contains(&slot) {
+                slots.push(slot);
+            }
+        }
+    }
+    slots
+}
+
+/// Views bound to intermediate slots for one recording.
+#[derive(Default)]
+struct SlotViews {
+    ping: [Option<gpu::TextureView>; 2],
+    bloom: [Option<gpu::TextureView>; 2],
+}
+
+/// Cached uniforms and bind group for one planned step. The bind group is
+/// rebuilt only when the step samples different views.
+struct StepBinding {
+    buffer: gpu::Buffer,
+    params: Params,
+    input: gpu::TextureView,
+    secondary: gpu::TextureView,
+    bind_group: gpu::BindGroup,
+}
+
+struct Intermediates {
+    size: Size<Physical, u32>,
+    _textures: Vec<gpu::Texture>,
+    ping: [gpu::TextureView; 2],
+    bloom: [gpu::TextureView; 2],
+}
+
+/// An ordered list of fullscreen effects bound to one device and queue.
+pub struct PostProcessStack {
+    device: gpu::Device,
+    queue: gpu::Queue,
+    effects: Vec<PostEffect>,
+    layout: gpu::BindGroupLayout,
+    pipeline_layout: gpu::PipelineLayout,
+    sampler: gpu::Sampler,
+    builtin: gpu::ShaderModule,
+    custom: HashMap<u64, gpu::ShaderModule>,
+    pipelines: HashMap<(ShaderKey, gpu::TextureFormat), gpu::RenderPipeline>,
+    bindings: Vec<StepBinding>,
+    intermediates: Option<Intermediates>,
+}
+
+impl PostProcessStack {
+    /// Creates an empty stack for one matching device/queue pair.
+    pub fn new(device: gpu::Device, queue: gpu::Queue) -> Result<Self, PostError> {
+        if device.id() != queue.device_id() {
+            return Err(PostError::new("device and queue do not match"));
+        }
+        let texture_entry = |binding| gpu::BindGroupLayoutEntry {
+            binding,
+            visibility: gpu::ShaderStages::FRAGMENT,
+            ty: gpu::BindingType::Texture {
+                sample_type: gpu::TextureSampleType::Float,
+                view_dimension: gpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+        };
+        let layout = device.create_bind_group_layout(gpu::BindGroupLayoutDescriptor {
+            label: Some("post-process layout".into()),
+            entries: vec![
+                texture_entry(0),
+                gpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: gpu::ShaderStages::FRAGMENT,
+                    ty: gpu::BindingType::Sampler(gpu::SamplerBindingType::Filtering),
+                },
+                gpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: gpu::ShaderStages::FRAGMENT,
+                    ty: gpu::BindingType::Buffer {
+                        ty: gpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                },
+                texture_entry(3),
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(gpu::PipelineLayoutDescriptor {
+            label: Some("post-process pipeline layout".into()),
+            bind_group_layouts: vec![layout.clone()],
+        })?;
+        let sampler = device.create_sampler(gpu::SamplerDescriptor {
+            label: Some("post-process sampler".into()),
+            mag_filter: gpu::FilterMode::Linear,
+            min_filter: gpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        let builtin = device.create_shader_module(gpu::ShaderModuleDescriptor {
+            label: Some("post-process shader".into()),
+            wgsl: BUILTIN.into(),
+        });
+        Ok(Self {
+            device,
+            queue,
+            effects: Vec::new(),
+            layout,
+            pipeline_layout,
+            sampler,
+            builtin,
+            custom: HashMap::new(),
+            pipelines: HashMap::new(),
+            bindings: Vec::new(),
+            intermediates: None,
+        })
+    }
+
+    /// Appends an effect to the end of the stack.
+    pub fn push(&mut self, effect: PostEffect) -> &mut Self {
+        self.effects.push(effect);
+        self
+    }
+
+    /// Effects in execution order.
+    pub fn effects(&self) -> &[PostEffect] {
+        &self.effects
+    }
+
+    /// Mutable effects, for reordering or adjusting parameters per frame.
+    pub fn effects_mut(&mut self) -> &mut Vec<PostEffect> {
+        &mut self.effects
+    }
+
+    /// Releases intermediates, cached pipelines, and per-pass uniforms.
+    pub fn trim(&mut self) {
+        self.intermediates = None;
+        self.pipelines.clear();
+        self.custom.clear();
+        self.bindings.clear();
+    }
+
+    /// Runs every effect from `source` into `target`.
+    ///
+    /// `source` must be a single-sampled filterable view of `size`, distinct
+    /// from `target`. With no effects the source is copied through unchanged.
+    ///
+    /// Effect parameters are written through the queue, so each stack should
+    /// record at most once per submission, like other per-frame uniforms.
+    pub fn render(
+        &mut self,
+        encoder: &mut gpu::CommandEncoder,
+        source: &gpu::TextureView,
+        size: Size<Physical, u32>,
+        target: &gpu::TextureView,
+    ) -> Result<(), PostError> {
+        self.validate(source, target)?;
+        if size.width == 0 || size.height == 0 {
+            return Ok(());
+        }
+        let steps = plan(&self.effects, needs_srgb_encode(target.format())?);
+        self.ensure_intermediates(size);
+        let intermediates = self.intermediates.as_ref().expect("intermediates exist");
+        let views = SlotViews {
+            ping: intermediates.ping.clone().map(Some),
+            bloom: intermediates.bloom.clone().map(Some),
+        };
+        self.record(encoder, &steps, &views, source, size, target)
+    }
+
+    /// Runs every effect like [`PostProcessStack::render`], leasing
+    /// intermediates from `pool` instead of owning them.
+    ///
+    /// Only the intermediates the current effects need are leased, and they
+    /// return to the pool once the passes are recorded, so later passes in
+    /// the same frame can alias them. The stack's own intermediates are
+    /// released.
+    pub fn render_pooled(
+        &mut self,
+        encoder: &mut gpu::CommandEncoder,
+        pool: &mut TransientPool,
+        source: &gpu::TextureView,
+        size: Size<Physical, u32>,
+        target: &gpu::TextureView,
+    ) -> Result<(), PostError> {
+        self.validate(source, target)?;
+        self.intermediates = None;
+        if size.width == 0 || size.height == 0 {
+            return Ok(());
+        }
+        let steps = plan(&self.effects, needs_srgb_encode(target.format())?);
+        let mut views = SlotViews::default();
+        let mut leases = Vec::new();
+        let mut lease = |slot| -> Result<(), PostError> {
+            let (label, size) = match slot {
+                Slot::Ping(_) => ("post-process ping", size),
+                _ => ("post-process bloom", half(size)),
+            };
+            let lease = pool.acquire(&TransientTexture {
+                label: label.into(),
+                size,
+                format: INTERMEDIATE_FORMAT,
+                sample_count: 1,
+                usage: gpu::TextureUsages::RENDER_ATTACHMENT | gpu::TextureUsages::TEXTURE_BINDING,
+            })?;
+            let view = Some(lease.view().clone());
+            match slot {
+                Slot::Ping(index) => views.ping[index] = view,
+                Slot::Bloom(index) => views.bloom[index] = view,
+                Slot::Source | Slot::Target => unreachable!("only intermediates are leased"),
+            }
+            leases.push(lease);
+            Ok(())
+        };
+        let leased = intermediate_slots(&steps)
+            .into_iter()
+            .try_for_each(&mut lease);
+        let result =
+            leased.and_then(|()| self.record(encoder, &steps, &views, source, size, target));
+        let mut released = Ok(());
+        for lease in leases {
+            released = released.and(pool.release(lease));
+        }
+        result.and(released.map_err(PostError::from))
+    }
+
+    fn validate(
+        &self,
+        source: &gpu::TextureView,
+        target: &gpu::TextureView,
+    ) -> Result<(), PostError> {
+        if source.device_id() != self.device.id() || target.device_id() != self.device.id() {
+            return Err(PostError::new(
+                "post-process views belong to another device",
+            ));
+        }
+        if source.sample_count() != 1 || target.sample_count() != 1 {
+            return Err(PostError::new(
+                "post-process source and target must be single-sampled",
+            ));
+        }
+        if source.same_resource(target) {
+            return Err(PostError::new(
+                "post-process source and target must be different views",
+            ));
+        }
+        Ok(())
+    }
+
+    fn record(
+        &mut self,
+        encoder: &mut gpu::CommandEncoder,
+        steps: &[Step],
+        views: &SlotViews,
+        source: &gpu::TextureView,
+        size: Size<Physical, u32>,
+        target: &gpu::TextureView,
+    ) -> Result<(), PostError> {
+        self.evict_removed_effects();
+        for step in steps {
+            let format = match step.output {
+                Slot::Target => target.format(),
+                _ => INTERMEDIATE_FORMAT,
+            };
+            self.ensure_pipeline(step.shader, format)?;
+        }
+        let bloom_size = half(size);
+        let view = |slot| match slot {
+            Slot::Source => (source, size),
+            Slot::Ping(index) => (views.ping[index].as_ref().expect("slot is bound"), size),
+            Slot::Bloom(index) => (
+                views.bloom[index].as_ref().expect("slot is bound"),
+                bloom_size,
+            ),
+            Slot::Target => (target, size),
+        };
+        self.bindings.truncate(steps.len());
+        for (index, step) in steps.iter().enumerate() {
+            let (input, input_size) = view(step.input);
+            let secondary = step.secondary.map_or(input, |slot| view(slot).0);
+            let params = Params {
+                texel_size: [
+                    1.0 / input_size.width as f32,
+                    1.0 / input_size.height as f32,
+                ],
+                padding: [0.0; 2],
+                values: step.values,
+                extra: step.extra,
+            };
+            self.bind_step(index, input, secondary, params)?;
+        }
+        encoder.push_debug_group("post-process");
+        let mut record = || -> Result<(), PostError> {
+            for (step, binding) in steps.iter().zip(&self.bindings) {
+                let (output, _) = view(step.output);
+                let mut pass = encoder.begin_render_pass(gpu::RenderPassDescriptor {
+                    label: Some(match step.shader {
+                        ShaderKey::Builtin(entry_point) => entry_point.into(),
+                        ShaderKey::Custom(_) => "custom effect".into(),
+                    }),
+                    color_attachments: vec![Some(gpu::RenderPassColorAttachment {
+                        view: output.clone(),
+                        resolve_target: None,
+                        load: gpu::LoadOp::Clear(gpu::Color {
+                            r: 0.0,
+                            g: 0.0,
+                            b: 0.0,
+                            a: 0.0,
+                        }),
+                        store: gpu::StoreOp::Store,
+                    })],
+                    depth_stencil_attachment: None,
+                    timestamp_writes: None,
+                })?;
+                pass.set_pipeline(&self.pipelines[&(step.shader, output.format())])?;
+                pass.set_bind_group(0, &binding.bind_group, &[])?;
+                pass.draw(0..3, 0..1);
+            }
+            Ok(())
+        };
+        let result = record();
+        encoder.pop_debug_group();
+        result
+    }
+
+    /// Updates the uniforms of step `index` in place and rebuilds its bind
+    /// group only when the sampled views changed.
+    fn bind_step(
+        &mut self,
+        index: usize,
+        input: &gpu::TextureView,
+        secondary: &gpu::TextureView,
+        params: Params,
+    ) -> Result<(), PostError> {
+        if let Some(binding) = self.bindings.get_mut(index) {
+            if binding.params != params {
+                self.queue
+                    .write_buffer(&binding.buffer, 0, bytemuck::bytes_of(&params))?;
+                binding.params = params;
+            }
+            if !binding.input.same_resource(input) || !binding.secondary.same_resource(secondary) {
+                binding.bind_group = create_bind_group(
+                    &self.device,
+                    &self.layout,
+                    &self.sampler,
+                    &binding.buffer,
+                    input,
+                    secondary,
+                )?;
+                binding.input = input.clone();
+                binding.secondary = secondary.clone();
+            }
+            return Ok(());
+        }
+        let buffer = self.device.create_buffer_init(
+            &self.queue,
+            Some("post-process params".into()),
+            bytemuck::bytes_of(&params),
+            gpu::BufferUsages::UNIFORM | gpu::BufferUsages::COPY_DST,
+        )?;
+        let bind_group = create_bind_group(
+            &self.device,
+            &self.layout,
+            &self.sampler,
+            &buffer,
+            input,
+            secondary,
+        )?;
+        self.bindings.push(StepBinding {
+            buffer,
+            params,
+            input: input.clone(),
+            secondary: secondary.clone(),
+            bind_group,
+        });
+        Ok(())
+    }
+
+    /// Drops shader modules and pipelines of custom effects that are no
+    /// longer in the stack, so swapping effects does not accumulate them.
+    fn evict_removed_effects(&mut self) {
+        let effects = &self.effects;
+        let live = |id: u64| {
+            effects
+                .iter()
+                .any(|effect| matches!(effect, PostEffect::Custom(custom) if custom.id == id))
+        };
+        self.custom.retain(|&id, _| live(id));
+        self.pipelines.retain(|(shader, _), _| match *shader {
+            ShaderKey::Builtin(_) => true,
+            ShaderKey::Custom(id) => live(id),
+        });
+    }
+
+    fn ensure_intermediates(&mut self, size: Size<Physical, u32>) {
+        if self
+            .intermediates
+            .as_ref()
+            .is_some_and(|intermediates| intermediates.size == size)
+        {
+            return;
+        }
+        let mut textures = Vec::new();
+        let mut create = |label: &str, size: Size<Physical, u32>| {
+            let texture = self.device.create_texture(gpu::TextureDescriptor {
+                label: Some(label.into()),
+                size: gpu::Extent3d::d2(size.width, size.height),
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: gpu::TextureDimension::D2,
+                format: INTERMEDIATE_FORMAT,
+                usage: gpu::TextureUsages::RENDER_ATTACHMENT | gpu::TextureUsages::TEXTURE_BINDING,
+            });
+            let view = texture.create_view(Default::default());
+            textures.push(texture);
+            view
+        };
+        let ping = [
+            create("post-process ping", size),
+            create("post-process pong", size),
+        ];
+        let bloom = [
+            create("post-process bloom", half(size)),
+            create("post-process bloom blur", half(size)),
+        ];
+        self.intermediates = Some(Intermediates {
+            size,
+            _textures: textures,
+            ping,
+            bloom,
+        });
+    }
+
+    fn ensure_pipeline(
+        &mut self,
+        shader: ShaderKey,
+        format: gpu::TextureFormat,
+    ) -> Result<(), PostError> {
+        if self.pipelines.contains_key(&(shader, format)) {
+            return Ok(());
+        }
+        let (module, entry_point) = match shader {
+            ShaderKey::Builtin(entry_point) => (self.builtin.clone(), entry_point),
+            ShaderKey::Custom(id) => {
+                let effect = self
+                    .effects
+                    .iter()
+                    .find_map(|effect| match effect {
+                        PostEffect::Custom(custom) if custom.id == id => Some(custom),
+                        _ => None,
+                    })
+                    .expect("planned custom effect is in the stack");
+                let module = self
+                    .custom
+                    .entry(id)
+                    .or_insert_with(|| {
+                        self.device
+                            .create_shader_module(gpu::ShaderModuleDescriptor {
+                                label: Some(effect.label.clone()),
+                                wgsl: format!("{PRELUDE}\n{}", effect.source),
+                            })
+                    })
+                    .clone();
+                (module, "fs_main")
+            }
+        };
+        let pipeline = self
+            .device
+            .create_render_pipeline(gpu::RenderPipelineDescriptor {
+                label: Some(format!("post-process {entry_point}")),
+                layout: Some(self.pipeline_layout.clone()),
+                vertex: gpu::VertexState {
+                    module: module.clone(),
+                    entry_point: "vs_fullscreen".into(),
+                    buffers: Vec::new(),
+                },
+                primitive: Default::default(),
+                depth_stencil: None,
+                multisample: Default::default(),
+                fragment: Some(gpu::FragmentState {
+                    module,
+                    entry_point: entry_point.into(),
+                    targets: vec![Some(gpu::ColorTargetState {
+                        format,
+                        blend: None,
+                        write_mask: gpu::ColorWrites::ALL,
+                    })],
+                }),
+                cache: None,
+            })?;
+        self.pipelines.insert((shader, format), pipeline);
+        Ok(())
+    }
+}
+
+fn create_bind_group(
+    device: &gpu::Device,
+    layout: &gpu::BindGroupLayout,
+    sampler: &gpu::Sampler,
+    buffer: &gpu::Buffer,
+    input: &gpu::TextureView,
+    secondary: &gpu::TextureView,
+) -> Result<gpu::BindGroup, PostError> {
+    Ok(device.create_bind_group(gpu::BindGroupDescriptor {
+        label: Some("post-process bind group".into()),
+        layout: layout.clone(),
+        entries: vec![
+            gpu::BindGroupEntry {
+                binding: 0,
+                resource: gpu::BindingResource::TextureView(input.clone()),
+            },
+            gpu::BindGroupEntry {
+                binding: 1,
+                resource: gpu::BindingResource::Sampler(sampler.clone()),
+            },
+            gpu::BindGroupEntry {
+                binding: 2,
+                resource: gpu::BindingResource::Buffer(gpu::BufferBinding {
+                    buffer: buffer.clone(),
+                    offset: 0,
+                    size: None,
+                }),
+            },
+            gpu::BindGroupEntry {
+                binding: 3,
+                resource: gpu::BindingResource::TextureView(secondary.clone()),
+            },
+        ],
+    })?)
+}
+
+/// Whether the tonemap must apply the sRGB transfer curve itself for a
+/// `format` target.
+///
+/// 8-bit unorm color targets store display values, so only those without
+/// hardware sRGB encoding need it; float targets hold linear light. Other
+/// formats cannot take post-processed color.
+fn needs_srgb_encode(format: gpu::TextureFormat) -> Result<bool, PostError> {
+    use gpu::TextureFormat as F;
+    match format {
+        F::R8Unorm | F::Rgba8Unorm | F::Rgba8UnormSrgb | F::Bgra8Unorm | F::Bgra8UnormSrgb => {
+            Ok(!format.is_srgb())
+        }
+        F::Rgba16Float => Ok(false),
+        _ => Err(PostError::new(format!(
+            "post-process targets must be 8-bit unorm or Rgba16Float color, not {format:?}"
+        ))),
+    }
+}
+
+fn half(size: Size<Physical, u32>) -> Size<Physical, u32> {
+    Size::new((size.width / 2).max(1), (size.height / 2).max(1))
+}
+
+/// Post-processing failure.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PostError(String);
+
+impl PostError {
+    pub(crate) fn new(message: impl Into<String>) -> Self {
+        Self(message.into())
+    }
+}
+
+impl fmt::Display for PostError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(formatter)
+    }
+}
+
+impl Error for PostError {}
+
+impl From<GraphError> for PostError {
+    fn from(value: GraphError) -> Self {
+        Self::new(value.to_string())
+    }
+}
+
+impl From<gpu::GpuError> for PostError {
+    fn from(value: gpu::GpuError) -> Self {
+        Self::new(value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plan_alternates_intermediates_and_ends_at_the_target() {
+        let steps = plan(
+            &[
+                PostEffect::Bloom(Bloom {
+                    passes: 1,
+                    ..Default::default()
+                }),
+                PostEffect::Tonemap(Tonemap::default()),
+                PostEffect::Fxaa(Fxaa::default()),
+            ],
+            false,
+        );
+        let routes = steps
+            .iter()
+            .map(|step| (step.input, step.output))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            routes,
+            vec![
+                (Slot::Source, Slot::Bloom(0)),
+                (Slot::Bloom(0), Slot::Bloom(1)),
+                (Slot::Bloom(1), Slot::Bloom(0)),
+                (Slot::Source, Slot::Ping(0)),
+                (Slot::Ping(0), Slot::Ping(1)),
+                (Slot::Ping(1), Slot::Target),
+            ]
+        );
+        assert_eq!(steps[3].secondary, Some(Slot::Bloom(0)));
+        assert_eq!(plan(&[], false)[0].shader, ShaderKey::Builtin("fs_copy"));
+        assert_eq!(
+            intermediate_slots(&steps),
+            [Slot::Bloom(0), Slot::Bloom(1), Slot::Ping(0), Slot::Ping(1)]
+        );
+        assert!(
+            intermediate_slots(&plan(&[PostEffect::Tonemap(Tonemap::default())], false)).is_empty(),
+            "single effects lease nothing"
+        );
+    }
+
+    #[test]
+    fn bloom_without_passes_is_skipped() {
+        let off = PostEffect::Bloom(Bloom {
+            passes: 0,
+            ..Default::default()
+        });
+        let steps = plan(&[off.clone(), PostEffect::Fxaa(Fxaa::default())], false);
+        assert_eq!(steps.len(), 1);
+        assert_eq!(steps[0].shader, ShaderKey::Builtin("fs_fxaa"));
+        assert_eq!(
+            (steps[0].input, steps[0].output),
+            (Slot::Source, Slot::Target)
+        );
+        let steps = plan(&[PostEffect::Tonemap(Tonemap::default()), off], false);
+        assert_eq!(steps.len(), 1, "the tonemap writes the target directly");
+        assert_eq!(steps[0].output, Slot::Target);
+    }
+
+    #[test]
+    fn only_the_last_tonemap_encodes_for_linear_targets() {
+        let effects = [
+            PostEffect::Tonemap(Tonemap::default()),
+            PostEffect::Tonemap(Tonemap::default()),
+            PostEffect::Fxaa(Fxaa::default()),
+        ];
+        let encoded = plan(&effects, true)
+            .iter()
+            .map(|step| step.values[2])
+            .collect::<Vec<_>>();
+        assert_eq!(encoded, [0.0, 1.0, 0.0]);
+        assert!(
+            plan(&effects, false)
+                .iter()
+                .all(|step| step.values[2] == 0.0)
+        );
+        assert_eq!(needs_srgb_encode(gpu::TextureFormat::Bgra8Unorm), Ok(true));
+        assert_eq!(needs_srgb_encode(gpu::TextureFormat::R8Unorm), Ok(true));
+        assert_eq!(
+            needs_srgb_encode(gpu::TextureFormat::Rgba8UnormSrgb),
+            Ok(false)
+        );
+        assert_eq!(
+            needs_srgb_encode(gpu::TextureFormat::Bgra8UnormSrgb),
+            Ok(false)
+        );
+        assert_eq!(
+            needs_srgb_encode(gpu::TextureFormat::Rgba16Float),
+            Ok(false)
+        );
+        assert!(needs_srgb_encode(gpu::TextureFormat::R32Float).is_err());
+        assert!(needs_srgb_encode(gpu::TextureFormat::Depth32Float).is_err());
+        assert!(needs_srgb_encode(gpu::TextureFormat::Bc7RgbaUnormSrgb).is_err());
+    }
+}
@@ -0,0 +1,119 @@
+@fragment
+fn fs_copy(input: FullscreenOut) -> @location(0) vec4<f32> {
+    return textureSample(input_texture, input_sampler, input.uv);
+}
+
+fn aces(color: vec3<f32>) -> vec3<f32> {
+    let a = color * (2.51 * color + 0.03);
+    let b = color * (2.43 * color + 0.59) + 0.14;
+    return clamp(a / b, vec3<f32>(0.0), vec3<f32>(1.0));
+}
+
+fn srgb_encode(color: vec3<f32>) -> vec3<f32> {
+    let low = color * 12.92;
+    let high = 1.055 * pow(color, vec3<f32>(1.0 / 2.4)) - 0.055;
+    return select(high, low, color <= vec3<f32>(0.0031308));
+}
+
+// values: x = exposure, y = operator (0 ACES, 1 Reinhard, 2 clamp),
+// z = 1 to apply the sRGB transfer curve for targets without sRGB encoding.
+@fragment
+fn fs_tonemap(input: FullscreenOut) -> @location(0) vec4<f32> {
+    let sample = textureSample(input_texture, input_sampler, input.uv);
+    let color = max(sample.rgb * params.values.x, vec3<f32>(0.0));
+    var mapped = clamp(color, vec3<f32>(0.0), vec3<f32>(1.0));
+    if params.values.y < 0.5 {
+        mapped = aces(color);
+    } else if params.values.y < 1.5 {
+        mapped = color / (1.0 + color);
+    }
+    if params.values.z > 0.5 {
+        mapped = srgb_encode(mapped);
+    }
+    return vec4<f32>(mapped, sample.a);
+}
+
+// values: x = threshold, y = soft knee. Downsamples with a 4-tap box.
+@fragment
+fn fs_bloom_extract(input: FullscreenOut) -> @location(0) vec4<f32> {
+    let offset = params.texel_size * 0.5;
+    let color = 0.25 * (
+        textureSample(input_texture, input_sampler, input.uv + vec2<f32>(-offset.x, -offset.y)).rgb +
+        textureSample(input_texture, input_sampler, input.uv + vec2<f32>(offset.x, -offset.y)).rgb +
+        textureSample(input_texture, input_sampler, input.uv + vec2<f32>(-offset.x, offset.y)).rgb +
+        textureSample(input_texture, input_sampler, input.uv + vec2<f32>(offset.x, offset.y)).rgb
+    );
+    let brightness = max(color.r, max(color.g, color.b));
+    let knee = max(params.values.x * params.values.y, 1e-4);
+    let soft = clamp(brightness - params.values.x + knee, 0.0, 2.0 * knee);
+    let contribution = max(soft * soft / (4.0 * knee), brightness - params.values.x);
+    return vec4<f32>(color * max(contribution, 0.0) / max(brightness, 1e-4), 1.0);
+}
+
+// values.xy = blur direction in texels.
+@fragment
+fn fs_blur(input: FullscreenOut) -> @location(0) vec4<f32> {
+    let step = params.values.xy * params.texel_size;
+    let weights = array<f32, 5>(0.227027, 0.1945946, 0.1216216, 0.054054, 0.016216);
+    var color = textureSample(input_texture, input_sampler, input.uv).rgb * weights[0];
+    for (var index = 1; index < 5; index += 1) {
+        let offset = step * f32(index);
+        color += textureSample(input_texture, input_sampler, input.uv + offset).rgb * weights[index];
+        color += textureSample(input_texture, input_sampler, input.uv - offset).rgb * weights[index];
+    }
+    return vec4<f32>(color, 1.0);
+}
+
+// values.x = bloom intensity; the secondary texture holds the blurred bloom.
+@fragment
+fn fs_bloom_combine(input: FullscreenOut) -> @location(0) vec4<f32> {
+    let scene = textureSample(input_texture, input_sampler, input.uv);
+    let bloom = textureSample(secondary_texture, input_sampler, input.uv).rgb;
+    return vec4<f32>(scene.rgb + bloom * params.values.x, scene.a);
+}
+
+// values: x = intensity, y = radius, z = smoothness; extra.rgb = color.
+@fragment
+fn fs_vignette(input: FullscreenOut) -> @location(0) vec4<f32> {
+    let sample = textureSample(input_texture, input_sampler, input.uv);
+    let distance = length(input.uv - vec2<f32>(0.5)) * 1.41421356;
+    let edge = smoothstep(params.values.y, params.values.y + params.values.z, distance);
+    let amount = clamp(edge * params.values.x, 0.0, 1.0);
+    return vec4<f32>(mix(sample.rgb, params.extra.rgb, amount), sample.a);
+}
+
+// values: x = edge threshold, y = maximum search span in texels.
+@fragment
+fn fs_fxaa(input: FullscreenOut) -> @location(0) vec4<f32> {
+    let texel = params.texel_size;
+    let center = textureSample(input_texture, input_sampler, input.uv);
+    let north_west = luminance(textureSample(input_texture, input_sampler, input.uv + vec2<f32>(-1.0, -1.0) * texel).rgb);
+    let north_east = luminance(textureSample(input_texture, input_sampler, input.uv + vec2<f32>(1.0, -1.0) * texel).rgb);
+    let south_west = luminance(textureSample(input_texture, input_sampler, input.uv + vec2<f32>(-1.0, 1.0) * texel).rgb);
+    let south_east = luminance(textureSample(input_texture, input_sampler, input.uv + vec2<f32>(1.0, 1.0) * texel).rgb);
+    let middle = luminance(center.rgb);
+    let luma_min = min(middle, min(min(north_west, north_east), min(south_west, south_east)));
+    let luma_max = max(middle, max(max(north_west, north_east), max(south_west, south_east)));
+
+    var direction = vec2<f32>(
+        (south_west + south_east) - (north_west + north_east),
+        (north_west + south_west) - (north_east + south_east),
+    );
+    let reduce = max((north_west + north_east + south_west + south_east) * 0.03125, 1.0 / 128.0);
+    let scale = 1.0 / (min(abs(direction.x), abs(direction.y)) + reduce);
+    let span = params.values.y;
+    direction = clamp(direction * scale, vec2<f32>(-span), vec2<f32>(span)) * texel;
+
+    let near = 0.5 * (
+        textureSample(input_texture, input_sampler, input.uv + direction * (1.0 / 3.0 - 0.5)).rgb +
+        textureSample(input_texture, input_sampler, input.uv + direction * (2.0 / 3.0 - 0.5)).rgb
+    );
+    let far = near * 0.5 + 0.25 * (
+        textureSample(input_texture, input_sampler, input.uv - direction * 0.5).rgb +
+        textureSample(input_texture, input_sampler, input.uv + direction * 0.5).rgb
+    );
+    let far_luma = luminance(far);
+    let blended = select(far, near, far_luma < luma_min || far_luma > luma_max);
+    let is_edge = luma_max - luma_min >= max(params.values.x * luma_max, 1.0 / 32.0);
+    return vec4<f32>(select(center.rgb, blended, is_edge), center.a);
+}
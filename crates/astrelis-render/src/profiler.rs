@@ -0,0 +1,515 @@
+//! Backend-neutral GPU timestamp scopes.
+//!
+//! Scopes write encoder timestamps at their boundaries. At the end of a frame
+//! the queries are resolved into a readback buffer that is mapped once the
+//! queue reaches it, typically a few frames later, so collection never stalls
+//! the CPU on the GPU. Finished frames are forwarded to `astrelis-profiling`
+//! and kept for in-app overlays.
+//!
+//! The profiler's timeline lane is calibrated against the CPU clock when it
+//! is created and every few seconds after, through
+//! [`astrelis_profiling::gpu::calibrate_gpu_lane`] like the wgpu backend's
+//! own profiler, so GPU scopes line up with the CPU spans recorded around
+//! them. Recalibration reads its timestamps back on a later frame instead of
+//! waiting for the queue.
+
+use std::{
+    collections::VecDeque,
+    error::Error,
+    fmt,
+    task::{Context, Poll, Waker},
+    time::{Duration, Instant},
+};
+
+use astrelis_core::alloc::Pool;
+use astrelis_gpu::{self as gpu, backend::BackendFuture};
+use astrelis_profiling::{
+    Profiler,
+    data::GpuLaneId,
+    gpu::{GpuBackend, GpuClockSample, GpuFrame, GpuScope},
+};
+
+/// Frames whose results may be outstanding before new frames go untimed.
+const MAX_FRAMES_IN_FLIGHT: usize = 4;
+
+/// How often the lane's CPU-to-GPU clock offset is measured again, bounding
+/// drift between the two clocks.
+const CALIBRATION_INTERVAL: Duration = Duration::from_secs(5);
+
+struct Recorded {
+    label: String,
+    begin: u32,
+    end: u32,
+    nested: Vec<Recorded>,
+}
+
+struct Readback {
+    resolve: gpu::Buffer,
+    readback: gpu::Buffer,
+}
+
+impl Readback {
+    /// Unmaps the readback buffer so the next frame can copy into it.
+    fn unmap(&mut self) {
+        self.readback.unmap();
+    }
+}
+
+/// Two back-to-back timestamps on their way back to the CPU.
+struct Calibration {
+    readback: gpu::Buffer,
+    mapping: BackendFuture<Result<(), gpu::GpuError>>,
+    submitted_ns: u64,
+}
+
+struct InFlight {
+    scopes: Vec<Recorded>,
+    queries: u32,
+    buffers: Readback,
+    mapping: Option<BackendFuture<Result<(), gpu::GpuError>>>,
+}
+
+/// Per-queue GPU timestamp profiler.
+///
+/// This is the backend-neutral counterpart of the wgpu backend's
+/// `WgpuGpuProfiler`. Each registers its own timeline lane, so create one or
+/// the other per queue, not both.
+///
+/// Call [`GpuProfiler::scope`] around recorded work,
+/// [`GpuProfiler::resolve_frame`] before finishing the last encoder,
+/// [`GpuProfiler::end_frame`] right after submission, and
+/// [`GpuProfiler::process_finished_frames`] once per frame.
+pub struct GpuProfiler {
+    device: gpu::Device,
+    queue: gpu::Queue,
+    query_set: gpu::QuerySet,
+    capacity: u32,
+    lane: GpuLaneId,
+    next_query: u32,
+    open: Vec<Recorded>,
+    recorded: Vec<Recorded>,
+    dropped: u32,
+    resolved: Option<InFlight>,
+    in_flight: VecDeque<InFlight>,
+    spare: Pool<Readback>,
+    last_frame: Option<GpuFrame>,
+    calibration: Option<Calibration>,
+    last_calibration: Instant,
+}
+
+impl GpuProfiler {
+    /// Creates a profiler able to time up to `max_scopes` scopes per frame.
+    ///
+    /// Fails unless the device enables timestamp queries inside encoders.
+    /// Calibrating the lane's clock waits for the queue to go idle once.
+    pub fn new(
+        device: gpu::Device,
+        queue: gpu::Queue,
+        label: Option<&str>,
+        max_scopes: u32,
+    ) -> Result<Self, ProfilerError> {
+        if device.id() != queue.device_id() {
+            return Err(ProfilerError::new("device and queue do not match"));
+        }
+        let capabilities = device.capabilities();
+        let required =
+            gpu::Features::TIMESTAMP_QUERY | gpu::Features::TIMESTAMP_QUERY_INSIDE_ENCODERS;
+        if !capabilities.features.contains(required) {
+            return Err(ProfilerError::new(
+                "GPU profiling requires timestamp queries inside command encoders",
+            ));
+        }
+        if !capabilities.reliable_timestamps {
+            return Err(ProfilerError::new(
+                "timestamp queries are unreliable on this backend",
+            ));
+        }
+        if max_scopes == 0 {
+            return Err(ProfilerError::new("GPU profiler needs at least one scope"));
+        }
+        let capacity = max_scopes.saturating_mul(2).min(4096);
+        let query_set = device.create_query_set(gpu::QuerySetDescriptor {
+            label: Some("gpu profiler queries".into()),
+            query_type: gpu::QueryType::Timestamp,
+            count: capacity,
+        });
+        let mut calibration = Calibration::submit(&device, &queue)?;
+        device.poll(gpu::PollMode::Wait)?;
+        let sample = calibration
+            .poll(f64::from(queue.timestamp_period()))?
+            .ok_or_else(|| ProfilerError::new("timestamp calibration readback did not complete"))?;
+        let lane = astrelis_profiling::gpu::register_gpu_lane(backend(capabilities.api), label);
+        astrelis_profiling::gpu::calibrate_gpu_lane(lane, sample);
+        Ok(Self {
+            device,
+            queue,
+            query_set,
+            capacity,
+            lane,
+            next_query: 0,
+            open: Vec::new(),
+            recorded: Vec::new(),
+            dropped: 0,
+            resolved: None,
+            in_flight: VecDeque::new(),
+            spare: Pool::new(Readback::unmap),
+            last_frame: None,
+            calibration: None,
+            last_calibration: Instant::now(),
+        })
+    }
+
+    /// Profiler timeline lane assigned to this queue.
+    pub fn lane(&self) -> GpuLaneId {
+        self.lane
+    }
+
+    /// Most recent completed frame, for overlays and inspectors.
+    pub fn last_frame(&self) -> Option<&GpuFrame> {
+        self.last_frame.as_ref()
+    }
+
+    /// Scopes left untimed because the per-frame query budget was exhausted
+    /// or too many frames were in flight.
+    pub fn dropped_scopes(&self) -> u32 {
+        self.dropped
+    }
+
+    /// Times the commands `record` appends to `encoder`.
+    ///
+    /// Scopes nest. Work recorded while over budget still runs, untimed.
+    pub fn scope<R>(
+        &mut self,
+        label: impl Into<String>,
+        encoder: &mut gpu::CommandEncoder,
+        record: impl FnOnce(&mut Self, &mut gpu::CommandEncoder) -> R,
+    ) -> Result<R, ProfilerError> {
+        let timed =
+            self.next_query + 2 <= self.capacity && self.in_flight.len() < MAX_FRAMES_IN_FLIGHT;
+        if !timed {
+            self.dropped += 1;
+            return Ok(record(self, encoder));
+        }
+        let begin = self.next_query;
+        self.next_query += 2;
+        encoder.write_timestamp(&self.query_set, begin)?;
+        self.open.push(Recorded {
+            label: label.into(),
+            begin,
+            end: begin + 1,
+            nested: Vec::new(),
+        });
+        let result = record(self, encoder);
+        let scope = self.open.pop().expect("scope pushed above");
+        encoder.write_timestamp(&self.query_set, scope.end)?;
+        match self.open.last_mut() {
+            Some(parent) => parent.nested.push(scope),
+            None => self.recorded.push(scope),
+        }
+        Ok(result)
+    }
+
+    /// Appends query resolve commands before the frame's last encoder is
+    /// finished.
+    pub fn resolve_frame(
+        &mut self,
+        encoder: &mut gpu::CommandEncoder,
+    ) -> Result<(), ProfilerError> {
+        if !self.open.is_empty() {
+            return Err(ProfilerError::new("resolve_frame called inside a scope"));
+        }
+        if self.resolved.is_some() {
+            return Err(ProfilerError::new("frame was already resolved"));
+        }
+        let queries = std::mem::take(&mut self.next_query);
+        let scopes = std::mem::take(&mut self.recorded);
+        if queries == 0 {
+            return Ok(());
+        }
+        let buffers = self.spare.acquire_with(|| {
+            let size = u64::from(self.capacity) * 8;
+            Readback {
+                resolve: self.device.create_buffer(gpu::BufferDescriptor {
+                    label: Some("gpu profiler resolve".into()),
+                    size,
+                    usage: gpu::BufferUsages::QUERY_RESOLVE | gpu::BufferUsages::COPY_SRC,
+                    mapped_at_creation: false,
+                }),
+                readback: self.device.create_buffer(gpu::BufferDescriptor {
+                    label: Some("gpu profiler readback".into()),
+                    size,
+                    usage: gpu::BufferUsages::COPY_DST | gpu::BufferUsages::MAP_READ,
+                    mapped_at_creation: false,
+                }),
+            }
+        });
+        encoder.resolve_query_set(&self.query_set, 0..queries, &buffers.resolve, 0)?;
+        encoder.copy_buffer_to_buffer(
+            &buffers.resolve,
+            0,
+            &buffers.readback,
+            0,
+            u64::from(queries) * 8,
+        )?;
+        self.resolved = Some(InFlight {
+            scopes,
+            queries,
+            buffers,
+            mapping: None,
+        });
+        Ok(())
+    }
+
+    /// Marks the resolved frame submitted and starts reading it back.
+    ///
+    /// Call immediately after the queue submission containing
+    /// [`GpuProfiler::resolve_frame`].
+    pub fn end_frame(&mut self) {
+        if let Some(mut frame) = self.resolved.take() {
+            let size = u64::from(frame.queries) * 8;
+            frame.mapping = Some(
+                frame
+                    .buffers
+                    .readback
+                    .map_async(gpu::MapMode::Read, 0..size),
+            );
+            self.in_flight.push_back(frame);
+        }
+    }
+
+    /// Collects every frame whose results are available without blocking and
+    /// reports it to the profiler timeline. Returns the number processed.
+    ///
+    /// Every few seconds this also submits a clock calibration, which a later
+    /// call applies once its timestamps have been read back.
+    pub fn process_finished_frames(&mut self) -> Result<usize, ProfilerError> {
+        self.device.poll(gpu::PollMode::Poll)?;
+        let period = f64::from(self.queue.timestamp_period());
+        self.recalibrate(period)?;
+        let mut context = Context::from_waker(Waker::noop());
+        let mut processed = 0;
+        while let Some(frame) = self.in_flight.front_mut() {
+            let mapping = frame
+                .mapping
+                .as_mut()
+                .expect("in-flight frames are mapping");
+            let status = mapping.as_mut().poll(&mut context);
+            let Poll::Ready(status) = status else {
+                break;
+            };
+            let frame = self.in_flight.pop_front().expect("front exists");
+            status?;
+            let size = u64::from(frame.queries) * 8;
+            let bytes = frame.buffers.readback.read_mapped(0..size);
+            self.spare.release(frame.buffers);
+            let ticks = bytes?
+                .chunks_exact(8)
+                .map(|chunk| u64::from_le_bytes(chunk.try_into().expect("eight bytes")))
+                .collect::<Vec<_>>();
+            let gpu_frame = GpuFrame {
+                scopes: frame
+                    .scopes
+                    .iter()
+                    .filter_map(|scope| convert(scope, &ticks, period))
+                    .collect(),
+            };
+            astrelis_profiling::gpu::report_gpu_frame(self.lane, gpu_frame.clone());
+            self.last_frame = Some(gpu_frame);
+            processed += 1;
+        }
+        Ok(processed)
+    }
+
+    /// Applies a finished calibration, or submits the next one when due.
+    fn recalibrate(&mut self, period: f64) -> Result<(), ProfilerError> {
+        match &mut self.calibration {
+            Some(calibration) => {
+                let sample = calibration.poll(period);
+                if !matches!(sample, Ok(None)) {
+                    self.calibration = None;
+                    self.last_calibration = Instant::now();
+                }
+                if let Some(sample) = sample? {
+                    astrelis_profiling::gpu::calibrate_gpu_lane(self.lane, sample);
+                }
+            }
+            None if self.last_calibration.elapsed() >= CALIBRATION_INTERVAL => {
+                self.calibration = Some(Calibration::submit(&self.device, &self.queue)?);
+            }
+            None => {}
+        }
+        Ok(())
+    }
+}
+
+impl Calibration {
+    /// Submits two back-to-back timestamps and starts mapping them for
+    /// reading.
+    fn submit(device: &gpu::Device, queue: &gpu::Queue) -> Result<Self, ProfilerError> {
+        let query_set = device.create_query_set(gpu::QuerySetDescriptor {
+            label: Some("gpu profiler calibration".into()),
+            query_type: gpu::QueryType::Timestamp,
+            count: 2,
+        });
+        let resolve = device.create_buffer(gpu::BufferDescriptor {
+            label: Some("gpu profiler calibration resolve".into()),
+            size: 16,
+            usage: gpu::BufferUsages::QUERY_RESOLVE | gpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let readback = device.create_buffer(gpu::BufferDescriptor {
+            label: Some("gpu profiler calibration readback".into()),
+            size: 16,
+            usage: gpu::BufferUsages::COPY_DST | gpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let mut encoder = device.create_command_encoder(gpu::CommandEncoderDescriptor {
+            label: Some("gpu profiler calibration".into()),
+        });
+        encoder.write_timestamp(&query_set, 0)?;
+        encoder.write_timestamp(&query_set, 1)?;
+        encoder.resolve_query_set(&query_set, 0..2, &resolve, 0)?;
+        encoder.copy_buffer_to_buffer(&resolve, 0, &readback, 0, 16)?;
+        let submitted_ns = Profiler::get().clock.now_ns();
+        queue.submit([encoder.finish()?])?;
+        let mapping = readback.map_async(gpu::MapMode::Read, 0..16);
+        Ok(Self {
+            readback,
+            mapping,
+            submitted_ns,
+        })
+    }
+
+    /// The calibration sample once the timestamps have been read back,
+    /// bracketed by the submission and this call.
+    fn poll(&mut self, period: f64) -> Result<Option<GpuClockSample>, ProfilerError> {
+        let Poll::Ready(status) = self
+            .mapping
+            .as_mut()
+            .poll(&mut Context::from_waker(Waker::noop()))
+        else {
+            return Ok(None);
+        };
+        let read_ns = Profiler::get().clock.now_ns();
+        status?;
+        let bytes = self.readback.read_mapped(0..16);
+        self.readback.unmap();
+        let bytes = bytes?;
+        let tick = |range: std::ops::Range<usize>| {
+            u64::from_le_bytes(bytes[range].try_into().expect("eight bytes"))
+        };
+        let gpu_ns = timestamp_midpoint((tick(0..8), tick(8..16)), period).ok_or_else(|| {
+            ProfilerError::new("backend returned invalid timestamp calibration samples")
+        })?;
+        Ok(Some(GpuClockSample {
+            cpu_before_ns: self.submitted_ns,
+            cpu_after_ns: read_ns,
+            gpu_ns,
+        }))
+    }
+}
+
+/// Converts one recorded scope to nanoseconds, dropping scopes whose
+/// timestamps are missing or out of order.
+fn convert(scope: &Recorded, ticks: &[u64], period: f64) -> Option<GpuScope> {
+    let start = *ticks.get(scope.begin as usize)?;
+    let end = *ticks.get(scope.end as usize)?;
+    if start == 0 || end < start {
+        return None;
+    }
+    Some(GpuScope {
+        label: scope.label.clone(),
+        start_ns: (start as f64 * period) as i64,
+        end_ns: (end as f64 * period) as i64,
+        nested: scope
+            .nested
+            .iter()
+            .filter_map(|child| convert(child, ticks, period))
+            .collect(),
+    })
+}
+
+/// Midpoint of two GPU ticks in nanoseconds, or `None` when a query was not
+/// written or they are out of order.
+fn timestamp_midpoint(ticks: (u64, u64), period: f64) -> Option<u64> {
+    if ticks.0 == 0 || ticks.1 < ticks.0 {
+        return None;
+    }
+    Some((((u128::from(ticks.0) + u128::from(ticks.1)) / 2) as f64 * period) as u64)
+}
+
+fn backend(api: gpu::GraphicsApi) -> GpuBackend {
+    match api {
+        gpu::GraphicsApi::Vulkan => GpuBackend::Vulkan,
+        gpu::GraphicsApi::Metal => GpuBackend::Metal,
+        gpu::GraphicsApi::Dx12 => GpuBackend::Dx12,
+        gpu::GraphicsApi::Gl => GpuBackend::Gl,
+        gpu::GraphicsApi::WebGpu => GpuBackend::WebGpu,
+        _ => GpuBackend::Unknown,
+    }
+}
+
+/// GPU profiler failure.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProfilerError(String);
+
+impl ProfilerError {
+    fn new(message: impl Into<String>) -> Self {
+        Self(message.into())
+    }
+}
+
+impl fmt::Display for ProfilerError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(formatter)
+    }
+}
+
+impl Error for ProfilerError {}
+
+impl From<gpu::GpuError> for ProfilerError {
+    fn from(value: gpu::GpuError) -> Self {
+        Self::new(value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn calibration_ticks_meet_in_the_middle() {
+        assert_eq!(timestamp_midpoint((40, 60), 2.0), Some(100));
+        assert_eq!(timestamp_midpoint((1_000, 1_000), 1.0), Some(1_000));
+        assert_eq!(timestamp_midpoint((0, 5), 1.0), None, "unwritten query");
+        assert_eq!(timestamp_midpoint((9, 5), 1.0), None, "ticks out of order");
+    }
+
+    #[test]
+    fn converts_nested_scopes_and_skips_unwritten_queries() {
+        let scope = Recorded {
+            label: "frame".into(),
+            begin: 0,
+            end: 1,
+            nested: vec![
+                Recorded {
+                    label: "ui".into(),
+                    begin: 2,
+                    end: 3,
+                    nested: Vec::new(),
+                },
+                Recorded {
+                    label: "lost".into(),
+                    begin: 4,
+                    end: 5,
+                    nested: Vec::new(),
+                },
+            ],
+        };
+        let converted = convert(&scope, &[10, 50, 20, 30, 0, 0], 2.0).unwrap();
+        assert_eq!((converted.start_ns, converted.end_ns), (20, 100));
+        assert_eq!(converted.nested.len(), 1);
+        assert_eq!(converted.nested[0].label, "ui");
+        assert_eq!(converted.nested[0].end_ns, 60);
+    }
+}
@@ -0,0 +1,268 @@
+//! WGSL modules that can be edited while the application runs.
+//!
+//! A [`ShaderRegistry`] owns shader modules loaded from files or strings and
+//! the render pipelines built from them. [`ShaderRegistry::poll_changes`]
+//! compares file modification times, recompiles edited files, and rebuilds
+//! every dependent pipeline. A shader that fails to compile keeps its previous
+//! module and pipelines, and the failure is reported instead of panicking, so
+//! a typo never takes down the running frame.
+//!
+//! Compilation and every pipeline build run inside a validation error scope
+//! that is awaited, so an invalid shader or a pipeline the new module no
+//! longer satisfies is reported once, through the returned [`ShaderError`],
+//! and never reaches the device error handler. Native backends finish
+//! compiling immediately, so `pollster::block_on` is enough to drive these
+//! futures outside an async runtime.
+
+use std::{
+    error::Error,
+    fmt, fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use astrelis_gpu as gpu;
+
+/// Handle to a shader module owned by a [`ShaderRegistry`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ShaderId(u32);
+
+/// Handle to a render pipeline rebuilt by a [`ShaderRegistry`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ShaderPipelineId(u32);
+
+/// Outcome of reloading one edited shader.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShaderReload {
+    /// The edited shader.
+    pub shader: ShaderId,
+    /// Success, or the error that left the previous module in place.
+    pub result: Result<(), ShaderError>,
+}
+
+type PipelineBuilder =
+    Box<dyn FnMut(&gpu::ShaderModule) -> Result<gpu::RenderPipeline, gpu::GpuError>>;
+
+struct ShaderEntry {
+    label: String,
+    path: Option<PathBuf>,
+    modified: Option<SystemTime>,
+    module: gpu::ShaderModule,
+    generation: u64,
+}
+
+struct PipelineEntry {
+    shader: ShaderId,
+    build: PipelineBuilder,
+    pipeline: gpu::RenderPipeline,
+}
+
+/// Shader modules and their dependent pipelines for one device.
+pub struct ShaderRegistry {
+    device: gpu::Device,
+    shaders: Vec<ShaderEntry>,
+    pipelines: Vec<PipelineEntry>,
+}
+
+impl ShaderRegistry {
+    /// Creates an empty registry.
+    pub fn new(device: gpu::Device) -> Self {
+        Self {
+            device,
+            shaders: Vec::new(),
+            pipelines: Vec::new(),
+        }
+    }
+
+    /// Loads and compiles a WGSL file that is watched for changes.
+    pub async fn load(&mut self, path: impl AsRef<Path>) -> Result<ShaderId, ShaderError> {
+        let path = path.as_ref().to_path_buf();
+        let (source, modified) = read(&path)?;
+        let label = path.display().to_string();
+        let module = self.compile(&label, source).await?;
+        Ok(self.push(label, Some(path), modified, module))
+    }
+
+    /// Compiles in-memory WGSL, such as source embedded with `include_str!`.
+    ///
+    /// Inline shaders are never reloaded by [`ShaderRegistry::poll_changes`];
+    /// use [`ShaderRegistry::replace_source`] to update them.
+    pub async fn insert(
+        &mut self,
+        label: impl Into<String>,
+        wgsl: impl Into<String>,
+    ) -> Result<ShaderId, ShaderError> {
+        let label = label.into();
+        let module = self.compile(&label, wgsl.into()).await?;
+        Ok(self.push(label, None, None, module))
+    }
+
+    /// Current module for a shader.
+    pub fn module(&self, shader: ShaderId) -> &gpu::ShaderModule {
+        &self.shaders[shader.0 as usize].module
+    }
+
+    /// Number of successful compilations after the first, useful for
+    /// invalidating caches derived from a shader.
+    pub fn generation(&self, shader: ShaderId) -> u64 {
+        self.shaders[shader.0 as usize].generation
+    }
+
+    /// Registers a pipeline that is rebuilt whenever `shader` reloads.
+    ///
+    /// `build` receives the current module and runs once immediately, inside
+    /// a validation error scope; a validation error fails the registration.
+    pub async fn add_pipeline(
+        &mut self,
+        shader: ShaderId,
+        mut build: impl FnMut(&gpu::ShaderModule) -> Result<gpu::RenderPipeline, gpu::GpuError>
+        + 'static,
+    ) -> Result<ShaderPipelineId, ShaderError> {
+        let entry = &self.shaders[shader.0 as usize];
+        let pipeline = self
+            .device
+            .validated(|| build(&entry.module))
+            .await
+            .and_then(|built| built)
+            .map_err(|error| ShaderError::gpu(&entry.label, error))?;
+        self.pipelines.push(PipelineEntry {
+            shader,
+            build: Box::new(build),
+            pipeline,
+        });
+        Ok(ShaderPipelineId(self.pipelines.len() as u32 - 1))
+    }
+
+    /// Current pipeline for a registration.
+    pub fn pipeline(&self, pipeline: ShaderPipelineId) -> &gpu::RenderPipeline {
+        &self.pipelines[pipeline.0 as usize].pipeline
+    }
+
+    /// Recompiles an inline or file-backed shader from new source.
+    pub async fn replace_source(
+        &mut self,
+        shader: ShaderId,
+        wgsl: impl Into<String>,
+    ) -> Result<(), ShaderError> {
+        let label = self.shaders[shader.0 as usize].label.clone();
+        let module = self.compile(&label, wgsl.into()).await?;
+        self.swap(shader, module).await
+    }
+
+    /// Reloads every file whose modification time changed since it was last
+    /// read, returning one entry per attempted reload.
+    pub async fn poll_changes(&mut self) -> Vec<ShaderReload> {
+        let mut reloads = Vec::new();
+        for index in 0..self.shaders.len() {
+            let entry = &self.shaders[index];
+            let Some(path) = entry.path.clone() else {
+                continue;
+            };
+            let modified = fs::metadata(&path)
+                .and_then(|metadata| metadata.modified())
+                .ok();
+            if modified.is_none() || modified == entry.modified {
+                continue;
+            }
+            let shader = ShaderId(index as u32);
+            let result = match read(&path) {
+                Ok((source, modified)) => {
+                    // Record the attempt even on failure so a broken file is
+                    // reported once per save rather than every poll.
+                    self.shaders[index].modified = modified;
+                    let label = self.shaders[index].label.clone();
+                    match self.compile(&label, source).await {
+                        Ok(module) => self.swap(shader, module).await,
+                        Err(error) => Err(error),
+                    }
+                }
+                Err(error) => Err(error),
+            };
+            reloads.push(ShaderReload { shader, result });
+        }
+        reloads
+    }
+
+    fn push(
+        &mut self,
+        label: String,
+        path: Option<PathBuf>,
+        modified: Option<SystemTime>,
+        module: gpu::ShaderModule,
+    ) -> ShaderId {
+        self.shaders.push(ShaderEntry {
+            label,
+            path,
+            modified,
+            module,
+            generation: 0,
+        });
+        ShaderId(self.shaders.len() as u32 - 1)
+    }
+
+    async fn compile(&self, label: &str, wgsl: String) -> Result<gpu::ShaderModule, ShaderError> {
+        self.device
+            .create_shader_module_checked(gpu::ShaderModuleDescriptor {
+                label: Some(label.into()),
+                wgsl,
+            })
+            .await
+            .map_err(|error| ShaderError::gpu(label, error))
+    }
+
+    /// Rebuilds dependents against `module`, each inside a validation error
+    /// scope, committing only if every build and scope succeeds.
+    async fn swap(
+        &mut self,
+        shader: ShaderId,
+        module: gpu::ShaderModule,
+    ) -> Result<(), ShaderError> {
+        let label = &self.shaders[shader.0 as usize].label;
+        let mut rebuilt = Vec::new();
+        for (index, entry) in self.pipelines.iter_mut().enumerate() {
+            if entry.shader == shader {
+                let pipeline = self
+                    .device
+                    .validated(|| (entry.build)(&module))
+                    .await
+                    .and_then(|built| built)
+                    .map_err(|error| ShaderError::gpu(label, error))?;
+                rebuilt.push((index, pipeline));
+            }
+        }
+        for (index, pipeline) in rebuilt {
+            self.pipelines[index].pipeline = pipeline;
+        }
+        let entry = &mut self.shaders[shader.0 as usize];
+        entry.module = module;
+        entry.generation += 1;
+        Ok(())
+    }
+}
+
+fn read(path: &Path) -> Result<(String, Option<SystemTime>), ShaderError> {
+    let source = fs::read_to_string(path)
+        .map_err(|error| ShaderError(format!("{}: {error}", path.display())))?;
+    let modified = fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok();
+    Ok((source, modified))
+}
+
+/// Shader loading, compilation, or pipeline rebuild failure.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShaderError(String);
+
+impl ShaderError {
+    fn gpu(label: &str, error: gpu::GpuError) -> Self {
+        Self(format!("{label}: {error}"))
+    }
+}
+
+impl fmt::Display for ShaderError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(formatter)
+    }
+}
+
+impl Error for ShaderError {}
@@ -0,0 +1,242 @@
+//! Coalesced buffer uploads through reusable mapped staging chunks.
+
+use std::{
+    error::Error,
+    fmt,
+    task::{Context, Poll, Waker},
+};
+
+use astrelis_gpu::{self as gpu, backend::BackendFuture};
+
+/// Alignment of sub-allocations, satisfying both mapping and copy rules.
+const ALIGNMENT: u64 = 8;
+
+/// Chunks a belt may own before writes fail, bounding the memory held by
+/// callers that never recall.
+const MAX_CHUNKS: usize = 256;
+
+/// Recalls an oversized chunk may sit unused before it is dropped, so a
+/// one-off large upload does not pin its memory for the belt's lifetime.
+const OVERSIZE_IDLE_RECALLS: u32 = 60;
+
+/// Error returned by [`StagingBelt`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StagingError(String);
+
+impl StagingError {
+    fn new(message: impl Into<String>) -> Self {
+        Self(message.into())
+    }
+}
+
+impl fmt::Display for StagingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Error for StagingError {}
+
+impl From<gpu::GpuError> for StagingError {
+    fn from(error: gpu::GpuError) -> Self {
+        Self::new(error.to_string())
+    }
+}
+
+struct Chunk {
+    buffer: gpu::Buffer,
+    offset: u64,
+    /// Recalls since the chunk last became free.
+    idle: u32,
+}
+
+struct Recalling {
+    chunk: Chunk,
+    mapping: BackendFuture<Result<(), gpu::GpuError>>,
+}
+
+/// Streams many small buffer writes through a few large staging buffers.
+///
+/// Each [`StagingBelt::write_buffer`] copies into a chunk that is already
+/// mapped and records a `copy_buffer_to_buffer` into the caller's encoder,
+/// instead of making the queue allocate staging memory per write. The
+/// per-frame protocol is:
+///
+/// 1. [`StagingBelt::write_buffer`] any number of times, into any number of
+///    encoders;
+/// 2. [`StagingBelt::finish`] once before submitting those encoders;
+/// 3. [`StagingBelt::recall`] after submitting them, which remaps chunks so
+///    later frames reuse them once the GPU has consumed the copies.
+///
+/// Writes between two calls to `finish` share chunks, so finishing per
+/// encoder rather than per submission wastes a chunk each time. A belt that
+/// is never recalled stops growing after 256 chunks and reports an error.
+/// Chunks larger than the chunk size, made for oversize writes, are dropped
+/// once they go 60 recalls without being reused.
+pub struct StagingBelt {
+    device: gpu::Device,
+    chunk_size: u64,
+    active: Vec<Chunk>,
+    closed: Vec<Chunk>,
+    recalling: Vec<Recalling>,
+    free: Vec<Chunk>,
+}
+
+impl StagingBelt {
+    /// Creates a belt whose chunks hold at least `chunk_size` bytes. Larger
+    /// writes get a dedicated chunk of their own size.
+    pub fn new(device: gpu::Device, chunk_size: u64) -> Self {
+        Self {
+            device,
+            chunk_size: chunk_size.max(ALIGNMENT).next_multiple_of(ALIGNMENT),
+            active: Vec::new(),
+            closed: Vec::new(),
+            recalling: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+
+    /// Minimum chunk size in bytes.
+    pub fn chunk_size(&self) -> u64 {
+        self.chunk_size
+    }
+
+    /// Number of staging buffers owned by the belt, in any state.
+    pub fn chunk_count(&self) -> usize {
+        self.active.len() + self.closed.len() + self.recalling.len() + self.free.len()
+    }
+
+    /// Records a copy of `data` into `target` at `offset`.
+    ///
+    /// `offset` and the length of `data` must be multiples of four, and
+    /// `target` must have `COPY_DST` usage. The write becomes visible to
+    /// commands recorded after it in the same encoder. Fails when a new chunk
+    /// is needed but the belt already owns its maximum.
+    pub fn write_buffer(
+        &mut self,
+        encoder: &mut gpu::CommandEncoder,
+        target: &gpu::Buffer,
+        offset: u64,
+        data: &[u8],
+    ) -> Result<(), StagingError> {
+        if data.is_empty() {
+            return Ok(());
+        }
+        let size = data.len() as u64;
+        if !offset.is_multiple_of(4) || !size.is_multiple_of(4) {
+            return Err(StagingError::new(
+                "staged writes must be four-byte aligned in offset and size",
+            ));
+        }
+        let end = offset
+            .checked_add(size)
+            .ok_or_else(|| StagingError::new("staged write range overflows"))?;
+        if end > target.size() {
+            return Err(StagingError::new(format!(
+                "staged write ends at {end} but the buffer holds {} bytes",
+                target.size()
+            )));
+        }
+        let chunk = self.allocate(size)?;
+        let source_offset = chunk.offset;
+        chunk.buffer.write_mapped(source_offset, data)?;
+        chunk.offset = (source_offset + size).next_multiple_of(ALIGNMENT);
+        encoder.copy_buffer_to_buffer(&chunk.buffer, source_offset, target, offset, size)?;
+        Ok(())
+    }
+
+    /// Unmaps every chunk written this frame. Call before submitting the
+    /// encoders passed to [`StagingBelt::write_buffer`].
+    pub fn finish(&mut self) {
+        for chunk in self.active.drain(..) {
+            chunk.buffer.unmap();
+            self.closed.push(chunk);
+        }
+    }
+
+    /// Starts remapping chunks closed by [`StagingBelt::finish`], reclaims
+    /// those the GPU has finished with, and drops idle oversized chunks.
+    /// Call after submission; it never blocks.
+    pub fn recall(&mut self) -> Result<(), StagingError> {
+        for chunk in self.closed.drain(..) {
+            let size = chunk.buffer.size();
+            let mapping = chunk.buffer.map_async(gpu::MapMode::Write, 0..size);
+            self.recalling.push(Recalling { chunk, mapping });
+        }
+        self.device.poll(gpu::PollMode::Poll)?;
+        let mut context = Context::from_waker(Waker::noop());
+        let mut error = None;
+        let mut index = 0;
+        while index < self.recalling.len() {
+            let Poll::Ready(status) = self.recalling[index].mapping.as_mut().poll(&mut context)
+            else {
+                index += 1;
+                continue;
+            };
+            let Recalling { mut chunk, .. } = self.recalling.swap_remove(index);
+            match status {
+                Ok(()) => {
+                    chunk.offset = 0;
+                    chunk.idle = 0;
+                    self.free.push(chunk);
+                }
+                Err(failure) => error = Some(failure),
+            }
+        }
+        let chunk_size = self.chunk_size;
+        self.free.retain_mut(|chunk| {
+            chunk.idle += 1;
+            chunk.buffer.size() <= chunk_size || chunk.idle <= OVERSIZE_IDLE_RECALLS
+        });
+        match error {
+            Some(error) => Err(error.into()),
+            None => Ok(()),
+        }
+    }
+
+    fn allocate(&mut self, size: u64) -> Result<&mut Chunk, StagingError> {
+        if let Some(index) = self
+            .active
+            .iter()
+            .position(|chunk| chunk.offset + size <= chunk.buffer.size())
+        {
+            return Ok(&mut self.active[index]);
+        }
+        let chunk = match self
+            .free
+            .iter()
+            .position(|chunk| chunk.buffer.size() >= size)
+        {
+            Some(index) => self.free.swap_remove(index),
+            None if self.chunk_count() >= MAX_CHUNKS => {
+                return Err(StagingError::new(format!(
+                    "staging belt already owns {MAX_CHUNKS} chunks; call recall after each submission"
+                )));
+            }
+            None => Chunk {
+                buffer: self.device.create_buffer(gpu::BufferDescriptor {
+                    label: Some("staging belt chunk".into()),
+                    size: size.max(self.chunk_size).next_multiple_of(ALIGNMENT),
+                    usage: gpu::BufferUsages::MAP_WRITE | gpu::BufferUsages::COPY_SRC,
+                    mapped_at_creation: true,
+                }),
+                offset: 0,
+                idle: 0,
+            },
+        };
+        self.active.push(chunk);
+        Ok(self.active.last_mut().expect("chunk was pushed"))
+    }
+}
+
+impl fmt::Debug for StagingBelt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StagingBelt")
+            .field("chunk_size", &self.chunk_size)
+            .field("active", &self.active.len())
+            .field("closed", &self.closed.len())
+            .field("recalling", &self.recalling.len())
+            .field("free", &self.free.len())
+            .finish()
+    }
+}
@@ -0,0 +1,672 @@
+//! Sampled 2D textures with mip chains.
+//!
+//! A [`TextureLoader`] uploads decoded RGBA8 pixels or KTX2 containers and
+//! returns a [`Texture2D`]. RGBA8 mip chains are generated on the GPU by a
+//! compute pass that box-filters each level in linear space, writes packed
+//! texels into a buffer, and copies them into the next level; this avoids
+//! storage textures, which sRGB formats cannot be bound as. Compressed KTX2
+//! files carry their own mip levels.
+
+use std::{error::Error, fmt};
+
+use astrelis_gpu as gpu;
+use bytemuck::{Pod, Zeroable};
+
+const MIPMAP_SHADER: &str = include_str!("mipmap.wgsl");
+const KTX2_IDENTIFIER: [u8; 12] = [
+    0xab, 0x4b, 0x54, 0x58, 0x20, 0x32, 0x30, 0xbb, 0x0d, 0x0a, 0x1a, 0x0a,
+];
+
+/// Transfer function of color data in a texture.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorSpace {
+    /// sRGB-encoded color, decoded to linear when sampled. Use for albedo,
+    /// sprites, and UI images.
+    #[default]
+    Srgb,
+    /// Values sampled as stored. Use for normal maps, masks, and other data.
+    Linear,
+}
+
+/// Settings applied when creating a [`Texture2D`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextureOptions {
+    /// Optional debug label.
+    pub label: Option<String>,
+    /// Transfer function, overriding the one recorded in a KTX2 file.
+    pub color_space: ColorSpace,
+    /// Generates a full mip chain for RGBA8 sources. KTX2 files keep the
+    /// levels they contain.
+    pub generate_mipmaps: bool,
+}
+
+impl Default for TextureOptions {
+    fn default() -> Self {
+        Self {
+            label: None,
+            color_space: ColorSpace::Srgb,
+            generate_mipmaps: true,
+        }
+    }
+}
+
+/// An immutable sampled 2D texture and its full view.
+#[derive(Clone, Debug)]
+pub struct Texture2D {
+    texture: gpu::Texture,
+    view: gpu::TextureView,
+    width: u32,
+    height: u32,
+    format: gpu::TextureFormat,
+    mip_level_count: u32,
+}
+
+impl Texture2D {
+    /// The texture resource.
+    pub fn texture(&self) -> &gpu::Texture {
+        &self.texture
+    }
+
+    /// View covering every mip level.
+    pub fn view(&self) -> &gpu::TextureView {
+        &self.view
+    }
+
+    /// Width of the base level in texels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Height of the base level in texels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Texel format.
+    pub fn format(&self) -> gpu::TextureFormat {
+        self.format
+    }
+
+    /// Number of mip levels.
+    pub fn mip_level_count(&self) -> u32 {
+        self.mip_level_count
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct MipParams {
+    size: [u32; 2],
+    row_words: u32,
+    srgb: u32,
+}
+
+#[derive(Clone)]
+struct MipPipeline {
+    layout: gpu::BindGroupLayout,
+    pipeline: gpu::ComputePipeline,
+}
+
+/// Creates [`Texture2D`]s on one device, reusing the mip generation
+/// pipeline across loads.
+pub struct TextureLoader {
+    device: gpu::Device,
+    queue: gpu::Queue,
+    mip_pipeline: Option<MipPipeline>,
+}
+
+impl TextureLoader {
+    /// Creates a loader; the mip pipeline is built on first use.
+    pub fn new(device: gpu::Device, queue: gpu::Queue) -> Result<Self, TextureError> {
+        if device.id() != queue.device_id() {
+            return Err(TextureError::new("device and queue do not match"));
+        }
+        Ok(Self {
+            device,
+            queue,
+            mip_pipeline: None,
+        })
+    }
+
+    /// Uploads tightly packed, straight-alpha RGBA8 pixels, such as the
+    /// output of a PNG or JPEG decoder.
+    pub fn load_rgba8(
+        &mut self,
+        width: u32,
+        height: u32,
+        pixels: &[u8],
+        options: &TextureOptions,
+    ) -> Result<Texture2D, TextureError> {
+        if width == 0 || height == 0 {
+            return Err(TextureError::new("texture dimensions must be non-zero"));
+        }
+        if pixels.len() as u64 != u64::from(width) * u64::from(height) * 4 {
+            return Err(TextureError::new(format!(
+                "expected {} bytes of RGBA8 pixels for {width}x{height}, got {}",
+                u64::from(width) * u64::from(height) * 4,
+                pixels.len()
+            )));
+        }
+        let format = match options.color_space {
+            ColorSpace::Srgb => gpu::TextureFormat::Rgba8UnormSrgb,
+            ColorSpace::Linear => gpu::TextureFormat::Rgba8Unorm,
+        };
+        let mip_level_count = if options.generate_mipmaps {
+            full_mip_count(width, height)
+        } else {
+            1
+        };
+        let texture = self.create(options, width, height, format, mip_level_count);
+        self.write_level(&texture, format, 0, width, height, pixels)?;
+        if mip_level_count > 1 {
+            self.generate_mipmaps(&texture, format, width, height, mip_level_count)?;
+        }
+        Ok(finish(texture, width, height, format, mip_level_count))
+    }
+
+    /// Uploads a KTX2 container holding RGBA8, BC1, BC3, BC7, or ASTC 4x4
+    /// data without supercompression.
+    ///
+    /// Files declaring zero levels request generated mipmaps, which is only
+    /// possible for RGBA8 data.
+    pub fn load_ktx2(
+        &mut self,
+        bytes: &[u8],
+        options: &TextureOptions,
+    ) -> Result<Texture2D, TextureError> {
+        let ktx = Ktx2::parse(bytes)?;
+        let format = with_color_space(ktx.format, options.color_space);
+        let required = format.required_features();
+        if !self.device.capabilities().features.contains(required) {
+            return Err(TextureError::new(format!(
+                "{format:?} textures require {required:?}"
+            )));
+        }
+        check_block_dimensions(format, ktx.width, ktx.height)?;
+        let generate = ktx.levels.len() == 1 && ktx.generate_mipmaps;
+        if generate && format.is_compressed() {
+            return Err(TextureError::new(
+                "mipmaps cannot be generated for compressed KTX2 data",
+            ));
+        }
+        let mip_level_count = if generate {
+            full_mip_count(ktx.width, ktx.height)
+        } else {
+            ktx.levels.len() as u32
+        };
+        let texture = self.create(options, ktx.width, ktx.height, format, mip_level_count);
+        for (level, data) in ktx.levels.iter().enumerate() {
+            let level = level as u32;
+            let width = (ktx.width >> level).max(1);
+            let height = (ktx.height >> level).max(1);
+            self.write_level(&texture, format, level, width, height, data)?;
+        }
+        if generate {
+            self.generate_mipmaps(&texture, format, ktx.width, ktx.height, mip_level_count)?;
+        }
+        Ok(finish(
+            texture,
+            ktx.width,
+            ktx.height,
+            format,
+            mip_level_count,
+        ))
+    }
+
+    fn create(
+        &self,
+        options: &TextureOptions,
+        width: u32,
+        height: u32,
+        format: gpu::TextureFormat,
+        mip_level_count: u32,
+    ) -> gpu::Texture {
+        self.device.create_texture(gpu::TextureDescriptor {
+            label: options.label.clone(),
+            size: gpu::Extent3d::d2(width, height),
+            mip_level_count,
+            sample_count: 1,
+            dimension: gpu::TextureDimension::D2,
+            format,
+            usage: gpu::TextureUsages::TEXTURE_BINDING | gpu::TextureUsages::COPY_DST,
+        })
+    }
+
+    fn write_level(
+        &self,
+        texture: &gpu::Texture,
+        format: gpu::TextureFormat,
+        level: u32,
+        width: u32,
+        height: u32,
+        data: &[u8],
+    ) -> Result<(), TextureError> {
+        let (block_width, block_height) = format.block_dimensions();
+        let block_size = format
+            .block_copy_size()
+            .ok_or_else(|| TextureError::new("depth formats cannot be uploaded"))?;
+        let columns = width.div_ceil(block_width);
+        let rows = height.div_ceil(block_height);
+        let expected = u64::from(columns) * u64::from(rows) * u64::from(block_size);
+        if data.len() as u64 != expected {
+            return Err(TextureError::new(format!(
+                "mip level {level} holds {} bytes, expected {expected}",
+                data.len()
+            )));
+        }
+        self.queue.write_texture(
+            &gpu::TextureCopy {
+                texture: texture.clone(),
+                mip_level: level,
+                origin: gpu::Origin3d::default(),
+            },
+            data,
+            gpu::TextureDataLayout {
+                offset: 0,
+                bytes_per_row: Some(columns * block_size),
+                rows_per_image: Some(rows),
+            },
+            // Compressed copies cover whole blocks, even past the edge of
+            // levels smaller than a block.
+            gpu::Extent3d::d2(columns * block_width, rows * block_height),
+        )?;
+        Ok(())
+    }
+
+    fn generate_mipmaps(
+        &mut self,
+        texture: &gpu::Texture,
+        format: gpu::TextureFormat,
+        width: u32,
+        height: u32,
+        mip_level_count: u32,
+    ) -> Result<(), TextureError> {
+        let srgb = u32::from(format.is_srgb());
+        let mip = self.mip_pipeline()?.clone();
+        let mut encoder = self
+            .device
+            .create_command_encoder(gpu::CommandEncoderDescriptor {
+                label: Some("mipmap generation".into()),
+            });
+        for level in 1..mip_level_count {
+            let level_width = (width >> level).max(1);
+            let level_height = (height >> level).max(1);
+            // Buffer-to-texture copies need 256-byte aligned rows.
+            let row_bytes = (level_width * 4).next_multiple_of(256);
+            let staging = self.device.create_buffer(gpu::BufferDescriptor {
+                label: Some("mipmap staging".into()),
+                size: u64::from(row_bytes) * u64::from(level_height),
+                usage: gpu::BufferUsages::STORAGE | gpu::BufferUsages::COPY_SRC,
+                mapped_at_creation: false,
+            });
+            let params = self.device.create_buffer_init(
+                &self.queue,
+                Some("mipmap params".into()),
+                bytemuck::bytes_of(&MipParams {
+                    size: [level_width, level_height],
+                    row_words: row_bytes / 4,
+                    srgb,
+                }),
+                gpu::BufferUsages::UNIFORM,
+            )?;
+            let source = texture.create_view(gpu::TextureViewDescriptor {
+                label: Some("mipmap source".into()),
+                base_mip_level: level - 1,
+                mip_level_count: Some(1),
+                ..Default::default()
+            });
+            let bind_group = self.device.create_bind_group(gpu::BindGroupDescriptor {
+                label: Some("mipmap bind group".into()),
+                layout: mip.layout.clone(),
+                entries: vec![
+                    gpu::BindGroupEntry {
+                        binding: 0,
+                        resource: gpu::BindingResource::TextureView(source),
+                    },
+                    gpu::BindGroupEntry {
+                        binding: 1,
+                        resource: gpu::BindingResource::Buffer(gpu::BufferBinding {
+                            buffer: staging.clone(),
+                            offset: 0,
+                            size: None,
+                        }),
+                    },
+                    gpu::BindGroupEntry {
+                        binding: 2,
+                        resource: gpu::BindingResource::Buffer(gpu::BufferBinding {
+                            buffer: params,
+                            offset: 0,
+                            size: None,
+                        }),
+                    },
+                ],
+            })?;
+            {
+                let mut pass = encoder.begin_compute_pass(gpu::ComputePassDescriptor {
+                    label: Some(format!("mip level {level}")),
+                })?;
+                pass.set_pipeline(&mip.pipeline)?;
+                pass.set_bind_group(0, &bind_group, &[])?;
+                pass.dispatch_workgroups(level_width.div_ceil(8), level_height.div_ceil(8), 1);
+            }
+            encoder.copy_buffer_to_texture(
+                &gpu::BufferTextureCopy {
+                    buffer: staging,
+                    offset: 0,
+                    bytes_per_row: Some(row_bytes),
+                    rows_per_image: Some(level_height),
+                },
+                &gpu::TextureCopy {
+                    texture: texture.clone(),
+                    mip_level: level,
+                    origin: gpu::Origin3d::default(),
+                },
+                gpu::Extent3d::d2(level_width, level_height),
+            )?;
+        }
+        self.queue.submit([encoder.finish()?])?;
+        Ok(())
+    }
+
+    fn mip_pipeline(&mut self) -> Result<&MipPipeline, TextureError> {
+        if self.mip_pipeline.is_none() {
+            let layout = self
+                .device
+                .create_bind_group_layout(gpu::BindGroupLayoutDescriptor {
+                    label: Some("mipmap layout".into()),
+                    entries: vec![
+                        gpu::BindGroupLayoutEntry {
+                            binding: 0,
+                            visibility: gpu::ShaderStages::COMPUTE,
+                            ty: gpu::BindingType::Texture {
+                                sample_type: gpu::TextureSampleType::Float,
+                                view_dimension: gpu::TextureViewDimension::D2,
+                                multisampled: false,
+                            },
+                        },
+                        gpu::BindGroupLayoutEntry {
+                            binding: 1,
+                            visibility: gpu::ShaderStages::COMPUTE,
+                            ty: gpu::BindingType::Buffer {
+                                ty: gpu::BufferBindingType::Storage,
+                                has_dynamic_offset: false,
+                                min_binding_size: None,
+                            },
+                        },
+                        gpu::BindGroupLayoutEntry {
+                            binding: 2,
+                            visibility: gpu::ShaderStages::COMPUTE,
+                            ty: gpu::BindingType::Buffer {
+                                ty: gpu::BufferBindingType::Uniform,
+                                has_dynamic_offset: false,
+                                min_binding_size: None,
+                            },
+                        },
+                    ],
+                });
+            let pipeline_layout =
+                self.device
+                    .create_pipeline_layout(gpu::PipelineLayoutDescriptor {
+                        label: Some("mipmap pipeline layout".into()),
+                        bind_group_layouts: vec![layout.clone()],
+                    })?;
+            let module = self
+                .device
+                .create_shader_module(gpu::ShaderModuleDescriptor {
+                    label: Some("mipmap shader".into()),
+                    wgsl: MIPMAP_SHADER.into(),
+                });
+            let pipeline = self
+                .device
+                .create_compute_pipeline(gpu::ComputePipelineDescriptor {
+                    label: Some("mipmap pipeline".into()),
+                    layout: Some(pipeline_layout),
+                    module,
+                    entry_point: "cs_main".into(),
+                    cache: None,
+                })?;
+            self.mip_pipeline = Some(MipPipeline { layout, pipeline });
+        }
+        Ok(self.mip_pipeline.as_ref().expect("created above"))
+    }
+}
+
+fn finish(
+    texture: gpu::Texture,
+    width: u32,
+    height: u32,
+    format: gpu::TextureFormat,
+    mip_level_count: u32,
+) -> Texture2D {
+    let view = texture.create_view(Default::default());
+    Texture2D {
+        texture,
+        view,
+        width,
+        height,
+        format,
+        mip_level_count,
+    }
+}
+
+/// Rejects block-compressed images whose size is not a whole number of
+/// blocks. Smaller mip levels are padded to whole blocks by the format.
+fn check_block_dimensions(
+    format: gpu::TextureFormat,
+    width: u32,
+    height: u32,
+) -> Result<(), TextureError> {
+    let (block_width, block_height) = format.block_dimensions();
+    if !width.is_multiple_of(block_width) || !height.is_multiple_of(block_height) {
+        return Err(TextureError::new(format!(
+            "{format:?} textures must be a multiple of {block_width}x{block_height} texels, \
+             not {width}x{height}"
+        )));
+    }
+    Ok(())
+}
+
+fn full_mip_count(width: u32, height: u32) -> u32 {
+    u32::BITS - width.max(height).leading_zeros()
+}
+
+fn with_color_space(format: gpu::TextureFormat, color_space: ColorSpace) -> gpu::TextureFormat {
+    use gpu::TextureFormat as F;
+    let (linear, srgb) = match format {
+        F::Rgba8Unorm | F::Rgba8UnormSrgb => (F::Rgba8Unorm, F::Rgba8UnormSrgb),
+        F::Bc1RgbaUnorm | F::Bc1RgbaUnormSrgb => (F::Bc1RgbaUnorm, F::Bc1RgbaUnormSrgb),
+        F::Bc3RgbaUnorm | F::Bc3RgbaUnormSrgb => (F::Bc3RgbaUnorm, F::Bc3RgbaUnormSrgb),
+        F::Bc7RgbaUnorm | F::Bc7RgbaUnormSrgb => (F::Bc7RgbaUnorm, F::Bc7RgbaUnormSrgb),
+        F::Astc4x4Unorm | F::Astc4x4UnormSrgb => (F::Astc4x4Unorm, F::Astc4x4UnormSrgb),
+        other => return other,
+    };
+    match color_space {
+        ColorSpace::Srgb => srgb,
+        ColorSpace::Linear => linear,
+    }
+}
+
+/// Borrowed view of a parsed KTX2 container.
+#[derive(Debug)]
+struct Ktx2<'a> {
+    format: gpu::TextureFormat,
+    width: u32,
+    height: u32,
+    /// Level data from the base level down.
+    levels: Vec<&'a [u8]>,
+    generate_mipmaps: bool,
+}
+
+impl<'a> Ktx2<'a> {
+    fn parse(bytes: &'a [u8]) -> Result<Self, TextureError> {
+        if bytes.get(..12) != Some(&KTX2_IDENTIFIER[..]) {
+            return Err(TextureError::new("not a KTX2 file"));
+        }
+        let word = |offset: usize| -> Result<u32, TextureError> {
+            bytes
+                .get(offset..offset + 4)
+                .map(|word| u32::from_le_bytes(word.try_into().expect("four bytes")))
+                .ok_or_else(|| TextureError::new("truncated KTX2 header"))
+        };
+        let long = |offset: usize| -> Result<u64, TextureError> {
+            bytes
+                .get(offset..offset + 8)
+                .map(|long| u64::from_le_bytes(long.try_into().expect("eight bytes")))
+                .ok_or_else(|| TextureError::new("truncated KTX2 level index"))
+        };
+        let vk_format = word(12)?;
+        let width = word(20)?;
+        let height = word(24)?;
+        let depth = word(28)?;
+        let layers = word(32)?;
+        let faces = word(36)?;
+        let level_count = word(40)?;
+        let supercompression = word(44)?;
+        let format = match vk_format {
+            37 => gpu::TextureFormat::Rgba8Unorm,
+            43 => gpu::TextureFormat::Rgba8UnormSrgb,
+            133 => gpu::TextureFormat::Bc1RgbaUnorm,
+            134 => gpu::TextureFormat::Bc1RgbaUnormSrgb,
+            137 => gpu::TextureFormat::Bc3RgbaUnorm,
+            138 => gpu::TextureFormat::Bc3RgbaUnormSrgb,
+            145 => gpu::TextureFormat::Bc7RgbaUnorm,
+            146 => gpu::TextureFormat::Bc7RgbaUnormSrgb,
+            157 => gpu::TextureFormat::Astc4x4Unorm,
+            158 => gpu::TextureFormat::Astc4x4UnormSrgb,
+            0 => {
+                return Err(TextureError::new(
+                    "KTX2 files without a GPU format (Basis Universal) are not supported",
+                ));
+            }
+            other => {
+                return Err(TextureError::new(format!(
+                    "unsupported KTX2 Vulkan format {other}"
+                )));
+            }
+        };
+        if supercompression != 0 {
+            return Err(TextureError::new(format!(
+                "unsupported KTX2 supercompression scheme {supercompression}"
+            )));
+        }
+        if width == 0 || height == 0 || depth > 1 || layers > 1 || faces != 1 {
+            return Err(TextureError::new(
+                "only single 2D images are supported in KTX2 files",
+            ));
+        }
+        let max_levels = full_mip_count(width, height);
+        if level_count > max_levels {
+            return Err(TextureError::new(format!(
+                "{level_count} KTX2 levels exceed the {max_levels} a {width}x{height} image has"
+            )));
+        }
+        let levels = (0..level_count.max(1) as usize)
+            .map(|level| {
+                let entry = 80 + level * 24;
+                let offset = long(entry)?;
+                let length = long(entry + 8)?;
+                usize::try_from(offset)
+                    .ok()
+                    .zip(usize::try_from(length).ok())
+                    .and_then(|(offset, length)| bytes.get(offset..offset.checked_add(length)?))
+                    .ok_or_else(|| TextureError::new(format!("KTX2 level {level} is truncated")))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            format,
+            width,
+            height,
+            levels,
+            generate_mipmaps: level_count == 0,
+        })
+    }
+}
+
+/// Texture decoding or upload failure.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextureError(String);
+
+impl TextureError {
+    fn new(message: impl Into<String>) -> Self {
+        Self(message.into())
+    }
+}
+
+impl fmt::Display for TextureError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(formatter)
+    }
+}
+
+impl Error for TextureError {}
+
+impl From<gpu::GpuError> for TextureError {
+    fn from(value: gpu::GpuError) -> Self {
+        Self::new(value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ktx2(vk_format: u32, width: u32, height: u32, levels: &[&[u8]]) -> Vec<u8> {
+        let mut bytes = KTX2_IDENTIFIER.to_vec();
+        for word in [vk_format, 1, width, height, 0, 0, 1, levels.len() as u32, 0] {
+            bytes.extend_from_slice(&word.to_le_bytes());
+        }
+        bytes.resize(80, 0);
+        let mut offset = 80 + levels.len() * 24;
+        for level in levels {
+            for long in [offset, level.len(), level.len()] {
+                bytes.extend_from_slice(&(long as u64).to_le_bytes());
+            }
+            offset += level.len();
+        }
+        for level in levels {
+            bytes.extend_from_slice(level);
+        }
+        bytes
+    }
+
+    #[test]
+    fn parses_ktx2_levels_and_rejects_unsupported_files() {
+        let base = [1; 32];
+        let small = [2; 16];
+        let file = ktx2(146, 8, 4, &[&base, &small]);
+        let parsed = Ktx2::parse(&file).unwrap();
+        assert_eq!(parsed.format, gpu::TextureFormat::Bc7RgbaUnormSrgb);
+        assert_eq!((parsed.width, parsed.height), (8, 4));
+        assert_eq!(parsed.levels, [&base[..], &small[..]]);
+        assert!(!parsed.generate_mipmaps);
+
+        assert!(Ktx2::parse(&ktx2(43, 2, 2, &[&[0; 16]])).is_ok());
+        assert!(Ktx2::parse(&ktx2(0, 2, 2, &[&[0; 16]])).is_err());
+        assert!(Ktx2::parse(&file[..100]).is_err());
+        assert!(Ktx2::parse(b"\x89PNG\r\n\x1a\n").is_err());
+    }
+
+    #[test]
+    fn compressed_images_must_be_whole_blocks() {
+        let bc7 = gpu::TextureFormat::Bc7RgbaUnorm;
+        assert!(check_block_dimensions(bc7, 8, 4).is_ok());
+        let error = check_block_dimensions(bc7, 6, 4).unwrap_err();
+        assert!(error.to_string().contains("4x4"), "{error}");
+        assert!(check_block_dimensions(bc7, 8, 2).is_err());
+        assert!(check_block_dimensions(gpu::TextureFormat::Rgba8Unorm, 3, 5).is_ok());
+    }
+
+    #[test]
+    fn color_space_selects_format_variant() {
+        assert_eq!(full_mip_count(256, 64), 9);
+        assert_eq!(full_mip_count(1, 1), 1);
+        assert_eq!(
+            with_color_space(gpu::TextureFormat::Bc1RgbaUnormSrgb, ColorSpace::Linear),
+            gpu::TextureFormat::Bc1RgbaUnorm
+        );
+        assert_eq!(
+            with_color_space(gpu::TextureFormat::Rgba8Unorm, ColorSpace::Srgb),
+            gpu::TextureFormat::Rgba8UnormSrgb
+        );
+    }
+}
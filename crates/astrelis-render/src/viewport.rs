@@ -0,0 +1,277 @@
+//! Split-screen and multi-view surface division.
+
+use astrelis_core::{
+    color::Color,
+    geometry::{Physical, Point, Rect, Size},
+};
+use astrelis_gpu::TextureView;
+
+use crate::CompositedRenderTarget;
+
+/// How a [`ViewportManager`] divides its surface.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum SplitLayout {
+    /// Side-by-side for two views, one wide view above the rest for three,
+    /// and a near-square grid beyond that.
+    #[default]
+    Auto,
+    /// Equal-width columns.
+    Columns,
+    /// Equal-height rows.
+    Rows,
+    /// A grid with this many columns, filled row by row.
+    Grid(u32),
+    /// Normalized `0..1` rectangles, one per view; views without a rectangle
+    /// are hidden.
+    Custom(Vec<Rect<Physical, f32>>),
+}
+
+/// One view's camera and background.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Viewport<C> {
+    /// Camera used to render this view.
+    pub camera: C,
+    /// Linear background cleared inside this view.
+    pub clear_color: Color,
+}
+
+/// Divides a surface into viewports, each with its own camera, and routes
+/// renderer output into them through [`CompositedRenderTarget`]s.
+///
+/// Every view's scissor equals its viewport, so renderers never draw into a
+/// neighbour. The camera type is the renderer's own, such as a 2D or 3D
+/// camera.
+#[derive(Clone, Debug)]
+pub struct ViewportManager<C> {
+    layout: SplitLayout,
+    gap: u32,
+    views: Vec<Viewport<C>>,
+}
+
+impl<C> ViewportManager<C> {
+    /// Creates a manager with no views.
+    pub const fn new(layout: SplitLayout) -> Self {
+        Self {
+            layout,
+            gap: 0,
+            views: Vec::new(),
+        }
+    }
+
+    /// Division policy.
+    pub fn layout(&self) -> &SplitLayout {
+        &self.layout
+    }
+
+    /// Changes the division policy.
+    pub fn set_layout(&mut self, layout: SplitLayout) {
+        self.layout = layout;
+    }
+
+    /// Physical pixels left between adjacent views.
+    pub fn gap(&self) -> u32 {
+        self.gap
+    }
+
+    /// Sets the physical pixels left between adjacent views.
+    pub fn set_gap(&mut self, gap: u32) {
+        self.gap = gap;
+    }
+
+    /// Appends a view and returns its index.
+    pub fn push(&mut self, camera: C, clear_color: Color) -> usize {
+        self.views.push(Viewport {
+            camera,
+            clear_color,
+        });
+        self.views.len() - 1
+    }
+
+    /// Removes a view, shifting later indices down.
+    pub fn remove(&mut self, index: usize) -> Option<Viewport<C>> {
+        (index < self.views.len()).then(|| self.views.remove(index))
+    }
+
+    /// Number of views.
+    pub fn len(&self) -> usize {
+        self.views.len()
+    }
+
+    /// Returns whether there are no views.
+    pub fn is_empty(&self) -> bool {
+        self.views.is_empty()
+    }
+
+    /// A view by index.
+    pub fn get(&self, index: usize) -> Option<&Viewport<C>> {
+        self.views.get(index)
+    }
+
+    /// A mutable view by index, for moving its camera.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut Viewport<C>> {
+        self.views.get_mut(index)
+    }
+
+    /// Physical rectangle of every view, `None` for hidden ones.
+    pub fn rects(&self, size: Size<Physical, u32>) -> Vec<Option<Rect<Physical, u32>>> {
+        let count = self.views.len() as u32;
+        let cells = match &self.layout {
+            SplitLayout::Custom(rects) => {
+                return (0..self.views.len())
+                    .map(|index| {
+                        rects
+                            .get(index)
+                            .and_then(|rect| self.normalized(*rect, size))
+                    })
+                    .collect();
+            }
+            _ if count == 0 => return Vec::new(),
+            SplitLayout::Columns => (0..count).map(|index| (index, 0, 1)).collect(),
+            SplitLayout::Rows => (0..count).map(|index| (0, index, 1)).collect(),
+            SplitLayout::Grid(columns) => grid(count, (*columns).max(1)),
+            SplitLayout::Auto if count == 3 => vec![(0, 0, 2), (0, 1, 1), (1, 1, 1)],
+            SplitLayout::Auto => grid(count, count.isqrt() + u32::from(!is_square(count))),
+        };
+        let columns = cells
+            .iter()
+            .map(|&(column, _, span)| column + span)
+            .max()
+            .unwrap_or(1);
+        let rows = cells.iter().map(|&(_, row, _)| row + 1).max().unwrap_or(1);
+        cells
+            .into_iter()
+            .map(|(column, row, span)| self.cell(size, (columns, rows), (column, row), span))
+            .collect()
+    }
+
+    /// Index of the topmost view containing a physical point, for routing
+    /// pointer input.
+    pub fn view_at(&self, size: Size<Physical, u32>, point: Point<Physical, u32>) -> Option<usize> {
+        self.rects(size)
+            .into_iter()
+            .enumerate()
+            .rev()
+            .find(|(_, rect)| rect.is_some_and(|rect| rect.contains(point)))
+            .map(|(index, _)| index)
+    }
+
+    /// Per-view destinations inside a full-surface attachment, skipping
+    /// hidden and empty views.
+    pub fn targets<'a>(
+        &'a self,
+        view: &'a TextureView,
+        size: Size<Physical, u32>,
+        scale_factor: f32,
+    ) -> impl Iterator<Item = (usize, &'a Viewport<C>, CompositedRenderTarget)> + 'a {
+        self.rects(size)
+            .into_iter()
+            .zip(&self.views)
+            .enumerate()
+            .filter_map(move |(index, (rect, viewport))| {
+                let rect = rect?;
+                Some((
+                    index,
+                    viewport,
+                    CompositedRenderTarget {
+                        view: view.clone(),
+                        size,
+                        viewport: rect,
+                        scissor: rect,
+                        scale_factor,
+                        clear_color: viewport.clear_color,
+                    },
+                ))
+            })
+    }
+
+    fn cell(
+        &self,
+        size: Size<Physical, u32>,
+        (columns, rows): (u32, u32),
+        (column, row): (u32, u32),
+        span: u32,
+    ) -> Option<Rect<Physical, u32>> {
+        // Start of cell `index`; the start of cell `count` is one gap past
+        // the far edge, so subtracting a gap always yields a cell's end.
+        let edge = |extent: u32, count: u32, index: u32| {
+            let usable = extent.saturating_sub(self.gap * (count - 1));
+            usable * index / count + self.gap * index
+        };
+        let x = edge(size.width, columns, column);
+        let y = edge(size.height, rows, row);
+        let right = (edge(size.width, columns, column + span) - self.gap).min(size.width);
+        let bottom = (edge(size.height, rows, row + 1) - self.gap).min(size.height);
+        (right > x && bottom > y).then(|| Rect::from_xywh(x, y, right - x, bottom - y))
+    }
+
+    fn normalized(
+        &self,
+        rect: Rect<Physical, f32>,
+        size: Size<Physical, u32>,
+    ) -> Option<Rect<Physical, u32>> {
+        let scale =
+            |value: f32, extent: u32| (value.clamp(0.0, 1.0) * extent as f32).round() as u32;
+        let x = scale(rect.min_x(), size.width);
+        let y = scale(rect.min_y(), size.height);
+        let right = scale(rect.max_x(), size.width);
+        let bottom = scale(rect.max_y(), size.height);
+        (right > x && bottom > y).then(|| Rect::from_xywh(x, y, right - x, bottom - y))
+    }
+}
+
+fn grid(count: u32, columns: u32) -> Vec<(u32, u32, u32)> {
+    (0..count)
+        .map(|index| (index % columns, index / columns, 1))
+        .collect()
+}
+
+fn is_square(count: u32) -> bool {
+    count.isqrt().pow(2) == count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auto_layouts_tile_the_surface_with_gaps() {
+        let size = Size::new(200, 100);
+        let mut views = ViewportManager::new(SplitLayout::Auto);
+        views.push((), Color::BLACK);
+        assert_eq!(views.rects(size), [Some(Rect::from_xywh(0, 0, 200, 100))]);
+
+        views.set_gap(2);
+        views.push((), Color::BLACK);
+        assert_eq!(
+            views.rects(size),
+            [
+                Some(Rect::from_xywh(0, 0, 99, 100)),
+                Some(Rect::from_xywh(101, 0, 99, 100)),
+            ]
+        );
+
+        views.push((), Color::BLACK);
+        assert_eq!(
+            views.rects(size),
+            [
+                Some(Rect::from_xywh(0, 0, 200, 49)),
+                Some(Rect::from_xywh(0, 51, 99, 49)),
+                Some(Rect::from_xywh(101, 51, 99, 49)),
+            ]
+        );
+        assert_eq!(views.view_at(size, Point::new(150, 75)), Some(2));
+        assert_eq!(
+            views.view_at(size, Point::new(100, 75)),
+            None,
+            "gaps hit nothing"
+        );
+
+        views.set_layout(SplitLayout::Custom(vec![Rect::from_xywh(
+            0.5, 0.0, 0.5, 1.0,
+        )]));
+        assert_eq!(
+            views.rects(size),
+            [Some(Rect::from_xywh(100, 0, 100, 100)), None, None]
+        );
+    }
+}
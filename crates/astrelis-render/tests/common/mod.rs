@@ -0,0 +1,25 @@
+//! Device setup shared by the GPU integration tests.
+
+use astrelis_gpu::{Device, DeviceDescriptor, Queue, RequestAdapterOptions};
+
+/// A device on the default adapter, or `None` when there is no adapter, in
+/// which case the `subject` GPU test reports that it is skipped.
+pub async fn device(subject: &str) -> Option<(Device, Queue)> {
+    let instance = astrelis_gpu_wgpu::create_instance(Default::default());
+    let adapter = match instance
+        .request_adapter(RequestAdapterOptions::default())
+        .await
+    {
+        Ok(adapter) => adapter,
+        Err(error) => {
+            eprintln!("skipping {subject} GPU test: {error}");
+            return None;
+        }
+    };
+    Some(
+        adapter
+            .request_device(DeviceDescriptor::default())
+            .await
+            .expect("request device"),
+    )
+}
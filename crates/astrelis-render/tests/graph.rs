@@ -0,0 +1,200 @@
+//! Headless render graph and transient pool tests.
+
+mod common;
+
+use std::cell::RefCell;
+
+use astrelis_core::geometry::Size;
+use astrelis_gpu::{
+    Device, Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+    TextureView,
+};
+use astrelis_render::{GraphError, GraphStats, RenderGraph, TransientPool, TransientTexture};
+
+fn descriptor(label: &str) -> TransientTexture {
+    TransientTexture {
+        label: label.into(),
+        size: Size::new(4, 4),
+        format: TextureFormat::Rgba8Unorm,
+        sample_count: 1,
+        usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC,
+    }
+}
+
+fn target(device: &Device) -> TextureView {
+    device
+        .create_texture(TextureDescriptor {
+            label: Some("graph test target".into()),
+            size: Extent3d::d2(4, 4),
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba8Unorm,
+            usage: TextureUsages::RENDER_ATTACHMENT,
+        })
+        .create_view(Default::default())
+}
+
+#[test]
+fn execute_records_observed_passes_with_their_declared_views() {
+    pollster::block_on(async {
+        let Some((device, _queue)) = common::device("render graph").await else {
+            return;
+        };
+        let mut pool = TransientPool::new(device.clone());
+        let recorded = RefCell::new(Vec::new());
+        let recorded = &recorded;
+        let mut graph = RenderGraph::new();
+        let output = graph.import_texture(target(&device));
+        let lit = graph.create_texture(descriptor("lit"));
+        let unused = graph.create_texture(descriptor("unused"));
+
+        let mut debug = graph.add_pass("debug");
+        debug.write(unused);
+        debug.execute(move |_, _| {
+            recorded.borrow_mut().push("debug");
+            Ok(())
+        });
+        let mut lighting = graph.add_pass("lighting");
+        let lit = lighting.write(lit);
+        lighting.execute(move |_, resources| {
+            recorded.borrow_mut().push("lighting");
+            resources.view(lit)?;
+            Ok(())
+        });
+        let mut compose = graph.add_pass("compose");
+        compose.read(lit);
+        let composed = compose.write(output);
+        compose.execute(move |_, resources| {
+            recorded.borrow_mut().push("compose");
+            resources.view(lit)?;
+            resources.view(composed)?;
+            let error = resources.view(unused).expect_err("undeclared texture");
+            assert!(error.to_string().contains("`compose`"), "{error}");
+            Ok(())
+        });
+
+        let mut encoder = device.create_command_encoder(Default::default());
+        let stats = graph.execute(&mut pool, &mut encoder).expect("execute");
+        assert_eq!(*recorded.borrow(), ["lighting", "compose"]);
+        assert_eq!(
+            stats,
+            GraphStats {
+                passes: 2,
+                culled_passes: 1,
+                textures_created: 1,
+                textures_reused: 0,
+            }
+        );
+    });
+}
+
+#[test]
+fn transients_alias_after_their_last_use_and_persist_across_frames() {
+    pollster::block_on(async {
+        let Some((device, _queue)) = common::device("render graph").await else {
+            return;
+        };
+        let mut pool = TransientPool::new(device.clone());
+        let output = target(&device);
+        let mut frame = || {
+            let collected = RefCell::new(Vec::new());
+            let views = &collected;
+            let mut graph = RenderGraph::new();
+            let output = graph.import_texture(output.clone());
+            let first = graph.create_texture(descriptor("first"));
+            let second = graph.create_texture(descriptor("second"));
+            let mut draw = graph.add_pass("draw first");
+            let first = draw.write(first);
+            draw.execute(move |_, _| Ok(()));
+            let mut resolve = graph.add_pass("resolve first");
+            resolve.read(first);
+            let output = resolve.write(output);
+            resolve.execute(move |_, resources| {
+                views.borrow_mut().push(resources.view(first)?.clone());
+                Ok(())
+            });
+            let mut draw = graph.add_pass("draw second");
+            let second = draw.write(second);
+            draw.execute(move |_, _| Ok(()));
+            let mut resolve = graph.add_pass("resolve second");
+            resolve.read(second);
+            resolve.write(output);
+            resolve.execute(move |_, resources| {
+                views.borrow_mut().push(resources.view(second)?.clone());
+                Ok(())
+            });
+            let mut encoder = device.create_command_encoder(Default::default());
+            let stats = graph.execute(&mut pool, &mut encoder).expect("execute");
+            let views = collected.into_inner();
+            assert!(
+                views[0].same_resource(&views[1]),
+                "the second transient aliases the first"
+            );
+            (stats, views[0].clone())
+        };
+
+        let (first_frame, first_view) = frame();
+        assert_eq!(first_frame.textures_created, 1);
+        assert_eq!(first_frame.textures_reused, 1);
+        let (second_frame, second_view) = frame();
+        assert_eq!(second_frame.textures_created, 0);
+        assert_eq!(second_frame.textures_reused, 2);
+        assert!(first_view.same_resource(&second_view));
+        assert_eq!(pool.len(), 1);
+    });
+}
+
+#[test]
+fn failed_passes_return_their_transients_to_the_pool() {
+    pollster::block_on(async {
+        let Some((device, _queue)) = common::device("render graph").await else {
+            return;
+        };
+        let mut pool = TransientPool::new(device.clone());
+        let mut graph = RenderGraph::new();
+        let output = graph.import_texture(target(&device));
+        let scratch = graph.create_texture(descriptor("scratch"));
+        let mut draw = graph.add_pass("draw");
+        let scratch = draw.write(scratch);
+        draw.execute(|_, _| Ok(()));
+        let mut resolve = graph.add_pass("resolve");
+        resolve.read(scratch);
+        resolve.write(output);
+        resolve.execute(|_, _| Err(GraphError::new("resolve failed")));
+
+        let mut encoder = device.create_command_encoder(Default::default());
+        let error = graph.execute(&mut pool, &mut encoder).unwrap_err();
+        assert_eq!(error.to_string(), "resolve failed");
+        assert_eq!(pool.len(), 1);
+        let lease = pool.acquire(&descriptor("after failure")).expect("lease");
+        assert!(!lease.created(), "the failed graph released its texture");
+    });
+}
+
+#[test]
+fn leases_only_return_to_the_pool_that_issued_them() {
+    pollster::block_on(async {
+        let Some((device, _queue)) = common::device("render graph").await else {
+            return;
+        };
+        let mut first = TransientPool::new(device.clone());
+        let mut second = TransientPool::new(device.clone());
+        let foreign = first.acquire(&descriptor("foreign")).expect("lease");
+        let held = second.acquire(&descriptor("held")).expect("lease");
+
+        let error = second.release(foreign).expect_err("wrong pool");
+        assert!(error.to_string().contains("did not issue it"), "{error}");
+        let reused = second.acquire(&descriptor("reused")).expect("lease");
+        assert!(reused.created(), "the held texture stays leased");
+
+        second.release(held).expect("own lease");
+        second.release(reused).expect("own lease");
+        assert!(
+            !second
+                .acquire(&descriptor("again"))
+                .expect("lease")
+                .created()
+        );
+    });
+}
@@ -0,0 +1,215 @@
+//! Headless material layout and instancing key tests.
+
+mod common;
+
+use std::{
+    collections::HashSet,
+    hash::{BuildHasher, RandomState},
+};
+
+use astrelis_gpu::{
+    Device, Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureSampleType,
+    TextureUsages, TextureView, TextureViewDimension,
+};
+use astrelis_render::{Material, MaterialLayout, MaterialParamKind};
+use bytemuck::{Pod, Zeroable};
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct Tint {
+    color: [f32; 4],
+}
+
+fn view(device: &Device) -> TextureView {
+    texture_view(device, TextureFormat::Rgba8UnormSrgb, 1)
+}
+
+fn texture_view(device: &Device, format: TextureFormat, layers: u32) -> TextureView {
+    device
+        .create_texture(TextureDescriptor {
+            label: Some("material test texture".into()),
+            size: Extent3d {
+                width: 4,
+                height: 4,
+                depth_or_array_layers: layers,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format,
+            usage: TextureUsages::TEXTURE_BINDING,
+        })
+        .create_view(Default::default())
+}
+
+#[test]
+fn keys_ignore_uniforms_but_not_layouts() {
+    pollster::block_on(async {
+        let Some((device, _queue)) = common::device("material").await else {
+            return;
+        };
+        let layout = || {
+            MaterialLayout::builder("tinted")
+                .uniform::<Tint>("tint")
+                .build(&device)
+                .expect("layout")
+        };
+        let tinted = layout();
+        let red = {
+            let mut material = Material::new(&tinted);
+            material
+                .set_uniform(
+                    "tint",
+                    &Tint {
+                        color: [1.0, 0.0, 0.0, 1.0],
+                    },
+                )
+                .expect("tint");
+            material
+        };
+        let unset = Material::new(&tinted);
+        assert_eq!(
+            red.key(),
+            unset.key(),
+            "uniform values and unset uniforms share a key"
+        );
+        assert_ne!(
+            unset.key(),
+            Material::new(&layout()).key(),
+            "identically declared layouts are still different material types"
+        );
+    });
+}
+
+#[test]
+fn textures_must_match_the_declared_dimension_and_sample_type() {
+    pollster::block_on(async {
+        let Some((device, _queue)) = common::device("material").await else {
+            return;
+        };
+        let layout = MaterialLayout::builder("sampled")
+            .texture("albedo")
+            .param(
+                "heights",
+                MaterialParamKind::Texture {
+                    sample_type: TextureSampleType::UnfilterableFloat,
+                    view_dimension: TextureViewDimension::D2,
+                },
+            )
+            .build(&device)
+            .expect("layout");
+        let mut material = Material::new(&layout);
+
+        let layered = texture_view(&device, TextureFormat::Rgba8Unorm, 6);
+        let error = material.set_texture("albedo", layered).unwrap_err();
+        assert!(error.to_string().contains("D2Array"), "{error}");
+        let integer = texture_view(&device, TextureFormat::R32Uint, 1);
+        let error = material.set_texture("albedo", integer).unwrap_err();
+        assert!(error.to_string().contains("R32Uint"), "{error}");
+        let unfilterable = texture_view(&device, TextureFormat::R32Float, 1);
+        assert!(
+            material
+                .set_texture("albedo", unfilterable.clone())
+                .is_err()
+        );
+
+        material
+            .set_texture("heights", unfilterable)
+            .expect("unfilterable floats bind as declared");
+        material
+            .set_texture("heights", view(&device))
+            .expect("filterable floats also bind as unfilterable");
+        material
+            .set_texture("albedo", view(&device))
+            .expect("albedo");
+    });
+}
+
+#[test]
+fn keys_hash_the_bound_textures() {
+    pollster::block_on(async {
+        let Some((device, _queue)) = common::device("material").await else {
+            return;
+        };
+        let layout = MaterialLayout::builder("textured")
+            .uniform::<Tint>("tint")
+            .texture("albedo")
+            .build(&device)
+            .expect("layout");
+        let grass = view(&device);
+        let stone = view(&device);
+        let material = |view: &TextureView, red| {
+            let mut material = Material::new(&layout);
+            material
+                .set_uniform(
+                    "tint",
+                    &Tint {
+                        color: [red, 1.0, 1.0, 1.0],
+                    },
+                )
+                .expect("tint");
+            material
+                .set_texture("albedo", view.clone())
+                .expect("albedo");
+            material
+        };
+
+        let state = RandomState::new();
+        let first = material(&grass, 0.0).key();
+        let tinted = material(&grass, 1.0).key();
+        let other = material(&stone, 0.0).key();
+        assert_eq!(first, tinted, "uniform values do not split batches");
+        assert_eq!(state.hash_one(&first), state.hash_one(&tinted));
+        assert_ne!(first, other);
+        assert_ne!(
+            state.hash_one(&first),
+            state.hash_one(&other),
+            "different textures land in different buckets"
+        );
+        let batches = [first, tinted, other].into_iter().collect::<HashSet<_>>();
+        assert_eq!(batches.len(), 2);
+    });
+}
+
+#[test]
+fn layouts_reject_unpadded_uniforms() {
+    pollster::block_on(async {
+        let Some((device, _queue)) = common::device("material").await else {
+            return;
+        };
+        let error = MaterialLayout::builder("unpadded")
+            .uniform::<[f32; 3]>("tint")
+            .build(&device)
+            .expect_err("12-byte uniforms are rejected");
+        assert!(error.to_string().contains("pad it to 16"), "{error}");
+        assert!(
+            MaterialLayout::builder("duplicate")
+                .texture("albedo")
+                .texture("albedo")
+                .build(&device)
+                .is_err()
+        );
+    });
+}
+
+#[test]
+fn bind_groups_need_every_parameter_and_exact_uniform_sizes() {
+    pollster::block_on(async {
+        let Some((device, queue)) = common::device("material").await else {
+            return;
+        };
+        let layout = MaterialLayout::builder("tinted")
+            .uniform::<Tint>("tint")
+            .build(&device)
+            .expect("layout");
+        let mut material = Material::new(&layout);
+        let error = material.bind_group(&device, &queue).unwrap_err();
+        assert!(error.to_string().contains("`tint` is unset"), "{error}");
+        assert!(material.set_uniform("tint", &[0.0f32; 2]).is_err());
+        assert!(material.set_texture("tint", view(&device)).is_err());
+        material
+            .set_uniform("tint", &Tint { color: [1.0; 4] })
+            .expect("tint");
+        material.bind_group(&device, &queue).expect("bind group");
+    });
+}
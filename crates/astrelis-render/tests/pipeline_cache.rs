@@ -0,0 +1,226 @@
+//! Headless pipeline cache integration tests.
+
+mod common;
+
+use std::time::{Duration, Instant};
+
+use astrelis_gpu::{
+    ColorTargetState, ColorWrites, Device, FragmentState, MultisampleState, PrimitiveState,
+    RenderPipelineDescriptor, ShaderModule, ShaderModuleDescriptor, TextureFormat, VertexState,
+};
+use astrelis_render::PipelineCache;
+
+const SHADER: &str = r#"
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+    let x = f32(index & 1u) * 2.0 - 1.0;
+    let y = f32(index >> 1u) * 2.0 - 1.0;
+    return vec4<f32>(x, y, 0.0, 1.0);
+}
+
+@fragment
+fn fs_main() -> @location(0) vec4<f32> {
+    return vec4<f32>(1.0, 0.0, 0.0, 1.0);
+}
+"#;
+
+/// Two devices on the default adapter, or `None` when there is no adapter.
+async fn devices() -> Option<(Device, Device)> {
+    let (first, _) = common::device("pipeline cache").await?;
+    let (second, _) = common::device("pipeline cache").await?;
+    Some((first, second))
+}
+
+fn module(device: &Device) -> ShaderModule {
+    device.create_shader_module(ShaderModuleDescriptor {
+        label: Some("pipeline cache test".into()),
+        wgsl: SHADER.into(),
+    })
+}
+
+fn descriptor(
+    module: &ShaderModule,
+    label: &str,
+    format: TextureFormat,
+) -> RenderPipelineDescriptor {
+    RenderPipelineDescriptor {
+        label: Some(label.into()),
+        layout: None,
+        vertex: VertexState {
+            module: module.clone(),
+            entry_point: "vs_main".into(),
+            buffers: Vec::new(),
+        },
+        primitive: PrimitiveState::default(),
+        depth_stencil: None,
+        multisample: MultisampleState::default(),
+        fragment: Some(FragmentState {
+            module: module.clone(),
+            entry_point: "fs_main".into(),
+            targets: vec![Some(ColorTargetState {
+                format,
+                blend: None,
+                write_mask: ColorWrites::ALL,
+            })],
+        }),
+        cache: None,
+    }
+}
+
+/// Polls until no variant is compiling, failing after a generous timeout.
+fn settle(cache: &PipelineCache) {
+    let start = Instant::now();
+    while cache.poll() > 0 {
+        assert!(
+            start.elapsed() < Duration::from_secs(30),
+            "background compilation never finished"
+        );
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
+
+#[test]
+fn variants_are_keyed_on_state_and_resource_identity() {
+    pollster::block_on(async {
+        let Some((device, _)) = devices().await else {
+            return;
+        };
+        let cache = PipelineCache::new(device.clone(), 0);
+        let shared = module(&device);
+        cache
+            .get_or_create(descriptor(&shared, "first", TextureFormat::Rgba8Unorm))
+            .await
+            .expect("first pipeline");
+        cache
+            .get_or_create(descriptor(&shared, "second", TextureFormat::Rgba8Unorm))
+            .await
+            .expect("relabelled pipeline");
+        assert_eq!(cache.len(), 1, "labels do not split variants");
+
+        cache
+            .get_or_create(descriptor(&shared, "hdr", TextureFormat::Rgba16Float))
+            .await
+            .expect("hdr pipeline");
+        assert_eq!(cache.len(), 2, "target formats split variants");
+
+        let twin = module(&device);
+        cache
+            .get_or_create(descriptor(&twin, "twin", TextureFormat::Rgba8Unorm))
+            .await
+            .expect("twin pipeline");
+        assert_eq!(
+            cache.len(),
+            3,
+            "equal source in another module is a new variant"
+        );
+        assert!(
+            cache
+                .request(descriptor(&shared, "again", TextureFormat::Rgba16Float))
+                .expect("ready pipeline")
+                .is_some(),
+            "without workers a request compiles immediately"
+        );
+        assert_eq!(cache.len(), 3);
+    });
+}
+
+#[test]
+fn background_compiles_become_ready_and_drop_does_not_wait_for_the_queue() {
+    pollster::block_on(async {
+        let Some((device, _)) = devices().await else {
+            return;
+        };
+        let cache = PipelineCache::new(device.clone(), 2);
+        let shared = module(&device);
+        let variant = descriptor(&shared, "background", TextureFormat::Rgba8Unorm);
+        assert!(cache.request(variant.clone()).expect("queue").is_none());
+        assert!(
+            cache
+                .request(variant.clone())
+                .expect("still pending")
+                .is_none(),
+            "a pending variant is queued once"
+        );
+        settle(&cache);
+        assert!(cache.request(variant).expect("ready").is_some());
+        assert_eq!(cache.len(), 1);
+
+        let backlog = PipelineCache::new(device.clone(), 1);
+        for mask in 1..=64u64 {
+            let mut variant = descriptor(&shared, "backlog", TextureFormat::Rgba8Unorm);
+            variant.multisample.mask = mask;
+            assert!(backlog.request(variant).expect("queue").is_none());
+        }
+        drop(backlog);
+    });
+}
+
+#[test]
+fn failures_are_reported_once_and_then_retried() {
+    pollster::block_on(async {
+        let Some((device, other)) = devices().await else {
+            return;
+        };
+        let foreign = module(&other);
+        let broken = descriptor(&foreign, "foreign", TextureFormat::Rgba8Unorm);
+        let cache = PipelineCache::new(device.clone(), 0);
+        assert!(cache.get_or_create(broken.clone()).await.is_err());
+        assert!(cache.is_empty());
+
+        let cache = PipelineCache::new(device.clone(), 1);
+        assert!(cache.request(broken.clone()).expect("queue").is_none());
+        settle(&cache);
+        assert!(
+            cache.request(broken.clone()).is_err(),
+            "the failure is reported"
+        );
+        assert!(
+            cache.request(broken).expect("requeue").is_none(),
+            "a reported failure is compiled again"
+        );
+        settle(&cache);
+        assert!(cache.is_empty());
+
+        let module = module(&device);
+        let mut invalid = descriptor(&module, "invalid", TextureFormat::Rgba8Unorm);
+        invalid.vertex.entry_point = "missing".into();
+        let error = cache
+            .get_or_create(invalid.clone())
+            .await
+            .expect_err("the device rejects an unknown entry point");
+        assert!(!error.to_string().is_empty());
+        assert!(cache.request(invalid.clone()).expect("queue").is_none());
+        settle(&cache);
+        assert!(
+            cache.request(invalid).is_err(),
+            "validation failures are stored as errors"
+        );
+        assert!(cache.is_empty());
+    });
+}
+
+#[test]
+fn variants_can_be_removed_or_cleared() {
+    pollster::block_on(async {
+        let Some((device, _)) = devices().await else {
+            return;
+        };
+        let cache = PipelineCache::new(device.clone(), 0);
+        let shared = module(&device);
+        let ldr = descriptor(&shared, "ldr", TextureFormat::Rgba8Unorm);
+        let hdr = descriptor(&shared, "hdr", TextureFormat::Rgba16Float);
+        let first = cache.get_or_create(ldr.clone()).await.expect("ldr");
+        cache.get_or_create(hdr.clone()).await.expect("hdr");
+        assert_eq!(cache.len(), 2);
+
+        assert!(cache.remove(&ldr));
+        assert!(!cache.remove(&ldr), "the variant is already gone");
+        assert_eq!(cache.len(), 1);
+        let second = cache.get_or_create(ldr).await.expect("recompiled");
+        assert!(!first.same_resource(&second));
+
+        cache.clear();
+        assert!(cache.is_empty());
+        assert!(cache.request(hdr).expect("recompiled").is_some());
+    });
+}
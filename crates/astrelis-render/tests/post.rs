@@ -0,0 +1,81 @@
+//! Headless per-window post-processing tests.
+
+mod common;
+
+use astrelis_core::{color::Color, geometry::Size};
+use astrelis_gpu::{
+    Device, Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+    TextureView,
+};
+use astrelis_render::{Bloom, Fxaa, PostEffect, WindowPostStacks};
+
+fn target(device: &Device) -> TextureView {
+    device
+        .create_texture(TextureDescriptor {
+            label: Some("post test target".into()),
+            size: Extent3d::d2(8, 8),
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba8UnormSrgb,
+            usage: TextureUsages::RENDER_ATTACHMENT,
+        })
+        .create_view(Default::default())
+}
+
+#[test]
+fn windows_keep_separate_effects_and_scene_targets() {
+    pollster::block_on(async {
+        let Some((device, queue)) = common::device("post-processing").await else {
+            return;
+        };
+        let mut stacks = WindowPostStacks::new(device.clone(), queue).expect("stacks");
+        stacks
+            .stack_mut("main")
+            .expect("main stack")
+            .push(PostEffect::Fxaa(Fxaa::default()));
+        stacks
+            .stack_mut("preview")
+            .expect("preview stack")
+            .push(PostEffect::Bloom(Bloom {
+                passes: 0,
+                ..Default::default()
+            }));
+        assert_eq!(stacks.len(), 2);
+        assert_eq!(
+            stacks.stack(&"main").map(|stack| stack.effects().len()),
+            Some(1)
+        );
+        assert!(stacks.stack(&"settings").is_none());
+
+        let size = Size::new(8, 8);
+        let main = stacks
+            .render_target("main", size, 1.0, Color::BLACK)
+            .expect("main scene");
+        let preview = stacks
+            .render_target("preview", Size::new(4, 4), 1.0, Color::BLACK)
+            .expect("preview scene");
+        assert!(!main.view.same_resource(&preview.view));
+        assert_eq!(preview.allocation_size, Size::new(4, 4));
+
+        let output = target(&device);
+        let mut encoder = device.create_command_encoder(Default::default());
+        stacks
+            .resolve(&"main", &mut encoder, size, &output)
+            .expect("resolve main");
+        assert!(
+            stacks
+                .resolve(&"settings", &mut encoder, size, &output)
+                .is_err(),
+            "windows without a scene have nothing to resolve"
+        );
+        assert_eq!(
+            stacks.stack(&"main").map(|stack| stack.effects().len()),
+            Some(1),
+            "the default tonemap is not kept"
+        );
+        assert!(stacks.remove(&"main"));
+        assert!(!stacks.remove(&"main"));
+        assert_eq!(stacks.len(), 1);
+    });
+}
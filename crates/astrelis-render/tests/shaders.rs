@@ -0,0 +1,227 @@
+//! Headless shader registry tests: registration, hot reload, and errors.
+
+mod common;
+
+use std::{
+    cell::Cell,
+    fs,
+    path::PathBuf,
+    rc::Rc,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::{Duration, SystemTime},
+};
+
+use astrelis_gpu::{
+    ColorTargetState, ColorWrites, Device, FragmentState, GpuError, MultisampleState, PollMode,
+    PrimitiveState, RenderPipeline, RenderPipelineDescriptor, ShaderModule, TextureFormat,
+    VertexState,
+};
+use astrelis_render::ShaderRegistry;
+
+const RED: &str = r#"
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+    return vec4<f32>(f32(index & 1u), f32(index >> 1u), 0.0, 1.0);
+}
+
+@fragment
+fn fs_main() -> @location(0) vec4<f32> {
+    return vec4<f32>(1.0, 0.0, 0.0, 1.0);
+}
+"#;
+
+const BROKEN: &str = "@fragment fn fs_main() -> @location(0) vec4<f32> { return missing; }";
+
+/// A device whose error handler counts reports, or `None` without an adapter.
+async fn device() -> Option<(Device, Arc<AtomicUsize>)> {
+    let (device, _) = common::device("shader registry").await?;
+    let reported = Arc::new(AtomicUsize::new(0));
+    let counter = reported.clone();
+    device.set_error_handler(move |_| {
+        counter.fetch_add(1, Ordering::SeqCst);
+    });
+    Some((device, reported))
+}
+
+/// A pipeline builder that counts how often it runs.
+fn builder(
+    device: &Device,
+    builds: &Rc<Cell<u32>>,
+) -> impl FnMut(&ShaderModule) -> Result<RenderPipeline, GpuError> + 'static {
+    let device = device.clone();
+    let builds = builds.clone();
+    move |module| {
+        builds.set(builds.get() + 1);
+        device.create_render_pipeline(RenderPipelineDescriptor {
+            label: Some("shader registry test".into()),
+            layout: None,
+            vertex: VertexState {
+                module: module.clone(),
+                entry_point: "vs_main".into(),
+                buffers: Vec::new(),
+            },
+            primitive: PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: MultisampleState::default(),
+            fragment: Some(FragmentState {
+                module: module.clone(),
+                entry_point: "fs_main".into(),
+                targets: vec![Some(ColorTargetState {
+                    format: TextureFormat::Rgba8Unorm,
+                    blend: None,
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+            cache: None,
+        })
+    }
+}
+
+fn scratch_file(name: &str) -> PathBuf {
+    let directory = std::env::temp_dir().join(format!("astrelis-shaders-{}", std::process::id()));
+    fs::create_dir_all(&directory).expect("scratch directory");
+    directory.join(name)
+}
+
+/// Rewrites a file and moves its modification time forward, since coarse
+/// filesystem clocks may not register two writes within one test.
+fn save(path: &PathBuf, source: &str, seconds: u64) {
+    fs::write(path, source).expect("write shader");
+    fs::File::options()
+        .write(true)
+        .open(path)
+        .and_then(|file| file.set_modified(SystemTime::now() + Duration::from_secs(seconds)))
+        .expect("touch shader");
+}
+
+#[test]
+fn registered_pipelines_build_once() {
+    pollster::block_on(async {
+        let Some((device, reported)) = device().await else {
+            return;
+        };
+        let mut registry = ShaderRegistry::new(device.clone());
+        let shader = registry.insert("red", RED).await.expect("compile");
+        let builds = Rc::new(Cell::new(0));
+        registry
+            .add_pipeline(shader, builder(&device, &builds))
+            .await
+            .expect("pipeline");
+        assert_eq!(builds.get(), 1);
+        assert_eq!(registry.generation(shader), 0);
+        assert!(
+            registry.poll_changes().await.is_empty(),
+            "inline shaders are never polled"
+        );
+        device.poll(PollMode::Wait).expect("poll");
+        assert_eq!(reported.load(Ordering::SeqCst), 0);
+    });
+}
+
+#[test]
+fn edited_files_reload_and_rebuild_dependents() {
+    pollster::block_on(async {
+        let Some((device, _)) = device().await else {
+            return;
+        };
+        let path = scratch_file("reload.wgsl");
+        save(&path, RED, 0);
+        let mut registry = ShaderRegistry::new(device.clone());
+        let shader = registry.load(&path).await.expect("load");
+        let builds = Rc::new(Cell::new(0));
+        registry
+            .add_pipeline(shader, builder(&device, &builds))
+            .await
+            .expect("pipeline");
+        assert!(registry.poll_changes().await.is_empty(), "nothing changed");
+
+        save(&path, &RED.replace("1.0, 0.0, 0.0", "0.0, 1.0, 0.0"), 10);
+        let reloads = registry.poll_changes().await;
+        assert_eq!(reloads.len(), 1);
+        assert_eq!(reloads[0].shader, shader);
+        assert_eq!(reloads[0].result, Ok(()));
+        assert_eq!(registry.generation(shader), 1);
+        assert_eq!(builds.get(), 2, "dependents are rebuilt");
+        assert!(registry.poll_changes().await.is_empty());
+        let _ = fs::remove_file(path);
+    });
+}
+
+#[test]
+fn invalid_source_is_reported_once_and_keeps_the_previous_module() {
+    pollster::block_on(async {
+        let Some((device, reported)) = device().await else {
+            return;
+        };
+        let mut registry = ShaderRegistry::new(device.clone());
+        let error = registry
+            .insert("broken", BROKEN)
+            .await
+            .expect_err("undefined identifiers fail to compile");
+        assert!(error.to_string().starts_with("broken: "), "{error}");
+
+        let shader = registry.insert("red", RED).await.expect("compile");
+        let previous = registry.module(shader).clone();
+        assert!(registry.replace_source(shader, BROKEN).await.is_err());
+        assert!(registry.module(shader).same_resource(&previous));
+        assert_eq!(registry.generation(shader), 0);
+
+        let path = scratch_file("broken.wgsl");
+        save(&path, RED, 0);
+        let loaded = registry.load(&path).await.expect("load");
+        save(&path, BROKEN, 10);
+        let reloads = registry.poll_changes().await;
+        assert_eq!(reloads.len(), 1);
+        assert!(reloads[0].result.is_err());
+        assert_eq!(registry.generation(loaded), 0);
+        assert!(
+            registry.poll_changes().await.is_empty(),
+            "a broken save is reported once"
+        );
+
+        device.poll(PollMode::Wait).expect("poll");
+        assert_eq!(
+            reported.load(Ordering::SeqCst),
+            0,
+            "errors are not duplicated through the device handler"
+        );
+        let _ = fs::remove_file(path);
+    });
+}
+
+#[test]
+fn pipelines_the_new_module_cannot_build_keep_the_previous_ones() {
+    pollster::block_on(async {
+        let Some((device, reported)) = device().await else {
+            return;
+        };
+        let mut registry = ShaderRegistry::new(device.clone());
+        let vertex_only = RED.split("@fragment").next().expect("vertex stage");
+        let partial = registry
+            .insert("vertex only", vertex_only)
+            .await
+            .expect("compile");
+        let builds = Rc::new(Cell::new(0));
+        let error = registry
+            .add_pipeline(partial, builder(&device, &builds))
+            .await
+            .expect_err("the fragment entry point is missing");
+        assert!(error.to_string().starts_with("vertex only: "), "{error}");
+
+        let shader = registry.insert("red", RED).await.expect("compile");
+        let pipeline = registry
+            .add_pipeline(shader, builder(&device, &builds))
+            .await
+            .expect("pipeline");
+        let previous = registry.pipeline(pipeline).clone();
+        assert!(registry.replace_source(shader, vertex_only).await.is_err());
+        assert!(registry.pipeline(pipeline).same_resource(&previous));
+        assert_eq!(registry.generation(shader), 0);
+
+        device.poll(PollMode::Wait).expect("poll");
+        assert_eq!(reported.load(Ordering::SeqCst), 0);
+    });
+}
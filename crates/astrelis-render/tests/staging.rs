@@ -0,0 +1,157 @@
+//! Headless staging belt tests: chunk sharing, reuse, and oversize writes.
+
+mod common;
+
+use astrelis_gpu::{Buffer, BufferDescriptor, BufferUsages, Device, MapMode, PollMode, Queue};
+use astrelis_render::StagingBelt;
+
+fn target(device: &Device, size: u64) -> Buffer {
+    device.create_buffer(BufferDescriptor {
+        label: Some("staging test target".into()),
+        size,
+        usage: BufferUsages::COPY_DST | BufferUsages::COPY_SRC,
+        mapped_at_creation: false,
+    })
+}
+
+/// Writes `data` at offset zero and submits it in its own encoder.
+fn upload(device: &Device, queue: &Queue, belt: &mut StagingBelt, target: &Buffer, data: &[u8]) {
+    let mut encoder = device.create_command_encoder(Default::default());
+    belt.write_buffer(&mut encoder, target, 0, data)
+        .expect("staged write");
+    belt.finish();
+    queue
+        .submit([encoder.finish().expect("finish encoder")])
+        .expect("submit");
+}
+
+/// Recalls until every chunk is mapped again.
+fn recall_all(device: &Device, belt: &mut StagingBelt) {
+    for _ in 0..8 {
+        belt.recall().expect("recall");
+        device.poll(PollMode::Wait).expect("poll");
+    }
+    belt.recall().expect("recall");
+}
+
+#[test]
+fn writes_between_finishes_share_a_chunk() {
+    pollster::block_on(async {
+        let Some((device, queue)) = common::device("staging belt").await else {
+            return;
+        };
+        let mut belt = StagingBelt::new(device.clone(), 256);
+        let first = target(&device, 64);
+        let second = target(&device, 64);
+        let mut encoders = Vec::new();
+        for target in [&first, &second] {
+            let mut encoder = device.create_command_encoder(Default::default());
+            belt.write_buffer(&mut encoder, target, 0, &[1; 64])
+                .expect("staged write");
+            encoders.push(encoder.finish().expect("finish encoder"));
+        }
+        assert_eq!(belt.chunk_count(), 1, "both encoders use the same chunk");
+        belt.finish();
+        queue.submit(encoders).expect("submit");
+        belt.recall().expect("recall");
+    });
+}
+
+#[test]
+fn recalled_chunks_are_reused_and_copies_land() {
+    pollster::block_on(async {
+        let Some((device, queue)) = common::device("staging belt").await else {
+            return;
+        };
+        let mut belt = StagingBelt::new(device.clone(), 256);
+        let destination = target(&device, 16);
+        let readback = device.create_buffer(BufferDescriptor {
+            label: Some("staging test readback".into()),
+            size: 16,
+            usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        for frame in 0..4u8 {
+            let data = [frame; 16];
+            let mut encoder = device.create_command_encoder(Default::default());
+            belt.write_buffer(&mut encoder, &destination, 0, &data)
+                .expect("staged write");
+            encoder
+                .copy_buffer_to_buffer(&destination, 0, &readback, 0, 16)
+                .expect("copy to readback");
+            belt.finish();
+            queue
+                .submit([encoder.finish().expect("finish encoder")])
+                .expect("submit");
+            recall_all(&device, &mut belt);
+            assert_eq!(belt.chunk_count(), 1, "frame {frame} reuses the chunk");
+
+            let mapping = readback.map_async(MapMode::Read, 0..16);
+            device.poll(PollMode::Wait).expect("poll");
+            mapping.await.expect("map readback");
+            assert_eq!(readback.read_mapped(0..16).expect("read readback"), data);
+            readback.unmap();
+        }
+    });
+}
+
+#[test]
+fn oversize_writes_get_a_dedicated_chunk() {
+    pollster::block_on(async {
+        let Some((device, queue)) = common::device("staging belt").await else {
+            return;
+        };
+        let mut belt = StagingBelt::new(device.clone(), 64);
+        let large = target(&device, 256);
+        let small = target(&device, 16);
+        let mut encoder = device.create_command_encoder(Default::default());
+        belt.write_buffer(&mut encoder, &small, 0, &[1; 16])
+            .expect("small write");
+        belt.write_buffer(&mut encoder, &large, 0, &[2; 256])
+            .expect("oversize write");
+        assert_eq!(belt.chunk_count(), 2);
+        belt.finish();
+        queue
+            .submit([encoder.finish().expect("finish encoder")])
+            .expect("submit");
+        recall_all(&device, &mut belt);
+
+        upload(&device, &queue, &mut belt, &large, &[3; 256]);
+        recall_all(&device, &mut belt);
+        assert_eq!(belt.chunk_count(), 2, "the oversize chunk is reused");
+
+        for _ in 0..64 {
+            belt.recall().expect("recall");
+        }
+        assert_eq!(belt.chunk_count(), 1, "the idle oversize chunk is trimmed");
+        upload(&device, &queue, &mut belt, &small, &[4; 16]);
+        assert_eq!(belt.chunk_count(), 1, "regular chunks are kept");
+    });
+}
+
+#[test]
+fn skipping_recall_is_an_error_instead_of_unbounded_growth() {
+    pollster::block_on(async {
+        let Some((device, queue)) = common::device("staging belt").await else {
+            return;
+        };
+        let mut belt = StagingBelt::new(device.clone(), 8);
+        let destination = target(&device, 8);
+        let mut error = None;
+        for _ in 0..1024 {
+            let mut encoder = device.create_command_encoder(Default::default());
+            if let Err(failure) = belt.write_buffer(&mut encoder, &destination, 0, &[0; 8]) {
+                error = Some(failure);
+                break;
+            }
+            belt.finish();
+            queue
+                .submit([encoder.finish().expect("finish encoder")])
+                .expect("submit");
+        }
+        let error = error.expect("the belt stops growing");
+        assert!(error.to_string().contains("call recall"), "{error}");
+        recall_all(&device, &mut belt);
+        upload(&device, &queue, &mut belt, &destination, &[1; 8]);
+    });
+}
@@ -2,11 +2,18 @@
 
 #![warn(missing_docs)]
 
-use std::{collections::HashMap, error::Error, fmt};
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt,
+};
 
-use astrelis_core::geometry::{LogicalRect, Physical, Rect, Size};
+use astrelis_core::{
+    alloc::{FrameArena, FrameVec},
+    geometry::{LogicalRect, Physical, Rect, Size},
+};
 use astrelis_gpu as gpu;
-use astrelis_text::{GlyphRun, TextLayout};
+use astrelis_text::{FontFace, GlyphRun, TextLayout};
 use etagere::{AtlasAllocator, size2};
 use swash::{
     FontRef,
@@ -14,22 +21,46 @@ use swash::{
         Render, ScaleContext, Source, StrikeWith,
         image::{Content, Image},
     },
+    zeno::{Format, Vector},
 };
 
+#[cfg(not(target_arch = "wasm32"))]
+mod worker;
+
 const SOURCES: [Source; 3] = [
     Source::ColorOutline(0),
     Source::ColorBitmap(StrikeWith::BestFit),
     Source::Outline,
 ];
 const PADDING: u32 = 1;
+const SUBPIXEL_BINS: f32 = 4.0;
+
+/// Outline glyph coverage mode.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TextAntialiasing {
+    /// One coverage value per pixel.
+    #[default]
+    Grayscale,
+    /// Separate coverage for horizontal RGB subpixels.
+    ///
+    /// This sharpens small text on standard-DPI LCD panels but produces
+    /// color fringes on rotated, scaled, or non-RGB-striped output.
+    Subpixel,
+}
 
 /// Persistent glyph-atlas configuration.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct GlyphCacheOptions {
     /// Width and height of newly allocated atlas pages.
     pub page_size: u32,
     /// Soft limit for all mask and color atlas texture bytes.
     pub max_bytes: usize,
+    /// Outline glyph coverage mode.
+    pub antialiasing: TextAntialiasing,
+    /// Rasterizes quarter-pixel horizontal offsets instead of one pixel grid.
+    pub subpixel_positioning: bool,
+    /// Coverage gamma; values above one thicken light-on-dark strokes.
+    pub gamma: f32,
 }
 
 impl Default for GlyphCacheOptions {
@@ -37,6 +68,9 @@ impl Default for GlyphCacheOptions {
         Self {
             page_size: 2_048,
             max_bytes: 64 << 20,
+            antialiasing: TextAntialiasing::Grayscale,
+            subpixel_positioning: false,
+            gamma: 1.0,
         }
     }
 }
@@ -72,6 +106,8 @@ pub enum AtlasKind {
     Mask,
     /// Straight-alpha sRGB color glyph.
     Color,
+    /// Linear per-channel RGB coverage mask.
+    Subpixel,
 }
 
 /// One GPU-ready glyph quad.
@@ -96,6 +132,8 @@ pub struct GlyphCacheStats {
     pub misses: u32,
     /// Newly uploaded non-empty glyph images.
     pub uploads: u32,
+    /// Glyphs left blank while a background worker rasterizes them.
+    pub pending: u32,
 }
 
 #[derive(Clone, PartialEq, Eq, Hash)]
@@ -104,9 +142,17 @@ struct GlyphKey {
     font_index: u32,
     glyph: u32,
     ppem_quarters: u32,
+    x_bin: u8,
     coords: Vec<i16>,
 }
 
+struct RasterJob {
+    key: GlyphKey,
+    font: FontFace,
+    physical_scale: f32,
+    format: Format,
+}
+
 #[derive(Clone)]
 struct CachedGlyph {
     page: usize,
@@ -131,8 +177,13 @@ pub struct GlyphCache {
     layout: gpu::BindGroupLayout,
     sampler: gpu::Sampler,
     scale_context: ScaleContext,
+    gamma_table: [u8; 256],
     pages: Vec<AtlasPage>,
     glyphs: HashMap<GlyphKey, CachedGlyph>,
+    pending: HashSet<GlyphKey>,
+    blank: HashSet<GlyphKey>,
+    #[cfg(not(target_arch = "wasm32"))]
+    worker: Option<worker::RasterWorker>,
     frame: u64,
 }
 
@@ -148,6 +199,11 @@ impl GlyphCache {
                 "device and queue belong to different devices",
             ));
         }
+        if !options.gamma.is_finite() || options.gamma <= 0.0 {
+            return Err(GlyphCacheError::new(
+                "glyph coverage gamma must be finite and positive",
+            ));
+        }
         let maximum = device.capabilities().limits.max_texture_dimension_2d;
         options.page_size = options.page_size.clamp(64, maximum);
         let layout = device.create_bind_group_layout(gpu::BindGroupLayoutDescriptor {
@@ -181,8 +237,13 @@ impl GlyphCache {
             layout,
             sampler,
             scale_context: ScaleContext::new(),
+            gamma_table: gamma_table(options.gamma),
             pages: Vec::new(),
             glyphs: HashMap::new(),
+            pending: HashSet::new(),
+            blank: HashSet::new(),
+            #[cfg(not(target_arch = "wasm32"))]
+            worker: None,
             frame: 0,
         })
     }
@@ -192,6 +253,35 @@ impl GlyphCache {
         self.layout.clone()
     }
 
+    /// Moves rasterization of newly seen glyphs onto a background thread.
+    ///
+    /// Until a glyph's image arrives its quad is omitted, leaving its advance
+    /// as blank space, and [`GlyphCacheStats::pending`] counts it. The worker
+    /// calls `wake` after each finished glyph so a reactive host can request
+    /// another frame. On wasm this is a no-op and rasterization stays
+    /// synchronous. Calling it again replaces any existing worker.
+    pub fn enable_async_rasterization<W>(&mut self, wake: W)
+    where
+        W: Fn() + Send + Sync + 'static,
+    {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            // Results belong to the worker that produced them, so glyphs still
+            // in flight on a replaced worker must be requested again.
+            self.pending.clear();
+            self.worker = Some(worker::RasterWorker::spawn(wake));
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            let _ = wake;
+        }
+    }
+
+    /// Returns whether any glyph is waiting on the background worker.
+    pub fn has_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
     /// Begins a preparation frame and pins pages used during that frame.
     pub fn begin_frame(&mut self) {
         self.frame = self.frame.wrapping_add(1);
@@ -203,23 +293,57 @@ impl GlyphCache {
         text: &TextLayout,
         physical_scale: f32,
     ) -> Result<(Vec<(usize, PreparedGlyph)>, GlyphCacheStats), GlyphCacheError> {
+        let mut prepared = Vec::new();
+        let stats = self.prepare_into(text, physical_scale, &mut prepared)?;
+        Ok((prepared, stats))
+    }
+
+    /// Prepares every visible glyph in a retained layout into per-frame
+    /// scratch storage, avoiding a heap allocation per text draw.
+    pub fn prepare_layout_in<'a>(
+        &mut self,
+        arena: &'a FrameArena,
+        text: &TextLayout,
+        physical_scale: f32,
+    ) -> Result<(FrameVec<'a, (usize, PreparedGlyph)>, GlyphCacheStats), GlyphCacheError> {
+        let mut prepared = arena.vec();
+        let stats = self.prepare_into(text, physical_scale, &mut prepared)?;
+        Ok((prepared, stats))
+    }
+
+    fn prepare_into(
+        &mut self,
+        text: &TextLayout,
+        physical_scale: f32,
+        prepared: &mut impl Extend<(usize, PreparedGlyph)>,
+    ) -> Result<GlyphCacheStats, GlyphCacheError> {
         if !physical_scale.is_finite() || physical_scale <= 0.0 {
             return Err(GlyphCacheError::new(
                 "physical text scale must be finite and positive",
             ));
         }
-        let mut prepared = Vec::new();
         let mut stats = GlyphCacheStats::default();
+        self.receive_rasterized(&mut stats)?;
         for (run_index, run) in text.glyph_runs().iter().enumerate() {
             for glyph in run.glyphs.iter() {
+                let x_bin = if self.options.subpixel_positioning {
+                    let fraction = (glyph.position.x * physical_scale).rem_euclid(1.0);
+                    ((fraction * SUBPIXEL_BINS).round() as u8) % SUBPIXEL_BINS as u8
+                } else {
+                    0
+                };
+                // The rasterized offset is baked into the bitmap placement, so
+                // the quad starts from the pixel-aligned remainder.
+                let snapped_x =
+                    glyph.position.x - f32::from(x_bin) / SUBPIXEL_BINS / physical_scale;
                 if let Some(value) =
-                    self.prepare_glyph(run, glyph.id, physical_scale, &mut stats)?
+                    self.prepare_glyph(run, glyph.id, x_bin, physical_scale, &mut stats)?
                 {
-                    prepared.push((
+                    prepared.extend([(
                         run_index,
                         PreparedGlyph {
                             rect: Rect::from_xywh(
-                                glyph.position.x + value.rect.origin.x,
+                                snapped_x + value.rect.origin.x,
                                 glyph.position.y + value.rect.origin.y,
                                 value.rect.size.width,
                                 value.rect.size.height,
@@ -228,11 +352,11 @@ impl GlyphCache {
                             bind_group: self.pages[value.page].bind_group.clone(),
                             kind: value.kind,
                         },
-                    ));
+                    )]);
                 }
             }
         }
-        Ok((prepared, stats))
+        Ok(stats)
     }
 
     /// Evicts unpinned whole pages until the soft budget is met.
@@ -254,6 +378,8 @@ impl GlyphCache {
     /// Clears all glyphs and atlas pages.
     pub fn clear(&mut self) {
         self.glyphs.clear();
+        self.pending.clear();
+        self.blank.clear();
         self.pages.clear();
     }
 
@@ -264,7 +390,7 @@ impl GlyphCache {
             .map(|page| {
                 let channels = match page.kind {
                     AtlasKind::Mask => 1,
-                    AtlasKind::Color => 4,
+                    AtlasKind::Color | AtlasKind::Subpixel => 4,
                 };
                 self.options.page_size as usize * self.options.page_size as usize * channels
             })
@@ -275,6 +401,7 @@ impl GlyphCache {
         &mut self,
         run: &GlyphRun,
         glyph: u32,
+        x_bin: u8,
         physical_scale: f32,
         stats: &mut GlyphCacheStats,
     ) -> Result<Option<CachedGlyph>, GlyphCacheError> {
@@ -284,6 +411,7 @@ impl GlyphCache {
             font_index: run.font.cache_id().1,
             glyph,
             ppem_quarters,
+            x_bin,
             coords: run.normalized_coords.to_vec(),
         };
         if let Some(cached) = self.glyphs.get(&key).cloned() {
@@ -291,30 +419,82 @@ impl GlyphCache {
             stats.hits += 1;
             return Ok(Some(cached));
         }
-        stats.misses += 1;
-        let font = FontRef::from_index(run.font.data(), run.font.index() as usize)
-            .ok_or_else(|| GlyphCacheError::new("resolved font could not be parsed by Swash"))?;
-        let ppem = ppem_quarters as f32 / 4.0;
-        let mut scaler = self
-            .scale_context
-            .builder_with_id(
-                font,
-                [run.font.cache_id().0, u64::from(run.font.cache_id().1)],
-            )
-            .size(ppem)
-            .hint(true)
-            .normalized_coords(run.normalized_coords.iter())
-            .build();
-        let Some(image) = Render::new(&SOURCES).render(&mut scaler, glyph as u16) else {
-            return Ok(None);
+        let job = RasterJob {
+            key,
+            font: run.font.clone(),
+            physical_scale,
+            format: match self.options.antialiasing {
+                TextAntialiasing::Grayscale => Format::Alpha,
+                TextAntialiasing::Subpixel => Format::Subpixel,
+            },
         };
-        if image.placement.width == 0 || image.placement.height == 0 {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(worker) = &self.worker {
+            if !self.blank.contains(&job.key) {
+                if self.pending.insert(job.key.clone()) {
+                    stats.misses += 1;
+                    worker.send(job);
+                }
+                stats.pending += 1;
+            }
             return Ok(None);
         }
+        stats.misses += 1;
+        let image = rasterize(&mut self.scale_context, &job)?;
+        self.upload(job.key, image, physical_scale, stats)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn receive_rasterized(&mut self, stats: &mut GlyphCacheStats) -> Result<(), GlyphCacheError> {
+        let Some(worker) = self.worker.take() else {
+            return Ok(());
+        };
+        let mut result = Ok(());
+        for done in worker.try_drain() {
+            // Results for glyphs dropped by `clear` are still valid images but
+            // are no longer wanted.
+            if !self.pending.remove(&done.key) {
+                continue;
+            }
+            match done.image {
+                Ok(None) => {
+                    self.blank.insert(done.key);
+                }
+                Ok(image) => {
+                    if let Err(error) = self.upload(done.key, image, done.physical_scale, stats) {
+                        result = Err(error);
+                        break;
+                    }
+                }
+                Err(error) => {
+                    result = Err(GlyphCacheError::new(error));
+                    break;
+                }
+            }
+        }
+        self.worker = Some(worker);
+        result
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn receive_rasterized(&mut self, _stats: &mut GlyphCacheStats) -> Result<(), GlyphCacheError> {
+        Ok(())
+    }
+
+    fn upload(
+        &mut self,
+        key: GlyphKey,
+        image: Option<Image>,
+        physical_scale: f32,
+        stats: &mut GlyphCacheStats,
+    ) -> Result<Option<CachedGlyph>, GlyphCacheError> {
+        let Some(image) = image else {
+            return Ok(None);
+        };
         let kind = match image.content {
             Content::Mask => AtlasKind::Mask,
             Content::Color => AtlasKind::Color,
-            Content::SubpixelMask => AtlasKind::Color,
+            Content::SubpixelMask => AtlasKind::Subpixel,
         };
         let page = self.allocate_page(
             kind,
@@ -330,10 +510,10 @@ impl GlyphCache {
             .expect("page allocation was checked");
         let x = allocation.rectangle.min.x as u32 + PADDING;
         let y = allocation.rectangle.min.y as u32 + PADDING;
-        let data = normalize_image(&image);
+        let data = normalize_image(&image, &self.gamma_table);
         let channels = match kind {
             AtlasKind::Mask => 1,
-            AtlasKind::Color => 4,
+            AtlasKind::Color | AtlasKind::Subpixel => 4,
         };
         self.queue.write_texture(
             &gpu::TextureCopy {
@@ -398,6 +578,7 @@ impl GlyphCache {
         let format = match kind {
             AtlasKind::Mask => gpu::TextureFormat::R8Unorm,
             AtlasKind::Color => gpu::TextureFormat::Rgba8UnormSrgb,
+            AtlasKind::Subpixel => gpu::TextureFormat::Rgba8Unorm,
         };
         let texture = self.device.create_texture(gpu::TextureDescriptor {
             label: Some("text glyph atlas".into()),
@@ -451,18 +632,151 @@ impl GlyphCache {
     }
 }
 
-fn normalize_image(image: &Image) -> Vec<u8> {
+fn rasterize(
+    scale_context: &mut ScaleContext,
+    job: &RasterJob,
+) -> Result<Option<Image>, GlyphCacheError> {
+    let font = FontRef::from_index(job.font.data(), job.font.index() as usize)
+        .ok_or_else(|| GlyphCacheError::new("resolved font could not be parsed by Swash"))?;
+    let mut scaler = scale_context
+        .builder_with_id(font, [job.key.font_blob, u64::from(job.key.font_index)])
+        .size(job.key.ppem_quarters as f32 / 4.0)
+        .hint(true)
+        .normalized_coords(job.key.coords.iter())
+        .build();
+    let image = Render::new(&SOURCES)
+        .format(job.format)
+        .offset(Vector::new(f32::from(job.key.x_bin) / SUBPIXEL_BINS, 0.0))
+        .render(&mut scaler, job.key.glyph as u16);
+    Ok(image.filter(|image| image.placement.width != 0 && image.placement.height != 0))
+}
+
+fn normalize_image(image: &Image, gamma: &[u8; 256]) -> Vec<u8> {
     match image.content {
-        Content::Mask | Content::Color => image.data.clone(),
+        Content::Color => image.data.clone(),
+        Content::Mask => image
+            .data
+            .iter()
+            .map(|&value| gamma[value as usize])
+            .collect(),
         Content::SubpixelMask => image
             .data
             .chunks_exact(4)
-            .flat_map(|pixel| [255, 255, 255, pixel[0].max(pixel[1]).max(pixel[2])])
+            .flat_map(|pixel| {
+                let [r, g, b] = [pixel[0], pixel[1], pixel[2]].map(|value| gamma[value as usize]);
+                [r, g, b, r.max(g).max(b)]
+            })
             .collect(),
     }
 }
 
+fn gamma_table(gamma: f32) -> [u8; 256] {
+    std::array::from_fn(|index| ((index as f32 / 255.0).powf(gamma.recip()) * 255.0).round() as u8)
+}
+
 #[allow(dead_code)]
 fn _physical_size(value: u32) -> Size<Physical, u32> {
     Size::new(value, value)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unit_gamma_preserves_coverage() {
+        let table = gamma_table(1.0);
+        assert!(
+            table
+                .iter()
+                .enumerate()
+                .all(|(index, &value)| value as usize == index)
+        );
+        let thick = gamma_table(2.2);
+        assert_eq!((thick[0], thick[255]), (0, 255));
+        assert!(thick[64] > 64);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn worker_rasterizes_glyphs_off_thread() {
+        let mut fonts = astrelis_text::FontDatabase::default();
+        let layout = astrelis_text::TextLayoutContext::new()
+            .layout(&mut fonts, astrelis_text::TextLayoutRequest::new("I"))
+            .expect("layout");
+        let run = &layout.glyph_runs()[0];
+        let job = RasterJob {
+            key: GlyphKey {
+                font_blob: run.font.cache_id().0,
+                font_index: run.font.cache_id().1,
+                glyph: run.glyphs[0].id,
+                ppem_quarters: 64,
+                x_bin: 0,
+                coords: Vec::new(),
+            },
+            font: run.font.clone(),
+            physical_scale: 1.0,
+            format: Format::Alpha,
+        };
+        let (wake_tx, wake_rx) = std::sync::mpsc::channel();
+        let worker = worker::RasterWorker::spawn(move || {
+            let _ = wake_tx.send(());
+        });
+        worker.send(job);
+        wake_rx
+            .recv_timeout(std::time::Duration::from_secs(10))
+            .expect("worker wakes after rasterizing");
+        let done = worker.try_drain().next().expect("finished glyph");
+        let image = done.image.expect("rasterized").expect("visible glyph");
+        assert!(image.placement.width > 0 && image.placement.height > 0);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn dropping_worker_discards_queued_jobs() {
+        let mut fonts = astrelis_text::FontDatabase::default();
+        let layout = astrelis_text::TextLayoutContext::new()
+            .layout(&mut fonts, astrelis_text::TextLayoutRequest::new("I"))
+            .expect("layout");
+        let run = &layout.glyph_runs()[0];
+        let job = |ppem_quarters| RasterJob {
+            key: GlyphKey {
+                font_blob: run.font.cache_id().0,
+                font_index: run.font.cache_id().1,
+                glyph: run.glyphs[0].id,
+                ppem_quarters,
+                x_bin: 0,
+                coords: Vec::new(),
+            },
+            font: run.font.clone(),
+            physical_scale: 1.0,
+            format: Format::Alpha,
+        };
+        let wakes = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let (started_tx, started_rx) = std::sync::mpsc::channel();
+        let (release_tx, release_rx) = std::sync::mpsc::channel::<()>();
+        let release_rx = std::sync::Mutex::new(release_rx);
+        let counter = wakes.clone();
+        let worker = worker::RasterWorker::spawn(move || {
+            if counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst) == 0 {
+                let _ = started_tx.send(());
+                let _ = release_rx.lock().expect("release lock").recv();
+            }
+        });
+        for index in 0..64 {
+            worker.send(job(64 + index));
+        }
+        started_rx
+            .recv_timeout(std::time::Duration::from_secs(10))
+            .expect("worker finishes its first glyph");
+        // Hold the worker inside its first wake until the drop has raised the
+        // stop flag; every job still queued must then be skipped.
+        let releaser = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            let _ = release_tx.send(());
+        });
+        drop(worker);
+        releaser.join().expect("releaser");
+        assert_eq!(wakes.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+}
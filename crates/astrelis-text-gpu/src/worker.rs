@@ -0,0 +1,119 @@
+//! Background glyph rasterization.
+//!
+//! Outline scaling and coverage rasterization dominate the cost of first
+//! displaying a large block of text. With a worker enabled, the cache records
+//! each miss as pending, leaves its space empty for the current frame, and
+//! rasterizes on a single background thread. Finished images travel back over
+//! a channel and are uploaded at the start of the next `prepare_layout`.
+//!
+//! Atlas allocation and texture uploads stay on the owning thread: the device
+//! and queue are shared handles, but page eviction and glyph placement must be
+//! serialized with frame preparation anyway.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{Receiver, Sender};
+
+use swash::scale::{ScaleContext, image::Image};
+
+use super::{GlyphKey, RasterJob, rasterize};
+
+/// A unit of work sent to the rasterization worker.
+pub(crate) enum WorkerJob {
+    /// Rasterize one glyph.
+    Raster(RasterJob),
+    /// Wakes a worker blocked on an empty queue so it observes the stop flag.
+    Stop,
+}
+
+/// A completed rasterization returned by the worker.
+pub(crate) struct WorkerDone {
+    /// Cache key the image belongs to.
+    pub(crate) key: GlyphKey,
+    /// Scale used to convert the image placement back to logical units.
+    pub(crate) physical_scale: f32,
+    /// The image, `None` for glyphs without visible coverage, or the
+    /// rasterization error rendered as a string.
+    pub(crate) image: Result<Option<Image>, String>,
+}
+
+/// Handle to the background rasterization thread and its result channel.
+pub(crate) struct RasterWorker {
+    job_tx: Sender<WorkerJob>,
+    done_rx: Receiver<WorkerDone>,
+    stop: Arc<AtomicBool>,
+    handle: Option<std::thread::JoinHandle<()>>,
+}
+
+impl RasterWorker {
+    /// Spawns the worker thread, which calls `wake` after posting each result
+    /// so a reactive host can schedule a frame.
+    pub(crate) fn spawn<W>(wake: W) -> Self
+    where
+        W: Fn() + Send + Sync + 'static,
+    {
+        let (job_tx, job_rx) = std::sync::mpsc::channel::<WorkerJob>();
+        let (done_tx, done_rx) = std::sync::mpsc::channel::<WorkerDone>();
+        let stop = Arc::new(AtomicBool::new(false));
+        let worker_stop = stop.clone();
+        let handle = std::thread::Builder::new()
+            .name("astrelis-glyphs".to_owned())
+            .spawn(move || run(&job_rx, &done_tx, &worker_stop, wake))
+            .expect("failed to spawn glyph rasterization worker");
+        Self {
+            job_tx,
+            done_rx,
+            stop,
+            handle: Some(handle),
+        }
+    }
+
+    /// Enqueues a job. A send failure means the worker thread has already
+    /// gone; the glyph then stays pending until the cache is cleared.
+    pub(crate) fn send(&self, job: RasterJob) {
+        let _ = self.job_tx.send(WorkerJob::Raster(job));
+    }
+
+    /// Non-blocking drain of every result ready right now.
+    pub(crate) fn try_drain(&self) -> impl Iterator<Item = WorkerDone> + '_ {
+        self.done_rx.try_iter()
+    }
+}
+
+fn run<W>(job_rx: &Receiver<WorkerJob>, done_tx: &Sender<WorkerDone>, stop: &AtomicBool, wake: W)
+where
+    W: Fn(),
+{
+    let mut scale_context = ScaleContext::new();
+    while let Ok(WorkerJob::Raster(job)) = job_rx.recv() {
+        // Checked per job so a drop discards the backlog instead of
+        // rasterizing every glyph still queued ahead of `Stop`.
+        if stop.load(Ordering::Acquire) {
+            break;
+        }
+        let image = rasterize(&mut scale_context, &job).map_err(|error| error.to_string());
+        if done_tx
+            .send(WorkerDone {
+                key: job.key,
+                physical_scale: job.physical_scale,
+                image,
+            })
+            .is_err()
+        {
+            break;
+        }
+        wake();
+    }
+}
+
+/// Raises the stop flag, wakes an idle worker, and joins it. Queued jobs are
+/// discarded, so the join waits for at most the glyph currently in progress.
+impl Drop for RasterWorker {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Release);
+        let _ = self.job_tx.send(WorkerJob::Stop);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
@@ -21,7 +21,8 @@ use parley::{
     Alignment as ParleyAlignment, AlignmentOptions, FontContext, FontFamily as ParleyFontFamily,
     FontFamilyName, FontStyle as ParleyFontStyle, FontWeight as ParleyFontWeight, FontWidth,
     GenericFamily, Layout as ParleyLayout, LayoutContext as ParleyLayoutContext,
-    LineHeight as ParleyLineHeight, PositionedLayoutItem, StyleProperty, TextWrapMode,
+    LineHeight as ParleyLineHeight, OverflowWrap, PositionedLayoutItem, StyleProperty,
+    TextWrapMode, WordBreak,
     editing::{Cursor, Selection},
     fontique::{Blob, Collection, CollectionOptions, SourceCache},
     layout::Affinity as ParleyAffinity,
@@ -263,10 +264,22 @@ pub enum TextWrap {
     /// Wrap at Unicode line-breaking opportunities.
     #[default]
     Wrap,
+    /// Wrap like [`TextWrap::Wrap`], but split a word that cannot fit on a
+    /// line by itself at any grapheme boundary.
+    BreakAnywhere,
+    /// Wrap only between words, including in CJK text that would otherwise
+    /// break between any two ideographs.
+    KeepAll,
     /// Only break at explicit line endings.
     NoWrap,
 }
 
+impl TextWrap {
+    const fn wraps(self) -> bool {
+        !matches!(self, Self::NoWrap)
+    }
+}
+
 /// Horizontal paragraph alignment.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum TextAlignment {
@@ -325,6 +338,20 @@ impl TextLayoutRequest {
             paragraph: ParagraphStyle::default(),
         }
     }
+
+    /// Appends a styled span after any existing spans.
+    ///
+    /// The range must start at or after the end of the previous span; this is
+    /// checked when the request is laid out.
+    pub fn with_span(mut self, range: Range<usize>, style: TextStylePatch) -> Self {
+        self.spans.push(TextSpan { range, style });
+        self
+    }
+
+    /// Checks styles, paragraph settings, and span ranges without shaping.
+    pub fn validate(&self) -> Result<(), TextError> {
+        validate_request(self)
+    }
 }
 
 /// Reusable scratch context for shaping and constructing layouts.
@@ -346,23 +373,30 @@ impl TextLayoutContext {
         fonts: &mut FontDatabase,
         request: TextLayoutRequest,
     ) -> Result<TextLayout, TextError> {
-        validate_request(&request)?;
+        request.validate()?;
         let mut builder = self
             .inner
             .ranged_builder(&mut fonts.context, &request.text, 1.0, false);
         push_default_style(&mut builder, &request.style);
-        builder.push_default(StyleProperty::TextWrapMode(match request.paragraph.wrap {
-            TextWrap::Wrap => TextWrapMode::Wrap,
-            TextWrap::NoWrap => TextWrapMode::NoWrap,
+        let wrap = request.paragraph.wrap;
+        builder.push_default(StyleProperty::TextWrapMode(if wrap.wraps() {
+            TextWrapMode::Wrap
+        } else {
+            TextWrapMode::NoWrap
+        }));
+        builder.push_default(StyleProperty::WordBreak(match wrap {
+            TextWrap::KeepAll => WordBreak::KeepAll,
+            _ => WordBreak::Normal,
+        }));
+        builder.push_default(StyleProperty::OverflowWrap(match wrap {
+            TextWrap::BreakAnywhere => OverflowWrap::Anywhere,
+            _ => OverflowWrap::Normal,
         }));
         for span in &request.spans {
             push_patch(&mut builder, &span.style, span.range.clone());
         }
         let mut layout = builder.build(&request.text);
-        layout.break_all_lines(match request.paragraph.wrap {
-            TextWrap::Wrap => request.paragraph.max_width,
-            TextWrap::NoWrap => None,
-        });
+        layout.break_all_lines(request.paragraph.max_width.filter(|_| wrap.wraps()));
         layout.align(
             match request.paragraph.alignment {
                 TextAlignment::Start => ParleyAlignment::Start,
@@ -909,6 +943,55 @@ mod tests {
         assert!(context.layout(&mut fonts, request).is_err());
     }
 
+    #[test]
+    fn span_builder_requires_sorted_ranges() {
+        let bold = TextStylePatch {
+            weight: Some(700.0),
+            ..Default::default()
+        };
+        let request = TextLayoutRequest::new("rich text")
+            .with_span(0..4, bold.clone())
+            .with_span(5..9, bold.clone());
+        assert!(request.validate().is_ok());
+        assert!(request.with_span(2..3, bold).validate().is_err());
+    }
+
+    #[test]
+    fn break_anywhere_splits_words_wider_than_the_line() {
+        let mut fonts = FontDatabase::default();
+        let mut context = TextLayoutContext::new();
+        let mut lines = |wrap| {
+            let mut request = TextLayoutRequest::new("unbreakableword");
+            request.paragraph.max_width = Some(30.0);
+            request.paragraph.wrap = wrap;
+            context
+                .layout(&mut fonts, request)
+                .expect("layout")
+                .lines()
+                .len()
+        };
+        assert_eq!(lines(TextWrap::Wrap), 1);
+        assert!(lines(TextWrap::BreakAnywhere) > 1);
+    }
+
+    #[test]
+    fn keep_all_only_breaks_cjk_text_between_words() {
+        let mut fonts = FontDatabase::default();
+        let mut context = TextLayoutContext::new();
+        let mut lines = |wrap| {
+            let mut request = TextLayoutRequest::new("日本語の文章 を折り返す");
+            request.paragraph.max_width = Some(30.0);
+            request.paragraph.wrap = wrap;
+            context
+                .layout(&mut fonts, request)
+                .expect("layout")
+                .lines()
+                .len()
+        };
+        assert!(lines(TextWrap::Wrap) > 2);
+        assert_eq!(lines(TextWrap::KeepAll), 2);
+    }
+
     #[test]
     fn system_layout_supports_hit_testing_and_selection() {
         let mut fonts = FontDatabase::default();
@@ -10,7 +10,9 @@ use astrelis_gpu::{
 };
 use astrelis_paint::{Brush, CornerRadii, Painter, RoundedRect, StrokeStyle};
 use astrelis_paint_gpu::{RenderTarget, Renderer, RendererOptions};
-use astrelis_platform::{CursorIcon, Window, WindowAttributes, WindowEvent, WindowId};
+use astrelis_platform::{
+    CursorIcon, CursorRequests, Window, WindowAttributes, WindowEvent, WindowId,
+};
 use astrelis_text::{FontDatabase, FontFamily};
 use astrelis_ui_core::{
     Column, Edges, ElementHandle, EventFilter, FlexStyle, FlexWrap, FocusScopeOptions, Insets,
@@ -103,6 +105,7 @@ struct Settings {
     window: Option<Window>,
     gpu: Option<GpuState>,
     ui: Ui<Message>,
+    cursors: CursorRequests,
     handles: Handles,
     overlay_open: bool,
 }
@@ -375,6 +378,7 @@ impl Settings {
         Ok(Self {
             instance: astrelis_gpu_wgpu::create_instance(descriptor),
             window: None,
+            cursors: CursorRequests::new(),
             gpu: None,
             ui,
             handles: Handles {
@@ -462,9 +466,11 @@ impl Settings {
                 },
             )
             .map_err(io::Error::other)?;
+        gpu.renderer.before_submit();
         gpu.queue
             .submit([encoder.finish().map_err(io::Error::other)?])
             .map_err(io::Error::other)?;
+        gpu.renderer.after_submit().map_err(io::Error::other)?;
         frame.present().map_err(io::Error::other)?;
         #[cfg(target_arch = "wasm32")]
         set_web_status(None);
@@ -690,6 +696,8 @@ impl App for Settings {
                 .ui
                 .handle_window_event(window, &context.clipboard(), &event)
                 .map_err(io::Error::other)?;
+            self.ui.request_cursor(&mut self.cursors);
+            self.cursors.apply(window).map_err(io::Error::other)?;
             self.consume_events()?;
             if update.redraw || self.ui.needs_redraw() {
                 context.invalidate_window(id);
@@ -167,7 +167,7 @@ impl<Message: 'static> Ui<Message> {
     pub(crate) fn semantic_node(&self, id: ElementId) -> Result<SemanticNode, UiError> {
         let node = self.node(id)?;
         let (role, label, value, selection, actions) = match &node.kind {
-            Kind::Label { text } => (SemanticRole::Label, text.clone(), None, None, vec![]),
+            Kind::Label { text, .. } => (SemanticRole::Label, text.clone(), None, None, vec![]),
             Kind::Button { text } => (
                 SemanticRole::Button,
                 text.clone(),
@@ -30,6 +30,8 @@ pub enum EventFilter {
     Keyboard,
     /// Wheel or trackpad scrolling.
     Scroll,
+    /// Raw touch and stylus contacts.
+    Touch,
     /// In-process drag-and-drop lifecycle events.
     Drag,
 }
@@ -185,6 +187,23 @@ pub enum RoutedEventKind {
         /// Cancelled pointer identity.
         device_id: DeviceId,
     },
+    /// A raw touch or stylus contact changed, before pointer emulation.
+    ///
+    /// Preventing the default on `Started` claims the contact: it is then
+    /// delivered only as `Touch` events, so gesture recognizers can track
+    /// several contacts without synthesized pointer presses.
+    Touch {
+        /// Normalized pointer identity shared with the emulated pointer.
+        device_id: DeviceId,
+        /// Platform contact identifier.
+        id: u64,
+        /// Logical window position.
+        position: LogicalPoint,
+        /// Contact phase.
+        phase: TouchPhase,
+        /// Pressure and stylus altitude, when the device reports them.
+        force: Option<TouchForce>,
+    },
     /// Keyboard input.
     Keyboard(KeyboardInput),
     /// Input-method composition.
@@ -294,6 +313,7 @@ impl RoutedEventKind {
                     )
                     | (EventFilter::Keyboard, Self::Keyboard(_) | Self::Ime(_))
                     | (EventFilter::Scroll, Self::Scroll { .. })
+                    | (EventFilter::Touch, Self::Touch { .. })
                     | (
                         EventFilter::Drag,
                         Self::DragStarted { .. }
@@ -104,7 +104,7 @@ impl<Message: 'static> Ui<Message> {
             .unwrap_or_default();
         self.hover_paths.insert(device_id, new_path);
         self.hover = target;
-        for index in 0..self.slots.len() {
+        for index in 0..self.nodes.slot_len() {
             let Some(id) = self.id_at(index) else {
                 continue;
             };
@@ -554,7 +554,7 @@ impl<Message: 'static> Ui<Message> {
         // resweep (theme/viewport, or a custom widget resizing itself) falls
         // back to the full walk.
         if self.measure_resweep {
-            for index in 0..self.slots.len() {
+            for index in 0..self.nodes.slot_len() {
                 if let Some(id) = self.id_at(index) {
                     self.sync_taffy_node(cache, id)?;
                 }
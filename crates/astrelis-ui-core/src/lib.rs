@@ -11,6 +11,7 @@ use std::{
 };
 
 use astrelis_core::{
+    alloc::{Handle, HandleMap},
     color::Color,
     geometry::{LogicalPoint, LogicalRect, LogicalSize, PhysicalRect, Point, Rect, Size},
     math::{Affine2, Vec2},
@@ -19,13 +20,13 @@ use astrelis_paint::{
     Brush, CornerRadii, DisplayList, Painter, RoundedRect, ShadowStyle, StrokeStyle,
 };
 use astrelis_platform::{
-    Clipboard, CursorIcon, DeviceId, ElementState, ImeEvent, ImePurpose, Key, KeyboardInput,
-    Modifiers, NamedKey, PlatformError, PointerButton, ScrollDelta, TouchPhase, Window,
-    WindowEvent,
+    Clipboard, CursorIcon, CursorRequests, DeviceId, ElementState, ImeEvent, ImePurpose, Key,
+    KeyboardInput, Modifiers, NamedKey, PlatformError, PointerButton, ScrollDelta, TouchForce,
+    TouchPhase, Window, WindowEvent,
 };
 use astrelis_text::{
     Affinity, CaretMovement, FontDatabase, ParagraphStyle, TextLayout, TextLayoutContext,
-    TextLayoutRequest, TextPosition, TextWrap,
+    TextLayoutRequest, TextPosition, TextSpan, TextWrap,
 };
 use bitflags::bitflags;
 use taffy::prelude::{
@@ -95,8 +96,7 @@ bitflags! {
 
 /// Persistent UI tree associated with one native window.
 pub struct Ui<Message = ()> {
-    pub(crate) slots: Vec<Slot>,
-    pub(crate) free: Vec<u32>,
+    pub(crate) nodes: HandleMap<Node>,
     pub(crate) taffy_cache: TaffyCache,
     pub(crate) root: ElementId,
     pub(crate) theme: Theme,
@@ -134,10 +134,13 @@ pub struct Ui<Message = ()> {
     pub(crate) hover: Option<ElementId>,
     pub(crate) hover_paths: HashMap<DeviceId, Vec<ElementId>>,
     pub(crate) capture: HashMap<DeviceId, ElementId>,
+    /// Touch contacts claimed by a raw `Touch` handler, which receive no
+    /// pointer emulation.
+    pub(crate) raw_touches: HashMap<DeviceId, ElementId>,
     pub(crate) pointer_positions: HashMap<DeviceId, LogicalPoint>,
     pub(crate) modifiers: Modifiers,
     pub(crate) window_focused: bool,
-    pub(crate) applied_cursor: Option<CursorIcon>,
+    pub(crate) cursor: CursorIcon,
     pub(crate) events: VecDeque<UiEvent>,
     pub(crate) messages: VecDeque<Message>,
     pub(crate) listeners: HashMap<ElementId, Vec<Listener<Message>>>,
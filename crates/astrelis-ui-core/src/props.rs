@@ -61,6 +61,7 @@ impl<Message: 'static> Ui<Message> {
             path.retain(|hovered| *hovered != id);
         }
         self.capture.retain(|_, captured| *captured != id);
+        self.raw_touches.retain(|_, claimed| *claimed != id);
         self.listeners.remove(&id);
         self.semantic_roles.remove(&id);
         self.semantic_labels.remove(&id);
@@ -72,9 +73,7 @@ impl<Message: 'static> Ui<Message> {
         if let Some(mut widget) = self.custom_widgets.remove(&id) {
             widget.unmounted();
         }
-        let slot = &mut self.slots[id.index as usize];
-        slot.node = None;
-        self.free.push(id.index);
+        self.nodes.remove(id.handle());
     }
 
     /// Changes an element's sizing constraints.
@@ -331,6 +330,35 @@ impl<Message: 'static> Ui<Message> {
         self.set_static_text(handle.id, text.into(), false)
     }
 
+    /// Replaces label text together with its styled spans.
+    ///
+    /// Spans are UTF-8 byte ranges into `text`, sorted and non-overlapping,
+    /// whose overrides apply on top of the themed label style. The paragraph
+    /// is shaped as one layout, so mixed colors, weights, and sizes wrap
+    /// together. [`Ui::set_label_text`] clears any spans.
+    pub fn set_label_spans(
+        &mut self,
+        handle: ElementHandle<Label>,
+        text: impl Into<String>,
+        spans: Vec<TextSpan>,
+    ) -> Result<(), UiError> {
+        let mut request = TextLayoutRequest::new(text);
+        request.spans = spans;
+        request
+            .validate()
+            .map_err(|error| UiError::new(error.to_string()))?;
+        let node = self.node_mut(handle.id)?;
+        let Kind::Label { text, spans } = &mut node.kind else {
+            return Err(UiError::new("handle has the wrong widget type"));
+        };
+        if *text != request.text || *spans != request.spans {
+            *text = request.text;
+            *spans = request.spans;
+            self.invalidate_node(handle.id, Dirty::all());
+        }
+        Ok(())
+    }
+
     /// Replaces button text.
     pub fn set_button_text(
         &mut self,
@@ -347,13 +375,17 @@ impl<Message: 'static> Ui<Message> {
         button: bool,
     ) -> Result<(), UiError> {
         let node = self.node_mut(id)?;
-        let text = match &mut node.kind {
-            Kind::Label { text } if !button => text,
-            Kind::Button { text } if button => text,
+        let (text, spans) = match &mut node.kind {
+            Kind::Label { text, spans } if !button => (text, Some(spans)),
+            Kind::Button { text } if button => (text, None),
             _ => return Err(UiError::new("handle has the wrong widget type")),
         };
-        if *text != value {
+        let styled = spans.as_ref().is_some_and(|spans| !spans.is_empty());
+        if *text != value || styled {
             *text = value;
+            if let Some(spans) = spans {
+                spans.clear();
+            }
             self.invalidate_node(id, Dirty::all());
         }
         Ok(())
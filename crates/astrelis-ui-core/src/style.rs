@@ -21,6 +21,13 @@ pub struct WidgetStyle {
     pub font_weight: Option<f32>,
 }
 
+astrelis_core::reflect_struct!(WidgetStyle {
+    foreground,
+    background,
+    font_size,
+    font_weight,
+});
+
 /// Optional visual overrides for a checkbox.
 ///
 /// Every field defaults to `None`, meaning "resolve from the active theme when
@@ -36,6 +43,12 @@ pub struct CheckboxStyle {
     pub radius: Option<f32>,
 }
 
+astrelis_core::reflect_struct!(CheckboxStyle {
+    background,
+    indicator,
+    radius,
+});
+
 /// Optional visual overrides for a horizontal slider.
 ///
 /// Unset fields resolve from the active theme at paint time; see
@@ -50,6 +63,12 @@ pub struct SliderStyle {
     pub thumb_size: Option<f32>,
 }
 
+astrelis_core::reflect_struct!(SliderStyle {
+    track,
+    thumb,
+    thumb_size,
+});
+
 /// Optional visual overrides for a vertical scroll view.
 ///
 /// Unset fields resolve from the active theme at paint time; see
@@ -73,6 +73,43 @@ fn handles_are_generational_and_subtree_removal_is_recursive() {
     assert_ne!(label.id(), replacement.id());
 }
 
+#[test]
+fn label_spans_shape_in_one_layout_and_reset_with_plain_text() {
+    let mut ui: Ui = Ui::new(async_test_fonts(), async_test_theme());
+    ui.set_viewport(LogicalSize::new(640.0, 480.0), 1.0);
+    let root = ui.root();
+    let label = ui.add_label(root, "small LARGE").unwrap();
+    let plain = ui.layout_bounds(label).unwrap();
+    let large = astrelis_text::TextStylePatch {
+        size: Some(48.0),
+        ..Default::default()
+    };
+    assert!(
+        ui.set_label_spans(
+            label,
+            "small LARGE",
+            vec![TextSpan {
+                range: 6..20,
+                style: large.clone(),
+            }],
+        )
+        .is_err()
+    );
+    ui.set_label_spans(
+        label,
+        "small LARGE",
+        vec![TextSpan {
+            range: 6..11,
+            style: large,
+        }],
+    )
+    .unwrap();
+    let styled = ui.layout_bounds(label).unwrap();
+    assert!(styled.size.height > plain.size.height);
+    ui.set_label_text(label, "small LARGE").unwrap();
+    assert_eq!(ui.layout_bounds(label).unwrap(), plain);
+}
+
 #[test]
 fn taffy_lays_out_rows_and_padding_without_leaking_types() {
     let mut ui = ui();
@@ -1732,6 +1769,32 @@ fn inspection_reports_widget_style_overrides() {
     assert_eq!(inspection.widget_style, style);
 }
 
+#[test]
+fn widget_styles_are_editable_through_reflection() {
+    use astrelis_core::reflect::{Reflect, Value};
+
+    let mut ui = ui();
+    let root = ui.root();
+    let label = ui.add_label(root, "styled").unwrap();
+    let mut style = ui.inspect_element(label).unwrap().widget_style;
+    assert_eq!(
+        style.field_names(),
+        ["foreground", "background", "font_size", "font_weight"]
+    );
+    style.set_field("font_size", Value::Int(21)).unwrap();
+    style
+        .set_field("background", Value::Color(Color::BLACK))
+        .unwrap();
+    assert!(style.set_field("font_size", Value::Bool(true)).is_err());
+    assert!(style.set_field("padding", Value::None).is_err());
+    ui.set_widget_style(label, style).unwrap();
+
+    let fields = ui.widget_style(label).unwrap().fields();
+    assert_eq!(fields[0], ("foreground", Value::None));
+    assert_eq!(fields[1], ("background", Value::Color(Color::BLACK)));
+    assert_eq!(fields[2], ("font_size", Value::Float(21.0)));
+}
+
 #[test]
 fn handles_recovered_from_ids_are_kind_checked() {
     let mut ui = ui();
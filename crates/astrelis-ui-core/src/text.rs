@@ -57,7 +57,7 @@ impl<Message: 'static> Ui<Message> {
         // widget) revisits everything. Snapshot the ids so the loop can take
         // `&mut self` per node.
         let ids: Vec<ElementId> = if self.measure_resweep {
-            (0..self.slots.len())
+            (0..self.nodes.slot_len())
                 .filter_map(|index| self.id_at(index))
                 .collect()
         } else {
@@ -186,7 +186,7 @@ impl<Message: 'static> Ui<Message> {
     fn build_text_request(&self, id: ElementId) -> Result<Option<TextLayoutRequest>, UiError> {
         let node = self.node(id)?;
         let request = match &node.kind {
-            Kind::Label { text } | Kind::Button { text } => {
+            Kind::Label { text, .. } | Kind::Button { text } => {
                 let visual = node.visual;
                 let enabled = node.enabled;
                 let wrap_width = node.wrap.then(|| match node.style.max_width {
@@ -213,6 +213,9 @@ impl<Message: 'static> Ui<Message> {
                     max_width: wrap_width,
                     ..Default::default()
                 };
+                if let Kind::Label { spans, .. } = &node.kind {
+                    request.spans = spans.clone();
+                }
                 Some(request)
             }
             Kind::TextField(field) => {
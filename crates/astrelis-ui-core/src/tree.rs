@@ -15,6 +15,19 @@ pub struct ElementHandle<T> {
     pub(crate) marker: PhantomData<fn() -> T>,
 }
 
+impl ElementId {
+    pub(crate) const fn from_handle(handle: Handle<Node>) -> Self {
+        Self {
+            index: handle.index(),
+            generation: handle.generation(),
+        }
+    }
+
+    pub(crate) const fn handle(self) -> Handle<Node> {
+        Handle::from_raw_parts(self.index, self.generation)
+    }
+}
+
 impl<T> ElementHandle<T> {
     /// Returns the erased element identity.
     pub const fn id(self) -> ElementId {
@@ -107,6 +120,7 @@ mod marker_sealed {
 pub(crate) enum Kind {
     Label {
         text: String,
+        spans: Vec<TextSpan>,
     },
     Button {
         text: String,
@@ -220,11 +234,6 @@ pub(crate) struct Node {
     pub(crate) pressed: bool,
 }
 
-pub(crate) struct Slot {
-    pub(crate) generation: u32,
-    pub(crate) node: Option<Node>,
-}
-
 pub(crate) struct DragSession {
     pub(crate) id: DragSessionId,
     pub(crate) source: ElementId,
@@ -239,44 +248,38 @@ pub(crate) struct DragSession {
 impl<Message: 'static> Ui<Message> {
     /// Creates a UI tree with a root column container.
     pub fn new(fonts: FontDatabase, theme: Theme) -> Self {
-        let root = ElementId {
-            index: 0,
-            generation: 1,
-        };
+        let mut nodes = HandleMap::new();
+        let root = ElementId::from_handle(nodes.insert(Node {
+            parent: None,
+            children: Vec::new(),
+            kind: Kind::Column {
+                flex: FlexStyle {
+                    row_gap: theme.gap,
+                    ..Default::default()
+                },
+            },
+            style: LayoutStyle::default(),
+            visual: WidgetStyle::default(),
+            wrap: false,
+            enabled: true,
+            visibility: Visibility::Visible,
+            overflow: Overflow::Visible,
+            z_index: 0,
+            transform: Affine2::IDENTITY,
+            transform_origin: LogicalPoint::ZERO,
+            cursor: None,
+            bounds: Rect::default(),
+            resolved_padding: Insets::default(),
+            resolved_border: Insets::default(),
+            resolved_margin: Insets::default(),
+            text_layout: None,
+            text_request: None,
+            pending: None,
+            hovered: false,
+            pressed: false,
+        }));
         Self {
-            slots: vec![Slot {
-                generation: 1,
-                node: Some(Node {
-                    parent: None,
-                    children: Vec::new(),
-                    kind: Kind::Column {
-                        flex: FlexStyle {
-                            row_gap: theme.gap,
-                            ..Default::default()
-                        },
-                    },
-                    style: LayoutStyle::default(),
-                    visual: WidgetStyle::default(),
-                    wrap: false,
-                    enabled: true,
-                    visibility: Visibility::Visible,
-                    overflow: Overflow::Visible,
-                    z_index: 0,
-                    transform: Affine2::IDENTITY,
-                    transform_origin: LogicalPoint::ZERO,
-                    cursor: None,
-                    bounds: Rect::default(),
-                    resolved_padding: Insets::default(),
-                    resolved_border: Insets::default(),
-                    resolved_margin: Insets::default(),
-                    text_layout: None,
-                    text_request: None,
-                    pending: None,
-                    hovered: false,
-                    pressed: false,
-                }),
-            }],
-            free: Vec::new(),
+            nodes,
             taffy_cache: TaffyCache::default(),
             root,
             theme,
@@ -296,10 +299,11 @@ impl<Message: 'static> Ui<Message> {
             hover: None,
             hover_paths: HashMap::new(),
             capture: HashMap::new(),
+            raw_touches: HashMap::new(),
             pointer_positions: HashMap::new(),
             modifiers: Modifiers::default(),
             window_focused: true,
-            applied_cursor: None,
+            cursor: CursorIcon::Default,
             events: VecDeque::new(),
             messages: VecDeque::new(),
             listeners: HashMap::new(),
@@ -353,7 +357,21 @@ impl<Message: 'static> Ui<Message> {
         )
     }
 
+    /// Logical viewport size.
+    pub fn viewport(&self) -> LogicalSize {
+        self.viewport
+    }
+
+    /// DPI scale from logical to physical pixels.
+    pub fn scale_factor(&self) -> f32 {
+        self.scale_factor
+    }
+
     /// Changes the logical viewport and DPI scale.
+    ///
+    /// [`Ui::handle_window_event`] applies this on
+    /// [`WindowEvent::ScaleFactorChanged`], so text re-rasterizes at the new
+    /// scale as soon as a window moves between monitors.
     pub fn set_viewport(&mut self, viewport: LogicalSize, scale_factor: f32) {
         if self.viewport != viewport || self.scale_factor != scale_factor {
             self.viewport = viewport;
@@ -404,7 +422,13 @@ impl<Message: 'static> Ui<Message> {
         parent: ElementHandle<T>,
         text: impl Into<String>,
     ) -> Result<ElementHandle<Label>, UiError> {
-        self.insert(parent.id, Kind::Label { text: text.into() })
+        self.insert(
+            parent.id,
+            Kind::Label {
+                text: text.into(),
+                spans: Vec::new(),
+            },
+        )
     }
 
     /// Adds a button.
@@ -609,25 +633,7 @@ impl<Message: 'static> Ui<Message> {
         kind: Kind,
     ) -> Result<ElementHandle<T>, UiError> {
         self.node(parent)?;
-        let id = if let Some(index) = self.free.pop() {
-            let slot = &mut self.slots[index as usize];
-            slot.generation = slot.generation.wrapping_add(1).max(1);
-            ElementId {
-                index,
-                generation: slot.generation,
-            }
-        } else {
-            let id = ElementId {
-                index: self.slots.len() as u32,
-                generation: 1,
-            };
-            self.slots.push(Slot {
-                generation: 1,
-                node: None,
-            });
-            id
-        };
-        self.slots[id.index as usize].node = Some(Node {
+        let id = ElementId::from_handle(self.nodes.insert(Node {
             parent: Some(parent),
             children: Vec::new(),
             kind,
@@ -650,7 +656,7 @@ impl<Message: 'static> Ui<Message> {
             pending: None,
             hovered: false,
             pressed: false,
-        });
+        }));
         self.node_mut(parent)?.children.push(id);
         self.taffy_cache.structure_dirty = true;
         self.invalidate_layout();
@@ -721,7 +727,7 @@ impl<Message: 'static> Ui<Message> {
             self.hover_paths.remove(&device);
         }
         self.remove_subtree(handle.id);
-        for index in 0..self.slots.len() {
+        for index in 0..self.nodes.slot_len() {
             let Some(id) = self.id_at(index) else {
                 continue;
             };
@@ -778,36 +784,20 @@ impl<Message: 'static> Ui<Message> {
     }
 
     pub(crate) fn node(&self, id: ElementId) -> Result<&Node, UiError> {
-        let Some(slot) = self.slots.get(id.index as usize) else {
-            return Err(UiError::new("stale element handle"));
-        };
-        if slot.generation != id.generation {
-            return Err(UiError::new("stale element handle"));
-        }
-        slot.node
-            .as_ref()
+        self.nodes
+            .get(id.handle())
             .ok_or_else(|| UiError::new("stale element handle"))
     }
 
     pub(crate) fn node_mut(&mut self, id: ElementId) -> Result<&mut Node, UiError> {
-        let Some(slot) = self.slots.get_mut(id.index as usize) else {
-            return Err(UiError::new("stale element handle"));
-        };
-        if slot.generation != id.generation {
-            return Err(UiError::new("stale element handle"));
-        }
-        slot.node
-            .as_mut()
+        self.nodes
+            .get_mut(id.handle())
             .ok_or_else(|| UiError::new("stale element handle"))
     }
 
     /// Reconstructs the live identity occupying an arena slot, if any.
     pub(crate) fn id_at(&self, index: usize) -> Option<ElementId> {
-        let slot = self.slots.get(index)?;
-        slot.node.as_ref().map(|_| ElementId {
-            index: index as u32,
-            generation: slot.generation,
-        })
+        self.nodes.handle_at(index).map(ElementId::from_handle)
     }
 
     /// Returns a node's children in ascending z-order, breaking ties by
@@ -847,9 +837,9 @@ impl<Message: 'static> Ui<Message> {
     ///
     /// This borrows `self` immutably for the lifetime of the iterator, so it
     /// suits read-only sweeps. Loops that mutate a node per iteration should
-    /// instead range over `0..self.slots.len()` and resolve each index with
+    /// instead range over `0..self.nodes.slot_len()` and resolve each index with
     /// [`Self::id_at`], which re-borrows fresh on every step.
     pub(crate) fn ids(&self) -> impl Iterator<Item = ElementId> + '_ {
-        (0..self.slots.len()).filter_map(|index| self.id_at(index))
+        (0..self.nodes.slot_len()).filter_map(|index| self.id_at(index))
     }
 }
@@ -103,8 +103,13 @@ pub struct MountContext<'a, Message: 'static> {
 impl<Message: 'static> MountContext<'_, Message> {
     /// Adds a label owned by the mounting widget.
     pub fn add_label(&mut self, text: impl Into<String>) -> Result<ElementHandle<Label>, UiError> {
-        self.ui
-            .insert(self.parent, Kind::Label { text: text.into() })
+        self.ui.insert(
+            self.parent,
+            Kind::Label {
+                text: text.into(),
+                spans: Vec::new(),
+            },
+        )
     }
     /// Adds a column owned by the mounting widget.
     pub fn add_column(&mut self) -> Result<ElementHandle<Column>, UiError> {
@@ -3,6 +3,22 @@
 use super::*;
 
 impl<Message: 'static> Ui<Message> {
+    /// Priority at which [`Ui::request_cursor`] submits the UI's cursor.
+    /// Layers drawn over the UI, such as drag previews or gizmos, request
+    /// above it to win.
+    pub const CURSOR_PRIORITY: i32 = 0;
+
+    /// Cursor for the hovered element or active drag, as of the last event.
+    pub fn cursor(&self) -> CursorIcon {
+        self.cursor
+    }
+
+    /// Submits [`Ui::cursor`] at [`Ui::CURSOR_PRIORITY`]. The UI never sets
+    /// the window cursor itself; the owner of `requests` applies the winner.
+    pub fn request_cursor(&self, requests: &mut CursorRequests) {
+        requests.request(Self::CURSOR_PRIORITY, self.cursor);
+    }
+
     /// Routes one platform window event through the retained UI tree.
     pub fn handle_window_event(
         &mut self,
@@ -14,6 +30,20 @@ impl<Message: 'static> Ui<Message> {
         let mut platform_state_changed = false;
         match event {
             WindowEvent::ModifiersChanged(modifiers) => self.modifiers = *modifiers,
+            WindowEvent::ScaleFactorChanged {
+                scale_factor,
+                inner_size,
+            } => {
+                let scale = (*scale_factor as f32).max(f32::EPSILON);
+                self.set_viewport(
+                    Size::new(
+                        inner_size.width.max(1) as f32 / scale,
+                        inner_size.height.max(1) as f32 / scale,
+                    ),
+                    scale,
+                );
+                self.dirty |= Dirty::PAINT;
+            }
             WindowEvent::Focused(focused) => {
                 self.window_focused = *focused;
                 if !focused {
@@ -22,7 +52,7 @@ impl<Message: 'static> Ui<Message> {
                         self.cancel_drag_id(device_id)?;
                     }
                     self.capture.clear();
-                    for index in 0..self.slots.len() {
+                    for index in 0..self.nodes.slot_len() {
                         let Some(id) = self.id_at(index) else {
                             continue;
                         };
@@ -258,89 +288,128 @@ impl<Message: 'static> Ui<Message> {
                 }
             }
             WindowEvent::Touch(touch) => {
+                self.ensure_layout()?;
                 let device_id = DeviceId(touch.device_id.0 ^ touch.id.rotate_left(32));
                 let logical = Point::new(
                     touch.position.x as f32 / self.scale_factor,
                     touch.position.y as f32 / self.scale_factor,
                 );
                 self.pointer_positions.insert(device_id, logical);
-                match touch.phase {
-                    TouchPhase::Started => {
-                        if let Some(target) = self.hit_test(logical)
-                            && !self.dispatch_routed(
-                                target,
-                                RoutedEventKind::PointerButton {
-                                    device_id,
-                                    position: logical,
-                                    button: PointerButton::Primary,
-                                    state: ElementState::Pressed,
-                                },
-                            )?
-                        {
-                            self.set_focus(Some(target))?;
-                            self.capture.insert(device_id, target);
-                            self.node_mut(target)?.pressed = true;
-                            if matches!(self.node(target)?.kind, Kind::Slider { .. }) {
-                                self.set_slider_from_point(target, logical)?;
-                            } else if matches!(self.node(target)?.kind, Kind::ScrollView { .. }) {
-                                self.set_scroll_from_point(target, logical)?;
+                // Raw contacts reach touch-aware widgets first. Preventing the
+                // default on `Started` claims the contact, which then skips
+                // pointer emulation until it ends.
+                let claimed = self.raw_touches.get(&device_id).copied();
+                let raw_target = match touch.phase {
+                    TouchPhase::Started => self.hit_test(logical),
+                    _ => claimed.or_else(|| self.capture.get(&device_id).copied()),
+                };
+                let prevented = match raw_target {
+                    Some(target) => self.dispatch_routed(
+                        target,
+                        RoutedEventKind::Touch {
+                            device_id,
+                            id: touch.id,
+                            position: logical,
+                            phase: touch.phase,
+                            force: touch.force,
+                        },
+                    )?,
+                    None => false,
+                };
+                if claimed.is_some() || (touch.phase == TouchPhase::Started && prevented) {
+                    match touch.phase {
+                        TouchPhase::Started => {
+                            if let Some(target) = raw_target {
+                                self.raw_touches.insert(device_id, target);
                             }
                         }
+                        TouchPhase::Ended | TouchPhase::Cancelled => {
+                            self.raw_touches.remove(&device_id);
+                        }
+                        TouchPhase::Moved => {}
                     }
-                    TouchPhase::Moved => {
-                        if let Some(target) = self.capture.get(&device_id).copied() {
-                            self.dispatch_routed(
-                                target,
-                                RoutedEventKind::PointerMoved {
-                                    device_id,
-                                    position: logical,
-                                },
-                            )?;
-                            self.update_drag(device_id, logical)?;
-                            if matches!(self.node(target)?.kind, Kind::Slider { .. }) {
-                                self.set_slider_from_point(target, logical)?;
-                            } else if matches!(self.node(target)?.kind, Kind::ScrollView { .. }) {
-                                self.set_scroll_from_point(target, logical)?;
+                } else {
+                    match touch.phase {
+                        TouchPhase::Started => {
+                            if let Some(target) = self.hit_test(logical)
+                                && !self.dispatch_routed(
+                                    target,
+                                    RoutedEventKind::PointerButton {
+                                        device_id,
+                                        position: logical,
+                                        button: PointerButton::Primary,
+                                        state: ElementState::Pressed,
+                                    },
+                                )?
+                            {
+                                self.set_focus(Some(target))?;
+                                self.capture.insert(device_id, target);
+                                self.node_mut(target)?.pressed = true;
+                                if matches!(self.node(target)?.kind, Kind::Slider { .. }) {
+                                    self.set_slider_from_point(target, logical)?;
+                                } else if matches!(self.node(target)?.kind, Kind::ScrollView { .. })
+                                {
+                                    self.set_scroll_from_point(target, logical)?;
+                                }
                             }
                         }
-                    }
-                    TouchPhase::Ended => {
-                        if self.finish_drag(device_id, logical)? {
-                            self.dirty |= Dirty::PAINT;
-                            self.sync_platform_state(window)?;
-                            return Ok(UiUpdate {
-                                redraw: true,
-                                platform_state_changed,
-                            });
+                        TouchPhase::Moved => {
+                            if let Some(target) = self.capture.get(&device_id).copied() {
+                                self.dispatch_routed(
+                                    target,
+                                    RoutedEventKind::PointerMoved {
+                                        device_id,
+                                        position: logical,
+                                    },
+                                )?;
+                                self.update_drag(device_id, logical)?;
+                                if matches!(self.node(target)?.kind, Kind::Slider { .. }) {
+                                    self.set_slider_from_point(target, logical)?;
+                                } else if matches!(self.node(target)?.kind, Kind::ScrollView { .. })
+                                {
+                                    self.set_scroll_from_point(target, logical)?;
+                                }
+                            }
                         }
-                        if let Some(target) = self.capture.remove(&device_id) {
-                            self.node_mut(target)?.pressed = false;
-                            if !self.dispatch_routed(
-                                target,
-                                RoutedEventKind::PointerButton {
-                                    device_id,
-                                    position: logical,
-                                    button: PointerButton::Primary,
-                                    state: ElementState::Released,
-                                },
-                            )? && self.hit_test(logical) == Some(target)
-                            {
-                                if matches!(self.node(target)?.kind, Kind::Checkbox { .. }) {
-                                    self.toggle_checkbox_id(target)?;
-                                } else if matches!(self.node(target)?.kind, Kind::Button { .. }) {
-                                    self.dispatch_routed(target, RoutedEventKind::Activate)?;
+                        TouchPhase::Ended => {
+                            if self.finish_drag(device_id, logical)? {
+                                self.dirty |= Dirty::PAINT;
+                                self.sync_platform_state(window)?;
+                                return Ok(UiUpdate {
+                                    redraw: true,
+                                    platform_state_changed,
+                                });
+                            }
+                            if let Some(target) = self.capture.remove(&device_id) {
+                                self.node_mut(target)?.pressed = false;
+                                if !self.dispatch_routed(
+                                    target,
+                                    RoutedEventKind::PointerButton {
+                                        device_id,
+                                        position: logical,
+                                        button: PointerButton::Primary,
+                                        state: ElementState::Released,
+                                    },
+                                )? && self.hit_test(logical) == Some(target)
+                                {
+                                    if matches!(self.node(target)?.kind, Kind::Checkbox { .. }) {
+                                        self.toggle_checkbox_id(target)?;
+                                    } else if matches!(self.node(target)?.kind, Kind::Button { .. })
+                                    {
+                                        self.dispatch_routed(target, RoutedEventKind::Activate)?;
+                                    }
                                 }
                             }
                         }
-                    }
-                    TouchPhase::Cancelled => {
-                        self.cancel_drag_id(device_id)?;
-                        if let Some(target) = self.capture.remove(&device_id) {
-                            self.node_mut(target)?.pressed = false;
-                            self.dispatch_routed(
-                                target,
-                                RoutedEventKind::PointerCancelled { device_id },
-                            )?;
+                        TouchPhase::Cancelled => {
+                            self.cancel_drag_id(device_id)?;
+                            if let Some(target) = self.capture.remove(&device_id) {
+                                self.node_mut(target)?.pressed = false;
+                                self.dispatch_routed(
+                                    target,
+                                    RoutedEventKind::PointerCancelled { device_id },
+                                )?;
+                            }
                         }
                     }
                 }
@@ -399,10 +468,7 @@ impl<Message: 'static> Ui<Message> {
                 })
                 .unwrap_or(CursorIcon::Default)
         });
-        if self.applied_cursor != Some(cursor) {
-            window.set_cursor_icon(cursor);
-            self.applied_cursor = Some(cursor);
-        }
+        self.cursor = cursor;
         let Some(focus) = self.focus.filter(|_| self.window_focused) else {
             window.set_ime_allowed(false);
             return Ok(());
@@ -6,8 +6,9 @@ use std::io;
 use astrelis_app::{App, AppContext, Runtime, RuntimeConfig};
 use astrelis_core::geometry::{LogicalSize, Point, Size};
 use astrelis_platform::{
-    DeviceId, ElementState, Key, KeyLocation, KeyboardInput, NamedKey, PhysicalKey, PointerButton,
-    Window, WindowAttributes, WindowEvent, WindowId,
+    CursorIcon, CursorRequests, DeviceId, ElementState, Key, KeyLocation, KeyboardInput, NamedKey,
+    PhysicalKey, PointerButton, Touch, TouchForce, TouchPhase, Window, WindowAttributes,
+    WindowEvent, WindowId,
 };
 use astrelis_platform_test::{ScriptEvent, TestRunner};
 use astrelis_text::FontDatabase;
@@ -136,17 +137,17 @@ impl<Message: 'static> Widget<Message> for PressTakeoverWidget {
     }
 }
 
-struct TakeoverApp {
+struct TakeoverApp<W = PressTakeoverWidget> {
     window: Option<Window>,
     ui: Ui,
-    widget: ElementHandle<PressTakeoverWidget>,
+    widget: ElementHandle<W>,
 }
 
-impl TakeoverApp {
+impl<W: Widget<()> + Default> TakeoverApp<W> {
     fn new() -> Self {
         let mut ui = Ui::new(FontDatabase::default(), Theme::default());
         let root = ui.root();
-        let widget = ui.add_widget(root, PressTakeoverWidget::default()).unwrap();
+        let widget = ui.add_widget(root, W::default()).unwrap();
         ui.set_viewport(Size::new(800.0, 600.0), 1.0);
         Self {
             window: None,
@@ -155,12 +156,12 @@ impl TakeoverApp {
         }
     }
 
-    fn releases(&self) -> usize {
-        self.ui.widget(self.widget).unwrap().releases
+    fn widget(&self) -> &W {
+        self.ui.widget(self.widget).unwrap()
     }
 }
 
-impl App for TakeoverApp {
+impl<W: Widget<()> + Default> App for TakeoverApp<W> {
     type Error = io::Error;
 
     fn resumed(&mut self, context: &mut AppContext<'_, '_, Self>) -> Result<(), Self::Error> {
@@ -232,16 +233,141 @@ fn preventing_the_press_default_still_delivers_the_release() {
     runner.push(ScriptEvent::Exit);
 
     let (runtime, _state) = runner
-        .run_return(Runtime::new(TakeoverApp::new(), RuntimeConfig::default()))
+        .run_return(Runtime::new(
+            TakeoverApp::<PressTakeoverWidget>::new(),
+            RuntimeConfig::default(),
+        ))
         .unwrap();
     let app = runtime.into_result().unwrap();
     assert_eq!(
-        app.releases(),
+        app.widget().releases,
         1,
         "widget that prevented the press default never received its release"
     );
 }
 
+/// Claims every contact so it can recognize multi-touch gestures itself.
+#[derive(Default)]
+struct GestureWidget {
+    contacts: Vec<(u64, TouchPhase, Option<f64>)>,
+    pointer_presses: usize,
+}
+
+impl<Message: 'static> Widget<Message> for GestureWidget {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn intrinsic_size(&self, _theme: &Theme) -> LogicalSize {
+        Size::new(200.0, 200.0)
+    }
+
+    fn hit_testable(&self) -> bool {
+        true
+    }
+
+    fn event(&mut self, context: &mut EventContext<'_, Message>, event: &RoutedEvent) {
+        match &event.kind {
+            RoutedEventKind::Touch {
+                id, phase, force, ..
+            } => {
+                self.contacts
+                    .push((*id, *phase, force.map(|force| force.normalized())));
+                context.prevent_default();
+            }
+            RoutedEventKind::PointerButton { .. } => self.pointer_presses += 1,
+            _ => {}
+        }
+    }
+}
+
+fn touch<T>(id: u64, phase: TouchPhase, x: f64, force: Option<TouchForce>) -> ScriptEvent<T> {
+    ScriptEvent::Window(
+        WindowId(1),
+        WindowEvent::Touch(Touch {
+            device_id: DeviceId(3),
+            phase,
+            position: Point::new(x, 50.0),
+            id,
+            force,
+        }),
+    )
+}
+
+#[test]
+fn claimed_touch_contacts_skip_pointer_emulation() {
+    let pen = TouchForce::Calibrated {
+        force: 0.25,
+        max_possible_force: 1.0,
+        altitude_angle: Some(std::f64::consts::FRAC_PI_6),
+    };
+    let mut runner = TestRunner::new();
+    runner.push(ScriptEvent::Resumed);
+    runner.push(touch(1, TouchPhase::Started, 20.0, None));
+    runner.push(touch(2, TouchPhase::Started, 60.0, Some(pen)));
+    runner.push(touch(1, TouchPhase::Moved, 10.0, None));
+    runner.push(touch(2, TouchPhase::Moved, 300.0, Some(pen)));
+    runner.push(touch(1, TouchPhase::Ended, 10.0, None));
+    runner.push(touch(2, TouchPhase::Cancelled, 300.0, None));
+    runner.push(ScriptEvent::Exit);
+
+    let (runtime, _state) = runner
+        .run_return(Runtime::new(
+            TakeoverApp::<GestureWidget>::new(),
+            RuntimeConfig::default(),
+        ))
+        .unwrap();
+    let app = runtime.into_result().unwrap();
+    let widget = app.widget();
+    assert_eq!(widget.pointer_presses, 0);
+    assert_eq!(
+        widget.contacts,
+        [
+            (1, TouchPhase::Started, None),
+            (2, TouchPhase::Started, Some(0.5)),
+            (1, TouchPhase::Moved, None),
+            (2, TouchPhase::Moved, Some(0.5)),
+            (1, TouchPhase::Ended, None),
+            (2, TouchPhase::Cancelled, None),
+        ],
+        "claimed contacts follow their widget even outside its bounds"
+    );
+}
+
+#[test]
+fn scale_factor_changes_update_the_viewport_before_hit_testing() {
+    let mut runner = TestRunner::new();
+    runner.push(ScriptEvent::Resumed);
+    runner.push(ScriptEvent::Window(
+        WindowId(1),
+        WindowEvent::ScaleFactorChanged {
+            scale_factor: 2.0,
+            inner_size: Size::new(1600, 1200),
+        },
+    ));
+    runner.push(touch(1, TouchPhase::Started, 300.0, None));
+    runner.push(ScriptEvent::Exit);
+
+    let (runtime, _state) = runner
+        .run_return(Runtime::new(
+            TakeoverApp::<GestureWidget>::new(),
+            RuntimeConfig::default(),
+        ))
+        .unwrap();
+    let app = runtime.into_result().unwrap();
+    assert_eq!(app.ui.scale_factor(), 2.0);
+    assert_eq!(app.ui.viewport(), Size::new(800.0, 600.0));
+    assert_eq!(
+        app.widget().contacts,
+        [(1, TouchPhase::Started, None)],
+        "physical x 300 lands inside the 200-wide widget at scale 2"
+    );
+}
+
 fn tab_event() -> WindowEvent {
     WindowEvent::KeyboardInput(KeyboardInput {
         device_id: DeviceId(1),
@@ -313,4 +439,17 @@ fn ui_invalidates_once_then_desktop_runtime_returns_to_wait() {
         .filter(|command| matches!(command, astrelis_platform::WindowCommand::RequestRedraw))
         .count();
     assert_eq!(redraw_requests, 1);
+    assert_eq!(app.ui.cursor(), CursorIcon::Pointer);
+    assert!(
+        !state.windows[0]
+            .1
+            .commands
+            .iter()
+            .any(|command| matches!(command, astrelis_platform::WindowCommand::SetCursorIcon(_))),
+        "the UI submits its cursor instead of setting it"
+    );
+    let mut cursors = CursorRequests::new();
+    app.ui.request_cursor(&mut cursors);
+    cursors.request(Ui::<()>::CURSOR_PRIORITY + 1, CursorIcon::Move);
+    assert_eq!(cursors.requested(), Some(&CursorIcon::Move.into()));
 }
@@ -9,7 +9,7 @@ use astrelis_gpu::{
     SurfaceFrameStatus, SurfaceTarget, TextureUsages, TextureViewDescriptor,
 };
 use astrelis_paint_gpu::{RenderTarget, Renderer, RendererOptions};
-use astrelis_platform::{Window, WindowAttributes, WindowEvent, WindowId};
+use astrelis_platform::{CursorRequests, Window, WindowAttributes, WindowEvent, WindowId};
 use astrelis_text::{FontDatabase, FontFamily};
 use astrelis_ui_core::{
     ElementHandle, EventFilter, Label, LayoutStyle, Length, RoutedEventKind, Theme, Ui,
@@ -45,6 +45,7 @@ struct DockingExample {
     window: Option<Window>,
     gpu: Option<GpuState>,
     ui: Ui<Message>,
+    cursors: CursorRequests,
     workspace: DockWorkspace<Message>,
     default_layout: DockLayout,
     saved_json: Option<String>,
@@ -181,6 +182,7 @@ impl DockingExample {
         Ok(Self {
             instance: astrelis_gpu_wgpu::create_instance(Default::default()),
             window: None,
+            cursors: CursorRequests::new(),
             gpu: None,
             ui,
             workspace,
@@ -349,9 +351,11 @@ impl DockingExample {
                 },
             )
             .map_err(io::Error::other)?;
+        gpu.renderer.before_submit();
         gpu.queue
             .submit([encoder.finish().map_err(io::Error::other)?])
             .map_err(io::Error::other)?;
+        gpu.renderer.after_submit().map_err(io::Error::other)?;
         frame.present().map_err(io::Error::other)
     }
 }
@@ -589,6 +593,8 @@ impl App for DockingExample {
                 .ui
                 .handle_window_event(window, &context.clipboard(), &event)
                 .map_err(io::Error::other)?;
+            self.ui.request_cursor(&mut self.cursors);
+            self.cursors.apply(window).map_err(io::Error::other)?;
             self.consume_messages()?;
             if update.redraw || self.ui.needs_redraw() {
                 context.invalidate_window(id);
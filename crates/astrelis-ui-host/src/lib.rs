@@ -2,46 +2,95 @@
 
 #![warn(missing_docs)]
 
-use std::{error::Error, fmt};
+mod manager;
 
-#[cfg(target_arch = "wasm32")]
-use std::sync::{Arc, Mutex};
+use std::{
+    error::Error,
+    fmt,
+    sync::{Arc, Mutex, OnceLock},
+};
 
 use astrelis_app::{App, AppContext};
 use astrelis_compositor::{CompositionStats, Compositor, ViewOptions, ViewRenderTarget};
 use astrelis_core::{color::Color, geometry::Size};
 use astrelis_gpu::{
-    CompositeAlphaMode, DeviceDescriptor, PresentMode, RequestAdapterOptions, SurfaceConfiguration,
-    SurfaceFrameStatus, SurfaceTarget, TextureUsages, TextureViewDescriptor,
+    CompositeAlphaMode, DeviceDescriptor, PresentMode, RequestAdapterOptions, SurfaceCapabilities,
+    SurfaceConfiguration, SurfaceFrameStatus, SurfaceTarget, TextureFormat, TextureUsages,
+    TextureViewDescriptor,
 };
 use astrelis_paint::CompositorViewId;
 use astrelis_paint_gpu::{ExternalImage, RenderStats, RenderTarget, Renderer, RendererOptions};
-use astrelis_platform::{Window, WindowAttributes, WindowEvent, WindowId};
+use astrelis_platform::{CursorRequests, Window, WindowAttributes, WindowEvent, WindowId};
 use astrelis_ui_core::Ui;
 
+pub use manager::WindowManager;
+
 /// Shared graphics entry point used to open Astrelis windows.
-#[derive(Clone)]
+///
+/// The first window opened through a context selects the adapter and creates
+/// the device; later windows reuse both whenever the adapter can present to
+/// their surface, so textures and pipelines can be shared between windows.
 pub struct GraphicsContext {
     instance: astrelis_gpu::Instance,
+    /// Created on first use so construction stays `const`; clones share it.
+    shared: OnceLock<Arc<Mutex<Option<SharedDevice>>>>,
+}
+
+#[derive(Clone)]
+struct SharedDevice {
+    adapter: astrelis_gpu::Adapter,
+    device: astrelis_gpu::Device,
+    queue: astrelis_gpu::Queue,
 }
 
 impl GraphicsContext {
     /// Creates graphics using Astrelis's default wgpu instance configuration.
     pub fn new() -> Self {
-        Self {
-            instance: astrelis_gpu_wgpu::create_instance(Default::default()),
-        }
+        Self::from_instance(astrelis_gpu_wgpu::create_instance(Default::default()))
     }
 
     /// Wraps an application-configured backend-neutral instance.
     pub const fn from_instance(instance: astrelis_gpu::Instance) -> Self {
-        Self { instance }
+        Self {
+            instance,
+            shared: OnceLock::new(),
+        }
     }
 
     /// Returns the underlying backend-neutral instance.
     pub const fn instance(&self) -> &astrelis_gpu::Instance {
         &self.instance
     }
+
+    /// Returns the device shared by this context's windows, once one exists.
+    pub fn device(&self) -> Option<astrelis_gpu::Device> {
+        self.shared_device().map(|shared| shared.device)
+    }
+
+    /// Returns the queue of [`GraphicsContext::device`].
+    pub fn queue(&self) -> Option<astrelis_gpu::Queue> {
+        self.shared_device().map(|shared| shared.queue)
+    }
+
+    fn shared(&self) -> &Arc<Mutex<Option<SharedDevice>>> {
+        self.shared.get_or_init(Default::default)
+    }
+
+    fn shared_device(&self) -> Option<SharedDevice> {
+        self.shared()
+            .lock()
+            .expect("graphics context state poisoned")
+            .clone()
+    }
+}
+
+impl Clone for GraphicsContext {
+    fn clone(&self) -> Self {
+        Self {
+            instance: self.instance.clone(),
+            shared: OnceLock::from(self.shared().clone()),
+        }
+    }
 }
 
 impl Default for GraphicsContext {
@@ -59,6 +108,15 @@ pub struct WindowHostOptions {
     pub clear_color: Color,
     /// Painter renderer configuration.
     pub renderer: RendererOptions,
+    /// Preferred presentation mode; unsupported modes fall back to `Fifo`.
+    pub present_mode: PresentMode,
+    /// Preferred surface format; `None` or an unsupported format uses the
+    /// surface's first reported format.
+    pub surface_format: Option<TextureFormat>,
+    /// Rasterizes newly seen glyphs on a background thread and requests a
+    /// redraw as each one finishes, instead of stalling the frame that first
+    /// displays them. Ignored on wasm.
+    pub async_glyph_rasterization: bool,
 }
 
 impl Default for WindowHostOptions {
@@ -67,16 +125,30 @@ impl Default for WindowHostOptions {
             window: WindowAttributes::default(),
             clear_color: Color::BLACK,
             renderer: RendererOptions::default(),
+            present_mode: PresentMode::Fifo,
+            surface_format: None,
+            async_glyph_rasterization: false,
         }
     }
 }
 
+/// Surface settings forwarded to GPU initialization.
+#[derive(Clone, Copy, Debug)]
+struct SurfaceOptions {
+    renderer: RendererOptions,
+    present_mode: PresentMode,
+    format: Option<TextureFormat>,
+    async_glyph_rasterization: bool,
+}
+
 struct GpuState {
-    surface: astrelis_gpu::Surface,
+    /// `None` while suspended; platforms may destroy the native window then.
+    surface: Option<astrelis_gpu::Surface>,
     device: astrelis_gpu::Device,
     queue: astrelis_gpu::Queue,
     configuration: SurfaceConfiguration,
     render_format: astrelis_gpu::TextureFormat,
+    present_modes: Vec<PresentMode>,
     compositor: Compositor,
 }
 
@@ -103,13 +175,20 @@ pub struct HostUpdate {
 }
 
 /// One retained UI tree connected to a platform window and GPU surface.
+///
+/// Suspension is opt-in: the runtime does not know which hosts an app
+/// owns, so an app that targets mobile must call [`WindowHost::suspend`]
+/// from `App::suspended` and [`WindowHost::resume`] from `App::resumed` for
+/// each of its hosts. A host that is never suspended keeps its surface.
 pub struct WindowHost<Message = ()> {
     window: Window,
     gpu: Option<GpuState>,
     #[cfg(target_arch = "wasm32")]
     pending: Arc<Mutex<Option<Result<GpuState, HostError>>>>,
     failed: Option<HostError>,
+    suspended: bool,
     ui: Ui<Message>,
+    cursors: CursorRequests,
     clear_color: Color,
 }
 
@@ -124,6 +203,12 @@ impl<Message: 'static> WindowHost<Message> {
         ui: Ui<Message>,
         options: WindowHostOptions,
     ) -> Result<Self, HostError> {
+        let surface_options = SurfaceOptions {
+            renderer: options.renderer,
+            present_mode: options.present_mode,
+            format: options.surface_format,
+            async_glyph_rasterization: options.async_glyph_rasterization,
+        };
         let window = context
             .create_window(options.window)
             .map_err(HostError::from_display)?;
@@ -131,9 +216,9 @@ impl<Message: 'static> WindowHost<Message> {
         #[cfg(not(target_arch = "wasm32"))]
         {
             let result = pollster::block_on(initialize_gpu(
-                graphics.instance.clone(),
+                graphics.clone(),
                 window.clone(),
-                options.renderer,
+                surface_options,
             ));
             let gpu = match result {
                 Ok(gpu) => gpu,
@@ -146,7 +231,9 @@ impl<Message: 'static> WindowHost<Message> {
                 window,
                 gpu: Some(gpu),
                 failed: None,
+                suspended: false,
                 ui,
+                cursors: CursorRequests::new(),
                 clear_color: options.clear_color,
             };
             host.sync_viewport();
@@ -157,11 +244,11 @@ impl<Message: 'static> WindowHost<Message> {
         {
             let pending = Arc::new(Mutex::new(None));
             let completion = pending.clone();
-            let instance = graphics.instance.clone();
+            let graphics = graphics.clone();
             let initialization_window = window.clone();
             wasm_bindgen_futures::spawn_local(async move {
                 let result =
-                    initialize_gpu(instance, initialization_window.clone(), options.renderer).await;
+                    initialize_gpu(graphics, initialization_window.clone(), surface_options).await;
                 *completion
                     .lock()
                     .expect("host initialization state poisoned") = Some(result);
@@ -172,7 +259,9 @@ impl<Message: 'static> WindowHost<Message> {
                 gpu: None,
                 pending,
                 failed: None,
+                suspended: false,
                 ui,
+                cursors: CursorRequests::new(),
                 clear_color: options.clear_color,
             };
             host.sync_viewport();
@@ -198,6 +287,17 @@ impl<Message: 'static> WindowHost<Message> {
         self.failed.as_ref()
     }
 
+    /// Returns whether a redraw can present now: initialization has not
+    /// stalled and the window is not minimized to a zero-sized surface.
+    pub fn is_presentable(&mut self) -> bool {
+        if self.suspended || self.status() == HostStatus::Initializing {
+            return false;
+        }
+        self.window
+            .inner_size()
+            .is_ok_and(|size| size.width > 0 && size.height > 0)
+    }
+
     /// Returns the platform window identifier.
     pub fn id(&self) -> WindowId {
         self.window.id()
@@ -218,6 +318,14 @@ impl<Message: 'static> WindowHost<Message> {
         &mut self.ui
     }
 
+    /// Cursor arbitration for this window. The UI submits its cursor on
+    /// every handled event; other layers request here, at a priority above
+    /// [`Ui::CURSOR_PRIORITY`] to override it, and the winner is applied at
+    /// the end of the next [`WindowHost::handle_event`].
+    pub fn cursor_requests(&mut self) -> &mut CursorRequests {
+        &mut self.cursors
+    }
+
     /// Returns the GPU device, or `None` while initialization is pending or failed.
     pub fn device(&mut self) -> Option<&astrelis_gpu::Device> {
         self.sync_initialization();
@@ -236,6 +344,66 @@ impl<Message: 'static> WindowHost<Message> {
         self.gpu.as_ref().map(|gpu| gpu.configuration.format)
     }
 
+    /// Returns the active presentation mode once GPU initialization completes.
+    pub fn present_mode(&mut self) -> Option<PresentMode> {
+        self.sync_initialization();
+        self.gpu.as_ref().map(|gpu| gpu.configuration.present_mode)
+    }
+
+    /// Switches presentation mode, for example to disable vsync at runtime,
+    /// and returns the mode applied. Unsupported modes fall back to `Fifo`.
+    pub fn set_present_mode(&mut self, mode: PresentMode) -> Result<PresentMode, HostError> {
+        let gpu = self.ready_gpu()?;
+        let mode = choose_present_mode(&gpu.present_modes, mode);
+        if gpu.configuration.present_mode != mode {
+            gpu.configuration.present_mode = mode;
+            Self::reconfigure_gpu(gpu)?;
+        }
+        Ok(mode)
+    }
+
+    /// Stops presenting and releases the surface until
+    /// [`WindowHost::resume`]. The runtime does not call this; call it from
+    /// `App::suspended`, after which mobile platforms destroy the native
+    /// window the surface was created from.
+    pub fn suspend(&mut self) {
+        self.suspended = true;
+        if let Some(gpu) = &mut self.gpu {
+            gpu.surface = None;
+        }
+    }
+
+    /// Recreates the surface on the existing device and resumes presenting.
+    /// The runtime does not call this; call it from `App::resumed`. The
+    /// device, textures, and UI tree survive. Does nothing unless suspended,
+    /// and on failure the host stays suspended so the call can be retried.
+    pub fn resume(&mut self, graphics: &GraphicsContext) -> Result<(), HostError> {
+        if !self.suspended {
+            return Ok(());
+        }
+        self.sync_initialization();
+        if let Some(gpu) = &mut self.gpu {
+            let surface = graphics
+                .instance
+                .create_surface(SurfaceTarget::new(self.window.clone()))
+                .map_err(HostError::from_display)?;
+            gpu.surface = Some(surface);
+            if let Ok(size) = self.window.inner_size() {
+                gpu.configuration.width = size.width.max(1);
+                gpu.configuration.height = size.height.max(1);
+            }
+            if let Err(error) = Self::reconfigure_gpu(gpu) {
+                gpu.surface = None;
+                return Err(error);
+            }
+        }
+        self.suspended = false;
+        self.cursors.invalidate();
+        self.sync_viewport();
+        self.window.request_redraw();
+        Ok(())
+    }
+
     /// Registers or replaces an application-owned texture sampled by a render view.
     pub fn register_external_image(
         &mut self,
@@ -286,6 +454,10 @@ impl<Message: 'static> WindowHost<Message> {
             .ui
             .handle_window_event(&self.window, clipboard, event)
             .map_err(HostError::from_display)?;
+        self.ui.request_cursor(&mut self.cursors);
+        self.cursors
+            .apply(&self.window)
+            .map_err(HostError::from_display)?;
         Ok(HostUpdate {
             close_requested: false,
             redraw: update.redraw || self.ui.needs_redraw(),
@@ -327,12 +499,15 @@ impl<Message: 'static> WindowHost<Message> {
         if let Some(error) = &self.failed {
             return Err(error.clone());
         }
-        if self.gpu.is_none() {
+        if self.suspended {
             return Ok(None);
         }
+        let Some(surface) = self.gpu.as_ref().and_then(|gpu| gpu.surface.clone()) else {
+            return Ok(None);
+        };
         let list = self.ui.display_list().map_err(HostError::from_display)?;
         let gpu = self.gpu.as_mut().expect("checked above");
-        let frame = match gpu.surface.acquire().map_err(HostError::from_display)? {
+        let frame = match surface.acquire().map_err(HostError::from_display)? {
             SurfaceFrameStatus::Ready(frame) | SurfaceFrameStatus::Suboptimal(frame) => frame,
             SurfaceFrameStatus::Outdated | SurfaceFrameStatus::Lost => {
                 Self::reconfigure_gpu(gpu)?;
@@ -355,16 +530,20 @@ impl<Message: 'static> WindowHost<Message> {
                     view,
                     format: gpu.render_format,
                     size: Size::new(gpu.configuration.width, gpu.configuration.height),
-                    scale_factor: self.window.scale_factor() as f32,
+                    scale_factor: self.ui.scale_factor(),
                     clear_color: self.clear_color,
                 },
                 view_options,
                 render_view,
             )
             .map_err(HostError::from_display)?;
+        gpu.compositor.before_submit();
         gpu.queue
             .submit([encoder.finish().map_err(HostError::from_display)?])
             .map_err(HostError::from_display)?;
+        gpu.compositor
+            .after_submit()
+            .map_err(HostError::from_display)?;
         frame.present().map_err(HostError::from_display)?;
         Ok(Some(stats))
     }
@@ -379,7 +558,12 @@ impl<Message: 'static> WindowHost<Message> {
                 .take();
             if let Some(result) = result {
                 match result {
-                    Ok(gpu) => self.gpu = Some(gpu),
+                    Ok(mut gpu) => {
+                        if self.suspended {
+                            gpu.surface = None;
+                        }
+                        self.gpu = Some(gpu);
+                    }
                     Err(error) => self.failed = Some(error),
                 }
                 self.sync_viewport();
@@ -411,7 +595,10 @@ impl<Message: 'static> WindowHost<Message> {
     }
 
     fn reconfigure_gpu(gpu: &GpuState) -> Result<(), HostError> {
-        gpu.surface
+        let Some(surface) = &gpu.surface else {
+            return Ok(());
+        };
+        surface
             .configure(&gpu.device, gpu.configuration.clone())
             .map_err(HostError::from_display)
     }
@@ -433,31 +620,66 @@ impl<Message: 'static> WindowHost<Message> {
 }
 
 async fn initialize_gpu(
-    instance: astrelis_gpu::Instance,
+    graphics: GraphicsContext,
     window: Window,
-    renderer_options: RendererOptions,
+    options: SurfaceOptions,
 ) -> Result<GpuState, HostError> {
-    let surface = instance
+    let surface = graphics
+        .instance
         .create_surface(SurfaceTarget::new(window.clone()))
         .map_err(HostError::from_display)?;
-    let adapter = instance
-        .request_adapter(RequestAdapterOptions {
-            compatible_surface: Some(surface.clone()),
-            ..Default::default()
-        })
-        .await
-        .map_err(HostError::from_display)?;
-    let (device, queue) = adapter
-        .request_device(DeviceDescriptor::default())
-        .await
-        .map_err(HostError::from_display)?;
-    let capabilities = surface
-        .capabilities(&adapter)
-        .map_err(HostError::from_display)?;
-    let format = capabilities
-        .formats
-        .first()
-        .copied()
+    let reusable = graphics.shared_device().and_then(|shared| {
+        surface
+            .capabilities(&shared.adapter)
+            .ok()
+            .filter(|capabilities| !capabilities.formats.is_empty())
+            .map(|capabilities| (shared, capabilities))
+    });
+    let (SharedDevice { device, queue, .. }, capabilities) = match reusable {
+        Some(reusable) => reusable,
+        None => {
+            let adapter = graphics
+                .instance
+                .request_adapter(RequestAdapterOptions {
+                    compatible_surface: Some(surface.clone()),
+                    ..Default::default()
+                })
+                .await
+                .map_err(HostError::from_display)?;
+            let (device, queue) = adapter
+                .request_device(DeviceDescriptor::default())
+                .await
+                .map_err(HostError::from_display)?;
+            let own = SharedDevice {
+                adapter,
+                device,
+                queue,
+            };
+            // Another window's initialization may have finished first; its
+            // device stays the shared one and is used here when it can
+            // present to this surface.
+            let shared = graphics
+                .shared()
+                .lock()
+                .expect("graphics context state poisoned")
+                .get_or_insert_with(|| own.clone())
+                .clone();
+            match surface
+                .capabilities(&shared.adapter)
+                .ok()
+                .filter(|capabilities| !capabilities.formats.is_empty())
+            {
+                Some(capabilities) => (shared, capabilities),
+                None => {
+                    let capabilities = surface
+                        .capabilities(&own.adapter)
+                        .map_err(HostError::from_display)?;
+                    (own, capabilities)
+                }
+            }
+        }
+    };
+    let format = choose_format(&capabilities, options.format)
         .ok_or_else(|| HostError::new("surface reported no supported formats"))?;
     let size = window.inner_size().map_err(HostError::from_display)?;
     let render_format = srgb_view_format(format);
@@ -470,7 +692,7 @@ async fn initialize_gpu(
             .collect(),
         width: size.width.max(1),
         height: size.height.max(1),
-        present_mode: PresentMode::Fifo,
+        present_mode: choose_present_mode(&capabilities.present_modes, options.present_mode),
         alpha_mode: capabilities
             .alpha_modes
             .first()
@@ -481,15 +703,20 @@ async fn initialize_gpu(
     surface
         .configure(&device, configuration.clone())
         .map_err(HostError::from_display)?;
-    let painter = Renderer::new(device.clone(), queue.clone(), renderer_options)
+    let mut painter = Renderer::new(device.clone(), queue.clone(), options.renderer)
         .map_err(HostError::from_display)?;
+    if options.async_glyph_rasterization {
+        let redraw = window.clone();
+        painter.enable_async_glyph_rasterization(move || redraw.request_redraw());
+    }
     let compositor = Compositor::new(device.clone(), painter);
     Ok(GpuState {
-        surface,
+        surface: Some(surface),
         device,
         queue,
         configuration,
         render_format,
+        present_modes: capabilities.present_modes,
         compositor,
     })
 }
@@ -516,6 +743,23 @@ impl fmt::Display for HostError {
 
 impl Error for HostError {}
 
+fn choose_format(
+    capabilities: &SurfaceCapabilities,
+    preferred: Option<TextureFormat>,
+) -> Option<TextureFormat> {
+    preferred
+        .filter(|format| capabilities.formats.contains(format))
+        .or_else(|| capabilities.formats.first().copied())
+}
+
+fn choose_present_mode(supported: &[PresentMode], preferred: PresentMode) -> PresentMode {
+    if supported.contains(&preferred) {
+        preferred
+    } else {
+        PresentMode::Fifo
+    }
+}
+
 fn srgb_view_format(format: astrelis_gpu::TextureFormat) -> astrelis_gpu::TextureFormat {
     match format {
         astrelis_gpu::TextureFormat::Bgra8Unorm => astrelis_gpu::TextureFormat::Bgra8UnormSrgb,
@@ -526,9 +770,167 @@ fn srgb_view_format(format: astrelis_gpu::TextureFormat) -> astrelis_gpu::Textur
 
 #[cfg(test)]
 mod tests {
-    use astrelis_gpu::TextureFormat;
+    use std::sync::Arc;
+
+    use astrelis_compositor::Compositor;
+    use astrelis_core::{color::Color, geometry::Size};
+    use astrelis_gpu::{
+        DeviceDescriptor, PresentMode, RequestAdapterOptions, SurfaceCapabilities,
+        SurfaceConfiguration, TextureFormat, TextureUsages,
+    };
+    use astrelis_paint_gpu::{Renderer, RendererOptions};
+    use astrelis_platform::{
+        PlatformError, Window, WindowCapabilities, WindowCommand, WindowId, WindowValue, backend,
+    };
+    use astrelis_ui_core::{Theme, Ui};
+    use raw_window_handle::{
+        DisplayHandle, HandleError, HasDisplayHandle, HasWindowHandle, WindowHandle,
+    };
+
+    use super::{
+        GpuState, GraphicsContext, WindowHost, choose_format, choose_present_mode, srgb_view_format,
+    };
+
+    #[derive(Debug)]
+    struct SizedWindow;
 
-    use super::srgb_view_format;
+    impl HasWindowHandle for SizedWindow {
+        fn window_handle(&self) -> Result<WindowHandle<'_>, HandleError> {
+            Err(HandleError::Unavailable)
+        }
+    }
+
+    impl HasDisplayHandle for SizedWindow {
+        fn display_handle(&self) -> Result<DisplayHandle<'_>, HandleError> {
+            Err(HandleError::Unavailable)
+        }
+    }
+
+    impl backend::Window for SizedWindow {
+        fn id(&self) -> WindowId {
+            WindowId(1)
+        }
+        fn capabilities(&self) -> WindowCapabilities {
+            WindowCapabilities::default()
+        }
+        fn command(&self, command: WindowCommand) -> Result<Option<WindowValue>, PlatformError> {
+            Ok(match command {
+                WindowCommand::InnerSize => Some(WindowValue::PhysicalSize(Size::new(64, 64))),
+                WindowCommand::ScaleFactor => Some(WindowValue::Float(1.0)),
+                _ => None,
+            })
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn suspended_host_releases_surface_and_skips_frames() {
+        pollster::block_on(async {
+            let instance = astrelis_gpu_wgpu::create_instance(Default::default());
+            let adapter = match instance
+                .request_adapter(RequestAdapterOptions::default())
+                .await
+            {
+                Ok(adapter) => adapter,
+                Err(error) => {
+                    eprintln!("skipping suspended host GPU test: {error}");
+                    return;
+                }
+            };
+            let (device, queue) = adapter
+                .request_device(DeviceDescriptor::default())
+                .await
+                .expect("request device");
+            let painter = Renderer::new(device.clone(), queue.clone(), RendererOptions::default())
+                .expect("painter");
+            // Headless adapters cannot present, so the host starts from the
+            // state `suspend` leaves behind: a live device with no surface.
+            let gpu = GpuState {
+                surface: None,
+                device: device.clone(),
+                queue,
+                configuration: SurfaceConfiguration {
+                    usage: TextureUsages::RENDER_ATTACHMENT,
+                    format: TextureFormat::Rgba8Unorm,
+                    view_formats: Vec::new(),
+                    width: 64,
+                    height: 64,
+                    present_mode: PresentMode::Fifo,
+                    alpha_mode: astrelis_gpu::CompositeAlphaMode::Opaque,
+                    desired_maximum_frame_latency: 2,
+                },
+                render_format: TextureFormat::Rgba8Unorm,
+                present_modes: vec![PresentMode::Fifo],
+                compositor: Compositor::new(device, painter),
+            };
+            let mut host = WindowHost::<()> {
+                window: Window::from_backend(Arc::new(SizedWindow)),
+                gpu: Some(gpu),
+                failed: None,
+                suspended: false,
+                ui: Ui::new(astrelis_text::FontDatabase::default(), Theme::default()),
+                cursors: Default::default(),
+                clear_color: Color::BLACK,
+            };
+            assert!(host.is_presentable());
+
+            host.suspend();
+            assert!(host.gpu.as_ref().is_some_and(|gpu| gpu.surface.is_none()));
+            assert!(!host.is_presentable());
+            assert!(matches!(host.redraw(), Ok(None)));
+            assert_eq!(
+                host.set_present_mode(PresentMode::Fifo),
+                Ok(PresentMode::Fifo)
+            );
+
+            // The test window has no native handle, so surface creation
+            // fails and the host must stay suspended for a retry.
+            let graphics = super::GraphicsContext::from_instance(instance);
+            assert!(host.resume(&graphics).is_err());
+            assert!(host.suspended);
+            assert!(host.gpu.as_ref().is_some_and(|gpu| gpu.surface.is_none()));
+            assert!(matches!(host.redraw(), Ok(None)));
+            assert!(host.resume(&graphics).is_err(), "resume can be retried");
+
+            host.suspended = false;
+            assert_eq!(host.resume(&graphics), Ok(()), "not suspended: no-op");
+        });
+    }
+
+    #[test]
+    fn clones_share_the_device_slot_created_after_const_construction() {
+        let graphics =
+            GraphicsContext::from_instance(astrelis_gpu_wgpu::create_instance(Default::default()));
+        let early = graphics.clone();
+        let late = graphics.clone();
+        assert!(Arc::ptr_eq(graphics.shared(), early.shared()));
+        assert!(Arc::ptr_eq(early.shared(), late.shared()));
+    }
+
+    #[test]
+    fn unsupported_surface_preferences_fall_back() {
+        let capabilities = SurfaceCapabilities {
+            formats: vec![TextureFormat::Bgra8Unorm, TextureFormat::Rgba16Float],
+            present_modes: vec![PresentMode::Fifo, PresentMode::Mailbox],
+            alpha_modes: Vec::new(),
+        };
+        assert_eq!(
+            choose_format(&capabilities, Some(TextureFormat::Rgba16Float)),
+            Some(TextureFormat::Rgba16Float)
+        );
+        assert_eq!(
+            choose_format(&capabilities, Some(TextureFormat::Rgba8Unorm)),
+            Some(TextureFormat::Bgra8Unorm)
+        );
+        assert_eq!(
+            choose_present_mode(&capabilities.present_modes, PresentMode::Mailbox),
+            PresentMode::Mailbox
+        );
+        assert_eq!(
+            choose_present_mode(&capabilities.present_modes, PresentMode::Immediate),
+            PresentMode::Fifo
+        );
+    }
 
     #[test]
     fn linear_surface_formats_use_srgb_frame_views() {
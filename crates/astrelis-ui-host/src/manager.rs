@@ -0,0 +1,103 @@
+//! Ownership and frame scheduling for several hosted windows.
+
+use astrelis_platform::{Clipboard, WindowEvent, WindowId};
+
+use crate::{HostError, HostUpdate, WindowHost};
+
+/// Hosted windows keyed by platform identifier, in opening order.
+///
+/// Open every window through the same [`crate::GraphicsContext`] so they
+/// share one device. [`WindowManager::render_all`] skips windows that cannot
+/// present (still initializing or minimized) and reports failures per window,
+/// so one lost surface does not stop the others from drawing.
+pub struct WindowManager<Message = ()> {
+    windows: Vec<WindowHost<Message>>,
+}
+
+impl<Message: 'static> WindowManager<Message> {
+    /// Creates an empty manager.
+    pub const fn new() -> Self {
+        Self {
+            windows: Vec::new(),
+        }
+    }
+
+    /// Adds a window, replacing any host with the same identifier.
+    pub fn insert(&mut self, host: WindowHost<Message>) -> WindowId {
+        let id = host.id();
+        match self.position(id) {
+            Some(index) => self.windows[index] = host,
+            None => self.windows.push(host),
+        }
+        id
+    }
+
+    /// Removes and returns a window, dropping its surface once released.
+    pub fn remove(&mut self, id: WindowId) -> Option<WindowHost<Message>> {
+        self.position(id).map(|index| self.windows.remove(index))
+    }
+
+    /// Returns one window.
+    pub fn get(&self, id: WindowId) -> Option<&WindowHost<Message>> {
+        self.windows.iter().find(|host| host.id() == id)
+    }
+
+    /// Returns one window for updates.
+    pub fn get_mut(&mut self, id: WindowId) -> Option<&mut WindowHost<Message>> {
+        self.windows.iter_mut().find(|host| host.id() == id)
+    }
+
+    /// Number of windows.
+    pub fn len(&self) -> usize {
+        self.windows.len()
+    }
+
+    /// Returns whether no windows remain, typically the signal to exit.
+    pub fn is_empty(&self) -> bool {
+        self.windows.is_empty()
+    }
+
+    /// Iterates windows in opening order.
+    pub fn iter(&self) -> impl Iterator<Item = &WindowHost<Message>> {
+        self.windows.iter()
+    }
+
+    /// Iterates windows in opening order for updates.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut WindowHost<Message>> {
+        self.windows.iter_mut()
+    }
+
+    /// Routes an event to its window. Events for unknown windows return `None`.
+    pub fn handle_event(
+        &mut self,
+        id: WindowId,
+        clipboard: &Clipboard,
+        event: &WindowEvent,
+    ) -> Result<Option<HostUpdate>, HostError> {
+        self.get_mut(id)
+            .map(|host| host.handle_event(clipboard, event))
+            .transpose()
+    }
+
+    /// Calls `render` for every window that can present now, collecting each
+    /// window's result.
+    pub fn render_all<T>(
+        &mut self,
+        mut render: impl FnMut(&mut WindowHost<Message>) -> Result<T, HostError>,
+    ) -> Vec<(WindowId, Result<T, HostError>)> {
+        self.windows
+            .iter_mut()
+            .filter_map(|host| host.is_presentable().then(|| (host.id(), render(host))))
+            .collect()
+    }
+
+    fn position(&self, id: WindowId) -> Option<usize> {
+        self.windows.iter().position(|host| host.id() == id)
+    }
+}
+
+impl<Message: 'static> Default for WindowManager<Message> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
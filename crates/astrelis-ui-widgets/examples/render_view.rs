@@ -13,7 +13,7 @@ use astrelis_gpu::{
 use astrelis_paint::ExternalImage;
 use astrelis_paint_gpu::{RenderTarget, Renderer, RendererOptions};
 use astrelis_platform::{
-    ElementState, Key, NamedKey, Window, WindowAttributes, WindowEvent, WindowId,
+    CursorRequests, ElementState, Key, NamedKey, Window, WindowAttributes, WindowEvent, WindowId,
 };
 use astrelis_text::{FontDatabase, FontFamily};
 use astrelis_ui_core::{ElementHandle, Label, LayoutStyle, Length, Theme, Ui, UiEventKind};
@@ -49,6 +49,7 @@ struct Example {
     window: Option<Window>,
     gpu: Option<GpuState>,
     ui: Ui<Message>,
+    cursors: CursorRequests,
     view: ElementHandle<RenderView<Message>>,
     toggle: ElementHandle<astrelis_ui_core::Button>,
     status: ElementHandle<Label>,
@@ -113,6 +114,7 @@ impl Example {
         Ok(Self {
             instance: astrelis_gpu_wgpu::create_instance(Default::default()),
             window: None,
+            cursors: CursorRequests::new(),
             gpu: None,
             ui,
             view,
@@ -312,9 +314,11 @@ impl Example {
                 },
             )
             .map_err(io::Error::other)?;
+        gpu.renderer.before_submit();
         gpu.queue
             .submit([encoder.finish().map_err(io::Error::other)?])
             .map_err(io::Error::other)?;
+        gpu.renderer.after_submit().map_err(io::Error::other)?;
         frame.present().map_err(io::Error::other)
     }
 }
@@ -409,6 +413,8 @@ impl App for Example {
                 .ui
                 .handle_window_event(window, &context.clipboard(), &event)
                 .map_err(io::Error::other)?;
+            self.ui.request_cursor(&mut self.cursors);
+            self.cursors.apply(window).map_err(io::Error::other)?;
             let toggle = self.ui.drain_events().any(|event| {
                 event.is_from(self.toggle) && event.kind == UiEventKind::ButtonActivated
             });
@@ -19,7 +19,7 @@ use astrelis_paint_gpu::{
     RenderTarget as PaintTarget, Renderer as PaintRenderer, RendererOptions as PaintOptions,
 };
 use astrelis_platform::{
-    Application, PlatformContext, Window, WindowAttributes, WindowEvent, WindowId,
+    Application, CursorRequests, PlatformContext, Window, WindowAttributes, WindowEvent, WindowId,
 };
 use astrelis_render_2d::{
     Camera2D, DrawList2D, Renderer2D, SpriteDraw, TextureOptions as TextureOptions2D,
@@ -51,6 +51,7 @@ struct Demo {
     window: Option<Window>,
     gpu: Option<GpuState>,
     ui: Ui<()>,
+    cursors: CursorRequests,
     scene_2d: CompositorViewId,
     scene_3d: CompositorViewId,
     started: Instant,
@@ -128,6 +129,7 @@ impl Demo {
         Self {
             instance: astrelis_gpu_wgpu::create_instance(Default::default()),
             window: None,
+            cursors: CursorRequests::new(),
             gpu: None,
             ui,
             scene_2d,
@@ -258,9 +260,11 @@ impl Demo {
                 },
             )
             .expect("compose UI and scenes");
+        gpu.compositor.before_submit();
         gpu.queue
             .submit([encoder.finish().expect("finish")])
             .expect("submit");
+        gpu.compositor.after_submit().expect("after submit");
         frame.present().expect("present");
         if let Some(window) = &self.window {
             window.request_redraw();
@@ -376,6 +380,8 @@ impl Application for Demo {
                 .ui
                 .handle_window_event(window, &context.clipboard(), &event)
                 .expect("UI event");
+            self.ui.request_cursor(&mut self.cursors);
+            self.cursors.apply(window).expect("apply cursor");
             if update.redraw {
                 window.request_redraw();
             }
@@ -19,7 +19,7 @@ use astrelis_gpu::{
 };
 use astrelis_paint::{Brush, CornerRadii, Painter, RoundedRect};
 use astrelis_paint_gpu::{RenderTarget, Renderer, RendererOptions};
-use astrelis_platform::{Window, WindowAttributes, WindowEvent, WindowId};
+use astrelis_platform::{CursorRequests, Window, WindowAttributes, WindowEvent, WindowId};
 use astrelis_text::{FontDatabase, FontFamily};
 use astrelis_ui_core::{
     Column, ElementHandle, EventFilter, EventPhase, Insets, Label, LayoutStyle, Length, Theme, Ui,
@@ -74,6 +74,7 @@ struct Gallery {
     window: Option<Window>,
     gpu: Option<GpuState>,
     ui: Ui<Message>,
+    cursors: CursorRequests,
     dark: bool,
     theme_status: ElementHandle<Label>,
 }
@@ -97,6 +98,7 @@ impl Gallery {
         Ok(Self {
             instance: astrelis_gpu_wgpu::create_instance(descriptor),
             window: None,
+            cursors: CursorRequests::new(),
             gpu: None,
             ui,
             dark: true,
@@ -200,9 +202,11 @@ impl Gallery {
                 },
             )
             .map_err(io::Error::other)?;
+        gpu.renderer.before_submit();
         gpu.queue
             .submit([encoder.finish().map_err(io::Error::other)?])
             .map_err(io::Error::other)?;
+        gpu.renderer.after_submit().map_err(io::Error::other)?;
         frame.present().map_err(io::Error::other)
     }
 }
@@ -456,6 +460,8 @@ impl App for Gallery {
                 .ui
                 .handle_window_event(window, &context.clipboard(), &event)
                 .map_err(io::Error::other)?;
+            self.ui.request_cursor(&mut self.cursors);
+            self.cursors.apply(window).map_err(io::Error::other)?;
             self.consume_messages()?;
             if update.redraw || self.ui.needs_redraw() {
                 context.invalidate_window(id);
@@ -9,7 +9,7 @@ use astrelis_gpu::{
     SurfaceFrameStatus, SurfaceTarget, TextureUsages, TextureViewDescriptor,
 };
 use astrelis_paint_gpu::{RenderTarget, Renderer, RendererOptions};
-use astrelis_platform::{Window, WindowAttributes, WindowEvent, WindowId};
+use astrelis_platform::{CursorRequests, Window, WindowAttributes, WindowEvent, WindowId};
 use astrelis_text::{FontDatabase, FontFamily};
 use astrelis_ui_core::{
     DragPayload, DropOperation, ElementHandle, Label, LayoutStyle, Length, Theme, Ui,
@@ -44,6 +44,7 @@ struct Gallery {
     window: Option<Window>,
     gpu: Option<GpuState>,
     ui: Ui<Message>,
+    cursors: CursorRequests,
     status: ElementHandle<Label>,
     virtual_list: VirtualList,
     virtual_status: ElementHandle<Label>,
@@ -372,6 +373,7 @@ impl Gallery {
         Ok(Self {
             instance: astrelis_gpu_wgpu::create_instance(descriptor),
             window: None,
+            cursors: CursorRequests::new(),
             gpu: None,
             ui,
             status,
@@ -503,9 +505,11 @@ impl Gallery {
                 },
             )
             .map_err(io::Error::other)?;
+        gpu.renderer.before_submit();
         gpu.queue
             .submit([encoder.finish().map_err(io::Error::other)?])
             .map_err(io::Error::other)?;
+        gpu.renderer.after_submit().map_err(io::Error::other)?;
         frame.present().map_err(io::Error::other)
     }
 }
@@ -634,6 +638,8 @@ impl App for Gallery {
                 .ui
                 .handle_window_event(window, &context.clipboard(), &event)
                 .map_err(io::Error::other)?;
+            self.ui.request_cursor(&mut self.cursors);
+            self.cursors.apply(window).map_err(io::Error::other)?;
             self.sync_virtual_list()?;
             self.consume_messages()?;
             if update.redraw || self.ui.needs_redraw() {
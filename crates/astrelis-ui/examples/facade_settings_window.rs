@@ -18,7 +18,7 @@ use astrelis_gpu::{
 };
 use astrelis_paint::{Brush, CornerRadii, Painter, RoundedRect, StrokeStyle};
 use astrelis_paint_gpu::{RenderTarget, Renderer, RendererOptions};
-use astrelis_platform::{Window, WindowAttributes, WindowEvent, WindowId};
+use astrelis_platform::{CursorRequests, Window, WindowAttributes, WindowEvent, WindowId};
 use astrelis_text::{FontDatabase, FontFamily};
 use astrelis_ui::prelude::*;
 
@@ -106,6 +106,7 @@ struct Settings {
     window: Option<Window>,
     gpu: Option<GpuState>,
     ui: Ui<Message>,
+    cursors: CursorRequests,
     handles: Handles,
     overlay_open: bool,
 }
@@ -289,6 +290,7 @@ impl Settings {
         Ok(Self {
             instance: astrelis_gpu_wgpu::create_instance(descriptor),
             window: None,
+            cursors: CursorRequests::new(),
             gpu: None,
             ui,
             handles: Handles {
@@ -376,9 +378,11 @@ impl Settings {
                 },
             )
             .map_err(io::Error::other)?;
+        gpu.renderer.before_submit();
         gpu.queue
             .submit([encoder.finish().map_err(io::Error::other)?])
             .map_err(io::Error::other)?;
+        gpu.renderer.after_submit().map_err(io::Error::other)?;
         frame.present().map_err(io::Error::other)?;
         #[cfg(target_arch = "wasm32")]
         set_web_status(None);
@@ -573,6 +577,8 @@ impl App for Settings {
                 .ui
                 .handle_window_event(window, &context.clipboard(), &event)
                 .map_err(io::Error::other)?;
+            self.ui.request_cursor(&mut self.cursors);
+            self.cursors.apply(window).map_err(io::Error::other)?;
             self.consume_events()?;
             if update.redraw || self.ui.needs_redraw() {
                 context.invalidate_window(id);